@@ -69,6 +69,35 @@ export const oauthConfigs = {
     tokenUrl: 'https://www.strava.com/oauth/token'
   },
 
+  fitbit: {
+    clientId: process.env.FITBIT_CLIENT_ID || '',
+    clientSecret: process.env.FITBIT_CLIENT_SECRET || '',
+    redirectUri: process.env.FITBIT_REDIRECT_URI || 'https://auth.virtues.com/fitbit/callback',
+    scopes: ['sleep', 'heartrate', 'activity', 'profile'],
+    authUrl: 'https://www.fitbit.com/oauth2/authorize',
+    tokenUrl: 'https://api.fitbit.com/oauth2/token'
+  },
+
+  slack: {
+    clientId: process.env.SLACK_CLIENT_ID || '',
+    clientSecret: process.env.SLACK_CLIENT_SECRET || '',
+    redirectUri: process.env.SLACK_REDIRECT_URI || 'https://auth.virtues.com/slack/callback',
+    // User token scopes (requested as user_scope, granted comma-separated)
+    scopes: [
+      'channels:read',
+      'channels:history',
+      'groups:read',
+      'groups:history',
+      'im:read',
+      'im:history',
+      'mpim:read',
+      'mpim:history',
+      'users:read'
+    ],
+    authUrl: 'https://slack.com/oauth/v2/authorize',
+    tokenUrl: 'https://slack.com/api/oauth.v2.access'
+  },
+
   plaid: {
     clientId: process.env.PLAID_CLIENT_ID || '',
     clientSecret: process.env.PLAID_SECRET || '',
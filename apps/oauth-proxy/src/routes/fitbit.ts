@@ -0,0 +1,265 @@
+import express, { Router, Request, Response } from 'express';
+import { oauthConfigs } from '../config/oauth-apps';
+import { createError } from '../middleware/error-handler';
+import { isValidReturnUrl } from '../utils/url-validator';
+import { pkceChallengeFromQuery, redirectWithCode, setPkceChallenge } from '../utils/pkce';
+
+const router: Router = express.Router();
+
+// Generate state parameter for CSRF protection
+const generateState = () => {
+  return Math.random().toString(36).substring(2, 15) + 
+         Math.random().toString(36).substring(2, 15);
+};
+
+// Initiate Fitbit OAuth flow
+router.get('/auth', (req: Request, res: Response) => {
+  try {
+    const { return_url, state: originalState } = req.query;
+    
+    if (!return_url || typeof return_url !== 'string') {
+      throw createError('Missing return_url parameter', 400);
+    }
+    
+    // Validate return_url to prevent open redirect attacks
+    if (!isValidReturnUrl(return_url)) {
+      throw createError('Invalid return_url parameter', 400);
+    }
+    
+    const state = generateState();
+    const config = oauthConfigs.fitbit;
+    const pkce = pkceChallengeFromQuery(req.query);
+    
+    // Debug: Check if client_id is loaded
+    console.log('Fitbit OAuth config:', {
+      clientId: config.clientId ? 'SET' : 'MISSING',
+      clientSecret: config.clientSecret ? 'SET' : 'MISSING',
+      redirectUri: config.redirectUri
+    });
+    
+    // Store state and return_url (in production, use Redis or similar)
+    // For now, encode in state parameter
+    const stateData = {
+      state: originalState || state,  // Use original state if provided
+      return_url,
+      pkce: pkce !== undefined,
+      timestamp: Date.now()
+    };
+    
+    const encodedState = Buffer.from(JSON.stringify(stateData)).toString('base64');
+    
+    const authUrl = new URL(config.authUrl);
+    authUrl.searchParams.set('client_id', config.clientId);
+    authUrl.searchParams.set('redirect_uri', config.redirectUri);
+    authUrl.searchParams.set('scope', config.scopes.join(' ')); // Fitbit uses space-separated scopes
+    authUrl.searchParams.set('response_type', 'code');
+    setPkceChallenge(authUrl, pkce);
+    authUrl.searchParams.set('state', encodedState);
+    
+    res.redirect(authUrl.toString());
+    
+  } catch (error) {
+    console.error('Fitbit auth error:', error);
+    res.status(500).json({ error: 'Failed to initiate Fitbit OAuth' });
+  }
+});
+
+// Handle Fitbit OAuth callback
+router.get('/callback', async (req: Request, res: Response) => {
+  try {
+    const { code, state, error } = req.query;
+    
+    if (error) {
+      throw createError(`OAuth error: ${error}`, 400);
+    }
+    
+    if (!code || !state) {
+      throw createError('Missing code or state parameter', 400);
+    }
+    
+    // Decode state to get return_url and original state
+    const stateData = JSON.parse(Buffer.from(state as string, 'base64').toString());
+    const { return_url, state: originalState, pkce } = stateData;
+    
+    if (!return_url) {
+      throw createError('Invalid state parameter', 400);
+    }
+    
+    // Validate return_url again
+    if (!isValidReturnUrl(return_url)) {
+      throw createError('Invalid return_url in state', 400);
+    }
+    
+    // A PKCE-bound code can only be redeemed with the verifier the instance holds
+    if (pkce) {
+      return redirectWithCode(res, return_url, code as string, 'fitbit', originalState);
+    }
+
+    // Exchange code for tokens HERE in the auth-proxy
+    const tokens = await exchangeCodeForTokens(code as string);
+    
+    // Redirect back to user's instance with the tokens
+    const returnUrl = new URL(return_url);
+    returnUrl.searchParams.set('access_token', tokens.access_token);
+    if (tokens.refresh_token) {
+      returnUrl.searchParams.set('refresh_token', tokens.refresh_token);
+    }
+    if (tokens.expires_in) {
+      returnUrl.searchParams.set('expires_in', tokens.expires_in.toString());
+    }
+    returnUrl.searchParams.set('provider', 'fitbit');
+    // Pass the original state back to the user's callback
+    if (originalState) {
+      returnUrl.searchParams.set('state', originalState);
+    }
+    
+    res.redirect(returnUrl.toString());
+    
+  } catch (error) {
+    console.error('Fitbit callback error:', error);
+    
+    // Redirect to user's instance with error
+    try {
+      const stateData = JSON.parse(Buffer.from(req.query.state as string, 'base64').toString());
+      const returnUrl = new URL(stateData.return_url);
+      returnUrl.searchParams.set('error', 'token_exchange_failed');
+      res.redirect(returnUrl.toString());
+    } catch {
+      res.status(500).json({ error: 'Failed to process Fitbit OAuth callback' });
+    }
+  }
+});
+
+// Fitbit's token endpoint requires the client credentials as HTTP Basic auth
+const basicAuthHeader = () => {
+  const config = oauthConfigs.fitbit;
+  return 'Basic ' + Buffer.from(`${config.clientId}:${config.clientSecret}`).toString('base64');
+};
+
+// Exchange authorization code for tokens
+async function exchangeCodeForTokens(code: string, codeVerifier?: string) {
+  const config = oauthConfigs.fitbit;
+  
+  const body = new URLSearchParams({
+    code,
+    client_id: config.clientId,
+    redirect_uri: config.redirectUri,
+    grant_type: 'authorization_code',
+    ...(codeVerifier ? { code_verifier: codeVerifier } : {})
+  });
+
+  const response = await fetch(config.tokenUrl, {
+    method: 'POST',
+    headers: {
+      'Authorization': basicAuthHeader(),
+      'Content-Type': 'application/x-www-form-urlencoded'
+    },
+    body: body.toString()
+  });
+
+  if (!response.ok) {
+    const errorData = await response.text();
+    throw new Error(`Token exchange failed: ${response.status} ${errorData}`);
+  }
+
+  const tokens = await response.json();
+  
+  if (!tokens.access_token) {
+    throw new Error('No access token received');
+  }
+
+  return tokens;
+}
+
+// Refresh access token using refresh token
+router.post('/refresh', async (req: Request, res: Response) => {
+  try {
+    const { refresh_token } = req.body;
+    
+    if (!refresh_token) {
+      throw createError('Missing required parameter: refresh_token', 400);
+    }
+    
+    // Use the auth proxy's own OAuth credentials
+    const config = oauthConfigs.fitbit;
+    
+    const body = new URLSearchParams({
+      refresh_token,
+      grant_type: 'refresh_token'
+    });
+
+    const response = await fetch(config.tokenUrl, {
+      method: 'POST',
+      headers: {
+        'Authorization': basicAuthHeader(),
+        'Content-Type': 'application/x-www-form-urlencoded'
+      },
+      body: body.toString()
+    });
+
+    if (!response.ok) {
+      const errorData = await response.text();
+      console.error('Token refresh failed:', response.status, errorData);
+      
+      // Check if it's an invalid_grant error (refresh token expired or revoked)
+      if (errorData.includes('invalid_grant')) {
+        throw createError('Refresh token is invalid or expired', 401);
+      }
+      
+      throw createError(`Token refresh failed: ${response.status}`, response.status);
+    }
+
+    const tokens: any = await response.json();
+    
+    if (!tokens.access_token) {
+      throw createError('No access token received from refresh', 500);
+    }
+
+    // Return the new tokens. Fitbit refresh tokens are single-use, so the
+    // rotated one must replace the stored token.
+    res.json({
+      access_token: tokens.access_token,
+      refresh_token: tokens.refresh_token || refresh_token,
+      expires_in: tokens.expires_in || 28800, // 8 hours default for Fitbit
+      token_type: tokens.token_type || 'Bearer'
+    });
+    
+  } catch (error: any) {
+    console.error('Token refresh error:', error);
+    
+    if (error.statusCode) {
+      res.status(error.statusCode).json({ 
+        error: error.message,
+        code: error.statusCode === 401 ? 'invalid_refresh_token' : 'refresh_failed'
+      });
+    } else {
+      res.status(500).json({ 
+        error: 'Failed to refresh token',
+        code: 'refresh_failed'
+      });
+    }
+  }
+});
+
+/**
+ * Exchange an authorization code for tokens
+ * Used by instances that redeem PKCE-bound codes with their code verifier
+ * @route POST /fitbit/token
+ */
+router.post('/token', async (req: Request, res: Response) => {
+  const { code, code_verifier } = req.body;
+
+  if (!code) {
+    return res.status(400).json({ error: 'Missing code parameter' });
+  }
+
+  try {
+    const tokens = await exchangeCodeForTokens(code, code_verifier);
+    res.json(tokens);
+  } catch (error) {
+    console.error('Fitbit /token exchange failed:', error);
+    res.status(502).json({ error: 'Token exchange failed' });
+  }
+});
+
+export { router as fitbitRouter };
\ No newline at end of file
@@ -2,6 +2,7 @@ import express, { Router, Request, Response } from 'express';
 import { oauthConfigs } from '../config/oauth-apps';
 import { createError } from '../middleware/error-handler';
 import { isValidReturnUrl } from '../utils/url-validator';
+import { pkceChallengeFromQuery, redirectWithCode, setPkceChallenge } from '../utils/pkce';
 
 const router: Router = express.Router();
 
@@ -27,12 +28,14 @@ router.get('/auth', (req: Request, res: Response) => {
 
     const state = generateState();
     const config = oauthConfigs.github;
+    const pkce = pkceChallengeFromQuery(req.query);
 
     // Store state and return_url (in production, use Redis or similar)
     // For now, encode in state parameter
     const stateData = {
       state: originalState || state,
       return_url,
+      pkce: pkce !== undefined,
       timestamp: Date.now()
     };
 
@@ -42,6 +45,7 @@ router.get('/auth', (req: Request, res: Response) => {
     authUrl.searchParams.set('client_id', config.clientId);
     authUrl.searchParams.set('redirect_uri', config.redirectUri);
     authUrl.searchParams.set('scope', config.scopes.join(' '));
+    setPkceChallenge(authUrl, pkce);
     authUrl.searchParams.set('state', encodedState);
 
     res.redirect(authUrl.toString());
@@ -67,7 +71,7 @@ router.get('/callback', async (req: Request, res: Response) => {
 
     // Decode state to get return_url and original state
     const stateData = JSON.parse(Buffer.from(state as string, 'base64').toString());
-    const { return_url, state: originalState } = stateData;
+    const { return_url, state: originalState, pkce } = stateData;
 
     if (!return_url) {
       throw createError('Invalid state parameter', 400);
@@ -78,6 +82,11 @@ router.get('/callback', async (req: Request, res: Response) => {
       throw createError('Invalid return_url in state', 400);
     }
 
+    // A PKCE-bound code can only be redeemed with the verifier the instance holds
+    if (pkce) {
+      return redirectWithCode(res, return_url, code as string, 'github', originalState);
+    }
+
     // Exchange code for tokens
     const tokens = await exchangeCodeForTokens(code as string);
 
@@ -114,13 +123,14 @@ router.get('/callback', async (req: Request, res: Response) => {
 });
 
 // Exchange authorization code for tokens
-async function exchangeCodeForTokens(code: string) {
+async function exchangeCodeForTokens(code: string, codeVerifier?: string) {
   const config = oauthConfigs.github;
 
   const body = new URLSearchParams({
     code,
     client_id: config.clientId,
-    client_secret: config.clientSecret
+    client_secret: config.clientSecret,
+    ...(codeVerifier ? { code_verifier: codeVerifier } : {})
   });
 
   // GitHub requires Accept: application/json to get JSON response
@@ -209,4 +219,25 @@ router.post('/refresh', async (req: Request, res: Response) => {
   }
 });
 
+/**
+ * Exchange an authorization code for tokens
+ * Used by instances that redeem PKCE-bound codes with their code verifier
+ * @route POST /github/token
+ */
+router.post('/token', async (req: Request, res: Response) => {
+  const { code, code_verifier } = req.body;
+
+  if (!code) {
+    return res.status(400).json({ error: 'Missing code parameter' });
+  }
+
+  try {
+    const tokens = await exchangeCodeForTokens(code, code_verifier);
+    res.json(tokens);
+  } catch (error) {
+    console.error('GitHub /token exchange failed:', error);
+    res.status(502).json({ error: 'Token exchange failed' });
+  }
+});
+
 export { router as githubRouter };
@@ -2,6 +2,7 @@ import express, { Router, Request, Response } from 'express';
 import { oauthConfigs } from '../config/oauth-apps';
 import { createError } from '../middleware/error-handler';
 import { isValidReturnUrl } from '../utils/url-validator';
+import { pkceChallengeFromQuery, redirectWithCode, setPkceChallenge } from '../utils/pkce';
 
 const router: Router = express.Router();
 
@@ -27,6 +28,7 @@ router.get('/auth', (req: Request, res: Response) => {
     
     const state = generateState();
     const config = oauthConfigs.google;
+    const pkce = pkceChallengeFromQuery(req.query);
     
     // Debug: Check if client_id is loaded
     console.log('Google OAuth config:', {
@@ -40,6 +42,7 @@ router.get('/auth', (req: Request, res: Response) => {
     const stateData = {
       state: originalState || state,  // Use original state if provided
       return_url,
+      pkce: pkce !== undefined,
       timestamp: Date.now()
     };
     
@@ -52,6 +55,7 @@ router.get('/auth', (req: Request, res: Response) => {
     authUrl.searchParams.set('response_type', 'code');
     authUrl.searchParams.set('access_type', 'offline');
     authUrl.searchParams.set('prompt', 'consent');
+    setPkceChallenge(authUrl, pkce);
     authUrl.searchParams.set('state', encodedState);
     
     res.redirect(authUrl.toString());
@@ -77,7 +81,7 @@ router.get('/callback', async (req: Request, res: Response) => {
     
     // Decode state to get return_url and original state
     const stateData = JSON.parse(Buffer.from(state as string, 'base64').toString());
-    const { return_url, state: originalState } = stateData;
+    const { return_url, state: originalState, pkce } = stateData;
     
     if (!return_url) {
       throw createError('Invalid state parameter', 400);
@@ -88,6 +92,11 @@ router.get('/callback', async (req: Request, res: Response) => {
       throw createError('Invalid return_url in state', 400);
     }
     
+    // A PKCE-bound code can only be redeemed with the verifier the instance holds
+    if (pkce) {
+      return redirectWithCode(res, return_url, code as string, 'google', originalState);
+    }
+
     // Exchange code for tokens HERE in the auth-proxy
     const tokens = await exchangeCodeForTokens(code as string);
     
@@ -124,7 +133,7 @@ if (tokens.expires_in) {
 });
 
 // Exchange authorization code for tokens
-async function exchangeCodeForTokens(code: string) {
+async function exchangeCodeForTokens(code: string, codeVerifier?: string) {
   const config = oauthConfigs.google;
   const tokenEndpoint = 'https://oauth2.googleapis.com/token';
   
@@ -133,7 +142,8 @@ async function exchangeCodeForTokens(code: string) {
     client_id: config.clientId,
     client_secret: config.clientSecret,
     redirect_uri: config.redirectUri,
-    grant_type: 'authorization_code'
+    grant_type: 'authorization_code',
+    ...(codeVerifier ? { code_verifier: codeVerifier } : {})
   });
 
   const response = await fetch(tokenEndpoint, {
@@ -232,4 +242,25 @@ router.post('/refresh', async (req: Request, res: Response) => {
   }
 });
 
+/**
+ * Exchange an authorization code for tokens
+ * Used by instances that redeem PKCE-bound codes with their code verifier
+ * @route POST /google/token
+ */
+router.post('/token', async (req: Request, res: Response) => {
+  const { code, code_verifier } = req.body;
+
+  if (!code) {
+    return res.status(400).json({ error: 'Missing code parameter' });
+  }
+
+  try {
+    const tokens = await exchangeCodeForTokens(code, code_verifier);
+    res.json(tokens);
+  } catch (error) {
+    console.error('Google /token exchange failed:', error);
+    res.status(502).json({ error: 'Token exchange failed' });
+  }
+});
+
 export { router as googleRouter };
\ No newline at end of file
@@ -2,6 +2,7 @@ import express, { Router, Request, Response } from 'express';
 import { oauthConfigs } from '../config/oauth-apps';
 import { createError } from '../middleware/error-handler';
 import { isValidReturnUrl } from '../utils/url-validator';
+import { pkceChallengeFromQuery, redirectWithCode } from '../utils/pkce';
 
 // Type for Notion OAuth token response
 interface NotionTokenResponse {
@@ -34,6 +35,7 @@ setInterval(() => {
 router.get('/auth', (req: Request, res: Response) => {
   const returnUrl = req.query.return_url as string;
   const originalState = req.query.state as string;
+  const pkce = pkceChallengeFromQuery(req.query);
 
   if (!returnUrl) {
     return res.status(400).json({ error: 'Missing return_url parameter' });
@@ -43,6 +45,7 @@ router.get('/auth', (req: Request, res: Response) => {
   const stateData = {
     return_url: returnUrl,
     state: originalState,
+    pkce: pkce !== undefined,
     timestamp: Date.now()
   };
   const encodedState = Buffer.from(JSON.stringify(stateData)).toString('base64');
@@ -55,7 +58,8 @@ router.get('/auth', (req: Request, res: Response) => {
     redirect_uri: config.redirectUri,
     response_type: 'code',
     state: encodedState,
-    owner: 'user' // Notion-specific: 'user' for personal integrations
+    owner: 'user', // Notion-specific: 'user' for personal integrations
+    ...(pkce ?? {})
   });
 
   const authUrl = `${config.authUrl}?${params.toString()}`;
@@ -80,7 +84,7 @@ router.get('/callback', async (req: Request, res: Response) => {
 
     // Decode state from parameter (serverless-compatible)
     const stateData = JSON.parse(Buffer.from(state as string, 'base64').toString());
-    const { return_url, state: originalState, timestamp } = stateData;
+    const { return_url, state: originalState, pkce, timestamp } = stateData;
 
     if (!return_url) {
       throw createError('Invalid state parameter', 400);
@@ -96,6 +100,11 @@ router.get('/callback', async (req: Request, res: Response) => {
       throw createError('Invalid return URL', 400);
     }
 
+    // A PKCE-bound code can only be redeemed with the verifier the instance holds
+    if (pkce) {
+      return redirectWithCode(res, return_url, code as string, 'notion', originalState);
+    }
+
     const config = oauthConfigs.notion;
 
     // Exchange code for access token
@@ -168,11 +177,12 @@ router.post('/refresh', async (req: Request, res: Response) => {
 
 /**
  * Exchange authorization code for access token
- * Used by CLI and other clients that can't use the redirect flow
+ * Used by CLI and other clients that can't use the redirect flow, and by
+ * instances redeeming PKCE-bound codes with their code verifier
  * @route POST /notion/token
  */
 router.post('/token', async (req: Request, res: Response) => {
-  const { code } = req.body;
+  const { code, code_verifier } = req.body;
 
   if (!code) {
     return res.status(400).json({ error: 'Missing code parameter' });
@@ -192,7 +202,8 @@ router.post('/token', async (req: Request, res: Response) => {
       body: new URLSearchParams({
         grant_type: 'authorization_code',
         code: code as string,
-        redirect_uri: config.redirectUri
+        redirect_uri: config.redirectUri,
+        ...(code_verifier ? { code_verifier } : {})
       }).toString()
     });
 
@@ -0,0 +1,242 @@
+import express, { Router, Request, Response } from 'express';
+import { oauthConfigs } from '../config/oauth-apps';
+import { createError } from '../middleware/error-handler';
+import { isValidReturnUrl } from '../utils/url-validator';
+import { pkceChallengeFromQuery, redirectWithCode, setPkceChallenge } from '../utils/pkce';
+
+const router: Router = express.Router();
+
+// Generate state parameter for CSRF protection
+const generateState = () => {
+  return Math.random().toString(36).substring(2, 15) +
+         Math.random().toString(36).substring(2, 15);
+};
+
+// Initiate Slack OAuth flow
+router.get('/auth', (req: Request, res: Response) => {
+  try {
+    const { return_url, state: originalState } = req.query;
+
+    if (!return_url || typeof return_url !== 'string') {
+      throw createError('Missing return_url parameter', 400);
+    }
+
+    // Validate return_url to prevent open redirect attacks
+    if (!isValidReturnUrl(return_url)) {
+      throw createError('Invalid return_url parameter', 400);
+    }
+
+    const state = generateState();
+    const config = oauthConfigs.slack;
+    const pkce = pkceChallengeFromQuery(req.query);
+
+    // Store state and return_url (in production, use Redis or similar)
+    // For now, encode in state parameter
+    const stateData = {
+      state: originalState || state,
+      return_url,
+      pkce: pkce !== undefined,
+      timestamp: Date.now()
+    };
+
+    const encodedState = Buffer.from(JSON.stringify(stateData)).toString('base64');
+
+    const authUrl = new URL(config.authUrl);
+    authUrl.searchParams.set('client_id', config.clientId);
+    authUrl.searchParams.set('redirect_uri', config.redirectUri);
+    // Messages are read as the user, so request user scopes rather than bot scopes
+    authUrl.searchParams.set('user_scope', config.scopes.join(','));
+    setPkceChallenge(authUrl, pkce);
+    authUrl.searchParams.set('state', encodedState);
+
+    res.redirect(authUrl.toString());
+
+  } catch (error) {
+    console.error('Slack auth error:', error);
+    res.status(500).json({ error: 'Failed to initiate Slack OAuth' });
+  }
+});
+
+// Handle Slack OAuth callback
+router.get('/callback', async (req: Request, res: Response) => {
+  try {
+    const { code, state, error } = req.query;
+
+    if (error) {
+      throw createError(`OAuth error: ${error}`, 400);
+    }
+
+    if (!code || !state) {
+      throw createError('Missing code or state parameter', 400);
+    }
+
+    // Decode state to get return_url and original state
+    const stateData = JSON.parse(Buffer.from(state as string, 'base64').toString());
+    const { return_url, state: originalState, pkce } = stateData;
+
+    if (!return_url) {
+      throw createError('Invalid state parameter', 400);
+    }
+
+    // Validate return_url again
+    if (!isValidReturnUrl(return_url)) {
+      throw createError('Invalid return_url in state', 400);
+    }
+
+    // A PKCE-bound code can only be redeemed with the verifier the instance holds
+    if (pkce) {
+      return redirectWithCode(res, return_url, code as string, 'slack', originalState);
+    }
+
+    // Exchange code for tokens
+    const tokens = await exchangeCodeForTokens(code as string);
+
+    // Redirect back to user's instance with the tokens
+    const returnUrl = new URL(return_url);
+    returnUrl.searchParams.set('access_token', tokens.access_token);
+    // Only issued when token rotation is enabled for the app
+    if (tokens.refresh_token) {
+      returnUrl.searchParams.set('refresh_token', tokens.refresh_token);
+    }
+    if (tokens.expires_in) {
+      returnUrl.searchParams.set('expires_in', tokens.expires_in.toString());
+    }
+    if (tokens.scope) {
+      returnUrl.searchParams.set('scope', tokens.scope);
+    }
+    returnUrl.searchParams.set('provider', 'slack');
+    if (originalState) {
+      returnUrl.searchParams.set('state', originalState);
+    }
+
+    res.redirect(returnUrl.toString());
+
+  } catch (error) {
+    console.error('Slack callback error:', error);
+
+    // Redirect to user's instance with error
+    try {
+      const stateData = JSON.parse(Buffer.from(req.query.state as string, 'base64').toString());
+      const returnUrl = new URL(stateData.return_url);
+      returnUrl.searchParams.set('error', 'token_exchange_failed');
+      res.redirect(returnUrl.toString());
+    } catch {
+      res.status(500).json({ error: 'Failed to process Slack OAuth callback' });
+    }
+  }
+});
+
+// Call oauth.v2.access, which reports failures as HTTP 200 with ok: false
+async function requestTokens(params: Record<string, string>) {
+  const config = oauthConfigs.slack;
+
+  const body = new URLSearchParams({
+    ...params,
+    client_id: config.clientId,
+    client_secret: config.clientSecret
+  });
+
+  const response = await fetch(config.tokenUrl, {
+    method: 'POST',
+    headers: {
+      'Content-Type': 'application/x-www-form-urlencoded'
+    },
+    body: body.toString()
+  });
+
+  if (!response.ok) {
+    const errorData = await response.text();
+    throw new Error(`Token request failed: ${response.status} ${errorData}`);
+  }
+
+  const data: any = await response.json();
+
+  if (!data.ok) {
+    throw new Error(`Token request failed: ${data.error}`);
+  }
+
+  // User tokens arrive under authed_user on install, at the top level on refresh
+  const tokens = data.authed_user?.access_token ? data.authed_user : data;
+
+  if (!tokens.access_token) {
+    throw new Error('No user access token received');
+  }
+
+  return tokens;
+}
+
+// Exchange authorization code for tokens
+async function exchangeCodeForTokens(code: string, codeVerifier?: string) {
+  return requestTokens({
+    code,
+    redirect_uri: oauthConfigs.slack.redirectUri,
+    ...(codeVerifier ? { code_verifier: codeVerifier } : {})
+  });
+}
+
+// Refresh access token (only for apps with token rotation enabled)
+router.post('/refresh', async (req: Request, res: Response) => {
+  try {
+    const { refresh_token } = req.body;
+
+    if (!refresh_token) {
+      // Without token rotation Slack user tokens don't expire
+      throw createError('Slack tokens do not expire unless token rotation is enabled. Re-authorize if token is revoked.', 400);
+    }
+
+    const tokens = await requestTokens({
+      grant_type: 'refresh_token',
+      refresh_token
+    });
+
+    res.json({
+      access_token: tokens.access_token,
+      refresh_token: tokens.refresh_token || refresh_token,
+      expires_in: tokens.expires_in,
+      token_type: 'Bearer'
+    });
+
+  } catch (error: any) {
+    console.error('Token refresh error:', error);
+
+    if (error.statusCode) {
+      res.status(error.statusCode).json({
+        error: error.message,
+        code: 'refresh_failed'
+      });
+    } else if (String(error.message).includes('invalid_refresh_token')) {
+      res.status(401).json({
+        error: 'Refresh token is invalid or expired',
+        code: 'invalid_refresh_token'
+      });
+    } else {
+      res.status(500).json({
+        error: 'Failed to refresh token',
+        code: 'refresh_failed'
+      });
+    }
+  }
+});
+
+/**
+ * Exchange an authorization code for tokens
+ * Used by instances that redeem PKCE-bound codes with their code verifier
+ * @route POST /slack/token
+ */
+router.post('/token', async (req: Request, res: Response) => {
+  const { code, code_verifier } = req.body;
+
+  if (!code) {
+    return res.status(400).json({ error: 'Missing code parameter' });
+  }
+
+  try {
+    const tokens = await exchangeCodeForTokens(code, code_verifier);
+    res.json(tokens);
+  } catch (error) {
+    console.error('Slack /token exchange failed:', error);
+    res.status(502).json({ error: 'Token exchange failed' });
+  }
+});
+
+export { router as slackRouter };
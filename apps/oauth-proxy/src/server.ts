@@ -10,6 +10,8 @@ import { githubRouter } from './routes/github';
 import { googleRouter } from './routes/google';
 import notionRouter from './routes/notion';
 import { stravaRouter } from './routes/strava';
+import { fitbitRouter } from './routes/fitbit';
+import { slackRouter } from './routes/slack';
 import { errorHandler } from './middleware/error-handler';
 import { logger } from './middleware/logger';
 
@@ -109,6 +111,8 @@ app.use('/github', githubRouter);
 app.use('/google', googleRouter);
 app.use('/notion', notionRouter);
 app.use('/strava', stravaRouter);
+app.use('/fitbit', fitbitRouter);
+app.use('/slack', slackRouter);
 
 // Error handling
 app.use(errorHandler);
@@ -123,7 +127,7 @@ if (process.env.NODE_ENV !== 'production' || !process.env.VERCEL) {
   app.listen(PORT, () => {
     console.log(`🚀 OAuth proxy server running on port ${PORT}`);
     console.log(`🌐 Environment: ${process.env.NODE_ENV || 'development'}`);
-    console.log(`📦 Providers: Google, Notion, Microsoft, GitHub, Strava`);
+    console.log(`📦 Providers: Google, Notion, Microsoft, GitHub, Strava, Fitbit, Slack`);
   });
 }
 
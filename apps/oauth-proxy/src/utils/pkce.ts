@@ -0,0 +1,67 @@
+/**
+ * PKCE (RFC 7636) pass-through for OAuth proxy
+ *
+ * The user's instance generates the code verifier and never sends it through
+ * the browser; only its challenge reaches /auth. The proxy forwards the
+ * challenge to the provider, and since it can't redeem a code bound to a
+ * verifier it doesn't hold, the callback hands the code back to the instance,
+ * which redeems it through POST /:provider/token with the verifier.
+ */
+
+import { Request, Response } from 'express';
+
+export interface PkceChallenge {
+  code_challenge: string;
+  code_challenge_method: string;
+}
+
+/**
+ * Read the PKCE challenge from an /auth request, if the instance sent one
+ *
+ * @param query - The /auth request's query parameters
+ * @returns The challenge, or undefined for instances that don't use PKCE
+ */
+export function pkceChallengeFromQuery(query: Request['query']): PkceChallenge | undefined {
+  const { code_challenge, code_challenge_method } = query;
+
+  if (!code_challenge || typeof code_challenge !== 'string') {
+    return undefined;
+  }
+
+  return {
+    code_challenge,
+    // RFC 7636 4.3: the method defaults to plain when omitted
+    code_challenge_method: typeof code_challenge_method === 'string' ? code_challenge_method : 'plain'
+  };
+}
+
+/**
+ * Add the PKCE challenge to a provider authorization URL
+ */
+export function setPkceChallenge(authUrl: URL, pkce: PkceChallenge | undefined): void {
+  if (pkce) {
+    authUrl.searchParams.set('code_challenge', pkce.code_challenge);
+    authUrl.searchParams.set('code_challenge_method', pkce.code_challenge_method);
+  }
+}
+
+/**
+ * Send the authorization code back to the user's instance for it to redeem
+ * with its code verifier
+ */
+export function redirectWithCode(
+  res: Response,
+  return_url: string,
+  code: string,
+  provider: string,
+  originalState?: string
+): void {
+  const returnUrl = new URL(return_url);
+  returnUrl.searchParams.set('code', code);
+  returnUrl.searchParams.set('provider', provider);
+  if (originalState) {
+    returnUrl.searchParams.set('state', originalState);
+  }
+
+  res.redirect(returnUrl.toString());
+}
@@ -57,6 +57,10 @@ pub struct Config {
     /// Vercel AI Gateway URL (default: https://ai-gateway.vercel.sh)
     pub ai_gateway_url: String,
 
+    /// Most inputs sent upstream in one embeddings call (default: 2048)
+    /// Larger batches are split into sub-batches transparently
+    pub embedding_max_batch_size: usize,
+
     // =========================================================================
     // External Service API Keys (All billable services proxied through Tollbooth)
     // =========================================================================
@@ -123,6 +127,16 @@ impl Config {
                 .context("AI_GATEWAY_API_KEY is required")?,
             ai_gateway_url: std::env::var("AI_GATEWAY_URL")
                 .unwrap_or_else(|_| "https://ai-gateway.vercel.sh".to_string()),
+            embedding_max_batch_size: {
+                let size: usize = std::env::var("TOLLBOOTH_EMBEDDING_MAX_BATCH")
+                    .unwrap_or_else(|_| "2048".to_string())
+                    .parse()
+                    .context("Invalid TOLLBOOTH_EMBEDDING_MAX_BATCH")?;
+                if size == 0 {
+                    bail!("TOLLBOOTH_EMBEDDING_MAX_BATCH must be at least 1");
+                }
+                size
+            },
 
             // External service API keys
             exa_api_key: std::env::var("EXA_API_KEY").ok(),
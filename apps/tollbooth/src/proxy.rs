@@ -10,6 +10,7 @@
 //! We ONLY:
 //! - Check budget (in routes, before calling providers)
 //! - Extract usage metadata from responses for billing
+//! - Reshape upstream error bodies into one OpenAI-compatible envelope
 //!
 //! This code is open source so you can verify these guarantees.
 
@@ -72,15 +73,22 @@ impl IntoResponse for ProxyError {
                     ),
                 };
 
+                // The provider's own type and code win; the status only fills in
+                // what the provider didn't say
+                let upstream = normalize_upstream_error(&message);
+                let upstream_type = upstream.error_type.as_deref().unwrap_or(error_type);
+                let upstream_code = upstream.code.as_deref().unwrap_or(upstream_type);
+
                 (
                     StatusCode::from_u16(status).unwrap_or(StatusCode::BAD_GATEWAY),
                     serde_json::json!({
                         "error": {
-                            "message": format!("[{}] {}", error_type, message),
-                            "type": error_type,
-                            "code": error_type,
+                            "message": format!("[{}] {}", error_type, upstream.message),
+                            "type": upstream_type,
+                            "code": upstream_code,
                             "hint": hint,
-                            "upstream_status": status
+                            "upstream_status": status,
+                            "provider_error": upstream.original
                         }
                     }),
                 )
@@ -100,3 +108,151 @@ impl IntoResponse for ProxyError {
         (status, axum::Json(body)).into_response()
     }
 }
+
+/// An upstream error body reduced to a message, type and code, with the
+/// original kept
+#[derive(Debug, PartialEq)]
+pub struct UpstreamErrorBody {
+    /// Human-readable message extracted from the provider's error
+    pub message: String,
+    /// The provider's error type (`invalid_request_error`, `INVALID_ARGUMENT`)
+    pub error_type: Option<String>,
+    /// The provider's error code, when it sends one as a string
+    pub code: Option<String>,
+    /// The provider's error as sent (parsed JSON, or the raw text)
+    pub original: serde_json::Value,
+}
+
+/// Extract the message, type and code from a provider error body, whatever
+/// its shape
+///
+/// Handles the shapes our upstreams use:
+/// - OpenAI / Cerebras / gateway: `{"error": {"message": ..., "type": ..., "code": ...}}`
+/// - Anthropic: `{"type": "error", "error": {"type": ..., "message": ...}}`
+/// - Vertex AI: `{"error": {"code": 429, "message": ..., "status": ...}}`,
+///   sometimes wrapped in a one-element array; `status` is its type
+/// - xAI: `{"code": ..., "error": "message"}`
+/// - flat `{"message": ...}` bodies
+///
+/// Anything else (HTML error pages, plain text) is passed through as the
+/// message unchanged, with no type or code.
+pub fn normalize_upstream_error(body: &str) -> UpstreamErrorBody {
+    let Ok(original) = serde_json::from_str::<serde_json::Value>(body) else {
+        return UpstreamErrorBody {
+            message: body.to_string(),
+            error_type: None,
+            code: None,
+            original: serde_json::Value::String(body.to_string()),
+        };
+    };
+
+    let root = match &original {
+        serde_json::Value::Array(items) if items.len() == 1 => &items[0],
+        other => other,
+    };
+
+    let message = match root.get("error") {
+        Some(serde_json::Value::String(message)) => Some(message.clone()),
+        Some(error) => error
+            .get("message")
+            .and_then(|m| m.as_str())
+            .map(str::to_string),
+        None => None,
+    }
+    .or_else(|| {
+        root.get("message")
+            .and_then(|m| m.as_str())
+            .map(str::to_string)
+    })
+    .unwrap_or_else(|| body.to_string());
+
+    let string = |value: Option<&serde_json::Value>| {
+        value
+            .and_then(|v| v.as_str())
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    let error = root.get("error").filter(|e| e.is_object());
+    let error_type = string(error.and_then(|e| e.get("type")))
+        .or_else(|| string(error.and_then(|e| e.get("status"))));
+    let code = string(error.and_then(|e| e.get("code"))).or_else(|| string(root.get("code")));
+
+    UpstreamErrorBody {
+        message,
+        error_type,
+        code,
+        original,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_provider_error_shapes() {
+        let cases = [
+            // OpenAI / Cerebras
+            r#"{"error": {"message": "bad model", "type": "invalid_request_error", "code": null}}"#,
+            // Anthropic
+            r#"{"type": "error", "error": {"type": "invalid_request_error", "message": "bad model"}}"#,
+            // Vertex AI
+            r#"[{"error": {"code": 400, "message": "bad model", "status": "INVALID_ARGUMENT"}}]"#,
+            // xAI
+            r#"{"code": "Client specified an invalid argument", "error": "bad model"}"#,
+            // Flat
+            r#"{"message": "bad model"}"#,
+        ];
+        for body in cases {
+            let normalized = normalize_upstream_error(body);
+            assert_eq!(normalized.message, "bad model", "{body}");
+            assert!(normalized.original.is_object() || normalized.original.is_array());
+        }
+    }
+
+    #[test]
+    fn test_provider_type_and_code_kept() {
+        let openai = normalize_upstream_error(
+            r#"{"error": {"message": "no such model", "type": "invalid_request_error", "code": "model_not_found"}}"#,
+        );
+        assert_eq!(openai.error_type.as_deref(), Some("invalid_request_error"));
+        assert_eq!(openai.code.as_deref(), Some("model_not_found"));
+
+        let anthropic = normalize_upstream_error(
+            r#"{"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}}"#,
+        );
+        assert_eq!(anthropic.error_type.as_deref(), Some("overloaded_error"));
+        assert_eq!(anthropic.code, None);
+
+        let vertex = normalize_upstream_error(
+            r#"[{"error": {"code": 429, "message": "quota", "status": "RESOURCE_EXHAUSTED"}}]"#,
+        );
+        assert_eq!(vertex.error_type.as_deref(), Some("RESOURCE_EXHAUSTED"));
+        assert_eq!(vertex.code, None);
+    }
+
+    #[tokio::test]
+    async fn test_envelope_uses_provider_type_and_code() {
+        let response = ProxyError::UpstreamError {
+            status: 400,
+            message: r#"{"error": {"message": "no such model", "type": "invalid_request_error", "code": "model_not_found"}}"#.to_string(),
+        }
+        .into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(body["error"]["type"], "invalid_request_error");
+        assert_eq!(body["error"]["code"], "model_not_found");
+        assert_eq!(body["error"]["message"], "[upstream_error] no such model");
+    }
+
+    #[test]
+    fn test_normalize_non_json_error() {
+        let normalized = normalize_upstream_error("Bad Gateway");
+        assert_eq!(normalized.message, "Bad Gateway");
+        assert_eq!(normalized.error_type, None);
+        assert_eq!(normalized.original, serde_json::json!("Bad Gateway"));
+    }
+}
@@ -92,13 +92,52 @@ async fn completions(
     complete_with_billing(&state, &auth, request).await
 }
 
+/// OpenAI-format embeddings request
+///
+/// `input` is a single string, a single token array, or a batch of either.
+/// Other fields (`dimensions`, `encoding_format`, ...) are forwarded as-is.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingRequest {
+    pub model: String,
+    pub input: serde_json::Value,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl EmbeddingRequest {
+    /// The individual inputs of the request, in order
+    fn inputs(&self) -> Vec<serde_json::Value> {
+        match &self.input {
+            // A flat array of numbers is one tokenized input, not a batch
+            serde_json::Value::Array(items) if !items.iter().all(|i| i.is_number()) => {
+                items.clone()
+            }
+            single => vec![single.clone()],
+        }
+    }
+
+    /// Upstream request for one sub-batch of inputs
+    fn with_inputs(&self, inputs: &[serde_json::Value]) -> Self {
+        Self {
+            model: self.model.clone(),
+            input: serde_json::Value::Array(inputs.to_vec()),
+            extra: self.extra.clone(),
+        }
+    }
+}
+
 /// POST /v1/embeddings
 ///
 /// Embeddings endpoint - forwards to AI Gateway
+///
+/// A batch within `embedding_max_batch_size` goes upstream in a single call
+/// and a successful gateway response is returned untouched. Larger batches
+/// are split into sub-batches whose embeddings and usage are merged back into
+/// one response, with `index` values relative to the original batch.
 async fn embeddings(
     State(state): State<Arc<AppState>>,
     auth: AuthenticatedRequest,
-    body: Bytes,
+    Json(request): Json<EmbeddingRequest>,
 ) -> Result<Response, ProxyError> {
     // Check subscription first
     if !state.subscription.is_active(&auth.user_id) {
@@ -114,6 +153,91 @@ async fn embeddings(
         return Err(ProxyError::InsufficientBudget { balance });
     }
 
+    let inputs = request.inputs();
+    let max_batch = state.config.embedding_max_batch_size;
+
+    if inputs.len() <= max_batch {
+        let (status, body_bytes) = forward_embeddings(&state, &auth, &request).await?;
+        if !status.is_success() {
+            return Err(ProxyError::UpstreamError {
+                status: status.as_u16(),
+                message: String::from_utf8_lossy(&body_bytes).into_owned(),
+            });
+        }
+        return Ok((StatusCode::OK, body_bytes).into_response());
+    }
+
+    tracing::debug!(
+        user_id = %auth.user_id,
+        inputs = inputs.len(),
+        max_batch,
+        "Splitting embeddings batch"
+    );
+
+    let mut data = Vec::with_capacity(inputs.len());
+    let mut prompt_tokens = 0u64;
+    let mut total_tokens = 0u64;
+    let mut model = serde_json::Value::String(request.model.clone());
+
+    for (chunk_index, chunk) in inputs.chunks(max_batch).enumerate() {
+        let offset = chunk_index * max_batch;
+        let (status, body_bytes) =
+            forward_embeddings(&state, &auth, &request.with_inputs(chunk)).await?;
+
+        // Sub-batches already embedded stay billed; the error goes back as-is
+        if !status.is_success() {
+            return Err(ProxyError::UpstreamError {
+                status: status.as_u16(),
+                message: String::from_utf8_lossy(&body_bytes).into_owned(),
+            });
+        }
+
+        let resp: serde_json::Value =
+            serde_json::from_slice(&body_bytes).map_err(|e| ProxyError::UpstreamError {
+                status: 500,
+                message: format!("Failed to parse AI Gateway response: {}", e),
+            })?;
+
+        if let Some(usage) = resp.get("usage") {
+            prompt_tokens += usage
+                .get("prompt_tokens")
+                .and_then(|t| t.as_u64())
+                .unwrap_or(0);
+            total_tokens += usage
+                .get("total_tokens")
+                .and_then(|t| t.as_u64())
+                .unwrap_or(0);
+        }
+        if let Some(m) = resp.get("model") {
+            model = m.clone();
+        }
+
+        let chunk_data = resp
+            .get("data")
+            .and_then(|d| d.as_array())
+            .cloned()
+            .unwrap_or_default();
+        data.extend(reindex_embeddings(chunk_data, offset));
+    }
+
+    Ok(Json(serde_json::json!({
+        "object": "list",
+        "data": data,
+        "model": model,
+        "usage": {
+            "prompt_tokens": prompt_tokens,
+            "total_tokens": total_tokens
+        }
+    }))
+    .into_response())
+}
+
+/// Send one embeddings call to the AI Gateway and bill its usage
+async fn forward_embeddings(
+    state: &AppState,
+    auth: &AuthenticatedRequest,
+    request: &EmbeddingRequest,
+) -> Result<(reqwest::StatusCode, Bytes), ProxyError> {
     let config = get_embeddings_config(&state.config);
 
     // Forward to AI Gateway embeddings endpoint
@@ -122,7 +246,7 @@ async fn embeddings(
         .post(&config.endpoint)
         .header("Authorization", format!("Bearer {}", config.api_key))
         .header("Content-Type", "application/json")
-        .body(body.to_vec())
+        .json(request)
         .send()
         .await
         .map_err(|e| ProxyError::NetworkError {
@@ -149,11 +273,19 @@ async fn embeddings(
         }
     }
 
-    Ok((
-        StatusCode::from_u16(status.as_u16()).unwrap_or(StatusCode::OK),
-        body_bytes,
-    )
-        .into_response())
+    Ok((status, body_bytes))
+}
+
+/// Shift the `index` of a sub-batch's embeddings to the original batch
+fn reindex_embeddings(data: Vec<serde_json::Value>, offset: usize) -> Vec<serde_json::Value> {
+    data.into_iter()
+        .map(|mut item| {
+            if let Some(index) = item.get("index").and_then(|i| i.as_u64()) {
+                item["index"] = serde_json::json!(index as usize + offset);
+            }
+            item
+        })
+        .collect()
 }
 
 /// GET /v1/models
@@ -431,3 +563,44 @@ async fn complete_with_billing(
     // 8. Return response (already in OpenAI format from gateway)
     Ok(Json(resp).into_response())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(input: serde_json::Value) -> EmbeddingRequest {
+        serde_json::from_value(serde_json::json!({
+            "model": "openai/text-embedding-3-small",
+            "input": input,
+            "dimensions": 256
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_embedding_inputs() {
+        assert_eq!(request(serde_json::json!("hello")).inputs().len(), 1);
+        assert_eq!(request(serde_json::json!([1, 2, 3])).inputs().len(), 1);
+        assert_eq!(request(serde_json::json!(["a", "b", "c"])).inputs().len(), 3);
+        assert_eq!(request(serde_json::json!([[1, 2], [3]])).inputs().len(), 2);
+    }
+
+    #[test]
+    fn test_sub_batch_keeps_extra_fields() {
+        let req = request(serde_json::json!(["a", "b", "c"]));
+        let sub = serde_json::to_value(req.with_inputs(&req.inputs()[2..])).unwrap();
+        assert_eq!(sub["input"], serde_json::json!(["c"]));
+        assert_eq!(sub["dimensions"], 256);
+    }
+
+    #[test]
+    fn test_reindex_embeddings() {
+        let data = vec![
+            serde_json::json!({"object": "embedding", "index": 0, "embedding": [0.1]}),
+            serde_json::json!({"object": "embedding", "index": 1, "embedding": [0.2]}),
+        ];
+        let shifted = reindex_embeddings(data, 4);
+        assert_eq!(shifted[0]["index"], 4);
+        assert_eq!(shifted[1]["index"], 5);
+    }
+}
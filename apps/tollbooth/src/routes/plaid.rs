@@ -9,6 +9,7 @@
 //! - POST /v1/services/plaid/link-token - Create a Plaid Link token
 //! - POST /v1/services/plaid/exchange-token - Exchange public token for access token
 //! - POST /v1/services/plaid/sync - Sync transactions (called by Core scheduler)
+//! - POST /v1/services/plaid/webhook-verification-key - Get a webhook signing key
 
 use axum::{
     extract::State,
@@ -104,6 +105,10 @@ pub struct CreateLinkTokenRequest {
     pub country_codes: Vec<String>,
     /// Optional redirect URI for OAuth-based institutions
     pub redirect_uri: Option<String>,
+    /// Access token of an existing Item, to open Link in update mode
+    pub access_token: Option<String>,
+    /// URL Plaid sends the Item's webhooks to
+    pub webhook: Option<String>,
 }
 
 fn default_products() -> Vec<String> {
@@ -152,6 +157,12 @@ fn default_count() -> i32 {
     500
 }
 
+#[derive(Debug, Deserialize)]
+pub struct WebhookVerificationKeyRequest {
+    /// Key id from the `kid` header of a webhook's Plaid-Verification JWT
+    pub key_id: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct AccountsGetRequest {
     /// The access_token for the Item
@@ -249,16 +260,23 @@ async fn create_link_token(
     }
 
     // Build Plaid request
-    let plaid_request = serde_json::json!({
+    let mut plaid_request = serde_json::json!({
         "client_name": "Virtues",
         "user": {
             "client_user_id": request.user_client_id
         },
-        "products": request.products,
         "country_codes": request.country_codes,
         "language": "en",
         "redirect_uri": request.redirect_uri
     });
+    if let Some(webhook) = request.webhook {
+        plaid_request["webhook"] = webhook.into();
+    }
+    // Update mode reconnects an existing Item, whose products are already set
+    match request.access_token {
+        Some(access_token) => plaid_request["access_token"] = access_token.into(),
+        None => plaid_request["products"] = request.products.into(),
+    }
 
     match plaid_post::<_, CreateLinkTokenResponse>(&state, "/link/token/create", &plaid_request)
         .await
@@ -413,6 +431,29 @@ async fn item_remove(
     }
 }
 
+/// POST /v1/services/plaid/webhook-verification-key
+/// Get the public key a Plaid webhook was signed with
+async fn webhook_verification_key(
+    State(state): State<Arc<AppState>>,
+    _auth: AuthenticatedRequest,
+    Json(request): Json<WebhookVerificationKeyRequest>,
+) -> Response {
+    let plaid_request = serde_json::json!({
+        "key_id": request.key_id
+    });
+
+    match plaid_post::<_, serde_json::Value>(
+        &state,
+        "/webhook_verification_key/get",
+        &plaid_request,
+    )
+    .await
+    {
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
+        Err(e) => (StatusCode::BAD_GATEWAY, Json(e)).into_response(),
+    }
+}
+
 // =============================================================================
 // Router
 // =============================================================================
@@ -424,4 +465,8 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/services/plaid/transactions/sync", post(transactions_sync))
         .route("/services/plaid/accounts/get", post(accounts_get))
         .route("/services/plaid/item/remove", post(item_remove))
+        .route(
+            "/services/plaid/webhook-verification-key",
+            post(webhook_verification_key),
+        )
 }
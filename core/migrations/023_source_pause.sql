@@ -0,0 +1,8 @@
+-- 023: Source-level pause
+--
+-- "Paused" is distinct from "inactive": a paused source keeps its tokens and
+-- can still be synced on demand, but the scheduler skips all of its streams.
+-- Previously pause flipped is_active, which also broke token refresh and
+-- manual syncs for the source.
+
+ALTER TABLE elt_source_connections ADD COLUMN is_paused INTEGER NOT NULL DEFAULT 0;
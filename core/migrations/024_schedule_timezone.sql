@@ -0,0 +1,7 @@
+-- 024: Timezone-aware stream schedules
+--
+-- Optional IANA timezone (e.g. "America/New_York") that a stream's
+-- cron_schedule is evaluated in. NULL means the scheduler default
+-- (SCHEDULER_TIMEZONE, falling back to UTC).
+
+ALTER TABLE elt_stream_connections ADD COLUMN cron_timezone TEXT;
@@ -0,0 +1,8 @@
+-- 025: Soft-delete for source connections
+--
+-- Deleting a source now sets deleted_at instead of removing the row, so an
+-- accidental disconnect keeps stream configs, cursors and archived data.
+-- Soft-deleted sources are hidden from listings and never scheduled, and
+-- can be restored within a grace window.
+
+ALTER TABLE elt_source_connections ADD COLUMN deleted_at TEXT;
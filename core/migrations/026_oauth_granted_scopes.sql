@@ -0,0 +1,8 @@
+-- 026: Track granted OAuth scopes per source connection
+--
+-- Space/comma separated scopes the provider reported (or we requested) at
+-- the last authorization. NULL means the connection predates tracking and
+-- is assumed to hold the provider's base scopes. Used to detect when a newly
+-- enabled stream needs a broader grant and to drive re-authorization.
+
+ALTER TABLE elt_source_connections ADD COLUMN granted_scopes TEXT;
@@ -0,0 +1,8 @@
+-- 027: Resume point for syncs stopped by a per-run record cap
+--
+-- When a sync loop hits `max_records_per_run` it stops paginating and stores
+-- where it left off (a provider page token, page number, or cursor). The next
+-- run continues from here instead of starting over; NULL once the listing has
+-- been fully consumed.
+
+ALTER TABLE elt_stream_connections ADD COLUMN resume_cursor TEXT;
@@ -0,0 +1,20 @@
+-- 028: Derived lifecycle status for source connections
+--
+-- Exposes the source's lifecycle as a single `status` value so code can match
+-- on one typed field (`SourceStatus`) instead of combining is_active,
+-- is_paused and deleted_at by hand. The column is generated from those flags,
+-- so existing rows map automatically and the flags remain the source of truth:
+--
+--   deleted_at set         -> 'deleted'
+--   is_active false/NULL   -> 'inactive'
+--   is_paused              -> 'paused'
+--   otherwise              -> 'active'
+
+ALTER TABLE elt_source_connections ADD COLUMN status TEXT GENERATED ALWAYS AS (
+    CASE
+        WHEN deleted_at IS NOT NULL THEN 'deleted'
+        WHEN COALESCE(is_active, 0) = 0 THEN 'inactive'
+        WHEN is_paused THEN 'paused'
+        ELSE 'active'
+    END
+) VIRTUAL;
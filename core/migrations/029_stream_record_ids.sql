@@ -0,0 +1,16 @@
+-- 029: Natural-id index for archive deduplication
+--
+-- Streams that declare a natural id (message_id, event_id, transaction_id, ...)
+-- record every id they archive here. A backfill or full re-sync whose window
+-- overlaps data already in the archive checks this index and skips records it
+-- has seen, instead of writing the same event into a second partition file.
+--
+-- Only the id is kept (no payload), and the table is WITHOUT ROWID so the
+-- primary key is the only storage.
+
+CREATE TABLE IF NOT EXISTS elt_stream_record_ids (
+    source_connection_id TEXT NOT NULL REFERENCES elt_source_connections(id) ON DELETE CASCADE,
+    stream_name TEXT NOT NULL,
+    record_id TEXT NOT NULL,
+    PRIMARY KEY (source_connection_id, stream_name, record_id)
+) WITHOUT ROWID;
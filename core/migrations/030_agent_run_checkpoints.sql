@@ -0,0 +1,19 @@
+-- 030: Step checkpoints for resumable agent runs
+--
+-- The agent loop saves its state here after each LLM call and after each
+-- round of tool results, keyed by the conversation (chat) it belongs to. If
+-- the process restarts mid-run, `AgentLoop::resume` rebuilds the loop from
+-- the last checkpoint instead of losing the tool chain. The row is removed
+-- when the run finishes, or pruned once it's too old to be worth resuming.
+--
+-- `state` is the JSON-serialized `AgentCheckpoint`: messages, tools, tool
+-- context, model/sampling, thought signature, and any tool calls the LLM
+-- requested whose results had not been appended yet.
+
+CREATE TABLE IF NOT EXISTS agent_run_checkpoints (
+    chat_id TEXT PRIMARY KEY REFERENCES app_chats(id) ON DELETE CASCADE,
+    step INTEGER NOT NULL,
+    state TEXT NOT NULL,  -- JSON
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
@@ -0,0 +1,16 @@
+-- 031: Pending OAuth authorizations (PKCE verifier + CSRF state)
+--
+-- `initiate_oauth_flow` stores one row per issued state token holding the
+-- PKCE code verifier and the redirect_uri sent to the provider. The callback
+-- consumes the row: a state with no row (replayed, expired, or issued
+-- elsewhere) is rejected, and the verifier and redirect_uri are supplied to
+-- the code exchange. Rows older than the state validity window are purged
+-- whenever a new authorization starts.
+
+CREATE TABLE IF NOT EXISTS oauth_pending_authorizations (
+    state TEXT PRIMARY KEY,
+    provider TEXT NOT NULL,
+    code_verifier TEXT NOT NULL,
+    redirect_uri TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
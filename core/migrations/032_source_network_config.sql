@@ -0,0 +1,9 @@
+-- 032: Per-source network (egress) configuration
+--
+-- JSON `NetworkConfig` for the source's outbound HTTP clients:
+--   {"proxy": "http://proxy.corp:3128"}  route this source through a proxy,
+--                                        overriding HTTPS_PROXY/HTTP_PROXY
+--   {"offline": true}                    refuse all outbound requests
+-- NULL means the process-wide defaults (proxy env vars honored).
+
+ALTER TABLE elt_source_connections ADD COLUMN network_config TEXT;
@@ -0,0 +1,6 @@
+-- 033: Correlation id for jobs
+--
+-- The `X-Request-Id` of the HTTP request that created the job (or of the
+-- parent job's request, for chained jobs). NULL for scheduled jobs.
+
+ALTER TABLE elt_jobs ADD COLUMN request_id TEXT;
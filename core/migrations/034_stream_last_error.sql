@@ -0,0 +1,7 @@
+-- 034: Last sync error per stream
+--
+-- Set by the job executor when a stream's sync job fails and cleared when one
+-- succeeds, so a failing stream shows why without digging through job logs.
+
+ALTER TABLE elt_stream_connections ADD COLUMN last_error TEXT;
+ALTER TABLE elt_stream_connections ADD COLUMN last_error_at TEXT;
@@ -0,0 +1,10 @@
+-- 035: Archive partition granularity per stream
+--
+-- How much time one archive partition covers: 'hourly'
+-- (`date=YYYY-MM-DD/hour=HH/`), 'daily' (`date=YYYY-MM-DD/`) or 'monthly'
+-- (`month=YYYY-MM/`). Only affects batches written after a change; reads list
+-- every layout.
+
+ALTER TABLE elt_stream_connections
+    ADD COLUMN partition_granularity TEXT NOT NULL DEFAULT 'daily'
+    CHECK (partition_granularity IN ('hourly', 'daily', 'monthly'));
@@ -0,0 +1,9 @@
+-- 036: End-to-end encrypted device streams
+--
+-- When set, devices seal each record with a key only they (and any trusted
+-- transform worker) hold, and /ingest accepts only sealed envelopes for the
+-- stream. The server archives the envelopes as they arrive and never runs
+-- transforms on them.
+
+ALTER TABLE elt_stream_connections
+    ADD COLUMN end_to_end_encrypted INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,17 @@
+-- 037: Stream config version
+--
+-- Bumped whenever a stream's config changes, so API clients can send it back
+-- in If-Match and find out (409) when someone else changed the config since
+-- they read it, instead of silently overwriting their change.
+
+ALTER TABLE elt_stream_connections ADD COLUMN config_version INTEGER NOT NULL DEFAULT 0;
+
+CREATE TRIGGER IF NOT EXISTS elt_stream_connections_bump_config_version
+    AFTER UPDATE OF config ON elt_stream_connections
+    FOR EACH ROW
+    WHEN NEW.config IS NOT OLD.config AND NEW.config_version = OLD.config_version
+BEGIN
+    UPDATE elt_stream_connections
+    SET config_version = OLD.config_version + 1
+    WHERE id = NEW.id;
+END;
@@ -0,0 +1,30 @@
+-- 038: Sources that need the user to reconnect
+--
+-- A provider can reject a source's credentials for good (Plaid's
+-- ITEM_LOGIN_REQUIRED when the bank login changed, or a revoked access token).
+-- Retrying can't fix that, so the sync records why in `reauth_reason` and the
+-- source reports status 'reauth_required', which the scheduler skips until a
+-- reconnect clears the reason.
+--
+-- SQLite can't alter a generated column, so `status` is dropped and re-added
+-- with the new state:
+--
+--   deleted_at set         -> 'deleted'
+--   is_active false/NULL   -> 'inactive'
+--   reauth_reason set      -> 'reauth_required'
+--   is_paused              -> 'paused'
+--   otherwise              -> 'active'
+
+ALTER TABLE elt_source_connections ADD COLUMN reauth_reason TEXT;
+
+ALTER TABLE elt_source_connections DROP COLUMN status;
+
+ALTER TABLE elt_source_connections ADD COLUMN status TEXT GENERATED ALWAYS AS (
+    CASE
+        WHEN deleted_at IS NOT NULL THEN 'deleted'
+        WHEN COALESCE(is_active, 0) = 0 THEN 'inactive'
+        WHEN reauth_reason IS NOT NULL THEN 'reauth_required'
+        WHEN is_paused THEN 'paused'
+        ELSE 'active'
+    END
+) VIRTUAL;
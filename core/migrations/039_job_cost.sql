@@ -0,0 +1,6 @@
+-- 039: Per-job cost accounting
+--
+-- Billed usage (transcription seconds, LLM tokens) a job spent, plus that of
+-- the jobs descending from it, as JSON. NULL unless JOB_COST_ACCOUNTING is on.
+
+ALTER TABLE elt_jobs ADD COLUMN cost TEXT;
@@ -0,0 +1,7 @@
+-- 040: Background backfill after a bounded first sync
+--
+-- A stream's first sync fetches only recent history; the rest is filled in
+-- by a chain of backfill jobs. Their progress is kept here as JSON, NULL for
+-- streams whose first sync wasn't bounded.
+
+ALTER TABLE elt_stream_connections ADD COLUMN backfill_progress TEXT;
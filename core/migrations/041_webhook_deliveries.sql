@@ -0,0 +1,14 @@
+-- 041: Durable webhook deliveries
+--
+-- A verified webhook delivery is stored before the provider is answered and
+-- deleted once its syncs have been started, so deliveries still queued when
+-- the server stops are processed on the next start. The body is kept as
+-- received and turned into syncs again on replay.
+
+CREATE TABLE IF NOT EXISTS elt_webhook_deliveries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider TEXT NOT NULL,
+    body BLOB NOT NULL,
+    request_id TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
@@ -0,0 +1,196 @@
+//! Agent Run Checkpoints
+//!
+//! Persists the agent loop's state after each LLM call and each round of tool
+//! results, so a run interrupted by a restart can be picked up again with
+//! `AgentLoop::resume` (`POST /api/chat/resume`). Checkpoints are keyed by
+//! conversation (chat) id and deleted when the run finishes; those of runs
+//! nobody resumed are pruned after `CHECKPOINT_MAX_AGE_HOURS`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqlitePool;
+
+use crate::error::Result;
+
+/// Age after which an unresumed checkpoint is pruned
+pub const CHECKPOINT_MAX_AGE_HOURS: i64 = 24;
+use crate::tools::ToolContext;
+
+use super::stream::{SamplingParams, ToolCall};
+
+/// Tool calls the LLM requested whose results are not in `messages` yet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingToolCalls {
+    /// Assistant text that accompanied the tool calls
+    pub content: String,
+    pub tool_calls: Vec<ToolCall>,
+    pub thought_signature: Option<String>,
+}
+
+/// Everything the loop needs to continue a run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentCheckpoint {
+    /// Last step started (an LLM call counts as a step)
+    pub step: u32,
+    pub model: String,
+    pub sampling: SamplingParams,
+    pub messages: Vec<Value>,
+    pub tools: Vec<Value>,
+    pub context: ToolContext,
+    pub next_thought_signature: Option<String>,
+    /// Set between an LLM response with tool calls and their results being
+    /// appended; a resumed run answers these as interrupted rather than
+    /// executing them twice
+    pub pending: Option<PendingToolCalls>,
+}
+
+/// Save (or replace) the checkpoint for a conversation
+pub async fn save_checkpoint(
+    pool: &SqlitePool,
+    conversation_id: &str,
+    checkpoint: &AgentCheckpoint,
+) -> Result<()> {
+    let state = serde_json::to_string(checkpoint)?;
+    sqlx::query(
+        "INSERT INTO agent_run_checkpoints (chat_id, step, state)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (chat_id) DO UPDATE SET
+             step = excluded.step,
+             state = excluded.state,
+             updated_at = datetime('now')",
+    )
+    .bind(conversation_id)
+    .bind(checkpoint.step as i64)
+    .bind(state)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Load the checkpoint for a conversation, if a run was interrupted
+pub async fn load_checkpoint(
+    pool: &SqlitePool,
+    conversation_id: &str,
+) -> Result<Option<AgentCheckpoint>> {
+    let state: Option<String> =
+        sqlx::query_scalar("SELECT state FROM agent_run_checkpoints WHERE chat_id = $1")
+            .bind(conversation_id)
+            .fetch_optional(pool)
+            .await?;
+
+    Ok(state.map(|s| serde_json::from_str(&s)).transpose()?)
+}
+
+/// Remove a conversation's checkpoint once its run has finished
+pub async fn clear_checkpoint(pool: &SqlitePool, conversation_id: &str) -> Result<()> {
+    sqlx::query("DELETE FROM agent_run_checkpoints WHERE chat_id = $1")
+        .bind(conversation_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Delete checkpoints last saved more than `CHECKPOINT_MAX_AGE_HOURS` ago
+///
+/// Returns the number removed.
+pub async fn prune_checkpoints(pool: &SqlitePool) -> Result<u64> {
+    let result =
+        sqlx::query("DELETE FROM agent_run_checkpoints WHERE updated_at <= datetime('now', $1)")
+            .bind(format!("-{CHECKPOINT_MAX_AGE_HOURS} hours"))
+            .execute(pool)
+            .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn test_pool() -> SqlitePool {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE agent_run_checkpoints (
+                chat_id TEXT PRIMARY KEY,
+                step INTEGER NOT NULL,
+                state TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool
+    }
+
+    fn checkpoint(step: u32) -> AgentCheckpoint {
+        AgentCheckpoint {
+            step,
+            model: "test-model".to_string(),
+            sampling: SamplingParams::default(),
+            messages: vec![serde_json::json!({ "role": "user", "content": "hi" })],
+            tools: vec![],
+            context: ToolContext::default(),
+            next_thought_signature: Some("sig".to_string()),
+            pending: Some(PendingToolCalls {
+                content: String::new(),
+                tool_calls: vec![ToolCall {
+                    id: "call_1".to_string(),
+                    name: "query_data".to_string(),
+                    arguments: serde_json::json!({}),
+                }],
+                thought_signature: None,
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_round_trip() {
+        let pool = test_pool().await;
+        assert!(load_checkpoint(&pool, "chat_1").await.unwrap().is_none());
+
+        save_checkpoint(&pool, "chat_1", &checkpoint(1))
+            .await
+            .unwrap();
+        save_checkpoint(&pool, "chat_1", &checkpoint(2))
+            .await
+            .unwrap();
+
+        let loaded = load_checkpoint(&pool, "chat_1").await.unwrap().unwrap();
+        assert_eq!(loaded.step, 2);
+        assert_eq!(loaded.next_thought_signature.as_deref(), Some("sig"));
+        assert_eq!(loaded.pending.unwrap().tool_calls[0].id, "call_1");
+
+        clear_checkpoint(&pool, "chat_1").await.unwrap();
+        assert!(load_checkpoint(&pool, "chat_1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_prune_removes_only_stale_checkpoints() {
+        let pool = test_pool().await;
+        save_checkpoint(&pool, "stale", &checkpoint(1))
+            .await
+            .unwrap();
+        save_checkpoint(&pool, "fresh", &checkpoint(1))
+            .await
+            .unwrap();
+        sqlx::query(
+            "UPDATE agent_run_checkpoints SET updated_at = datetime('now', '-2 days')
+             WHERE chat_id = 'stale'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(prune_checkpoints(&pool).await.unwrap(), 1);
+        assert!(load_checkpoint(&pool, "stale").await.unwrap().is_none());
+        assert!(load_checkpoint(&pool, "fresh").await.unwrap().is_some());
+    }
+}
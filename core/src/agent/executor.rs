@@ -9,7 +9,7 @@ use futures::future::join_all;
 use serde_json::Value;
 use tokio::time::timeout;
 
-use crate::tools::{ToolContext, ToolError, ToolExecutor, ToolResult};
+use crate::tools::{validate_tool_arguments, ToolContext, ToolError, ToolExecutor, ToolResult};
 
 use super::protocol::AgentEvent;
 use super::stream::ToolCall;
@@ -81,8 +81,17 @@ pub enum ToolExecutionError {
     #[error("Invalid arguments: {0}")]
     InvalidArguments(String),
 
+    /// Arguments did not match the tool's parameter schema
+    #[error("Invalid arguments for `{tool}`: {}. Fix the arguments and call the tool again.", .errors.join("; "))]
+    SchemaViolation { tool: String, errors: Vec<String> },
+
     #[error("Execution failed: {0}")]
     ExecutionFailed(String),
+
+    /// The run stopped while the call was outstanding, so whether it took
+    /// effect is unknown
+    #[error("The run was interrupted before this call's result was recorded, so it may or may not have taken effect. Check its effects before calling it again.")]
+    Interrupted,
 }
 
 impl From<ToolError> for ToolExecutionError {
@@ -113,6 +122,22 @@ pub async fn execute_tools(
     }
 }
 
+/// Results for tool calls left outstanding by an interrupted run
+///
+/// The calls aren't executed again, since they may already have taken
+/// effect; the LLM gets an `Interrupted` error for each and decides whether
+/// to retry.
+pub fn interrupted_results(tool_calls: &[ToolCall]) -> Vec<ToolExecutionResult> {
+    tool_calls
+        .iter()
+        .map(|tc| ToolExecutionResult {
+            tool_call_id: tc.id.clone(),
+            tool_name: tc.name.clone(),
+            result: Err(ToolExecutionError::Interrupted),
+        })
+        .collect()
+}
+
 /// Execute tools in parallel
 async fn execute_parallel(
     executor: &ToolExecutor,
@@ -163,6 +188,24 @@ async fn execute_single(
         "Executing tool"
     );
 
+    // Reject malformed arguments before running the tool
+    if let Err(errors) = validate_tool_arguments(&tool_call.name, &tool_call.arguments) {
+        tracing::warn!(
+            tool_call_id = %tool_call.id,
+            tool_name = %tool_call.name,
+            errors = ?errors,
+            "Tool arguments failed schema validation"
+        );
+        return ToolExecutionResult {
+            tool_call_id: tool_call.id.clone(),
+            tool_name: tool_call.name.clone(),
+            result: Err(ToolExecutionError::SchemaViolation {
+                tool: tool_call.name.clone(),
+                errors,
+            }),
+        };
+    }
+
     let result = timeout(
         config.tool_timeout,
         executor.execute(&tool_call.name, tool_call.arguments.clone(), context),
@@ -203,6 +246,26 @@ async fn execute_single(
     }
 }
 
+/// Cap a tool result before it is appended to the conversation
+///
+/// Cuts at the last char boundary within `max_bytes` and appends a marker with
+/// the number of bytes dropped, so the model knows the result is partial.
+pub fn truncate_tool_content(content: String, max_bytes: usize) -> String {
+    if content.len() <= max_bytes {
+        return content;
+    }
+
+    let mut cut = max_bytes;
+    while !content.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    format!(
+        "{}\n[{} more bytes omitted]",
+        &content[..cut],
+        content.len() - cut
+    )
+}
+
 /// Build the tool result message for the LLM
 pub fn build_tool_result_message(tool_call_id: &str, content: &str) -> Value {
     serde_json::json!({
@@ -239,3 +302,27 @@ pub fn build_assistant_tool_message(
 
     msg
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_tool_content() {
+        let short = "{\"ok\":true}".to_string();
+        assert_eq!(truncate_tool_content(short.clone(), 100), short);
+
+        let long = "a".repeat(150);
+        assert_eq!(
+            truncate_tool_content(long, 100),
+            format!("{}\n[50 more bytes omitted]", "a".repeat(100))
+        );
+    }
+
+    #[test]
+    fn test_truncate_tool_content_respects_char_boundaries() {
+        // "é" is two bytes; a cut at byte 3 would split the second one
+        let truncated = truncate_tool_content("éééé".to_string(), 3);
+        assert_eq!(truncated, "é\n[6 more bytes omitted]");
+    }
+}
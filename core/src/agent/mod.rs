@@ -23,16 +23,20 @@
 //! # Usage
 //!
 //! ```rust,ignore
-//! use virtues::agent::{AgentLoop, AgentConfig};
+//! use virtues::agent::{AgentLoop, AgentConfig, RunOptions};
 //!
 //! let agent = AgentLoop::new(pool, tollbooth_config);
-//! let stream = agent.run(messages, tools, context);
+//! let stream = agent.run(RunOptions::default(), messages, tools, context, None, None);
 //!
 //! while let Some(event) = stream.next().await {
 //!     // Handle AgentEvent
 //! }
+//!
+//! // After a restart, continue a chat's interrupted run from its checkpoint
+//! let stream = agent.resume(&chat_id, None).await?;
 //! ```
 
+pub mod checkpoint;
 pub mod executor;
 pub mod prompt;
 pub mod protocol;
@@ -48,12 +52,16 @@ use serde_json::Value;
 use sqlx::SqlitePool;
 use tokio_util::sync::CancellationToken;
 
+use crate::error::{Error, Result};
 use crate::tools::{ToolContext, ToolExecutor};
 use crate::server::yjs::YjsState;
 
+pub use checkpoint::{AgentCheckpoint, PendingToolCalls};
 pub use executor::{ExecutorConfig, ToolExecutionError, ToolExecutionResult};
 pub use protocol::{AgentEvent, ErrorCode, FinishReason, StepReason};
-pub use stream::{LlmConfig, LlmStreamResult, StreamError, ToolCall, TokenUsage};
+pub use stream::{
+    LlmConfig, LlmRequest, LlmStreamResult, SamplingParams, StreamError, TokenUsage, ToolCall,
+};
 
 /// Configuration for the AgentLoop
 #[derive(Debug, Clone)]
@@ -64,6 +72,22 @@ pub struct AgentConfig {
     pub tool_timeout: Duration,
     /// Whether to execute multiple tools in parallel
     pub parallel_tools: bool,
+    /// Model used when a run doesn't specify one
+    pub default_model: String,
+    /// Sampling temperature; None uses the provider default
+    ///
+    /// Models with extended thinking enabled (see
+    /// `stream::build_provider_options`) may reject anything but the default.
+    pub temperature: Option<f32>,
+    /// Nucleus sampling cutoff; None uses the provider default
+    pub top_p: Option<f32>,
+    /// Completion token cap per LLM call; None uses the provider default
+    ///
+    /// For thinking models this must exceed the thinking budget.
+    pub max_tokens: Option<u32>,
+    /// Tool results larger than this many bytes are truncated before being
+    /// added to the conversation; None appends them verbatim
+    pub max_tool_result_bytes: Option<usize>,
 }
 
 impl Default for AgentConfig {
@@ -72,6 +96,70 @@ impl Default for AgentConfig {
             max_steps: 20,
             tool_timeout: Duration::from_secs(30),
             parallel_tools: true,
+            default_model: virtues_registry::models::default_model_for_slot(
+                virtues_registry::models::ModelSlot::Chat,
+            )
+            .to_string(),
+            temperature: None,
+            top_p: None,
+            max_tokens: None,
+            max_tool_result_bytes: Some(64 * 1024),
+        }
+    }
+}
+
+impl AgentConfig {
+    /// Defaults, with the model and sampling taken from the environment
+    ///
+    /// Reads `AGENT_DEFAULT_MODEL`, `AGENT_TEMPERATURE`, `AGENT_TOP_P` and
+    /// `AGENT_MAX_TOKENS`; unset or unparsable values keep the default.
+    pub fn from_env() -> Self {
+        Self::default().with_env(|var| std::env::var(var).ok())
+    }
+
+    fn with_env(mut self, var: impl Fn(&str) -> Option<String>) -> Self {
+        fn parse<T: std::str::FromStr>(value: Option<String>) -> Option<T> {
+            value.and_then(|s| s.trim().parse().ok())
+        }
+
+        if let Some(model) = var("AGENT_DEFAULT_MODEL").filter(|m| !m.trim().is_empty()) {
+            self.default_model = model.trim().to_string();
+        }
+        self.temperature = parse(var("AGENT_TEMPERATURE")).or(self.temperature);
+        self.top_p = parse(var("AGENT_TOP_P")).or(self.top_p);
+        self.max_tokens = parse(var("AGENT_MAX_TOKENS")).or(self.max_tokens);
+        self
+    }
+
+    /// Resolve the model and sampling for a run, preferring per-run overrides
+    fn resolve(&self, options: RunOptions) -> (String, SamplingParams) {
+        let model = options.model.unwrap_or_else(|| self.default_model.clone());
+        let sampling = SamplingParams {
+            temperature: options.temperature.or(self.temperature),
+            top_p: options.top_p.or(self.top_p),
+            max_tokens: options.max_tokens.or(self.max_tokens),
+        };
+        (model, sampling)
+    }
+}
+
+/// Per-run overrides of the [`AgentConfig`] model and sampling settings
+///
+/// Fields left as None fall back to the config.
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    pub model: Option<String>,
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    pub max_tokens: Option<u32>,
+}
+
+impl RunOptions {
+    /// Override only the model
+    pub fn with_model(model: impl Into<String>) -> Self {
+        Self {
+            model: Some(model.into()),
+            ..Default::default()
         }
     }
 }
@@ -84,7 +172,7 @@ impl Default for AgentConfig {
 /// 3. Executing tool calls
 /// 4. Continuing until completion
 pub struct AgentLoop {
-    _pool: Arc<SqlitePool>,
+    pool: Arc<SqlitePool>,
     llm_config: LlmConfig,
     tool_executor: ToolExecutor,
     config: AgentConfig,
@@ -105,12 +193,8 @@ impl AgentLoop {
                 tollbooth_url.clone(),
                 tollbooth_secret.clone(),
             ),
-            llm_config: LlmConfig {
-                tollbooth_url,
-                tollbooth_user_id,
-                tollbooth_secret,
-            },
-            _pool: pool,
+            llm_config: LlmConfig::new(tollbooth_url, tollbooth_user_id, tollbooth_secret),
+            pool,
             config: AgentConfig::default(),
         }
     }
@@ -131,12 +215,8 @@ impl AgentLoop {
                 tollbooth_secret.clone(),
                 yjs_state,
             ),
-            llm_config: LlmConfig {
-                tollbooth_url,
-                tollbooth_user_id,
-                tollbooth_secret,
-            },
-            _pool: pool,
+            llm_config: LlmConfig::new(tollbooth_url, tollbooth_user_id, tollbooth_secret),
+            pool,
             config: AgentConfig::default(),
         }
     }
@@ -151,15 +231,79 @@ impl AgentLoop {
     ///
     /// Returns a stream of AgentEvents that can be forwarded to the client.
     /// Pass a CancellationToken to allow stopping the loop early.
+    ///
+    /// When `context.chat_id` is set, the loop checkpoints its state after
+    /// every step so the run can be continued with [`AgentLoop::resume`].
     pub fn run(
         &self,
-        model: String,
+        options: RunOptions,
         initial_messages: Vec<Value>,
         tools: Vec<Value>,
         context: ToolContext,
         initial_thought_signature: Option<String>,
         cancel_token: Option<CancellationToken>,
     ) -> Pin<Box<dyn Stream<Item = AgentEvent> + Send + '_>> {
+        let (model, sampling) = self.config.resolve(options);
+        let conversation_id = context.chat_id.clone();
+
+        self.drive(
+            AgentCheckpoint {
+                step: 0,
+                model,
+                sampling,
+                messages: initial_messages,
+                tools,
+                context,
+                next_thought_signature: initial_thought_signature,
+                pending: None,
+            },
+            conversation_id,
+            cancel_token,
+        )
+    }
+
+    /// Continue a run that was interrupted (e.g. by a restart or deploy)
+    ///
+    /// Rebuilds the loop from the conversation's last checkpoint. Tool calls
+    /// the LLM had requested but whose results were never recorded are not
+    /// executed again, as they may already have taken effect; each is
+    /// answered with an interrupted error before the next LLM call. Fails
+    /// with `NotFound` when the conversation has no interrupted run.
+    pub async fn resume(
+        &self,
+        conversation_id: &str,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<Pin<Box<dyn Stream<Item = AgentEvent> + Send + '_>>> {
+        let checkpoint = checkpoint::load_checkpoint(&self.pool, conversation_id)
+            .await?
+            .ok_or_else(|| {
+                Error::NotFound(format!(
+                    "No interrupted agent run for conversation {}",
+                    conversation_id
+                ))
+            })?;
+
+        tracing::info!(
+            conversation_id,
+            step = checkpoint.step,
+            pending_tool_calls = checkpoint
+                .pending
+                .as_ref()
+                .map_or(0, |p| p.tool_calls.len()),
+            "Resuming agent run from checkpoint"
+        );
+
+        Ok(self.drive(checkpoint, Some(conversation_id.to_string()), cancel_token))
+    }
+
+    /// Drive the loop from `state` until the run finishes
+    fn drive(
+        &self,
+        mut state: AgentCheckpoint,
+        conversation_id: Option<String>,
+        cancel_token: Option<CancellationToken>,
+    ) -> Pin<Box<dyn Stream<Item = AgentEvent> + Send + '_>> {
+        let pool = self.pool.clone();
         let llm_config = self.llm_config.clone();
         let tool_executor = self.tool_executor.clone();
         let config = self.config.clone();
@@ -169,130 +313,155 @@ impl AgentLoop {
         };
 
         Box::pin(stream! {
-            let mut messages = initial_messages;
-            let mut step: u32 = 0;
-            let mut next_thought_signature = initial_thought_signature;
-
             // Emit loop started
             yield AgentEvent::LoopStarted {
                 max_steps: config.max_steps,
             };
 
             loop {
-                step += 1;
+                // A resumed run may have stopped after the LLM requested tools
+                // but before their results were recorded; answer those first
+                let (pending, interrupted) = match state.pending.take() {
+                    Some(pending) => (pending, true),
+                    None => {
+                        state.step += 1;
+                        let step = state.step;
+
+                        // Check for cancellation at start of each step
+                        if let Some(ref token) = cancel_token {
+                            if token.is_cancelled() {
+                                tracing::info!(step, "Agent loop cancelled by user");
+                                yield AgentEvent::done_with_reason(step.saturating_sub(1), protocol::FinishReason::Cancelled);
+                                break;
+                            }
+                        }
 
-                // Check for cancellation at start of each step
-                if let Some(ref token) = cancel_token {
-                    if token.is_cancelled() {
-                        tracing::info!(step, "Agent loop cancelled by user");
-                        yield AgentEvent::done_with_reason(step.saturating_sub(1), protocol::FinishReason::Cancelled);
-                        break;
-                    }
-                }
+                        // Check max steps
+                        if step > config.max_steps {
+                            yield AgentEvent::error(
+                                format!("Maximum steps ({}) exceeded", config.max_steps),
+                                Some(ErrorCode::MaxStepsExceeded),
+                                false,
+                            );
+                            break;
+                        }
 
-                // Check max steps
-                if step > config.max_steps {
-                    yield AgentEvent::error(
-                        format!("Maximum steps ({}) exceeded", config.max_steps),
-                        Some(ErrorCode::MaxStepsExceeded),
-                        false,
-                    );
-                    break;
-                }
+                        tracing::info!(step, "Agent loop step");
+
+                        // Build provider options for reasoning models
+                        let provider_options = stream::build_provider_options(&state.model);
+
+                        // Use the next_thought_signature if available, otherwise look in history
+                        let thought_signature = if state.next_thought_signature.is_some() {
+                            state.next_thought_signature.take()
+                        } else {
+                            state.messages.iter().rev()
+                                .filter_map(|m| m.get("thought_signature").and_then(|s| s.as_str()))
+                                .next()
+                                .map(|s| s.to_string())
+                        };
+
+                        // Stream events through a channel for incremental delivery.
+                        // Previously events were collected into a Vec and yielded in a
+                        // burst after the entire LLM response completed. Using a channel
+                        // lets each text-delta reach the client as it arrives.
+                        let (ev_tx, mut ev_rx) = tokio::sync::mpsc::unbounded_channel::<AgentEvent>();
+
+                        // Clone data needed for the spawned streaming task
+                        let llm_cfg = llm_config.clone();
+                        let mdl = state.model.clone();
+                        let msgs = state.messages.clone();
+                        let tls = state.tools.clone();
+                        let sampling = state.sampling;
+
+                        // Spawn streaming in a separate task so events are sent
+                        // through the channel immediately (not buffered until completion)
+                        let stream_handle = tokio::spawn(async move {
+                            let request = stream::LlmRequest {
+                                model: &mdl,
+                                messages: &msgs,
+                                tools: &tls,
+                                sampling,
+                                provider_options,
+                                thought_signature,
+                            };
+                            stream::stream_llm_response(&llm_cfg, request, |event| {
+                                let _ = ev_tx.send(event);
+                            })
+                            .await
+                        });
+
+                        // Yield events incrementally as they arrive from the stream
+                        while let Some(event) = ev_rx.recv().await {
+                            if let AgentEvent::ThoughtSignature { ref signature } = event {
+                                state.next_thought_signature = Some(signature.clone());
+                            }
+                            yield event;
+                        }
 
-                tracing::info!(step, "Agent loop step");
+                        // Get the streaming result after the task completes
+                        let result = match stream_handle.await {
+                            Ok(inner) => inner,
+                            Err(e) => Err(StreamError::Connection(format!("Stream task panicked: {}", e))),
+                        };
+
+                        let result = match result {
+                            Ok(r) => r,
+                            Err(e) => {
+                                yield AgentEvent::error(
+                                    e.to_string(),
+                                    Some(ErrorCode::LlmError),
+                                    false,
+                                );
+                                break;
+                            }
+                        };
 
-                // Build provider options for reasoning models
-                let provider_options = stream::build_provider_options(&model);
+                        // Check if we're done (no tool calls)
+                        if result.tool_calls.is_empty() {
+                            yield AgentEvent::step_complete(step, result.finish_reason);
+                            break;
+                        }
 
-                // Use the next_thought_signature if available, otherwise look in history
-                let thought_signature = if next_thought_signature.is_some() {
-                    next_thought_signature.take()
-                } else {
-                    messages.iter().rev()
-                        .filter_map(|m| m.get("thought_signature").and_then(|s| s.as_str()))
-                        .next()
-                        .map(|s| s.to_string())
+                        // We have tool calls - emit step complete
+                        yield AgentEvent::step_complete(step, StepReason::ToolCalls);
+
+                        // Checkpoint the requested calls before running them
+                        let pending = PendingToolCalls {
+                            content: result.content,
+                            tool_calls: result.tool_calls,
+                            thought_signature: result.thought_signature,
+                        };
+                        state.pending = Some(pending.clone());
+                        save_checkpoint_or_warn(&pool, conversation_id.as_deref(), &state).await;
+                        state.pending = None;
+                        (pending, false)
+                    }
                 };
-
-                // Stream events through a channel for incremental delivery.
-                // Previously events were collected into a Vec and yielded in a
-                // burst after the entire LLM response completed. Using a channel
-                // lets each text-delta reach the client as it arrives.
-                let (ev_tx, mut ev_rx) = tokio::sync::mpsc::unbounded_channel::<AgentEvent>();
-
-                // Clone data needed for the spawned streaming task
-                let llm_cfg = llm_config.clone();
-                let mdl = model.clone();
-                let msgs = messages.clone();
-                let tls = tools.clone();
-
-                // Spawn streaming in a separate task so events are sent
-                // through the channel immediately (not buffered until completion)
-                let stream_handle = tokio::spawn(async move {
-                    stream::stream_llm_response(
-                        &llm_cfg,
-                        &mdl,
-                        &msgs,
-                        &tls,
-                        provider_options,
-                        thought_signature,
-                        |event| {
-                            let _ = ev_tx.send(event);
-                        },
+                let step = state.step;
+
+                // Execute tools, unless they were outstanding when the run
+                // was interrupted and may already have run
+                let tool_results = if interrupted {
+                    tracing::info!(
+                        count = pending.tool_calls.len(),
+                        "Not re-running tool calls interrupted mid-run"
+                    );
+                    executor::interrupted_results(&pending.tool_calls)
+                } else {
+                    tracing::info!(
+                        count = pending.tool_calls.len(),
+                        "Executing tool calls"
+                    );
+                    executor::execute_tools(
+                        &tool_executor,
+                        &pending.tool_calls,
+                        &state.context,
+                        &executor_config,
                     )
                     .await
-                });
-
-                // Yield events incrementally as they arrive from the stream
-                while let Some(event) = ev_rx.recv().await {
-                    if let AgentEvent::ThoughtSignature { ref signature } = event {
-                        next_thought_signature = Some(signature.clone());
-                    }
-                    yield event;
-                }
-
-                // Get the streaming result after the task completes
-                let result = match stream_handle.await {
-                    Ok(inner) => inner,
-                    Err(e) => Err(StreamError::Connection(format!("Stream task panicked: {}", e))),
-                };
-
-                let result = match result {
-                    Ok(r) => r,
-                    Err(e) => {
-                        yield AgentEvent::error(
-                            e.to_string(),
-                            Some(ErrorCode::LlmError),
-                            false,
-                        );
-                        break;
-                    }
                 };
 
-                // Check if we're done (no tool calls)
-                if result.tool_calls.is_empty() {
-                    yield AgentEvent::step_complete(step, result.finish_reason);
-                    break;
-                }
-
-                // We have tool calls - emit step complete
-                yield AgentEvent::step_complete(step, StepReason::ToolCalls);
-
-                // Execute tools
-                tracing::info!(
-                    count = result.tool_calls.len(),
-                    "Executing tool calls"
-                );
-
-                let tool_results = executor::execute_tools(
-                    &tool_executor,
-                    &result.tool_calls,
-                    &context,
-                    &executor_config,
-                )
-                .await;
-
                 // Emit tool results, checking for awaiting_user condition
                 let mut awaiting_user = false;
                 for tool_result in &tool_results {
@@ -325,17 +494,30 @@ impl AgentLoop {
 
                 // Build messages for next iteration
                 // 1. Add assistant message with tool calls
-                messages.push(executor::build_assistant_tool_message(
-                    &result.content,
-                    &result.tool_calls,
-                    result.thought_signature.as_deref(),
+                state.messages.push(executor::build_assistant_tool_message(
+                    &pending.content,
+                    &pending.tool_calls,
+                    pending.thought_signature.as_deref(),
                 ));
 
-                // 2. Add tool result messages
+                // 2. Add tool result messages, capping oversized results so
+                //    the next LLM call stays within the context window
                 for tool_result in &tool_results {
-                    messages.push(executor::build_tool_result_message(
+                    let mut content = tool_result.to_llm_content();
+                    if let Some(max_bytes) = config.max_tool_result_bytes {
+                        if content.len() > max_bytes {
+                            tracing::warn!(
+                                tool_call_id = %tool_result.tool_call_id,
+                                bytes = content.len(),
+                                max_bytes,
+                                "Truncating oversized tool result"
+                            );
+                            content = executor::truncate_tool_content(content, max_bytes);
+                        }
+                    }
+                    state.messages.push(executor::build_tool_result_message(
                         &tool_result.tool_call_id,
-                        &tool_result.to_llm_content(),
+                        &content,
                     ));
                 }
 
@@ -347,22 +529,50 @@ impl AgentLoop {
                         steps_remaining,
                         if steps_remaining == 1 { "" } else { "s" }
                     );
-                    messages.push(serde_json::json!({
+                    state.messages.push(serde_json::json!({
                         "role": "user",
                         "content": warning
                     }));
                     tracing::debug!(steps_remaining, "Injected turn limit warning");
                 }
 
+                // Tool results recorded; a restart from here starts the next step
+                save_checkpoint_or_warn(&pool, conversation_id.as_deref(), &state).await;
+
                 // Continue loop for next LLM call
             }
 
+            // The run is over, nothing left to resume
+            if let Some(ref id) = conversation_id {
+                if let Err(e) = checkpoint::clear_checkpoint(&pool, id).await {
+                    tracing::warn!(conversation_id = %id, error = %e, "Failed to clear agent checkpoint");
+                }
+            }
+
             // Emit done
-            yield AgentEvent::done(step);
+            yield AgentEvent::done(state.step);
         })
     }
 }
 
+/// Persist a step checkpoint, logging rather than failing the run on error
+async fn save_checkpoint_or_warn(
+    pool: &SqlitePool,
+    conversation_id: Option<&str>,
+    state: &AgentCheckpoint,
+) {
+    if let Some(id) = conversation_id {
+        if let Err(e) = checkpoint::save_checkpoint(pool, id, state).await {
+            tracing::warn!(
+                conversation_id = id,
+                step = state.step,
+                error = %e,
+                "Failed to save agent checkpoint; run will not be resumable from this step"
+            );
+        }
+    }
+}
+
 impl std::fmt::Debug for AgentLoop {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AgentLoop")
@@ -382,5 +592,62 @@ mod tests {
         assert_eq!(config.max_steps, 20);
         assert_eq!(config.tool_timeout, Duration::from_secs(30));
         assert!(config.parallel_tools);
+        assert!(!config.default_model.is_empty());
+        assert_eq!(config.temperature, None);
+        assert_eq!(config.top_p, None);
+        assert_eq!(config.max_tokens, None);
+        assert_eq!(config.max_tool_result_bytes, Some(64 * 1024));
+    }
+
+    #[test]
+    fn test_config_from_env() {
+        let env = |var: &str| match var {
+            "AGENT_DEFAULT_MODEL" => Some("env-model".to_string()),
+            "AGENT_TEMPERATURE" => Some("0.2".to_string()),
+            "AGENT_TOP_P" => Some("0.9".to_string()),
+            "AGENT_MAX_TOKENS" => Some("many".to_string()),
+            _ => None,
+        };
+        let config = AgentConfig::default().with_env(env);
+        assert_eq!(config.default_model, "env-model");
+        assert_eq!(config.temperature, Some(0.2));
+        assert_eq!(config.top_p, Some(0.9));
+        assert_eq!(config.max_tokens, None);
+
+        let config = AgentConfig::default().with_env(|_| None);
+        assert_eq!(config.default_model, AgentConfig::default().default_model);
+    }
+
+    #[test]
+    fn test_run_options_override_config() {
+        let config = AgentConfig {
+            default_model: "default-model".to_string(),
+            temperature: Some(0.7),
+            top_p: Some(0.95),
+            max_tokens: Some(4096),
+            ..AgentConfig::default()
+        };
+
+        let (model, sampling) = config.resolve(RunOptions::default());
+        assert_eq!(model, "default-model");
+        assert_eq!(
+            sampling,
+            SamplingParams {
+                temperature: Some(0.7),
+                top_p: Some(0.95),
+                max_tokens: Some(4096),
+            }
+        );
+
+        let (model, sampling) = config.resolve(RunOptions {
+            model: Some("other-model".to_string()),
+            temperature: Some(0.0),
+            top_p: None,
+            max_tokens: None,
+        });
+        assert_eq!(model, "other-model");
+        assert_eq!(sampling.temperature, Some(0.0));
+        assert_eq!(sampling.top_p, Some(0.95));
+        assert_eq!(sampling.max_tokens, Some(4096));
     }
 }
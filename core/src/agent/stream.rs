@@ -7,15 +7,85 @@ use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 
 use super::protocol::{AgentEvent, StepReason};
 
+/// Default cap on a whole LLM request, including streaming the response
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Default longest wait for the next stream chunk
+///
+/// Reasoning models can think for minutes before the first token, so this is
+/// generous; it only has to catch an upstream that stalled for good.
+pub const DEFAULT_STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(180);
+
 /// Configuration for the LLM client
 #[derive(Debug, Clone)]
 pub struct LlmConfig {
     pub tollbooth_url: String,
     pub tollbooth_user_id: String,
     pub tollbooth_secret: String,
+    /// Cap on a whole request, from `LLM_REQUEST_TIMEOUT_SECS`
+    pub request_timeout: Duration,
+    /// Longest gap between stream chunks, from `LLM_STREAM_IDLE_TIMEOUT_SECS`
+    pub idle_timeout: Duration,
+}
+
+impl LlmConfig {
+    /// Create a config, reading the timeouts from the environment
+    pub fn new(tollbooth_url: String, tollbooth_user_id: String, tollbooth_secret: String) -> Self {
+        Self {
+            tollbooth_url,
+            tollbooth_user_id,
+            tollbooth_secret,
+            request_timeout: timeout_from_env("LLM_REQUEST_TIMEOUT_SECS", DEFAULT_REQUEST_TIMEOUT),
+            idle_timeout: timeout_from_env(
+                "LLM_STREAM_IDLE_TIMEOUT_SECS",
+                DEFAULT_STREAM_IDLE_TIMEOUT,
+            ),
+        }
+    }
+
+    /// Override the request and stream idle timeouts
+    pub fn with_timeouts(mut self, request_timeout: Duration, idle_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self.idle_timeout = idle_timeout;
+        self
+    }
+}
+
+/// Read a positive number of seconds from an env var
+fn timeout_from_env(var: &str, default: Duration) -> Duration {
+    std::env::var(var)
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(default)
+}
+
+/// Sampling parameters sent with each LLM request
+///
+/// `None` leaves the value to the provider's default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SamplingParams {
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    pub max_tokens: Option<u32>,
+}
+
+/// What one LLM call sends, besides the connection settings in [`LlmConfig`]
+#[derive(Debug, Clone)]
+pub struct LlmRequest<'a> {
+    pub model: &'a str,
+    pub messages: &'a [Value],
+    pub tools: &'a [Value],
+    pub sampling: SamplingParams,
+    /// Provider-specific options, such as a thinking budget
+    pub provider_options: Option<Value>,
+    /// Gemini thought signature carried over from the previous step
+    pub thought_signature: Option<String>,
 }
 
 /// A parsed tool call from the LLM response
@@ -58,18 +128,26 @@ pub struct TokenUsage {
 /// 2. Parses the SSE stream
 /// 3. Emits AgentEvents for each chunk
 /// 4. Returns the accumulated result
+///
+/// Fails with `StreamError::Timeout` if the whole request exceeds
+/// `config.request_timeout`, or no chunk arrives within `config.idle_timeout`.
 pub async fn stream_llm_response<F>(
     config: &LlmConfig,
-    model: &str,
-    messages: &[Value],
-    tools: &[Value],
-    provider_options: Option<Value>,
-    thought_signature: Option<String>,
+    request: LlmRequest<'_>,
     mut emit: F,
 ) -> Result<LlmStreamResult, StreamError>
 where
     F: FnMut(AgentEvent),
 {
+    let LlmRequest {
+        model,
+        messages,
+        tools,
+        sampling,
+        provider_options,
+        thought_signature,
+    } = request;
+
     // Build request body
     let mut body = serde_json::json!({
         "model": model,
@@ -82,6 +160,18 @@ where
         body["tool_choice"] = serde_json::json!("auto");
     }
 
+    if let Some(temperature) = sampling.temperature {
+        body["temperature"] = serde_json::json!(temperature);
+    }
+
+    if let Some(top_p) = sampling.top_p {
+        body["top_p"] = serde_json::json!(top_p);
+    }
+
+    if let Some(max_tokens) = sampling.max_tokens {
+        body["max_tokens"] = serde_json::json!(max_tokens);
+    }
+
     if let Some(opts) = provider_options {
         body["provider_options"] = opts;
     }
@@ -98,10 +188,20 @@ where
         &config.tollbooth_secret,
     )
     .header("Content-Type", "application/json")
+    .timeout(config.request_timeout)
     .json(&body)
     .send()
     .await
-    .map_err(|e| StreamError::Connection(e.to_string()))?;
+    .map_err(|e| {
+        if e.is_timeout() {
+            StreamError::Timeout(format!(
+                "no response within {}s",
+                config.request_timeout.as_secs()
+            ))
+        } else {
+            StreamError::Connection(e.to_string())
+        }
+    })?;
 
     if !response.status().is_success() {
         let status = response.status().as_u16();
@@ -130,9 +230,29 @@ where
     let mut usage = TokenUsage::default();
     let mut finish_reason = StepReason::EndTurn;
 
-    while let Some(chunk) = bytes_stream.next().await {
+    loop {
+        let chunk = match tokio::time::timeout(config.idle_timeout, bytes_stream.next()).await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break,
+            Err(_) => {
+                tracing::warn!(
+                    idle_secs = config.idle_timeout.as_secs(),
+                    "LLM stream stalled"
+                );
+                return Err(StreamError::Timeout(format!(
+                    "no data for {}s",
+                    config.idle_timeout.as_secs()
+                )));
+            }
+        };
         let chunk = match chunk {
             Ok(c) => c,
+            Err(e) if e.is_timeout() => {
+                return Err(StreamError::Timeout(format!(
+                    "response not complete within {}s",
+                    config.request_timeout.as_secs()
+                )));
+            }
             Err(e) => {
                 tracing::error!("Stream error: {}", e);
                 break;
@@ -318,6 +438,9 @@ pub enum StreamError {
 
     #[error("Stream interrupted")]
     Interrupted,
+
+    #[error("LLM request timed out: {0}")]
+    Timeout(String),
 }
 
 /// Build provider options for reasoning models
@@ -341,3 +464,45 @@ pub fn build_provider_options(model: &str) -> Option<Value> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_stalled_stream_times_out() {
+        // Sends the headers and one chunk, then goes silent
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 8192];
+            let _ = socket.read(&mut buf).await;
+            let chunk = "data: {\"choices\":[{\"delta\":{\"content\":\"Hi\"}}]}\n\n";
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nTransfer-Encoding: chunked\r\n\r\n{:x}\r\n{}\r\n",
+                chunk.len(),
+                chunk
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            tokio::time::sleep(Duration::from_secs(30)).await;
+        });
+
+        let config = LlmConfig::new(url, "user".to_string(), "secret".to_string())
+            .with_timeouts(Duration::from_secs(30), Duration::from_millis(200));
+        let mut events = Vec::new();
+        let request = LlmRequest {
+            model: "test-model",
+            messages: &[],
+            tools: &[],
+            sampling: SamplingParams::default(),
+            provider_options: None,
+            thought_signature: None,
+        };
+        let result = stream_llm_response(&config, request, |event| events.push(event)).await;
+
+        assert!(matches!(result, Err(StreamError::Timeout(_))));
+        assert!(matches!(&events[..], [AgentEvent::TextDelta { content }] if content == "Hi"));
+    }
+}
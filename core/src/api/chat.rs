@@ -28,7 +28,7 @@ use std::pin::Pin;
 use std::sync::Arc;
 use tokio_stream::StreamExt;
 
-use crate::agent::{AgentConfig, AgentEvent, AgentLoop};
+use crate::agent::{AgentConfig, AgentEvent, AgentLoop, RunOptions};
 use crate::api::chat_usage::{record_chat_usage, UsageData};
 use crate::api::chats::{append_message, ChatMessage, ToolCall};
 use crate::api::compaction::{build_context_for_llm, compact_chat, CompactionOptions};
@@ -1102,6 +1102,17 @@ pub async fn chat_handler(
         compaction_needed,
     );
 
+    // We can't easily send the signature in headers for a streaming response
+    // because it's discovered DURING the stream.
+    // However, the frontend can extract it from the stream itself if we emit a special event.
+
+    ui_message_stream_response(stream)
+}
+
+/// SSE response for a UI Message Stream
+fn ui_message_stream_response(
+    stream: Pin<Box<dyn Stream<Item = Result<SseEvent, Infallible>> + Send>>,
+) -> Response {
     // AI SDK v6 requires this header for UI Message Stream Protocol
     let mut response = Sse::new(stream)
         .keep_alive(axum::response::sse::KeepAlive::new())
@@ -1112,11 +1123,7 @@ pub async fn chat_handler(
         axum::http::HeaderValue::from_static("v1"),
     );
 
-    // We can't easily send the signature in headers for a streaming response
-    // because it's discovered DURING the stream.
-    // However, the frontend can extract it from the stream itself if we emit a special event.
-
-    response.into_response()
+    response
 }
 
 /// Legacy: Create SSE stream without agent loop (no tool execution)
@@ -1542,28 +1549,7 @@ fn create_agent_stream(
             }
         }
 
-        // Determine max_steps based on agent mode
-        // - agent: 20 (full access — edit, search, data)
-        // - research: 50 (read-only, needs more exploration)
-        // - chat: 20 (conversational, no tools but allows multi-turn)
-        let max_steps = match request.agent_mode.as_str() {
-            "research" => 50,
-            _ => 20, // "agent", "chat", or default
-        };
-
-        // Create AgentLoop with YjsState for real-time page editing
-        let agent = AgentLoop::new_with_yjs(
-            pool.clone(),
-            tollbooth_config.url.clone(),
-            tollbooth_config.user_id.clone(),
-            tollbooth_config.secret.clone(),
-            yjs_state,
-        )
-        .with_config(AgentConfig {
-            max_steps,
-            tool_timeout: std::time::Duration::from_secs(30),
-            parallel_tools: true,
-        });
+        let agent = build_agent(&pool, yjs_state, &tollbooth_config, &request.agent_mode);
 
         // Build tool context from request
         let context = ToolContext {
@@ -1576,6 +1562,110 @@ fn create_agent_stream(
         // Get tool definitions based on agent mode
         let tools = crate::tools::get_tools_for_agent_mode(&request.agent_mode);
 
+        let thought_signature = request.thought_signature.clone().or_else(|| {
+            // Fallback: look for signature in the last assistant message of the history
+            api_messages.iter().rev()
+                .filter_map(|m| m.get("thought_signature").and_then(|s| s.as_str()))
+                .next()
+                .map(|s| s.to_string())
+        });
+        let run = AgentRun::Start(Box::new(RunStart {
+            options: RunOptions::with_model(model.clone()),
+            messages: api_messages,
+            tools,
+            context,
+            thought_signature,
+        }));
+
+        let mut events = stream_agent_run(pool, agent, run, cancel_token, chat_id.clone(), model, agent_id, msg_id);
+        while let Some(event) = events.next().await {
+            yield event;
+        }
+
+        // Clean up cancellation token when stream ends
+        cancel_state.remove(&chat_id);
+    })
+}
+
+/// How an agent stream starts
+enum AgentRun {
+    /// A new run over the chat's messages
+    Start(Box<RunStart>),
+    /// The chat's interrupted run, from its checkpoint
+    Resume,
+}
+
+/// Arguments of a new agent run
+struct RunStart {
+    options: RunOptions,
+    messages: Vec<serde_json::Value>,
+    tools: Vec<serde_json::Value>,
+    context: ToolContext,
+    thought_signature: Option<String>,
+}
+
+/// Create the agent for a chat request, with the step limit of its agent mode
+fn build_agent(
+    pool: &SqlitePool,
+    yjs_state: YjsState,
+    tollbooth_config: &TollboothConfig,
+    agent_mode: &str,
+) -> AgentLoop {
+    // Determine max_steps based on agent mode
+    // - agent: 20 (full access — edit, search, data)
+    // - research: 50 (read-only, needs more exploration)
+    // - chat: 20 (conversational, no tools but allows multi-turn)
+    let max_steps = match agent_mode {
+        "research" => 50,
+        _ => 20, // "agent", "chat", or default
+    };
+
+    // Create AgentLoop with YjsState for real-time page editing
+    AgentLoop::new_with_yjs(
+        pool.clone(),
+        tollbooth_config.url.clone(),
+        tollbooth_config.user_id.clone(),
+        tollbooth_config.secret.clone(),
+        yjs_state,
+    )
+    .with_config(AgentConfig {
+        max_steps,
+        tool_timeout: std::time::Duration::from_secs(30),
+        parallel_tools: true,
+        ..AgentConfig::from_env()
+    })
+}
+
+/// Run or resume the agent loop, streaming its events as UI Message Stream
+/// events and saving the assistant message when it finishes
+#[allow(clippy::too_many_arguments)]
+fn stream_agent_run(
+    pool: SqlitePool,
+    agent: AgentLoop,
+    run: AgentRun,
+    cancel_token: CancellationToken,
+    chat_id: String,
+    model: String,
+    agent_id: String,
+    msg_id: String,
+) -> Pin<Box<dyn Stream<Item = Result<SseEvent, Infallible>> + Send>> {
+    Box::pin(async_stream::stream! {
+        let mut agent_stream = match run {
+            AgentRun::Start(start) => {
+                let RunStart { options, messages, tools, context, thought_signature } = *start;
+                agent.run(options, messages, tools, context, thought_signature, Some(cancel_token))
+            }
+            AgentRun::Resume => match agent.resume(&chat_id, Some(cancel_token)).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    let event = StreamEvent::Error { error_text: e.to_string() };
+                    yield Ok(SseEvent::default().data(serialize_event(&event)));
+                    yield Ok(SseEvent::default().data("[DONE]"));
+                    return;
+                }
+            },
+        };
+
         // Send text-start event
         let start_event = StreamEvent::TextStart { id: msg_id.clone() };
         yield Ok(SseEvent::default().data(serialize_event(&start_event)));
@@ -1592,22 +1682,6 @@ fn create_agent_stream(
         // Tool call tracking for persistence
         let mut all_tool_calls: Vec<ToolCall> = Vec::new();
 
-        // Run the agent loop with cancellation support
-        let mut agent_stream = agent.run(
-            model.clone(),
-            api_messages.clone(),
-            tools,
-            context,
-            request.thought_signature.clone().or_else(|| {
-                // Fallback: look for signature in the last assistant message of the history
-                api_messages.iter().rev()
-                    .filter_map(|m| m.get("thought_signature").and_then(|s| s.as_str()))
-                    .next()
-                    .map(|s| s.to_string())
-            }),
-            Some(cancel_token),
-        );
-
         while let Some(event) = agent_stream.next().await {
             match event {
                 AgentEvent::TextDelta { content } => {
@@ -1770,8 +1844,6 @@ fn create_agent_stream(
             }
         }
 
-        // Clean up cancellation token when stream ends
-        cancel_state.remove(&chat_id);
     })
 }
 
@@ -1863,6 +1935,91 @@ pub async fn cancel_chat_handler(
     (StatusCode::OK, Json(response))
 }
 
+/// Request body for resuming a chat's interrupted agent run
+#[derive(Debug, Deserialize)]
+pub struct ResumeChatRequest {
+    #[serde(rename = "chatId")]
+    pub chat_id: String,
+    #[serde(rename = "agentId", default = "default_agent")]
+    pub agent_id: String,
+    /// Agent mode of the interrupted run, which sets its step limit
+    #[serde(rename = "agentMode", default = "default_agent_mode")]
+    pub agent_mode: String,
+}
+
+/// POST /api/chat/resume - Continue a chat's agent run interrupted by a restart
+///
+/// Streams like `/api/chat`, picking the run up from its last checkpoint.
+/// Fails with 404 when the chat has no interrupted run, and with 409 while a
+/// request for the chat is still in progress (its run checkpoints as it goes).
+pub async fn resume_chat_handler(
+    State(pool): State<SqlitePool>,
+    State(yjs_state): State<YjsState>,
+    State(cancel_state): State<ChatCancellationState>,
+    user: AuthUser,
+    Json(request): Json<ResumeChatRequest>,
+) -> Response {
+    let chat_error = |status: StatusCode, error: &str, details: Option<String>| {
+        let error = ChatError {
+            error: error.to_string(),
+            details,
+        };
+        (status, Json(error)).into_response()
+    };
+
+    if cancel_state.is_active(&request.chat_id) {
+        return chat_error(StatusCode::CONFLICT, "Chat request in progress", None);
+    }
+
+    let tollbooth_config = match TollboothConfig::from_env(&user.id.to_string()) {
+        Ok(config) => config,
+        Err(error) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response();
+        }
+    };
+
+    let checkpoint = match crate::agent::checkpoint::load_checkpoint(&pool, &request.chat_id).await
+    {
+        Ok(Some(checkpoint)) => checkpoint,
+        Ok(None) => {
+            return chat_error(StatusCode::NOT_FOUND, "No interrupted run for this chat", None);
+        }
+        Err(e) => {
+            return chat_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load agent checkpoint",
+                Some(e.to_string()),
+            );
+        }
+    };
+
+    let agent = build_agent(&pool, yjs_state, &tollbooth_config, &request.agent_mode);
+    let msg_id = format!("msg_{}", generate_id());
+    let chat_id = request.chat_id;
+
+    let stream = Box::pin(async_stream::stream! {
+        let cancel_token = cancel_state.register(&chat_id);
+
+        let mut events = stream_agent_run(
+            pool,
+            agent,
+            AgentRun::Resume,
+            cancel_token,
+            chat_id.clone(),
+            checkpoint.model,
+            request.agent_id,
+            msg_id,
+        );
+        while let Some(event) = events.next().await {
+            yield event;
+        }
+
+        cancel_state.remove(&chat_id);
+    });
+
+    ui_message_stream_response(stream)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
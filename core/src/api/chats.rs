@@ -851,6 +851,7 @@ Conversation:
     }
 
     let response_json: serde_json::Value = response.json().await?;
+    crate::jobs::cost::record_llm_usage(&response_json);
     let mut title = response_json["choices"][0]["message"]["content"]
         .as_str()
         .unwrap_or("New Chat")
@@ -14,7 +14,8 @@ use crate::api::chats::ChatMessage;
 use crate::api::token_estimation::{estimate_session_context, ContextStatus};
 use crate::types::Timestamp;
 use crate::error::Result;
-use crate::llm::client::{LLMClient, LLMRequest, TollboothClient};
+use crate::llm::client::{LLMClient, LLMRequest};
+use crate::llm::FailoverLLMClient;
 
 // ============================================================================
 // Constants
@@ -130,7 +131,7 @@ RULES:
 
 /// Generate a summary of messages using the LLM
 async fn generate_summary(
-    client: &TollboothClient,
+    client: &dyn LLMClient,
     messages: &[ChatMessage],
     existing_summary: Option<&str>,
     model: &str,
@@ -171,6 +172,7 @@ async fn generate_summary(
         max_tokens: SUMMARY_MAX_TOKENS,
         temperature: SUMMARY_TEMPERATURE,
         system: Some(SUMMARY_SYSTEM_PROMPT.to_string()),
+        response_format: None,
     };
 
     let response = client
@@ -322,8 +324,8 @@ pub async fn compact_chat(
         });
     };
 
-    // Create LLM client for summarization
-    let client = TollboothClient::from_env()
+    // Shared LLM client for summarization (falls back to secondary endpoints)
+    let client = FailoverLLMClient::shared()
         .map_err(|e| crate::Error::Other(format!("Failed to create LLM client: {}", e)))?;
 
     // Get background model from assistant profile
@@ -333,7 +335,7 @@ pub async fn compact_chat(
     let new_summary = timeout(
         Duration::from_secs(60),
         generate_summary(
-            &client,
+            client.as_ref(),
             messages_to_summarize,
             conversation_summary.as_deref(),
             &background_model,
@@ -907,6 +907,7 @@ async fn call_tollbooth(pool: &SqlitePool, user_prompt: &str) -> Result<String>
         .json()
         .await
         .map_err(|e| Error::ExternalApi(format!("Failed to parse Tollbooth response: {e}")))?;
+    crate::jobs::cost::record_llm_usage(&response_json);
 
     let summary = response_json["choices"][0]["message"]["content"]
         .as_str()
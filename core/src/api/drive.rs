@@ -1219,6 +1219,7 @@ pub async fn download_file(
 /// * `pool` - Database connection pool
 /// * `storage` - Storage backend (S3/local)
 /// * `file_id` - Virtual lake object ID (e.g., "virtual:lake:object:abc123")
+/// * `resolve_bodies` - Fill in Gmail bodies stored outside the records
 ///
 /// # Returns
 /// Tuple of (DriveFile metadata, raw bytes)
@@ -1226,6 +1227,7 @@ pub async fn download_lake_object(
     pool: &SqlitePool,
     storage: &crate::storage::Storage,
     file_id: &str,
+    resolve_bodies: bool,
 ) -> Result<(DriveFile, Vec<u8>)> {
     // Extract the real object ID
     let object_id = extract_lake_object_id(file_id)
@@ -1251,6 +1253,11 @@ pub async fn download_lake_object(
 
     // Download from filesystem storage
     let data = storage.download(&storage_key).await?;
+    let data = if resolve_bodies {
+        crate::api::storage::resolve_jsonl_bodies(storage, data).await?
+    } else {
+        data
+    };
 
     Ok((file, data))
 }
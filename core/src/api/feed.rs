@@ -0,0 +1,398 @@
+//! Unified feed - new records across every enabled stream
+//!
+//! Reads the stream objects archived after `since` and tags each record with
+//! where it came from, so a client can poll one endpoint for a timeline
+//! instead of querying each stream. Device records still buffered in memory
+//! show up once flushed.
+//!
+//! Pages follow archive order: objects by when they were archived, then
+//! records by their line in the object. A record that arrives late, with an
+//! event time older than what a client has already seen, still comes after
+//! the client's cursor. The cursor is the position of the last record
+//! returned; polling with it picks up where the previous page stopped.
+//!
+//! Each record is tagged with its event time: its stream's `partition_key`
+//! field, else its `timestamp` field, else the end of its object's time
+//! range.
+
+use base64::Engine as _;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::SqlitePool;
+
+use crate::error::{Error, Result};
+use crate::jobs::archive::record_timestamp;
+use crate::storage::Storage;
+use crate::types::Timestamp;
+
+/// Records per page when the client doesn't ask
+pub const DEFAULT_FEED_LIMIT: usize = 100;
+
+/// Most records returned per page
+pub const MAX_FEED_LIMIT: usize = 1000;
+
+/// One page of the feed
+#[derive(Debug, Clone, Serialize)]
+pub struct FeedPage {
+    /// Records in archive order, tagged with `_source`, `_source_id`,
+    /// `_stream` and `_event_time`
+    pub records: Vec<Value>,
+    /// Pass back as `cursor` to continue after this page
+    pub next_cursor: Option<String>,
+    /// More records were already available past this page
+    pub has_more: bool,
+}
+
+/// Position of a record in archive order
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FeedPosition {
+    /// When the record's object was archived
+    archived_at: DateTime<Utc>,
+    /// Row id of the object, ordering objects archived in the same second
+    object: i64,
+    line: usize,
+}
+
+impl FeedPosition {
+    fn encode(&self) -> String {
+        let raw = format!(
+            "{}\n{}\n{}",
+            self.archived_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+            self.object,
+            self.line
+        );
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    fn decode(cursor: &str) -> Result<Self> {
+        let invalid = || Error::InvalidInput("Invalid feed cursor".to_string());
+        let raw = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(cursor)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or_else(invalid)?;
+
+        let mut parts = raw.splitn(3, '\n');
+        let (Some(archived_at), Some(object), Some(line)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        Ok(Self {
+            archived_at: DateTime::parse_from_rfc3339(archived_at)
+                .map_err(|_| invalid())?
+                .with_timezone(&Utc),
+            object: object.parse().map_err(|_| invalid())?,
+            line: line.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// An archived object that may hold feed records
+#[derive(Debug, sqlx::FromRow)]
+struct FeedObject {
+    object: i64,
+    source_connection_id: String,
+    source: String,
+    stream_name: String,
+    storage_key: String,
+    max_timestamp: Option<Timestamp>,
+    created_at: Timestamp,
+}
+
+impl FeedObject {
+    /// Event time for records that carry none of their own
+    fn fallback_time(&self) -> DateTime<Utc> {
+        self.max_timestamp.unwrap_or(self.created_at).into_inner()
+    }
+}
+
+/// Get the records archived after `since`, or after `cursor` when given
+///
+/// At most one object past the page is downloaded. An object that can't be
+/// read fails the page rather than being skipped, so the cursor never moves
+/// past records the client hasn't seen.
+pub async fn get_feed(
+    pool: &SqlitePool,
+    storage: &Storage,
+    since: Option<DateTime<Utc>>,
+    cursor: Option<&str>,
+    limit: Option<usize>,
+) -> Result<FeedPage> {
+    // Records after (archived_at, object, line); a bare `since` starts after
+    // every object archived in or before that second
+    let after = match (cursor, since) {
+        (Some(cursor), _) => FeedPosition::decode(cursor)?,
+        (None, Some(since)) => FeedPosition {
+            archived_at: since,
+            object: i64::MAX,
+            line: 0,
+        },
+        (None, None) => {
+            return Err(Error::InvalidInput(
+                "Either since or cursor is required".to_string(),
+            ))
+        }
+    };
+    let limit = limit.unwrap_or(DEFAULT_FEED_LIMIT).clamp(1, MAX_FEED_LIMIT);
+
+    // Every object holds at least one record, so the page and the record
+    // telling whether there is more fit in this many objects past the cursor
+    let max_objects = limit + 2;
+    let objects = sqlx::query_as::<_, FeedObject>(
+        r#"
+        SELECT
+            so.rowid AS object,
+            so.source_connection_id,
+            sc.source,
+            so.stream_name,
+            so.storage_key,
+            so.max_timestamp,
+            so.created_at
+        FROM elt_stream_objects so
+        JOIN elt_source_connections sc ON so.source_connection_id = sc.id
+        JOIN elt_stream_connections st
+            ON st.source_connection_id = so.source_connection_id
+           AND st.stream_name = so.stream_name
+        WHERE st.is_enabled = 1
+          AND sc.deleted_at IS NULL
+          AND (datetime(so.created_at) > datetime($1)
+               OR (datetime(so.created_at) = datetime($1) AND so.rowid >= $2))
+        ORDER BY datetime(so.created_at), so.rowid
+        LIMIT $3
+        "#,
+    )
+    .bind(Timestamp::from_utc(after.archived_at).to_sqlite_string())
+    .bind(after.object)
+    .bind(max_objects as i64)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| Error::Database(format!("Failed to list feed objects: {e}")))?;
+
+    // One record past the page tells whether there is more
+    let mut page: Vec<(FeedPosition, Value)> = Vec::new();
+    for object in &objects {
+        if page.len() > limit {
+            break;
+        }
+
+        let records = storage
+            .download_jsonl::<Value>(&object.storage_key)
+            .await
+            .map_err(|e| {
+                Error::Storage(format!(
+                    "Failed to read feed object {}: {e}",
+                    object.storage_key
+                ))
+            })?;
+
+        let partition_key = crate::registry::get_stream(&object.source, &object.stream_name)
+            .and_then(|stream| stream.partition_key);
+        let archived_at = object.created_at.into_inner();
+        for (line, record) in records.into_iter().enumerate() {
+            if object.object == after.object && line <= after.line {
+                continue;
+            }
+            let time = partition_key
+                .and_then(|key| record_timestamp(&record, key))
+                .or_else(|| record_timestamp(&record, "timestamp"))
+                .unwrap_or_else(|| object.fallback_time());
+            let position = FeedPosition {
+                archived_at,
+                object: object.object,
+                line,
+            };
+            page.push((position, tag_record(record, object, time)));
+            if page.len() > limit {
+                break;
+            }
+        }
+    }
+
+    let has_more = page.len() > limit || objects.len() == max_objects;
+    page.truncate(limit);
+    let next_cursor = match (page.last(), cursor) {
+        (Some((position, _)), _) => Some(position.encode()),
+        (None, cursor) => cursor.map(String::from),
+    };
+
+    Ok(FeedPage {
+        records: page.into_iter().map(|(_, record)| record).collect(),
+        next_cursor,
+        has_more,
+    })
+}
+
+/// Tag a record with its source, stream and event time
+fn tag_record(mut record: Value, object: &FeedObject, time: DateTime<Utc>) -> Value {
+    if let Value::Object(fields) = &mut record {
+        fields.insert("_source".to_string(), Value::from(object.source.clone()));
+        fields.insert(
+            "_source_id".to_string(),
+            Value::from(object.source_connection_id.clone()),
+        );
+        fields.insert(
+            "_stream".to_string(),
+            Value::from(object.stream_name.clone()),
+        );
+        fields.insert(
+            "_event_time".to_string(),
+            Value::from(time.to_rfc3339_opts(SecondsFormat::Millis, true)),
+        );
+    }
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::base::mock_transport::testing::migrated_pool;
+    use serde_json::json;
+
+    async fn archive(
+        pool: &SqlitePool,
+        storage: &Storage,
+        source_id: &str,
+        stream: &str,
+        key: &str,
+        records: &[Value],
+    ) {
+        storage.upload_jsonl(key, records).await.unwrap();
+        sqlx::query(
+            "INSERT INTO elt_stream_objects
+             (id, source_connection_id, stream_name, storage_key, record_count, size_bytes,
+              min_timestamp, max_timestamp)
+             VALUES ($1, $2, $3, $4, $5, 0, '2024-01-01 00:00:00', '2024-01-01 23:59:59')",
+        )
+        .bind(key)
+        .bind(source_id)
+        .bind(stream)
+        .bind(key)
+        .bind(records.len() as i32)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_feed_merges_streams_and_paginates() {
+        let pool = migrated_pool().await;
+        let storage = Storage::in_memory();
+        for (source_id, provider, stream, table) in [
+            ("src-mac", "mac", "apps", "stream_mac_apps"),
+            ("src-google", "google", "calendar", "stream_google_calendar"),
+        ] {
+            sqlx::query(
+                "INSERT INTO elt_source_connections (id, source, name) VALUES ($1, $2, $1)",
+            )
+            .bind(source_id)
+            .bind(provider)
+            .execute(&pool)
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT INTO elt_stream_connections (id, source_connection_id, stream_name, table_name)
+                 VALUES ($1, $1, $2, $3)",
+            )
+            .bind(source_id)
+            .bind(stream)
+            .bind(table)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        archive(
+            &pool,
+            &storage,
+            "src-mac",
+            "apps",
+            "mac/apps.jsonl",
+            &[
+                json!({ "app_name": "Mail", "timestamp": "2024-01-01T09:00:00Z" }),
+                json!({ "app_name": "Notes", "timestamp": "2024-01-01T11:00:00Z" }),
+            ],
+        )
+        .await;
+        archive(
+            &pool,
+            &storage,
+            "src-google",
+            "calendar",
+            "google/calendar.jsonl",
+            &[
+                json!({ "summary": "Standup", "start_time": "2024-01-01T10:00:00Z" }),
+                json!({ "summary": "Old", "start_time": "2023-12-31T10:00:00Z" }),
+            ],
+        )
+        .await;
+
+        let since = "2024-01-01T00:00:00Z".parse().unwrap();
+        let page = get_feed(&pool, &storage, Some(since), None, Some(3))
+            .await
+            .unwrap();
+        let names: Vec<&Value> = page
+            .records
+            .iter()
+            .map(|r| r.get("app_name").unwrap_or(&r["summary"]))
+            .collect();
+        assert_eq!(names, vec!["Mail", "Notes", "Standup"]);
+        assert_eq!(page.records[0]["_stream"], "apps");
+        assert_eq!(page.records[2]["_stream"], "calendar");
+        assert_eq!(page.records[2]["_source"], "google");
+        assert_eq!(page.records[2]["_event_time"], "2024-01-01T10:00:00.000Z");
+        assert!(page.has_more);
+
+        // Archive order, not event time: an old event archived now is new
+        let cursor = page.next_cursor.unwrap();
+        let page = get_feed(&pool, &storage, None, Some(&cursor), Some(3))
+            .await
+            .unwrap();
+        assert_eq!(page.records.len(), 1);
+        assert_eq!(page.records[0]["summary"], "Old");
+        assert!(!page.has_more);
+
+        // Nothing new: the cursor stays put for the next poll
+        let last = page.next_cursor.unwrap();
+        let page = get_feed(&pool, &storage, None, Some(&last), None)
+            .await
+            .unwrap();
+        assert!(page.records.is_empty());
+        assert_eq!(page.next_cursor, Some(last.clone()));
+
+        // A late record is picked up after the cursor
+        archive(
+            &pool,
+            &storage,
+            "src-mac",
+            "apps",
+            "mac/apps-late.jsonl",
+            &[json!({ "app_name": "Late", "timestamp": "2023-06-01T09:00:00Z" })],
+        )
+        .await;
+        let page = get_feed(&pool, &storage, None, Some(&last), None)
+            .await
+            .unwrap();
+        assert_eq!(page.records.len(), 1);
+        assert_eq!(page.records[0]["app_name"], "Late");
+
+        // An object that can't be read fails the page instead of being skipped
+        let last = page.next_cursor.unwrap();
+        sqlx::query(
+            "INSERT INTO elt_stream_objects
+             (id, source_connection_id, stream_name, storage_key, record_count, size_bytes)
+             VALUES ('missing', 'src-mac', 'apps', 'mac/missing.jsonl', 1, 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert!(get_feed(&pool, &storage, None, Some(&last), None)
+            .await
+            .is_err());
+
+        assert!(get_feed(&pool, &storage, None, Some("garbage"), None)
+            .await
+            .is_err());
+    }
+}
@@ -1,8 +1,11 @@
 //! Jobs API for async job tracking and management
 
+use crate::entity_resolution::ResolutionStage;
 use crate::error::{Error, Result};
+use crate::jobs::progress::{self, SyncPhase, SyncProgress};
 use crate::jobs::{
-    self, ApiKeys, CreateJobRequest, Job, JobExecutor, JobStatus, SyncJobMetadata, TransformContext,
+    self, ApiKeys, CreateJobRequest, Job, JobExecutor, JobStatus, Pipeline, SyncJobMetadata,
+    TransformContext,
 };
 use crate::storage::{stream_writer::StreamWriter, Storage};
 use crate::types::Timestamp;
@@ -30,21 +33,41 @@ pub async fn trigger_stream_sync(
     stream_name: &str,
     sync_mode: Option<crate::sources::base::SyncMode>,
 ) -> Result<CreateJobResponse> {
+    // Streams without a sync implementation would only fail in the executor
+    let provider: Option<String> =
+        sqlx::query_scalar("SELECT source FROM elt_source_connections WHERE id = $1")
+            .bind(&source_id)
+            .fetch_optional(db)
+            .await?;
+    if let Some(stream) = provider
+        .as_deref()
+        .and_then(|provider| crate::registry::get_stream(provider, stream_name))
+    {
+        super::streams::ensure_implemented(stream)?;
+    }
+
     // Check if there's already an active sync for this stream
     if jobs::has_active_sync_job(db, &source_id, stream_name).await? {
-        return Err(Error::InvalidInput(format!(
-            "Stream '{}' already has an active sync job",
-            stream_name
-        )));
+        return Err(Error::SyncInProgress(format!("stream '{}'", stream_name)));
     }
 
-    // Convert sync mode to string for storage
-    let sync_mode_str = match sync_mode {
-        Some(crate::sources::base::SyncMode::FullRefresh) => "full_refresh",
-        Some(crate::sources::base::SyncMode::Backfill { .. }) => "backfill",
-        Some(crate::sources::base::SyncMode::Incremental { .. }) | None => "incremental",
-    }
-    .to_string();
+    // Convert sync mode to string for storage; backfill ranges go in metadata
+    let sync_mode = sync_mode.unwrap_or_default();
+    let sync_mode_str = sync_mode.as_str().to_string();
+    let (start_date, end_date) = match sync_mode {
+        crate::sources::base::SyncMode::Backfill {
+            start_date,
+            end_date,
+        } => {
+            if start_date >= end_date {
+                return Err(Error::InvalidInput(
+                    "Backfill start_date must be before end_date".to_string(),
+                ));
+            }
+            (Some(start_date), Some(end_date))
+        }
+        _ => (None, None),
+    };
 
     // Load cursor from database for incremental syncs
     let cursor_before = if sync_mode_str == "incremental" {
@@ -68,6 +91,8 @@ pub async fn trigger_stream_sync(
         SyncJobMetadata {
             sync_mode: sync_mode_str,
             cursor_before,
+            start_date,
+            end_date,
         },
     );
 
@@ -90,11 +115,248 @@ pub async fn trigger_stream_sync(
     })
 }
 
+/// A sync job created by a bulk source sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamSyncJob {
+    pub stream_name: String,
+    pub job_id: String,
+    pub status: String,
+    pub started_at: Timestamp,
+}
+
+/// A stream that a bulk source sync did not start a job for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedStreamSync {
+    pub stream_name: String,
+    pub reason: String,
+}
+
+/// Response when syncing every enabled stream of a source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerSourceSyncResponse {
+    pub source_id: String,
+    pub jobs: Vec<StreamSyncJob>,
+    pub skipped: Vec<SkippedStreamSync>,
+}
+
+/// Trigger a sync for every enabled stream of a source
+///
+/// Creates one sync job per enabled stream and returns the created job ids.
+/// Disabled streams are ignored, and streams that already have an active
+/// sync are reported in `skipped` instead of failing the whole request.
+/// Jobs still go through the process-wide `SyncConcurrencyManager`, shared
+/// with scheduled syncs, so a source with many streams queues rather than
+/// running them all at once.
+pub async fn trigger_source_sync(
+    db: &SqlitePool,
+    storage: &Storage,
+    stream_writer: Arc<Mutex<StreamWriter>>,
+    source_id: String,
+    sync_mode: Option<crate::sources::base::SyncMode>,
+) -> Result<TriggerSourceSyncResponse> {
+    let exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM elt_source_connections WHERE id = $1)",
+    )
+    .bind(&source_id)
+    .fetch_one(db)
+    .await?;
+    if !exists {
+        return Err(Error::NotFound(format!("Source not found: {}", source_id)));
+    }
+
+    let stream_names = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT stream_name
+        FROM elt_stream_connections
+        WHERE source_connection_id = $1 AND is_enabled = true
+        ORDER BY stream_name
+        "#,
+    )
+    .bind(&source_id)
+    .fetch_all(db)
+    .await?;
+
+    let mut jobs = Vec::with_capacity(stream_names.len());
+    let mut skipped = Vec::new();
+
+    for stream_name in stream_names {
+        match trigger_stream_sync(
+            db,
+            storage,
+            stream_writer.clone(),
+            source_id.clone(),
+            &stream_name,
+            sync_mode.clone(),
+        )
+        .await
+        {
+            Ok(response) => jobs.push(StreamSyncJob {
+                stream_name,
+                job_id: response.job_id,
+                status: response.status,
+                started_at: response.started_at,
+            }),
+            Err(Error::InvalidInput(reason)) => skipped.push(SkippedStreamSync {
+                stream_name,
+                reason,
+            }),
+            Err(e @ Error::SyncInProgress(_)) => skipped.push(SkippedStreamSync {
+                stream_name,
+                reason: e.to_string(),
+            }),
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(TriggerSourceSyncResponse {
+        source_id,
+        jobs,
+        skipped,
+    })
+}
+
+/// Trigger an on-demand run of a scheduler pipeline
+///
+/// Creates a tracked `pipeline` job and starts it in the background, returning
+/// immediately. `date` only applies to `daily_summary` (defaults to yesterday);
+/// `stage` only to `entity_resolution` (defaults to every stage).
+pub async fn trigger_pipeline_job(
+    db: &SqlitePool,
+    storage: &Storage,
+    stream_writer: Arc<Mutex<StreamWriter>>,
+    pipeline_name: &str,
+    date: Option<chrono::NaiveDate>,
+    stage: Option<ResolutionStage>,
+) -> Result<CreateJobResponse> {
+    let pipeline: Pipeline = pipeline_name.parse().map_err(Error::NotFound)?;
+
+    if jobs::pipeline_job::has_active_pipeline_job(db, pipeline).await? {
+        return Err(Error::InvalidInput(format!(
+            "Pipeline '{}' already has an active job",
+            pipeline
+        )));
+    }
+
+    let metadata = match (date, stage) {
+        (Some(date), None) if pipeline == Pipeline::DailySummary => {
+            serde_json::json!({ "date": date.to_string() })
+        }
+        (None, Some(stage)) if pipeline == Pipeline::EntityResolution => {
+            serde_json::json!({ "stage": stage.as_str() })
+        }
+        (Some(_), _) => {
+            return Err(Error::InvalidInput(format!(
+                "Pipeline '{}' does not take a date",
+                pipeline
+            )))
+        }
+        (None, Some(_)) => {
+            return Err(Error::InvalidInput(format!(
+                "Pipeline '{}' does not take a stage",
+                pipeline
+            )))
+        }
+        (None, None) => serde_json::json!({}),
+    };
+
+    let request = CreateJobRequest::new_pipeline_job(pipeline.as_str(), metadata);
+    let job = jobs::create_job(db, request).await?;
+
+    let api_keys = ApiKeys::from_env();
+    let context = TransformContext::new(Arc::new(storage.clone()), stream_writer, api_keys);
+    let executor = JobExecutor::new(db.clone(), context);
+    executor.execute_async(job.id.clone());
+
+    Ok(CreateJobResponse {
+        job_id: job.id,
+        status: job.status.to_string(),
+        started_at: job.started_at,
+    })
+}
+
 /// Get job status by ID
 pub async fn get_job_status(db: &SqlitePool, job_id: &str) -> Result<Job> {
     jobs::get_job(db, job_id).await
 }
 
+/// How often a pending sync is re-checked while waiting for it to start
+const PROGRESS_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Progress of a stream's active sync, as a stream of snapshots
+///
+/// Attaches to the stream's most recent pending or running sync job. While
+/// the job waits for a sync permit a `pending` snapshot is sent; once it runs,
+/// every progress update is forwarded, and the stream ends with the job's
+/// final snapshot.
+pub async fn sync_progress_stream(
+    db: SqlitePool,
+    source_id: String,
+    stream_name: &str,
+) -> Result<impl futures::Stream<Item = SyncProgress> + Send> {
+    let job_id = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT id FROM elt_jobs
+        WHERE source_connection_id = $1
+          AND stream_name = $2
+          AND job_type = 'sync'
+          AND status IN ('pending', 'running')
+        ORDER BY created_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(&source_id)
+    .bind(stream_name)
+    .fetch_optional(&db)
+    .await?
+    .ok_or_else(|| Error::NotFound(format!("Stream '{}' has no active sync", stream_name)))?;
+
+    Ok(async_stream::stream! {
+        let mut sent_pending = false;
+        loop {
+            if let Some(mut rx) = progress::subscribe(&job_id) {
+                let mut last = rx.borrow_and_update().clone();
+                yield last.clone();
+                while rx.changed().await.is_ok() {
+                    last = rx.borrow_and_update().clone();
+                    yield last.clone();
+                }
+                // The channel may close right after its final update
+                let closing = rx.borrow().clone();
+                if closing != last {
+                    yield closing;
+                }
+                break;
+            }
+
+            // Not tracked: either still waiting to start, or already done
+            let job = match jobs::get_job(&db, &job_id).await {
+                Ok(job) => job,
+                Err(e) => {
+                    tracing::warn!(job_id = %job_id, error = %e, "Failed to load sync job");
+                    break;
+                }
+            };
+            let phase = match job.status {
+                JobStatus::Pending => SyncPhase::Pending,
+                JobStatus::Running => SyncPhase::Running,
+                JobStatus::Succeeded => SyncPhase::Completed,
+                JobStatus::Failed | JobStatus::Cancelled => SyncPhase::Failed,
+            };
+            if phase == SyncPhase::Pending && !sent_pending {
+                sent_pending = true;
+                yield SyncProgress::new(&job_id, phase);
+            } else if matches!(phase, SyncPhase::Completed | SyncPhase::Failed) {
+                let mut done = SyncProgress::new(&job_id, phase);
+                done.records_written = job.records_processed.max(0) as usize;
+                done.error = job.error_message;
+                yield done;
+                break;
+            }
+            tokio::time::sleep(PROGRESS_POLL_INTERVAL).await;
+        }
+    })
+}
+
 /// Query jobs with filters
 #[derive(Debug, Clone, Deserialize)]
 pub struct QueryJobsRequest {
@@ -158,3 +420,58 @@ pub async fn get_job_history(
 
     Ok(jobs)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::base::mock_transport::testing::migrated_pool;
+
+    #[tokio::test]
+    async fn test_source_sync_skips_streams_already_syncing() {
+        let pool = migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, name) VALUES ('src-google', 'google', 'Google')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        for (stream, table) in [
+            ("calendar", "stream_google_calendar"),
+            ("gmail", "stream_google_gmail"),
+        ] {
+            sqlx::query(
+                "INSERT INTO elt_stream_connections (id, source_connection_id, stream_name, table_name)
+                 VALUES ($1, 'src-google', $1, $2)",
+            )
+            .bind(stream)
+            .bind(table)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query(
+            "INSERT INTO elt_jobs (id, job_type, status, source_connection_id, stream_name)
+             VALUES ('job-calendar', 'sync', 'pending', 'src-google', 'calendar')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let response = trigger_source_sync(
+            &pool,
+            &Storage::in_memory(),
+            Arc::new(Mutex::new(StreamWriter::new())),
+            "src-google".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        // Calendar is reported and gmail, after it, still gets its job
+        assert_eq!(response.skipped.len(), 1);
+        assert_eq!(response.skipped[0].stream_name, "calendar");
+        assert!(response.skipped[0].reason.contains("in progress"));
+        assert_eq!(response.jobs.len(), 1);
+        assert_eq!(response.jobs[0].stream_name, "gmail");
+    }
+}
@@ -12,6 +12,7 @@
 //! - `device_pairing` - Device registration and pairing
 //! - `streams` - Stream management and configuration
 //! - `jobs` - Async job tracking and management
+//! - `feed` - Unified feed of new records across streams
 //! - `registry` - Catalog/registry queries
 //! - `ontologies` - Ontology table queries
 
@@ -20,6 +21,7 @@
 //! - `agents` - AI agent configurations
 //! - `seed_testing` - Seed data pipeline validation and inspection
 //! - `metrics` - Activity metrics and job statistics
+//! - `webhooks` - Provider webhook verification and sync dispatch
 
 pub mod agents;
 pub mod assistant_profile;
@@ -37,6 +39,7 @@ pub mod device_pairing;
 pub mod drive;
 pub mod entities;
 pub mod exa;
+pub mod feed;
 pub mod feedback;
 pub mod internal;
 pub mod jobs;
@@ -73,11 +76,12 @@ pub mod unsplash;
 pub mod usage;
 pub mod validation;
 pub mod views;
+pub mod webhooks;
 pub mod wiki;
 
 // Re-export commonly used types
 pub use streams::StreamConnection;
-pub use types::{SourceConnection, SourceConnectionStatus};
+pub use types::{SourceConnection, SourceConnectionStatus, SourceStatus, StreamError};
 
 // Re-export all functions for convenience
 pub use agents::{get_agent, list_agents, AgentInfo};
@@ -155,10 +159,12 @@ pub use entities::{
 pub use exa::{
     search as exa_search, SearchRequest as ExaSearchRequest, SearchResponse as ExaSearchResponse,
 };
+pub use feed::{get_feed, FeedPage};
 pub use feedback::{submit_feedback, FeedbackRequest};
 pub use jobs::{
-    cancel_job, get_job_history, get_job_status, query_jobs, trigger_stream_sync,
-    CreateJobResponse, QueryJobsRequest,
+    cancel_job, get_job_history, get_job_status, query_jobs, sync_progress_stream, trigger_pipeline_job,
+    trigger_source_sync, trigger_stream_sync, CreateJobResponse, QueryJobsRequest, SkippedStreamSync, StreamSyncJob,
+    TriggerSourceSyncResponse,
 };
 pub use media::{
     get_media, is_audio_type, is_image_type, is_supported_media_type, is_video_type, upload_media,
@@ -172,9 +178,9 @@ pub use models::{
     get_model, list_models, list_recommended_models, ModelInfo, RecommendedModelsResponse,
 };
 pub use oauth::{
-    create_source, handle_oauth_callback, initiate_oauth_flow, register_device,
-    CreateSourceRequest, OAuthAuthorizeRequest, OAuthAuthorizeResponse, OAuthCallbackParams,
-    RegisterDeviceRequest,
+    create_source, handle_oauth_callback, initiate_oauth_flow, initiate_oauth_reauthorization,
+    initiate_oauth_reconnect, missing_oauth_scopes, register_device, CreateSourceRequest,
+    OAuthAuthorizeRequest, OAuthAuthorizeResponse, OAuthCallbackParams, RegisterDeviceRequest,
 };
 pub use unsplash::{
     search as unsplash_search, SearchRequest as UnsplashSearchRequest,
@@ -238,7 +244,8 @@ pub use places::{
     AutocompleteResponse, PlaceDetailsRequest, PlaceDetailsResponse,
 };
 pub use plaid::{
-    create_link_token, exchange_public_token, get_plaid_accounts, remove_plaid_item,
+    create_link_token, exchange_public_token, get_plaid_accounts, reconnect_plaid_item,
+    remove_plaid_item,
     CreateLinkTokenRequest, CreateLinkTokenResponse, ExchangeTokenRequest, ExchangeTokenResponse,
     PlaidAccount,
 };
@@ -275,14 +282,21 @@ pub use seed_testing::{
     get_data_quality_metrics, get_pipeline_status, DataQualityMetrics, PipelineStatus,
 };
 pub use sources::{
-    delete_source, get_source, get_source_status, list_sources, pause_source, resume_source,
+    delete_source, execute_prune, get_source, get_source_network, get_source_status, list_sources,
+    pause_source, plan_prune, plan_source_purge, restore_source, resume_source, soft_delete_source,
+    update_source_network, ConfirmedPrune, ExpiredSourcePurge, PrunePlan,
+};
+pub use storage::{
+    get_object_content, list_recent_objects, preview_stream_records, ObjectContent,
+    StreamObjectSummary, StreamPreview,
 };
-pub use storage::{get_object_content, list_recent_objects, ObjectContent, StreamObjectSummary};
 pub use streams::{
-    bulk_update_streams, disable_stream, enable_stream, get_stream_info, list_source_streams,
-    update_stream_config, update_stream_schedule, BulkUpdateStreamsRequest,
-    BulkUpdateStreamsResponse, EnableStreamRequest, StreamUpdate, UpdateStreamConfigRequest,
-    UpdateStreamScheduleRequest,
+    bulk_update_streams, disable_stream, enable_stream, get_stream_cursor, get_stream_info,
+    list_source_streams, merge_config_patch, patch_stream_config, reset_stream_cursor,
+    update_stream_config, update_stream_encryption, update_stream_partitioning,
+    update_stream_schedule, BulkUpdateStreamsRequest, BulkUpdateStreamsResponse,
+    EnableStreamRequest, StreamCursor, StreamUpdate, UpdateStreamConfigRequest,
+    UpdateStreamEncryptionRequest, UpdateStreamPartitioningRequest, UpdateStreamScheduleRequest,
 };
 pub use system_update::CURRENT_COMMIT;
 pub use token_estimation::{
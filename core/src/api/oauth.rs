@@ -8,6 +8,8 @@ use tokio::sync::Mutex;
 use super::sources::get_source;
 use super::types::SourceConnection;
 use crate::error::{Error, Result};
+use crate::sources::base::oauth::pkce::{self, PendingAuthorization, PkcePair};
+use crate::sources::base::oauth::state::OAuthSession;
 use crate::sources::base::TokenManager;
 use crate::storage::{stream_writer::StreamWriter, Storage};
 
@@ -74,8 +76,9 @@ fn validate_return_url(url: &str) -> Result<()> {
 /// Request parameters for initiating OAuth authorization
 #[derive(Debug, serde::Deserialize)]
 pub struct OAuthAuthorizeRequest {
-    /// @deprecated - No longer used. The backend determines the OAuth callback URL.
-    /// Kept for API backwards compatibility only.
+    /// @deprecated - No longer used. The callback URL is set per deployment
+    /// (`OAUTH_REDIRECT_URI` or `BACKEND_URL`). Kept for API backwards
+    /// compatibility only.
     #[serde(default)]
     pub redirect_uri: Option<String>,
     
@@ -113,6 +116,8 @@ pub struct OAuthCallbackParams {
     pub access_token: Option<String>,
     pub refresh_token: Option<String>,
     pub expires_in: Option<i64>,
+    /// Scopes actually granted, if the provider/proxy reports them
+    pub scope: Option<String>,
     pub provider: String,
     pub state: Option<String>,
     // Notion-specific fields
@@ -144,6 +149,7 @@ pub struct RegisterDeviceRequest {
 /// Initiate OAuth authorization flow
 ///
 /// # Arguments
+/// * `db` - Database pool, used to persist the PKCE verifier for the callback
 /// * `provider` - OAuth provider name (e.g., "google", "notion")
 /// * `redirect_uri` - Unused, kept for API compatibility
 /// * `return_url` - Full URL where user should be redirected after OAuth completes.
@@ -154,6 +160,7 @@ pub struct RegisterDeviceRequest {
 ///   - `virtues://oauth/callback` (iOS app)
 ///   - `/data/sources/add` (relative path, resolved by client)
 pub async fn initiate_oauth_flow(
+    db: &SqlitePool,
     provider: &str,
     _redirect_uri: Option<String>,
     return_url: Option<String>,
@@ -176,10 +183,6 @@ pub async fn initiate_oauth_flow(
         .as_ref()
         .ok_or_else(|| Error::Configuration(format!("No OAuth config for provider: {provider}")))?;
 
-    // Get backend URL for OAuth callback (where OAuth provider redirects)
-    let backend_url = std::env::var("BACKEND_URL")
-        .unwrap_or_else(|_| "http://localhost:8000".to_string());
-
     // Validate and store the full return URL in state
     // This is where the user will be redirected after OAuth completes
     let return_url = return_url.unwrap_or_else(|| "/data/sources/add".to_string());
@@ -187,27 +190,246 @@ pub async fn initiate_oauth_flow(
     
     let state_token = crate::sources::base::oauth::state::generate_state(Some(&return_url))?;
 
-    let proxy_url =
-        std::env::var("OAUTH_PROXY_URL").unwrap_or_else(|_| "https://auth.virtues.com".to_string());
+    let scopes = oauth_config.scopes.join(" ");
 
-    // Backend callback URL - where OAuth provider redirects after authorization
-    let backend_callback_url = format!("{}/oauth/callback", backend_url);
+    start_authorization(db, provider, state_token, &scopes).await
+}
 
-    let scopes = oauth_config.scopes.join(" ");
+/// Callback URL the OAuth provider redirects to after authorization
+///
+/// `OAUTH_REDIRECT_URI` sets it explicitly (e.g. when the backend sits behind
+/// a reverse proxy on its own domain); otherwise it is `BACKEND_URL` plus
+/// `/oauth/callback`.
+pub fn oauth_redirect_uri() -> String {
+    std::env::var("OAUTH_REDIRECT_URI")
+        .ok()
+        .filter(|uri| !uri.is_empty())
+        .unwrap_or_else(|| {
+            let backend_url = std::env::var("BACKEND_URL")
+                .unwrap_or_else(|_| "http://localhost:8000".to_string());
+            format!("{}/oauth/callback", backend_url.trim_end_matches('/'))
+        })
+}
 
-    let authorization_url = format!(
-        "{proxy_url}/{provider}/auth?return_url={}&state={}&scope={}",
-        urlencoding::encode(&backend_callback_url),
-        urlencoding::encode(&state_token),
-        urlencoding::encode(&scopes)
+/// Persist a fresh PKCE verifier for `state_token` and build the authorize URL
+async fn start_authorization(
+    db: &SqlitePool,
+    provider: &str,
+    state_token: String,
+    scopes: &str,
+) -> Result<OAuthAuthorizeResponse> {
+    let pkce = PkcePair::generate();
+    let redirect_uri = oauth_redirect_uri();
+
+    let authorization_url = build_authorization_url(
+        provider,
+        &state_token,
+        scopes,
+        &redirect_uri,
+        &pkce.challenge,
     );
 
+    pkce::save_pending(
+        db,
+        &state_token,
+        &PendingAuthorization {
+            provider: provider.to_string(),
+            code_verifier: pkce.verifier,
+            redirect_uri,
+        },
+    )
+    .await?;
+
     Ok(OAuthAuthorizeResponse {
         authorization_url,
         state: state_token,
     })
 }
 
+/// Build the OAuth proxy authorization URL for a provider
+fn build_authorization_url(
+    provider: &str,
+    state_token: &str,
+    scopes: &str,
+    redirect_uri: &str,
+    code_challenge: &str,
+) -> String {
+    let proxy_url =
+        std::env::var("OAUTH_PROXY_URL").unwrap_or_else(|_| "https://auth.virtues.com".to_string());
+
+    format!(
+        "{proxy_url}/{provider}/auth?return_url={}&state={}&scope={}&code_challenge={}&code_challenge_method=S256",
+        urlencoding::encode(redirect_uri),
+        urlencoding::encode(state_token),
+        urlencoding::encode(scopes),
+        urlencoding::encode(code_challenge)
+    )
+}
+
+/// Split a scope string into individual scopes
+///
+/// Google reports scopes space-separated while GitHub and Strava use commas,
+/// so both are accepted.
+fn split_scopes(scopes: &str) -> impl Iterator<Item = &str> {
+    scopes
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+}
+
+/// Scopes in `required` that aren't covered by `granted`
+fn missing_scopes(required: &[String], granted: &str) -> Vec<String> {
+    let granted: std::collections::HashSet<&str> = split_scopes(granted).collect();
+    let mut missing: Vec<String> = Vec::new();
+    for scope in required.iter().flat_map(|s| split_scopes(s)) {
+        if !granted.contains(scope) && !missing.iter().any(|m| m == scope) {
+            missing.push(scope.to_string());
+        }
+    }
+    missing
+}
+
+/// OAuth scope state of a source connection
+struct SourceScopes {
+    provider: String,
+    /// Scopes granted at the last authorization
+    granted: String,
+    /// Base provider scopes plus those needed by enabled streams
+    required: Vec<String>,
+}
+
+/// Load granted and required scopes for an OAuth source
+///
+/// Returns `None` for sources that don't use OAuth2. Connections made before
+/// scopes were tracked are assumed to hold the provider's base scopes.
+async fn load_source_scopes(db: &SqlitePool, source_id: &str) -> Result<Option<SourceScopes>> {
+    let row = sqlx::query_as::<_, (String, Option<String>)>(
+        "SELECT source, granted_scopes FROM elt_source_connections WHERE id = $1",
+    )
+    .bind(source_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| Error::Database(format!("Failed to load source scopes: {e}")))?
+    .ok_or_else(|| Error::NotFound(format!("Source not found: {source_id}")))?;
+    let (provider, granted_scopes) = row;
+
+    let Some(registered) = crate::registry::get_source(&provider) else {
+        return Ok(None);
+    };
+    let Some(oauth_config) = registered
+        .descriptor
+        .oauth_config
+        .as_ref()
+        .filter(|_| registered.descriptor.auth_type == crate::registry::AuthType::OAuth2)
+    else {
+        return Ok(None);
+    };
+
+    let enabled_streams = sqlx::query_scalar::<_, String>(
+        "SELECT stream_name FROM elt_stream_connections WHERE source_connection_id = $1 AND is_enabled = true",
+    )
+    .bind(source_id)
+    .fetch_all(db)
+    .await
+    .map_err(|e| Error::Database(format!("Failed to load enabled streams: {e}")))?;
+
+    let mut required: Vec<String> = oauth_config.scopes.iter().map(|s| s.to_string()).collect();
+    for stream in &registered.streams {
+        if enabled_streams
+            .iter()
+            .any(|name| name == stream.descriptor.name)
+        {
+            for scope in &stream.descriptor.required_scopes {
+                if !required.iter().any(|r| r == scope) {
+                    required.push(scope.to_string());
+                }
+            }
+        }
+    }
+
+    Ok(Some(SourceScopes {
+        provider,
+        granted: granted_scopes.unwrap_or_else(|| oauth_config.scopes.join(" ")),
+        required,
+    }))
+}
+
+/// Scopes the source's enabled streams need but the stored grant lacks
+///
+/// Empty for non-OAuth sources and for sources whose grant covers every
+/// enabled stream.
+pub async fn missing_oauth_scopes(db: &SqlitePool, source_id: &str) -> Result<Vec<String>> {
+    Ok(load_source_scopes(db, source_id)
+        .await?
+        .map(|scopes| missing_scopes(&scopes.required, &scopes.granted))
+        .unwrap_or_default())
+}
+
+/// Scopes to request when re-authorizing: everything already granted plus
+/// whatever is missing, so the upgrade never narrows the grant
+fn reauthorization_scopes(scopes: &SourceScopes) -> String {
+    let mut requested: Vec<String> = split_scopes(&scopes.granted).map(String::from).collect();
+    requested.extend(missing_scopes(&scopes.required, &scopes.granted));
+    requested.join(" ")
+}
+
+/// Initiate re-authorization of an existing OAuth source
+///
+/// Produces an authorize URL requesting the union of granted and required
+/// scopes. On callback the stored credentials are replaced in place, so the
+/// connection, its streams and cursors are kept.
+pub async fn initiate_oauth_reauthorization(
+    db: &SqlitePool,
+    source_id: &str,
+    return_url: Option<String>,
+) -> Result<OAuthAuthorizeResponse> {
+    start_reauthorization(db, source_id, return_url, false).await
+}
+
+/// Initiate reconnection of an OAuth source whose grant was revoked or expired
+///
+/// Same flow as `initiate_oauth_reauthorization`: the callback updates the
+/// existing connection with the new tokens, keeping its id, stream configs
+/// and cursors. It then queues a sync of every enabled stream, which resumes
+/// incrementally from the stored cursors instead of starting over.
+pub async fn initiate_oauth_reconnect(
+    db: &SqlitePool,
+    source_id: &str,
+    return_url: Option<String>,
+) -> Result<OAuthAuthorizeResponse> {
+    start_reauthorization(db, source_id, return_url, true).await
+}
+
+async fn start_reauthorization(
+    db: &SqlitePool,
+    source_id: &str,
+    return_url: Option<String>,
+    reconnect: bool,
+) -> Result<OAuthAuthorizeResponse> {
+    let scopes = load_source_scopes(db, source_id).await?.ok_or_else(|| {
+        Error::InvalidInput(format!(
+            "Source {source_id} does not use OAuth2 authentication"
+        ))
+    })?;
+
+    let return_url = return_url.unwrap_or_else(|| "/data/sources".to_string());
+    validate_return_url(&return_url)?;
+
+    let session = OAuthSession {
+        return_url: Some(return_url),
+        reauth_source_id: Some(source_id.to_string()),
+        reconnect,
+    };
+    let state_token = crate::sources::base::oauth::state::generate_state(Some(&session.encode()))?;
+
+    start_authorization(
+        db,
+        &scopes.provider,
+        state_token,
+        &reauthorization_scopes(&scopes),
+    )
+    .await
+}
+
 /// Fetch a meaningful source name based on the OAuth provider
 /// Falls back to "{Provider} Account" if fetching fails
 async fn fetch_source_name(provider: &str, access_token: &str, display_name: &str) -> String {
@@ -262,13 +484,29 @@ pub async fn handle_oauth_callback(
     params: &OAuthCallbackParams,
 ) -> Result<OAuthCallbackResponse> {
     // SECURITY: Validate state parameter and extract return URL
-    let return_url = if let Some(ref state) = params.state {
-        crate::sources::base::oauth::state::validate_and_extract_state(state)?
-    } else {
+    let Some(ref state) = params.state else {
         return Err(Error::InvalidInput(
             "Missing state parameter - possible CSRF attempt".to_string(),
         ));
     };
+    let session = OAuthSession::decode(
+        crate::sources::base::oauth::state::validate_and_extract_state(state)?,
+    );
+
+    // The state must have been issued here and not used yet; this also yields
+    // the PKCE verifier and redirect_uri for the code exchange
+    let pending = pkce::take_pending(db, state).await?.ok_or_else(|| {
+        Error::InvalidInput(
+            "OAuth state already used or not issued by this server - possible CSRF attempt"
+                .to_string(),
+        )
+    })?;
+    if pending.provider != params.provider {
+        return Err(Error::InvalidInput(format!(
+            "OAuth state was issued for {}, not {}",
+            pending.provider, params.provider
+        )));
+    }
 
     // Validate provider exists
     super::validation::validate_provider_name(&params.provider)?;
@@ -277,12 +515,14 @@ pub async fn handle_oauth_callback(
         .ok_or_else(|| Error::InvalidInput(format!("Unknown provider: {}", params.provider)))?;
 
     // Get tokens either directly from callback or by exchanging code
-    let (access_token, refresh_token, expires_in) = if let Some(token) = &params.access_token {
+    let (access_token, refresh_token, expires_in, scope) = if let Some(token) = &params.access_token
+    {
         // Direct token flow (used by Notion, Google)
         (
             token.clone(),
             params.refresh_token.clone(),
             params.expires_in,
+            params.scope.clone(),
         )
     } else if let Some(code) = &params.code {
         // Code exchange flow
@@ -295,6 +535,8 @@ pub async fn handle_oauth_callback(
             .post(&format!("{}/{}/token", proxy_url, params.provider))
             .json(&serde_json::json!({
                 "code": code,
+                "code_verifier": pending.code_verifier,
+                "redirect_uri": pending.redirect_uri,
             }))
             .send()
             .await
@@ -319,6 +561,8 @@ pub async fn handle_oauth_callback(
             refresh_token: Option<String>,
             #[serde(default)]
             expires_in: Option<i64>,
+            #[serde(default)]
+            scope: Option<String>,
         }
 
         let token_data: TokenResponse = response
@@ -330,6 +574,7 @@ pub async fn handle_oauth_callback(
             token_data.access_token,
             token_data.refresh_token,
             token_data.expires_in,
+            token_data.scope,
         )
     } else {
         return Err(Error::Other(
@@ -337,9 +582,68 @@ pub async fn handle_oauth_callback(
         ));
     };
 
+    let token_manager = std::sync::Arc::new(TokenManager::new(db.clone())?);
+
+    // Scope upgrade: replace credentials on the existing connection in place
+    if let Some(source_id) = session.reauth_source_id {
+        let scopes = load_source_scopes(db, &source_id).await?.ok_or_else(|| {
+            Error::InvalidInput(format!(
+                "Source {source_id} does not use OAuth2 authentication"
+            ))
+        })?;
+        if scopes.provider != params.provider {
+            return Err(Error::InvalidInput(format!(
+                "Re-authorization provider mismatch for source {source_id}"
+            )));
+        }
+
+        let granted = scope.unwrap_or_else(|| reauthorization_scopes(&scopes));
+        token_manager
+            .update_source_tokens(
+                &source_id,
+                access_token,
+                refresh_token,
+                expires_in,
+                &granted,
+            )
+            .await?;
+
+        tracing::info!(
+            source_id = %source_id,
+            provider = %params.provider,
+            reconnect = session.reconnect,
+            "OAuth source re-authorized"
+        );
+
+        if session.reconnect {
+            if let (Some(storage), Some(stream_writer)) = (storage, stream_writer) {
+                let enabled: Vec<String> = sqlx::query_scalar(
+                    "SELECT stream_name FROM elt_stream_connections
+                     WHERE source_connection_id = $1 AND is_enabled = true",
+                )
+                .bind(&source_id)
+                .fetch_all(db)
+                .await?;
+                spawn_stream_syncs(
+                    db,
+                    storage,
+                    stream_writer,
+                    &source_id,
+                    &params.provider,
+                    enabled,
+                );
+            }
+        }
+
+        let source = get_source(db, source_id).await?;
+        return Ok(OAuthCallbackResponse {
+            source,
+            return_url: session.return_url,
+        });
+    }
+
     // Fetch a meaningful name based on the provider
     let source_name = fetch_source_name(&params.provider, &access_token, &descriptor.descriptor.display_name).await;
-    let token_manager = std::sync::Arc::new(TokenManager::new(db.clone())?);
 
     let source_id = token_manager
         .store_initial_tokens(
@@ -351,62 +655,96 @@ pub async fn handle_oauth_callback(
         )
         .await?;
 
+    let granted = scope.unwrap_or_else(|| {
+        descriptor
+            .descriptor
+            .oauth_config
+            .as_ref()
+            .map(|c| c.scopes.join(" "))
+            .unwrap_or_default()
+    });
+    token_manager
+        .store_granted_scopes(&source_id, &granted)
+        .await?;
+
     super::streams::enable_default_streams(db, source_id.clone(), &params.provider).await?;
 
     // Trigger initial sync for all enabled streams (only if storage and stream_writer are provided)
     if let (Some(storage), Some(stream_writer)) = (storage, stream_writer) {
-        let source_reg = crate::registry::get_source(&params.provider);
-        if let Some(reg) = source_reg {
-            for stream_reg in &reg.streams {
-                let stream_desc = &stream_reg.descriptor;
-                if !stream_desc.enabled {
-                    continue;
-                }
-
-                let db_clone = db.clone();
-                let storage_clone = storage.clone();
-                let stream_writer_clone = stream_writer.clone();
-                let stream_name = stream_desc.name.to_string();
-                let source_id_clone = source_id.clone();
-                let provider = params.provider.clone();
-
-                tokio::spawn(async move {
-                    match crate::api::jobs::trigger_stream_sync(
-                        &db_clone,
-                        &storage_clone,
-                        stream_writer_clone,
-                        source_id_clone.clone(),
-                        &stream_name,
-                        None,
-                    )
-                    .await
-                    {
-                        Ok(response) => {
-                            tracing::info!(
-                                source_id = %source_id_clone,
-                                provider = %provider,
-                                stream = %stream_name,
-                                job_id = %response.job_id,
-                                "Initial sync job created for OAuth stream"
-                            );
-                        }
-                        Err(e) => {
-                            tracing::error!(
-                                source_id = %source_id_clone,
-                                provider = %provider,
-                                stream = %stream_name,
-                                error = %e,
-                                "Failed to create initial sync job for OAuth stream"
-                            );
-                        }
-                    }
-                });
-            }
-        }
+        let enabled: Vec<String> = crate::registry::get_source(&params.provider)
+            .map(|reg| {
+                reg.streams
+                    .iter()
+                    .filter(|s| s.descriptor.enabled)
+                    .map(|s| s.descriptor.name.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        spawn_stream_syncs(
+            db,
+            storage,
+            stream_writer,
+            &source_id,
+            &params.provider,
+            enabled,
+        );
     }
 
     let source = get_source(db, source_id).await?;
-    Ok(OAuthCallbackResponse { source, return_url })
+    Ok(OAuthCallbackResponse {
+        source,
+        return_url: session.return_url,
+    })
+}
+
+/// Queue a sync job for each stream in the background
+fn spawn_stream_syncs(
+    db: &SqlitePool,
+    storage: &Storage,
+    stream_writer: Arc<Mutex<StreamWriter>>,
+    source_id: &str,
+    provider: &str,
+    stream_names: Vec<String>,
+) {
+    for stream_name in stream_names {
+        let db_clone = db.clone();
+        let storage_clone = storage.clone();
+        let stream_writer_clone = stream_writer.clone();
+        let source_id_clone = source_id.to_string();
+        let provider = provider.to_string();
+
+        tokio::spawn(async move {
+            match crate::api::jobs::trigger_stream_sync(
+                &db_clone,
+                &storage_clone,
+                stream_writer_clone,
+                source_id_clone.clone(),
+                &stream_name,
+                None,
+            )
+            .await
+            {
+                Ok(response) => {
+                    tracing::info!(
+                        source_id = %source_id_clone,
+                        provider = %provider,
+                        stream = %stream_name,
+                        job_id = %response.job_id,
+                        "Sync job created for OAuth stream"
+                    );
+                }
+                Err(e) => {
+                    tracing::error!(
+                        source_id = %source_id_clone,
+                        provider = %provider,
+                        stream = %stream_name,
+                        error = %e,
+                        "Failed to create sync job for OAuth stream"
+                    );
+                }
+            }
+        });
+    }
 }
 
 /// Create a source manually (for testing or direct token input)
@@ -579,6 +917,75 @@ mod tests {
         assert!(validate_return_url("https://fake-virtues.com/callback").is_err());
     }
 
+    #[test]
+    fn test_authorization_url_carries_redirect_uri_and_pkce_challenge() {
+        let url = build_authorization_url(
+            "google",
+            "state_token",
+            "openid email",
+            "https://jace.virtues.com/oauth/callback",
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM",
+        );
+        let query: std::collections::HashMap<String, String> = url::Url::parse(&url)
+            .unwrap()
+            .query_pairs()
+            .into_owned()
+            .collect();
+
+        assert_eq!(
+            query["return_url"],
+            "https://jace.virtues.com/oauth/callback"
+        );
+        assert_eq!(query["state"], "state_token");
+        assert_eq!(query["scope"], "openid email");
+        assert_eq!(
+            query["code_challenge"],
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+        assert_eq!(query["code_challenge_method"], "S256");
+    }
+
+    #[test]
+    fn test_missing_scopes() {
+        let required = vec![
+            "https://www.googleapis.com/auth/calendar.readonly".to_string(),
+            "https://www.googleapis.com/auth/gmail.readonly".to_string(),
+        ];
+
+        let missing = missing_scopes(
+            &required,
+            "openid https://www.googleapis.com/auth/calendar.readonly",
+        );
+        assert_eq!(
+            missing,
+            vec!["https://www.googleapis.com/auth/gmail.readonly"]
+        );
+
+        assert!(missing_scopes(&required, &required.join(" ")).is_empty());
+
+        // Comma-separated grants (GitHub, Strava)
+        let required = vec!["read,activity:read_all".to_string()];
+        assert!(missing_scopes(&required, "read,activity:read_all").is_empty());
+        assert_eq!(missing_scopes(&required, "read"), vec!["activity:read_all"]);
+    }
+
+    #[test]
+    fn test_reauthorization_scopes_keep_existing_grant() {
+        let scopes = SourceScopes {
+            provider: "google".to_string(),
+            granted: "openid https://www.googleapis.com/auth/calendar.readonly".to_string(),
+            required: vec![
+                "https://www.googleapis.com/auth/calendar.readonly".to_string(),
+                "https://www.googleapis.com/auth/gmail.readonly".to_string(),
+            ],
+        };
+
+        assert_eq!(
+            reauthorization_scopes(&scopes),
+            "openid https://www.googleapis.com/auth/calendar.readonly https://www.googleapis.com/auth/gmail.readonly"
+        );
+    }
+
     #[test]
     fn test_validate_return_url_edge_cases() {
         // Empty string should fail
@@ -1,6 +1,8 @@
 //! Ontologies API
 //!
-//! Endpoints for querying available ontology tables based on enabled streams.
+//! Endpoints for querying available ontology tables based on enabled streams,
+//! which streams feed which ontology tables, and whether the schema has a
+//! table for every ontology the registry expects.
 
 use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
@@ -168,6 +170,112 @@ pub async fn get_ontologies_overview(db: &SqlitePool) -> Result<Vec<OntologyOver
     Ok(overviews)
 }
 
+/// Filters for `ontology_lineage`; unset fields match everything
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct OntologyLineageQuery {
+    /// Source name (e.g. `google`)
+    pub source: Option<String>,
+    /// Stream name (e.g. `calendar`) or stream table (`stream_google_calendar`)
+    pub stream: Option<String>,
+    /// Ontology table, with or without the `data_` prefix
+    pub ontology: Option<String>,
+}
+
+/// One stream → ontology table edge from the registry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OntologyLineage {
+    pub source: String,
+    pub stream: String,
+    pub stream_table: String,
+    pub ontology: String,
+}
+
+/// Which ontology tables each stream feeds, from the registry
+///
+/// Filtering by source or stream answers "what does this feed?"; filtering
+/// by ontology answers "what feeds this?". Covers every enabled stream in
+/// the registry, whether or not it is connected.
+pub fn ontology_lineage(query: &OntologyLineageQuery) -> Vec<OntologyLineage> {
+    let ontology = query
+        .ontology
+        .as_deref()
+        .map(|o| o.strip_prefix("data_").unwrap_or(o));
+
+    let mut edges: Vec<OntologyLineage> = registry::list_all_streams()
+        .into_iter()
+        .filter(|(source, _)| query.source.as_deref().is_none_or(|s| s == *source))
+        .filter(|(_, stream)| {
+            query
+                .stream
+                .as_deref()
+                .is_none_or(|s| s == stream.descriptor.name || s == stream.descriptor.table_name)
+        })
+        .flat_map(|(source, stream)| {
+            stream
+                .descriptor
+                .target_ontologies
+                .iter()
+                .filter(move |target| ontology.is_none_or(|o| o == **target))
+                .map(move |target| OntologyLineage {
+                    source: source.to_string(),
+                    stream: stream.descriptor.name.to_string(),
+                    stream_table: stream.descriptor.table_name.to_string(),
+                    ontology: target.to_string(),
+                })
+        })
+        .collect();
+
+    edges.sort_by(|a, b| {
+        (&a.source, &a.stream, &a.ontology).cmp(&(&b.source, &b.stream, &b.ontology))
+    });
+    edges
+}
+
+/// A registry ontology with no table in the database schema
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MissingOntologyTable {
+    pub ontology: String,
+    pub table_name: String,
+    /// `source/stream` pairs that transform into this ontology
+    pub fed_by: Vec<String>,
+}
+
+/// Registry ontologies whose `data_*` table is missing from the schema
+///
+/// Checks the `target_ontologies` of every enabled stream against
+/// `sqlite_master`, so migration drift shows up before a transform fails on
+/// it. Returns an empty list when the schema is complete.
+pub async fn missing_ontology_tables(db: &SqlitePool) -> Result<Vec<MissingOntologyTable>> {
+    let existing: HashSet<String> = sqlx::query_scalar(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name LIKE 'data_%'",
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .collect();
+
+    let mut missing: Vec<MissingOntologyTable> = Vec::new();
+    for edge in ontology_lineage(&OntologyLineageQuery::default()) {
+        let table_name = format!("data_{}", edge.ontology);
+        if existing.contains(&table_name) {
+            continue;
+        }
+
+        let fed_by = format!("{}/{}", edge.source, edge.stream);
+        match missing.iter_mut().find(|m| m.ontology == edge.ontology) {
+            Some(entry) => entry.fed_by.push(fed_by),
+            None => missing.push(MissingOntologyTable {
+                ontology: edge.ontology,
+                table_name,
+                fed_by: vec![fed_by],
+            }),
+        }
+    }
+
+    missing.sort_by(|a, b| a.ontology.cmp(&b.ontology));
+    Ok(missing)
+}
+
 /// Extract domain name from table name
 fn extract_domain(table_name: &str) -> String {
     let parts: Vec<&str> = table_name.split('_').collect();
@@ -216,6 +324,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_ontology_lineage_both_directions() {
+        let feeds = ontology_lineage(&OntologyLineageQuery {
+            source: Some("google".to_string()),
+            stream: Some("calendar".to_string()),
+            ontology: None,
+        });
+        assert!(!feeds.is_empty());
+        assert!(feeds
+            .iter()
+            .all(|e| e.stream_table == "stream_google_calendar"));
+
+        let ontology = feeds[0].ontology.clone();
+        let fed_by = ontology_lineage(&OntologyLineageQuery {
+            ontology: Some(format!("data_{ontology}")),
+            ..Default::default()
+        });
+        assert!(fed_by.contains(&feeds[0]));
+        assert!(fed_by.iter().all(|e| e.ontology == ontology));
+    }
+
     #[test]
     fn test_registry_has_disabled_streams() {
         // Verify that disabled streams exist when including disabled
@@ -246,4 +375,20 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_missing_ontology_tables_reports_absent_schema() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE data_calendar (id TEXT PRIMARY KEY)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let missing = missing_ontology_tables(&pool).await.unwrap();
+        assert!(!missing.is_empty());
+        assert!(missing.iter().all(|m| m.table_name != "data_calendar"));
+        assert!(missing
+            .iter()
+            .all(|m| m.table_name == format!("data_{}", m.ontology) && !m.fed_by.is_empty()));
+    }
 }
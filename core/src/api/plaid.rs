@@ -11,6 +11,14 @@
 //! 4. Plaid returns a public_token to the frontend
 //! 5. Frontend calls `POST /api/plaid/exchange-token` with the public_token
 //! 6. Backend exchanges public_token for access_token and stores it (encrypted)
+//!
+//! Reconnecting a source whose bank login stopped working (status
+//! `reauth_required`) uses Link's update mode instead:
+//! 1. Frontend calls `POST /api/plaid/link-token` with the `source_id`
+//! 2. The user fixes the login in Plaid Link; no public_token is exchanged,
+//!    the Item keeps its access token
+//! 3. Frontend calls `POST /api/plaid/:source_id/reconnect`, which checks the
+//!    Item works again, clears the reauth requirement and syncs the source
 
 use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
@@ -19,6 +27,7 @@ use tokio::sync::Mutex;
 
 use crate::error::{Error, Result};
 use crate::sources::base::oauth::encryption::TokenEncryptor;
+use crate::sources::base::{NetworkConfig, SourceClient};
 use crate::sources::plaid::client::PlaidClient;
 use crate::storage::{stream_writer::StreamWriter, Storage};
 
@@ -55,7 +64,7 @@ pub struct PlaidAccountSnapshot {
 /// Request to create a Plaid Link token
 #[derive(Debug, Deserialize)]
 pub struct CreateLinkTokenRequest {
-    /// Optional: existing source_id if re-linking an existing connection
+    /// Existing source to reconnect; opens Link in update mode for its Item
     pub source_id: Option<String>,
 }
 
@@ -106,10 +115,11 @@ pub struct ConnectedAccountSummary {
 
 /// Create a Plaid Link token for initializing Plaid Link
 ///
-/// This is called by the frontend before showing the Plaid Link UI.
+/// This is called by the frontend before showing the Plaid Link UI. With a
+/// `source_id` the token opens Link in update mode for that source's Item.
 pub async fn create_link_token(
-    _db: &SqlitePool,
-    _request: CreateLinkTokenRequest,
+    db: &SqlitePool,
+    request: CreateLinkTokenRequest,
 ) -> Result<CreateLinkTokenResponse> {
     let client = PlaidClient::from_env()?;
 
@@ -125,6 +135,23 @@ pub async fn create_link_token(
     // Get redirect URI from environment (optional - only needed for OAuth-based institutions)
     // In sandbox mode, we can skip this. In production, configure in Plaid dashboard.
     let redirect_uri = std::env::var("PLAID_REDIRECT_URI").ok();
+    let webhook_url = std::env::var("PLAID_WEBHOOK_URL").ok();
+
+    if let Some(source_id) = &request.source_id {
+        let access_token = load_access_token(db, source_id).await?;
+        let response = client
+            .link_token_create_update(
+                &user_client_id,
+                &access_token,
+                country_codes,
+                redirect_uri.as_deref(),
+            )
+            .await?;
+        return Ok(CreateLinkTokenResponse {
+            link_token: response.link_token,
+            expiration: response.expiration,
+        });
+    }
 
     let response = client
         .link_token_create(
@@ -132,7 +159,7 @@ pub async fn create_link_token(
             products,
             country_codes,
             redirect_uri.as_deref(),
-            None, // webhook_url
+            webhook_url.as_deref(),
         )
         .await?;
 
@@ -145,7 +172,8 @@ pub async fn create_link_token(
 /// Exchange a public token for an access token
 ///
 /// Called after the user completes the Plaid Link flow.
-/// Creates a new source connection with the access token (encrypted).
+/// Creates a new source connection with the access token (encrypted), or
+/// reconnects the existing one when the same item is linked again.
 /// Also fetches connected accounts to determine which streams are relevant.
 /// Triggers initial sync for all enabled streams.
 pub async fn exchange_public_token(
@@ -238,11 +266,22 @@ pub async fn exchange_public_token(
     let metadata_json = serde_json::to_value(&metadata)
         .map_err(|e| Error::Other(format!("Failed to serialize metadata: {e}")))?;
 
-    // Insert source connection with encrypted access token
+    // Insert source connection with encrypted access token. Relinking the same
+    // item reconnects the existing source, lifting any reauth requirement.
     sqlx::query(
         r#"
         INSERT INTO elt_source_connections (id, source, name, auth_type, access_token, is_active, is_internal, metadata, created_at, updated_at)
         VALUES ($1, 'plaid', $2, 'plaid', $3, true, false, $4, datetime('now'), datetime('now'))
+        ON CONFLICT (id)
+        DO UPDATE SET
+            access_token = EXCLUDED.access_token,
+            metadata = EXCLUDED.metadata,
+            is_active = true,
+            deleted_at = NULL,
+            error_message = NULL,
+            error_at = NULL,
+            reauth_reason = NULL,
+            updated_at = datetime('now')
         "#,
     )
     .bind(&source_id)
@@ -264,6 +303,23 @@ pub async fn exchange_public_token(
     );
 
     // Trigger initial sync for all enabled streams
+    trigger_stream_syncs(db, storage, stream_writer, &source_id);
+
+    Ok(ExchangeTokenResponse {
+        source_id,
+        item_id,
+        institution_name: request.institution_name,
+        connected_accounts,
+    })
+}
+
+/// Start a sync of each enabled Plaid stream of a source in the background
+fn trigger_stream_syncs(
+    db: &SqlitePool,
+    storage: &Storage,
+    stream_writer: Arc<Mutex<StreamWriter>>,
+    source_id: &str,
+) {
     let source_reg = crate::registry::get_source("plaid");
     if let Some(reg) = source_reg {
         for stream_reg in &reg.streams {
@@ -276,7 +332,7 @@ pub async fn exchange_public_token(
             let storage_clone = storage.clone();
             let stream_writer_clone = stream_writer.clone();
             let stream_name = stream_desc.name.to_string();
-            let source_id_clone = source_id.clone();
+            let source_id_clone = source_id.to_string();
 
             tokio::spawn(async move {
                 match crate::api::jobs::trigger_stream_sync(
@@ -294,7 +350,7 @@ pub async fn exchange_public_token(
                             source_id = %source_id_clone,
                             stream = %stream_name,
                             job_id = %response.job_id,
-                            "Initial sync job created for Plaid stream"
+                            "Sync job created for Plaid stream"
                         );
                     }
                     Err(e) => {
@@ -302,31 +358,49 @@ pub async fn exchange_public_token(
                             source_id = %source_id_clone,
                             stream = %stream_name,
                             error = %e,
-                            "Failed to create initial sync job for Plaid stream"
+                            "Failed to create sync job for Plaid stream"
                         );
                     }
                 }
             });
         }
     }
-
-    Ok(ExchangeTokenResponse {
-        source_id,
-        item_id,
-        institution_name: request.institution_name,
-        connected_accounts,
-    })
 }
 
-/// Get accounts for an existing Plaid connection
+/// Reconnect a source after the user fixed its Item in Link's update mode
 ///
-/// Useful for showing the user which accounts are connected.
-pub async fn get_plaid_accounts(db: &SqlitePool, source_id: String) -> Result<Vec<PlaidAccount>> {
-    // Load encrypted access token from source_connections
+/// Checks that Plaid accepts the Item's access token again before clearing
+/// the source's reauth requirement, then syncs its streams.
+pub async fn reconnect_plaid_item(
+    db: &SqlitePool,
+    storage: &Storage,
+    stream_writer: Arc<Mutex<StreamWriter>>,
+    source_id: String,
+) -> Result<super::SourceConnection> {
+    let access_token = load_access_token(db, &source_id).await?;
+    source_client(db, &source_id)
+        .await?
+        .accounts_get(&access_token)
+        .await?;
+
+    super::sources::clear_reauth_required(db, &source_id).await?;
+    tracing::info!(source_id = %source_id, "Plaid source reconnected");
+
+    trigger_stream_syncs(db, storage, stream_writer, &source_id);
+    super::sources::get_source(db, source_id).await
+}
+
+/// Plaid client for calls on behalf of an existing source, with its network settings
+async fn source_client(db: &SqlitePool, source_id: &str) -> Result<PlaidClient> {
+    Ok(PlaidClient::from_env()?.with_network(NetworkConfig::load(db, source_id).await?))
+}
+
+/// Load and decrypt the access token of a Plaid source
+async fn load_access_token(db: &SqlitePool, source_id: &str) -> Result<String> {
     let row = sqlx::query_as::<_, (Option<String>,)>(
         "SELECT access_token FROM elt_source_connections WHERE id = $1 AND source = 'plaid'",
     )
-    .bind(&source_id)
+    .bind(source_id)
     .fetch_optional(db)
     .await?
     .ok_or_else(|| Error::NotFound(format!("Plaid source not found: {}", source_id)))?;
@@ -335,11 +409,17 @@ pub async fn get_plaid_accounts(db: &SqlitePool, source_id: String) -> Result<Ve
         .0
         .ok_or_else(|| Error::Configuration("Plaid source has no access token".to_string()))?;
 
-    // Decrypt the access token
-    let encryptor = TokenEncryptor::from_env()?;
-    let access_token = encryptor.decrypt(&encrypted_token)?;
+    TokenEncryptor::from_env()?.decrypt(&encrypted_token)
+}
 
-    let client = PlaidClient::from_env()?;
+/// Get accounts for an existing Plaid connection
+///
+/// Useful for showing the user which accounts are connected, and for picking
+/// the `account_ids_include`/`account_ids_exclude` of the Plaid streams.
+pub async fn get_plaid_accounts(db: &SqlitePool, source_id: String) -> Result<Vec<PlaidAccount>> {
+    let access_token = load_access_token(db, &source_id).await?;
+
+    let client = source_client(db, &source_id).await?;
     let response = client.accounts_get(&access_token).await?;
 
     let accounts = response
@@ -377,25 +457,10 @@ pub struct PlaidAccount {
 
 /// Remove a Plaid Item (disconnect bank account)
 pub async fn remove_plaid_item(db: &SqlitePool, source_id: String) -> Result<()> {
-    // Load encrypted access token
-    let row = sqlx::query_as::<_, (Option<String>,)>(
-        "SELECT access_token FROM elt_source_connections WHERE id = $1 AND source = 'plaid'",
-    )
-    .bind(&source_id)
-    .fetch_optional(db)
-    .await?
-    .ok_or_else(|| Error::NotFound(format!("Plaid source not found: {}", source_id)))?;
-
-    let encrypted_token = row
-        .0
-        .ok_or_else(|| Error::Configuration("Plaid source has no access token".to_string()))?;
-
-    // Decrypt the access token
-    let encryptor = TokenEncryptor::from_env()?;
-    let access_token = encryptor.decrypt(&encrypted_token)?;
+    let access_token = load_access_token(db, &source_id).await?;
 
     // Revoke access with Plaid
-    let client = PlaidClient::from_env()?;
+    let client = source_client(db, &source_id).await?;
     client.item_remove(&access_token).await?;
 
     // Delete source connection
@@ -1,9 +1,16 @@
 //! Source management API - CRUD operations for data sources
 
+use serde::Serialize;
 use sqlx::SqlitePool;
 
-use super::types::{SourceConnection, SourceConnectionStatus};
+use super::types::{SourceConnection, SourceConnectionStatus, SourceStatus};
 use crate::error::{Error, Result};
+use crate::sources::base::{NetworkConfig, TokenManager};
+use crate::storage::models::StreamKey;
+use crate::storage::{ConfirmedDeletion, DeletionPlan, DeletionReport, Storage};
+
+/// Default number of days a soft-deleted source can be restored
+const DEFAULT_RESTORE_GRACE_DAYS: i64 = 30;
 
 /// List all configured sources
 ///
@@ -16,9 +23,12 @@ pub async fn list_sources(db: &SqlitePool) -> Result<Vec<SourceConnection>> {
             s.source,
             s.name,
             s.auth_type,
+            s.status,
             s.is_active,
+            s.is_paused,
             s.is_internal,
             s.error_message,
+            s.deleted_at,
             s.created_at,
             s.updated_at,
             MAX(st.last_sync_at) as last_sync_at,
@@ -27,7 +37,8 @@ pub async fn list_sources(db: &SqlitePool) -> Result<Vec<SourceConnection>> {
         FROM elt_source_connections s
         LEFT JOIN elt_stream_connections st ON s.id = st.source_connection_id
         WHERE NOT (s.auth_type = 'device' AND s.pairing_status IS NULL)
-        GROUP BY s.id, s.source, s.name, s.auth_type, s.is_active, s.is_internal, s.error_message, s.created_at, s.updated_at
+          AND s.deleted_at IS NULL
+        GROUP BY s.id, s.source, s.name, s.auth_type, s.status, s.is_active, s.is_paused, s.is_internal, s.error_message, s.deleted_at, s.created_at, s.updated_at
         ORDER BY s.created_at DESC
         "#,
     )
@@ -48,9 +59,12 @@ pub async fn get_source(db: &SqlitePool, source_id: String) -> Result<SourceConn
             s.source,
             s.name,
             s.auth_type,
+            s.status,
             s.is_active,
+            s.is_paused,
             s.is_internal,
             s.error_message,
+            s.deleted_at,
             s.created_at,
             s.updated_at,
             MAX(st.last_sync_at) as last_sync_at,
@@ -59,7 +73,7 @@ pub async fn get_source(db: &SqlitePool, source_id: String) -> Result<SourceConn
         FROM elt_source_connections s
         LEFT JOIN elt_stream_connections st ON s.id = st.source_connection_id
         WHERE s.id = $1
-        GROUP BY s.id, s.source, s.name, s.auth_type, s.is_active, s.is_internal, s.error_message, s.created_at, s.updated_at
+        GROUP BY s.id, s.source, s.name, s.auth_type, s.status, s.is_active, s.is_paused, s.is_internal, s.error_message, s.deleted_at, s.created_at, s.updated_at
         "#,
     )
     .bind(&source_id_str)
@@ -70,12 +84,14 @@ pub async fn get_source(db: &SqlitePool, source_id: String) -> Result<SourceConn
     Ok(source)
 }
 
-/// Pause a source by setting is_active to false
+/// Pause a source by setting is_paused to true
 ///
-/// This prevents scheduled syncs from running but keeps the source configured.
+/// This prevents scheduled syncs from running but keeps the source configured
+/// and active, so tokens stay valid and manual syncs still work. The scheduler
+/// drops the source's jobs on its next reload.
 pub async fn pause_source(db: &SqlitePool, source_id: String) -> Result<SourceConnection> {
     let source_id_str = &source_id;
-    sqlx::query("UPDATE elt_source_connections SET is_active = false, updated_at = datetime('now') WHERE id = $1")
+    sqlx::query("UPDATE elt_source_connections SET is_paused = true, updated_at = datetime('now') WHERE id = $1")
         .bind(source_id_str)
         .execute(db)
         .await
@@ -84,12 +100,12 @@ pub async fn pause_source(db: &SqlitePool, source_id: String) -> Result<SourceCo
     get_source(db, source_id).await
 }
 
-/// Resume a source by setting is_active to true
+/// Resume a paused source by setting is_paused to false
 ///
 /// This re-enables scheduled syncs for the source.
 pub async fn resume_source(db: &SqlitePool, source_id: String) -> Result<SourceConnection> {
     let source_id_str = &source_id;
-    sqlx::query("UPDATE elt_source_connections SET is_active = true, updated_at = datetime('now') WHERE id = $1")
+    sqlx::query("UPDATE elt_source_connections SET is_paused = false, updated_at = datetime('now') WHERE id = $1")
         .bind(source_id_str)
         .execute(db)
         .await
@@ -98,10 +114,381 @@ pub async fn resume_source(db: &SqlitePool, source_id: String) -> Result<SourceC
     get_source(db, source_id).await
 }
 
-/// Delete a source by ID
+/// Stop scheduling a source until the user reconnects it
+///
+/// Called when the provider rejects the source's credentials in a way only a
+/// reconnect can fix (`Error::TokenExpired`). The source reports status
+/// `reauth_required` with `reason` until a reconnect clears it.
+pub async fn mark_reauth_required(db: &SqlitePool, source_id: &str, reason: &str) -> Result<()> {
+    sqlx::query(
+        "UPDATE elt_source_connections
+         SET reauth_reason = $1, error_message = $1, error_at = datetime('now'),
+             updated_at = datetime('now')
+         WHERE id = $2",
+    )
+    .bind(reason)
+    .bind(source_id)
+    .execute(db)
+    .await
+    .map_err(|e| Error::Database(format!("Failed to mark source for reauth: {e}")))?;
+
+    Ok(())
+}
+
+/// Let a source sync again after the user reconnected it
+pub async fn clear_reauth_required(db: &SqlitePool, source_id: &str) -> Result<()> {
+    sqlx::query(
+        "UPDATE elt_source_connections
+         SET reauth_reason = NULL, error_message = NULL, error_at = NULL,
+             updated_at = datetime('now')
+         WHERE id = $1",
+    )
+    .bind(source_id)
+    .execute(db)
+    .await
+    .map_err(|e| Error::Database(format!("Failed to clear source reauth: {e}")))?;
+
+    Ok(())
+}
+
+/// Get a source's network (proxy / offline) settings
+pub async fn get_source_network(db: &SqlitePool, source_id: String) -> Result<NetworkConfig> {
+    get_source(db, source_id.clone()).await?;
+    NetworkConfig::load(db, &source_id).await
+}
+
+/// Replace a source's network (proxy / offline) settings
+///
+/// Takes effect from the source's next sync, when its client is rebuilt.
+pub async fn update_source_network(
+    db: &SqlitePool,
+    source_id: String,
+    config: NetworkConfig,
+) -> Result<NetworkConfig> {
+    config.save(db, &source_id).await?;
+    Ok(config)
+}
+
+/// Plan the purge of a source's stored stream archives
+///
+/// Lists the exact objects (and total bytes) `soft_delete_source` would
+/// remove when given this plan confirmed. Deletes nothing.
+pub async fn plan_source_purge(
+    db: &SqlitePool,
+    storage: &Storage,
+    source_id: String,
+) -> Result<DeletionPlan> {
+    let source = get_source(db, source_id.clone()).await?;
+    storage
+        .plan_delete_prefix(&StreamKey::source_prefix(&source.source, &source_id))
+        .await
+}
+
+/// Soft-delete a source by ID
+///
+/// Marks the connection deleted so it is hidden and never scheduled, but keeps
+/// its rows, OAuth tokens and stored archives so it can be restored with
+/// `restore_source`. With a confirmed plan from `plan_source_purge`, exactly
+/// the planned archives are also removed from storage (irreversible), and
+/// OAuth grants are revoked at the provider and their tokens dropped, so a
+/// restored OAuth source has to be re-authorized before it syncs again.
+/// Returns the number of storage objects deleted.
+pub async fn soft_delete_source(
+    db: &SqlitePool,
+    storage: &Storage,
+    source_id: String,
+    purge: Option<ConfirmedDeletion>,
+) -> Result<u64> {
+    let source = get_source(db, source_id.clone()).await?;
+    if source.status == SourceStatus::Deleted {
+        return Err(Error::NotFound(format!("Source not found: {source_id}")));
+    }
+
+    if let Some(purge) = &purge {
+        let prefix = StreamKey::source_prefix(&source.source, &source_id);
+        if purge.plan().prefix != prefix {
+            return Err(Error::InvalidInput(format!(
+                "Purge plan for {} does not belong to source {source_id}",
+                purge.plan().prefix
+            )));
+        }
+    }
+
+    if purge.is_some() {
+        revoke_oauth_grant(db, &source).await;
+    }
+
+    sqlx::query(
+        "UPDATE elt_source_connections SET deleted_at = datetime('now'), updated_at = datetime('now') WHERE id = $1",
+    )
+    .bind(&source_id)
+    .execute(db)
+    .await
+    .map_err(|e| Error::Database(format!("Failed to delete source: {e}")))?;
+
+    let purge_data = purge.is_some();
+    let mut purged = 0;
+    if let Some(purge) = purge {
+        purged = purge_source_archives(db, storage, &source_id, purge)
+            .await?
+            .objects_deleted;
+    }
+
+    tracing::info!(
+        source_id = %source_id,
+        purge_data,
+        objects_deleted = purged,
+        "Source soft-deleted"
+    );
+
+    Ok(purged)
+}
+
+/// Revoke an OAuth source's grant at the provider and drop its stored tokens
+///
+/// Revocation failures (provider down, token already invalid) are logged and
+/// don't block the deletion; the local tokens are dropped either way.
+///
+/// Providers such as Google revoke the whole grant, which every connection of
+/// the same account shares. Which account a connection belongs to isn't
+/// recorded, so the grant is left in place while another connection of the
+/// provider still holds tokens.
+async fn revoke_oauth_grant(db: &SqlitePool, source: &SourceConnection) {
+    if source.auth_type != "oauth2" {
+        return;
+    }
+
+    let manager = match TokenManager::new(db.clone()) {
+        Ok(manager) => manager,
+        Err(e) => {
+            tracing::warn!(source_id = %source.id, error = %e, "Cannot revoke OAuth grant");
+            return;
+        }
+    };
+
+    let shares_provider: Result<bool> = sqlx::query_scalar(
+        "SELECT EXISTS (
+             SELECT 1 FROM elt_source_connections
+             WHERE source = $1 AND id != $2 AND access_token IS NOT NULL
+         )",
+    )
+    .bind(&source.source)
+    .bind(&source.id)
+    .fetch_one(db)
+    .await
+    .map_err(Error::from);
+
+    let revoked = match shares_provider {
+        Ok(false) => manager.revoke_token(&source.id).await,
+        Ok(true) => {
+            tracing::info!(
+                source_id = %source.id,
+                provider = %source.source,
+                "Another connection may share the OAuth grant; not revoking it"
+            );
+            Ok(false)
+        }
+        Err(e) => Err(e),
+    };
+    match revoked {
+        Ok(true) => tracing::info!(source_id = %source.id, "OAuth grant revoked"),
+        Ok(false) => tracing::debug!(
+            source_id = %source.id,
+            provider = %source.source,
+            "OAuth grant not revoked"
+        ),
+        Err(e) => tracing::warn!(
+            source_id = %source.id,
+            error = %e,
+            "Failed to revoke OAuth grant; dropping local tokens anyway"
+        ),
+    }
+
+    if let Err(e) = manager.clear_tokens(&source.id).await {
+        tracing::warn!(source_id = %source.id, error = %e, "Failed to drop OAuth tokens");
+    }
+}
+
+/// Delete a source's planned archives, the stream object rows indexing them
+/// and the record ids deduplicated against them
+///
+/// Left behind, the ids would make a restored source's next backfill skip
+/// records that are no longer archived anywhere.
+async fn purge_source_archives(
+    db: &SqlitePool,
+    storage: &Storage,
+    source_id: &str,
+    purge: ConfirmedDeletion,
+) -> Result<DeletionReport> {
+    let report = storage.execute_deletion(purge).await?;
+
+    let mut tx = db.begin().await?;
+    sqlx::query("DELETE FROM elt_stream_objects WHERE source_connection_id = $1")
+        .bind(source_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(format!("Failed to delete stream objects: {e}")))?;
+    sqlx::query("DELETE FROM elt_stream_record_ids WHERE source_connection_id = $1")
+        .bind(source_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(format!("Failed to delete stream record ids: {e}")))?;
+    tx.commit().await?;
+
+    Ok(report)
+}
+
+/// Days a soft-deleted source stays restorable (`SOURCE_RESTORE_GRACE_DAYS`)
+fn restore_grace_days() -> i64 {
+    std::env::var("SOURCE_RESTORE_GRACE_DAYS")
+        .ok()
+        .and_then(|s| s.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_RESTORE_GRACE_DAYS)
+}
+
+/// Stored archives of a deleted source whose restore window has passed
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpiredSourcePurge {
+    pub source_id: String,
+    pub source: String,
+    pub deleted_at: String,
+    pub plan: DeletionPlan,
+}
+
+/// What pruning expired sources would delete, computed without deleting
+#[derive(Debug, Clone, Serialize)]
+pub struct PrunePlan {
+    pub grace_days: i64,
+    pub sources: Vec<ExpiredSourcePurge>,
+    pub total_objects: u64,
+    pub total_bytes: u64,
+}
+
+impl PrunePlan {
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Explicitly approve this plan for execution
+    pub fn confirm(self) -> ConfirmedPrune {
+        ConfirmedPrune { plan: self }
+    }
+}
+
+/// A prune plan the caller has explicitly confirmed
+///
+/// Only obtainable through `PrunePlan::confirm`.
+#[derive(Debug)]
+pub struct ConfirmedPrune {
+    plan: PrunePlan,
+}
+
+/// Plan pruning the archives of sources deleted beyond the restore window
+///
+/// Such sources can no longer be restored, so their stored data is only
+/// retained until pruned. Sources with nothing left in storage are skipped.
+pub async fn plan_prune(db: &SqlitePool, storage: &Storage) -> Result<PrunePlan> {
+    let grace_days = restore_grace_days();
+    let expired: Vec<(String, String, String)> = sqlx::query_as(
+        "SELECT id, source, deleted_at FROM elt_source_connections
+         WHERE deleted_at IS NOT NULL AND deleted_at <= datetime('now', $1)
+         ORDER BY deleted_at",
+    )
+    .bind(format!("-{grace_days} days"))
+    .fetch_all(db)
+    .await
+    .map_err(|e| Error::Database(format!("Failed to list deleted sources: {e}")))?;
+
+    let mut sources = Vec::new();
+    for (source_id, source, deleted_at) in expired {
+        let plan = storage
+            .plan_delete_prefix(&StreamKey::source_prefix(&source, &source_id))
+            .await?;
+        if !plan.is_empty() {
+            sources.push(ExpiredSourcePurge {
+                source_id,
+                source,
+                deleted_at,
+                plan,
+            });
+        }
+    }
+
+    Ok(PrunePlan {
+        grace_days,
+        total_objects: sources.iter().map(|s| s.plan.object_count()).sum(),
+        total_bytes: sources.iter().map(|s| s.plan.total_bytes).sum(),
+        sources,
+    })
+}
+
+/// Delete exactly the archives of a confirmed prune plan
+pub async fn execute_prune(
+    db: &SqlitePool,
+    storage: &Storage,
+    prune: ConfirmedPrune,
+) -> Result<DeletionReport> {
+    let mut report = DeletionReport::default();
+    for expired in prune.plan.sources {
+        // Past the restore window, nothing needs the grant any more
+        if let Ok(source) = get_source(db, expired.source_id.clone()).await {
+            revoke_oauth_grant(db, &source).await;
+        }
+
+        let source_report =
+            purge_source_archives(db, storage, &expired.source_id, expired.plan.confirm()).await?;
+        tracing::info!(
+            source_id = %expired.source_id,
+            objects_deleted = source_report.objects_deleted,
+            bytes_deleted = source_report.bytes_deleted,
+            "Pruned expired source archives"
+        );
+        report.merge(&source_report);
+    }
+
+    Ok(report)
+}
+
+/// Restore a soft-deleted source
+///
+/// Only allowed within the grace window (`SOURCE_RESTORE_GRACE_DAYS`,
+/// default 30). Purged archives are not recovered.
+pub async fn restore_source(db: &SqlitePool, source_id: String) -> Result<SourceConnection> {
+    let grace_days = restore_grace_days();
+
+    let source = get_source(db, source_id.clone()).await?;
+    let Some(deleted_at) = source.deleted_at else {
+        return Err(Error::InvalidInput(format!(
+            "Source {source_id} is not deleted"
+        )));
+    };
+
+    if chrono::Utc::now() - *deleted_at > chrono::Duration::days(grace_days) {
+        return Err(Error::InvalidInput(format!(
+            "Source {source_id} was deleted more than {grace_days} days ago and can no longer be restored"
+        )));
+    }
+
+    sqlx::query(
+        "UPDATE elt_source_connections SET deleted_at = NULL, updated_at = datetime('now') WHERE id = $1",
+    )
+    .bind(&source_id)
+    .execute(db)
+    .await
+    .map_err(|e| Error::Database(format!("Failed to restore source: {e}")))?;
+
+    get_source(db, source_id).await
+}
+
+/// Permanently delete a source by ID
 ///
-/// This will cascade delete all associated data in stream tables.
+/// This will cascade delete all associated data in stream tables. OAuth grants
+/// are revoked at the provider first.
 pub async fn delete_source(db: &SqlitePool, source_id: String) -> Result<()> {
+    if let Ok(source) = get_source(db, source_id.clone()).await {
+        revoke_oauth_grant(db, &source).await;
+    }
+
     let source_id_str = &source_id;
     sqlx::query("DELETE FROM elt_source_connections WHERE id = $1")
         .bind(source_id_str)
@@ -117,16 +504,19 @@ pub async fn delete_source(db: &SqlitePool, source_id: String) -> Result<()> {
 /// Returns detailed status including sync history and success rates.
 pub async fn get_source_status(db: &SqlitePool, source_id: String) -> Result<SourceConnectionStatus> {
     let source_id_str = &source_id;
-    let status = sqlx::query_as::<_, SourceConnectionStatus>(
+    let mut status = sqlx::query_as::<_, SourceConnectionStatus>(
         r#"
         SELECT
             s.id,
             s.name,
             s.source,
+            s.status,
             s.is_active,
+            s.is_paused,
             s.is_internal,
             (SELECT MAX(completed_at) FROM elt_jobs WHERE source_connection_id = s.id AND status = 'succeeded') as last_sync_at,
             s.error_message,
+            s.reauth_reason,
             COUNT(j.id) as total_syncs,
             COALESCE(SUM(CASE WHEN j.status = 'succeeded' THEN 1 ELSE 0 END), 0) as successful_syncs,
             COALESCE(SUM(CASE WHEN j.status = 'failed' THEN 1 ELSE 0 END), 0) as failed_syncs,
@@ -135,7 +525,7 @@ pub async fn get_source_status(db: &SqlitePool, source_id: String) -> Result<Sou
         FROM elt_source_connections s
         LEFT JOIN elt_jobs j ON s.id = j.source_connection_id AND j.job_type = 'sync'
         WHERE s.id = $1
-        GROUP BY s.id, s.name, s.source, s.is_active, s.is_internal, s.error_message
+        GROUP BY s.id, s.name, s.source, s.status, s.is_active, s.is_paused, s.is_internal, s.error_message, s.reauth_reason
         "#
     )
     .bind(&source_id_str)
@@ -143,5 +533,137 @@ pub async fn get_source_status(db: &SqlitePool, source_id: String) -> Result<Sou
     .await
     .map_err(|e| Error::Database(format!("Failed to get source status: {e}")))?;
 
+    status.missing_scopes = super::oauth::missing_oauth_scopes(db, &source_id).await?;
+    status.needs_reauth = !status.missing_scopes.is_empty() || status.reauth_reason.is_some();
+    status.stream_errors = sqlx::query_as(
+        "SELECT stream_name, last_error, last_error_at FROM elt_stream_connections
+         WHERE source_connection_id = $1 AND last_error IS NOT NULL
+         ORDER BY last_error_at DESC",
+    )
+    .bind(&source_id)
+    .fetch_all(db)
+    .await
+    .map_err(|e| Error::Database(format!("Failed to get stream errors: {e}")))?;
+
     Ok(status)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn setup() -> (SqlitePool, Storage) {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE elt_source_connections (
+                id TEXT PRIMARY KEY,
+                source TEXT NOT NULL,
+                deleted_at TEXT
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("CREATE TABLE elt_stream_objects (source_connection_id TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE elt_stream_record_ids (source_connection_id TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, deleted_at) VALUES
+                ('expired', 'google', datetime('now', '-40 days')),
+                ('recent', 'google', datetime('now', '-1 day')),
+                ('live', 'google', NULL)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let storage = Storage::in_memory();
+        for source_id in ["expired", "recent", "live"] {
+            let key = format!("streams/google/{source_id}/gmail/date=2025-01-15/records.jsonl");
+            storage.upload(&key, vec![b'x'; 8]).await.unwrap();
+            for table in ["elt_stream_objects", "elt_stream_record_ids"] {
+                sqlx::query(&format!(
+                    "INSERT INTO {table} (source_connection_id) VALUES ($1)"
+                ))
+                .bind(source_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            }
+        }
+
+        (pool, storage)
+    }
+
+    #[tokio::test]
+    async fn test_prune_plans_only_expired_sources() {
+        let (pool, storage) = setup().await;
+
+        let plan = plan_prune(&pool, &storage).await.unwrap();
+        assert_eq!(plan.sources.len(), 1);
+        assert_eq!(plan.sources[0].source_id, "expired");
+        assert_eq!(plan.total_objects, 1);
+        assert_eq!(plan.total_bytes, 8);
+
+        // Planning deletes nothing
+        assert_eq!(storage.list("streams/google/").await.unwrap().len(), 3);
+
+        let report = execute_prune(&pool, &storage, plan.confirm())
+            .await
+            .unwrap();
+        assert_eq!(report.objects_deleted, 1);
+        assert_eq!(report.bytes_deleted, 8);
+
+        let remaining = storage.list("streams/google/").await.unwrap();
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining.iter().all(|key| !key.contains("/expired/")));
+        for table in ["elt_stream_objects", "elt_stream_record_ids"] {
+            let rows: i64 = sqlx::query_scalar(&format!(
+                "SELECT COUNT(*) FROM {table} WHERE source_connection_id = 'expired'"
+            ))
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            assert_eq!(rows, 0, "{table}");
+        }
+
+        // Nothing left to prune
+        assert!(plan_prune(&pool, &storage).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reauth_required_surfaces_in_status() {
+        let pool = crate::sources::base::mock_transport::testing::migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, name, is_active)
+             VALUES ('bank', 'plaid', 'Bank', true)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let reason = "Plaid error [ITEM_LOGIN_REQUIRED]: login required";
+        mark_reauth_required(&pool, "bank", reason).await.unwrap();
+
+        let status = get_source_status(&pool, "bank".to_string()).await.unwrap();
+        assert_eq!(status.status, SourceStatus::ReauthRequired);
+        assert_eq!(status.reauth_reason.as_deref(), Some(reason));
+        assert!(status.needs_reauth);
+
+        clear_reauth_required(&pool, "bank").await.unwrap();
+
+        let status = get_source_status(&pool, "bank".to_string()).await.unwrap();
+        assert_ne!(status.status, SourceStatus::ReauthRequired);
+        assert!(status.reauth_reason.is_none());
+        assert!(!status.needs_reauth);
+    }
+}
@@ -1,12 +1,21 @@
 //! Storage API - List and view stored stream objects
 
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
 
 use crate::error::{Error, Result};
+use crate::jobs::archive::record_timestamp;
+use crate::sources::google::gmail::body::resolve_body;
 use crate::storage::Storage;
 use crate::types::Timestamp;
 
+/// Records previewed per request when the client doesn't ask
+pub const DEFAULT_PREVIEW_LIMIT: usize = 100;
+
+/// Most records previewed per request
+pub const MAX_PREVIEW_LIMIT: usize = 1000;
+
 /// Summary of a stream object for listing
 /// Note: UUIDs are stored as TEXT in SQLite
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -76,13 +85,47 @@ struct StreamObjectMetadata {
     storage_key: String,
 }
 
+/// Fill in the bodies of Gmail records stored with `body_storage: external`
+///
+/// A body that can't be fetched is logged and its record left as it is.
+pub async fn resolve_record_bodies(storage: &Storage, records: &mut [serde_json::Value]) {
+    for record in records {
+        if let Err(e) = resolve_body(storage, record).await {
+            tracing::warn!("Failed to resolve external body: {e}");
+        }
+    }
+}
+
+/// Resolve the external bodies of the records in a JSONL object
+pub async fn resolve_jsonl_bodies(storage: &Storage, data: Vec<u8>) -> Result<Vec<u8>> {
+    let content = String::from_utf8(data)
+        .map_err(|e| Error::Other(format!("Invalid UTF-8 in object content: {e}")))?;
+    let mut records = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect::<std::result::Result<Vec<serde_json::Value>, _>>()?;
+
+    resolve_record_bodies(storage, &mut records).await;
+
+    let mut jsonl = Vec::new();
+    for record in &records {
+        serde_json::to_writer(&mut jsonl, record)?;
+        jsonl.push(b'\n');
+    }
+    Ok(jsonl)
+}
+
 /// Get content of a stream object
 ///
 /// Fetches the object from storage and parses the JSONL content into a vector of JSON values.
+/// With `resolve_bodies`, Gmail records stored with `body_storage: external` get
+/// their bodies filled back in.
 pub async fn get_object_content(
     pool: &SqlitePool,
     storage: &Storage,
     object_id: String,
+    resolve_bodies: bool,
 ) -> Result<ObjectContent> {
     let object_id_str = object_id;
 
@@ -110,7 +153,7 @@ pub async fn get_object_content(
     let content = String::from_utf8(data)
         .map_err(|e| Error::Other(format!("Invalid UTF-8 in object content: {e}")))?;
 
-    let records: Vec<serde_json::Value> = content
+    let mut records: Vec<serde_json::Value> = content
         .lines()
         .filter(|line| !line.trim().is_empty())
         .filter_map(|line| {
@@ -123,6 +166,10 @@ pub async fn get_object_content(
         })
         .collect();
 
+    if resolve_bodies {
+        resolve_record_bodies(storage, &mut records).await;
+    }
+
     Ok(ObjectContent {
         id: metadata.id,
         storage_key: metadata.storage_key,
@@ -130,3 +177,201 @@ pub async fn get_object_content(
         records,
     })
 }
+
+/// Records a stream archived for the days in a range
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamPreview {
+    pub source_id: String,
+    pub stream_name: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub records: Vec<serde_json::Value>,
+    pub record_count: usize,
+    /// More records in the range than `limit`
+    pub truncated: bool,
+}
+
+/// Preview the records a stream archived for the days `start..=end`
+///
+/// Only the partitions covering the range are listed and downloaded (see
+/// `Storage::list_stream_partitions`). Monthly partitions can hold records
+/// from outside the range, so records are filtered by their event time: the
+/// stream's `partition_key` field, else `timestamp`. Records with neither
+/// are kept, since their partition is the only time they have.
+#[allow(clippy::too_many_arguments)]
+pub async fn preview_stream_records(
+    pool: &SqlitePool,
+    storage: &Storage,
+    source_id: &str,
+    stream_name: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+    limit: Option<usize>,
+    resolve_bodies: bool,
+) -> Result<StreamPreview> {
+    let provider: String =
+        sqlx::query_scalar("SELECT source FROM elt_source_connections WHERE id = $1")
+            .bind(source_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| Error::Database(format!("Failed to get source: {e}")))?
+            .ok_or_else(|| Error::NotFound(format!("Source not found: {source_id}")))?;
+    let limit = limit
+        .unwrap_or(DEFAULT_PREVIEW_LIMIT)
+        .clamp(1, MAX_PREVIEW_LIMIT);
+
+    let keys = storage
+        .list_stream_partitions(&provider, source_id, stream_name, start, end)
+        .await?;
+    let partition_key =
+        crate::registry::get_stream(&provider, stream_name).and_then(|stream| stream.partition_key);
+    let in_range = |record: &serde_json::Value| {
+        partition_key
+            .and_then(|key| record_timestamp(record, key))
+            .or_else(|| record_timestamp(record, "timestamp"))
+            .is_none_or(|time| (start..=end).contains(&time.date_naive()))
+    };
+
+    // One record past the limit tells whether the range holds more
+    let mut records = Vec::new();
+    for key in keys {
+        if records.len() > limit {
+            break;
+        }
+        let object = storage
+            .download_jsonl::<serde_json::Value>(&key)
+            .await
+            .map_err(|e| Error::Storage(format!("Failed to read stream object {key}: {e}")))?;
+        records.extend(object.into_iter().filter(|record| in_range(record)));
+    }
+
+    let truncated = records.len() > limit;
+    records.truncate(limit);
+    if resolve_bodies {
+        resolve_record_bodies(storage, &mut records).await;
+    }
+
+    Ok(StreamPreview {
+        source_id: source_id.to_string(),
+        stream_name: stream_name.to_string(),
+        start,
+        end,
+        record_count: records.len(),
+        records,
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::google::gmail::body::{store_body, StoredBody};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_resolve_jsonl_bodies() {
+        let storage = Storage::in_memory();
+        let body = StoredBody {
+            body_plain: Some("Hello".to_string()),
+            body_html: None,
+        };
+        let key = store_body(&storage, "source_1", "msg_1", &body)
+            .await
+            .unwrap();
+
+        let jsonl = format!(
+            "{}\n{}\n",
+            json!({ "message_id": "msg_1", "body_key": key }),
+            json!({ "message_id": "msg_2", "body_plain": "Inline" })
+        );
+        let resolved = resolve_jsonl_bodies(&storage, jsonl.into_bytes())
+            .await
+            .unwrap();
+        let records: Vec<serde_json::Value> = String::from_utf8(resolved)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records[0]["body_plain"], "Hello");
+        assert_eq!(records[1]["body_plain"], "Inline");
+    }
+
+    #[tokio::test]
+    async fn test_preview_stream_records() {
+        let pool = crate::sources::base::mock_transport::testing::migrated_pool().await;
+        sqlx::query("INSERT INTO elt_source_connections (id, source, name) VALUES ('cal', 'google', 'Calendar')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let storage = Storage::in_memory();
+        let prefix = "streams/google/cal/calendar";
+        for (partition, records) in [
+            (
+                "date=2025-02-28",
+                vec![json!({ "timestamp": "2025-02-28T09:00:00Z" })],
+            ),
+            (
+                "date=2025-03-15",
+                vec![json!({ "timestamp": "2025-03-15T09:00:00Z" })],
+            ),
+            (
+                "month=2025-03",
+                vec![
+                    json!({ "timestamp": "2025-03-02T09:00:00Z" }),
+                    json!({ "timestamp": "2025-03-20T09:00:00Z" }),
+                ],
+            ),
+            (
+                "date=2025-04-01",
+                vec![json!({ "timestamp": "2025-04-01T09:00:00Z" })],
+            ),
+        ] {
+            storage
+                .upload_jsonl(&format!("{prefix}/{partition}/records_1.jsonl"), &records)
+                .await
+                .unwrap();
+        }
+
+        let day = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+        let preview = |limit| {
+            preview_stream_records(
+                &pool,
+                &storage,
+                "cal",
+                "calendar",
+                day(10),
+                day(31),
+                limit,
+                false,
+            )
+        };
+
+        // The monthly partition's record from before the range is left out
+        let all = preview(None).await.unwrap();
+        let times: Vec<_> = all.records.iter().map(|r| r["timestamp"].clone()).collect();
+        assert_eq!(
+            times,
+            vec![json!("2025-03-15T09:00:00Z"), json!("2025-03-20T09:00:00Z")]
+        );
+        assert!(!all.truncated);
+
+        let first = preview(Some(1)).await.unwrap();
+        assert_eq!(first.record_count, 1);
+        assert!(first.truncated);
+
+        assert!(matches!(
+            preview_stream_records(
+                &pool,
+                &storage,
+                "missing",
+                "calendar",
+                day(1),
+                day(2),
+                None,
+                false
+            )
+            .await,
+            Err(Error::NotFound(_))
+        ));
+    }
+}
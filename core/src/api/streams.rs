@@ -9,6 +9,9 @@ use tokio::sync::Mutex;
 use super::plaid::PlaidSourceMetadata;
 use super::sources::get_source;
 use crate::error::{Error, Result};
+use crate::jobs::first_sync::BackfillProgress;
+use crate::storage::models::PartitionGranularity;
+use crate::storage::redaction::Redactor;
 use crate::storage::stream_writer::StreamWriter;
 use crate::types::Timestamp;
 
@@ -22,13 +25,30 @@ pub struct StreamConnection {
     pub table_name: String,
     pub is_enabled: bool,
     pub cron_schedule: Option<String>,
+    /// IANA timezone the cron schedule is evaluated in (None = scheduler default)
+    pub cron_timezone: Option<String>,
     pub config: serde_json::Value,
+    /// Bumped on every config change; send it back in `If-Match` to detect
+    /// concurrent edits
+    pub config_version: i64,
     pub last_sync_at: Option<Timestamp>,
+    /// Error of the latest sync, if it failed
+    pub last_error: Option<String>,
+    pub last_error_at: Option<Timestamp>,
+    /// Time span of each archive partition
+    pub partition_granularity: PartitionGranularity,
+    /// Whether the stream only accepts records sealed on the device
+    pub end_to_end_encrypted: bool,
     pub supports_incremental: bool,
     pub supports_full_refresh: bool,
     pub config_schema: serde_json::Value,
     pub config_example: serde_json::Value,
     pub default_cron_schedule: Option<String>,
+    /// Whether the stream has a sync implementation (unimplemented streams
+    /// can't be enabled or synced)
+    pub implemented: bool,
+    /// Background backfill of the history a bounded first sync left out
+    pub backfill: Option<BackfillProgress>,
 }
 
 /// Request for enabling a stream
@@ -43,10 +63,25 @@ pub struct UpdateStreamConfigRequest {
     pub config: serde_json::Value,
 }
 
+/// Request for updating a stream's archive partitioning
+#[derive(Debug, serde::Deserialize)]
+pub struct UpdateStreamPartitioningRequest {
+    pub granularity: PartitionGranularity,
+}
+
+/// Request for turning end-to-end encryption on or off for a stream
+#[derive(Debug, serde::Deserialize)]
+pub struct UpdateStreamEncryptionRequest {
+    pub enabled: bool,
+}
+
 /// Request for updating stream schedule
 #[derive(Debug, serde::Deserialize)]
 pub struct UpdateStreamScheduleRequest {
     pub cron_schedule: Option<String>,
+    /// IANA timezone for the schedule (e.g. "America/New_York")
+    #[serde(default)]
+    pub timezone: Option<String>,
 }
 
 /// List all streams for a source with their connection status
@@ -68,11 +103,20 @@ pub async fn list_source_streams(
         String,
         bool,
         Option<String>,
+        Option<String>,
         serde_json::Value,
         Option<Timestamp>,
+        Option<String>,
+        Option<Timestamp>,
+        PartitionGranularity,
+        bool,
+        i64,
+        Option<sqlx::types::Json<BackfillProgress>>,
     )> = sqlx::query_as(
         r#"
-            SELECT stream_name, is_enabled, cron_schedule, config, last_sync_at
+            SELECT stream_name, is_enabled, cron_schedule, cron_timezone, config, last_sync_at,
+                   last_error, last_error_at, partition_granularity, end_to_end_encrypted,
+                   config_version, backfill_progress
             FROM elt_stream_connections
             WHERE source_connection_id = $1
             "#,
@@ -95,12 +139,48 @@ pub async fn list_source_streams(
         // Find matching database record
         let db_record = enabled_streams
             .iter()
-            .find(|(name, _, _, _, _)| name == stream_desc.name);
+            .find(|(name, ..)| name == stream_desc.name);
 
-        let (is_enabled, cron_schedule, config, last_sync_at) = if let Some(record) = db_record {
-            (record.1, record.2.clone(), record.3.clone(), record.4.clone())
+        let (
+            is_enabled,
+            cron_schedule,
+            cron_timezone,
+            config,
+            last_sync_at,
+            last_error,
+            last_error_at,
+            partition_granularity,
+            end_to_end_encrypted,
+            config_version,
+            backfill,
+        ) = if let Some(record) = db_record {
+            (
+                record.1,
+                record.2.clone(),
+                record.3.clone(),
+                record.4.clone(),
+                record.5,
+                record.6.clone(),
+                record.7,
+                record.8,
+                record.9,
+                record.10,
+                record.11.clone().map(|progress| progress.0),
+            )
         } else {
-            (false, None, serde_json::json!({}), None)
+            (
+                false,
+                None,
+                None,
+                serde_json::json!({}),
+                None,
+                None,
+                None,
+                PartitionGranularity::default(),
+                false,
+                0,
+                None,
+            )
         };
 
         result.push(StreamConnection {
@@ -110,13 +190,21 @@ pub async fn list_source_streams(
             table_name: stream_desc.table_name.to_string(),
             is_enabled,
             cron_schedule,
+            cron_timezone,
             config,
+            config_version,
             last_sync_at,
+            last_error,
+            last_error_at,
+            partition_granularity,
+            end_to_end_encrypted,
             supports_incremental: stream_desc.supports_incremental,
             supports_full_refresh: stream_desc.supports_full_refresh,
             config_schema: stream_reg.config_schema.clone(),
             config_example: stream_reg.config_example.clone(),
             default_cron_schedule: stream_desc.default_cron_schedule.map(|s| s.to_string()),
+            implemented: stream_reg.is_implemented(),
+            backfill,
         });
     }
 
@@ -155,6 +243,18 @@ pub async fn list_source_streams(
     Ok(result)
 }
 
+/// Reject streams that are registered without a sync implementation
+pub(crate) fn ensure_implemented(stream: &crate::registry::RegisteredStream) -> Result<()> {
+    if stream.is_implemented() {
+        Ok(())
+    } else {
+        Err(Error::InvalidInput(format!(
+            "Stream '{}' is not implemented yet",
+            stream.descriptor.name
+        )))
+    }
+}
+
 /// Get details for a specific stream
 pub async fn get_stream_info(
     db: &SqlitePool,
@@ -183,6 +283,7 @@ pub async fn enable_stream(
     // Validate stream exists in registry
     let stream_reg = crate::registry::get_stream(&source.source, stream_name)
         .ok_or_else(|| Error::Other(format!("Stream not found: {stream_name}")))?;
+    ensure_implemented(stream_reg)?;
     let stream_desc = &stream_reg.descriptor;
 
     // Use provided config or empty object
@@ -274,41 +375,271 @@ pub async fn disable_stream(db: &SqlitePool, source_id: String, stream_name: &st
 }
 
 /// Update stream configuration
+///
+/// Replaces the whole config. With `if_match`, fails with `Error::Conflict`
+/// unless the stored config is still at that version.
 pub async fn update_stream_config(
     db: &SqlitePool,
     source_id: String,
     stream_name: &str,
     config: serde_json::Value,
+    if_match: Option<i64>,
+) -> Result<StreamConnection> {
+    // Validate stream exists
+    let current = get_stream_info(db, source_id.clone(), stream_name).await?;
+    if let Some(version) = if_match {
+        check_config_version(&current, version)?;
+    }
+
+    write_config(db, &source_id, stream_name, &config, if_match).await?;
+
+    // Return updated stream info
+    get_stream_info(db, source_id, stream_name).await
+}
+
+/// Attempts at a patch without `If-Match` before giving up on a busy config
+const PATCH_ATTEMPTS: usize = 3;
+
+/// Merge a partial update into a stream's configuration (PATCH semantics)
+///
+/// The patch is applied as a JSON merge patch (RFC 7396): its keys replace
+/// the stored ones, nested objects merge, and `null` removes a key. The
+/// merged config must validate against the stream's `config_schema`.
+///
+/// The write only lands if the config is still at the version it was read
+/// at. With `if_match`, that's the caller's version and a mismatch is an
+/// `Error::Conflict`; without it, a concurrent change is merged again on
+/// top of the newer config.
+pub async fn patch_stream_config(
+    db: &SqlitePool,
+    source_id: String,
+    stream_name: &str,
+    patch: serde_json::Value,
+    if_match: Option<i64>,
+) -> Result<StreamConnection> {
+    if !patch.is_object() {
+        return Err(Error::InvalidInput(
+            "Config patch must be a JSON object".to_string(),
+        ));
+    }
+
+    let source = get_source(db, source_id.clone()).await?;
+    let stream_reg = crate::registry::get_stream(&source.source, stream_name)
+        .ok_or_else(|| Error::Other(format!("Stream not found: {stream_name}")))?;
+
+    for _ in 0..PATCH_ATTEMPTS {
+        let current = get_stream_info(db, source_id.clone(), stream_name).await?;
+        if let Some(version) = if_match {
+            check_config_version(&current, version)?;
+        }
+
+        let mut config = current.config.clone();
+        merge_config_patch(&mut config, &patch);
+        super::validation::validate_config(&config, &stream_reg.config_schema)?;
+
+        match write_config(
+            db,
+            &source_id,
+            stream_name,
+            &config,
+            Some(current.config_version),
+        )
+        .await
+        {
+            Err(Error::Conflict(_)) if if_match.is_none() => continue,
+            result => result?,
+        }
+
+        return get_stream_info(db, source_id, stream_name).await;
+    }
+
+    Err(Error::Conflict(format!(
+        "Config for stream '{stream_name}' kept changing; retry the update"
+    )))
+}
+
+/// Apply a JSON merge patch (RFC 7396) to a config
+pub fn merge_config_patch(config: &mut serde_json::Value, patch: &serde_json::Value) {
+    let serde_json::Value::Object(patch) = patch else {
+        *config = patch.clone();
+        return;
+    };
+    if !config.is_object() {
+        *config = serde_json::json!({});
+    }
+    let serde_json::Value::Object(fields) = config else {
+        return;
+    };
+
+    for (key, value) in patch {
+        if value.is_null() {
+            fields.remove(key);
+        } else {
+            merge_config_patch(
+                fields.entry(key.clone()).or_insert(serde_json::Value::Null),
+                value,
+            );
+        }
+    }
+}
+
+fn check_config_version(current: &StreamConnection, expected: i64) -> Result<()> {
+    if current.config_version == expected {
+        Ok(())
+    } else {
+        Err(Error::Conflict(format!(
+            "Config for stream '{}' is at version {}, not {expected}",
+            current.stream_name, current.config_version
+        )))
+    }
+}
+
+/// Store a config, unless it changed since `version` was read
+async fn write_config(
+    db: &SqlitePool,
+    source_id: &str,
+    stream_name: &str,
+    config: &serde_json::Value,
+    version: Option<i64>,
+) -> Result<()> {
+    // Bad redaction rules are refused here rather than failing every sync
+    Redactor::from_stream_config(config)?;
+
+    // The version itself is bumped by a trigger when the config changes
+    let updated = sqlx::query(
+        r#"
+        UPDATE elt_stream_connections
+        SET config = $1, updated_at = datetime('now')
+        WHERE source_connection_id = $2 AND stream_name = $3
+          AND ($4 IS NULL OR config_version = $4)
+        "#,
+    )
+    .bind(config)
+    .bind(source_id)
+    .bind(stream_name)
+    .bind(version)
+    .execute(db)
+    .await
+    .map_err(|e| Error::Database(format!("Failed to update stream config: {e}")))?;
+
+    if updated.rows_affected() > 0 {
+        return Ok(());
+    }
+
+    let stored: Option<i64> = sqlx::query_scalar(
+        "SELECT config_version FROM elt_stream_connections
+         WHERE source_connection_id = $1 AND stream_name = $2",
+    )
+    .bind(source_id)
+    .bind(stream_name)
+    .fetch_optional(db)
+    .await?;
+
+    match (stored, version) {
+        (Some(stored), Some(version)) => Err(Error::Conflict(format!(
+            "Config for stream '{stream_name}' changed since version {version} (now {stored})"
+        ))),
+        _ => Err(Error::InvalidInput(format!(
+            "Stream '{stream_name}' must be enabled before changing its config"
+        ))),
+    }
+}
+
+/// Update a stream's archive partition granularity
+///
+/// Applies to batches archived from now on; existing partitions keep their
+/// layout and are still listed by range reads.
+pub async fn update_stream_partitioning(
+    db: &SqlitePool,
+    source_id: String,
+    stream_name: &str,
+    granularity: PartitionGranularity,
 ) -> Result<StreamConnection> {
     // Validate stream exists
     get_stream_info(db, source_id.clone(), stream_name).await?;
 
-    // Update config
     sqlx::query(
         r#"
         UPDATE elt_stream_connections
-        SET config = $1, updated_at = datetime('now')
+        SET partition_granularity = $1, updated_at = datetime('now')
         WHERE source_connection_id = $2 AND stream_name = $3
         "#,
     )
-    .bind(&config)
+    .bind(granularity)
     .bind(&source_id)
     .bind(stream_name)
     .execute(db)
     .await
-    .map_err(|e| Error::Database(format!("Failed to update stream config: {e}")))?;
- 
-    // Return updated stream info
+    .map_err(|e| Error::Database(format!("Failed to update stream partitioning: {e}")))?;
+
+    get_stream_info(db, source_id, stream_name).await
+}
+
+/// Turn end-to-end encryption on or off for an enabled device stream
+///
+/// While on, /ingest accepts only sealed envelopes for the stream and skips
+/// its transforms (see `sources::envelope`). Turning it off doesn't decrypt
+/// anything: records already archived stay sealed.
+pub async fn update_stream_encryption(
+    db: &SqlitePool,
+    source_id: String,
+    stream_name: &str,
+    enabled: bool,
+) -> Result<StreamConnection> {
+    // Validate stream exists
+    get_stream_info(db, source_id.clone(), stream_name).await?;
+
+    // Only device streams push records through /ingest; a pulled stream is
+    // fetched by the server, in the clear
+    let source = get_source(db, source_id.clone()).await?;
+    let pushed = crate::registry::get_source(&source.source)
+        .is_some_and(|s| s.descriptor.auth_type == crate::registry::AuthType::Device);
+    if !pushed {
+        return Err(Error::InvalidInput(format!(
+            "Stream '{stream_name}' is pulled by the server; only device streams can be end-to-end encrypted"
+        )));
+    }
+
+    let updated = sqlx::query(
+        r#"
+        UPDATE elt_stream_connections
+        SET end_to_end_encrypted = $1, updated_at = datetime('now')
+        WHERE source_connection_id = $2 AND stream_name = $3
+        "#,
+    )
+    .bind(enabled)
+    .bind(&source_id)
+    .bind(stream_name)
+    .execute(db)
+    .await
+    .map_err(|e| Error::Database(format!("Failed to update stream encryption: {e}")))?;
+
+    // Silently leaving a stream unencrypted would be worse than failing
+    if updated.rows_affected() == 0 {
+        return Err(Error::InvalidInput(format!(
+            "Stream '{stream_name}' must be enabled before changing its encryption"
+        )));
+    }
+
     get_stream_info(db, source_id, stream_name).await
 }
 
 /// Update stream cron schedule
+///
+/// `cron_timezone` is an optional IANA timezone the schedule fires in; `None`
+/// uses the scheduler default. Takes effect on the scheduler's next reload.
 pub async fn update_stream_schedule(
     db: &SqlitePool,
     source_id: String,
     stream_name: &str,
     cron_schedule: Option<String>,
+    cron_timezone: Option<String>,
 ) -> Result<StreamConnection> {
+    if let Some(ref tz) = cron_timezone {
+        tz.parse::<chrono_tz::Tz>()
+            .map_err(|_| Error::InvalidInput(format!("Unknown timezone: {tz}")))?;
+    }
+
     // Validate stream exists
     get_stream_info(db, source_id.clone(), stream_name).await?;
  
@@ -316,11 +647,12 @@ pub async fn update_stream_schedule(
     sqlx::query(
         r#"
         UPDATE elt_stream_connections
-        SET cron_schedule = $1, updated_at = datetime('now')
-        WHERE source_connection_id = $2 AND stream_name = $3
+        SET cron_schedule = $1, cron_timezone = $2, updated_at = datetime('now')
+        WHERE source_connection_id = $3 AND stream_name = $4
         "#,
     )
     .bind(&cron_schedule)
+    .bind(&cron_timezone)
     .bind(&source_id)
     .bind(stream_name)
     .execute(db)
@@ -331,6 +663,88 @@ pub async fn update_stream_schedule(
     get_stream_info(db, source_id, stream_name).await
 }
 
+/// Stored sync cursor for a stream
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct StreamCursor {
+    pub stream_name: String,
+    pub last_sync_token: Option<String>,
+    pub last_sync_at: Option<Timestamp>,
+    pub sync_status: String,
+}
+
+/// Get the stored sync cursor for a stream
+pub async fn get_stream_cursor(
+    db: &SqlitePool,
+    source_id: String,
+    stream_name: &str,
+) -> Result<StreamCursor> {
+    sqlx::query_as::<_, StreamCursor>(
+        r#"
+        SELECT stream_name, last_sync_token, last_sync_at, sync_status
+        FROM elt_stream_connections
+        WHERE source_connection_id = $1 AND stream_name = $2
+        "#,
+    )
+    .bind(&source_id)
+    .bind(stream_name)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| Error::Database(format!("Failed to get stream cursor: {e}")))?
+    .ok_or_else(|| {
+        Error::NotFound(format!(
+            "Stream '{}' not found for source {}",
+            stream_name, source_id
+        ))
+    })
+}
+
+/// Clear the stored sync cursor for a stream
+///
+/// The next run has no cursor to resume from and performs a full refresh,
+/// which can be expensive, so callers must pass `confirm = true`. Refuses to
+/// reset while a sync is active, since that job would write its cursor back.
+pub async fn reset_stream_cursor(
+    db: &SqlitePool,
+    source_id: String,
+    stream_name: &str,
+    confirm: bool,
+) -> Result<StreamCursor> {
+    if !confirm {
+        return Err(Error::InvalidInput(
+            "Resetting a cursor forces a full resync; pass confirm=true to proceed".to_string(),
+        ));
+    }
+
+    let previous = get_stream_cursor(db, source_id.clone(), stream_name).await?;
+
+    if crate::jobs::has_active_sync_job(db, &source_id, stream_name).await? {
+        return Err(Error::SyncInProgress(format!("stream '{}'", stream_name)));
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE elt_stream_connections
+        SET last_sync_token = NULL, last_sync_at = NULL, sync_status = 'pending', updated_at = datetime('now')
+        WHERE source_connection_id = $1 AND stream_name = $2
+        "#,
+    )
+    .bind(&source_id)
+    .bind(stream_name)
+    .execute(db)
+    .await
+    .map_err(|e| Error::Database(format!("Failed to reset stream cursor: {e}")))?;
+
+    tracing::warn!(
+        source_id = %source_id,
+        stream = %stream_name,
+        previous_token = ?previous.last_sync_token,
+        previous_sync_at = ?previous.last_sync_at,
+        "Stream cursor reset; next sync will be a full refresh"
+    );
+
+    get_stream_cursor(db, source_id, stream_name).await
+}
+
 /// Enable default streams for a newly created source (internal helper)
 pub async fn enable_default_streams(
     db: &SqlitePool,
@@ -419,6 +833,7 @@ pub async fn bulk_update_streams(
         let config = update.config.clone().unwrap_or_else(|| serde_json::json!({}));
 
         if update.is_enabled {
+            ensure_implemented(stream_reg)?;
             sqlx::query(
                 r#"
                 INSERT INTO elt_stream_connections (id, source_connection_id, stream_name, table_name, is_enabled, config, cron_schedule, created_at, updated_at)
@@ -494,3 +909,105 @@ pub async fn bulk_update_streams(
         streams,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::base::mock_transport::testing::migrated_pool;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_config_patch() {
+        let mut config = json!({
+            "label_ids": ["INBOX"],
+            "fetch_body": true,
+            "sync_strategy": { "type": "time_window", "days_back": 365 },
+        });
+        merge_config_patch(
+            &mut config,
+            &json!({
+                "fetch_body": null,
+                "metadata_only": true,
+                "sync_strategy": { "days_back": 30 },
+            }),
+        );
+        assert_eq!(
+            config,
+            json!({
+                "label_ids": ["INBOX"],
+                "metadata_only": true,
+                "sync_strategy": { "type": "time_window", "days_back": 30 },
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_patch_checks_version_and_schema() {
+        let db = migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, name, auth_type, is_active, is_internal)
+             VALUES ('src-gmail', 'google', 'Google', 'oauth2', true, false)",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO elt_stream_connections (id, source_connection_id, stream_name, table_name, config)
+             VALUES ('stream-gmail', 'src-gmail', 'gmail', 'stream_google_gmail', '{\"fetch_body\": true}')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        let patch = |config, if_match| {
+            patch_stream_config(&db, "src-gmail".to_string(), "gmail", config, if_match)
+        };
+
+        let stream = patch(json!({ "metadata_only": true }), Some(0))
+            .await
+            .unwrap();
+        assert_eq!(stream.config_version, 1);
+        assert_eq!(
+            stream.config,
+            json!({ "fetch_body": true, "metadata_only": true })
+        );
+
+        // A stale version is refused rather than overwriting the newer config
+        let stale = patch(json!({ "fetch_body": false }), Some(0)).await;
+        assert!(matches!(stale, Err(Error::Conflict(_))));
+
+        let invalid = patch(json!({ "max_messages_per_sync": 0 }), None).await;
+        assert!(matches!(invalid, Err(Error::InvalidInput(_))));
+
+        let stream = patch(json!({ "fetch_body": false }), None).await.unwrap();
+        assert_eq!(stream.config_version, 2);
+        assert_eq!(stream.config["fetch_body"], json!(false));
+    }
+    #[tokio::test]
+    async fn test_encryption_only_for_device_streams() {
+        let db = migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, name, auth_type, is_active, is_internal)
+             VALUES ('src-gmail', 'google', 'Google', 'oauth2', true, false),
+                    ('src-mac', 'mac', 'Mac', 'device', true, false)",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO elt_stream_connections (id, source_connection_id, stream_name, table_name)
+             VALUES ('stream-gmail', 'src-gmail', 'gmail', 'stream_google_gmail'),
+                    ('stream-imessage', 'src-mac', 'imessage', 'stream_mac_imessage')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let pulled = update_stream_encryption(&db, "src-gmail".to_string(), "gmail", true).await;
+        assert!(matches!(pulled, Err(Error::InvalidInput(_))));
+
+        let stream = update_stream_encryption(&db, "src-mac".to_string(), "imessage", true)
+            .await
+            .unwrap();
+        assert!(stream.end_to_end_encrypted);
+    }
+}
@@ -2,6 +2,60 @@
 
 use crate::types::Timestamp;
 
+/// Lifecycle status of a source connection
+///
+/// Read from the generated `elt_source_connections.status` column, which is
+/// derived from `deleted_at`, `is_active`, `reauth_reason` and `is_paused`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+pub enum SourceStatus {
+    /// Connected and synced on schedule
+    Active,
+    /// Kept connected and syncable on demand, but skipped by the scheduler
+    Paused,
+    /// Disconnected (e.g. tokens revoked); not synced
+    Inactive,
+    /// The provider rejected the credentials; skipped by the scheduler until
+    /// the user reconnects
+    ReauthRequired,
+    /// Soft-deleted; restorable within the grace window
+    Deleted,
+}
+
+impl SourceStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SourceStatus::Active => "active",
+            SourceStatus::Paused => "paused",
+            SourceStatus::Inactive => "inactive",
+            SourceStatus::ReauthRequired => "reauth_required",
+            SourceStatus::Deleted => "deleted",
+        }
+    }
+}
+
+impl std::fmt::Display for SourceStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for SourceStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "active" => Ok(SourceStatus::Active),
+            "paused" => Ok(SourceStatus::Paused),
+            "inactive" => Ok(SourceStatus::Inactive),
+            "reauth_required" => Ok(SourceStatus::ReauthRequired),
+            "deleted" => Ok(SourceStatus::Deleted),
+            _ => Err(format!("Invalid source status: {}", s)),
+        }
+    }
+}
+
 /// A user's connected source instance
 /// This represents an actual connected account with auth tokens.
 /// For source type info, see registry::RegisteredSource.
@@ -13,9 +67,13 @@ pub struct SourceConnection {
     pub source: String,
     pub name: String,
     pub auth_type: String,
+    pub status: SourceStatus,
     pub is_active: bool,
+    pub is_paused: bool,
     pub is_internal: bool,
     pub error_message: Option<String>,
+    /// Set when the source is soft-deleted (restorable within a grace window)
+    pub deleted_at: Option<Timestamp>,
     pub created_at: Timestamp,
     pub updated_at: Timestamp,
     pub last_sync_at: Option<Timestamp>,
@@ -29,14 +87,43 @@ pub struct SourceConnectionStatus {
     pub id: String,
     pub name: String,
     pub source: String,
+    pub status: SourceStatus,
     pub is_active: bool,
+    pub is_paused: bool,
     pub is_internal: bool,
     pub last_sync_at: Option<Timestamp>,
     pub error_message: Option<String>,
+    /// Why the provider rejected the credentials, while `reauth_required`
+    pub reauth_reason: Option<String>,
     pub total_syncs: i64,
     pub successful_syncs: i64,
     pub failed_syncs: i64,
     pub last_sync_status: Option<String>,
     pub last_sync_duration_ms: Option<i32>,
+    /// OAuth scopes enabled streams need but the current grant lacks
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub missing_scopes: Vec<String>,
+    /// Whether the source must be re-authorized, to cover `missing_scopes`
+    /// or because of `reauth_reason`
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub needs_reauth: bool,
+    /// Streams whose latest sync failed
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub stream_errors: Vec<StreamError>,
+    /// Device records buffered in memory and not yet archived
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub pending: Vec<crate::storage::stream_writer::PendingBuffer>,
+}
+
+/// Latest sync error of a stream
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct StreamError {
+    pub stream_name: String,
+    pub last_error: String,
+    pub last_error_at: Option<Timestamp>,
 }
 
@@ -73,6 +73,140 @@ pub fn validate_name(name: &str, field: &str) -> Result<()> {
     Ok(())
 }
 
+/// Validate a stream config against its registry `config_schema`
+///
+/// Covers the JSON Schema keywords the registry schemas use: `type`,
+/// `nullable`, `enum`, `const`, `minimum`, `maximum`, `properties`,
+/// `required`, `additionalProperties: false`, `items` and `oneOf`. A `null`
+/// property is treated as unset. Every violation is reported, with its path.
+pub fn validate_config(config: &serde_json::Value, schema: &serde_json::Value) -> Result<()> {
+    let mut errors = Vec::new();
+    check_schema(config, schema, "config", &mut errors);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::InvalidInput(format!(
+            "Invalid config: {}",
+            errors.join("; ")
+        )))
+    }
+}
+
+fn check_schema(
+    value: &serde_json::Value,
+    schema: &serde_json::Value,
+    path: &str,
+    errors: &mut Vec<String>,
+) {
+    use serde_json::Value;
+
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+
+    if value.is_null() && schema.get("nullable") == Some(&Value::Bool(true)) {
+        return;
+    }
+
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(|t| t.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|t| has_type(value, t)) {
+            errors.push(format!("{path} must be of type {}", allowed.join(" or ")));
+            return;
+        }
+    }
+
+    if let Some(constant) = schema.get("const") {
+        if value != constant {
+            errors.push(format!("{path} must be {constant}"));
+        }
+    }
+    if let Some(Value::Array(options)) = schema.get("enum") {
+        if !options.contains(value) {
+            let options: Vec<String> = options.iter().map(|o| o.to_string()).collect();
+            errors.push(format!("{path} must be one of {}", options.join(", ")));
+        }
+    }
+
+    if let Some(number) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(|m| m.as_f64()) {
+            if number < min {
+                errors.push(format!("{path} must be at least {min}"));
+            }
+        }
+        if let Some(max) = schema.get("maximum").and_then(|m| m.as_f64()) {
+            if number > max {
+                errors.push(format!("{path} must be at most {max}"));
+            }
+        }
+    }
+
+    if let Value::Object(fields) = value {
+        let properties = schema.get("properties").and_then(|p| p.as_object());
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for key in required.iter().filter_map(|k| k.as_str()) {
+                if fields.get(key).is_none_or(Value::is_null) {
+                    errors.push(format!("{path}.{key} is required"));
+                }
+            }
+        }
+        for (key, field) in fields {
+            if field.is_null() {
+                continue;
+            }
+            match properties.and_then(|p| p.get(key)) {
+                Some(field_schema) => {
+                    check_schema(field, field_schema, &format!("{path}.{key}"), errors)
+                }
+                None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                    errors.push(format!("{path}.{key} is not allowed"));
+                }
+                None => {}
+            }
+        }
+    }
+
+    if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+        for (i, item) in items.iter().enumerate() {
+            check_schema(item, item_schema, &format!("{path}[{i}]"), errors);
+        }
+    }
+
+    if let Some(Value::Array(variants)) = schema.get("oneOf") {
+        let matching = variants
+            .iter()
+            .filter(|variant| {
+                let mut variant_errors = Vec::new();
+                check_schema(value, variant, path, &mut variant_errors);
+                variant_errors.is_empty()
+            })
+            .count();
+        if matching != 1 {
+            errors.push(format!(
+                "{path} must match exactly one of {} alternatives",
+                variants.len()
+            ));
+        }
+    }
+}
+
+fn has_type(value: &serde_json::Value, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,4 +237,42 @@ mod tests {
         assert!(validate_name("", "Device name").is_err());
         assert!(validate_name(&"a".repeat(300), "Device name").is_err());
     }
+
+    #[test]
+    fn test_validate_config() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "max_per_sync": { "type": "integer", "minimum": 1, "maximum": 100 },
+                "mode": { "type": "string", "enum": ["messages", "threads"] },
+                "labels": { "type": "array", "items": { "type": "string" } },
+                "query": { "type": "string" },
+                "strategy": crate::sources::base::SyncStrategy::json_schema(),
+            }
+        });
+
+        let valid = serde_json::json!({
+            "max_per_sync": 50,
+            "mode": "threads",
+            "labels": ["INBOX"],
+            "query": null,
+            "strategy": { "type": "time_window", "days_back": 30 },
+            "unknown": true,
+        });
+        assert!(validate_config(&valid, &schema).is_ok());
+
+        let invalid = serde_json::json!({
+            "max_per_sync": 500,
+            "mode": "digest",
+            "labels": ["INBOX", 3],
+            "strategy": { "type": "time_window" },
+        });
+        let err = validate_config(&invalid, &schema).unwrap_err().to_string();
+        assert!(err.contains("config.max_per_sync must be at most 100"));
+        assert!(err.contains("config.mode must be one of"));
+        assert!(err.contains("config.labels[1] must be of type string"));
+        assert!(err.contains("config.strategy must match exactly one"));
+
+        assert!(validate_config(&serde_json::json!([]), &schema).is_err());
+    }
 }
@@ -0,0 +1,525 @@
+//! Provider webhook dispatch
+//!
+//! Routes `/webhooks/:provider` deliveries to the provider's [`WebhookSource`].
+//! Providers retry or deactivate subscriptions that answer slowly, so the
+//! request path only verifies the delivery and enqueues it
+//! ([`verify_webhook_and_enqueue`]); a background worker drains the queue and
+//! turns each delivery into incremental syncs of the affected streams of the
+//! one source connected to the provider account the delivery names.
+//!
+//! The queue is bounded (`WEBHOOK_QUEUE_CAPACITY`, default 256). When it is
+//! full, deliveries are refused with `Error::Overloaded` (503) instead of
+//! piling up, and the provider's own retry brings them back later.
+//!
+//! Each queued delivery is also stored in `elt_webhook_deliveries` before the
+//! provider is answered, and deleted once its syncs are started. Deliveries
+//! still stored when the server starts are processed before any new ones.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::http::HeaderMap;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tokio::sync::{mpsc, Mutex};
+
+use crate::api::SourceStatus;
+use crate::error::{Error, Result};
+use crate::sources::base::{WebhookAccount, WebhookAction, WebhookRequest, WebhookSource};
+use crate::sources::plaid::PlaidWebhook;
+use crate::sources::strava::StravaWebhook;
+use crate::storage::{stream_writer::StreamWriter, Storage};
+
+/// Default number of verified deliveries waiting for the worker
+const DEFAULT_QUEUE_CAPACITY: usize = 256;
+
+/// A verified delivery, waiting for its syncs to be started
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookDelivery {
+    /// Row of the delivery in `elt_webhook_deliveries`
+    pub id: i64,
+    pub provider: String,
+    pub streams: Vec<&'static str>,
+    pub account: WebhookAccount,
+    /// Request id of the delivery, carried over to the sync jobs it starts
+    pub request_id: Option<String>,
+}
+
+/// Bounded queue of verified webhook deliveries
+#[derive(Clone)]
+pub struct WebhookQueue {
+    db: SqlitePool,
+    sender: mpsc::Sender<WebhookDelivery>,
+}
+
+impl WebhookQueue {
+    /// Create a queue and the receiving end for its worker
+    pub fn bounded(db: SqlitePool, capacity: usize) -> (Self, mpsc::Receiver<WebhookDelivery>) {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        (Self { db, sender }, receiver)
+    }
+
+    /// Create a queue sized from `WEBHOOK_QUEUE_CAPACITY` and spawn its worker
+    ///
+    /// The worker first processes the deliveries stored by a previous run.
+    pub async fn start(
+        db: SqlitePool,
+        storage: Arc<Storage>,
+        stream_writer: Arc<Mutex<StreamWriter>>,
+    ) -> Result<Self> {
+        let capacity = std::env::var("WEBHOOK_QUEUE_CAPACITY")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_QUEUE_CAPACITY);
+
+        // Loaded before the queue accepts anything, so no delivery is both
+        // replayed and received
+        let stored = load_stored_deliveries(&db).await?;
+        if !stored.is_empty() {
+            tracing::info!(count = stored.len(), "Replaying stored webhook deliveries");
+        }
+
+        let (queue, receiver) = Self::bounded(db.clone(), capacity);
+        tokio::spawn(run_webhook_worker(
+            stored,
+            receiver,
+            db,
+            storage,
+            stream_writer,
+        ));
+        Ok(queue)
+    }
+
+    /// Store a delivery and enqueue it, refusing it if the queue is full
+    async fn enqueue(
+        &self,
+        webhook: &dyn WebhookSource,
+        body: &[u8],
+        streams: Vec<&'static str>,
+        account: WebhookAccount,
+    ) -> Result<()> {
+        let provider = webhook.provider();
+
+        // Take a slot first, so a refused delivery is never stored
+        let permit = self.sender.try_reserve().map_err(|e| match e {
+            mpsc::error::TrySendError::Full(()) => {
+                tracing::warn!(provider, "Webhook queue full, shedding delivery");
+                Error::Overloaded("Webhook queue is full".to_string())
+            }
+            mpsc::error::TrySendError::Closed(()) => {
+                Error::Other("Webhook worker is not running".to_string())
+            }
+        })?;
+
+        let request_id = crate::middleware::request_id::current();
+        let id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO elt_webhook_deliveries (provider, body, request_id)
+             VALUES ($1, $2, $3)
+             RETURNING id",
+        )
+        .bind(provider)
+        .bind(body)
+        .bind(&request_id)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| Error::Database(format!("Failed to store webhook delivery: {e}")))?;
+
+        permit.send(WebhookDelivery {
+            id,
+            provider: provider.to_string(),
+            streams,
+            account,
+            request_id,
+        });
+        Ok(())
+    }
+}
+
+/// Acknowledgement returned to the provider
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WebhookAck {
+    /// Whether the delivery was queued (false for ignored events)
+    pub queued: bool,
+}
+
+/// Look up the webhook implementation for a provider
+pub fn webhook_source(provider: &str) -> Option<Box<dyn WebhookSource>> {
+    match provider {
+        "strava" => Some(Box::new(StravaWebhook::from_env())),
+        "plaid" => Some(Box::new(PlaidWebhook::from_env())),
+        _ => None,
+    }
+}
+
+fn require_webhook_source(provider: &str) -> Result<Box<dyn WebhookSource>> {
+    webhook_source(provider)
+        .ok_or_else(|| Error::NotFound(format!("No webhook handler for provider '{}'", provider)))
+}
+
+/// Answer a provider's subscription challenge (GET)
+pub fn handle_webhook_challenge(
+    provider: &str,
+    query: &HashMap<String, String>,
+) -> Result<serde_json::Value> {
+    require_webhook_source(provider)?.challenge(query)
+}
+
+/// Verify a webhook delivery (POST) and enqueue the syncs it implies
+///
+/// Does no I/O beyond the enqueue (and fetching a provider's signing key the
+/// first time it is seen), so it can answer the provider immediately.
+pub async fn verify_webhook_and_enqueue(
+    queue: &WebhookQueue,
+    provider: &str,
+    headers: &HeaderMap,
+    query: &HashMap<String, String>,
+    body: &[u8],
+) -> Result<WebhookAck> {
+    let webhook = require_webhook_source(provider)?;
+    enqueue_verified(queue, webhook.as_ref(), headers, query, body).await
+}
+
+async fn enqueue_verified(
+    queue: &WebhookQueue,
+    webhook: &dyn WebhookSource,
+    headers: &HeaderMap,
+    query: &HashMap<String, String>,
+    body: &[u8],
+) -> Result<WebhookAck> {
+    webhook
+        .verify(&WebhookRequest {
+            headers,
+            query,
+            body,
+        })
+        .await?;
+
+    let (streams, account) = match webhook.action(body)? {
+        WebhookAction::SyncStreams { streams, account } => (streams, account),
+        WebhookAction::Ignore => return Ok(WebhookAck { queued: false }),
+    };
+
+    queue.enqueue(webhook, body, streams, account).await?;
+    Ok(WebhookAck { queued: true })
+}
+
+/// Load the deliveries a previous run stored but didn't process, oldest first
+///
+/// Their bodies were verified when received; they are only turned into syncs
+/// again. Rows that no longer lead to a sync are deleted.
+async fn load_stored_deliveries(db: &SqlitePool) -> Result<Vec<WebhookDelivery>> {
+    let rows = sqlx::query_as::<_, (i64, String, Vec<u8>, Option<String>)>(
+        "SELECT id, provider, body, request_id FROM elt_webhook_deliveries ORDER BY id",
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut deliveries = Vec::new();
+    for (id, provider, body, request_id) in rows {
+        let action = require_webhook_source(&provider).and_then(|webhook| webhook.action(&body));
+        match action {
+            Ok(WebhookAction::SyncStreams { streams, account }) => {
+                deliveries.push(WebhookDelivery {
+                    id,
+                    provider,
+                    streams,
+                    account,
+                    request_id,
+                });
+            }
+            Ok(WebhookAction::Ignore) => delete_delivery(db, id).await?,
+            Err(e) => {
+                tracing::warn!(%provider, error = %e, "Dropping stored webhook delivery");
+                delete_delivery(db, id).await?;
+            }
+        }
+    }
+    Ok(deliveries)
+}
+
+async fn delete_delivery(db: &SqlitePool, id: i64) -> Result<()> {
+    sqlx::query("DELETE FROM elt_webhook_deliveries WHERE id = $1")
+        .bind(id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// Process the stored deliveries, then drain the webhook queue, starting the
+/// syncs for each delivery
+///
+/// A delivery's row is deleted once its syncs are started; one that fails is
+/// kept and retried on the next start.
+pub async fn run_webhook_worker(
+    stored: Vec<WebhookDelivery>,
+    mut receiver: mpsc::Receiver<WebhookDelivery>,
+    db: SqlitePool,
+    storage: Arc<Storage>,
+    stream_writer: Arc<Mutex<StreamWriter>>,
+) {
+    let mut stored = stored.into_iter();
+    loop {
+        let delivery = match stored.next() {
+            Some(delivery) => delivery,
+            None => match receiver.recv().await {
+                Some(delivery) => delivery,
+                None => break,
+            },
+        };
+
+        let result = crate::middleware::request_id::scope(
+            delivery.request_id.clone(),
+            process_webhook_delivery(&db, &storage, stream_writer.clone(), &delivery),
+        )
+        .await;
+        let result = match result {
+            Ok(_) => delete_delivery(&db, delivery.id).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            tracing::warn!(provider = %delivery.provider, error = %e, "Failed to process webhook delivery");
+        }
+    }
+}
+
+/// Active sources of a provider connected to `account` with `stream_name` enabled
+async fn account_sources(
+    db: &SqlitePool,
+    provider: &str,
+    stream_name: &str,
+    account: &WebhookAccount,
+) -> Result<Vec<String>> {
+    let account_path = format!("$.{}", account.metadata_key);
+    let source_ids = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT s.id
+        FROM elt_source_connections s
+        JOIN elt_stream_connections st ON st.source_connection_id = s.id
+        WHERE s.source = $1 AND s.status = $2
+          AND st.stream_name = $3 AND st.is_enabled = 1
+          AND json_extract(s.metadata, $4) = $5
+        "#,
+    )
+    .bind(provider)
+    .bind(SourceStatus::Active)
+    .bind(stream_name)
+    .bind(&account_path)
+    .bind(&account.id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(source_ids)
+}
+
+/// Trigger syncs for the streams a delivery affects, on the source connected
+/// to the delivery's account
+///
+/// Syncs run as background jobs, so this returns as soon as they are queued.
+/// Streams that already have a sync in flight are skipped. Returns the IDs of
+/// the jobs that were started.
+pub async fn process_webhook_delivery(
+    db: &SqlitePool,
+    storage: &Storage,
+    stream_writer: Arc<Mutex<StreamWriter>>,
+    delivery: &WebhookDelivery,
+) -> Result<Vec<String>> {
+    let provider = delivery.provider.as_str();
+
+    let mut job_ids = Vec::new();
+    for stream_name in delivery.streams.iter().copied() {
+        let source_ids = account_sources(db, provider, stream_name, &delivery.account).await?;
+
+        for source_id in source_ids {
+            match crate::api::trigger_stream_sync(
+                db,
+                storage,
+                stream_writer.clone(),
+                source_id.clone(),
+                stream_name,
+                None,
+            )
+            .await
+            {
+                Ok(response) => job_ids.push(response.job_id),
+                Err(Error::SyncInProgress(_)) => {
+                    tracing::debug!(%source_id, stream_name, "Sync already running, skipping webhook trigger");
+                }
+                Err(e) => {
+                    tracing::warn!(%source_id, stream_name, error = %e, "Failed to trigger webhook sync");
+                }
+            }
+        }
+    }
+
+    tracing::info!(provider, jobs = job_ids.len(), "Handled webhook event");
+    Ok(job_ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    /// Accepts deliveries signed "ok"; body "ignore" is an irrelevant event
+    struct TestWebhook;
+
+    #[async_trait]
+    impl WebhookSource for TestWebhook {
+        fn provider(&self) -> &'static str {
+            "test"
+        }
+
+        async fn verify(&self, request: &WebhookRequest<'_>) -> Result<()> {
+            match request.headers.get("x-signature") {
+                Some(sig) if sig == "ok" => Ok(()),
+                _ => Err(Error::Unauthorized("bad signature".to_string())),
+            }
+        }
+
+        fn action(&self, body: &[u8]) -> Result<WebhookAction> {
+            Ok(match body {
+                b"ignore" => WebhookAction::Ignore,
+                _ => WebhookAction::SyncStreams {
+                    streams: vec!["activities"],
+                    account: account(),
+                },
+            })
+        }
+    }
+
+    fn account() -> WebhookAccount {
+        WebhookAccount {
+            metadata_key: "athlete_id",
+            id: "134815".to_string(),
+        }
+    }
+
+    fn signed(signature: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-signature", signature.parse().unwrap());
+        headers
+    }
+
+    async fn stored_count(pool: &SqlitePool) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM elt_webhook_deliveries")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_enqueue_verified_delivery() {
+        let pool = crate::sources::base::mock_transport::testing::migrated_pool().await;
+        let (queue, mut receiver) = WebhookQueue::bounded(pool.clone(), 4);
+        let query = HashMap::new();
+
+        let ack = enqueue_verified(&queue, &TestWebhook, &signed("ok"), &query, b"{}")
+            .await
+            .unwrap();
+        assert_eq!(ack, WebhookAck { queued: true });
+        let delivery = receiver.try_recv().unwrap();
+        assert_eq!(
+            delivery,
+            WebhookDelivery {
+                id: delivery.id,
+                provider: "test".to_string(),
+                streams: vec!["activities"],
+                account: account(),
+                request_id: None,
+            }
+        );
+
+        // Ignored events and bad signatures queue nothing
+        let ack = enqueue_verified(&queue, &TestWebhook, &signed("ok"), &query, b"ignore")
+            .await
+            .unwrap();
+        assert_eq!(ack, WebhookAck { queued: false });
+        assert!(matches!(
+            enqueue_verified(&queue, &TestWebhook, &signed("forged"), &query, b"{}").await,
+            Err(Error::Unauthorized(_))
+        ));
+        assert!(receiver.try_recv().is_err());
+
+        // Only the queued delivery was stored
+        assert_eq!(stored_count(&pool).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_stored_deliveries_are_replayed() {
+        let pool = crate::sources::base::mock_transport::testing::migrated_pool().await;
+        let event = br#"{"aspect_type":"create","event_time":1516126040,"object_id":1360128428,"object_type":"activity","owner_id":134815,"subscription_id":120475,"updates":{}}"#;
+        let athlete = br#"{"aspect_type":"update","event_time":1516126040,"object_id":134815,"object_type":"athlete","owner_id":134815,"subscription_id":120475,"updates":{}}"#;
+        for body in [&event[..], &athlete[..]] {
+            sqlx::query(
+                "INSERT INTO elt_webhook_deliveries (provider, body, request_id) VALUES ('strava', $1, 'req-1')",
+            )
+            .bind(body)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let stored = load_stored_deliveries(&pool).await.unwrap();
+        assert_eq!(
+            stored,
+            [WebhookDelivery {
+                id: 1,
+                provider: "strava".to_string(),
+                streams: vec!["activities"],
+                account: account(),
+                request_id: Some("req-1".to_string()),
+            }]
+        );
+
+        // The athlete event leads to no sync and is dropped
+        assert_eq!(stored_count(&pool).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_delivery_targets_the_account_source() {
+        let pool = crate::sources::base::mock_transport::testing::migrated_pool().await;
+        for (id, athlete) in [("runner", "134815"), ("cyclist", "999")] {
+            sqlx::query(
+                "INSERT INTO elt_source_connections (id, source, name, metadata)
+                 VALUES ($1, 'strava', $1, json_object('athlete_id', $2))",
+            )
+            .bind(id)
+            .bind(athlete)
+            .execute(&pool)
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT INTO elt_stream_connections (id, source_connection_id, stream_name, table_name)
+                 VALUES ($1, $1, 'activities', 'stream_strava_activities')",
+            )
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let sources = account_sources(&pool, "strava", "activities", &account())
+            .await
+            .unwrap();
+        assert_eq!(sources, ["runner"]);
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_sheds_load() {
+        let pool = crate::sources::base::mock_transport::testing::migrated_pool().await;
+        let (queue, _receiver) = WebhookQueue::bounded(pool.clone(), 1);
+        let query = HashMap::new();
+
+        assert!(
+            enqueue_verified(&queue, &TestWebhook, &signed("ok"), &query, b"{}")
+                .await
+                .is_ok()
+        );
+        assert!(matches!(
+            enqueue_verified(&queue, &TestWebhook, &signed("ok"), &query, b"{}").await,
+            Err(Error::Overloaded(_))
+        ));
+        assert_eq!(stored_count(&pool).await, 1);
+    }
+}
@@ -2,6 +2,9 @@
 //!
 //! This binary starts the Virtues MCP server with stdio transport for AI assistants like Claude Desktop.
 //! For HTTP transport, use the main Virtues server (`cargo run serve`) which includes MCP at /mcp endpoint.
+//!
+//! Syncs requested over stdio are started by the running server at `VIRTUES_SERVER_URL`
+//! (default http://localhost:8000), which must be up for `sync_stream` to work.
 
 use anyhow::Result;
 use clap::Parser;
@@ -11,7 +14,7 @@ use sqlx::sqlite::SqlitePoolOptions;
 use std::env;
 use tracing::info;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use virtues::mcp::VirtuesMcpServer;
+use virtues::mcp::{sync::SyncTarget, VirtuesMcpServer};
 
 #[derive(Parser, Debug)]
 #[command(name = "virtues-mcp-server")]
@@ -50,8 +53,8 @@ async fn main() -> Result<()> {
 
     info!("Connected to database");
 
-    // Create MCP server
-    let server = VirtuesMcpServer::new(pool);
+    // Create MCP server; syncs run on the main server, under its limits
+    let server = VirtuesMcpServer::with_sync_target(pool, SyncTarget::remote_from_env());
 
     // Start server on stdio
     info!("Starting MCP server on stdio");
@@ -29,9 +29,14 @@ pub async fn handle_add_source(
 
     // Handle OAuth flow
     let redirect_uri = "http://localhost:8080";
-    let response = crate::initiate_oauth_flow(source_type, Some(redirect_uri.to_string()), None)
-        .await
-        .map_err(|e| format!("Failed to initiate OAuth flow: {e}"))?;
+    let response = crate::initiate_oauth_flow(
+        virtues.database.pool(),
+        source_type,
+        Some(redirect_uri.to_string()),
+        None,
+    )
+    .await
+    .map_err(|e| format!("Failed to initiate OAuth flow: {e}"))?;
 
     println!("\n🌐 Please visit the following URL to authorize:");
     println!("{}", response.authorization_url);
@@ -115,6 +120,7 @@ fn parse_callback_url(
     let access_token = params.get("access_token").map(|s| s.to_string());
     let refresh_token = params.get("refresh_token").map(|s| s.to_string());
     let expires_in = params.get("expires_in").and_then(|s| s.parse::<i64>().ok());
+    let scope = params.get("scope").map(|s| s.to_string());
     let state = params.get("state").map(|s| s.to_string());
 
     // Notion-specific fields
@@ -134,6 +140,7 @@ fn parse_callback_url(
         access_token,
         refresh_token,
         expires_in,
+        scope,
         provider: provider.to_string(),
         state,
         workspace_id,
@@ -0,0 +1,29 @@
+//! Doctor command handler - check the database schema against the registry
+
+use crate::Virtues;
+
+/// Handle `virtues doctor`
+///
+/// Reports every ontology the registry transforms into that has no table in
+/// the database, and exits non-zero if any are missing.
+pub async fn handle_doctor_command(virtues: Virtues) -> Result<(), Box<dyn std::error::Error>> {
+    let missing = crate::api::ontologies::missing_ontology_tables(virtues.database.pool()).await?;
+
+    if missing.is_empty() {
+        println!("✅ Every registry ontology has a table in the database");
+        return Ok(());
+    }
+
+    println!("❌ {} ontology table(s) missing:", missing.len());
+    for table in &missing {
+        println!(
+            "  {} (fed by {})",
+            table.table_name,
+            table.fed_by.join(", ")
+        );
+    }
+    println!();
+    println!("Run 'virtues migrate' to bring the schema up to date.");
+
+    Err(format!("{} ontology table(s) missing", missing.len()).into())
+}
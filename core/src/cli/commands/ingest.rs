@@ -0,0 +1,114 @@
+//! Ingest command handlers - watch device data arriving at a running server
+
+use std::env;
+
+use futures::StreamExt;
+
+use crate::cli::types::IngestCommands;
+use crate::server::ingest::IngestEvent;
+
+/// Handle ingest commands
+pub async fn handle_ingest_command(
+    action: IngestCommands,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match action {
+        IngestCommands::Tail {
+            source,
+            stream,
+            raw,
+        } => tail(source, stream, raw).await,
+    }
+}
+
+/// Follow the server's ingest tail until it closes or the user stops it
+async fn tail(
+    source: Option<String>,
+    stream: Option<String>,
+    raw: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let server_url =
+        env::var("VIRTUES_SERVER_URL").unwrap_or_else(|_| "http://localhost:8000".to_string());
+
+    let mut query = Vec::new();
+    if let Some(source) = &source {
+        query.push(("source", source.as_str()));
+    }
+    if let Some(stream) = &stream {
+        query.push(("stream", stream.as_str()));
+    }
+
+    let mut request = reqwest::Client::new()
+        .get(format!(
+            "{}/internal/ingest/tail",
+            server_url.trim_end_matches('/')
+        ))
+        .query(&query);
+    if let Ok(secret) = env::var("TOLLBOOTH_INTERNAL_SECRET") {
+        request = request.header("X-Tollbooth-Secret", secret);
+    }
+
+    let response = request.send().await?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Server returned {}: {}",
+            response.status(),
+            response.text().await.unwrap_or_default()
+        )
+        .into());
+    }
+
+    if !raw {
+        eprintln!("Tailing ingestion on {} (Ctrl+C to stop)...", server_url);
+    }
+
+    // Server-sent events: `event:` / `data:` lines, blank line ends an event
+    let mut body = response.bytes_stream();
+    let mut buffer = String::new();
+    let mut event_name = String::new();
+    while let Some(chunk) = body.next().await {
+        buffer.push_str(&String::from_utf8_lossy(&chunk?));
+        while let Some(newline) = buffer.find('\n') {
+            let line = buffer[..newline].trim_end_matches('\r').to_string();
+            buffer.drain(..=newline);
+
+            if let Some(name) = line.strip_prefix("event:") {
+                event_name = name.trim().to_string();
+            } else if let Some(data) = line.strip_prefix("data:") {
+                print_event(&event_name, data.trim(), raw)?;
+            } else if line.is_empty() {
+                event_name.clear();
+            }
+        }
+    }
+
+    if !raw {
+        eprintln!("Server closed the connection");
+    }
+    Ok(())
+}
+
+fn print_event(name: &str, data: &str, raw: bool) -> Result<(), Box<dyn std::error::Error>> {
+    match name {
+        "ingest" => {
+            let event: IngestEvent = serde_json::from_str(data)?;
+            if raw {
+                for record in &event.records {
+                    println!("{}", serde_json::to_string(record)?);
+                }
+            } else {
+                println!(
+                    "{}  {}/{}  {} record(s)  device={}  source_id={}",
+                    event.received_at.format("%H:%M:%S"),
+                    event.source,
+                    event.stream,
+                    event.records.len(),
+                    event.device_id,
+                    event.source_id
+                );
+            }
+        }
+        "lagged" => eprintln!("... skipped {} batch(es), tail fell behind", data),
+        _ => {}
+    }
+    Ok(())
+}
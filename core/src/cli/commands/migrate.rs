@@ -0,0 +1,72 @@
+//! Migrate command handlers - inspect and apply schema migrations
+
+use crate::cli::types::MigrateCommands;
+use crate::database::MigrationStatus;
+use crate::Virtues;
+
+/// Handle migration commands
+pub async fn handle_migrate_command(
+    virtues: Virtues,
+    action: MigrateCommands,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match action {
+        MigrateCommands::Status { json } => {
+            let status = virtues.database.migration_status().await?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&status)?);
+            } else {
+                display_migration_status(&status);
+            }
+
+            // Non-zero exit lets deploy scripts gate on the schema version
+            if !status.is_up_to_date() {
+                std::process::exit(1);
+            }
+        }
+
+        MigrateCommands::Up { target } => {
+            match target {
+                Some(version) => println!("Applying migrations up to {}...", version),
+                None => println!("Applying pending migrations..."),
+            }
+            let status = virtues.database.migrate_up(target).await?;
+            println!("✅ Migrations applied");
+            display_migration_status(&status);
+        }
+
+        MigrateCommands::Down { target } => {
+            println!("Reverting migrations down to {}...", target);
+            let status = virtues.database.migrate_down(target).await?;
+            println!("✅ Migrations reverted");
+            display_migration_status(&status);
+        }
+    }
+
+    Ok(())
+}
+
+fn display_migration_status(status: &MigrationStatus) {
+    let version = |v: Option<i64>| v.map_or_else(|| "none".to_string(), |v| format!("{:03}", v));
+
+    println!("Schema version: {}", version(status.current));
+    println!("Latest known:   {}", version(status.latest));
+
+    if !status.failed.is_empty() {
+        println!();
+        println!("❌ Failed migrations (need manual repair):");
+        for v in &status.failed {
+            println!("  {:03}", v);
+        }
+    }
+
+    if status.pending.is_empty() {
+        println!("✅ Up to date");
+    } else {
+        println!();
+        println!("Pending migrations:");
+        for m in &status.pending {
+            println!("  {:03} {}", m.version, m.description);
+        }
+    }
+}
@@ -2,12 +2,22 @@
 
 pub mod add;
 pub mod catalog;
+pub mod doctor;
+pub mod ingest;
+pub mod migrate;
+pub mod query;
 pub mod tunnel;
 pub mod source;
+pub mod storage;
 pub mod stream;
 
 pub use add::handle_add_source;
 pub use catalog::handle_catalog_command;
+pub use doctor::handle_doctor_command;
+pub use ingest::handle_ingest_command;
+pub use migrate::handle_migrate_command;
+pub use query::handle_query_command;
 pub use tunnel::handle_tunnel_command;
 pub use source::handle_source_command;
+pub use storage::handle_storage_command;
 pub use stream::handle_stream_command;
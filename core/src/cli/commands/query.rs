@@ -0,0 +1,32 @@
+//! Query command handler - run SQL against the database
+
+use crate::Virtues;
+
+/// Handle `virtues query`
+///
+/// Read-only by default (see `database::read_only`); `--allow-write` runs the
+/// statement as-is.
+pub async fn handle_query_command(
+    virtues: Virtues,
+    sql: &str,
+    allow_write: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !allow_write {
+        let rows = virtues.query(sql).await?;
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+        eprintln!("{} row(s)", rows.len());
+        return Ok(());
+    }
+
+    let pool = virtues.database.pool();
+    if crate::database::read_only::ensure_read_only(sql).is_ok() {
+        let rows = sqlx::query(sql).fetch_all(pool).await?;
+        let rows = crate::mcp::tools::convert_rows_to_json(&rows);
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+        eprintln!("{} row(s)", rows.len());
+    } else {
+        let result = sqlx::query(sql).execute(pool).await?;
+        println!("{} row(s) affected", result.rows_affected());
+    }
+    Ok(())
+}
@@ -3,6 +3,7 @@
 use crate::cli::display::display_pending_pairings;
 use crate::cli::types::SourceCommands;
 use crate::Virtues;
+use serde_json::{json, Value};
 use std::env;
 
 /// Handle source management commands
@@ -11,11 +12,41 @@ pub async fn handle_source_command(
     action: SourceCommands,
 ) -> Result<(), Box<dyn std::error::Error>> {
     match action {
-        SourceCommands::List { pending } => {
-            if pending {
+        SourceCommands::List { pending, json } => {
+            if pending && json {
+                let pairings = crate::list_pending_pairings(virtues.database.pool()).await?;
+                let pairings: Vec<Value> = pairings
+                    .iter()
+                    .map(|p| {
+                        json!({
+                            "source_id": p.source_id,
+                            "name": p.name,
+                            "device_type": p.device_type,
+                            "code": p.code,
+                            "expires_at": p.expires_at,
+                            "created_at": p.created_at,
+                        })
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&pairings)?);
+            } else if pending {
                 // Show pending pairings
                 let pairings = crate::list_pending_pairings(virtues.database.pool()).await?;
                 display_pending_pairings(&pairings);
+            } else if json {
+                let pool = virtues.database.pool();
+                let mut sources = Vec::new();
+                for source in crate::list_sources(pool).await? {
+                    let status = crate::get_source_status(pool, source.id.clone()).await?;
+                    let streams = enabled_streams(&virtues, &source.id).await;
+                    sources.push(source_json(&status, source.last_sync_at.as_ref(), streams));
+                }
+
+                let output = json!({
+                    "health": health_json(&virtues).await,
+                    "sources": sources,
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
             } else {
                 // Show all sources
                 let sources = crate::list_sources(virtues.database.pool()).await?;
@@ -30,14 +61,9 @@ pub async fn handle_source_command(
                 println!("{}", "-".repeat(80));
 
                 for source in sources {
-                    let status = if source.is_active {
-                        "active"
-                    } else {
-                        "inactive"
-                    };
                     println!(
                         "{} {:<20} {:<15} {}",
-                        source.id, source.name, source.source, status
+                        source.id, source.name, source.source, source.status
                     );
                 }
             }
@@ -87,14 +113,7 @@ pub async fn handle_source_command(
                     println!("  ID: {}", source.id);
                     println!("  Name: {}", source.name);
                     println!("  Provider: {}", source.source);
-                    println!(
-                        "  Status: {}",
-                        if source.is_active {
-                            "active"
-                        } else {
-                            "inactive"
-                        }
-                    );
+                    println!("  Status: {}", source.status);
 
                     if let Some(error) = source.error_message {
                         println!("  Error: {}", error);
@@ -106,7 +125,19 @@ pub async fn handle_source_command(
             }
         }
 
-        SourceCommands::Status { id } => {
+        SourceCommands::Status { id, json: true } => {
+            let source = crate::get_source(virtues.database.pool(), id.clone()).await?;
+            let status = crate::get_source_status(virtues.database.pool(), id.clone()).await?;
+            let streams = enabled_streams(&virtues, &id).await;
+
+            let output = json!({
+                "health": health_json(&virtues).await,
+                "source": source_json(&status, source.last_sync_at.as_ref(), streams),
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+
+        SourceCommands::Status { id, json: false } => {
             let status = crate::get_source_status(virtues.database.pool(), id).await?;
 
             println!("Source: {} ({})", status.name, status.source);
@@ -129,19 +160,51 @@ pub async fn handle_source_command(
             if let Some(duration) = status.last_sync_duration_ms {
                 println!("  Last Sync Duration: {}ms", duration);
             }
+
+            if !status.stream_errors.is_empty() {
+                println!();
+                println!("Failing Streams:");
+                for stream in &status.stream_errors {
+                    let at = stream
+                        .last_error_at
+                        .as_ref()
+                        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                        .unwrap_or_else(|| "-".to_string());
+                    println!("  {} (at {}): {}", stream.stream_name, at, stream.last_error);
+                }
+            }
         }
 
-        SourceCommands::Delete { id, yes } => {
+        SourceCommands::Delete {
+            id,
+            yes,
+            purge_data,
+        } => {
             // Get source details first
             let source = crate::get_source(virtues.database.pool(), id.clone()).await?;
 
+            // Purging always goes through a plan of the exact objects removed
+            let plan = if purge_data {
+                Some(
+                    crate::plan_source_purge(virtues.database.pool(), &virtues.storage, id.clone())
+                        .await?,
+                )
+            } else {
+                None
+            };
+
             if !yes {
                 println!("Are you sure you want to delete source:");
                 println!("  Name: {}", source.name);
                 println!("  Provider: {}", source.source);
                 println!("  ID: {}", source.id);
                 println!();
-                println!("This will delete ALL data associated with this source!");
+                if let Some(plan) = &plan {
+                    println!("This will permanently delete ALL stored data for this source!");
+                    super::storage::print_deletion_plan(plan);
+                } else {
+                    println!("Stored data is kept and the source can be restored later.");
+                }
                 println!();
                 print!("Type 'yes' to confirm: ");
 
@@ -157,8 +220,18 @@ pub async fn handle_source_command(
                 }
             }
 
-            crate::delete_source(virtues.database.pool(), id).await?;
-            println!("✅ Source deleted successfully");
+            let purged = crate::soft_delete_source(
+                virtues.database.pool(),
+                &virtues.storage,
+                id,
+                plan.map(|plan| plan.confirm()),
+            )
+            .await?;
+            if purge_data {
+                println!("✅ Source deleted ({} stored objects removed)", purged);
+            } else {
+                println!("✅ Source deleted successfully");
+            }
         }
 
         SourceCommands::History { id, limit } => {
@@ -222,3 +295,58 @@ pub async fn handle_source_command(
 
     Ok(())
 }
+
+/// Names of a source's enabled streams (empty if the provider is unknown)
+async fn enabled_streams(virtues: &Virtues, source_id: &str) -> Vec<String> {
+    crate::list_source_streams(virtues.database.pool(), source_id.to_string())
+        .await
+        .map(|streams| {
+            streams
+                .into_iter()
+                .filter(|s| s.is_enabled)
+                .map(|s| s.stream_name)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Machine-readable view of a source for `--json` output
+fn source_json(
+    status: &crate::SourceConnectionStatus,
+    last_sync_at: Option<&crate::types::Timestamp>,
+    enabled_streams: Vec<String>,
+) -> Value {
+    json!({
+        "id": status.id,
+        "name": status.name,
+        "provider": status.source,
+        "status": status.status,
+        "error_message": status.error_message,
+        "enabled_streams": enabled_streams,
+        "last_sync_at": last_sync_at,
+        "last_sync_status": status.last_sync_status,
+        "last_success_at": status.last_sync_at,
+        "last_sync_duration_ms": status.last_sync_duration_ms,
+        "total_syncs": status.total_syncs,
+        "successful_syncs": status.successful_syncs,
+        "failed_syncs": status.failed_syncs,
+        "needs_reauth": status.needs_reauth,
+        "reauth_reason": status.reauth_reason,
+        "missing_scopes": status.missing_scopes,
+        "stream_errors": status.stream_errors,
+    })
+}
+
+/// Database and storage health for `--json` output
+async fn health_json(virtues: &Virtues) -> Value {
+    let database = match virtues.database.health_check().await {
+        Ok(h) => json!({ "healthy": h.is_healthy, "message": h.message }),
+        Err(e) => json!({ "healthy": false, "message": e.to_string() }),
+    };
+    let storage = match virtues.storage.health_check().await {
+        Ok(h) => json!({ "healthy": h.is_healthy, "message": h.message }),
+        Err(e) => json!({ "healthy": false, "message": e.to_string() }),
+    };
+
+    json!({ "database": database, "storage": storage })
+}
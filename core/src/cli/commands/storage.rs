@@ -0,0 +1,83 @@
+//! Storage command handlers - plan and prune stored stream archives
+
+use crate::cli::types::StorageCommands;
+use crate::storage::DeletionPlan;
+use crate::Virtues;
+
+/// Handle storage commands
+pub async fn handle_storage_command(
+    virtues: Virtues,
+    action: StorageCommands,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match action {
+        StorageCommands::Prune {
+            dry_run,
+            confirm,
+            json,
+        } => {
+            let pool = virtues.database.pool();
+            let plan = crate::api::plan_prune(pool, &virtues.storage).await?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&plan)?);
+            } else if plan.is_empty() {
+                println!(
+                    "Nothing to prune (no sources deleted more than {} days ago with stored data)",
+                    plan.grace_days
+                );
+            } else {
+                println!(
+                    "Sources deleted more than {} days ago with stored data:",
+                    plan.grace_days
+                );
+                for expired in &plan.sources {
+                    println!();
+                    println!(
+                        "  {} ({}), deleted {}",
+                        expired.source_id, expired.source, expired.deleted_at
+                    );
+                    print_deletion_plan(&expired.plan);
+                }
+                println!();
+                println!(
+                    "Total: {} objects, {} bytes",
+                    plan.total_objects, plan.total_bytes
+                );
+            }
+
+            if plan.is_empty() || dry_run {
+                return Ok(());
+            }
+            if !confirm {
+                if !json {
+                    println!();
+                    println!("Nothing was deleted. Re-run with --confirm to delete these objects.");
+                }
+                return Ok(());
+            }
+
+            let report = crate::api::execute_prune(pool, &virtues.storage, plan.confirm()).await?;
+            if !json {
+                println!(
+                    "✅ Pruned {} objects ({} bytes)",
+                    report.objects_deleted, report.bytes_deleted
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the keys and total size a deletion would remove
+pub(crate) fn print_deletion_plan(plan: &DeletionPlan) {
+    println!(
+        "  {} objects, {} bytes under {}",
+        plan.object_count(),
+        plan.total_bytes,
+        plan.prefix
+    );
+    for object in &plan.objects {
+        println!("    {} ({} bytes)", object.key, object.size_bytes);
+    }
+}
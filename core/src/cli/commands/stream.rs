@@ -77,6 +77,11 @@ pub async fn handle_stream_command(
                 println!("  Last Sync: never");
             }
 
+            println!("  Partitioning: {}", stream.partition_granularity);
+            if stream.end_to_end_encrypted {
+                println!("  End-to-end encrypted: yes");
+            }
+
             // Show config if it's not an empty object
             if let serde_json::Value::Object(map) = &stream.config {
                 if !map.is_empty() {
@@ -123,6 +128,7 @@ pub async fn handle_stream_command(
             source_id,
             stream_name,
             cron,
+            timezone,
         } => {
             if let Some(cron_schedule) = cron {
                 println!(
@@ -134,6 +140,7 @@ pub async fn handle_stream_command(
                     source_id.clone(),
                     &stream_name,
                     Some(cron_schedule.clone()),
+                    timezone,
                 )
                 .await?;
                 println!("✅ Schedule updated successfully");
@@ -144,6 +151,7 @@ pub async fn handle_stream_command(
                     source_id.clone(),
                     &stream_name,
                     None,
+                    None,
                 )
                 .await?;
                 println!("✅ Schedule cleared (stream will be manual only)");
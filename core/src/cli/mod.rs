@@ -6,6 +6,7 @@ pub mod types;
 
 use crate::storage::stream_writer::StreamWriter;
 use crate::Virtues;
+use chrono::{NaiveTime, Utc};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use types::{Cli, Commands};
@@ -13,7 +14,7 @@ use types::{Cli, Commands};
 /// Run the CLI application
 pub async fn run(cli: Cli, virtues: Virtues) -> Result<(), Box<dyn std::error::Error>> {
     // Initialize StreamWriter (simple in-memory buffer)
-    let stream_writer = StreamWriter::new();
+    let stream_writer = StreamWriter::from_env();
     let stream_writer_arc = Arc::new(Mutex::new(stream_writer));
 
     // Command should always be Some at this point (main.rs handles None case)
@@ -25,12 +26,18 @@ pub async fn run(cli: Cli, virtues: Virtues) -> Result<(), Box<dyn std::error::E
             unreachable!("Init command should be handled in main.rs");
         }
 
-        Commands::Migrate => {
+        Commands::Migrate { action: None } => {
             println!("Running database migrations...");
             virtues.database.initialize().await?;
             println!("✅ Migrations completed successfully");
         }
 
+        Commands::Migrate {
+            action: Some(action),
+        } => {
+            commands::handle_migrate_command(virtues, action).await?;
+        }
+
         Commands::Catalog { action } => {
             commands::handle_catalog_command(action)?;
         }
@@ -47,12 +54,53 @@ pub async fn run(cli: Cli, virtues: Virtues) -> Result<(), Box<dyn std::error::E
             commands::handle_source_command(virtues, action).await?;
         }
 
+        Commands::Query { sql, allow_write } => {
+            commands::handle_query_command(virtues, &sql, allow_write).await?;
+        }
+
+        Commands::Storage { action } => {
+            commands::handle_storage_command(virtues, action).await?;
+        }
+
+        Commands::Ingest { action } => {
+            commands::handle_ingest_command(action).await?;
+        }
+
         Commands::Stream { action } => {
             commands::handle_stream_command(virtues, stream_writer_arc.clone(), action).await?;
         }
 
-        Commands::Sync { source_id } => {
-            println!("Syncing source: {}...", source_id);
+        Commands::Sync {
+            source_id,
+            since,
+            until,
+        } => {
+            let sync_mode = match since {
+                Some(since) => {
+                    let start_date = since.and_time(NaiveTime::MIN).and_utc();
+                    // --until is inclusive, so the window ends at the following midnight
+                    let end_date = match until {
+                        Some(until) => (until + chrono::Duration::days(1))
+                            .and_time(NaiveTime::MIN)
+                            .and_utc(),
+                        None => Utc::now(),
+                    };
+                    if start_date >= end_date {
+                        return Err("--since must be on or before --until".into());
+                    }
+                    println!(
+                        "Backfilling source: {} ({} to {})...",
+                        source_id,
+                        start_date.format("%Y-%m-%d"),
+                        end_date.format("%Y-%m-%d %H:%M")
+                    );
+                    crate::SyncMode::backfill(start_date, end_date)
+                }
+                None => {
+                    println!("Syncing source: {}...", source_id);
+                    crate::SyncMode::full_refresh()
+                }
+            };
 
             // Get all enabled streams for this source
             let streams = crate::list_source_streams(virtues.database.pool(), source_id.clone()).await?;
@@ -69,7 +117,6 @@ pub async fn run(cli: Cli, virtues: Virtues) -> Result<(), Box<dyn std::error::E
 
             println!("Syncing {} enabled stream(s)...\n", enabled_streams.len());
 
-            let sync_mode = crate::SyncMode::full_refresh();
             let mut jobs_created = 0;
             let mut failed_count = 0;
 
@@ -117,6 +164,16 @@ pub async fn run(cli: Cli, virtues: Virtues) -> Result<(), Box<dyn std::error::E
             println!("🌱 Seeding defaults...");
             crate::seeding::prod_seed::seed_production_data(&virtues.database).await?;
             println!("✅ Seeding complete");
+
+            match crate::api::ontologies::missing_ontology_tables(virtues.database.pool()).await {
+                Ok(missing) if !missing.is_empty() => {
+                    let tables: Vec<&str> = missing.iter().map(|m| m.table_name.as_str()).collect();
+                    println!("⚠️  Missing ontology tables: {}", tables.join(", "));
+                    tracing::warn!(tables = ?tables, "Registry ontologies missing from schema");
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!(error = %e, "Ontology schema check failed"),
+            }
             println!();
 
             println!("Starting Virtues server on {}:{}", host, port);
@@ -139,6 +196,10 @@ pub async fn run(cli: Cli, virtues: Virtues) -> Result<(), Box<dyn std::error::E
             println!("✅ Demo data seeded");
         }
 
+        Commands::Doctor => {
+            commands::handle_doctor_command(virtues).await?;
+        }
+
         Commands::Tunnel => {
             commands::handle_tunnel_command(virtues).await?;
         }
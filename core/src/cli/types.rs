@@ -1,5 +1,6 @@
 //! CLI argument types and command structures
 
+use chrono::NaiveDate;
 use clap::{Parser, Subcommand};
 
 /// Default port: reads NOMAD_PORT_http env var (Nomad host networking),
@@ -17,15 +18,23 @@ fn default_port() -> u16 {
 pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Commands>,
+
+    /// Keep everything on this machine under ~/.virtues, with a generated
+    /// encryption key (also enabled by VIRTUES_LOCAL=1)
+    #[arg(long, global = true)]
+    pub local: bool,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
-    /// Interactive setup wizard
+    /// Interactive setup wizard (`--local` skips it and sets up ~/.virtues)
     Init,
 
-    /// Run database migrations
-    Migrate,
+    /// Run database migrations (or inspect/apply them with a subcommand)
+    Migrate {
+        #[command(subcommand)]
+        action: Option<MigrateCommands>,
+    },
 
     /// Start the HTTP server
     Server {
@@ -71,11 +80,48 @@ pub enum Commands {
     },
 
     /// Sync all streams for a source
+    ///
+    /// With --since, backfills only the given window instead of a full refresh.
     Sync {
         /// Source ID (UUID)
         source_id: String,
+
+        /// Backfill from this date (YYYY-MM-DD, UTC)
+        #[arg(long, visible_alias = "from", value_parser = parse_date)]
+        since: Option<NaiveDate>,
+
+        /// Backfill up to and including this date (YYYY-MM-DD, UTC; defaults to now)
+        #[arg(long, visible_alias = "to", value_parser = parse_date, requires = "since")]
+        until: Option<NaiveDate>,
+    },
+
+    /// Run a SQL query against the database
+    ///
+    /// Queries are read-only unless --allow-write is given.
+    Query {
+        /// SQL statement
+        sql: String,
+
+        /// Allow statements that modify the database (admin use)
+        #[arg(long)]
+        allow_write: bool,
     },
 
+    /// Inspect and prune stored stream archives
+    Storage {
+        #[command(subcommand)]
+        action: StorageCommands,
+    },
+
+    /// Watch data arriving from devices
+    Ingest {
+        #[command(subcommand)]
+        action: IngestCommands,
+    },
+
+    /// Check the database schema against the registry's ontologies
+    Doctor,
+
     /// Seed the database with demo data (people, places, events, etc.)
     Seed,
 
@@ -86,6 +132,71 @@ pub enum Commands {
     WarmModels,
 }
 
+#[derive(Subcommand)]
+pub enum IngestCommands {
+    /// Print records as a running server accepts them
+    ///
+    /// Connects to VIRTUES_SERVER_URL (default http://localhost:8000).
+    /// Stop with Ctrl+C.
+    Tail {
+        /// Only this source identifier (e.g., ios.healthkit)
+        #[arg(long)]
+        source: Option<String>,
+
+        /// Only this stream
+        #[arg(long)]
+        stream: Option<String>,
+
+        /// Print every record as JSON instead of a per-batch summary
+        #[arg(long)]
+        raw: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum StorageCommands {
+    /// Delete archives of sources deleted beyond the restore window
+    ///
+    /// Always prints the plan first. Nothing is deleted without --confirm.
+    Prune {
+        /// Only print what would be deleted
+        #[arg(long, conflicts_with = "confirm")]
+        dry_run: bool,
+
+        /// Delete the planned objects
+        #[arg(long)]
+        confirm: bool,
+
+        /// Print the plan as machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MigrateCommands {
+    /// Show the current schema version and pending migrations
+    Status {
+        /// Print machine-readable JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Apply pending migrations
+    Up {
+        /// Stop after applying this version (default: apply all)
+        #[arg(long)]
+        target: Option<i64>,
+    },
+
+    /// Revert migrations down to a version (reversible migrations only)
+    Down {
+        /// Version to revert to; later migrations are undone
+        #[arg(long)]
+        target: i64,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum CatalogCommands {
     /// List all available sources
@@ -108,6 +219,10 @@ pub enum SourceCommands {
         /// Show only pending device pairings
         #[arg(long)]
         pending: bool,
+
+        /// Print machine-readable JSON instead of a table
+        #[arg(long)]
+        json: bool,
     },
 
     /// Show details about a source
@@ -120,6 +235,10 @@ pub enum SourceCommands {
     Status {
         /// Source ID (UUID)
         id: String,
+
+        /// Print machine-readable JSON instead of text
+        #[arg(long)]
+        json: bool,
     },
 
     /// Delete a source
@@ -130,6 +249,10 @@ pub enum SourceCommands {
         /// Skip confirmation prompt
         #[arg(long)]
         yes: bool,
+
+        /// Also delete the source's stored stream data (cannot be restored)
+        #[arg(long)]
+        purge_data: bool,
     },
 
     /// Show sync history for a source
@@ -189,6 +312,10 @@ pub enum StreamCommands {
         /// Cron expression in 6-field format: sec min hour day month dow (e.g., "0 0 */6 * * *")
         #[arg(long)]
         cron: Option<String>,
+
+        /// IANA timezone the cron expression is evaluated in (e.g., "America/New_York")
+        #[arg(long)]
+        timezone: Option<String>,
     },
 
     /// Show sync history for a specific stream
@@ -222,3 +349,9 @@ pub enum StreamCommands {
         stream_name: String,
     },
 }
+
+/// Parse a YYYY-MM-DD date argument
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("invalid date '{}', expected YYYY-MM-DD", value))
+}
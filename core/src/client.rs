@@ -1,14 +1,18 @@
 //! Virtues client - Main interface for the Virtues data pipeline
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use serde::Serialize;
 use serde_json::Value;
 
-use crate::database::Database;
+use crate::database::{Database, PoolConfig};
 use crate::error::{Error, Result};
 use crate::storage::Storage;
 
+/// Storage path that selects the in-memory backend (mirrors SQLite's `:memory:`)
+const IN_MEMORY_STORAGE_PATH: &str = ":memory:";
+
 /// Main Virtues client for managing personal data
 pub struct Virtues {
     pub database: Arc<Database>,
@@ -67,6 +71,15 @@ impl Virtues {
         })
     }
 
+    /// Run a read-only SQL query, returning rows as JSON objects
+    ///
+    /// Anything but a single SELECT is rejected, and the query runs with
+    /// SQLite's `query_only` guard (see `database::read_only`).
+    pub async fn query(&self, sql: &str) -> Result<Vec<Value>> {
+        let rows = self.database.query_read_only(sql).await?;
+        Ok(crate::mcp::tools::convert_rows_to_json(&rows))
+    }
+
     // Source management operations are now in api.rs
     // Use virtues::list_sources(), virtues::sync_stream(), etc.
 
@@ -91,6 +104,9 @@ impl Clone for Virtues {
 pub struct VirtuesBuilder {
     database_url: Option<String>,
     storage_path: Option<String>,
+    max_connections: Option<u32>,
+    acquire_timeout: Option<Duration>,
+    idle_timeout: Option<Duration>,
 }
 
 impl VirtuesBuilder {
@@ -105,6 +121,42 @@ impl VirtuesBuilder {
         self
     }
 
+    /// Set the maximum number of pooled database connections
+    ///
+    /// Overrides `DATABASE_MAX_CONNECTIONS`. Keep this above the scheduler's
+    /// `SYNC_MAX_CONCURRENCY`, since each running sync holds a connection
+    /// (see [`PoolConfig`]).
+    pub fn max_connections(mut self, max: u32) -> Self {
+        self.max_connections = Some(max.max(1));
+        self
+    }
+
+    /// Set how long to wait for a pooled connection before failing
+    ///
+    /// Overrides `DATABASE_ACQUIRE_TIMEOUT_SECS`.
+    pub fn acquire_timeout(mut self, timeout: Duration) -> Self {
+        self.acquire_timeout = Some(timeout);
+        self
+    }
+
+    /// Set how long an idle pooled connection is kept open
+    ///
+    /// Overrides `DATABASE_IDLE_TIMEOUT_SECS`.
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Resolve pool settings: builder overrides on top of the environment
+    fn pool_config(&self) -> PoolConfig {
+        let env = PoolConfig::from_env();
+        PoolConfig {
+            max_connections: self.max_connections.unwrap_or(env.max_connections),
+            acquire_timeout: self.acquire_timeout.unwrap_or(env.acquire_timeout),
+            idle_timeout: self.idle_timeout.unwrap_or(env.idle_timeout),
+        }
+    }
+
     /// Set storage path for stream archives (local file storage only)
     ///
     /// Note: This is ignored when S3 is configured via environment variables.
@@ -112,6 +164,8 @@ impl VirtuesBuilder {
     /// Default paths for file storage:
     /// - Dev: ./core/data/lake
     /// - Prod: Uses S3 storage instead
+    ///
+    /// Pass `:memory:` to keep everything in memory (nothing is persisted).
     pub fn storage_path(mut self, path: &str) -> Self {
         self.storage_path = Some(path.to_string());
         self
@@ -121,14 +175,16 @@ impl VirtuesBuilder {
     ///
     /// Storage backend selection:
     /// - If S3_ENDPOINT is set, uses S3 storage (production)
+    /// - If the storage path is `:memory:`, uses in-memory storage
     /// - Otherwise, uses file storage (local development)
     pub async fn build(self) -> Result<Virtues> {
+        let pool_config = self.pool_config();
         let database_url = self
             .database_url
             .or_else(|| std::env::var("DATABASE_URL").ok())
             .ok_or_else(|| Error::Configuration("Database URL required".to_string()))?;
 
-        let database = Database::new(&database_url)?;
+        let database = Database::with_pool_config(&database_url, pool_config)?;
 
         // Storage backend selection:
         // 1. If S3 is configured, use S3 storage
@@ -155,6 +211,9 @@ impl VirtuesBuilder {
                     Storage::file(file_storage_path)?
                 }
             }
+        } else if file_storage_path == IN_MEMORY_STORAGE_PATH {
+            tracing::warn!("Using in-memory storage backend; stream data will not be persisted");
+            Storage::in_memory()
         } else {
             tracing::info!(path = %file_storage_path, "Using file storage backend");
             Storage::file(file_storage_path)?
@@ -199,4 +258,15 @@ mod tests {
         assert!(builder.database_url.is_some());
         assert!(builder.storage_path.is_some());
     }
+
+    #[test]
+    fn test_builder_pool_overrides() {
+        let config = VirtuesBuilder::new()
+            .max_connections(12)
+            .acquire_timeout(Duration::from_secs(30))
+            .pool_config();
+
+        assert_eq!(config.max_connections, 12);
+        assert_eq!(config.acquire_timeout, Duration::from_secs(30));
+    }
 }
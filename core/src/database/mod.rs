@@ -3,10 +3,16 @@
 use std::sync::Once;
 use std::time::Duration;
 
-use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
+use serde::Serialize;
+use sqlx::{migrate::Migrator, sqlite::SqlitePoolOptions, SqlitePool};
 
 use crate::error::{Error, Result};
 
+pub mod read_only;
+
+/// Migrations embedded from the migrations folder at build time
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
 /// Register the sqlite-vec extension globally (once).
 ///
 /// This must be called before any SQLite connections are created.
@@ -24,6 +30,58 @@ fn register_sqlite_vec_extension() {
     });
 }
 
+/// Connection pool settings
+///
+/// Each running sync job holds a connection for most of its run, and the
+/// server, transforms and scheduler need connections of their own. Size
+/// `max_connections` above `SYNC_MAX_CONCURRENCY` (the scheduler's sync
+/// semaphore, default 4) or concurrent syncs will queue on the pool and
+/// eventually fail with "pool timed out" after `acquire_timeout`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolConfig {
+    /// Maximum open connections (`DATABASE_MAX_CONNECTIONS`, default 5)
+    pub max_connections: u32,
+    /// How long to wait for a free connection (`DATABASE_ACQUIRE_TIMEOUT_SECS`, default 10)
+    pub acquire_timeout: Duration,
+    /// How long an unused connection stays open (`DATABASE_IDLE_TIMEOUT_SECS`, default 600)
+    pub idle_timeout: Duration,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 5,
+            acquire_timeout: Duration::from_secs(10),
+            idle_timeout: Duration::from_secs(600),
+        }
+    }
+}
+
+impl PoolConfig {
+    /// Load pool settings from the environment, falling back to defaults
+    pub fn from_env() -> Self {
+        fn env_u64(key: &str) -> Option<u64> {
+            std::env::var(key)
+                .ok()
+                .and_then(|s| s.parse::<u64>().ok())
+                .filter(|n| *n > 0)
+        }
+
+        let defaults = Self::default();
+        Self {
+            max_connections: env_u64("DATABASE_MAX_CONNECTIONS")
+                .map(|n| n as u32)
+                .unwrap_or(defaults.max_connections),
+            acquire_timeout: env_u64("DATABASE_ACQUIRE_TIMEOUT_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.acquire_timeout),
+            idle_timeout: env_u64("DATABASE_IDLE_TIMEOUT_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.idle_timeout),
+        }
+    }
+}
+
 /// Database connection and operations
 #[derive(Clone)]
 pub struct Database {
@@ -32,22 +90,29 @@ pub struct Database {
 
 impl Database {
     /// Create a new database connection
+    ///
+    /// Pool settings come from the environment (see [`PoolConfig::from_env`]).
     pub fn new(database_url: &str) -> Result<Self> {
+        Self::with_pool_config(database_url, PoolConfig::from_env())
+    }
+
+    /// Create a new database connection with explicit pool settings
+    pub fn with_pool_config(database_url: &str, config: PoolConfig) -> Result<Self> {
         // Ensure sqlite-vec is registered before creating the pool
         register_sqlite_vec_extension();
-        // Get max connections from environment (default: 5 for SQLite)
-        let max_connections = std::env::var("DATABASE_MAX_CONNECTIONS")
-            .ok()
-            .and_then(|s| s.parse::<u32>().ok())
-            .unwrap_or(5);
 
-        tracing::info!("Database pool max connections: {}", max_connections);
+        tracing::info!(
+            max_connections = config.max_connections,
+            acquire_timeout_secs = config.acquire_timeout.as_secs(),
+            idle_timeout_secs = config.idle_timeout.as_secs(),
+            "Database pool configured"
+        );
 
         // Pool will be created on first use
         let pool = SqlitePoolOptions::new()
-            .max_connections(max_connections)
-            .acquire_timeout(Duration::from_secs(10))
-            .idle_timeout(Duration::from_secs(600))
+            .max_connections(config.max_connections)
+            .acquire_timeout(config.acquire_timeout)
+            .idle_timeout(config.idle_timeout)
             .max_lifetime(Duration::from_secs(1800))
             .after_connect(|conn, _meta| {
                 Box::pin(async move {
@@ -82,6 +147,11 @@ impl Database {
         &self.pool
     }
 
+    /// Run ad-hoc SQL that must not write (see [`read_only`])
+    pub async fn query_read_only(&self, sql: &str) -> Result<Vec<sqlx::sqlite::SqliteRow>> {
+        read_only::fetch_read_only(&self.pool, sql).await
+    }
+
     /// Initialize database (run migrations, etc.)
     pub async fn initialize(&self) -> Result<()> {
         // Test connection
@@ -99,7 +169,7 @@ impl Database {
     /// Run database migrations
     async fn run_migrations(&self) -> Result<()> {
         // Use sqlx migrate to run migrations from the migrations folder
-        sqlx::migrate!("./migrations")
+        MIGRATOR
             .run(&self.pool)
             .await
             .map_err(|e| Error::Database(format!("Failed to run migrations: {e}")))?;
@@ -107,6 +177,115 @@ impl Database {
         Ok(())
     }
 
+    /// Report the applied schema version and any pending migrations
+    ///
+    /// Read-only: unlike `initialize`, this never applies migrations.
+    pub async fn migration_status(&self) -> Result<MigrationStatus> {
+        let applied = self.applied_migrations().await?;
+
+        let pending = MIGRATOR
+            .iter()
+            .filter(|m| !m.migration_type.is_down_migration())
+            .filter(|m| !applied.iter().any(|(version, _)| *version == m.version))
+            .map(|m| MigrationInfo {
+                version: m.version,
+                description: m.description.to_string(),
+            })
+            .collect();
+
+        Ok(MigrationStatus {
+            current: applied
+                .iter()
+                .filter(|(_, success)| *success)
+                .map(|(version, _)| *version)
+                .max(),
+            latest: MIGRATOR.iter().map(|m| m.version).max(),
+            pending,
+            failed: applied
+                .iter()
+                .filter(|(_, success)| !*success)
+                .map(|(version, _)| *version)
+                .collect(),
+        })
+    }
+
+    /// Apply pending migrations, up to and including `target` if given
+    ///
+    /// Already-applied migrations are skipped, so this is safe to re-run.
+    pub async fn migrate_up(&self, target: Option<i64>) -> Result<MigrationStatus> {
+        match target {
+            None => self.run_migrations().await?,
+            Some(target) => {
+                if !MIGRATOR.iter().any(|m| m.version == target) {
+                    return Err(Error::InvalidInput(format!(
+                        "Unknown migration version: {target}"
+                    )));
+                }
+
+                let migrations: Vec<_> = MIGRATOR
+                    .iter()
+                    .filter(|m| m.version <= target)
+                    .cloned()
+                    .collect();
+                let mut migrator = Migrator {
+                    migrations: migrations.into(),
+                    ..Migrator::DEFAULT
+                };
+                // Versions above the target may already be applied
+                migrator.set_ignore_missing(true);
+                migrator
+                    .run(&self.pool)
+                    .await
+                    .map_err(|e| Error::Database(format!("Failed to run migrations: {e}")))?;
+            }
+        }
+
+        self.migration_status().await
+    }
+
+    /// Revert applied migrations down to `target` (exclusive)
+    ///
+    /// Only possible for reversible migrations (with `.down.sql` files).
+    pub async fn migrate_down(&self, target: i64) -> Result<MigrationStatus> {
+        let reversible = MIGRATOR
+            .iter()
+            .any(|m| m.migration_type.is_down_migration());
+        if !reversible {
+            return Err(Error::InvalidInput(
+                "Migrations are forward-only (no down migrations); restore from a backup to roll back"
+                    .into(),
+            ));
+        }
+
+        MIGRATOR
+            .undo(&self.pool, target)
+            .await
+            .map_err(|e| Error::Database(format!("Failed to revert migrations: {e}")))?;
+
+        self.migration_status().await
+    }
+
+    /// Applied migration versions with their success flag
+    async fn applied_migrations(&self) -> Result<Vec<(i64, bool)>> {
+        let has_table = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations')",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        if !has_table {
+            return Ok(Vec::new());
+        }
+
+        let applied = sqlx::query_as::<_, (i64, bool)>(
+            "SELECT version, success FROM _sqlx_migrations ORDER BY version",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(applied)
+    }
+
     /// Execute a query with parameters
     pub async fn execute(&self, sql: &str, params: &[&str]) -> Result<()> {
         let mut query = sqlx::query(sql);
@@ -199,6 +378,33 @@ pub struct HealthStatus {
     pub message: String,
 }
 
+/// Schema migration state
+#[derive(Debug, Serialize)]
+pub struct MigrationStatus {
+    /// Highest successfully applied migration version (None for a fresh database)
+    pub current: Option<i64>,
+    /// Highest migration version known to this build
+    pub latest: Option<i64>,
+    /// Migrations known to this build but not yet applied
+    pub pending: Vec<MigrationInfo>,
+    /// Migrations recorded as failed (partially applied; need manual repair)
+    pub failed: Vec<i64>,
+}
+
+impl MigrationStatus {
+    /// Whether the database schema matches this build
+    pub fn is_up_to_date(&self) -> bool {
+        self.pending.is_empty() && self.failed.is_empty()
+    }
+}
+
+/// A migration known to this build
+#[derive(Debug, Serialize)]
+pub struct MigrationInfo {
+    pub version: i64,
+    pub description: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -209,4 +415,27 @@ mod tests {
         let result = Database::new("sqlite::memory:");
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_migration_status() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Database::from_pool(pool);
+
+        let status = db.migration_status().await.unwrap();
+        assert_eq!(status.current, None);
+        assert!(!status.pending.is_empty());
+        assert!(!status.is_up_to_date());
+
+        let first = status.pending[0].version;
+        let status = db.migrate_up(Some(first)).await.unwrap();
+        assert_eq!(status.current, Some(first));
+        assert!(status.pending.iter().all(|m| m.version > first));
+
+        // Forward-only migrations can't be reverted
+        assert!(db.migrate_down(0).await.is_err());
+    }
 }
@@ -0,0 +1,258 @@
+//! Read-only SQL execution
+//!
+//! Ad-hoc SQL (the agent's `sql_query` tool, `virtues query`) goes through
+//! two checks:
+//!
+//! 1. `ensure_read_only` inspects the statement: exactly one statement,
+//!    starting with `SELECT`, `WITH` or `VALUES`, with no write keywords
+//!    outside string literals, quoted identifiers and comments. This gives a
+//!    clear error for the common cases.
+//! 2. `fetch_read_only` runs it on a connection with `PRAGMA query_only`
+//!    set, so SQLite itself refuses any write that slips past the inspection.
+//!    If the pragma can't be reset afterwards (or the query is cancelled),
+//!    the connection is closed rather than returned to the pool.
+
+use sqlx::pool::PoolConnection;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Sqlite, SqlitePool};
+
+use crate::error::{Error, Result};
+
+/// Statements a read-only query may start with
+const READ_STATEMENTS: &[&str] = &["select", "with", "values"];
+
+/// Keywords that only appear in statements that write or change the session
+///
+/// `into` covers `INSERT`/`REPLACE INTO` (including behind a `WITH` clause)
+/// without rejecting the `replace()` function.
+const WRITE_KEYWORDS: &[&str] = &[
+    "insert", "update", "delete", "into", "drop", "create", "alter", "attach", "detach", "pragma",
+    "vacuum", "reindex",
+];
+
+/// Split SQL into lowercased words and `;`, skipping literals and comments
+fn tokenize(sql: &str) -> Result<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut chars = sql.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '-' if chars.peek() == Some(&'-') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = '\0';
+                loop {
+                    match chars.next() {
+                        Some('/') if previous == '*' => break,
+                        Some(c) => previous = c,
+                        None => return Err(Error::InvalidInput("Unterminated comment".into())),
+                    }
+                }
+            }
+            '\'' | '"' | '`' | '[' => {
+                let close = if c == '[' { ']' } else { c };
+                loop {
+                    match chars.next() {
+                        // A doubled quote is an escaped quote
+                        Some(c) if c == close && close != ']' && chars.peek() == Some(&close) => {
+                            chars.next();
+                        }
+                        Some(c) if c == close => break,
+                        Some(_) => {}
+                        None => {
+                            return Err(Error::InvalidInput(
+                                "Unterminated string or quoted identifier".into(),
+                            ))
+                        }
+                    }
+                }
+            }
+            ';' => tokens.push(";".to_string()),
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = c.to_lowercase().to_string();
+                while let Some(&next) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_') {
+                        break;
+                    }
+                    word.extend(next.to_lowercase());
+                    chars.next();
+                }
+                tokens.push(word);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Reject anything but a single read-only statement
+pub fn ensure_read_only(sql: &str) -> Result<()> {
+    let tokens = tokenize(sql)?;
+    let mut statements = tokens
+        .split(|t| t == ";")
+        .filter(|statement| !statement.is_empty());
+
+    let Some(statement) = statements.next() else {
+        return Err(Error::InvalidInput("Query is empty".into()));
+    };
+    if statements.next().is_some() {
+        return Err(Error::InvalidInput(
+            "Only a single statement is allowed".into(),
+        ));
+    }
+    if !READ_STATEMENTS.contains(&statement[0].as_str()) {
+        return Err(Error::InvalidInput(
+            "Only SELECT queries are allowed".into(),
+        ));
+    }
+    if let Some(keyword) = statement
+        .iter()
+        .find(|t| WRITE_KEYWORDS.contains(&t.as_str()))
+    {
+        return Err(Error::InvalidInput(format!(
+            "Query contains forbidden keyword: {}",
+            keyword.to_uppercase()
+        )));
+    }
+    Ok(())
+}
+
+/// A pooled connection in `query_only` mode
+///
+/// Dropped without `release`, the connection is closed instead of going back
+/// to the pool, so a cancelled query can't leave a read-only connection behind.
+struct QueryOnlyConnection {
+    conn: Option<PoolConnection<Sqlite>>,
+}
+
+impl QueryOnlyConnection {
+    async fn acquire(pool: &SqlitePool) -> Result<Self> {
+        let mut conn = pool.acquire().await?;
+        sqlx::query("PRAGMA query_only = ON")
+            .execute(&mut *conn)
+            .await?;
+        Ok(Self { conn: Some(conn) })
+    }
+
+    fn conn(&mut self) -> &mut PoolConnection<Sqlite> {
+        self.conn
+            .as_mut()
+            .expect("connection is only taken on release")
+    }
+
+    /// Reset `query_only` and return the connection to the pool
+    async fn release(mut self) {
+        let reset = sqlx::query("PRAGMA query_only = OFF")
+            .execute(&mut **self.conn())
+            .await;
+        match reset {
+            Ok(_) => drop(self.conn.take()),
+            Err(e) => tracing::warn!(error = %e, "Failed to reset query_only; closing connection"),
+        }
+    }
+}
+
+impl Drop for QueryOnlyConnection {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            drop(conn.detach());
+        }
+    }
+}
+
+/// Run a query with SQLite's `query_only` guard, without inspecting it
+async fn fetch_query_only(pool: &SqlitePool, sql: &str) -> Result<Vec<SqliteRow>> {
+    let mut guard = QueryOnlyConnection::acquire(pool).await?;
+    let rows = sqlx::query(sql).fetch_all(&mut **guard.conn()).await;
+    guard.release().await;
+    rows.map_err(|e| Error::Database(format!("Query failed: {e}")))
+}
+
+/// Inspect a query and run it read-only
+pub async fn fetch_read_only(pool: &SqlitePool, sql: &str) -> Result<Vec<SqliteRow>> {
+    ensure_read_only(sql)?;
+    fetch_query_only(pool, sql).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use sqlx::Row;
+
+    #[test]
+    fn test_accepts_reads() {
+        for sql in [
+            "SELECT * FROM data_health_sleep",
+            "  select count(*) from data_calendar_event where updated_at > '2025-01-01';",
+            "WITH recent AS (SELECT * FROM data_health_steps LIMIT 10) SELECT * FROM recent",
+            "SELECT replace(subject, 'Re: ', '') FROM data_communication_email",
+            "SELECT 'drop table x; delete' AS note -- update later",
+            "SELECT \"insert\" FROM t /* create */",
+            "VALUES (1), (2)",
+        ] {
+            assert!(ensure_read_only(sql).is_ok(), "{sql}");
+        }
+    }
+
+    #[test]
+    fn test_rejects_writes() {
+        for sql in [
+            "DROP TABLE data_health_sleep",
+            "DELETE FROM data_health_sleep",
+            "WITH x AS (SELECT 1) DELETE FROM data_health_sleep",
+            "WITH x AS (SELECT 1) REPLACE INTO t SELECT * FROM x",
+            "SELECT 1; DROP TABLE data_health_sleep",
+            "PRAGMA writable_schema = ON",
+            "ATTACH DATABASE '/tmp/x.db' AS x",
+            "SELECT 'unterminated",
+            "",
+            "-- only a comment",
+        ] {
+            assert!(ensure_read_only(sql).is_err(), "{sql}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_only_connection() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE notes (body TEXT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO notes VALUES ('hello')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let rows = fetch_read_only(&pool, "SELECT body FROM notes")
+            .await
+            .unwrap();
+        assert_eq!(rows[0].get::<String, _>("body"), "hello");
+
+        // SQLite refuses writes even when the inspection is bypassed
+        assert!(fetch_query_only(&pool, "DELETE FROM notes").await.is_err());
+
+        // ...and the pooled connection is writable again afterwards
+        sqlx::query("INSERT INTO notes VALUES ('again')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM notes")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 2);
+    }
+}
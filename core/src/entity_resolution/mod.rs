@@ -1,7 +1,9 @@
 //! Entity Resolution Module
 //!
 //! Pre-resolution pipeline that converts raw ontology primitives into canonical entities.
-//! This module is called inline by the NarrativePrimitivePipeline, NOT as a separate cron job.
+//! Each stage runs as a transform chained from the location and calendar transforms
+//! (see `jobs::entity_resolution_job`), and the `entity_resolution` pipeline job
+//! (`POST /api/jobs/pipeline/entity_resolution/run?stage=people`) re-runs it on demand.
 //!
 //! ## Architecture
 //!
@@ -18,6 +20,9 @@
 //! ```rust
 //! let window = TimeWindow::new(start, end);
 //! let stats = entity_resolution::resolve_entities(db, window).await?;
+//!
+//! // Re-run only people resolution
+//! let people = entity_resolution::resolve_stage(db, ResolutionStage::People, window).await?;
 //! ```
 
 pub mod people;
@@ -47,38 +52,130 @@ impl TimeWindow {
     }
 }
 
+/// An independent stage of entity resolution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolutionStage {
+    /// Location clustering into visits and places
+    Places,
+    /// Calendar attendee resolution into people
+    People,
+}
+
+impl ResolutionStage {
+    /// All stages, in display order
+    pub const ALL: [ResolutionStage; 2] = [ResolutionStage::Places, ResolutionStage::People];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResolutionStage::Places => "places",
+            ResolutionStage::People => "people",
+        }
+    }
+}
+
+impl std::fmt::Display for ResolutionStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ResolutionStage {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        ResolutionStage::ALL
+            .into_iter()
+            .find(|stage| stage.as_str() == s)
+            .ok_or_else(|| {
+                let known: Vec<&str> = ResolutionStage::ALL.iter().map(|s| s.as_str()).collect();
+                format!(
+                    "Unknown resolution stage '{}'. Available: {}",
+                    s,
+                    known.join(", ")
+                )
+            })
+    }
+}
+
+/// Which stages `resolve_entities_with` runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResolutionStages {
+    pub places: bool,
+    pub people: bool,
+}
+
+impl Default for ResolutionStages {
+    fn default() -> Self {
+        Self {
+            places: true,
+            people: true,
+        }
+    }
+}
+
+impl ResolutionStages {
+    /// Only the given stage
+    pub fn only(stage: ResolutionStage) -> Self {
+        Self {
+            places: stage == ResolutionStage::Places,
+            people: stage == ResolutionStage::People,
+        }
+    }
+}
+
 /// Statistics from entity resolution
 #[derive(Debug, Default)]
 pub struct ResolutionStats {
     pub places_resolved: usize,
     pub people_resolved: usize,
+    /// Wall time of the whole run
     pub duration_ms: u128,
+    /// Time spent in place resolution (0 when skipped)
+    pub places_duration_ms: u128,
+    /// Time spent in people resolution (0 when skipped)
+    pub people_duration_ms: u128,
 }
 
 /// Main entry point: Resolve all entities in time window
 ///
 /// This function orchestrates place and people resolution.
-/// Called inline by the narrative primitive pipeline.
 pub async fn resolve_entities(db: &Database, window: TimeWindow) -> Result<ResolutionStats> {
+    resolve_entities_with(db, window, ResolutionStages::default()).await
+}
+
+/// Resolve entities in a time window, running only the enabled stages
+///
+/// Places and people read and write disjoint tables, so enabled stages run
+/// concurrently; the run fails if either stage does.
+pub async fn resolve_entities_with(
+    db: &Database,
+    window: TimeWindow,
+    stages: ResolutionStages,
+) -> Result<ResolutionStats> {
     let start = std::time::Instant::now();
 
     tracing::info!(
         start = %window.start,
         end = %window.end,
+        places = stages.places,
+        people = stages.people,
         "Starting entity resolution"
     );
 
-    // 1. Resolve places (location clustering)
-    let places_resolved = places::resolve_places(db, window).await?;
-
-    // 2. Resolve people (calendar attendees)
-    let people_resolved = people::resolve_people(db, window).await?;
+    let (places, people) = tokio::try_join!(
+        run_stage_if(db, window, ResolutionStage::Places, stages.places),
+        run_stage_if(db, window, ResolutionStage::People, stages.people),
+    )?;
+    let (places_resolved, places_duration_ms) = places;
+    let (people_resolved, people_duration_ms) = people;
 
     let duration_ms = start.elapsed().as_millis();
 
     tracing::info!(
         places_resolved,
         people_resolved,
+        places_duration_ms,
+        people_duration_ms,
         duration_ms,
         "Entity resolution completed"
     );
@@ -87,5 +184,57 @@ pub async fn resolve_entities(db: &Database, window: TimeWindow) -> Result<Resol
         places_resolved,
         people_resolved,
         duration_ms,
+        places_duration_ms,
+        people_duration_ms,
     })
 }
+
+/// Run a single resolution stage, e.g. to redo people after fixing a bug
+/// without re-clustering locations
+///
+/// Returns the number of entities the stage resolved.
+pub async fn resolve_stage(
+    db: &Database,
+    stage: ResolutionStage,
+    window: TimeWindow,
+) -> Result<usize> {
+    match stage {
+        ResolutionStage::Places => places::resolve_places(db, window).await,
+        ResolutionStage::People => people::resolve_people(db, window).await,
+    }
+}
+
+/// Run a stage when enabled, returning its count and duration
+async fn run_stage_if(
+    db: &Database,
+    window: TimeWindow,
+    stage: ResolutionStage,
+    enabled: bool,
+) -> Result<(usize, u128)> {
+    if !enabled {
+        return Ok((0, 0));
+    }
+    let start = std::time::Instant::now();
+    let resolved = resolve_stage(db, stage, window).await?;
+    Ok((resolved, start.elapsed().as_millis()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_names_round_trip() {
+        for stage in ResolutionStage::ALL {
+            assert_eq!(stage.as_str().parse::<ResolutionStage>().unwrap(), stage);
+        }
+        assert!("synthesis".parse::<ResolutionStage>().is_err());
+    }
+
+    #[test]
+    fn test_only_enables_one_stage() {
+        let stages = ResolutionStages::only(ResolutionStage::People);
+        assert!(!stages.places);
+        assert!(stages.people);
+    }
+}
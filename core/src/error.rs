@@ -29,6 +29,10 @@ pub enum Error {
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
 
+    /// Credentials the provider no longer accepts; the user must reconnect
+    #[error("Token expired: {0}")]
+    TokenExpired(String),
+
     /// Not found errors
     #[error("Not found: {0}")]
     NotFound(String),
@@ -49,6 +53,22 @@ pub enum Error {
     #[error("Network error: {0}")]
     Network(String),
 
+    /// The provider asked us to slow down for longer than we retry for
+    #[error("Rate limited: {message}")]
+    RateLimited {
+        message: String,
+        /// How long the provider last asked us to wait, when it said
+        retry_after: Option<std::time::Duration>,
+    },
+
+    /// A service we write to (storage) kept failing through our retries
+    #[error("Provider unavailable: {0}")]
+    ProviderUnavailable(String),
+
+    /// Load shedding (bounded queue full) - retry later
+    #[error("Overloaded: {0}")]
+    Overloaded(String),
+
     /// Serialization errors
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
@@ -73,6 +93,14 @@ pub enum Error {
     #[error("Missing device ID in payload")]
     MissingDeviceId,
 
+    /// A sync for the same stream is already queued or running
+    #[error("Sync already in progress: {0}")]
+    SyncInProgress(String),
+
+    /// The resource changed since the version the client last read
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
     /// Empty payload in push stream
     #[error("Empty payload - no records to ingest")]
     EmptyPayload,
@@ -86,10 +114,11 @@ impl Error {
     /// Get HTTP status code for this error
     pub fn http_status(&self) -> u16 {
         match self {
-            Error::Authentication(_) | Error::Unauthorized(_) => 401,
+            Error::Authentication(_) | Error::Unauthorized(_) | Error::TokenExpired(_) => 401,
             Error::NotFound(_) => 404,
             Error::InvalidInput(_) => 400,
-            Error::Configuration(_) => 503,
+            Error::SyncInProgress(_) | Error::Conflict(_) => 409,
+            Error::Configuration(_) | Error::Overloaded(_) | Error::ProviderUnavailable(_) => 503,
             _ => 500,
         }
     }
@@ -101,6 +130,7 @@ impl Error {
             self,
             Error::Database(_)
                 | Error::Storage(_)
+                | Error::ProviderUnavailable(_)
                 | Error::Configuration(_)
                 | Error::Network(_)
                 | Error::Sql(_)
@@ -0,0 +1,659 @@
+//! Partitioned archival of synced records
+//!
+//! A sync's records are split into one JSONL object per partition, using the
+//! stream's registered `partition_key` field and its configured
+//! `PartitionGranularity` (daily unless changed). Partitions are uploaded
+//! concurrently, bounded by `ARCHIVE_UPLOAD_CONCURRENCY` (default 4).
+//!
+//! Archiving is all-or-nothing: if any partition fails, the partitions that did
+//! upload are removed again and nothing is indexed, so the caller can leave
+//! the stream's cursor where it was. Only when every upload succeeded are the
+//! `elt_stream_objects` rows written, in the caller's transaction, alongside
+//! the cursor update. A failed upload is retried from the records still in
+//! memory, with backoff (`STORAGE_UPLOAD_RETRIES`, default 2), before the
+//! partition counts as failed.
+//!
+//! A full refresh that lists a stream from start to end in one run replaces
+//! the stream's archive instead of adding to it, for streams registered with
+//! `replace_on_full_refresh`. Its partitions are uploaded under a staging
+//! prefix (`staging/<job id>/`) that no reader lists, copied to their live
+//! keys once every upload landed, and swapped in with one transaction that
+//! drops the old `elt_stream_objects` rows and indexes the new ones. Readers
+//! go through that index, so they see either the complete old archive or the
+//! complete new one; the replaced objects and the staged copies are deleted
+//! after the swap.
+//!
+//! Device-pushed records aren't archived per request: they stay in the
+//! `StreamWriter` until the stream's buffer is older than
+//! `ARCHIVE_FLUSH_INTERVAL_SECS` or larger than `ARCHIVE_FLUSH_BYTES`
+//! (see `ArchiveFlushConfig`), whichever comes first.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::Semaphore;
+
+use crate::error::{Error, Result};
+use crate::storage::models::{PartitionGranularity, StreamKey};
+use crate::storage::{Storage, UploadRetry};
+
+/// Default number of partition uploads in flight per sync
+const DEFAULT_UPLOAD_CONCURRENCY: usize = 4;
+
+/// Partition uploads allowed in flight, from `ARCHIVE_UPLOAD_CONCURRENCY`
+pub fn upload_concurrency() -> usize {
+    std::env::var("ARCHIVE_UPLOAD_CONCURRENCY")
+        .ok()
+        .and_then(|s| s.parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_UPLOAD_CONCURRENCY)
+}
+
+/// Default age of buffered ingest records before they're flushed
+const DEFAULT_FLUSH_INTERVAL_SECS: u64 = 60;
+
+/// Default size of buffered ingest records that triggers a flush
+const DEFAULT_FLUSH_BYTES: usize = 8 * 1024 * 1024;
+
+/// When buffered ingest records are flushed to the archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveFlushConfig {
+    /// Longest a stream's records wait in memory
+    pub interval: Duration,
+    /// Buffered size at which a stream flushes right away
+    pub max_bytes: usize,
+}
+
+impl Default for ArchiveFlushConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(DEFAULT_FLUSH_INTERVAL_SECS),
+            max_bytes: DEFAULT_FLUSH_BYTES,
+        }
+    }
+}
+
+impl ArchiveFlushConfig {
+    /// Load from `ARCHIVE_FLUSH_INTERVAL_SECS` and `ARCHIVE_FLUSH_BYTES`
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let interval = std::env::var("ARCHIVE_FLUSH_INTERVAL_SECS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(defaults.interval);
+        let max_bytes = std::env::var("ARCHIVE_FLUSH_BYTES")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(defaults.max_bytes);
+        Self {
+            interval,
+            max_bytes,
+        }
+    }
+
+    /// Whether a buffer of `bytes`, first written `age` ago, should flush now
+    pub fn is_due(&self, bytes: usize, age: Duration) -> bool {
+        bytes >= self.max_bytes || age >= self.interval
+    }
+}
+
+/// Records for one partition
+#[derive(Debug, Clone, Default)]
+pub struct Partition {
+    pub records: Vec<Value>,
+    pub min_timestamp: Option<DateTime<Utc>>,
+    pub max_timestamp: Option<DateTime<Utc>>,
+}
+
+/// Result of uploading one partition, as reported in the job metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PartitionUpload {
+    /// Start of the partition
+    pub partition: NaiveDateTime,
+    pub storage_key: String,
+    pub record_count: usize,
+    pub size_bytes: i64,
+    pub succeeded: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Where a full refresh staged the partition until it's published to
+    /// `storage_key`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub staged_key: Option<String>,
+}
+
+impl PartitionUpload {
+    /// Key the partition's bytes were uploaded to
+    pub fn uploaded_key(&self) -> &str {
+        self.staged_key.as_deref().unwrap_or(&self.storage_key)
+    }
+}
+
+/// Read a record's timestamp, accepting RFC 3339 timestamps and plain dates
+pub fn record_timestamp(record: &Value, key: &str) -> Option<DateTime<Utc>> {
+    let value = record.get(key)?.as_str()?;
+    if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+        return Some(ts.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc())
+}
+
+/// Split records into partitions by `partition_key`
+///
+/// Partitions are keyed by their start. Records without a readable timestamp
+/// (or every record, when the stream has no partition key) go to the partition
+/// containing `fallback`, with the sync's overall time range.
+pub fn partition_records(
+    records: &[Value],
+    partition_key: Option<&str>,
+    granularity: PartitionGranularity,
+    fallback: DateTime<Utc>,
+    fallback_range: (Option<DateTime<Utc>>, Option<DateTime<Utc>>),
+) -> BTreeMap<NaiveDateTime, Partition> {
+    let mut partitions: BTreeMap<NaiveDateTime, Partition> = BTreeMap::new();
+
+    for record in records {
+        let timestamp = partition_key.and_then(|key| record_timestamp(record, key));
+        let start = granularity.partition_start(timestamp.unwrap_or(fallback).naive_utc());
+        let partition = partitions.entry(start).or_default();
+        partition.records.push(record.clone());
+
+        let (min, max) = match timestamp {
+            Some(ts) => (Some(ts), Some(ts)),
+            None => fallback_range,
+        };
+        partition.min_timestamp = min_opt(partition.min_timestamp, min);
+        partition.max_timestamp = max_opt(partition.max_timestamp, max);
+    }
+
+    partitions
+}
+
+fn min_opt(a: Option<DateTime<Utc>>, b: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+fn max_opt(a: Option<DateTime<Utc>>, b: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+fn to_jsonl(records: &[Value]) -> Result<Vec<u8>> {
+    let mut jsonl = Vec::new();
+    for record in records {
+        serde_json::to_writer(&mut jsonl, record)?;
+        jsonl.push(b'\n');
+    }
+    Ok(jsonl)
+}
+
+/// Upload every partition concurrently
+///
+/// Returns one outcome per partition, in time order. If any upload failed,
+/// the partitions that succeeded are deleted again (best effort), so a failed
+/// run leaves nothing behind.
+pub async fn upload_partitions(
+    storage: &Storage,
+    provider: &str,
+    source_id: &str,
+    stream_name: &str,
+    granularity: PartitionGranularity,
+    partitions: &BTreeMap<NaiveDateTime, Partition>,
+    concurrency: usize,
+) -> Vec<PartitionUpload> {
+    let keys = partition_keys(provider, source_id, stream_name, granularity, partitions)
+        .into_iter()
+        .map(|key| (key, None))
+        .collect();
+    upload_to_keys(storage, partitions, keys, concurrency).await
+}
+
+/// Prefix a full refresh stages its partitions under until the swap
+pub fn staging_prefix(job_id: &str) -> String {
+    format!("staging/{job_id}/")
+}
+
+/// Upload every partition under `staging_prefix`, for a full refresh swap
+///
+/// Like `upload_partitions`, but nothing appears under the stream's live
+/// prefix until `publish_staged` copies the partitions over.
+pub async fn upload_staged_partitions(
+    storage: &Storage,
+    staging_prefix: &str,
+    provider: &str,
+    source_id: &str,
+    stream_name: &str,
+    granularity: PartitionGranularity,
+    partitions: &BTreeMap<NaiveDateTime, Partition>,
+) -> Vec<PartitionUpload> {
+    let keys = partition_keys(provider, source_id, stream_name, granularity, partitions)
+        .into_iter()
+        .map(|key| (key.clone(), Some(format!("{staging_prefix}{key}"))))
+        .collect();
+    upload_to_keys(storage, partitions, keys, upload_concurrency()).await
+}
+
+/// Live key of each partition, in time order
+fn partition_keys(
+    provider: &str,
+    source_id: &str,
+    stream_name: &str,
+    granularity: PartitionGranularity,
+    partitions: &BTreeMap<NaiveDateTime, Partition>,
+) -> Vec<String> {
+    let seq = next_seq();
+    partitions
+        .keys()
+        .map(|start| {
+            StreamKey::build_partitioned(provider, source_id, stream_name, granularity, *start, seq)
+        })
+        .collect()
+}
+
+/// Sequence number for a run's object keys
+///
+/// Microseconds since the epoch, bumped past the previous run's so two runs
+/// of a stream in the same instant never write to the same key.
+fn next_seq() -> i64 {
+    static LAST: AtomicI64 = AtomicI64::new(0);
+    let now = Utc::now().timestamp_micros();
+    let previous = LAST
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+            Some(now.max(last + 1))
+        })
+        .unwrap_or(now);
+    now.max(previous + 1)
+}
+
+/// Upload each partition to its `(storage_key, staged_key)` target
+async fn upload_to_keys(
+    storage: &Storage,
+    partitions: &BTreeMap<NaiveDateTime, Partition>,
+    keys: Vec<(String, Option<String>)>,
+    concurrency: usize,
+) -> Vec<PartitionUpload> {
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let retry = UploadRetry::from_env();
+
+    let targets = partitions.iter().zip(keys);
+
+    let uploads = targets.map(|((start, partition), (storage_key, staged_key))| {
+        let semaphore = semaphore.clone();
+        async move {
+            let mut upload = PartitionUpload {
+                partition: *start,
+                storage_key,
+                record_count: partition.records.len(),
+                size_bytes: 0,
+                succeeded: false,
+                error: None,
+                staged_key,
+            };
+
+            let result = async {
+                let _permit = semaphore
+                    .acquire()
+                    .await
+                    .map_err(|e| Error::Other(format!("Upload semaphore closed: {e}")))?;
+                let jsonl = to_jsonl(&partition.records)?;
+                upload.size_bytes = jsonl.len() as i64;
+                storage
+                    .upload_with_retry(upload.uploaded_key(), jsonl, retry)
+                    .await
+            }
+            .await;
+
+            match result {
+                Ok(()) => upload.succeeded = true,
+                Err(e) => upload.error = Some(e.to_string()),
+            }
+            upload
+        }
+    });
+    let outcomes = futures::future::join_all(uploads).await;
+
+    if outcomes.iter().any(|u| !u.succeeded) {
+        for upload in outcomes.iter().filter(|u| u.succeeded) {
+            if let Err(e) = storage.delete(upload.uploaded_key()).await {
+                tracing::warn!(
+                    storage_key = %upload.uploaded_key(),
+                    error = %e,
+                    "Failed to remove partition of a failed archive run"
+                );
+            }
+        }
+    }
+
+    outcomes
+}
+
+/// Copy staged partitions to their live keys
+///
+/// Uploads that weren't staged are left alone. If a copy fails, the copies
+/// already made are removed again (best effort) and the staged objects are
+/// kept for the caller to discard.
+pub async fn publish_staged(storage: &Storage, uploads: &[PartitionUpload]) -> Result<()> {
+    let mut published = Vec::new();
+    for upload in uploads {
+        let Some(staged_key) = &upload.staged_key else {
+            continue;
+        };
+        if let Err(e) = storage.copy(staged_key, &upload.storage_key).await {
+            discard_objects(storage, &published).await;
+            return Err(e);
+        }
+        published.push(upload.storage_key.clone());
+    }
+    Ok(())
+}
+
+/// Delete objects an archive run no longer needs, logging failures
+pub async fn discard_objects(storage: &Storage, keys: &[String]) {
+    if keys.is_empty() {
+        return;
+    }
+    if let Err(e) = storage.delete_batch(keys).await {
+        tracing::warn!(
+            objects = keys.len(),
+            error = %e,
+            "Failed to delete some objects left over from an archive run"
+        );
+    }
+}
+
+/// Index successfully uploaded partitions in `elt_stream_objects`
+pub async fn record_partitions(
+    conn: &mut sqlx::SqliteConnection,
+    source_id: &str,
+    stream_name: &str,
+    partitions: &BTreeMap<NaiveDateTime, Partition>,
+    uploads: &[PartitionUpload],
+) -> Result<()> {
+    for upload in uploads {
+        let partition = &partitions[&upload.partition];
+        let stream_object_id =
+            crate::ids::generate_id(crate::ids::STREAM_OBJECT_PREFIX, &[&upload.storage_key]);
+        sqlx::query(
+            "INSERT INTO elt_stream_objects
+             (id, source_connection_id, stream_name, storage_key, record_count, size_bytes,
+              min_timestamp, max_timestamp, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, datetime('now'))",
+        )
+        .bind(&stream_object_id)
+        .bind(source_id)
+        .bind(stream_name)
+        .bind(&upload.storage_key)
+        .bind(upload.record_count as i32)
+        .bind(upload.size_bytes)
+        .bind(partition.min_timestamp)
+        .bind(partition.max_timestamp)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Drop a stream's indexed objects ahead of a full refresh swap
+///
+/// Removes the `elt_stream_objects` rows and the archived record ids (the new
+/// archive indexes its own), returning the storage keys of the replaced
+/// objects so they can be deleted once the swap is committed.
+pub async fn clear_stream_objects(
+    conn: &mut sqlx::SqliteConnection,
+    source_id: &str,
+    stream_name: &str,
+) -> Result<Vec<String>> {
+    let keys: Vec<String> = sqlx::query_scalar(
+        "DELETE FROM elt_stream_objects
+         WHERE source_connection_id = $1 AND stream_name = $2
+         RETURNING storage_key",
+    )
+    .bind(source_id)
+    .bind(stream_name)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        "DELETE FROM elt_stream_record_ids WHERE source_connection_id = $1 AND stream_name = $2",
+    )
+    .bind(source_id)
+    .bind(stream_name)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(keys)
+}
+
+/// A stream's configured partition granularity (daily if the stream has no row)
+pub async fn stream_granularity(
+    db: &sqlx::SqlitePool,
+    source_id: &str,
+    stream_name: &str,
+) -> Result<PartitionGranularity> {
+    let granularity: Option<PartitionGranularity> = sqlx::query_scalar(
+        "SELECT partition_granularity FROM elt_stream_connections
+         WHERE source_connection_id = $1 AND stream_name = $2",
+    )
+    .bind(source_id)
+    .bind(stream_name)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(granularity.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    /// Start of the daily partition of `s`
+    fn day(s: &str) -> NaiveDateTime {
+        date(s).and_hms_opt(0, 0, 0).unwrap()
+    }
+
+    fn fallback() -> DateTime<Utc> {
+        day("2025-02-01").and_utc()
+    }
+
+    #[test]
+    fn test_flush_is_due_on_either_threshold() {
+        let config = ArchiveFlushConfig {
+            interval: Duration::from_secs(60),
+            max_bytes: 1024,
+        };
+        assert!(!config.is_due(100, Duration::from_secs(10)));
+        assert!(config.is_due(1024, Duration::from_secs(10)));
+        assert!(config.is_due(100, Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_seq_unique_within_an_instant() {
+        let seqs: Vec<i64> = (0..100).map(|_| next_seq()).collect();
+        assert!(seqs.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_partition_records_by_date() {
+        let records = vec![
+            json!({"id": 1, "start_time": "2025-01-15T10:00:00Z"}),
+            json!({"id": 2, "start_time": "2025-01-14T23:30:00-02:00"}),
+            json!({"id": 3, "start_time": "2025-01-16"}),
+            json!({"id": 4, "start_time": "2025-01-15T08:00:00Z"}),
+            json!({"id": 5}),
+        ];
+
+        let partitions = partition_records(
+            &records,
+            Some("start_time"),
+            PartitionGranularity::Daily,
+            fallback(),
+            (None, None),
+        );
+        let starts: Vec<NaiveDateTime> = partitions.keys().copied().collect();
+        assert_eq!(
+            starts,
+            vec![day("2025-01-15"), day("2025-01-16"), day("2025-02-01")]
+        );
+
+        let jan_15 = &partitions[&day("2025-01-15")];
+        assert_eq!(jan_15.records.len(), 3);
+        assert_eq!(
+            jan_15.min_timestamp.unwrap().to_rfc3339(),
+            "2025-01-15T01:30:00+00:00"
+        );
+        assert_eq!(
+            jan_15.max_timestamp.unwrap().to_rfc3339(),
+            "2025-01-15T10:00:00+00:00"
+        );
+        assert_eq!(
+            partitions[&day("2025-02-01")].records,
+            vec![json!({"id": 5})]
+        );
+
+        // Without a partition key everything lands on the fallback date
+        let unkeyed = partition_records(
+            &records,
+            None,
+            PartitionGranularity::Daily,
+            fallback(),
+            (None, None),
+        );
+        assert_eq!(unkeyed.len(), 1);
+        assert_eq!(unkeyed[&day("2025-02-01")].records.len(), 5);
+    }
+
+    #[test]
+    fn test_partition_records_by_granularity() {
+        let records = vec![
+            json!({"start_time": "2025-01-15T10:05:00Z"}),
+            json!({"start_time": "2025-01-15T10:55:00Z"}),
+            json!({"start_time": "2025-01-15T11:00:00Z"}),
+            json!({"start_time": "2025-01-31T23:00:00Z"}),
+        ];
+        let partition = |granularity| {
+            partition_records(
+                &records,
+                Some("start_time"),
+                granularity,
+                fallback(),
+                (None, None),
+            )
+        };
+
+        let hourly = partition(PartitionGranularity::Hourly);
+        assert_eq!(hourly.len(), 3);
+        let ten = date("2025-01-15").and_hms_opt(10, 0, 0).unwrap();
+        assert_eq!(hourly[&ten].records.len(), 2);
+
+        let monthly = partition(PartitionGranularity::Monthly);
+        assert_eq!(monthly.len(), 1);
+        assert_eq!(monthly[&day("2025-01-01")].records.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_upload_partitions_concurrently() {
+        let storage = Storage::in_memory();
+        let records: Vec<Value> = (1..=20)
+            .map(|day| json!({"start_time": format!("2025-01-{:02}T12:00:00Z", day)}))
+            .collect();
+        let partitions = partition_records(
+            &records,
+            Some("start_time"),
+            PartitionGranularity::Daily,
+            fallback(),
+            (None, None),
+        );
+
+        let uploads = upload_partitions(
+            &storage,
+            "google",
+            "source_1",
+            "calendar",
+            PartitionGranularity::Daily,
+            &partitions,
+            3,
+        )
+        .await;
+        assert_eq!(uploads.len(), 20);
+        assert!(uploads.iter().all(|u| u.succeeded && u.record_count == 1));
+        assert_eq!(uploads[0].partition, day("2025-01-01"));
+
+        let stored = storage
+            .list("streams/google/source_1/calendar/")
+            .await
+            .unwrap();
+        assert_eq!(stored.len(), 20);
+        let body = storage.download(&uploads[0].storage_key).await.unwrap();
+        assert_eq!(body.len() as i64, uploads[0].size_bytes);
+    }
+
+    #[tokio::test]
+    async fn test_failed_partition_rolls_back_the_run() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = Storage::file(temp_dir.path().to_str().unwrap().to_string()).unwrap();
+        storage.initialize().await.unwrap();
+
+        // A file where the 2025-01-02 partition directory should go makes
+        // that one upload fail
+        let blocker = "streams/google/source_1/calendar/date=2025-01-02";
+        storage.upload(blocker, Vec::new()).await.unwrap();
+
+        let records: Vec<Value> = (1..=3)
+            .map(|day| json!({"start_time": format!("2025-01-{:02}T12:00:00Z", day)}))
+            .collect();
+        let partitions = partition_records(
+            &records,
+            Some("start_time"),
+            PartitionGranularity::Daily,
+            fallback(),
+            (None, None),
+        );
+
+        let uploads = upload_partitions(
+            &storage,
+            "google",
+            "source_1",
+            "calendar",
+            PartitionGranularity::Daily,
+            &partitions,
+            2,
+        )
+        .await;
+        let failed: Vec<NaiveDateTime> = uploads
+            .iter()
+            .filter(|u| !u.succeeded)
+            .map(|u| u.partition)
+            .collect();
+        assert_eq!(failed, vec![day("2025-01-02")]);
+        assert!(uploads[1].error.is_some());
+
+        // The partitions that did upload were removed again
+        let remaining = storage
+            .plan_delete_prefix("streams/google/source_1/")
+            .await
+            .unwrap();
+        let keys: Vec<&str> = remaining.objects.iter().map(|o| o.key.as_str()).collect();
+        assert_eq!(keys, vec![blocker]);
+    }
+}
@@ -0,0 +1,195 @@
+//! Cost accounting for jobs
+//!
+//! Opt-in with `JOB_COST_ACCOUNTING=true`. Transform and pipeline jobs then
+//! meter what they spend (seconds of audio transcribed, LLM tokens, texts
+//! embedded) and add it to their own `cost` and to that of every job they
+//! descend from: the sync whose records a transform transformed and, for
+//! chained transforms, the transforms before it. A sync's `cost` is what that
+//! sync led to being spent, which Tollbooth's usage pricing turns into money.
+//!
+//! Enabling it links sync-triggered transform jobs to their sync through
+//! `parent_job_id`, which is otherwise left unset for them.
+//!
+//! The LLM client and the embedder report what they spend themselves, as do
+//! callers that reach Tollbooth directly (with [`record_llm_usage`] and
+//! [`record_transcription_seconds`]); outside a metered job these do nothing.
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use crate::error::Result;
+
+tokio::task_local! {
+    static CURRENT_COST: Arc<Mutex<JobCost>>;
+}
+
+/// Billed usage attributed to a job
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct JobCost {
+    /// Seconds of audio sent for transcription
+    #[serde(default)]
+    pub transcription_seconds: f64,
+    /// Prompt tokens sent to LLMs
+    #[serde(default)]
+    pub llm_input_tokens: u64,
+    /// Completion tokens received from LLMs
+    #[serde(default)]
+    pub llm_output_tokens: u64,
+    /// Texts embedded for search
+    #[serde(default)]
+    pub embedded_texts: u64,
+}
+
+impl JobCost {
+    /// Whether nothing was spent
+    pub fn is_zero(&self) -> bool {
+        *self == JobCost::default()
+    }
+}
+
+/// Whether `JOB_COST_ACCOUNTING` is on
+pub fn enabled() -> bool {
+    matches!(
+        std::env::var("JOB_COST_ACCOUNTING").as_deref(),
+        Ok("1") | Ok("true")
+    )
+}
+
+/// Run a future, returning what it reported spending alongside its output
+pub async fn metered<F: Future>(future: F) -> (F::Output, JobCost) {
+    let cost = Arc::new(Mutex::new(JobCost::default()));
+    let output = CURRENT_COST.scope(cost.clone(), future).await;
+    let spent = *cost.lock().unwrap_or_else(|e| e.into_inner());
+    (output, spent)
+}
+
+fn record(f: impl FnOnce(&mut JobCost)) {
+    let _ = CURRENT_COST.try_with(|cost| {
+        let mut cost = cost.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut cost)
+    });
+}
+
+/// Report LLM tokens used by the current job
+pub fn record_llm_tokens(input_tokens: u64, output_tokens: u64) {
+    record(|cost| {
+        cost.llm_input_tokens += input_tokens;
+        cost.llm_output_tokens += output_tokens;
+    });
+}
+
+/// Report the `usage` of an OpenAI-style chat completion response
+pub fn record_llm_usage(response: &serde_json::Value) {
+    let tokens = |key: &str| response["usage"][key].as_u64().unwrap_or(0);
+    record_llm_tokens(tokens("prompt_tokens"), tokens("completion_tokens"));
+}
+
+/// Report texts embedded by the current job
+pub fn record_embedded_texts(texts: u64) {
+    record(|cost| cost.embedded_texts += texts);
+}
+
+/// Report audio sent for transcription by the current job
+pub fn record_transcription_seconds(seconds: f64) {
+    record(|cost| cost.transcription_seconds += seconds);
+}
+
+/// Add `cost` to a job and to every job it descends from
+pub async fn add_to_job(db: &SqlitePool, job_id: &str, cost: &JobCost) -> Result<()> {
+    sqlx::query(
+        r#"
+        WITH RECURSIVE lineage(id) AS (
+            SELECT $1
+            UNION
+            SELECT j.parent_job_id FROM elt_jobs j
+            JOIN lineage l ON j.id = l.id
+            WHERE j.parent_job_id IS NOT NULL
+        )
+        UPDATE elt_jobs
+        SET cost = json_object(
+            'transcription_seconds',
+                COALESCE(json_extract(cost, '$.transcription_seconds'), 0) + $2,
+            'llm_input_tokens', COALESCE(json_extract(cost, '$.llm_input_tokens'), 0) + $3,
+            'llm_output_tokens', COALESCE(json_extract(cost, '$.llm_output_tokens'), 0) + $4,
+            'embedded_texts', COALESCE(json_extract(cost, '$.embedded_texts'), 0) + $5
+        )
+        WHERE id IN (SELECT id FROM lineage)
+        "#,
+    )
+    .bind(job_id)
+    .bind(cost.transcription_seconds)
+    .bind(cost.llm_input_tokens as i64)
+    .bind(cost.llm_output_tokens as i64)
+    .bind(cost.embedded_texts as i64)
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::{create_job, get_job, CreateJobRequest, SyncJobMetadata};
+    use crate::sources::base::mock_transport::testing::migrated_pool;
+
+    #[tokio::test]
+    async fn test_transform_cost_rolls_up_to_its_sync() {
+        let pool = migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, name) VALUES ('src-ios', 'ios', 'iPhone')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let sync = create_job(
+            &pool,
+            CreateJobRequest::new_sync_job(
+                "src-ios".to_string(),
+                "microphone".to_string(),
+                "incremental".to_string(),
+                SyncJobMetadata {
+                    sync_mode: "incremental".to_string(),
+                    cursor_before: None,
+                    start_date: None,
+                    end_date: None,
+                },
+            ),
+        )
+        .await
+        .unwrap();
+        let mut transform =
+            CreateJobRequest::new_transform_job("mic".to_string(), "direct".to_string());
+        transform.parent_job_id = Some(sync.id.clone());
+        let transform = create_job(&pool, transform).await.unwrap();
+        assert_eq!(sync.cost, None);
+
+        let ((), spent) = metered(async {
+            record_transcription_seconds(30.5);
+            record_llm_usage(&serde_json::json!({
+                "usage": { "prompt_tokens": 1200, "completion_tokens": 300 }
+            }));
+            record_embedded_texts(4);
+        })
+        .await;
+        // Outside a metered job nothing is recorded
+        record_llm_tokens(1, 1);
+
+        add_to_job(&pool, &transform.id, &spent).await.unwrap();
+        add_to_job(&pool, &transform.id, &spent).await.unwrap();
+
+        let expected = JobCost {
+            transcription_seconds: 61.0,
+            llm_input_tokens: 2400,
+            llm_output_tokens: 600,
+            embedded_texts: 8,
+        };
+        assert_eq!(
+            get_job(&pool, &transform.id).await.unwrap().cost,
+            Some(expected)
+        );
+        assert_eq!(get_job(&pool, &sync.id).await.unwrap().cost, Some(expected));
+    }
+}
@@ -0,0 +1,267 @@
+//! Natural-id deduplication for archived stream records
+//!
+//! A backfill (or full re-sync) over a window the archive already covers
+//! fetches the same events again, and without a check they land in a second
+//! partition file. Streams that declare a `dedup_key` in the registry get
+//! their archived ids recorded in `elt_stream_record_ids`; before archiving a
+//! backfill or full refresh, records whose id is already indexed are dropped.
+//! A full refresh that runs to completion is the exception: it replaces the
+//! stream's archive (and its ids) outright, so it keeps every record.
+//!
+//! Incremental syncs are not filtered by the job: a record they return again
+//! is an update (a relabelled message, a moved event) and has to reach the
+//! transforms. Their ids are still indexed so a later backfill skips them.
+//! The exception is a stream that re-reads a window before its cursor to
+//! tolerate clock skew; it drops what that window returned again itself,
+//! via `skip_refetched`.
+
+use std::collections::HashSet;
+
+use serde_json::Value;
+use sqlx::SqlitePool;
+
+use crate::error::Result;
+use crate::sources::base::SyncMode;
+use crate::storage::stream_writer::RECORD_ID_FIELD;
+
+/// Ids looked up per query, well under SQLite's bound-parameter limit
+const LOOKUP_CHUNK: usize = 500;
+
+/// Whether records from a sync in this mode are filtered against the index
+pub fn applies_to(mode: &SyncMode) -> bool {
+    matches!(mode, SyncMode::Backfill { .. } | SyncMode::FullRefresh)
+}
+
+/// Read a record's natural id, accepting string or numeric ids
+pub fn natural_id(record: &Value, key: &str) -> Option<String> {
+    match record.get(key)? {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Drop records whose natural id is already archived for this stream
+///
+/// Repeats within `records` are dropped too, keeping the first occurrence.
+/// Records without the key are kept, since there is nothing to match on.
+/// Returns the remaining records and the number skipped.
+pub async fn skip_archived(
+    db: &SqlitePool,
+    source_id: &str,
+    stream_name: &str,
+    key: &str,
+    records: Vec<Value>,
+) -> Result<(Vec<Value>, usize)> {
+    let ids: Vec<String> = records
+        .iter()
+        .filter_map(|r| natural_id(r, key))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+
+    let mut seen: HashSet<String> = HashSet::new();
+    for chunk in ids.chunks(LOOKUP_CHUNK) {
+        let placeholders = vec!["?"; chunk.len()].join(", ");
+        let sql = format!(
+            "SELECT record_id FROM elt_stream_record_ids
+             WHERE source_connection_id = ? AND stream_name = ? AND record_id IN ({})",
+            placeholders
+        );
+
+        let mut query = sqlx::query_scalar::<_, String>(&sql)
+            .bind(source_id)
+            .bind(stream_name);
+        for id in chunk {
+            query = query.bind(id);
+        }
+        seen.extend(query.fetch_all(db).await?);
+    }
+
+    let total = records.len();
+    let kept: Vec<Value> = records
+        .into_iter()
+        .filter(|record| match natural_id(record, key) {
+            // `insert` is false for archived ids and for repeats in this batch
+            Some(id) => seen.insert(id),
+            None => true,
+        })
+        .collect();
+    let skipped = total - kept.len();
+
+    if skipped > 0 {
+        tracing::info!(
+            source_id,
+            stream_name,
+            skipped,
+            kept = kept.len(),
+            "Skipped records already in the archive"
+        );
+    }
+
+    Ok((kept, skipped))
+}
+
+/// Drop records an incremental run re-fetched from its cursor overlap window
+///
+/// Matches on the `_record_id` stamped by the stream writer, which is the
+/// stream's natural id when it registers a `dedup_key`. Returns the remaining
+/// records and the number skipped.
+pub async fn skip_refetched(
+    db: &SqlitePool,
+    source_id: &str,
+    stream_name: &str,
+    records: Option<Vec<Value>>,
+) -> Result<(Option<Vec<Value>>, usize)> {
+    let Some(records) = records else {
+        return Ok((None, 0));
+    };
+    let (kept, skipped) =
+        skip_archived(db, source_id, stream_name, RECORD_ID_FIELD, records).await?;
+    Ok((Some(kept).filter(|r| !r.is_empty()), skipped))
+}
+
+/// Record the natural ids of records that were just archived
+pub async fn index_archived(
+    db: &SqlitePool,
+    source_id: &str,
+    stream_name: &str,
+    key: &str,
+    records: &[Value],
+) -> Result<()> {
+    let mut tx = db.begin().await?;
+    for id in records.iter().filter_map(|r| natural_id(r, key)) {
+        sqlx::query(
+            "INSERT OR IGNORE INTO elt_stream_record_ids (source_connection_id, stream_name, record_id)
+             VALUES ($1, $2, $3)",
+        )
+        .bind(source_id)
+        .bind(stream_name)
+        .bind(&id)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+
+    async fn test_pool() -> SqlitePool {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE elt_stream_record_ids (
+                source_connection_id TEXT NOT NULL,
+                stream_name TEXT NOT NULL,
+                record_id TEXT NOT NULL,
+                PRIMARY KEY (source_connection_id, stream_name, record_id)
+            ) WITHOUT ROWID",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_backfill_skips_archived_records() {
+        let pool = test_pool().await;
+        let first = vec![json!({ "message_id": "a" }), json!({ "message_id": "b" })];
+        index_archived(&pool, "source", "gmail", "message_id", &first)
+            .await
+            .unwrap();
+
+        let overlap = vec![
+            json!({ "message_id": "b" }),
+            json!({ "message_id": "c" }),
+            json!({ "message_id": "c" }),
+            json!({ "subject": "no id" }),
+        ];
+        let (kept, skipped) = skip_archived(&pool, "source", "gmail", "message_id", overlap)
+            .await
+            .unwrap();
+
+        assert_eq!(skipped, 2);
+        assert_eq!(
+            kept,
+            vec![json!({ "message_id": "c" }), json!({ "subject": "no id" })]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refetched_overlap_matches_record_id() {
+        let pool = test_pool().await;
+        let archived = vec![json!({ "event_id": "1", "_record_id": "1" })];
+        index_archived(&pool, "source", "events", "event_id", &archived)
+            .await
+            .unwrap();
+
+        let refetched = vec![
+            json!({ "event_id": "1", "_record_id": "1" }),
+            json!({ "event_id": "2", "_record_id": "2" }),
+        ];
+        let (kept, skipped) = skip_refetched(&pool, "source", "events", Some(refetched))
+            .await
+            .unwrap();
+        assert_eq!(skipped, 1);
+        assert_eq!(kept.unwrap().len(), 1);
+
+        let only_repeats = vec![json!({ "event_id": "1", "_record_id": "1" })];
+        let (kept, skipped) = skip_refetched(&pool, "source", "events", Some(only_repeats))
+            .await
+            .unwrap();
+        assert_eq!((kept, skipped), (None, 1));
+    }
+
+    #[tokio::test]
+    async fn test_index_is_scoped_per_stream() {
+        let pool = test_pool().await;
+        let records = vec![json!({ "event_id": "x" })];
+        index_archived(&pool, "source", "calendar", "event_id", &records)
+            .await
+            .unwrap();
+
+        let (kept, skipped) = skip_archived(&pool, "source", "events", "event_id", records.clone())
+            .await
+            .unwrap();
+        assert_eq!((kept.len(), skipped), (1, 0));
+
+        let (kept, skipped) = skip_archived(&pool, "other", "calendar", "event_id", records)
+            .await
+            .unwrap();
+        assert_eq!((kept.len(), skipped), (1, 0));
+    }
+
+    #[tokio::test]
+    async fn test_numeric_ids_across_lookup_chunks() {
+        let pool = test_pool().await;
+        let records: Vec<Value> = (0..LOOKUP_CHUNK as i64 + 100)
+            .map(|id| json!({ "activity_id": id }))
+            .collect();
+        index_archived(&pool, "source", "activities", "activity_id", &records)
+            .await
+            .unwrap();
+
+        let (kept, skipped) = skip_archived(&pool, "source", "activities", "activity_id", records)
+            .await
+            .unwrap();
+        assert!(kept.is_empty());
+        assert_eq!(skipped, LOOKUP_CHUNK + 100);
+    }
+
+    #[test]
+    fn test_applies_to_backfill_and_full_refresh_only() {
+        assert!(applies_to(&SyncMode::full_refresh()));
+        assert!(applies_to(&SyncMode::backfill(Utc::now(), Utc::now())));
+        assert!(!applies_to(&SyncMode::incremental(None)));
+    }
+}
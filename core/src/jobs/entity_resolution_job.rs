@@ -17,7 +17,7 @@ use async_trait::async_trait;
 
 
 use crate::database::Database;
-use crate::entity_resolution::{self, TimeWindow};
+use crate::entity_resolution::{self, ResolutionStage, TimeWindow};
 use crate::error::Result;
 use crate::jobs::TransformContext;
 use crate::sources::base::{
@@ -25,7 +25,7 @@ use crate::sources::base::{
 };
 
 /// Default lookback window for entity resolution (24 hours)
-const DEFAULT_LOOKBACK_HOURS: i64 = 24;
+pub(crate) const DEFAULT_LOOKBACK_HOURS: i64 = 24;
 
 /// Place Resolution Transform
 ///
@@ -63,7 +63,8 @@ impl OntologyTransform for PlaceResolutionTransform {
             "Running place resolution transform"
         );
 
-        let visits_created = entity_resolution::places::resolve_places(db, window).await?;
+        let visits_created =
+            entity_resolution::resolve_stage(db, ResolutionStage::Places, window).await?;
 
         tracing::info!(visits_created, "Place resolution transform completed");
 
@@ -135,7 +136,8 @@ impl OntologyTransform for PeopleResolutionTransform {
             "Running people resolution transform"
         );
 
-        let people_resolved = entity_resolution::people::resolve_people(db, window).await?;
+        let people_resolved =
+            entity_resolution::resolve_stage(db, ResolutionStage::People, window).await?;
 
         tracing::info!(people_resolved, "People resolution transform completed");
 
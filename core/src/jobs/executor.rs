@@ -1,13 +1,94 @@
 //! Job executor for running async jobs in background tasks
 
 use crate::error::Result;
-use crate::jobs::models::{JobStatus, JobType};
-use crate::jobs::sync_job::execute_sync_job;
-use crate::jobs::transform_context::TransformContext;
+use crate::jobs::models::{Job, JobStatus, JobType};
+use crate::jobs::pipeline_job::execute_pipeline_job;
+use crate::jobs::sync_concurrency::sync_concurrency;
+use crate::jobs::sync_job::{execute_sync_job, record_stream_outcome};
+use crate::jobs::transform_context::{TransformConfig, TransformContext};
 use crate::jobs::transform_job::execute_transform_job;
+use crate::middleware::request_id;
 use crate::observability::JobTimer;
 use sqlx::SqlitePool;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use tokio::sync::{OwnedMutexGuard, Semaphore};
+use tracing::Instrument;
+
+static TRANSFORM_SEMAPHORE: OnceLock<Arc<Semaphore>> = OnceLock::new();
+
+type StreamKey = (String, String);
+
+type StreamLocks = HashMap<StreamKey, Arc<tokio::sync::Mutex<()>>>;
+
+static STREAM_SYNC_LOCKS: OnceLock<Mutex<StreamLocks>> = OnceLock::new();
+
+fn stream_sync_locks() -> MutexGuard<'static, StreamLocks> {
+    STREAM_SYNC_LOCKS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// A held stream sync lock; dropping it releases the lock and forgets the
+/// stream's entry once no other job is waiting on it
+pub struct StreamSyncGuard {
+    key: StreamKey,
+    guard: Option<OwnedMutexGuard<()>>,
+}
+
+impl Drop for StreamSyncGuard {
+    fn drop(&mut self) {
+        self.guard.take();
+        let mut locks = stream_sync_locks();
+        // Waiters clone the lock under the map's mutex, so only the map's own
+        // reference being left means nobody else wants it
+        if locks
+            .get(&self.key)
+            .is_some_and(|lock| Arc::strong_count(lock) == 1)
+        {
+            locks.remove(&self.key);
+        }
+    }
+}
+
+/// Process-wide limiter for concurrently running transform jobs
+///
+/// Sized from `TransformConfig::max_concurrency`, so a large sync that fans
+/// out into many transforms doesn't flood the database with writers.
+pub fn transform_semaphore() -> Arc<Semaphore> {
+    TRANSFORM_SEMAPHORE
+        .get_or_init(|| Arc::new(Semaphore::new(TransformConfig::from_env().max_concurrency)))
+        .clone()
+}
+
+/// Hold the sync lock for a (source, stream), waiting if another job has it
+///
+/// A manual full refresh and a scheduled incremental of the same stream
+/// would otherwise both read the cursor at start and race to save it. The
+/// later job stays `pending` until the earlier one finishes, then starts from
+/// the cursor it saved. The lock is per process; the trigger-time check in
+/// `trigger_stream_sync` keeps most duplicates from being queued at all.
+pub async fn lock_stream_sync(source_id: &str, stream_name: &str) -> StreamSyncGuard {
+    let key = (source_id.to_string(), stream_name.to_string());
+    let lock = stream_sync_locks().entry(key.clone()).or_default().clone();
+
+    let guard = match lock.clone().try_lock_owned() {
+        Ok(guard) => guard,
+        Err(_) => {
+            tracing::info!(
+                source_id,
+                stream_name,
+                "Sync already in progress for stream, waiting for it to finish"
+            );
+            lock.lock_owned().await
+        }
+    };
+    StreamSyncGuard {
+        key,
+        guard: Some(guard),
+    }
+}
 
 /// Job executor that spawns background tasks for async job execution
 #[derive(Clone)]
@@ -49,10 +130,65 @@ impl JobExecutor {
     }
 
     /// Internal method to run a job
+    ///
+    /// Runs inside a span carrying the job's id and request id, and with the
+    /// request id as the current one, so chained jobs inherit it.
     async fn run_job(db: &SqlitePool, context: &Arc<TransformContext>, job_id: &str) -> Result<()> {
         // Fetch the job
         let job = super::get_job(db, job_id).await?;
 
+        let span = tracing::info_span!(
+            "job",
+            job_id = %job.id,
+            request_id = tracing::field::Empty
+        );
+        if let Some(request_id) = &job.request_id {
+            span.record("request_id", request_id.as_str());
+        }
+
+        request_id::scope(
+            job.request_id.clone(),
+            Self::run_loaded_job(db, context, job_id, job),
+        )
+        .instrument(span)
+        .await
+    }
+
+    async fn run_loaded_job(
+        db: &SqlitePool,
+        context: &Arc<TransformContext>,
+        job_id: &str,
+        job: Job,
+    ) -> Result<()> {
+        // Sync jobs wait for their stream's lock, then sync and transform
+        // jobs wait for a concurrency permit, while still pending. The job is
+        // re-read afterwards since it may have been cancelled while queued.
+        let _stream_lock = match (&job.job_type, &job.source_connection_id, &job.stream_name) {
+            (JobType::Sync, Some(source_id), Some(stream_name)) => {
+                Some(lock_stream_sync(source_id, stream_name).await)
+            }
+            _ => None,
+        };
+        // Sync jobs beyond `SYNC_MAX_CONCURRENCY` stay `pending` until a permit
+        // frees up, however they were started
+        let _sync_permit = match job.job_type {
+            JobType::Sync => Some(sync_concurrency().acquire().await?),
+            _ => None,
+        };
+        let _transform_permit = match job.job_type {
+            JobType::Transform => {
+                Some(transform_semaphore().acquire_owned().await.map_err(|e| {
+                    crate::error::Error::Other(format!("transform semaphore closed: {e}"))
+                })?)
+            }
+            _ => None,
+        };
+        let job = if _sync_permit.is_some() || _transform_permit.is_some() {
+            super::get_job(db, job_id).await?
+        } else {
+            job
+        };
+
         // Check if job is in pending state
         if job.status != JobStatus::Pending {
             tracing::warn!(
@@ -97,8 +233,22 @@ impl JobExecutor {
                     "Archive job retry logic not yet implemented".to_string(),
                 ))
             }
+            JobType::Pipeline => execute_pipeline_job(db, &job).await,
         };
 
+        // Keep the stream's last error in line with its latest sync
+        if job.job_type == JobType::Sync {
+            if let (Some(source_id), Some(stream_name)) =
+                (&job.source_connection_id, &job.stream_name)
+            {
+                if let Err(e) =
+                    record_stream_outcome(db, source_id, stream_name, result.as_ref().err()).await
+                {
+                    tracing::warn!(job_id = %job.id, error = %e, "Failed to record stream outcome");
+                }
+            }
+        }
+
         // Record metrics and log result
         match &result {
             Ok(_) => {
@@ -121,3 +271,42 @@ impl JobExecutor {
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_stream_sync_lock_serializes_same_stream() {
+        let first = lock_stream_sync("source", "events").await;
+
+        // Another stream of the same source isn't blocked
+        let _other = lock_stream_sync("source", "repos").await;
+
+        let second = tokio::time::timeout(
+            Duration::from_millis(50),
+            lock_stream_sync("source", "events"),
+        )
+        .await;
+        assert!(second.is_err(), "second sync must wait for the first");
+
+        drop(first);
+        let second = tokio::time::timeout(
+            Duration::from_millis(50),
+            lock_stream_sync("source", "events"),
+        )
+        .await;
+        assert!(second.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_stream_sync_lock_forgotten_when_released() {
+        let key = ("source-pruned".to_string(), "events".to_string());
+        let first = lock_stream_sync("source-pruned", "events").await;
+        assert!(stream_sync_locks().contains_key(&key));
+
+        drop(first);
+        assert!(!stream_sync_locks().contains_key(&key));
+    }
+}
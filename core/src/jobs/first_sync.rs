@@ -0,0 +1,589 @@
+//! Bounded first syncs with a background backfill
+//!
+//! A stream's first sync (no cursor yet) would otherwise fetch all the history
+//! its config asks for in one run, which for a heavy account (a year of Gmail,
+//! a busy calendar) can run far longer than a sync should. Instead the first
+//! sync fetches only the last `first_sync_days` (see `StreamLimits`, default
+//! 30) and the older history is filled in afterwards by a chain of backfill
+//! jobs, one window of the same length at a time, newest first. Each window
+//! queues behind whatever sync of the stream is running and under the usual
+//! sync concurrency limit.
+//!
+//! Streams opt in by asking [`bound`] where a sync without a cursor should
+//! start; outside a first sync it hands their own start back unchanged. A
+//! stream whose API can't be bounded by time (Plaid's `/transactions/sync`)
+//! doesn't ask, and relies on the per-run record cap instead.
+//!
+//! Progress is kept on the stream connection as [`BackfillProgress`] and
+//! returned with the stream by the API. Backfill windows never move the
+//! stream's cursor. A window whose job fails is queued again after the
+//! stream's next successful sync. A window that stops at the record cap is
+//! queued again at half the length, so the rest of it isn't skipped. For a
+//! stream that keeps all history, the backfill stops after
+//! [`EMPTY_WINDOWS_BEFORE_COMPLETE`] windows in a row turn up no records.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::SqlitePool;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use crate::error::Result;
+use crate::jobs::{self, CreateJobRequest, Job, JobExecutor, JobStatus, SyncJobMetadata};
+use crate::sources::base::{
+    load_resume_cursor, stream_limits::DEFAULT_FIRST_SYNC_DAYS, StreamLimits, SyncMode,
+};
+
+/// Empty windows in a row that end the backfill of a stream keeping all history
+pub const EMPTY_WINDOWS_BEFORE_COMPLETE: u32 = 12;
+
+/// Shortest window a capped window is split down to
+const MIN_WINDOW_SECS: i64 = 60 * 60;
+
+/// `last_sync_token`, `last_sync_at` and `backfill_progress` of a stream
+type StreamSyncState = (
+    Option<String>,
+    Option<String>,
+    Option<Json<BackfillProgress>>,
+);
+
+tokio::task_local! {
+    static CURRENT_RUN: Arc<Mutex<FirstSyncRun>>;
+}
+
+/// How a sync was bounded
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FirstSyncRun {
+    /// Start of the first-sync window, when the sync was a first sync
+    pub window_start: Option<DateTime<Utc>>,
+    /// Whether the stream took the bound, leaving older history out
+    pub bounded: bool,
+    /// Where the stream would have started on its own (`None` = all history)
+    pub history_start: Option<DateTime<Utc>>,
+}
+
+/// Where a sync without a cursor should start fetching
+///
+/// `history_start` is where the stream would start on its own, `None` for
+/// all history. Inside a first sync anything older than the first-sync
+/// window is left to the background backfill.
+pub fn bound(history_start: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+    CURRENT_RUN
+        .try_with(|run| {
+            let mut run = run.lock().unwrap_or_else(|e| e.into_inner());
+            let window_start = run.window_start?;
+            if history_start.is_some_and(|start| start >= window_start) {
+                return history_start;
+            }
+            run.bounded = true;
+            run.history_start = history_start;
+            Some(window_start)
+        })
+        .unwrap_or(history_start)
+}
+
+/// Run a sync, bounded to `window_start` when it is a first sync
+pub async fn bounded<F: Future>(
+    window_start: Option<DateTime<Utc>>,
+    future: F,
+) -> (F::Output, FirstSyncRun) {
+    let run = Arc::new(Mutex::new(FirstSyncRun {
+        window_start,
+        ..Default::default()
+    }));
+    let output = CURRENT_RUN.scope(run.clone(), future).await;
+    let run = *run.lock().unwrap_or_else(|e| e.into_inner());
+    (output, run)
+}
+
+/// Stage of a stream's backfill
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackfillStatus {
+    /// The first sync stopped at the record cap and is still catching up
+    FirstSync,
+    /// Older history is being filled in
+    Backfilling,
+    /// All the history the stream is configured for has been fetched
+    Complete,
+}
+
+/// Progress of filling in the history a bounded first sync left out
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackfillProgress {
+    pub status: BackfillStatus,
+    /// Oldest history the stream fetches (`None` = all history)
+    pub history_start: Option<DateTime<Utc>>,
+    /// Everything from here to now has been fetched
+    pub filled_to: DateTime<Utc>,
+    /// Backfill windows finished so far
+    pub windows_done: u32,
+    /// Job filling the window before `filled_to`
+    pub job_id: Option<String>,
+    /// Windows in a row that turned up no records
+    #[serde(default)]
+    pub empty_windows: u32,
+    /// Length of the next window, when shortened after one hit the record cap
+    #[serde(default)]
+    pub window_secs: Option<i64>,
+}
+
+/// Load a stream's backfill progress, if its first sync was bounded
+pub async fn load_progress(
+    db: &SqlitePool,
+    source_id: &str,
+    stream_name: &str,
+) -> Result<Option<BackfillProgress>> {
+    let progress = sqlx::query_scalar::<_, Option<Json<BackfillProgress>>>(
+        "SELECT backfill_progress FROM elt_stream_connections WHERE source_connection_id = $1 AND stream_name = $2",
+    )
+    .bind(source_id)
+    .bind(stream_name)
+    .fetch_optional(db)
+    .await?
+    .flatten();
+
+    Ok(progress.map(|p| p.0))
+}
+
+async fn save_progress(
+    db: &SqlitePool,
+    source_id: &str,
+    stream_name: &str,
+    progress: &BackfillProgress,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE elt_stream_connections SET backfill_progress = $1, updated_at = datetime('now')
+         WHERE source_connection_id = $2 AND stream_name = $3",
+    )
+    .bind(Json(progress))
+    .bind(source_id)
+    .bind(stream_name)
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Start of the window to bound a sync to, if it is a first sync
+///
+/// Only incremental syncs without a cursor qualify: a stream's very first
+/// sync, and the runs finishing it after it stopped at the record cap.
+pub async fn window_start(
+    db: &SqlitePool,
+    source_id: &str,
+    stream_name: &str,
+    mode: &SyncMode,
+) -> Result<Option<DateTime<Utc>>> {
+    if !matches!(mode, SyncMode::Incremental { cursor: None }) {
+        return Ok(None);
+    }
+
+    let row: Option<StreamSyncState> = sqlx::query_as(
+        "SELECT last_sync_token, last_sync_at, backfill_progress FROM elt_stream_connections
+             WHERE source_connection_id = $1 AND stream_name = $2",
+    )
+    .bind(source_id)
+    .bind(stream_name)
+    .fetch_optional(db)
+    .await?;
+    let Some((token, synced_at, progress)) = row else {
+        return Ok(None);
+    };
+
+    match progress.map(|p| p.0) {
+        Some(progress) if progress.status == BackfillStatus::FirstSync => {
+            Ok(Some(progress.filled_to))
+        }
+        None if token.is_none() && synced_at.is_none() => {
+            let limits = StreamLimits::load(db, source_id, stream_name).await?;
+            Ok(limits.first_sync_window().map(|window| Utc::now() - window))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Whether `job_id` is filling one of the stream's backfill windows
+pub async fn is_backfill_window(
+    db: &SqlitePool,
+    source_id: &str,
+    stream_name: &str,
+    job_id: &str,
+) -> Result<bool> {
+    let progress = load_progress(db, source_id, stream_name).await?;
+    Ok(progress.is_some_and(|p| p.job_id.as_deref() == Some(job_id)))
+}
+
+/// Move the stream's backfill along after a successful sync
+///
+/// A bounded first sync that caught up starts the backfill; a finished
+/// window queues the next one, or completes the backfill; any other sync
+/// queues the current window again if its job failed.
+pub async fn advance(
+    db: &SqlitePool,
+    executor: &JobExecutor,
+    job: &Job,
+    run: FirstSyncRun,
+    records_fetched: usize,
+) -> Result<()> {
+    let (Some(source_id), Some(stream_name)) = (&job.source_connection_id, &job.stream_name) else {
+        return Ok(());
+    };
+
+    let mut progress = match (run.window_start, run.bounded) {
+        (Some(window_start), true) => {
+            let resuming = load_resume_cursor(db, source_id, stream_name)
+                .await?
+                .is_some();
+            BackfillProgress {
+                status: if resuming {
+                    BackfillStatus::FirstSync
+                } else {
+                    BackfillStatus::Backfilling
+                },
+                history_start: run.history_start,
+                filled_to: window_start,
+                windows_done: 0,
+                job_id: None,
+                empty_windows: 0,
+                window_secs: None,
+            }
+        }
+        _ => match load_progress(db, source_id, stream_name).await? {
+            Some(progress) if progress.status == BackfillStatus::Backfilling => progress,
+            _ => return Ok(()),
+        },
+    };
+
+    if progress.job_id.as_deref() == Some(job.id.as_str()) {
+        progress.job_id = None;
+        let start = job
+            .metadata
+            .get("start_date")
+            .and_then(|v| v.as_str())
+            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+        let limits = StreamLimits::load(db, source_id, stream_name).await?;
+        let window_secs = start.map(|start| (progress.filled_to - start).num_seconds());
+
+        match window_secs {
+            Some(secs) if limits.is_reached(records_fetched) && secs > MIN_WINDOW_SECS => {
+                // The older part of the window was never fetched: fetch the
+                // window again in halves rather than moving past it
+                progress.window_secs = Some((secs / 2).max(MIN_WINDOW_SECS));
+                tracing::info!(
+                    source_id = %source_id,
+                    stream_name = %stream_name,
+                    records_fetched,
+                    "Backfill window hit the record cap, queueing a shorter one"
+                );
+            }
+            _ => {
+                if limits.is_reached(records_fetched) {
+                    tracing::warn!(
+                        source_id = %source_id,
+                        stream_name = %stream_name,
+                        records_fetched,
+                        "Shortest backfill window hit the record cap, moving past it"
+                    );
+                }
+                progress.windows_done += 1;
+                progress.window_secs = None;
+                if let Some(start) = start {
+                    progress.filled_to = start;
+                }
+                if records_fetched == 0 {
+                    progress.empty_windows += 1;
+                } else {
+                    progress.empty_windows = 0;
+                }
+            }
+        }
+
+        let done = match progress.history_start {
+            Some(history_start) => progress.filled_to <= history_start,
+            None => progress.empty_windows >= EMPTY_WINDOWS_BEFORE_COMPLETE,
+        };
+        if done {
+            progress.status = BackfillStatus::Complete;
+            tracing::info!(
+                source_id = %source_id,
+                stream_name = %stream_name,
+                windows = progress.windows_done,
+                "Backfill after first sync complete"
+            );
+        }
+    } else if let Some(current) = &progress.job_id {
+        let status = jobs::get_job(db, current).await.map(|j| j.status);
+        if matches!(status, Ok(JobStatus::Pending | JobStatus::Running)) {
+            return Ok(());
+        }
+        progress.job_id = None;
+    }
+
+    if progress.status == BackfillStatus::Backfilling {
+        progress.job_id =
+            Some(queue_window(db, executor, source_id, stream_name, &progress).await?);
+    }
+    save_progress(db, source_id, stream_name, &progress).await
+}
+
+/// Queue a backfill job for the window before `filled_to`
+async fn queue_window(
+    db: &SqlitePool,
+    executor: &JobExecutor,
+    source_id: &str,
+    stream_name: &str,
+    progress: &BackfillProgress,
+) -> Result<String> {
+    let window = match progress.window_secs {
+        Some(secs) => Duration::seconds(secs),
+        None => StreamLimits::load(db, source_id, stream_name)
+            .await?
+            .first_sync_window()
+            .unwrap_or_else(|| Duration::days(DEFAULT_FIRST_SYNC_DAYS as i64)),
+    };
+    let end_date = progress.filled_to;
+    let start_date = progress
+        .history_start
+        .map_or(end_date - window, |history_start| {
+            history_start.max(end_date - window)
+        });
+
+    let mode = SyncMode::backfill(start_date, end_date);
+    let request = CreateJobRequest::new_sync_job(
+        source_id.to_string(),
+        stream_name.to_string(),
+        mode.as_str().to_string(),
+        SyncJobMetadata {
+            sync_mode: mode.as_str().to_string(),
+            cursor_before: None,
+            start_date: Some(start_date),
+            end_date: Some(end_date),
+        },
+    );
+    let job = jobs::create_job(db, request).await?;
+    executor.execute_async(job.id.clone());
+
+    tracing::info!(
+        source_id = %source_id,
+        stream_name = %stream_name,
+        job_id = %job.id,
+        start_date = %start_date,
+        end_date = %end_date,
+        "Queued backfill window after first sync"
+    );
+    Ok(job.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::{create_job, ApiKeys, TransformContext};
+    use crate::sources::base::mock_transport::testing::migrated_pool;
+    use crate::storage::{stream_writer::StreamWriter, Storage};
+
+    async fn backfill_job(pool: &SqlitePool, start: DateTime<Utc>, end: DateTime<Utc>) -> Job {
+        create_job(
+            pool,
+            CreateJobRequest::new_sync_job(
+                "src-google".to_string(),
+                "gmail".to_string(),
+                "backfill".to_string(),
+                SyncJobMetadata {
+                    sync_mode: "backfill".to_string(),
+                    cursor_before: None,
+                    start_date: Some(start),
+                    end_date: Some(end),
+                },
+            ),
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn gmail_pool(config: &str) -> SqlitePool {
+        let pool = migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, name) VALUES ('src-google', 'google', 'Google')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO elt_stream_connections (id, source_connection_id, stream_name, table_name, config)
+             VALUES ('stream-gmail', 'src-google', 'gmail', 'stream_google_gmail', $1)",
+        )
+        .bind(config)
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool
+    }
+
+    fn executor(pool: &SqlitePool) -> JobExecutor {
+        let context = TransformContext::new(
+            Arc::new(Storage::in_memory()),
+            Arc::new(tokio::sync::Mutex::new(StreamWriter::new())),
+            ApiKeys::from_env(),
+        );
+        JobExecutor::new(pool.clone(), context)
+    }
+
+    #[tokio::test]
+    async fn test_bound_only_inside_first_sync() {
+        let window_start = Utc::now() - Duration::days(30);
+        let year_ago = Utc::now() - Duration::days(365);
+        let last_week = Utc::now() - Duration::days(7);
+
+        // Outside a first sync the stream's own start is kept
+        assert_eq!(bound(Some(year_ago)), Some(year_ago));
+        assert_eq!(bound(None), None);
+
+        let (start, run) = bounded(Some(window_start), async { bound(Some(last_week)) }).await;
+        assert_eq!(start, Some(last_week));
+        assert!(!run.bounded);
+
+        let (start, run) = bounded(Some(window_start), async { bound(Some(year_ago)) }).await;
+        assert_eq!(start, Some(window_start));
+        assert!(run.bounded);
+        assert_eq!(run.history_start, Some(year_ago));
+
+        let (start, run) = bounded(None, async { bound(None) }).await;
+        assert_eq!(start, None);
+        assert!(!run.bounded);
+    }
+
+    #[tokio::test]
+    async fn test_last_window_completes_backfill() {
+        let pool = gmail_pool("{}").await;
+
+        let incremental = SyncMode::Incremental { cursor: None };
+        let window = window_start(&pool, "src-google", "gmail", &incremental)
+            .await
+            .unwrap()
+            .expect("never-synced stream gets a first-sync window");
+        assert!(window < Utc::now());
+
+        let history_start = window - Duration::days(10);
+        let job = backfill_job(&pool, history_start, window).await;
+        let progress = BackfillProgress {
+            status: BackfillStatus::Backfilling,
+            history_start: Some(history_start),
+            filled_to: window,
+            windows_done: 0,
+            job_id: Some(job.id.clone()),
+            empty_windows: 0,
+            window_secs: None,
+        };
+        save_progress(&pool, "src-google", "gmail", &progress)
+            .await
+            .unwrap();
+        assert!(is_backfill_window(&pool, "src-google", "gmail", &job.id)
+            .await
+            .unwrap());
+        // Once the backfill has started the stream is no longer a first sync
+        assert_eq!(
+            window_start(&pool, "src-google", "gmail", &incremental)
+                .await
+                .unwrap(),
+            None
+        );
+
+        advance(&pool, &executor(&pool), &job, FirstSyncRun::default(), 12)
+            .await
+            .unwrap();
+
+        let progress = load_progress(&pool, "src-google", "gmail")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(progress.status, BackfillStatus::Complete);
+        assert_eq!(progress.filled_to, history_start);
+        assert_eq!(progress.windows_done, 1);
+        assert_eq!(progress.job_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_capped_window_is_queued_again_shorter() {
+        let pool = gmail_pool(r#"{"max_records_per_run": 100}"#).await;
+        let end = Utc::now() - Duration::days(30);
+        let start = end - Duration::days(30);
+        let job = backfill_job(&pool, start, end).await;
+        let progress = BackfillProgress {
+            status: BackfillStatus::Backfilling,
+            history_start: None,
+            filled_to: end,
+            windows_done: 0,
+            job_id: Some(job.id.clone()),
+            empty_windows: 0,
+            window_secs: None,
+        };
+        save_progress(&pool, "src-google", "gmail", &progress)
+            .await
+            .unwrap();
+
+        advance(&pool, &executor(&pool), &job, FirstSyncRun::default(), 100)
+            .await
+            .unwrap();
+
+        let progress = load_progress(&pool, "src-google", "gmail")
+            .await
+            .unwrap()
+            .unwrap();
+        // The window isn't passed over: the same end, half the length
+        assert_eq!(progress.status, BackfillStatus::Backfilling);
+        assert_eq!(progress.filled_to, end);
+        assert_eq!(progress.windows_done, 0);
+        assert_eq!(progress.window_secs, Some(Duration::days(15).num_seconds()));
+        let next = jobs::get_job(&pool, progress.job_id.as_deref().unwrap())
+            .await
+            .unwrap();
+        let next_start = next
+            .metadata
+            .get("start_date")
+            .and_then(|v| v.as_str())
+            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+        assert_eq!(next_start, Some(end - Duration::days(15)));
+    }
+
+    #[tokio::test]
+    async fn test_unbounded_backfill_needs_several_empty_windows() {
+        let pool = gmail_pool("{}").await;
+        let mut end = Utc::now() - Duration::days(30);
+        let mut progress = BackfillProgress {
+            status: BackfillStatus::Backfilling,
+            history_start: None,
+            filled_to: end,
+            windows_done: 0,
+            job_id: None,
+            empty_windows: 0,
+            window_secs: None,
+        };
+
+        for window in 1..=EMPTY_WINDOWS_BEFORE_COMPLETE {
+            let start = end - Duration::days(30);
+            let job = backfill_job(&pool, start, end).await;
+            progress.job_id = Some(job.id.clone());
+            save_progress(&pool, "src-google", "gmail", &progress)
+                .await
+                .unwrap();
+
+            advance(&pool, &executor(&pool), &job, FirstSyncRun::default(), 0)
+                .await
+                .unwrap();
+
+            progress = load_progress(&pool, "src-google", "gmail")
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(progress.empty_windows, window);
+            assert_eq!(progress.filled_to, start);
+            if window < EMPTY_WINDOWS_BEFORE_COMPLETE {
+                assert_eq!(progress.status, BackfillStatus::Backfilling);
+            }
+            end = start;
+        }
+        assert_eq!(progress.status, BackfillStatus::Complete);
+    }
+}
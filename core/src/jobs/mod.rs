@@ -3,21 +3,31 @@
 //! Provides a unified job system for tracking sync, transform, and other async operations.
 //! Jobs are tracked in the database and can be polled for status updates.
 
+pub mod archive;
+pub mod cost;
+pub mod dedup;
 pub mod entity_resolution_job;
 pub mod executor;
+pub mod first_sync;
 pub mod models;
+pub mod pipeline_job;
+pub mod progress;
 
+pub mod sync_concurrency;
 pub mod sync_job;
 pub mod transform_context;
 pub mod transform_factory;
 pub mod transform_job;
 pub mod transform_trigger;
 
+pub use cost::JobCost;
 pub use entity_resolution_job::{chain_to_people_resolution, chain_to_place_resolution};
 pub use executor::JobExecutor;
 pub use models::{CreateJobRequest, Job, JobStatus, JobType, SyncJobMetadata};
+pub use pipeline_job::Pipeline;
+pub use sync_concurrency::{sync_concurrency, SyncConcurrencyManager};
 
-pub use transform_context::{ApiKeys, TransformContext};
+pub use transform_context::{ApiKeys, TransformConfig, TransformContext};
 pub use transform_factory::TransformFactory;
 pub use transform_trigger::create_transform_job_for_stream;
 
@@ -50,6 +60,10 @@ fn job_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<Job> {
         records_processed: row.try_get("records_processed")?,
         error_message: row.try_get("error_message")?,
         error_class: row.try_get("error_class")?,
+        request_id: row.try_get("request_id")?,
+        cost: row
+            .try_get::<Option<sqlx::types::Json<JobCost>>, _>("cost")?
+            .map(|cost| cost.0),
         metadata: row.try_get("metadata")?,
         created_at: row.try_get("created_at")?,
         updated_at: row.try_get("updated_at")?,
@@ -93,6 +107,10 @@ pub async fn create_job(db: &SqlitePool, request: CreateJobRequest) -> Result<Jo
             &chrono::Utc::now().to_rfc3339(),
         ],
     );
+    let request_id = request
+        .request_id
+        .clone()
+        .or_else(crate::middleware::request_id::current);
     let row = sqlx::query(
         r#"
         INSERT INTO elt_jobs (
@@ -106,8 +124,9 @@ pub async fn create_job(db: &SqlitePool, request: CreateJobRequest) -> Result<Jo
             transform_strategy,
             parent_job_id,
             transform_stage,
+            request_id,
             metadata
-        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
         RETURNING *
         "#,
     )
@@ -121,6 +140,7 @@ pub async fn create_job(db: &SqlitePool, request: CreateJobRequest) -> Result<Jo
     .bind(&request.transform_strategy)
     .bind(&request.parent_job_id)
     .bind(&request.transform_stage)
+    .bind(&request_id)
     .bind(&request.metadata)
     .fetch_one(db)
     .await?;
@@ -334,6 +354,7 @@ pub async fn create_chained_transform_job(
         transform_strategy: None,
         parent_job_id: Some(parent_job_id.to_string()),
         transform_stage: Some(transform_stage.to_string()),
+        request_id: None,
         metadata,
     };
 
@@ -1,5 +1,6 @@
 //! Job data models and types
 
+use crate::jobs::cost::JobCost;
 use crate::types::Timestamp;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -12,6 +13,7 @@ pub enum JobType {
     Sync,
     Transform,
     Archive,
+    Pipeline,
 }
 
 impl fmt::Display for JobType {
@@ -20,6 +22,7 @@ impl fmt::Display for JobType {
             JobType::Sync => write!(f, "sync"),
             JobType::Transform => write!(f, "transform"),
             JobType::Archive => write!(f, "archive"),
+            JobType::Pipeline => write!(f, "pipeline"),
         }
     }
 }
@@ -32,6 +35,7 @@ impl std::str::FromStr for JobType {
             "sync" => Ok(JobType::Sync),
             "transform" => Ok(JobType::Transform),
             "archive" => Ok(JobType::Archive),
+            "pipeline" => Ok(JobType::Pipeline),
             _ => Err(format!("Invalid job type: {}", s)),
         }
     }
@@ -87,7 +91,7 @@ pub struct Job {
     // Sync job fields
     pub source_connection_id: Option<String>,
     pub stream_name: Option<String>,
-    pub sync_mode: Option<String>, // 'full_refresh' or 'incremental'
+    pub sync_mode: Option<String>, // 'full_refresh', 'incremental' or 'backfill'
 
     // Transform job fields
     pub transform_id: Option<String>,
@@ -103,6 +107,12 @@ pub struct Job {
     pub records_processed: i64,
     pub error_message: Option<String>,
     pub error_class: Option<String>,
+    /// `X-Request-Id` of the request that created the job, for log correlation
+    pub request_id: Option<String>,
+    /// Billed usage of the job and the jobs it triggered, when accounted
+    /// (see `jobs::cost`)
+    #[sqlx(json(nullable))]
+    pub cost: Option<JobCost>,
 
     // Metadata
     pub metadata: serde_json::Value,
@@ -138,7 +148,7 @@ pub struct CreateJobRequest {
     // Sync job fields
     pub source_connection_id: Option<String>,
     pub stream_name: Option<String>,
-    pub sync_mode: Option<String>, // 'full_refresh' or 'incremental'
+    pub sync_mode: Option<String>, // 'full_refresh', 'incremental' or 'backfill'
 
     // Transform job fields
     pub transform_id: Option<String>,
@@ -148,6 +158,10 @@ pub struct CreateJobRequest {
     pub parent_job_id: Option<String>,
     pub transform_stage: Option<String>,
 
+    /// Correlation id; defaults to the request or job currently being handled
+    #[serde(default)]
+    pub request_id: Option<String>,
+
     // Metadata
     pub metadata: serde_json::Value,
 }
@@ -157,6 +171,12 @@ pub struct CreateJobRequest {
 pub struct SyncJobMetadata {
     pub sync_mode: String,
     pub cursor_before: Option<String>,
+    /// Backfill range start (backfill jobs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<chrono::DateTime<chrono::Utc>>,
+    /// Backfill range end (backfill jobs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl CreateJobRequest {
@@ -164,7 +184,7 @@ impl CreateJobRequest {
     pub fn new_sync_job(
         source_id: String,
         stream_name: String,
-        sync_mode: String, // 'full_refresh' or 'incremental'
+        sync_mode: String, // 'full_refresh', 'incremental' or 'backfill'
         metadata: SyncJobMetadata,
     ) -> Self {
         Self {
@@ -177,6 +197,7 @@ impl CreateJobRequest {
             transform_strategy: None,
             parent_job_id: None,
             transform_stage: None,
+            request_id: None,
             metadata: serde_json::to_value(metadata).unwrap_or_default(),
         }
     }
@@ -193,7 +214,34 @@ impl CreateJobRequest {
             transform_strategy: Some(transform_strategy),
             parent_job_id: None,
             transform_stage: None,
+            request_id: None,
             metadata: serde_json::json!({}),
         }
     }
+
+    /// Create a request for an on-demand pipeline job
+    ///
+    /// The pipeline name is stored in metadata under `pipeline`, alongside any
+    /// pipeline-specific parameters.
+    pub fn new_pipeline_job(pipeline: &str, mut metadata: serde_json::Value) -> Self {
+        if let Some(map) = metadata.as_object_mut() {
+            map.insert("pipeline".to_string(), serde_json::json!(pipeline));
+        } else {
+            metadata = serde_json::json!({ "pipeline": pipeline });
+        }
+
+        Self {
+            job_type: JobType::Pipeline,
+            status: JobStatus::Pending,
+            source_connection_id: None,
+            stream_name: None,
+            sync_mode: None,
+            transform_id: None,
+            transform_strategy: None,
+            parent_job_id: None,
+            transform_stage: None,
+            request_id: None,
+            metadata,
+        }
+    }
 }
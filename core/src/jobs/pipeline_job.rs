@@ -0,0 +1,166 @@
+//! On-demand pipeline job execution
+//!
+//! The scheduler runs maintenance pipelines (embedding indexing, daily
+//! summaries) on fixed crons. This module runs the same work as tracked
+//! `pipeline` jobs so it can be triggered immediately, e.g. after a bulk
+//! import or a transform replay, instead of waiting for the next tick.
+//! Entity resolution, otherwise chained from transforms, can be re-run the
+//! same way, whole or one stage at a time.
+
+use chrono::NaiveDate;
+use sqlx::SqlitePool;
+use std::fmt;
+
+use crate::database::Database;
+use crate::entity_resolution::{self, ResolutionStage, ResolutionStages, TimeWindow};
+use crate::error::{Error, Result};
+use crate::jobs::cost;
+use crate::jobs::entity_resolution_job::DEFAULT_LOOKBACK_HOURS;
+use crate::jobs::models::{Job, JobStatus};
+
+/// Pipelines that can be run on demand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pipeline {
+    /// Embed new records from searchable ontologies (scheduled every 15 minutes)
+    EmbeddingIndex,
+    /// Generate the daily summary for a day (scheduled at the maintenance hour)
+    DailySummary,
+    /// Resolve places and people over the last day (chained from transforms)
+    EntityResolution,
+}
+
+impl Pipeline {
+    /// All pipelines, in display order
+    pub const ALL: [Pipeline; 3] = [
+        Pipeline::EmbeddingIndex,
+        Pipeline::DailySummary,
+        Pipeline::EntityResolution,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Pipeline::EmbeddingIndex => "embedding_index",
+            Pipeline::DailySummary => "daily_summary",
+            Pipeline::EntityResolution => "entity_resolution",
+        }
+    }
+}
+
+impl fmt::Display for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Pipeline {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Pipeline::ALL
+            .into_iter()
+            .find(|p| p.as_str() == s)
+            .ok_or_else(|| {
+                let known: Vec<&str> = Pipeline::ALL.iter().map(|p| p.as_str()).collect();
+                format!("Unknown pipeline '{}'. Available: {}", s, known.join(", "))
+            })
+    }
+}
+
+/// Check if a pipeline already has an active (pending or running) job
+pub async fn has_active_pipeline_job(db: &SqlitePool, pipeline: Pipeline) -> Result<bool> {
+    let exists = sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM elt_jobs
+            WHERE job_type = 'pipeline'
+              AND json_extract(metadata, '$.pipeline') = $1
+              AND status IN ('pending', 'running')
+        )
+        "#,
+    )
+    .bind(pipeline.as_str())
+    .fetch_one(db)
+    .await?;
+
+    Ok(exists)
+}
+
+/// Execute a pipeline job
+///
+/// Reads the pipeline name (and, for `daily_summary`, an optional `date`;
+/// for `entity_resolution`, an optional `stage`) from job metadata. Failures are recorded on the job by the executor.
+#[tracing::instrument(skip(db, job), fields(job_id = %job.id, job_type = "pipeline"))]
+pub async fn execute_pipeline_job(db: &SqlitePool, job: &Job) -> Result<()> {
+    let pipeline: Pipeline = job
+        .metadata
+        .get("pipeline")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| Error::InvalidInput("Pipeline job missing pipeline in metadata".into()))?
+        .parse()
+        .map_err(Error::InvalidInput)?;
+
+    tracing::info!(pipeline = %pipeline, "Running pipeline job");
+
+    let (result, spent) = cost::metered(run_pipeline(db, job, pipeline)).await;
+    if cost::enabled() && !spent.is_zero() {
+        if let Err(e) = cost::add_to_job(db, &job.id, &spent).await {
+            tracing::warn!(job_id = %job.id, error = %e, "Failed to record pipeline cost");
+        }
+    }
+    result?;
+
+    super::update_job_status(db, &job.id, JobStatus::Succeeded, None).await?;
+
+    tracing::info!(pipeline = %pipeline, "Pipeline job completed");
+    Ok(())
+}
+
+/// Run a pipeline's work
+async fn run_pipeline(db: &SqlitePool, job: &Job, pipeline: Pipeline) -> Result<()> {
+    match pipeline {
+        Pipeline::EmbeddingIndex => crate::search::run_embedding_job(db).await?,
+        Pipeline::DailySummary => {
+            let date = match job.metadata.get("date").and_then(|v| v.as_str()) {
+                Some(date) => date.parse::<NaiveDate>().map_err(|_| {
+                    Error::InvalidInput(format!("Invalid date in pipeline metadata: {date}"))
+                })?,
+                None => chrono::Utc::now().date_naive() - chrono::Duration::days(1),
+            };
+            crate::api::day_summary::generate_day_summary(db, date).await?;
+        }
+        Pipeline::EntityResolution => {
+            let stages = match job.metadata.get("stage").and_then(|v| v.as_str()) {
+                Some(stage) => ResolutionStages::only(
+                    stage
+                        .parse::<ResolutionStage>()
+                        .map_err(Error::InvalidInput)?,
+                ),
+                None => ResolutionStages::default(),
+            };
+            let window = TimeWindow::from_lookback_hours(DEFAULT_LOOKBACK_HOURS);
+            entity_resolution::resolve_entities_with(
+                &Database::from_pool(db.clone()),
+                window,
+                stages,
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pipeline_names_round_trip() {
+        for pipeline in Pipeline::ALL {
+            assert_eq!(pipeline.as_str().parse::<Pipeline>().unwrap(), pipeline);
+        }
+
+        let err = "narrative_primitive".parse::<Pipeline>().unwrap_err();
+        assert!(err.contains("embedding_index"));
+    }
+}
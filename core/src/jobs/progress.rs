@@ -0,0 +1,223 @@
+//! Live progress of running sync jobs
+//!
+//! The sync executor runs each stream's `sync_pull` inside `track`, which
+//! opens a progress channel for the job. Sync loops call `report_page` after
+//! every page they fetch; outside a tracked sync that's a no-op, so streams
+//! don't need to know whether anyone is listening. Subscribers (the SSE
+//! progress endpoint) get the latest snapshot first and then every update,
+//! until the sync finishes and the channel closes.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serde::Serialize;
+use tokio::sync::watch;
+
+use crate::error::Result;
+
+/// Phase of a tracked sync
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncPhase {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Progress snapshot of a sync job
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SyncProgress {
+    pub job_id: String,
+    pub phase: SyncPhase,
+    pub pages_fetched: u64,
+    pub records_fetched: usize,
+    pub records_written: usize,
+    /// Cursor (page token, history id, ...) the sync has reached
+    pub cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl SyncProgress {
+    pub fn new(job_id: &str, phase: SyncPhase) -> Self {
+        Self {
+            job_id: job_id.to_string(),
+            phase,
+            pages_fetched: 0,
+            records_fetched: 0,
+            records_written: 0,
+            cursor: None,
+            error: None,
+        }
+    }
+}
+
+type Channels = Mutex<HashMap<String, Arc<watch::Sender<SyncProgress>>>>;
+
+static CHANNELS: OnceLock<Channels> = OnceLock::new();
+
+fn channels() -> &'static Channels {
+    CHANNELS.get_or_init(Default::default)
+}
+
+tokio::task_local! {
+    static CURRENT_PROGRESS: Arc<watch::Sender<SyncProgress>>;
+}
+
+/// Subscribe to a running sync's progress
+///
+/// Returns `None` when the job isn't being tracked (not started yet, or
+/// already finished).
+pub fn subscribe(job_id: &str) -> Option<watch::Receiver<SyncProgress>> {
+    let channels = channels().lock().unwrap_or_else(|e| e.into_inner());
+    channels.get(job_id).map(|tx| tx.subscribe())
+}
+
+/// Report a fetched page from inside a tracked sync
+///
+/// `records_fetched`/`records_written` are running totals for the sync.
+pub fn report_page(records_fetched: usize, records_written: usize, cursor: Option<&str>) {
+    let _ = CURRENT_PROGRESS.try_with(|tx| {
+        tx.send_modify(|progress| {
+            progress.pages_fetched += 1;
+            progress.records_fetched = records_fetched;
+            progress.records_written = records_written;
+            if let Some(cursor) = cursor {
+                progress.cursor = Some(cursor.to_string());
+            }
+        });
+    });
+}
+
+/// A tracked sync's channel, closed when the sync ends
+///
+/// Dropping it while the sync is still running (the sync was cancelled)
+/// marks the last snapshot failed, so subscribers don't see it stop mid-run.
+struct Tracked<'a> {
+    job_id: &'a str,
+    tx: Arc<watch::Sender<SyncProgress>>,
+}
+
+impl Drop for Tracked<'_> {
+    fn drop(&mut self) {
+        self.tx.send_if_modified(|progress| {
+            if progress.phase != SyncPhase::Running {
+                return false;
+            }
+            progress.phase = SyncPhase::Failed;
+            progress.error = Some("Sync was cancelled".to_string());
+            true
+        });
+        channels()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(self.job_id);
+    }
+}
+
+/// Run a job's sync with progress tracking
+///
+/// The final snapshot is marked completed or failed before the channel
+/// closes, including when the sync is cancelled before it finishes.
+pub async fn track<T, F>(job_id: &str, sync: F) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    let (tx, _) = watch::channel(SyncProgress::new(job_id, SyncPhase::Running));
+    let tracked = Tracked {
+        job_id,
+        tx: Arc::new(tx),
+    };
+    channels()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(job_id.to_string(), tracked.tx.clone());
+
+    let result = CURRENT_PROGRESS.scope(tracked.tx.clone(), sync).await;
+
+    tracked.tx.send_modify(|progress| match &result {
+        Ok(_) => progress.phase = SyncPhase::Completed,
+        Err(e) => {
+            progress.phase = SyncPhase::Failed;
+            progress.error = Some(e.to_string());
+        }
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    #[tokio::test]
+    async fn test_track_reports_pages() {
+        // Outside a tracked sync reporting is a no-op
+        report_page(1, 1, None);
+        assert!(subscribe("job_progress_1").is_none());
+
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+        let (resume_tx, resume_rx) = tokio::sync::oneshot::channel::<()>();
+        let sync = tokio::spawn(track("job_progress_1", async move {
+            report_page(100, 98, Some("page_2"));
+            started_tx.send(()).unwrap();
+            resume_rx.await.unwrap();
+            report_page(150, 147, None);
+            Ok(147)
+        }));
+
+        started_rx.await.unwrap();
+        let mut rx = subscribe("job_progress_1").unwrap();
+        let progress = rx.borrow_and_update().clone();
+        assert_eq!(progress.phase, SyncPhase::Running);
+        assert_eq!(progress.pages_fetched, 1);
+        assert_eq!(progress.records_written, 98);
+        assert_eq!(progress.cursor.as_deref(), Some("page_2"));
+
+        resume_tx.send(()).unwrap();
+        assert_eq!(sync.await.unwrap().unwrap(), 147);
+
+        let last = rx.borrow().clone();
+        assert_eq!(last.phase, SyncPhase::Completed);
+        assert_eq!(last.pages_fetched, 2);
+        assert_eq!(last.records_fetched, 150);
+        // The cursor is kept when a page reports none
+        assert_eq!(last.cursor.as_deref(), Some("page_2"));
+        assert!(subscribe("job_progress_1").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_track_marks_failures() {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let sync = tokio::spawn(track("job_progress_2", async move {
+            tx.send(subscribe("job_progress_2").unwrap()).unwrap();
+            Err::<(), _>(Error::Network("connection reset".into()))
+        }));
+
+        let receiver = rx.await.unwrap();
+        assert!(sync.await.unwrap().is_err());
+        let last = receiver.borrow().clone();
+        assert_eq!(last.phase, SyncPhase::Failed);
+        assert!(last.error.unwrap().contains("connection reset"));
+    }
+
+    #[tokio::test]
+    async fn test_track_closes_cancelled_sync() {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let sync = tokio::spawn(track("job_progress_3", async move {
+            tx.send(subscribe("job_progress_3").unwrap()).unwrap();
+            std::future::pending::<Result<()>>().await
+        }));
+
+        let receiver = rx.await.unwrap();
+        sync.abort();
+        assert!(sync.await.unwrap_err().is_cancelled());
+
+        let last = receiver.borrow().clone();
+        assert_eq!(last.phase, SyncPhase::Failed);
+        assert!(last.error.unwrap().contains("cancelled"));
+        assert!(subscribe("job_progress_3").is_none());
+    }
+}
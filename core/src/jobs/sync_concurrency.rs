@@ -0,0 +1,173 @@
+//! Process-wide limit on concurrently running syncs
+//!
+//! Every sync job runs through the executor, whether it was started for one
+//! stream or a whole source from the API, or by the scheduler. The executor
+//! holds a permit from the one `SyncConcurrencyManager` for as long as the
+//! sync runs, so bulk syncs and scheduled ticks share `SYNC_MAX_CONCURRENCY`
+//! rather than each adding their own allowance on top.
+//!
+//! Permits in use and time spent waiting for one are recorded as metrics
+//! (`virtues_sync_permits_in_use`, `virtues_sync_permit_wait_seconds`), and a
+//! sync that has to queue behind the cap logs it.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::error::{Error, Result};
+use crate::observability::metrics;
+
+/// Default number of sync jobs allowed to run at once
+pub const DEFAULT_SYNC_MAX_CONCURRENCY: usize = 4;
+
+static SYNC_CONCURRENCY: OnceLock<SyncConcurrencyManager> = OnceLock::new();
+
+/// The process-wide sync limiter
+///
+/// Sized from `SYNC_MAX_CONCURRENCY` (default 4) on first use.
+pub fn sync_concurrency() -> &'static SyncConcurrencyManager {
+    SYNC_CONCURRENCY.get_or_init(SyncConcurrencyManager::from_env)
+}
+
+/// Limits how many syncs run at once
+pub struct SyncConcurrencyManager {
+    semaphore: Arc<Semaphore>,
+    max_permits: usize,
+    waiting: AtomicUsize,
+}
+
+impl SyncConcurrencyManager {
+    /// A limiter allowing `max_permits` syncs at once
+    pub fn new(max_permits: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_permits)),
+            max_permits,
+            waiting: AtomicUsize::new(0),
+        }
+    }
+
+    /// A limiter sized from `SYNC_MAX_CONCURRENCY`
+    pub fn from_env() -> Self {
+        let permits = std::env::var("SYNC_MAX_CONCURRENCY")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_SYNC_MAX_CONCURRENCY);
+        Self::new(permits)
+    }
+
+    /// Wait for a permit to run a sync; the sync may run until it is dropped
+    pub async fn acquire(&self) -> Result<SyncPermit> {
+        let started = Instant::now();
+        let permit = match self.semaphore.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                let waiting = Waiting::enter(&self.waiting);
+                tracing::info!(
+                    max_permits = self.max_permits,
+                    waiting = waiting.count,
+                    "Sync queued behind the concurrency limit"
+                );
+                let permit = self.semaphore.clone().acquire_owned().await;
+                drop(waiting);
+                permit.map_err(|e| Error::Other(format!("sync semaphore closed: {e}")))?
+            }
+        };
+
+        if let Some(m) = metrics() {
+            m.record_sync_permit_acquired(started.elapsed().as_secs_f64());
+        }
+        Ok(SyncPermit { _permit: permit })
+    }
+
+    /// Most syncs allowed at once
+    pub fn max_permits(&self) -> usize {
+        self.max_permits
+    }
+
+    /// Syncs currently holding a permit
+    pub fn in_use(&self) -> usize {
+        self.max_permits - self.semaphore.available_permits()
+    }
+
+    /// Syncs currently waiting for a permit
+    pub fn waiting(&self) -> usize {
+        self.waiting.load(Ordering::SeqCst)
+    }
+}
+
+/// A sync counted as waiting for a permit until dropped, including when the
+/// wait is cancelled
+struct Waiting<'a> {
+    counter: &'a AtomicUsize,
+    /// Syncs waiting, this one included, when it started to wait
+    count: usize,
+}
+
+impl<'a> Waiting<'a> {
+    fn enter(counter: &'a AtomicUsize) -> Self {
+        let count = counter.fetch_add(1, Ordering::SeqCst) + 1;
+        Self { counter, count }
+    }
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.counter.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Permission to run one sync, released when dropped
+pub struct SyncPermit {
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Drop for SyncPermit {
+    fn drop(&mut self) {
+        if let Some(m) = metrics() {
+            m.record_sync_permit_released();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_syncs_queue_behind_the_limit() {
+        let manager = Arc::new(SyncConcurrencyManager::new(1));
+        let first = manager.acquire().await.unwrap();
+        assert_eq!(manager.in_use(), 1);
+
+        let queued = tokio::spawn({
+            let manager = manager.clone();
+            async move { manager.acquire().await.map(|_| ()) }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(manager.waiting(), 1);
+        assert!(!queued.is_finished());
+
+        drop(first);
+        tokio::time::timeout(Duration::from_secs(1), queued)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(manager.waiting(), 0);
+        assert_eq!(manager.in_use(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_wait_stops_counting() {
+        let manager = SyncConcurrencyManager::new(1);
+        let _first = manager.acquire().await.unwrap();
+
+        let cancelled = tokio::time::timeout(Duration::from_millis(50), manager.acquire()).await;
+        assert!(cancelled.is_err());
+        assert_eq!(manager.waiting(), 0);
+    }
+}
@@ -1,13 +1,22 @@
 //! Sync job execution logic
 
 use crate::error::Result;
+use crate::jobs::archive;
+use crate::jobs::dedup;
+use crate::jobs::first_sync;
 use crate::jobs::models::Job;
+use crate::jobs::progress;
 use crate::jobs::{JobExecutor, TransformContext};
-use crate::sources::base::SyncMode;
+use crate::sources::base::{
+    hold_checkpoint, hold_resume_cursor, load_resume_cursor, save_resume_cursor, Checkpoint,
+    StreamLimits, SyncMode, SyncResult,
+};
 use crate::sources::StreamFactory;
 use crate::registry;
+use chrono::{NaiveDateTime, Utc};
 use serde_json::json;
 use sqlx::SqlitePool;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 /// Execute a sync job
@@ -130,41 +139,57 @@ pub async fn execute_sync_job(
     // Load configuration
     pull_stream.load_config(db, &source_id).await?;
 
-    // Execute sync using PullStream API
-    let result = pull_stream.sync_pull(sync_mode.clone()).await;
+    // Execute sync using PullStream API. A first sync fetches only recent
+    // history; the rest is backfilled by later jobs. The resume cursor and
+    // checkpoint the stream saves are held back and committed with the
+    // archived records.
+    let cursor_snapshot = snapshot_cursor(db, &source_id, stream_name).await?;
+    let started_fresh = cursor_snapshot
+        .as_ref()
+        .is_none_or(|(_, _, resume_cursor)| resume_cursor.is_none());
+    let window_start = first_sync::window_start(db, &source_id, stream_name, &sync_mode).await?;
+    let (((result, first_sync_run), resume_cursor), checkpoint) =
+        hold_checkpoint(hold_resume_cursor(first_sync::bounded(
+            window_start,
+            progress::track(&job.id, pull_stream.sync_pull(sync_mode.clone())),
+        )))
+        .await;
 
     match result {
-        Ok(sync_result) => {
-            // Update watermarks and sync status
-            sqlx::query(
-                r#"
-                UPDATE elt_stream_connections
-                SET last_sync_at = $1, 
-                    last_sync_token = $2, 
-                    earliest_record_at = COALESCE(earliest_record_at, $3),
-                    latest_record_at = $4,
-                    sync_status = $5,
-                    updated_at = datetime('now')
-                WHERE source_connection_id = $6 AND stream_name = $7
-                "#,
-            )
-            .bind(sync_result.completed_at)
-            .bind(&sync_result.next_cursor)
-            .bind(sync_result.earliest_record_at)
-            .bind(sync_result.latest_record_at)
-            .bind(match sync_mode {
-                SyncMode::FullRefresh => "initial",
-                SyncMode::Incremental { .. } => "incremental",
-                SyncMode::Backfill { .. } => "backfilling",
-            })
-            .bind(&source_id)
-            .bind(stream_name)
-            .execute(db)
-            .await?;
-
+        Ok(mut sync_result) => {
             // Extract records for direct transform and archival
-            let has_records = sync_result.records.is_some();
-            let records = sync_result.records.clone().unwrap_or_default();
+            let mut records = sync_result.records.take().unwrap_or_default();
+
+            // A full refresh that listed the stream from start to end in this
+            // one run replaces the archive, so it keeps every record. One that
+            // continued an earlier run or stopped at a bound appends like any
+            // other run, and so does every refresh of a stream that hasn't
+            // opted in to replacing.
+            let complete_refresh = matches!(sync_mode, SyncMode::FullRefresh)
+                && registered_stream.replace_on_full_refresh
+                && started_fresh
+                && match &resume_cursor {
+                    Some(cursor) => cursor.is_none(),
+                    None => load_resume_cursor(db, &source_id, stream_name)
+                        .await?
+                        .is_none(),
+                }
+                && !StreamLimits::load(db, &source_id, stream_name)
+                    .await?
+                    .is_reached(sync_result.records_fetched);
+
+            // Backfills and full refreshes re-fetch windows the archive may
+            // already cover; drop records whose natural id was archived before
+            let dedup_key = registered_stream.dedup_key;
+            if let Some(key) =
+                dedup_key.filter(|_| dedup::applies_to(&sync_mode) && !complete_refresh)
+            {
+                let (kept, skipped) =
+                    dedup::skip_archived(db, &source_id, stream_name, key, records).await?;
+                records = kept;
+                sync_result.records_deduplicated = skipped;
+            }
+            let has_records = !records.is_empty();
 
             tracing::info!(
                 stream_name = %stream_name,
@@ -173,40 +198,175 @@ pub async fn execute_sync_job(
                 "Sync completed, checking for records to archive"
             );
 
-            // Write records directly to filesystem (sync, no async job queue)
-            let storage_key = if !records.is_empty() {
-                tracing::info!(
-                    stream_name = %stream_name,
-                    record_count = records.len(),
-                    "Writing records to filesystem"
-                );
-
-                let storage_key = write_stream_records(
-                    db,
-                    context.storage.as_ref(),
+            // Upload one object per partition, concurrently. Nothing is
+            // indexed and the cursor is not advanced unless all of them land.
+            // A complete refresh stages its partitions for an atomic swap; one
+            // that returned nothing leaves the existing archive in place.
+            let staging =
+                (complete_refresh && has_records).then(|| archive::staging_prefix(&job.id));
+            let granularity = archive::stream_granularity(db, &source_id, stream_name).await?;
+            let partitions = archive::partition_records(
+                &records,
+                registered_stream.partition_key,
+                granularity,
+                Utc::now(),
+                (sync_result.earliest_record_at, sync_result.latest_record_at),
+            );
+            let storage = context.storage.as_ref();
+            let uploads = if let Some(prefix) = &staging {
+                archive::upload_staged_partitions(
+                    storage,
+                    prefix,
+                    &source_conn.source,
                     &source_id,
+                    stream_name,
+                    granularity,
+                    &partitions,
+                )
+                .await
+            } else if has_records {
+                archive::upload_partitions(
+                    storage,
                     &source_conn.source,
+                    &source_id,
                     stream_name,
-                    &records,
-                    sync_result.earliest_record_at,
-                    sync_result.latest_record_at,
+                    granularity,
+                    &partitions,
+                    archive::upload_concurrency(),
                 )
-                .await?;
+                .await
+            } else {
+                tracing::warn!(
+                    stream_name = %stream_name,
+                    records_deduplicated = sync_result.records_deduplicated,
+                    "No new records collected from sync, skipping archival"
+                );
+                Vec::new()
+            };
+
+            let archived = match uploads.iter().find(|u| !u.succeeded) {
+                Some(failed) => Err(crate::Error::ProviderUnavailable(format!(
+                    "Failed to archive partition {} ({} of {} partitions failed): {}",
+                    failed.partition,
+                    uploads.iter().filter(|u| !u.succeeded).count(),
+                    uploads.len(),
+                    failed.error.as_deref().unwrap_or("unknown error")
+                ))),
+                None => match archive::publish_staged(storage, &uploads).await {
+                    Ok(()) => {
+                        let committed = commit_archive(
+                            db,
+                            &source_id,
+                            stream_name,
+                            CompletedRun {
+                                sync_mode: &sync_mode,
+                                sync_result: &sync_result,
+                                resume_cursor: resume_cursor.as_ref().map(Option::as_deref),
+                                checkpoint,
+                            },
+                            &partitions,
+                            &uploads,
+                        )
+                        .await;
+                        if committed.is_err() && staging.is_some() {
+                            // Published, but never indexed
+                            let live: Vec<String> =
+                                uploads.iter().map(|u| u.storage_key.clone()).collect();
+                            archive::discard_objects(storage, &live).await;
+                        }
+                        committed
+                    }
+                    Err(e) => Err(e),
+                },
+            };
+
+            // Staged copies are done with whether or not the swap went through;
+            // a failed upload already removed its run's objects
+            if uploads.iter().all(|u| u.succeeded) {
+                let staged: Vec<String> = uploads
+                    .iter()
+                    .filter_map(|u| u.staged_key.clone())
+                    .collect();
+                archive::discard_objects(storage, &staged).await;
+            }
 
+            let replaced = match archived {
+                Ok(replaced) => replaced,
+                Err(e) => {
+                    // Streams may persist their cursor while syncing; put it back
+                    // so the next run re-fetches what wasn't durably stored
+                    restore_cursor(db, &source_id, stream_name, cursor_snapshot).await?;
+
+                    let metadata = json!({
+                        "cursor_before": cursor_before,
+                        "error_class": classify_sync_error(&e),
+                        "partitions": uploads,
+                    });
+                    sqlx::query(
+                        r#"
+                        UPDATE elt_jobs
+                        SET status = 'failed',
+                            completed_at = datetime('now'),
+                            error_message = $1,
+                            error_class = $2,
+                            metadata = $3
+                        WHERE id = $4
+                        "#,
+                    )
+                    .bind(e.to_string())
+                    .bind(classify_sync_error(&e))
+                    .bind(metadata)
+                    .bind(&job.id)
+                    .execute(db)
+                    .await?;
+
+                    tracing::error!(
+                        job_id = %job.id,
+                        stream_name = %stream_name,
+                        error = %e,
+                        "Archiving failed, cursor left at its pre-sync position"
+                    );
+                    return Err(e);
+                }
+            };
+
+            if staging.is_some() {
+                archive::discard_objects(storage, &replaced).await;
                 tracing::info!(
                     stream_name = %stream_name,
-                    storage_key = %storage_key,
-                    "Records written to filesystem successfully"
+                    replaced_objects = replaced.len(),
+                    "Full refresh swapped in, replacing the stream's archive"
                 );
+            }
 
-                Some(storage_key)
-            } else {
-                tracing::warn!(
+            // A backfill window fills in history behind the cursor; leave the
+            // cursor where the stream's incremental syncs put it
+            if first_sync::is_backfill_window(db, &source_id, stream_name, &job.id).await? {
+                restore_cursor(db, &source_id, stream_name, cursor_snapshot).await?;
+            }
+
+            let storage_keys: Vec<&str> = uploads.iter().map(|u| u.storage_key.as_str()).collect();
+            if has_records {
+                tracing::info!(
                     stream_name = %stream_name,
-                    "No records collected from sync, skipping archival"
+                    partitions = uploads.len(),
+                    "Records archived successfully"
                 );
-                None
-            };
+
+                // A missing index entry only costs a possible duplicate on a
+                // later backfill, so don't fail the job over it
+                if let Some(key) = dedup_key {
+                    if let Err(e) =
+                        dedup::index_archived(db, &source_id, stream_name, key, &records).await
+                    {
+                        tracing::warn!(
+                            error = %e,
+                            stream_name = %stream_name,
+                            "Failed to index archived record ids, continuing"
+                        );
+                    }
+                }
+            }
 
             // Build metadata with detailed sync info
             let metadata = json!({
@@ -215,11 +375,13 @@ pub async fn execute_sync_job(
                 "records_fetched": sync_result.records_fetched,
                 "records_written": sync_result.records_written,
                 "records_failed": sync_result.records_failed,
+                "records_deduplicated": sync_result.records_deduplicated,
                 "earliest_record_at": sync_result.earliest_record_at,
                 "latest_record_at": sync_result.latest_record_at,
                 "duration_ms": sync_result.duration_ms(),
                 "direct_transform_enabled": has_records,
-                "storage_key": storage_key
+                "storage_keys": storage_keys,
+                "partitions": uploads
             });
 
             // Update job with final stats and metadata
@@ -244,12 +406,29 @@ pub async fn execute_sync_job(
                 stream_name = %stream_name,
                 records_fetched = sync_result.records_fetched,
                 records_written = sync_result.records_written,
+                records_deduplicated = sync_result.records_deduplicated,
                 duration_ms = sync_result.duration_ms(),
                 direct_transform = has_records,
-                storage_key = ?storage_key,
+                partitions = uploads.len(),
                 "Sync job completed successfully"
             );
 
+            if let Err(e) = first_sync::advance(
+                db,
+                executor,
+                job,
+                first_sync_run,
+                sync_result.records_fetched,
+            )
+            .await
+            {
+                tracing::warn!(
+                    error = %e,
+                    stream_name = %stream_name,
+                    "Failed to advance backfill after first sync, continuing"
+                );
+            }
+
             // Create transform job with optional memory data source (direct transform)
             // Only create transform job if we actually have records to transform
             if has_records {
@@ -260,6 +439,7 @@ pub async fn execute_sync_job(
                     source_id.clone(),
                     stream_name,
                     Some(records),
+                    crate::jobs::cost::enabled().then(|| job.id.clone()),
                 )
                 .await
                 .map(|_job_id| ())
@@ -280,6 +460,8 @@ pub async fn execute_sync_job(
             Ok(())
         }
         Err(e) => {
+            rollback_cursor_after(db, &source_id, stream_name, cursor_snapshot, &e).await?;
+
             // Classify error for monitoring
             let error_class = classify_sync_error(&e);
 
@@ -308,6 +490,18 @@ pub async fn execute_sync_job(
             .execute(db)
             .await?;
 
+            // Retrying can't fix rejected credentials: stop scheduling the
+            // source until the user reconnects it
+            if let crate::Error::TokenExpired(reason) = &e {
+                crate::api::sources::mark_reauth_required(db, &source_id, reason).await?;
+                tracing::warn!(
+                    source_id = %source_id,
+                    stream_name = %stream_name,
+                    reason = %reason,
+                    "Source credentials rejected, marked for reauthorization"
+                );
+            }
+
             // Handle rate limiting backoff
             if error_class == "rate_limit" {
                 tracing::info!(
@@ -332,63 +526,147 @@ pub async fn execute_sync_job(
     }
 }
 
-/// Write stream records directly to filesystem and record metadata
-///
-/// This replaces the async archive job system with synchronous filesystem writes.
-async fn write_stream_records(
+/// Position of a stream's cursor, as stored in `elt_stream_connections`
+type CursorSnapshot = Option<(Option<String>, Option<String>, Option<String>)>;
+
+/// Read a stream's cursor (`last_sync_token`, `last_sync_at`,
+/// `resume_cursor`) before syncing
+async fn snapshot_cursor(
     db: &SqlitePool,
-    storage: &crate::storage::Storage,
     source_id: &str,
-    source_type: &str,
     stream_name: &str,
-    records: &[serde_json::Value],
-    min_timestamp: Option<chrono::DateTime<chrono::Utc>>,
-    max_timestamp: Option<chrono::DateTime<chrono::Utc>>,
-) -> Result<String> {
-    use chrono::Utc;
-    use crate::storage::models::StreamKeyBuilder;
-
-    let date = Utc::now().date_naive();
-    let key_builder = StreamKeyBuilder::new(None, source_type, source_id, stream_name, date)
-        .map_err(|e| crate::Error::Other(format!("Invalid stream key: {}", e)))?;
-    let storage_key = key_builder.build();
-
-    // Write JSONL to filesystem
-    storage.upload_jsonl(&storage_key, records).await?;
-
-    // Calculate size
-    let size_bytes: i64 = records
-        .iter()
-        .map(|r| serde_json::to_string(r).unwrap_or_default().len() as i64)
-        .sum();
-
-    // Record metadata in elt_stream_objects
-    let stream_object_id = crate::ids::generate_id(crate::ids::STREAM_OBJECT_PREFIX, &[&storage_key]);
+) -> Result<CursorSnapshot> {
+    Ok(sqlx::query_as(
+        "SELECT last_sync_token, last_sync_at, resume_cursor FROM elt_stream_connections
+         WHERE source_connection_id = $1 AND stream_name = $2",
+    )
+    .bind(source_id)
+    .bind(stream_name)
+    .fetch_optional(db)
+    .await?)
+}
+
+/// Put a stream's cursor back to a snapshot taken before the sync
+async fn restore_cursor(
+    db: &SqlitePool,
+    source_id: &str,
+    stream_name: &str,
+    snapshot: CursorSnapshot,
+) -> Result<()> {
+    let Some((token, synced_at, resume_cursor)) = snapshot else {
+        return Ok(());
+    };
+
     sqlx::query(
-        "INSERT INTO elt_stream_objects
-         (id, source_connection_id, stream_name, storage_key, record_count, size_bytes,
-          min_timestamp, max_timestamp, created_at)
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, datetime('now'))",
+        "UPDATE elt_stream_connections
+         SET last_sync_token = $1, last_sync_at = $2, resume_cursor = $3,
+             updated_at = datetime('now')
+         WHERE source_connection_id = $4 AND stream_name = $5",
     )
-    .bind(&stream_object_id)
+    .bind(token)
+    .bind(synced_at)
+    .bind(resume_cursor)
     .bind(source_id)
     .bind(stream_name)
-    .bind(&storage_key)
-    .bind(records.len() as i32)
-    .bind(size_bytes)
-    .bind(min_timestamp)
-    .bind(max_timestamp)
     .execute(db)
     .await?;
 
-    tracing::info!(
-        stream_object_id = %stream_object_id,
-        storage_key = %storage_key,
-        record_count = records.len(),
-        "Stream object metadata recorded"
-    );
+    Ok(())
+}
+
+/// Put back the cursor of a sync that failed because storage was unavailable
+///
+/// Nothing fetched in such a run was archived, so a cursor the stream saved
+/// along the way would skip those records next run. Returns whether the
+/// cursor was restored.
+async fn rollback_cursor_after(
+    db: &SqlitePool,
+    source_id: &str,
+    stream_name: &str,
+    snapshot: CursorSnapshot,
+    error: &crate::error::Error,
+) -> Result<bool> {
+    if !matches!(error, crate::error::Error::ProviderUnavailable(_)) {
+        return Ok(false);
+    }
+    restore_cursor(db, source_id, stream_name, snapshot).await?;
+    Ok(true)
+}
+
+/// What a finished sync run leaves to commit with its archive
+struct CompletedRun<'a> {
+    sync_mode: &'a SyncMode,
+    sync_result: &'a SyncResult,
+    /// Resume cursor the stream saved during the run, if it saved one
+    resume_cursor: Option<Option<&'a str>>,
+    /// Checkpoint the stream handed off with its records, if any
+    checkpoint: Option<Checkpoint>,
+}
+
+/// Index the uploaded partitions and advance the stream's watermarks together
+///
+/// Runs in one transaction, so the cursor never moves past data that isn't
+/// recorded in `elt_stream_objects`. The resume cursor the stream saved during
+/// the run, if any, is committed in it too. Staged uploads (a complete full
+/// refresh) replace the stream's indexed objects in the same transaction; the
+/// keys of the replaced objects are returned for deletion.
+async fn commit_archive(
+    db: &SqlitePool,
+    source_id: &str,
+    stream_name: &str,
+    run: CompletedRun<'_>,
+    partitions: &BTreeMap<NaiveDateTime, archive::Partition>,
+    uploads: &[archive::PartitionUpload],
+) -> Result<Vec<String>> {
+    let mut tx = db.begin().await?;
+
+    let mut replaced = Vec::new();
+    if uploads.iter().any(|u| u.staged_key.is_some()) {
+        replaced = archive::clear_stream_objects(&mut tx, source_id, stream_name).await?;
+        replaced.retain(|key| uploads.iter().all(|u| u.storage_key != *key));
+    }
+    archive::record_partitions(&mut tx, source_id, stream_name, partitions, uploads).await?;
+    let CompletedRun {
+        sync_mode,
+        sync_result,
+        resume_cursor,
+        checkpoint,
+    } = run;
+    if let Some(checkpoint) = checkpoint {
+        checkpoint(&mut tx).await?;
+    }
+    if let Some(cursor) = resume_cursor {
+        save_resume_cursor(&mut *tx, source_id, stream_name, cursor).await?;
+    }
 
-    Ok(storage_key)
+    sqlx::query(
+        r#"
+        UPDATE elt_stream_connections
+        SET last_sync_at = $1,
+            last_sync_token = $2,
+            earliest_record_at = COALESCE(earliest_record_at, $3),
+            latest_record_at = $4,
+            sync_status = $5,
+            updated_at = datetime('now')
+        WHERE source_connection_id = $6 AND stream_name = $7
+        "#,
+    )
+    .bind(sync_result.completed_at)
+    .bind(&sync_result.next_cursor)
+    .bind(sync_result.earliest_record_at)
+    .bind(sync_result.latest_record_at)
+    .bind(match sync_mode {
+        SyncMode::FullRefresh => "initial",
+        SyncMode::Incremental { .. } => "incremental",
+        SyncMode::Backfill { .. } => "backfilling",
+    })
+    .bind(source_id)
+    .bind(stream_name)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(replaced)
 }
 
 /// Classify errors for monitoring and alerting
@@ -419,9 +697,398 @@ fn classify_sync_error(error: &crate::error::Error) -> &'static str {
         Error::Source(_) => "sync_token_error",
         Error::Database(_) => "database_error",
         Error::Storage(_) => "storage_error",
+        Error::ProviderUnavailable(_) => "provider_unavailable",
         Error::Authentication(_) | Error::Unauthorized(_) => "auth_error",
+        Error::TokenExpired(_) => "reauth_required",
+        Error::RateLimited { .. } => "rate_limit",
         Error::Serialization(_) => "serialization_error",
         Error::Configuration(_) => "config_error",
         _ => "unknown_error",
     }
 }
+
+/// Record the outcome of a sync job on its stream
+///
+/// A failure stores the error as the stream's `last_error`; a success clears it.
+pub async fn record_stream_outcome(
+    db: &SqlitePool,
+    source_id: &str,
+    stream_name: &str,
+    error: Option<&crate::error::Error>,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE elt_stream_connections
+         SET last_error = $1,
+             last_error_at = CASE WHEN $1 IS NULL THEN NULL ELSE datetime('now') END
+         WHERE source_connection_id = $2 AND stream_name = $3",
+    )
+    .bind(error.map(|e| e.to_string()))
+    .bind(source_id)
+    .bind(stream_name)
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_record_stream_outcome() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE elt_stream_connections (
+                source_connection_id TEXT NOT NULL,
+                stream_name TEXT NOT NULL,
+                last_error TEXT,
+                last_error_at TEXT
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO elt_stream_connections VALUES ('source_1', 'gmail', NULL, NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let error = crate::error::Error::Authentication("token expired".into());
+        record_stream_outcome(&pool, "source_1", "gmail", Some(&error))
+            .await
+            .unwrap();
+        let (last_error, last_error_at): (Option<String>, Option<String>) =
+            sqlx::query_as("SELECT last_error, last_error_at FROM elt_stream_connections")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(last_error.unwrap().contains("token expired"));
+        assert!(last_error_at.is_some());
+
+        record_stream_outcome(&pool, "source_1", "gmail", None)
+            .await
+            .unwrap();
+        let (last_error, last_error_at): (Option<String>, Option<String>) =
+            sqlx::query_as("SELECT last_error, last_error_at FROM elt_stream_connections")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(last_error.is_none());
+        assert!(last_error_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_storage_outage_mid_sync_keeps_the_cursor() {
+        use crate::sources::google::gmail::body::{store_body, StoredBody};
+        use crate::storage::Storage;
+
+        let pool = crate::sources::base::mock_transport::testing::migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, name) VALUES ('src', 'google', 'src')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO elt_stream_connections
+             (id, source_connection_id, stream_name, table_name, last_sync_token)
+             VALUES ('st', 'src', 'gmail', 'stream_google_gmail', 'history-1')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let cursor = || async {
+            sqlx::query_as::<_, (Option<String>, Option<String>)>(
+                "SELECT last_sync_token, resume_cursor FROM elt_stream_connections WHERE id = 'st'",
+            )
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+        };
+        let snapshot = snapshot_cursor(&pool, "src", "gmail").await.unwrap();
+
+        // The stream saves its progress, then storage fails the next write
+        sqlx::query(
+            "UPDATE elt_stream_connections
+             SET last_sync_token = 'history-2', resume_cursor = 'page-3' WHERE id = 'st'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = Storage::file(temp_dir.path().to_str().unwrap().to_string()).unwrap();
+        storage.upload("streams", Vec::new()).await.unwrap();
+        let body = StoredBody {
+            body_plain: Some("hello".to_string()),
+            body_html: None,
+        };
+        let error = store_body(&storage, "src", "msg-1", &body)
+            .await
+            .unwrap_err();
+        assert_eq!(classify_sync_error(&error), "provider_unavailable");
+
+        assert!(
+            rollback_cursor_after(&pool, "src", "gmail", snapshot.clone(), &error)
+                .await
+                .unwrap()
+        );
+        assert_eq!(cursor().await, (Some("history-1".to_string()), None));
+
+        // Other failures leave the cursor to the stream
+        sqlx::query("UPDATE elt_stream_connections SET resume_cursor = 'page-3' WHERE id = 'st'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let other = crate::error::Error::Http("API error (500)".to_string());
+        assert!(
+            !rollback_cursor_after(&pool, "src", "gmail", snapshot, &other)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            cursor().await,
+            (Some("history-1".to_string()), Some("page-3".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_full_refresh_swaps_in_atomically() {
+        use crate::api::feed::get_feed;
+        use crate::storage::{models::PartitionGranularity, Storage};
+        use serde_json::Value;
+
+        let pool = crate::sources::base::mock_transport::testing::migrated_pool().await;
+        let storage = Storage::in_memory();
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, name) VALUES ('src', 'google', 'src')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO elt_stream_connections (id, source_connection_id, stream_name, table_name)
+             VALUES ('st', 'src', 'calendar', 'stream_google_calendar')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // The archive before the refresh
+        let old_key = "streams/google/src/calendar/date=2024-01-01/records_1.jsonl";
+        storage
+            .upload_jsonl(
+                old_key,
+                &[json!({ "summary": "Old", "start_time": "2024-01-01T10:00:00Z" })],
+            )
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO elt_stream_objects
+             (id, source_connection_id, stream_name, storage_key, record_count, size_bytes,
+              min_timestamp, max_timestamp)
+             VALUES ('old', 'src', 'calendar', $1, 1, 0, '2024-01-01 10:00:00', '2024-01-01 10:00:00')",
+        )
+        .bind(old_key)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let (db, store) = (&pool, &storage);
+        let read = || async move {
+            let since = "2023-01-01T00:00:00Z".parse().unwrap();
+            let page = get_feed(db, store, Some(since), None, None).await.unwrap();
+            page.records
+                .iter()
+                .map(|r| r["summary"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let records = vec![
+            json!({ "summary": "New 1", "start_time": "2024-01-01T09:00:00Z" }),
+            json!({ "summary": "New 2", "start_time": "2024-01-02T09:00:00Z" }),
+        ];
+        let partitions = archive::partition_records(
+            &records,
+            Some("start_time"),
+            PartitionGranularity::Daily,
+            Utc::now(),
+            (None, None),
+        );
+        let uploads = archive::upload_staged_partitions(
+            &storage,
+            &archive::staging_prefix("job-1"),
+            "google",
+            "src",
+            "calendar",
+            PartitionGranularity::Daily,
+            &partitions,
+        )
+        .await;
+        assert!(uploads.iter().all(|u| u.succeeded));
+
+        // Mid-refresh: staged and published partitions aren't visible yet
+        assert_eq!(read().await, ["Old"]);
+        let live = storage.list("streams/").await.unwrap();
+        assert_eq!(live, [old_key]);
+        archive::publish_staged(&storage, &uploads).await.unwrap();
+        assert_eq!(read().await, ["Old"]);
+
+        let replaced = commit_archive(
+            &pool,
+            "src",
+            "calendar",
+            CompletedRun {
+                sync_mode: &SyncMode::FullRefresh,
+                sync_result: &SyncResult::new(Utc::now()),
+                resume_cursor: None,
+                checkpoint: None,
+            },
+            &partitions,
+            &uploads,
+        )
+        .await
+        .unwrap();
+        assert_eq!(replaced, [old_key]);
+        assert_eq!(read().await, ["New 1", "New 2"]);
+
+        archive::discard_objects(&storage, &replaced).await;
+        let staged: Vec<String> = uploads
+            .iter()
+            .filter_map(|u| u.staged_key.clone())
+            .collect();
+        archive::discard_objects(&storage, &staged).await;
+        assert!(storage.list("staging/").await.unwrap().is_empty());
+        assert_eq!(storage.list("streams/").await.unwrap().len(), 2);
+        let first: Vec<Value> = storage
+            .download_jsonl(&uploads[0].storage_key)
+            .await
+            .unwrap();
+        assert_eq!(first[0]["summary"], "New 1");
+    }
+
+    #[tokio::test]
+    async fn test_full_refresh_over_several_runs_keeps_every_run() {
+        use crate::jobs::{create_job, ApiKeys, CreateJobRequest, SyncJobMetadata};
+        use crate::sources::base::mock_transport::testing::{migrated_pool, with_transport};
+        use crate::sources::base::mock_transport::MockTransport;
+        use crate::storage::{stream_writer::StreamWriter, Storage};
+        use serde_json::Value;
+
+        let pool = migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, name) VALUES ('src', 'notion', 'Notion')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO elt_stream_connections (id, source_connection_id, stream_name, table_name, config)
+             VALUES ('st', 'src', 'databases', 'stream_notion_databases', '{\"max_records_per_run\": 1}')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let row = |id: &str, database: &str| {
+            json!({
+                "id": id,
+                "created_time": "2024-01-01T00:00:00Z",
+                "last_edited_time": "2024-05-01T12:00:00Z",
+                "created_by": { "id": "user-1" },
+                "last_edited_by": { "id": "user-1" },
+                "parent": { "type": "database_id", "database_id": database },
+                "archived": false,
+                "properties": {},
+                "url": format!("https://www.notion.so/{id}")
+            })
+        };
+        let database = |id: &str| {
+            json!({
+                "id": id,
+                "title": [{ "plain_text": id, "href": null }],
+                "last_edited_time": "2024-01-01T00:00:00Z",
+                "archived": false,
+                "url": format!("https://www.notion.so/{id}")
+            })
+        };
+        let page = |results: Vec<Value>| json!({ "results": results, "has_more": false, "next_cursor": null });
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_response("search", page(vec![database("db-1"), database("db-2")]))
+                .with_response("databases/db-1/query", page(vec![row("r1", "db-1")]))
+                .with_response("databases/db-2/query", page(vec![row("r2", "db-2")])),
+        );
+
+        let context = Arc::new(TransformContext::new(
+            Arc::new(Storage::in_memory()),
+            Arc::new(tokio::sync::Mutex::new(StreamWriter::new())),
+            ApiKeys::from_env(),
+        ));
+        let executor = JobExecutor::new(pool.clone(), context.as_ref().clone());
+        let full_refresh = || async {
+            let job = create_job(
+                &pool,
+                CreateJobRequest::new_sync_job(
+                    "src".to_string(),
+                    "databases".to_string(),
+                    "full_refresh".to_string(),
+                    SyncJobMetadata {
+                        sync_mode: "full_refresh".to_string(),
+                        cursor_before: None,
+                        start_date: None,
+                        end_date: None,
+                    },
+                ),
+            )
+            .await
+            .unwrap();
+            with_transport(
+                transport.clone(),
+                execute_sync_job(&pool, &executor, &context, &job),
+            )
+            .await
+            .unwrap();
+        };
+        let archived = || async {
+            sqlx::query_scalar::<_, String>(
+                "SELECT storage_key FROM elt_stream_objects WHERE stream_name = 'databases'
+                 ORDER BY storage_key",
+            )
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+        };
+
+        // Capped at one record: the refresh takes two runs, the second
+        // finishing what the first started, and neither replaces the other
+        full_refresh().await;
+        assert!(load_resume_cursor(&pool, "src", "databases")
+            .await
+            .unwrap()
+            .is_some());
+        full_refresh().await;
+        assert!(load_resume_cursor(&pool, "src", "databases")
+            .await
+            .unwrap()
+            .is_none());
+        let both_runs = archived().await;
+        assert_eq!(both_runs.len(), 2);
+
+        // A refresh listing everything in one run replaces the archive
+        sqlx::query("UPDATE elt_stream_connections SET config = '{}' WHERE id = 'st'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        full_refresh().await;
+        let replaced = archived().await;
+        assert_eq!(replaced.len(), 1);
+        assert!(!both_runs.contains(&replaced[0]));
+        let records: Vec<Value> = context.storage.download_jsonl(&replaced[0]).await.unwrap();
+        assert_eq!(records.len(), 2);
+    }
+}
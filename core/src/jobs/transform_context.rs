@@ -4,7 +4,7 @@
 //! (storage, API keys, stream reader) needed by transform jobs.
 
 use crate::error::{Error, Result};
-use crate::sources::base::TransformDataSource;
+use crate::sources::base::{get_chunk_size, TransformDataSource};
 use crate::storage::{stream_writer::StreamWriter, Storage};
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -54,6 +54,9 @@ pub struct TransformContext {
     /// Use this to pass parameters like lookback windows, thresholds, etc.
     /// Defaults to empty JSON object.
     pub metadata: serde_json::Value,
+
+    /// Batching and concurrency limits for transform runs
+    pub config: TransformConfig,
 }
 
 impl TransformContext {
@@ -72,6 +75,7 @@ impl TransformContext {
             memory_data_source: None,
             api_keys,
             metadata: serde_json::json!({}),
+            config: TransformConfig::from_env(),
         }
     }
 
@@ -91,6 +95,7 @@ impl TransformContext {
             memory_data_source: Some(memory_data_source),
             api_keys,
             metadata: serde_json::json!({}),
+            config: TransformConfig::from_env(),
         }
     }
 
@@ -118,6 +123,7 @@ impl TransformContext {
             memory_data_source: None,
             api_keys,
             metadata,
+            config: TransformConfig::from_env(),
         }
     }
 
@@ -136,6 +142,48 @@ impl TransformContext {
     }
 }
 
+/// Default number of transform jobs allowed to run at once
+const DEFAULT_TRANSFORM_MAX_CONCURRENCY: usize = 4;
+
+/// Batching and concurrency limits for transform runs
+///
+/// `batch_size` is the number of records handed to a transform per batch
+/// (`TRANSFORM_CHUNK_SIZE`, default 10,000); transforms checkpoint after each
+/// batch and insert it in bounded statements, so no single transaction spans
+/// a whole sync. `max_concurrency` caps how many transform jobs run at once
+/// (`TRANSFORM_MAX_CONCURRENCY`, default 4); jobs over the limit stay
+/// `pending` until a slot frees up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransformConfig {
+    pub batch_size: usize,
+    pub max_concurrency: usize,
+}
+
+impl Default for TransformConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: get_chunk_size(),
+            max_concurrency: DEFAULT_TRANSFORM_MAX_CONCURRENCY,
+        }
+    }
+}
+
+impl TransformConfig {
+    /// Load limits from the environment, ignoring unset or zero values
+    pub fn from_env() -> Self {
+        let max_concurrency = std::env::var("TRANSFORM_MAX_CONCURRENCY")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_TRANSFORM_MAX_CONCURRENCY);
+
+        Self {
+            max_concurrency,
+            ..Self::default()
+        }
+    }
+}
+
 /// API keys for external services
 ///
 /// All keys are optional since not all transforms need all keys.
@@ -196,6 +244,20 @@ mod tests {
         assert_eq!(keys.anthropic, None);
     }
 
+    #[test]
+    fn test_transform_config_from_env() {
+        std::env::set_var("TRANSFORM_MAX_CONCURRENCY", "2");
+        assert_eq!(TransformConfig::from_env().max_concurrency, 2);
+
+        std::env::set_var("TRANSFORM_MAX_CONCURRENCY", "0");
+        assert_eq!(
+            TransformConfig::from_env().max_concurrency,
+            DEFAULT_TRANSFORM_MAX_CONCURRENCY
+        );
+
+        std::env::remove_var("TRANSFORM_MAX_CONCURRENCY");
+    }
+
     #[test]
     fn test_anthropic_required_success() {
         let keys = ApiKeys {
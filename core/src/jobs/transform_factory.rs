@@ -64,7 +64,7 @@ mod tests {
     use tokio::sync::Mutex;
 
     fn create_test_context() -> TransformContext {
-        let storage = Storage::local("/tmp/test".to_string()).unwrap();
+        let storage = Storage::in_memory();
         let stream_writer = Arc::new(Mutex::new(
             crate::storage::stream_writer::StreamWriter::new(),
         ));
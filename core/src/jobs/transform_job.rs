@@ -8,6 +8,7 @@ use sqlx::SqlitePool;
 use std::sync::Arc;
 
 use crate::error::Result;
+use crate::jobs::cost;
 use crate::jobs::models::Job;
 use crate::jobs::transform_context::TransformContext;
 use crate::jobs::transform_factory::TransformFactory;
@@ -66,10 +67,28 @@ pub async fn execute_transform_job(
     let db_wrapper = crate::database::Database::from_pool(db.clone());
 
     // Execute transformation
-    let result = transformer.transform(&db_wrapper, context, source_id.clone()).await;
+    let started = std::time::Instant::now();
+    let (result, spent) =
+        cost::metered(transformer.transform(&db_wrapper, context, source_id.clone())).await;
+    let duration = started.elapsed().as_secs_f64();
+
+    // Record what was spent even when the transform failed
+    if cost::enabled() && !spent.is_zero() {
+        if let Err(e) = cost::add_to_job(db, &job.id, &spent).await {
+            tracing::warn!(job_id = %job.id, error = %e, "Failed to record transform cost");
+        }
+    }
 
     match result {
         Ok(transform_result) => {
+            if let Some(m) = crate::observability::metrics() {
+                m.record_transform_throughput(
+                    transform_result.records_written as u64,
+                    duration,
+                    target_table,
+                );
+            }
+
             // Build metadata with detailed transform info
             let metadata = json!({
                 "source_table": source_table,
@@ -105,6 +124,7 @@ pub async fn execute_transform_job(
                 records_read = transform_result.records_read,
                 records_written = transform_result.records_written,
                 records_failed = transform_result.records_failed,
+                duration_ms = (duration * 1000.0) as u64,
                 chained_transforms_count = transform_result.chained_transforms.len(),
                 "Transform job completed successfully"
             );
@@ -28,6 +28,8 @@ use std::sync::Arc;
 /// * `source_id` - UUID of the data source
 /// * `stream_name` - Name of the stream (e.g., "healthkit", "location")
 /// * `records` - In-memory records to transform (if None, uses S3 cold path)
+/// * `parent_job_id` - Job the transforms are accounted to (the sync that fetched
+///   the records), when cost accounting is on
 ///
 /// # Returns
 ///
@@ -40,6 +42,7 @@ pub async fn create_transform_job_for_stream(
     source_id: String,
     stream_name: &str,
     records: Option<Vec<serde_json::Value>>,
+    parent_job_id: Option<String>,
 ) -> Result<String> {
     // Normalize stream name using centralized registry function
     let table_name = registry::normalize_stream_name(stream_name);
@@ -86,8 +89,9 @@ pub async fn create_transform_job_for_stream(
             sync_mode: None,
             transform_id: None,
             transform_strategy: None,
-            parent_job_id: None,
+            parent_job_id: parent_job_id.clone(),
             transform_stage: None,
+            request_id: None,
             metadata,
         };
 
@@ -118,7 +122,8 @@ pub async fn create_transform_job_for_stream(
                 None, // min_timestamp - could be extracted if needed
                 None, // max_timestamp - could be extracted if needed
                 db.clone(),
-            );
+            )
+            .with_chunk_size(context.config.batch_size);
 
             // Create a new context with memory data source using with_data_source constructor
             let transform_context_with_memory = Arc::new(TransformContext::with_data_source(
@@ -28,6 +28,7 @@ pub mod sources;
 pub mod storage;
 pub mod tollbooth;
 pub mod types;
+pub mod util;
 
 // Re-export main types
 pub use client::{Virtues, VirtuesBuilder};
@@ -74,10 +75,15 @@ pub use api::{
     list_source_streams,
     // Generic source management
     list_sources,
+    patch_stream_config,
+    plan_source_purge,
     register_device,
+    restore_source,
+    soft_delete_source,
 
     update_last_seen,
     update_stream_config,
+    update_stream_partitioning,
     update_stream_schedule,
     validate_device_token,
     CreateSourceRequest,
@@ -94,8 +100,11 @@ pub use api::{
     // Types
     SourceConnection,
     SourceConnectionStatus,
+    SourceStatus,
     StreamConnection,
+    StreamError,
     UpdateStreamConfigRequest,
+    UpdateStreamPartitioningRequest,
     UpdateStreamScheduleRequest,
 };
 
@@ -7,6 +7,7 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::env;
 
+use crate::jobs::cost;
 use crate::tollbooth;
 
 /// LLM request structure
@@ -18,6 +19,9 @@ pub struct LLMRequest {
     pub temperature: f32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub system: Option<String>,
+    /// OpenAI-style `response_format` (JSON mode or a JSON schema)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<serde_json::Value>,
 }
 
 /// LLM response structure
@@ -101,6 +105,8 @@ struct ChatCompletionRequest {
     max_tokens: u32,
     temperature: f32,
     stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -165,6 +171,7 @@ impl LLMClient for TollboothClient {
             max_tokens: request.max_tokens,
             temperature: request.temperature,
             stream: false,
+            response_format: request.response_format,
         };
 
         let response = tollbooth::with_tollbooth_auth(
@@ -192,6 +199,10 @@ impl LLMClient for TollboothClient {
             .json()
             .await
             .map_err(|e| format!("Failed to parse response: {}", e))?;
+        cost::record_llm_tokens(
+            chat_response.usage.prompt_tokens.into(),
+            chat_response.usage.completion_tokens.into(),
+        );
 
         // Extract content from first choice
         let content = chat_response
@@ -247,4 +258,31 @@ mod tests {
         );
         assert_eq!(client.base_url, "https://tollbooth.example.com");
     }
+
+    #[tokio::test]
+    async fn test_generate_reports_token_usage() {
+        let response = concat!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 201\r\n\r\n",
+            r#"{"id":"c1","object":"chat.completion","created":1,"model":"m","choices":[{"index":0,"message":{"role":"assistant","content":"hi"}}],"usage":{"prompt_tokens":12,"completion_tokens":3,"total_tokens":15}}"#
+        );
+        let (url, server) =
+            crate::sources::base::mock_transport::testing::serve(vec![response]).await;
+        let client =
+            TollboothClient::with_base_url(TEST_SECRET.to_string(), "test-user".to_string(), url);
+
+        let (response, spent) = cost::metered(client.generate(LLMRequest {
+            model: "m".to_string(),
+            prompt: "hello".to_string(),
+            max_tokens: 10,
+            temperature: 0.0,
+            system: None,
+            response_format: None,
+        }))
+        .await;
+        server.await.unwrap();
+
+        assert_eq!(response.unwrap().content, "hi");
+        assert_eq!(spent.llm_input_tokens, 12);
+        assert_eq!(spent.llm_output_tokens, 3);
+    }
 }
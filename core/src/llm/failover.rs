@@ -0,0 +1,369 @@
+//! Failover LLM Client
+//!
+//! Wraps an ordered list of `LLMClient`s and falls through to the next one on
+//! retryable errors (network failures, 429, 5xx), so a single endpoint outage
+//! doesn't fail background jobs. Each endpoint has a small circuit breaker:
+//! after repeated failures it is skipped for a cooldown period instead of
+//! being retried on every request.
+
+use async_trait::async_trait;
+use std::env;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use super::client::{LLMClient, LLMRequest, LLMResponse, TollboothClient};
+use crate::tollbooth;
+
+/// Consecutive retryable failures before an endpoint's breaker opens
+const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+
+/// How long an open breaker skips its endpoint
+const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Client shared by every caller, so breaker state outlives one request
+static SHARED: OnceLock<Arc<FailoverLLMClient>> = OnceLock::new();
+
+/// Circuit breaker state for one endpoint
+#[derive(Debug, Default)]
+struct Breaker {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+struct Endpoint {
+    name: String,
+    client: Arc<dyn LLMClient>,
+    breaker: Mutex<Breaker>,
+}
+
+/// LLM client that tries each wrapped client in order
+pub struct FailoverLLMClient {
+    endpoints: Vec<Endpoint>,
+    failure_threshold: u32,
+    cooldown: Duration,
+}
+
+impl FailoverLLMClient {
+    /// Create a failover client from named clients, in priority order
+    pub fn new(clients: Vec<(String, Arc<dyn LLMClient>)>) -> Self {
+        Self {
+            endpoints: clients
+                .into_iter()
+                .map(|(name, client)| Endpoint {
+                    name,
+                    client,
+                    breaker: Mutex::new(Breaker::default()),
+                })
+                .collect(),
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            cooldown: DEFAULT_COOLDOWN,
+        }
+    }
+
+    /// Override the circuit breaker threshold and cooldown
+    pub fn with_breaker(mut self, failure_threshold: u32, cooldown: Duration) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Create from environment
+    ///
+    /// The primary endpoint is the regular Tollbooth client (`TOLLBOOTH_URL`).
+    /// Fallbacks come from `TOLLBOOTH_FALLBACK_URLS`, a comma-separated list of
+    /// additional Tollbooth deployments sharing the same internal secret.
+    pub fn from_env() -> Result<Self, String> {
+        let primary = TollboothClient::from_env()?;
+        let mut clients: Vec<(String, Arc<dyn LLMClient>)> =
+            vec![("primary".to_string(), Arc::new(primary))];
+
+        if let Ok(urls) = env::var("TOLLBOOTH_FALLBACK_URLS") {
+            let secret = env::var("TOLLBOOTH_INTERNAL_SECRET")
+                .map_err(|_| "TOLLBOOTH_INTERNAL_SECRET not set in environment".to_string())?;
+            for url in urls.split(',').map(str::trim).filter(|u| !u.is_empty()) {
+                let client = TollboothClient::with_base_url(
+                    secret.clone(),
+                    tollbooth::SYSTEM_USER_ID.to_string(),
+                    url.to_string(),
+                );
+                clients.push((url.to_string(), Arc::new(client)));
+            }
+        }
+
+        Ok(Self::new(clients))
+    }
+
+    /// The process-wide client, built from the environment on first use
+    ///
+    /// Callers should use this rather than `from_env`: a breaker only opens
+    /// after repeated failures, which a client built per request never sees.
+    pub fn shared() -> Result<Arc<Self>, String> {
+        Self::shared_in(&SHARED, Self::from_env)
+    }
+
+    /// Get the client held in `cell`, building it with `init` if it's empty
+    ///
+    /// A failed `init` leaves the cell empty, so the next call tries again.
+    fn shared_in(
+        cell: &OnceLock<Arc<Self>>,
+        init: impl FnOnce() -> Result<Self, String>,
+    ) -> Result<Arc<Self>, String> {
+        if let Some(client) = cell.get() {
+            return Ok(client.clone());
+        }
+        let client = init()?;
+        Ok(cell.get_or_init(|| Arc::new(client)).clone())
+    }
+
+    /// Whether the endpoint's breaker currently allows requests
+    fn is_available(&self, endpoint: &Endpoint) -> bool {
+        let mut breaker = endpoint.breaker.lock().unwrap_or_else(|e| e.into_inner());
+        match breaker.open_until {
+            Some(until) if Instant::now() < until => false,
+            Some(_) => {
+                // Cooldown elapsed: half-open, allow a trial request
+                breaker.open_until = None;
+                true
+            }
+            None => true,
+        }
+    }
+
+    fn record_success(&self, endpoint: &Endpoint) {
+        let mut breaker = endpoint.breaker.lock().unwrap_or_else(|e| e.into_inner());
+        *breaker = Breaker::default();
+    }
+
+    fn record_failure(&self, endpoint: &Endpoint) {
+        let mut breaker = endpoint.breaker.lock().unwrap_or_else(|e| e.into_inner());
+        breaker.consecutive_failures += 1;
+        if breaker.consecutive_failures >= self.failure_threshold {
+            breaker.open_until = Some(Instant::now() + self.cooldown);
+            tracing::warn!(
+                endpoint = %endpoint.name,
+                cooldown_secs = self.cooldown.as_secs(),
+                "LLM endpoint circuit breaker opened"
+            );
+        }
+    }
+}
+
+/// Whether an `LLMClient` error is worth retrying on another endpoint
+///
+/// Transport failures, rate limits and server errors are retryable; other
+/// API errors (bad request, auth, budget) would fail the same way elsewhere.
+/// Reads the error format of `TollboothClient::generate`, which
+/// `test_is_retryable` pins.
+fn is_retryable(error: &str) -> bool {
+    if error.starts_with("HTTP request failed") {
+        return true;
+    }
+
+    // Client errors are formatted as "... error (<status> <reason>): <body>"
+    let status = error
+        .split_once('(')
+        .and_then(|(_, rest)| rest.split(|c: char| !c.is_ascii_digit()).next())
+        .and_then(|code| code.parse::<u16>().ok());
+
+    matches!(status, Some(429) | Some(500..=599))
+}
+
+#[async_trait]
+impl LLMClient for FailoverLLMClient {
+    async fn generate(&self, request: LLMRequest) -> Result<LLMResponse, String> {
+        let mut last_error = None;
+
+        for endpoint in &self.endpoints {
+            if !self.is_available(endpoint) {
+                tracing::debug!(endpoint = %endpoint.name, "Skipping LLM endpoint with open breaker");
+                continue;
+            }
+
+            match endpoint.client.generate(request.clone()).await {
+                Ok(response) => {
+                    self.record_success(endpoint);
+                    return Ok(response);
+                }
+                Err(e) if is_retryable(&e) => {
+                    tracing::warn!(
+                        endpoint = %endpoint.name,
+                        error = %e,
+                        "LLM endpoint failed, trying next"
+                    );
+                    self.record_failure(endpoint);
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| "No LLM endpoints available".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::client::Usage;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct StubClient {
+        result: Result<&'static str, &'static str>,
+        calls: AtomicUsize,
+    }
+
+    impl StubClient {
+        fn new(result: Result<&'static str, &'static str>) -> Arc<Self> {
+            Arc::new(Self {
+                result,
+                calls: AtomicUsize::new(0),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl LLMClient for StubClient {
+        async fn generate(&self, request: LLMRequest) -> Result<LLMResponse, String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.result
+                .map(|content| LLMResponse {
+                    content: content.to_string(),
+                    model: request.model,
+                    usage: Usage {
+                        input_tokens: 0,
+                        output_tokens: 0,
+                    },
+                })
+                .map_err(str::to_string)
+        }
+    }
+
+    fn request() -> LLMRequest {
+        LLMRequest {
+            model: "test-model".to_string(),
+            prompt: "hello".to_string(),
+            max_tokens: 10,
+            temperature: 0.0,
+            system: None,
+            response_format: None,
+        }
+    }
+
+    /// The error `TollboothClient::generate` returns against `url`
+    async fn tollbooth_error(url: String) -> String {
+        TollboothClient::with_base_url(
+            "this-is-a-test-secret-32-chars!".to_string(),
+            "test-user".to_string(),
+            url,
+        )
+        .generate(request())
+        .await
+        .map(|_| ())
+        .unwrap_err()
+    }
+
+    #[tokio::test]
+    async fn test_is_retryable() {
+        let (url, server) = crate::sources::base::mock_transport::testing::serve(vec![
+            "HTTP/1.1 503 Service Unavailable\r\nconnection: close\r\ncontent-length: 4\r\n\r\ndown",
+            "HTTP/1.1 429 Too Many Requests\r\nconnection: close\r\ncontent-length: 9\r\n\r\nslow down",
+            "HTTP/1.1 400 Bad Request\r\nconnection: close\r\ncontent-length: 9\r\n\r\nbad model",
+            "HTTP/1.1 402 Payment Required\r\nconnection: close\r\ncontent-length: 6\r\n\r\nbudget",
+            "HTTP/1.1 200 OK\r\nconnection: close\r\ncontent-length: 2\r\n\r\n{}",
+        ])
+        .await;
+        let mut retryable = Vec::new();
+        for _ in 0..5 {
+            retryable.push(is_retryable(&tollbooth_error(url.clone()).await));
+        }
+        server.await.unwrap();
+        assert_eq!(retryable, vec![true, true, false, false, false]);
+
+        // Nothing listening on the port
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        assert!(is_retryable(&tollbooth_error(url).await));
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_on_retryable_error() {
+        let primary = StubClient::new(Err("HTTP request failed: timeout"));
+        let fallback = StubClient::new(Ok("from fallback"));
+        let client = FailoverLLMClient::new(vec![
+            ("primary".to_string(), primary.clone() as Arc<dyn LLMClient>),
+            (
+                "fallback".to_string(),
+                fallback.clone() as Arc<dyn LLMClient>,
+            ),
+        ]);
+
+        let response = client.generate(request()).await.unwrap();
+        assert_eq!(response.content, "from fallback");
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_error_is_returned() {
+        let primary = StubClient::new(Err("Tollbooth API error (400 Bad Request): nope"));
+        let fallback = StubClient::new(Ok("from fallback"));
+        let client = FailoverLLMClient::new(vec![
+            ("primary".to_string(), primary as Arc<dyn LLMClient>),
+            (
+                "fallback".to_string(),
+                fallback.clone() as Arc<dyn LLMClient>,
+            ),
+        ]);
+
+        assert!(client.generate(request()).await.is_err());
+        assert_eq!(fallback.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_breaker_skips_failing_endpoint() {
+        let primary = StubClient::new(Err("HTTP request failed: timeout"));
+        let fallback = StubClient::new(Ok("from fallback"));
+        let client = FailoverLLMClient::new(vec![
+            ("primary".to_string(), primary.clone() as Arc<dyn LLMClient>),
+            ("fallback".to_string(), fallback as Arc<dyn LLMClient>),
+        ])
+        .with_breaker(2, Duration::from_secs(60));
+
+        for _ in 0..4 {
+            client.generate(request()).await.unwrap();
+        }
+
+        // Breaker opened after two failures; later requests skip the primary
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_shared_breaker_stays_open_across_callers() {
+        let cell = OnceLock::new();
+        let primary = StubClient::new(Err("HTTP request failed: timeout"));
+        let fallback = StubClient::new(Ok("from fallback"));
+
+        // The first caller builds the client and trips the primary's breaker
+        let first = FailoverLLMClient::shared_in(&cell, || {
+            Ok(FailoverLLMClient::new(vec![
+                ("primary".to_string(), primary.clone() as Arc<dyn LLMClient>),
+                ("fallback".to_string(), fallback as Arc<dyn LLMClient>),
+            ])
+            .with_breaker(2, Duration::from_secs(60)))
+        })
+        .unwrap();
+        for _ in 0..2 {
+            first.generate(request()).await.unwrap();
+        }
+        drop(first);
+
+        // A later caller gets the same client, so it skips the primary too
+        let second =
+            FailoverLLMClient::shared_in(&cell, || Err("built twice".to_string())).unwrap();
+        assert_eq!(
+            second.generate(request()).await.unwrap().content,
+            "from fallback"
+        );
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 2);
+    }
+}
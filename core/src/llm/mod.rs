@@ -9,5 +9,9 @@
 //! - And other supported providers
 
 pub mod client;
+pub mod failover;
+pub mod structured;
 
 pub use client::{AIGatewayClient, LLMClient, LLMRequest, LLMResponse};
+pub use failover::FailoverLLMClient;
+pub use structured::StructuredOutput;
@@ -0,0 +1,194 @@
+//! Structured (JSON-schema) output
+//!
+//! Asks the provider for a reply constrained to a JSON schema through the
+//! OpenAI-compatible `response_format` (Tollbooth forwards it to the
+//! provider's JSON mode) and deserializes the reply, instead of scraping JSON
+//! out of a freeform completion.
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+
+use super::client::{LLMClient, LLMRequest};
+
+/// Structured completions for any `LLMClient`, including `dyn LLMClient`
+#[async_trait]
+pub trait StructuredOutput: LLMClient {
+    /// Generate a reply matching `schema` and deserialize it into `T`
+    ///
+    /// `name` identifies the schema to the provider. A reply that isn't valid
+    /// JSON for `T` is retried once, with the parse error appended to the
+    /// prompt; a second malformed reply is an error.
+    async fn complete_structured<T>(
+        &self,
+        request: LLMRequest,
+        name: &str,
+        schema: serde_json::Value,
+    ) -> Result<T, String>
+    where
+        T: DeserializeOwned + Send,
+    {
+        let mut request = LLMRequest {
+            response_format: Some(response_format(name, schema)),
+            ..request
+        };
+
+        let response = self.generate(request.clone()).await?;
+        let error = match parse_reply::<T>(&response.content) {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+
+        tracing::warn!(
+            model = %request.model,
+            schema = name,
+            error = %error,
+            "Malformed structured reply, retrying once"
+        );
+        request.prompt = format!(
+            "{}\n\nYour previous reply could not be parsed ({}). Reply with only a JSON object matching the schema.",
+            request.prompt, error
+        );
+
+        let response = self.generate(request).await?;
+        parse_reply(&response.content)
+            .map_err(|e| format!("Malformed structured reply for '{}': {}", name, e))
+    }
+}
+
+impl<C: LLMClient + ?Sized> StructuredOutput for C {}
+
+/// `response_format` requesting strict JSON-schema output
+fn response_format(name: &str, schema: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "type": "json_schema",
+        "json_schema": {
+            "name": name,
+            "schema": schema,
+            "strict": true,
+        }
+    })
+}
+
+/// Deserialize a reply, tolerating a Markdown code fence around the JSON
+fn parse_reply<T: DeserializeOwned>(content: &str) -> Result<T, serde_json::Error> {
+    let trimmed = content.trim();
+    let json = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .unwrap_or(trimmed);
+
+    serde_json::from_str(json.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::client::{LLMResponse, Usage};
+    use serde::Deserialize;
+    use std::sync::Mutex;
+
+    /// Replies with the queued contents in order, recording each request
+    struct ScriptedClient {
+        replies: Mutex<Vec<&'static str>>,
+        requests: Mutex<Vec<LLMRequest>>,
+    }
+
+    impl ScriptedClient {
+        fn new(mut replies: Vec<&'static str>) -> Self {
+            replies.reverse();
+            Self {
+                replies: Mutex::new(replies),
+                requests: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl LLMClient for ScriptedClient {
+        async fn generate(&self, request: LLMRequest) -> Result<LLMResponse, String> {
+            let content = self.replies.lock().unwrap().pop().ok_or("no reply")?;
+            let model = request.model.clone();
+            self.requests.lock().unwrap().push(request);
+            Ok(LLMResponse {
+                content: content.to_string(),
+                model,
+                usage: Usage {
+                    input_tokens: 0,
+                    output_tokens: 0,
+                },
+            })
+        }
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Mood {
+        label: String,
+        score: f32,
+    }
+
+    fn request() -> LLMRequest {
+        LLMRequest {
+            model: "test-model".to_string(),
+            prompt: "How was the day?".to_string(),
+            max_tokens: 100,
+            temperature: 0.0,
+            system: None,
+            response_format: None,
+        }
+    }
+
+    fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": { "label": { "type": "string" }, "score": { "type": "number" } },
+            "required": ["label", "score"],
+            "additionalProperties": false
+        })
+    }
+
+    #[tokio::test]
+    async fn test_sends_schema_and_parses_reply() {
+        let client =
+            ScriptedClient::new(vec!["```json\n{\"label\": \"calm\", \"score\": 0.5}\n```"]);
+
+        let mood: Mood = client
+            .complete_structured(request(), "mood", schema())
+            .await
+            .unwrap();
+        assert_eq!(
+            mood,
+            Mood {
+                label: "calm".to_string(),
+                score: 0.5
+            }
+        );
+
+        let requests = client.requests.lock().unwrap();
+        let format = requests[0].response_format.as_ref().unwrap();
+        assert_eq!(format["type"], "json_schema");
+        assert_eq!(format["json_schema"]["name"], "mood");
+    }
+
+    #[tokio::test]
+    async fn test_retries_once_on_malformed_reply() {
+        let client =
+            ScriptedClient::new(vec!["calm, 0.5", "{\"label\": \"calm\", \"score\": 0.5}"]);
+        let dyn_client: &dyn LLMClient = &client;
+
+        let mood: Mood = dyn_client
+            .complete_structured(request(), "mood", schema())
+            .await
+            .unwrap();
+        assert_eq!(mood.label, "calm");
+        assert!(client.requests.lock().unwrap()[1]
+            .prompt
+            .contains("could not be parsed"));
+
+        let client = ScriptedClient::new(vec!["nope", "still nope"]);
+        let result: Result<Mood, _> = client
+            .complete_structured(request(), "mood", schema())
+            .await;
+        assert!(result.is_err());
+    }
+}
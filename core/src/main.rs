@@ -45,8 +45,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
+    // Local mode fills in database, storage and key settings before anything reads them
+    let local = virtues::setup::local::is_requested(cli.local);
+    if local {
+        virtues::setup::local::enable()?;
+    }
+
     // Handle Init command early (doesn't need Virtues client)
-    if matches!(cli.command, Some(Commands::Init)) {
+    if !local && matches!(cli.command, Some(Commands::Init)) {
         let config = virtues::setup::run_init().await?;
 
         // Save configuration
@@ -92,6 +98,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
+    // Local init has nothing to ask: create the database and go
+    if local && matches!(cli.command, Some(Commands::Init)) {
+        println!("📊 Running migrations...");
+        let db = virtues::database::Database::new(&database_url)?;
+        db.initialize().await?;
+        println!("✅ Migrations complete");
+
+        println!();
+        println!("Run commands with --local (or set VIRTUES_LOCAL=1) to use this setup.");
+        virtues::setup::display_completion();
+        return Ok(());
+    }
+
     // Initialize Virtues client
     // Storage path: STORAGE_PATH env var or ./data/lake default
     let mut builder = VirtuesBuilder::new().database(&database_url);
@@ -132,6 +151,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 host: "0.0.0.0".to_string(),
                 port,
             }),
+            local,
         }
     } else {
         cli
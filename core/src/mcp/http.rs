@@ -26,7 +26,7 @@ use super::VirtuesMcpServer;
 /// use virtues::mcp::{VirtuesMcpServer, http::add_mcp_routes};
 ///
 /// let router = Router::new();
-/// let mcp_server = VirtuesMcpServer::new(pool);
+/// let mcp_server = VirtuesMcpServer::new(pool, storage, stream_writer);
 /// let router = add_mcp_routes(router, mcp_server);
 /// ```
 pub fn add_mcp_routes(router: Router, server: VirtuesMcpServer) -> Router {
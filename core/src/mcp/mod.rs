@@ -12,6 +12,7 @@
 pub mod http;
 pub mod schema;
 pub mod server;
+pub mod sync;
 pub mod tools;
 
 pub use server::VirtuesMcpServer;
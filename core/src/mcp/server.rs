@@ -6,17 +6,22 @@
 //! Tools are defined in virtues-registry and executed via the ToolExecutor.
 
 use rmcp::{
-    handler::server::router::tool::ToolRouter,
+    handler::server::{router::tool::ToolRouter, wrapper::Parameters},
     model::{
         Annotated, ErrorData as McpError, Implementation, ListResourcesResult,
         PaginatedRequestParam, ProtocolVersion, RawResource, ReadResourceRequestParam,
         ReadResourceResult, Resource, ResourceContents, ServerCapabilities, ServerInfo,
     },
     service::RequestContext,
-    tool_handler, tool_router, RoleServer, ServerHandler,
+    tool, tool_handler, tool_router, Json, RoleServer, ServerHandler,
 };
 use sqlx::SqlitePool;
 use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use super::sync::{self, SyncStreamOutcome, SyncStreamRequest, SyncTarget, SYNC_STREAM_WAIT};
+use crate::error::Error;
+use crate::storage::{stream_writer::StreamWriter, Storage};
 
 /// Virtues MCP Server
 ///
@@ -26,29 +31,70 @@ use std::sync::Arc;
 /// ToolExecutor in core/src/tools/, which is integrated into the chat API.
 /// 
 /// MCP clients (like Claude Desktop) can connect to discover available tools
-/// and resources. The actual tool execution happens through the chat endpoint.
-#[derive(Debug, Clone)]
+/// and resources. The actual tool execution happens through the chat endpoint,
+/// except for `sync_stream`, which starts a sync job on the server.
+#[derive(Clone)]
 pub struct VirtuesMcpServer {
-    #[allow(dead_code)]
     pool: Arc<SqlitePool>,
+    sync_target: SyncTarget,
     tool_router: ToolRouter<VirtuesMcpServer>,
 }
 
 #[tool_router]
 impl VirtuesMcpServer {
-    /// Create a new Virtues MCP server
-    pub fn new(pool: SqlitePool) -> Self {
+    /// Create a new Virtues MCP server inside the Virtues server process
+    pub fn new(
+        pool: SqlitePool,
+        storage: Arc<Storage>,
+        stream_writer: Arc<Mutex<StreamWriter>>,
+    ) -> Self {
+        Self::with_sync_target(
+            pool,
+            SyncTarget::Local {
+                storage,
+                stream_writer,
+            },
+        )
+    }
+
+    /// Create a new Virtues MCP server that starts syncs through `sync_target`
+    pub fn with_sync_target(pool: SqlitePool, sync_target: SyncTarget) -> Self {
         let tool_router = Self::tool_router();
         let tool_count = tool_router.list_all().len();
         tracing::info!("MCP Server initialized with {} tools", tool_count);
         Self {
             pool: Arc::new(pool),
+            sync_target,
             tool_router,
         }
     }
 
-    // Tools will be added here when MCP tool execution is implemented.
-    // For now, tools are executed through the chat API's ToolExecutor.
+    /// Sync a stream now and wait briefly for its record counts
+    #[tool(
+        description = "Run an incremental sync of one stream and return its record counts. Waits up to a minute; if the sync is still running, or queued because the sync limit is reached, returns its job id and status instead."
+    )]
+    async fn sync_stream(
+        &self,
+        Parameters(request): Parameters<SyncStreamRequest>,
+    ) -> Result<Json<SyncStreamOutcome>, McpError> {
+        sync::sync_stream(&self.pool, &self.sync_target, &request, SYNC_STREAM_WAIT)
+            .await
+            .map(Json)
+            .map_err(|e| match e {
+                Error::NotFound(_) | Error::InvalidInput(_) | Error::SyncInProgress(_) => {
+                    McpError::invalid_params(e.to_string(), None)
+                }
+                e => McpError::internal_error(e.to_string(), None),
+            })
+    }
+}
+
+impl std::fmt::Debug for VirtuesMcpServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VirtuesMcpServer")
+            .field("tool_router", &self.tool_router)
+            .finish_non_exhaustive()
+    }
 }
 
 #[tool_handler]
@@ -77,8 +123,9 @@ Available Tools:
 1. web_search - Search the web using Exa AI
 2. sql_query - Query your personal data with SQL (health, location, calendar, etc.)
 3. edit_page - AI-assisted page editing with accept/reject
+4. sync_stream - Sync a stream now (source and stream name) and get its record counts
 
-Note: Tools are currently executed through the Virtues chat API.
+Note: Tools other than sync_stream are currently executed through the Virtues chat API.
 For full tool functionality, use the Virtues web interface.
 
 Privacy & Data Sensitivity:
@@ -143,6 +190,7 @@ Privacy & Data Sensitivity:
 1. **web_search** - Search the web for current information
 2. **sql_query** - Query your personal data (health, location, calendar, etc.)
 3. **edit_page** - AI-assisted page editing
+4. **sync_stream** - Sync a stream now for fresh data
 
 ## Guidelines
 
@@ -0,0 +1,251 @@
+//! Sync a stream on behalf of an MCP client
+//!
+//! Backs the `sync_stream` tool: starts an incremental sync through the jobs
+//! API and waits a bounded time for it to finish, so an assistant can pull
+//! fresh data before answering. The sync runs under the server's sync limit
+//! and stream locks like any other; when it has to wait for them the tool
+//! doesn't wait its turn, it reports the sync as queued with its job id and
+//! returns.
+//!
+//! Only the server process holds those limits and locks, and only it keeps
+//! running a job after the request that started it. The stdio MCP binary is
+//! a separate process, so it asks the running server to start the sync over
+//! `POST /internal/sync-stream` instead of starting it itself.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use crate::error::{Error, Result};
+use crate::jobs::{self, Job, JobStatus};
+use crate::storage::{stream_writer::StreamWriter, Storage};
+
+/// Longest a `sync_stream` call waits for the sync to finish
+pub const SYNC_STREAM_WAIT: Duration = Duration::from_secs(60);
+
+/// How often the job is checked while waiting
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Where `sync_stream` starts syncs
+#[derive(Clone)]
+pub enum SyncTarget {
+    /// This process is the server: start the job here
+    Local {
+        storage: Arc<Storage>,
+        stream_writer: Arc<Mutex<StreamWriter>>,
+    },
+    /// Ask the server at this URL to start the job
+    Remote { server_url: String },
+}
+
+impl SyncTarget {
+    /// The server named by `VIRTUES_SERVER_URL` (default http://localhost:8000)
+    pub fn remote_from_env() -> Self {
+        Self::Remote {
+            server_url: std::env::var("VIRTUES_SERVER_URL")
+                .unwrap_or_else(|_| "http://localhost:8000".to_string()),
+        }
+    }
+}
+
+/// Arguments of the `sync_stream` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SyncStreamRequest {
+    /// Source connection id or name (e.g. "Google")
+    pub source: String,
+    /// Stream name within the source (e.g. "calendar")
+    pub stream: String,
+}
+
+/// What became of a `sync_stream` call
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SyncStreamOutcome {
+    pub job_id: String,
+    /// `succeeded` or `failed` once finished; `queued` when the sync limit
+    /// was reached, `running` when it was still going after the wait
+    pub status: String,
+    pub records_fetched: Option<u64>,
+    pub records_written: Option<u64>,
+    pub records_failed: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl SyncStreamOutcome {
+    fn pending(job_id: String, status: &str) -> Self {
+        Self {
+            job_id,
+            status: status.to_string(),
+            records_fetched: None,
+            records_written: None,
+            records_failed: None,
+            error: None,
+        }
+    }
+
+    fn finished(job: Job) -> Self {
+        let count = |key: &str| job.metadata.get(key).and_then(|v| v.as_u64());
+        Self {
+            records_fetched: count("records_fetched"),
+            records_written: count("records_written"),
+            records_failed: count("records_failed"),
+            status: job.status.to_string(),
+            error: job.error_message,
+            job_id: job.id,
+        }
+    }
+}
+
+/// Start an incremental sync of one stream and wait up to `wait` for it
+pub async fn sync_stream(
+    db: &SqlitePool,
+    target: &SyncTarget,
+    request: &SyncStreamRequest,
+    wait: Duration,
+) -> Result<SyncStreamOutcome> {
+    let (storage, stream_writer) = match target {
+        SyncTarget::Local {
+            storage,
+            stream_writer,
+        } => (storage, stream_writer),
+        SyncTarget::Remote { server_url } => return sync_on_server(server_url, request).await,
+    };
+
+    let source_id = resolve_source(db, &request.source).await?;
+    let job = crate::api::jobs::trigger_stream_sync(
+        db,
+        storage,
+        stream_writer.clone(),
+        source_id,
+        &request.stream,
+        None,
+    )
+    .await?;
+
+    // A sync job stays pending while it waits for a permit, so one that
+    // hasn't started by the first check is queued behind the limit
+    let started = Instant::now();
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        let current = jobs::get_job(db, &job.job_id).await?;
+        match current.status {
+            JobStatus::Pending => {
+                tracing::info!(
+                    job_id = %job.job_id,
+                    stream = %request.stream,
+                    "Sync requested over MCP is queued behind the concurrency limit"
+                );
+                return Ok(SyncStreamOutcome::pending(job.job_id, "queued"));
+            }
+            JobStatus::Running => {}
+            _ => return Ok(SyncStreamOutcome::finished(current)),
+        }
+        if started.elapsed() >= wait {
+            return Ok(SyncStreamOutcome::pending(job.job_id, "running"));
+        }
+    }
+}
+
+/// Have the running server start the sync and wait for it
+async fn sync_on_server(
+    server_url: &str,
+    request: &SyncStreamRequest,
+) -> Result<SyncStreamOutcome> {
+    let mut http = reqwest::Client::new()
+        .post(format!(
+            "{}/internal/sync-stream",
+            server_url.trim_end_matches('/')
+        ))
+        .json(request);
+    if let Ok(secret) = std::env::var("TOLLBOOTH_INTERNAL_SECRET") {
+        http = http.header("X-Tollbooth-Secret", secret);
+    }
+
+    let response = http.send().await.map_err(|e| {
+        Error::Http(format!(
+            "Failed to reach the Virtues server at {server_url}: {e}"
+        ))
+    })?;
+    let status = response.status();
+    if !status.is_success() {
+        let message = response
+            .json::<serde_json::Value>()
+            .await
+            .ok()
+            .and_then(|body| body.get("error")?.as_str().map(String::from))
+            .unwrap_or_else(|| status.to_string());
+        // The server's message already carries the error's prefix
+        let unprefixed =
+            |prefix: &str| message.strip_prefix(prefix).unwrap_or(&message).to_string();
+        return Err(match status.as_u16() {
+            400 => Error::InvalidInput(unprefixed("Invalid input: ")),
+            404 => Error::NotFound(unprefixed("Not found: ")),
+            409 => Error::SyncInProgress(unprefixed("Sync already in progress: ")),
+            _ => Error::Http(format!("Virtues server returned {status}: {message}")),
+        });
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| Error::Http(format!("Invalid sync_stream response from server: {e}")))
+}
+
+/// Find a source connection by id, or failing that by name
+async fn resolve_source(db: &SqlitePool, source: &str) -> Result<String> {
+    sqlx::query_scalar(
+        "SELECT id FROM elt_source_connections
+         WHERE (id = $1 OR name = $1) AND deleted_at IS NULL
+         ORDER BY id = $1 DESC
+         LIMIT 1",
+    )
+    .bind(source)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| Error::NotFound(format!("Source not found: {source}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::base::mock_transport::testing::{migrated_pool, serve};
+
+    #[tokio::test]
+    async fn test_resolve_source_by_id_or_name() {
+        let pool = migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, name) VALUES ('src-google', 'google', 'Google')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(
+            resolve_source(&pool, "src-google").await.unwrap(),
+            "src-google"
+        );
+        assert_eq!(resolve_source(&pool, "Google").await.unwrap(), "src-google");
+        assert!(matches!(
+            resolve_source(&pool, "Notion").await,
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_keep_their_kind() {
+        const SOURCE_NOT_FOUND: &str = "HTTP/1.1 404 Not Found\r\ncontent-type: application/json\r\ncontent-length: 42\r\nconnection: close\r\n\r\n{\"error\":\"Not found: Source not found: x\"}";
+        let (url, server) = serve(vec![SOURCE_NOT_FOUND]).await;
+        let request = SyncStreamRequest {
+            source: "x".to_string(),
+            stream: "calendar".to_string(),
+        };
+
+        match sync_on_server(&url, &request).await {
+            Err(Error::NotFound(message)) => assert_eq!(message, "Source not found: x"),
+            other => panic!("expected NotFound, got {other:?}"),
+        }
+        assert!(server.await.unwrap()[0].starts_with("post /internal/sync-stream"));
+    }
+}
@@ -2,8 +2,11 @@
 //!
 //! This module provides middleware for:
 //! - Authentication via session cookies
+//! - Request ID propagation
 //! - Rate limiting
 
 pub mod auth;
+pub mod request_id;
 
 pub use auth::{require_auth, AuthUser};
+pub use request_id::RequestId;
@@ -0,0 +1,146 @@
+//! Request ID middleware for Axum
+//!
+//! Every request gets a correlation id: the caller's `X-Request-Id` header when
+//! it is usable, otherwise a fresh UUID. The id is echoed back on the response,
+//! attached to the request's tracing span, and kept in a task-local so jobs
+//! created while handling the request record it (see `jobs::create_job`). The
+//! job executor re-enters the id when it runs the job, so everything logged
+//! during a sync carries the id of the request that started it.
+
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use std::future::Future;
+use tracing::Instrument;
+
+/// Header carrying the request id
+pub static REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest caller-supplied request id that is accepted
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: String;
+}
+
+/// The request id, available as a request extension
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+/// The request id of the request or job currently being handled, if any
+pub fn current() -> Option<String> {
+    CURRENT_REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Run a future with `request_id` as the current request id
+pub async fn scope<F: Future>(request_id: Option<String>, future: F) -> F::Output {
+    match request_id {
+        Some(id) => CURRENT_REQUEST_ID.scope(id, future).await,
+        None => future.await,
+    }
+}
+
+/// Use the caller's request id if it is short, printable ASCII
+fn accept_request_id(value: Option<&HeaderValue>) -> Option<String> {
+    let id = value?.to_str().ok()?.trim();
+    let valid = !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id.bytes().all(|b| b.is_ascii_graphic());
+    valid.then(|| id.to_string())
+}
+
+/// Assign, propagate and log a request id for every request
+///
+/// ```ignore
+/// let app = app.layer(axum::middleware::from_fn(request_id));
+/// ```
+pub async fn request_id(mut req: Request, next: Next) -> Response {
+    let id = accept_request_id(req.headers().get(&REQUEST_ID_HEADER))
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    let span = tracing::info_span!(
+        "request",
+        request_id = %id,
+        method = %req.method(),
+        path = %req.uri().path(),
+    );
+    req.extensions_mut().insert(RequestId(id.clone()));
+
+    let mut response = scope(Some(id.clone()), next.run(req))
+        .instrument(span)
+        .await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response
+            .headers_mut()
+            .insert(REQUEST_ID_HEADER.clone(), value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_accept_request_id() {
+        let header = |s: &str| HeaderValue::from_str(s).unwrap();
+
+        assert_eq!(
+            accept_request_id(Some(&header("abc-123"))),
+            Some("abc-123".to_string())
+        );
+        assert_eq!(accept_request_id(None), None);
+        assert_eq!(accept_request_id(Some(&header(""))), None);
+        assert_eq!(accept_request_id(Some(&header("has space"))), None);
+        assert_eq!(
+            accept_request_id(Some(&header(&"x".repeat(MAX_REQUEST_ID_LEN + 1)))),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_propagated() {
+        let app = Router::new()
+            .route("/", get(|| async { current().unwrap_or_default() }))
+            .layer(axum::middleware::from_fn(request_id));
+
+        // A caller-supplied id is echoed and visible to the handler
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/")
+                    .header("X-Request-Id", "trace-me")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.headers()[&REQUEST_ID_HEADER], "trace-me");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"trace-me");
+
+        // Otherwise one is generated
+        let response = app
+            .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let generated = response.headers()[&REQUEST_ID_HEADER].to_str().unwrap();
+        assert!(uuid::Uuid::parse_str(generated).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_scope() {
+        assert_eq!(current(), None);
+        let inner = scope(Some("req_1".to_string()), async { current() }).await;
+        assert_eq!(inner, Some("req_1".to_string()));
+        assert_eq!(scope(None, async { current() }).await, None);
+    }
+}
@@ -3,7 +3,7 @@
 //! Provides OpenTelemetry integration for job execution metrics,
 //! distributed tracing, and operational visibility.
 
-use opentelemetry::metrics::{Counter, Histogram, Meter, MeterProvider};
+use opentelemetry::metrics::{Counter, Histogram, Meter, MeterProvider, UpDownCounter};
 use opentelemetry::KeyValue;
 use opentelemetry_sdk::metrics::SdkMeterProvider;
 use std::sync::OnceLock;
@@ -26,10 +26,16 @@ pub struct Metrics {
     pub records_synced: Counter<u64>,
     /// Records transformed per job
     pub records_transformed: Counter<u64>,
+    /// Transform throughput in records per second
+    pub transform_throughput: Histogram<f64>,
     /// S3 upload bytes
     pub s3_upload_bytes: Counter<u64>,
     /// S3 upload duration in seconds
     pub s3_upload_duration_seconds: Histogram<f64>,
+    /// Sync concurrency permits currently held
+    pub sync_permits_in_use: UpDownCounter<i64>,
+    /// Time a sync waited for a concurrency permit in seconds
+    pub sync_permit_wait_seconds: Histogram<f64>,
 }
 
 impl Metrics {
@@ -66,6 +72,11 @@ impl Metrics {
                 .with_description("Total number of records transformed")
                 .with_unit("records")
                 .build(),
+            transform_throughput: meter
+                .f64_histogram("virtues_transform_throughput_records_per_second")
+                .with_description("Records written per second by a transform job")
+                .with_unit("records/s")
+                .build(),
             s3_upload_bytes: meter
                 .u64_counter("virtues_s3_upload_bytes_total")
                 .with_description("Total bytes uploaded to S3")
@@ -76,6 +87,16 @@ impl Metrics {
                 .with_description("Duration of S3 uploads")
                 .with_unit("s")
                 .build(),
+            sync_permits_in_use: meter
+                .i64_up_down_counter("virtues_sync_permits_in_use")
+                .with_description("Syncs currently holding a concurrency permit")
+                .with_unit("syncs")
+                .build(),
+            sync_permit_wait_seconds: meter
+                .f64_histogram("virtues_sync_permit_wait_seconds")
+                .with_description("Time a sync waited for a concurrency permit")
+                .with_unit("s")
+                .build(),
         }
     }
 
@@ -115,11 +136,32 @@ impl Metrics {
         );
     }
 
+    /// Record a transform run's records written and throughput
+    pub fn record_transform_throughput(&self, records: u64, duration: f64, target_table: &str) {
+        let attrs = &[KeyValue::new("target_table", target_table.to_string())];
+        self.records_transformed.add(records, attrs);
+        if duration > 0.0 {
+            self.transform_throughput
+                .record(records as f64 / duration, attrs);
+        }
+    }
+
     /// Record S3 upload
     pub fn record_s3_upload(&self, bytes: u64, duration: f64) {
         self.s3_upload_bytes.add(bytes, &[]);
         self.s3_upload_duration_seconds.record(duration, &[]);
     }
+
+    /// Record a sync taking a concurrency permit after waiting `wait` seconds
+    pub fn record_sync_permit_acquired(&self, wait: f64) {
+        self.sync_permits_in_use.add(1, &[]);
+        self.sync_permit_wait_seconds.record(wait, &[]);
+    }
+
+    /// Record a sync releasing its concurrency permit
+    pub fn record_sync_permit_released(&self) {
+        self.sync_permits_in_use.add(-1, &[]);
+    }
 }
 
 /// Configuration for observability
@@ -27,7 +27,7 @@
 //! - What configuration options each stream accepts
 //! - What database schema each stream uses
 
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -35,7 +35,7 @@ use tokio::sync::Mutex;
 use crate::error::Result;
 use crate::jobs::TransformContext;
 use crate::sources::auth::SourceAuth;
-use crate::sources::base::OntologyTransform;
+use crate::sources::base::{OntologyTransform, StreamLimits};
 use crate::sources::stream_type::StreamType;
 use crate::storage::{stream_writer::StreamWriter, Storage};
 
@@ -59,6 +59,12 @@ pub type TransformCreator = fn(&TransformContext) -> Result<Box<dyn OntologyTran
 /// all necessary dependencies.
 pub type StreamCreator = fn(&StreamFactoryContext) -> Result<StreamType>;
 
+/// Type alias for config parser functions
+///
+/// Deserializes a stream config into the stream's concrete config type,
+/// discarding the result; used to check registry examples against the code.
+pub type ConfigParser = fn(&serde_json::Value) -> std::result::Result<(), serde_json::Error>;
+
 /// Context passed to stream creator functions
 ///
 /// This provides all the dependencies needed to create any type of stream.
@@ -124,6 +130,9 @@ pub struct RegisteredStream {
     /// Example configuration
     pub config_example: serde_json::Value,
 
+    /// Parses a config into the stream's concrete config type, if it has one
+    pub config_parser: Option<ConfigParser>,
+
     /// Transforms that map this stream's data to ontology tables
     /// 
     /// This unifies the catalog metadata with transform logic - the stream
@@ -137,6 +146,33 @@ pub struct RegisteredStream {
     /// statements in StreamFactory. If None, the stream cannot be instantiated
     /// dynamically (e.g., disabled or not yet implemented).
     pub stream_creator: Option<StreamCreator>,
+
+    /// Record field holding the stream's natural id (e.g. `message_id`)
+    ///
+    /// When set, archived ids are indexed so backfills and full re-syncs skip
+    /// records already in the archive. None disables deduplication.
+    pub dedup_key: Option<&'static str>,
+
+    /// Record field holding the record's timestamp (e.g. `start_time`)
+    ///
+    /// Archived records are split into `date=` partitions by this field's
+    /// date. None archives a whole sync under the sync's date.
+    pub partition_key: Option<&'static str>,
+
+    /// Whether a full refresh that ran to completion replaces the archive
+    ///
+    /// Only for streams whose API returns everything they hold in one
+    /// listing. Streams whose API keeps limited history (GitHub events) would
+    /// lose the records that aged out, so they leave it off and a full
+    /// refresh appends like any other run.
+    pub replace_on_full_refresh: bool,
+
+    /// Version of the record shape the stream currently writes
+    ///
+    /// Stamped on every record as `_schema_version`. Bump it when a change to
+    /// the stream changes its records' shape, so transforms can still read
+    /// archives written in the older shape.
+    pub schema_version: u32,
 }
 
 // Custom Debug implementation to skip function pointer fields
@@ -146,6 +182,11 @@ impl std::fmt::Debug for RegisteredStream {
             .field("descriptor", &self.descriptor)
             .field("transforms_count", &self.transforms.len())
             .field("has_stream_creator", &self.stream_creator.is_some())
+            .field("has_config_type", &self.config_parser.is_some())
+            .field("dedup_key", &self.dedup_key)
+            .field("partition_key", &self.partition_key)
+            .field("replace_on_full_refresh", &self.replace_on_full_refresh)
+            .field("schema_version", &self.schema_version)
             .finish()
     }
 }
@@ -157,10 +198,11 @@ impl Serialize for RegisteredStream {
         S: serde::Serializer,
     {
         use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("RegisteredStream", 3)?;
+        let mut state = serializer.serialize_struct("RegisteredStream", 4)?;
         state.serialize_field("descriptor", &self.descriptor)?;
         state.serialize_field("config_schema", &self.config_schema)?;
         state.serialize_field("config_example", &self.config_example)?;
+        state.serialize_field("implemented", &self.is_implemented())?;
         state.end()
     }
 }
@@ -188,11 +230,42 @@ impl RegisteredStream {
             descriptor,
             config_schema: serde_json::json!({}),
             config_example: serde_json::json!({}),
+            config_parser: None,
             transforms: vec![],
             stream_creator: None,
+            dedup_key: None,
+            partition_key: None,
+            replace_on_full_refresh: false,
+            schema_version: crate::storage::stream_writer::DEFAULT_SCHEMA_VERSION,
         }
     }
 
+    /// Whether the stream can actually be synced
+    ///
+    /// A stream is implemented when it registers a stream creator; without
+    /// one, syncs (pull) and ingests (push) of it fail. Such streams stay in
+    /// the catalog as metadata but shouldn't be offered for enabling.
+    pub fn is_implemented(&self) -> bool {
+        self.stream_creator.is_some()
+    }
+
+    /// Check that `config_example` is a config the stream accepts
+    ///
+    /// The example must validate against `config_schema`, and deserialize into
+    /// `StreamLimits` and the stream's concrete config type (if registered),
+    /// as a stored config would be when the stream runs.
+    pub fn validate_config_example(&self) -> Result<()> {
+        let example = &self.config_example;
+        crate::api::validation::validate_config(example, &self.config_schema)?;
+
+        let parsed = serde_json::from_value::<StreamLimits>(example.clone())
+            .map(|_| ())
+            .and_then(|()| self.config_parser.map_or(Ok(()), |parse| parse(example)));
+        parsed.map_err(|e| {
+            crate::error::Error::InvalidInput(format!("Config example does not deserialize: {e}"))
+        })
+    }
+
     /// Find a transform for a specific target ontology table
     pub fn get_transform(&self, target_table: &str) -> Option<&StreamTransform> {
         self.transforms.iter().find(|t| t.target_table == target_table)
@@ -232,8 +305,13 @@ pub struct StreamBuilder {
     descriptor: StreamDescriptor,
     config_schema: serde_json::Value,
     config_example: serde_json::Value,
+    config_parser: Option<ConfigParser>,
     transforms: Vec<StreamTransform>,
     stream_creator: Option<StreamCreator>,
+    dedup_key: Option<&'static str>,
+    partition_key: Option<&'static str>,
+    replace_on_full_refresh: bool,
+    schema_version: u32,
 }
 
 impl StreamBuilder {
@@ -247,6 +325,12 @@ impl StreamBuilder {
         self
     }
 
+    /// Declare the concrete type the stream deserializes its config into
+    pub fn config_type<T: DeserializeOwned>(mut self) -> Self {
+        self.config_parser = Some(parse_config::<T>);
+        self
+    }
+
     /// Add a transform that maps this stream to an ontology table
     pub fn transform(mut self, target_table: &'static str, creator: TransformCreator) -> Self {
         self.transforms.push(StreamTransform {
@@ -262,17 +346,52 @@ impl StreamBuilder {
         self
     }
 
+    /// Declare the record field that uniquely identifies a record in this stream
+    pub fn dedup_key(mut self, field: &'static str) -> Self {
+        self.dedup_key = Some(field);
+        self
+    }
+
+    /// Declare the record field whose date decides a record's archive partition
+    pub fn partition_key(mut self, field: &'static str) -> Self {
+        self.partition_key = Some(field);
+        self
+    }
+
+    /// Let a complete full refresh replace the stream's archive
+    pub fn replace_on_full_refresh(mut self) -> Self {
+        self.replace_on_full_refresh = true;
+        self
+    }
+
+    /// Declare the version of the record shape this stream writes
+    pub fn schema_version(mut self, version: u32) -> Self {
+        self.schema_version = version;
+        self
+    }
+
     pub fn build(self) -> RegisteredStream {
         RegisteredStream {
             descriptor: self.descriptor,
             config_schema: self.config_schema,
             config_example: self.config_example,
+            config_parser: self.config_parser,
             transforms: self.transforms,
             stream_creator: self.stream_creator,
+            dedup_key: self.dedup_key,
+            partition_key: self.partition_key,
+            replace_on_full_refresh: self.replace_on_full_refresh,
+            schema_version: self.schema_version,
         }
     }
 }
 
+fn parse_config<T: DeserializeOwned>(
+    config: &serde_json::Value,
+) -> std::result::Result<(), serde_json::Error> {
+    T::deserialize(config).map(|_| ())
+}
+
 /// Trait for sources to register themselves in the catalog
 pub trait SourceRegistry {
     /// Get the source descriptor
@@ -363,9 +482,11 @@ fn init_registry() -> Registry {
     let mut registry = Registry::new();
 
     // Register OAuth sources
+    registry.register(crate::sources::fitbit::registry::FitbitSource::descriptor());
     registry.register(crate::sources::github::registry::GitHubSource::descriptor());
     registry.register(crate::sources::google::registry::GoogleSource::descriptor());
     registry.register(crate::sources::notion::registry::NotionSource::descriptor());
+    registry.register(crate::sources::slack::registry::SlackSource::descriptor());
     registry.register(crate::sources::plaid::registry::PlaidSource::descriptor());
     registry.register(crate::sources::spotify::registry::SpotifySource::descriptor());
     registry.register(crate::sources::strava::registry::StravaSource::descriptor());
@@ -407,6 +528,32 @@ pub fn list_all_streams_including_disabled() -> Vec<(&'static str, &'static Regi
     registry().list_all_streams_including_disabled()
 }
 
+/// Check every registered stream's config example, including disabled streams
+///
+/// Catches drift between an example and the schema or config type it
+/// documents. Reports every failing stream.
+pub fn validate_examples() -> Result<()> {
+    let mut errors: Vec<String> = list_all_streams_including_disabled()
+        .into_iter()
+        .filter_map(|(source, stream)| {
+            stream
+                .validate_config_example()
+                .err()
+                .map(|e| format!("{source}/{}: {e}", stream.descriptor.name))
+        })
+        .collect();
+    errors.sort();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(crate::error::Error::Configuration(format!(
+            "Invalid registry config examples: {}",
+            errors.join("; ")
+        )))
+    }
+}
+
 /// Get a stream by its table name (e.g., "stream_google_calendar")
 ///
 /// Returns the source name and stream reference if found.
@@ -528,6 +675,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_stream_implemented() {
+        assert!(get_stream("google", "gmail").unwrap().is_implemented());
+        assert!(get_stream("ios", "healthkit").unwrap().is_implemented());
+        assert!(get_stream("notion", "pages").unwrap().is_implemented());
+
+        // Registered as metadata only, without a stream creator
+        let investments = get_stream("plaid", "investments").unwrap();
+        assert!(!investments.is_implemented());
+        let json = serde_json::to_value(investments).unwrap();
+        assert_eq!(json["implemented"], false);
+    }
+
+    #[test]
+    fn test_config_examples_validate() {
+        validate_examples().unwrap();
+
+        // Drift in either direction is caught
+        let mut calendar = get_stream("google", "calendar").unwrap().clone();
+        calendar.config_example = serde_json::json!({ "max_events_per_sync": 0 });
+        assert!(calendar.validate_config_example().is_err());
+
+        calendar.config_example = serde_json::json!({ "calendar_ids": "primary" });
+        calendar.config_schema = serde_json::json!({});
+        assert!(calendar.validate_config_example().is_err());
+    }
+
     #[test]
     fn test_list_all_streams() {
         let streams = list_all_streams();
@@ -16,19 +16,112 @@
 //! - `0 */15 * * * *` - Every 15 minutes
 //! - `0 0 0 * * *` - Daily at midnight
 //! - `0 0 9 * * 1` - Every Monday at 9:00 AM
+//!
+//! ## Timezones
+//!
+//! Expressions are evaluated in the stream's `cron_timezone` (an IANA name),
+//! falling back to [`SchedulerConfig::default_timezone`]. "Daily at 9am" in
+//! `America/New_York` fires at 9am local time on both sides of a DST change.
+//!
+//! ## Provider stagger
+//!
+//! Connections of the same provider (the registry source, e.g. two Google
+//! accounts) may share the provider's project-level API quota. Their
+//! scheduled syncs are offset from each other by
+//! [`SchedulerConfig::provider_stagger`], so identical cron schedules don't
+//! fire against the provider at the same moment. An offset longer than the
+//! stream's schedule interval wraps around within it, so a stagger never
+//! pushes a sync past its next run.
+//!
+//! ## Concurrency
+//!
+//! Scheduled syncs are ordinary sync jobs, so they run under the same
+//! process-wide [`SyncConcurrencyManager`](crate::jobs::SyncConcurrencyManager)
+//! as syncs started from the API: at most `SYNC_MAX_CONCURRENCY` run at once,
+//! whoever started them.
 
-use chrono::Timelike;
+use chrono::{DateTime, Timelike, Utc};
+use chrono_tz::Tz;
 use sqlx::SqlitePool;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 use tokio_cron_scheduler::{Job, JobScheduler};
+use uuid::Uuid;
 
 use crate::{
+    api::SourceStatus,
     error::{Error, Result},
     storage::{stream_writer::StreamWriter, Storage},
     types::Timestamp,
 };
 
+/// Scheduler-wide settings
+#[derive(Debug, Clone)]
+pub struct SchedulerConfig {
+    /// Timezone for stream schedules that don't set their own `cron_timezone`
+    pub default_timezone: Tz,
+    /// Offset between scheduled syncs of connections sharing a provider
+    pub provider_stagger: Duration,
+}
+
+/// Default offset between connections of the same provider
+const DEFAULT_PROVIDER_STAGGER: Duration = Duration::from_secs(60);
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            default_timezone: Tz::UTC,
+            provider_stagger: DEFAULT_PROVIDER_STAGGER,
+        }
+    }
+}
+
+impl SchedulerConfig {
+    /// Load from environment (`SCHEDULER_TIMEZONE`, default UTC, and
+    /// `SCHEDULER_PROVIDER_STAGGER_SECS`, default 60, 0 to disable)
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(tz_str) = std::env::var("SCHEDULER_TIMEZONE") {
+            match tz_str.parse::<Tz>() {
+                Ok(tz) => config.default_timezone = tz,
+                Err(_) => tracing::warn!(
+                    "Invalid SCHEDULER_TIMEZONE '{}', using {}",
+                    tz_str,
+                    config.default_timezone
+                ),
+            }
+        }
+        if let Ok(secs) = std::env::var("SCHEDULER_PROVIDER_STAGGER_SECS") {
+            match secs.parse::<u64>() {
+                Ok(secs) => config.provider_stagger = Duration::from_secs(secs),
+                Err(_) => tracing::warn!(
+                    "Invalid SCHEDULER_PROVIDER_STAGGER_SECS '{}', using {}s",
+                    secs,
+                    config.provider_stagger.as_secs()
+                ),
+            }
+        }
+        config
+    }
+
+    /// Resolve a stream's stored timezone, falling back to the default
+    pub fn resolve_timezone(&self, tz: Option<&str>) -> Tz {
+        match tz {
+            Some(tz_str) => tz_str.parse::<Tz>().unwrap_or_else(|_| {
+                tracing::warn!(
+                    "Invalid cron_timezone '{}', using {}",
+                    tz_str,
+                    self.default_timezone
+                );
+                self.default_timezone
+            }),
+            None => self.default_timezone,
+        }
+    }
+}
+
 /// Simplified scheduler using StreamFactory
 pub struct Scheduler {
     db: SqlitePool,
@@ -36,14 +129,28 @@ pub struct Scheduler {
     drive_config: crate::api::DriveConfig,
     stream_writer: Arc<Mutex<StreamWriter>>,
     scheduler: JobScheduler,
+    config: SchedulerConfig,
+    /// Job ids for per-stream sync jobs keyed by (source_id, stream_name),
+    /// so `reload` can replace them and `list_scheduled` can report next ticks
+    stream_jobs: Mutex<HashMap<(String, String), Uuid>>,
 }
 
 impl Scheduler {
-    /// Create a new scheduler
+    /// Create a new scheduler with config from the environment
     pub async fn new(
         db: SqlitePool,
         storage: Storage,
         stream_writer: Arc<Mutex<StreamWriter>>,
+    ) -> Result<Self> {
+        Self::with_config(db, storage, stream_writer, SchedulerConfig::from_env()).await
+    }
+
+    /// Create a new scheduler with explicit config
+    pub async fn with_config(
+        db: SqlitePool,
+        storage: Storage,
+        stream_writer: Arc<Mutex<StreamWriter>>,
+        config: SchedulerConfig,
     ) -> Result<Self> {
         let scheduler = JobScheduler::new()
             .await
@@ -58,6 +165,8 @@ impl Scheduler {
             drive_config,
             stream_writer,
             scheduler,
+            config,
+            stream_jobs: Mutex::new(HashMap::new()),
         })
     }
 
@@ -82,10 +191,11 @@ impl Scheduler {
             JOIN elt_source_connections s ON st.source_connection_id = s.id
             WHERE st.is_enabled = true
               AND st.cron_schedule IS NULL
-              AND s.is_active = true
+              AND s.status = $1
               AND s.source NOT IN ('mac', 'ios')  -- Exclude push-only sources
             "#,
         )
+        .bind(SourceStatus::Active)
         .fetch_all(&self.db)
         .await?;
 
@@ -139,43 +249,113 @@ impl Scheduler {
             }
         }
 
+        self.schedule_streams().await?;
+
+        // Start the scheduler
+        self.scheduler
+            .start()
+            .await
+            .map_err(|e| Error::Other(format!("Failed to start scheduler: {e}")))?;
+
+        tracing::info!("Scheduler started successfully");
+        Ok(())
+    }
+
+    /// Reload per-stream sync jobs from the database
+    ///
+    /// Removes every stream job created by `start` or a previous reload and
+    /// re-reads the schedulable streams, so changes to `is_enabled`,
+    /// `cron_schedule` or the source's status take effect without a
+    /// restart. System jobs (trash purge, daily summary, embeddings) are
+    /// left untouched. Returns the number of streams now scheduled.
+    pub async fn reload(&self) -> Result<usize> {
+        let existing = std::mem::take(&mut *self.stream_jobs.lock().await);
+        for job_id in existing.values() {
+            self.scheduler
+                .remove(job_id)
+                .await
+                .map_err(|e| Error::Other(format!("Failed to remove job {job_id}: {e}")))?;
+        }
+
+        let count = self.schedule_streams().await?;
+        tracing::info!(
+            "Scheduler reloaded: {} stream jobs removed, {} scheduled",
+            existing.len(),
+            count
+        );
+        Ok(count)
+    }
+
+    /// Create a sync job for every schedulable stream
+    ///
+    /// Skips disabled streams, inactive or paused sources, and push-only
+    /// sources. Returns the number of jobs added.
+    async fn schedule_streams(&self) -> Result<usize> {
         // Load enabled streams from database
         // Filter to only pull streams (not 'mac' or 'ios' which are push-only)
-        let streams = sqlx::query_as::<_, (String, String, String, String, Option<String>)>(
+        let streams = sqlx::query_as::<
+            _,
+            (
+                String,
+                String,
+                String,
+                String,
+                Option<String>,
+                Option<String>,
+            ),
+        >(
             r#"
             SELECT
                 s.id as source_id,
                 s.name as source_name,
                 s.source,
                 st.stream_name,
-                st.cron_schedule
+                st.cron_schedule,
+                st.cron_timezone
             FROM elt_stream_connections st
             JOIN elt_source_connections s ON st.source_connection_id = s.id
             WHERE st.is_enabled = true
               AND st.cron_schedule IS NOT NULL
-              AND s.is_active = true
+              AND s.status = $1
               AND s.source NOT IN ('mac', 'ios')  -- Exclude push-only sources
             "#,
         )
+        .bind(SourceStatus::Active)
         .fetch_all(&self.db)
         .await?;
 
         tracing::info!("Loading {} scheduled streams", streams.len());
 
+        let offsets = provider_offsets(
+            streams
+                .iter()
+                .map(|(source_id, _, provider, ..)| (provider.as_str(), source_id.as_str())),
+            self.config.provider_stagger,
+        );
+
         // Schedule each stream
-        for (source_id, source_name, provider, stream_name, cron_schedule) in streams {
+        let mut job_ids = HashMap::with_capacity(streams.len());
+        for (source_id, source_name, provider, stream_name, cron_schedule, cron_timezone) in streams
+        {
             let cron = cron_schedule.expect("cron_schedule is NOT NULL per WHERE clause");
+            let timezone = self.config.resolve_timezone(cron_timezone.as_deref());
+            let job_key = (source_id.clone(), stream_name.clone());
+            let offset = wrap_offset(
+                offsets.get(&source_id).copied().unwrap_or_default(),
+                schedule_interval(&cron),
+            );
 
             let db = self.db.clone();
             let storage = self.storage.clone();
             let stream_writer = self.stream_writer.clone();
 
             tracing::debug!(
-                "Scheduling {}/{} ({}) with cron: {}",
+                "Scheduling {}/{} ({}) with cron: {} ({})",
                 provider,
                 stream_name,
                 source_name,
-                cron
+                cron,
+                timezone
             );
 
             // Clone values for error message before they're moved into closure
@@ -183,7 +363,7 @@ impl Scheduler {
             let stream_name_for_error = stream_name.clone();
             let source_name_for_error = source_name.clone();
 
-            let job = Job::new_async(cron.as_str(), move |_uuid, _lock| {
+            let job = Job::new_async_tz(cron.as_str(), timezone, move |_uuid, _lock| {
                 let db = db.clone();
                 let storage = storage.clone();
                 let stream_writer = stream_writer.clone();
@@ -193,6 +373,17 @@ impl Scheduler {
                 let stream_name_str = stream_name.clone();
 
                 Box::pin(async move {
+                    // Let earlier connections of the same provider go first
+                    if !offset.is_zero() {
+                        tracing::debug!(
+                            "Delaying scheduled sync of {} ({}) by {}s for provider stagger",
+                            stream_name_str,
+                            source_name,
+                            offset.as_secs()
+                        );
+                        tokio::time::sleep(offset).await;
+                    }
+
                     tracing::info!(
                         "Running scheduled sync: {} ({})",
                         stream_name_str,
@@ -218,6 +409,12 @@ impl Scheduler {
                                 response.status
                             );
                         }
+                        Err(crate::Error::SyncInProgress(_)) => {
+                            tracing::debug!(
+                                "Skipping scheduled sync for {}: previous sync still active",
+                                stream_name_str
+                            );
+                        }
                         Err(e) => {
                             tracing::error!(
                                 "Failed to create scheduled sync job for {}: {}",
@@ -237,20 +434,17 @@ impl Scheduler {
                 ))
             })?;
 
-            self.scheduler
+            let job_id = self
+                .scheduler
                 .add(job)
                 .await
                 .map_err(|e| Error::Other(format!("Failed to add job: {e}")))?;
+            job_ids.insert(job_key, job_id);
         }
 
-        // Start the scheduler
-        self.scheduler
-            .start()
-            .await
-            .map_err(|e| Error::Other(format!("Failed to start scheduler: {e}")))?;
-
-        tracing::info!("Scheduler started successfully");
-        Ok(())
+        let count = job_ids.len();
+        self.stream_jobs.lock().await.extend(job_ids);
+        Ok(count)
     }
 
 
@@ -302,6 +496,41 @@ impl Scheduler {
         Ok(())
     }
 
+    /// Schedule the agent checkpoint prune job (hourly)
+    ///
+    /// Deletes checkpoints of agent runs that were interrupted and never
+    /// resumed (see `agent::checkpoint`).
+    pub async fn schedule_agent_checkpoint_prune_job(&self) -> Result<()> {
+        let db = self.db.clone();
+
+        // Hourly, on the hour
+        let cron_expr = "0 0 * * * *";
+
+        let job = Job::new_async(cron_expr, move |_uuid, _lock| {
+            let db = db.clone();
+
+            Box::pin(async move {
+                match crate::agent::checkpoint::prune_checkpoints(&db).await {
+                    Ok(0) => tracing::debug!("AgentCheckpointPruneJob: nothing to prune"),
+                    Ok(count) => tracing::info!(
+                        "AgentCheckpointPruneJob completed: {} stale checkpoints deleted",
+                        count
+                    ),
+                    Err(e) => tracing::error!("AgentCheckpointPruneJob failed: {}", e),
+                }
+            })
+        })
+        .map_err(|e| Error::Other(format!("Failed to create AgentCheckpointPruneJob: {}", e)))?;
+
+        self.scheduler
+            .add(job)
+            .await
+            .map_err(|e| Error::Other(format!("Failed to add AgentCheckpointPruneJob: {}", e)))?;
+
+        tracing::info!("AgentCheckpointPruneJob scheduled hourly");
+        Ok(())
+    }
+
     /// Schedule the daily summary job (hourly check, runs at user's update_check_hour)
     ///
     /// Checks every hour whether it's the user's configured maintenance hour
@@ -457,6 +686,9 @@ impl Scheduler {
     }
 
     /// Get list of scheduled streams
+    ///
+    /// Each entry includes the resolved timezone and, once the stream's job is
+    /// registered, its next fire time in UTC and in that timezone.
     pub async fn list_scheduled(&self) -> Result<Vec<ScheduledStream>> {
         let rows = sqlx::query_as::<
             _,
@@ -465,6 +697,7 @@ impl Scheduler {
                 String,
                 String,
                 String,
+                Option<String>,
                 Option<Timestamp>,
             ),
         >(
@@ -474,37 +707,112 @@ impl Scheduler {
                 s.name as source_name,
                 st.stream_name,
                 st.cron_schedule,
+                st.cron_timezone,
                 st.last_sync_at
             FROM elt_stream_connections st
             JOIN elt_source_connections s ON st.source_connection_id = s.id
             WHERE st.is_enabled = true
               AND st.cron_schedule IS NOT NULL
+              AND s.status = $1
               AND s.source NOT IN ('mac', 'ios')  -- Exclude push-only sources
             ORDER BY s.name, st.stream_name
             "#,
         )
+        .bind(SourceStatus::Active)
         .fetch_all(&self.db)
         .await?;
 
-        let streams = rows
-            .into_iter()
-            .map(
-                |(source_id, source_name, stream_name, cron_schedule, last_sync_at)| {
-                    ScheduledStream {
-                        source_id,
-                        source_name,
-                        stream_name,
-                        cron_schedule,
-                        last_sync_at,
-                    }
-                },
-            )
-            .collect();
+        let job_ids = self.stream_jobs.lock().await.clone();
+        let mut streams = Vec::with_capacity(rows.len());
+
+        for (source_id, source_name, stream_name, cron_schedule, cron_timezone, last_sync_at) in
+            rows
+        {
+            let timezone = self.config.resolve_timezone(cron_timezone.as_deref());
+
+            let next_fire_at = match job_ids.get(&(source_id.clone(), stream_name.clone())) {
+                Some(job_id) => self
+                    .scheduler
+                    .clone()
+                    .next_tick_for_job(*job_id)
+                    .await
+                    .ok()
+                    .flatten(),
+                None => None,
+            };
+
+            streams.push(ScheduledStream {
+                source_id,
+                source_name,
+                stream_name,
+                cron_schedule,
+                timezone,
+                next_fire_at,
+                next_fire_at_local: next_fire_at.map(|t| t.with_timezone(&timezone)),
+                last_sync_at,
+            });
+        }
 
         Ok(streams)
     }
 }
 
+/// Start delay of each connection's scheduled syncs, keyed by source id
+///
+/// Takes `(provider, source_id)` pairs (repeats are fine, one per stream).
+/// Within each provider, connections are ordered by id and the n-th waits
+/// n × `stagger`, so the offsets are stable across reloads. A provider's
+/// first connection, and every connection when `stagger` is zero, waits
+/// nothing.
+pub fn provider_offsets<'a>(
+    connections: impl IntoIterator<Item = (&'a str, &'a str)>,
+    stagger: Duration,
+) -> HashMap<String, Duration> {
+    let mut by_provider: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for (provider, source_id) in connections {
+        by_provider.entry(provider).or_default().insert(source_id);
+    }
+
+    by_provider
+        .into_values()
+        .flat_map(|source_ids| {
+            source_ids
+                .into_iter()
+                .enumerate()
+                .map(|(n, source_id)| (source_id.to_string(), stagger * n as u32))
+        })
+        .collect()
+}
+
+/// Shortest gap between the upcoming runs of a cron expression
+///
+/// Looks at the next few runs, so a schedule with uneven gaps (weekdays only)
+/// gets its tightest one. `None` if the expression doesn't parse.
+pub fn schedule_interval(cron: &str) -> Option<Duration> {
+    let schedule = croner::Cron::new(cron)
+        .with_seconds_required()
+        .with_dom_and_dow()
+        .parse()
+        .ok()?;
+    let runs: Vec<DateTime<Utc>> = schedule.iter_after(Utc::now()).take(8).collect();
+    runs.windows(2)
+        .filter_map(|pair| (pair[1] - pair[0]).to_std().ok())
+        .min()
+}
+
+/// Wrap a stagger offset to within one schedule interval
+///
+/// Connections past the interval start over from its beginning instead of
+/// waiting into the next run. Offsets of unknown schedules are left alone.
+pub fn wrap_offset(offset: Duration, interval: Option<Duration>) -> Duration {
+    match interval {
+        Some(interval) if !interval.is_zero() => {
+            Duration::from_secs(offset.as_secs() % interval.as_secs().max(1))
+        }
+        _ => offset,
+    }
+}
+
 /// Information about a scheduled stream
 #[derive(Debug)]
 pub struct ScheduledStream {
@@ -512,6 +820,12 @@ pub struct ScheduledStream {
     pub source_name: String,
     pub stream_name: String,
     pub cron_schedule: String,
+    /// Timezone the cron expression is evaluated in
+    pub timezone: Tz,
+    /// Next fire time in UTC (None if the job isn't registered yet)
+    pub next_fire_at: Option<DateTime<Utc>>,
+    /// Next fire time in `timezone`
+    pub next_fire_at_local: Option<DateTime<Tz>>,
     pub last_sync_at: Option<Timestamp>,
 }
 
@@ -522,9 +836,237 @@ mod tests {
     #[tokio::test]
     async fn test_scheduler_creation() {
         let pool = SqlitePool::connect_lazy("sqlite::memory:").unwrap();
-        let storage = Storage::local("./test_data".to_string()).unwrap();
+        let storage = Storage::in_memory();
         let stream_writer = Arc::new(Mutex::new(StreamWriter::new()));
         let result = Scheduler::new(pool, storage, stream_writer).await;
         assert!(result.is_ok());
     }
+
+    /// Minimal connection tables with just the columns the scheduler and
+    /// source API read.
+    async fn setup_pool() -> SqlitePool {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        sqlx::query(
+            r#"
+            CREATE TABLE elt_source_connections (
+                id TEXT PRIMARY KEY,
+                source TEXT NOT NULL,
+                name TEXT NOT NULL UNIQUE,
+                auth_type TEXT NOT NULL DEFAULT 'oauth2',
+                pairing_status TEXT,
+                is_active INTEGER DEFAULT 1,
+                is_paused INTEGER NOT NULL DEFAULT 0,
+                deleted_at TEXT,
+                status TEXT GENERATED ALWAYS AS (
+                    CASE
+                        WHEN deleted_at IS NOT NULL THEN 'deleted'
+                        WHEN COALESCE(is_active, 0) = 0 THEN 'inactive'
+                        WHEN is_paused THEN 'paused'
+                        ELSE 'active'
+                    END
+                ) VIRTUAL,
+                is_internal INTEGER DEFAULT 0,
+                error_message TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            r#"
+            CREATE TABLE elt_stream_connections (
+                id TEXT PRIMARY KEY,
+                source_connection_id TEXT NOT NULL,
+                stream_name TEXT NOT NULL,
+                is_enabled INTEGER NOT NULL DEFAULT 1,
+                cron_schedule TEXT,
+                cron_timezone TEXT,
+                last_sync_at TEXT
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        sqlx::query("INSERT INTO elt_source_connections (id, source, name) VALUES ('source_1', 'google', 'Google')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO elt_stream_connections (id, source_connection_id, stream_name, cron_schedule) \
+             VALUES ('stream_1', 'source_1', 'calendar', '0 */15 * * * *')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_pause_removes_jobs_on_reload() {
+        let pool = setup_pool().await;
+        let storage = Storage::in_memory();
+        let stream_writer = Arc::new(Mutex::new(StreamWriter::new()));
+        let scheduler = Scheduler::new(pool.clone(), storage, stream_writer)
+            .await
+            .unwrap();
+
+        assert_eq!(scheduler.reload().await.unwrap(), 1);
+        assert_eq!(scheduler.list_scheduled().await.unwrap().len(), 1);
+
+        let paused = crate::api::pause_source(&pool, "source_1".to_string())
+            .await
+            .unwrap();
+        assert!(paused.is_paused);
+        assert!(paused.is_active);
+        assert_eq!(paused.status, crate::api::SourceStatus::Paused);
+
+        assert_eq!(scheduler.reload().await.unwrap(), 0);
+        assert!(scheduler.list_scheduled().await.unwrap().is_empty());
+        assert!(scheduler.stream_jobs.lock().await.is_empty());
+
+        // The connection itself is kept
+        let source = crate::api::get_source(&pool, "source_1".to_string())
+            .await
+            .unwrap();
+        assert_eq!(source.total_streams_count, 1);
+
+        crate::api::resume_source(&pool, "source_1".to_string())
+            .await
+            .unwrap();
+        assert_eq!(scheduler.reload().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_soft_delete_stops_scheduling_until_restored() {
+        let pool = setup_pool().await;
+        let storage = Storage::in_memory();
+        let stream_writer = Arc::new(Mutex::new(StreamWriter::new()));
+        let scheduler = Scheduler::new(pool.clone(), storage.clone(), stream_writer)
+            .await
+            .unwrap();
+        assert_eq!(scheduler.reload().await.unwrap(), 1);
+
+        let purged = crate::api::soft_delete_source(&pool, &storage, "source_1".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(purged, 0);
+        assert_eq!(scheduler.reload().await.unwrap(), 0);
+        assert!(crate::api::list_sources(&pool).await.unwrap().is_empty());
+
+        let restored = crate::api::restore_source(&pool, "source_1".to_string())
+            .await
+            .unwrap();
+        assert!(restored.deleted_at.is_none());
+        assert_eq!(scheduler.reload().await.unwrap(), 1);
+
+        // Restoring a source that isn't deleted is rejected
+        assert!(crate::api::restore_source(&pool, "source_1".to_string())
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_resolve_timezone() {
+        let config = SchedulerConfig {
+            default_timezone: chrono_tz::America::Chicago,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.resolve_timezone(Some("Europe/Berlin")),
+            chrono_tz::Europe::Berlin
+        );
+        assert_eq!(config.resolve_timezone(None), chrono_tz::America::Chicago);
+        assert_eq!(
+            config.resolve_timezone(Some("Not/AZone")),
+            chrono_tz::America::Chicago
+        );
+    }
+
+    #[test]
+    fn test_provider_offsets_stagger_connections_of_a_provider() {
+        let stagger = Duration::from_secs(60);
+        let offsets = provider_offsets(
+            [
+                ("google", "src_b"),
+                ("google", "src_a"),
+                ("google", "src_b"), // second stream of the same connection
+                ("strava", "src_c"),
+                ("google", "src_d"),
+            ],
+            stagger,
+        );
+
+        assert_eq!(offsets.len(), 4);
+        assert_eq!(offsets["src_a"], Duration::ZERO);
+        assert_eq!(offsets["src_b"], Duration::from_secs(60));
+        assert_eq!(offsets["src_d"], Duration::from_secs(120));
+        // Other providers aren't pushed back by Google's connections
+        assert_eq!(offsets["src_c"], Duration::ZERO);
+
+        let disabled = provider_offsets([("google", "src_a"), ("google", "src_b")], Duration::ZERO);
+        assert!(disabled.values().all(|d| d.is_zero()));
+    }
+
+    #[test]
+    fn test_stagger_offset_stays_within_schedule_interval() {
+        let every_five_minutes = schedule_interval("0 */5 * * * *");
+        assert_eq!(every_five_minutes, Some(Duration::from_secs(300)));
+
+        // The 16th connection of a provider would otherwise wait 15 minutes
+        let offset = Duration::from_secs(15 * 60);
+        assert_eq!(wrap_offset(offset, every_five_minutes), Duration::ZERO);
+        assert_eq!(
+            wrap_offset(Duration::from_secs(420), every_five_minutes),
+            Duration::from_secs(120)
+        );
+        assert_eq!(
+            wrap_offset(offset, schedule_interval("0 0 9 * * *")),
+            offset
+        );
+        assert_eq!(wrap_offset(offset, schedule_interval("not a cron")), offset);
+    }
+
+    #[tokio::test]
+    async fn test_schedule_fires_in_stream_timezone() {
+        let pool = setup_pool().await;
+        sqlx::query(
+            "UPDATE elt_stream_connections SET cron_schedule = '0 0 9 * * *', cron_timezone = 'America/New_York'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let storage = Storage::in_memory();
+        let stream_writer = Arc::new(Mutex::new(StreamWriter::new()));
+        let scheduler =
+            Scheduler::with_config(pool, storage, stream_writer, SchedulerConfig::default())
+                .await
+                .unwrap();
+        scheduler.reload().await.unwrap();
+
+        let scheduled = scheduler.list_scheduled().await.unwrap();
+        assert_eq!(scheduled.len(), 1);
+        assert_eq!(scheduled[0].timezone, chrono_tz::America::New_York);
+
+        let local = scheduled[0]
+            .next_fire_at_local
+            .expect("registered job has a next tick");
+        assert_eq!(local.hour(), 9);
+        assert_eq!(
+            scheduled[0].next_fire_at.unwrap(),
+            local.with_timezone(&Utc)
+        );
+    }
 }
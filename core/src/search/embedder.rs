@@ -46,13 +46,16 @@ impl LocalEmbedder {
     /// Embed text on the blocking thread pool (async-safe).
     ///
     /// Moves the CPU-bound ONNX inference to `spawn_blocking` so it
-    /// doesn't block tokio worker threads.
+    /// doesn't block tokio worker threads. Counted towards the current job's
+    /// cost, which the blocking pool can't see.
     pub async fn embed_async(self: &Arc<Self>, text: &str) -> Result<Vec<f32>> {
         let this = self.clone();
         let text = text.to_string();
-        tokio::task::spawn_blocking(move || this.embed(&text))
+        let embedding = tokio::task::spawn_blocking(move || this.embed(&text))
             .await
-            .map_err(|e| anyhow::anyhow!("Embedding task panicked: {}", e))?
+            .map_err(|e| anyhow::anyhow!("Embedding task panicked: {}", e))??;
+        crate::jobs::cost::record_embedded_texts(1);
+        Ok(embedding)
     }
 
     /// Embed multiple texts in a single batch on the blocking thread pool (async-safe).
@@ -61,12 +64,14 @@ impl LocalEmbedder {
     /// for all texts (e.g. 8-16 events at once).
     pub async fn embed_batch_async(self: &Arc<Self>, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
         let this = self.clone();
-        tokio::task::spawn_blocking(move || {
+        let embeddings = tokio::task::spawn_blocking(move || {
             let refs: Vec<&str> = texts.iter().map(|s| s.as_str()).collect();
             this.embed_batch(&refs)
         })
         .await
-        .map_err(|e| anyhow::anyhow!("Batch embedding task panicked: {}", e))?
+        .map_err(|e| anyhow::anyhow!("Batch embedding task panicked: {}", e))??;
+        crate::jobs::cost::record_embedded_texts(embeddings.len() as u64);
+        Ok(embeddings)
     }
 }
 
@@ -3,7 +3,7 @@
 use axum::{
     body::Body,
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -34,8 +34,14 @@ fn api_response<T: Serialize>(result: crate::error::Result<T>) -> Response {
 fn error_response(error: Error) -> Response {
     let (status, message) = match &error {
         Error::NotFound(_) => (StatusCode::NOT_FOUND, error.to_string()),
-        Error::Unauthorized(_) => (StatusCode::UNAUTHORIZED, error.to_string()),
+        Error::Unauthorized(_) | Error::TokenExpired(_) => {
+            (StatusCode::UNAUTHORIZED, error.to_string())
+        }
         Error::InvalidInput(_) => (StatusCode::BAD_REQUEST, error.to_string()),
+        Error::Overloaded(_) | Error::ProviderUnavailable(_) => {
+            (StatusCode::SERVICE_UNAVAILABLE, error.to_string())
+        }
+        Error::SyncInProgress(_) | Error::Conflict(_) => (StatusCode::CONFLICT, error.to_string()),
         Error::Database(msg) if msg.contains("already has an active") => {
             (StatusCode::CONFLICT, error.to_string())
         }
@@ -83,24 +89,104 @@ pub async fn resume_source_handler(
     api_response(crate::api::resume_source(state.db.pool(), source_id).await)
 }
 
-/// Delete a source by ID
+/// Get a source's network (proxy / offline) settings
+pub async fn get_source_network_handler(
+    State(state): State<AppState>,
+    Path(source_id): Path<String>,
+) -> Response {
+    api_response(crate::api::get_source_network(state.db.pool(), source_id).await)
+}
+
+/// Replace a source's network (proxy / offline) settings
+pub async fn update_source_network_handler(
+    State(state): State<AppState>,
+    Path(source_id): Path<String>,
+    Json(config): Json<crate::sources::base::NetworkConfig>,
+) -> Response {
+    api_response(crate::api::update_source_network(state.db.pool(), source_id, config).await)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteSourceQuery {
+    /// Also delete the source's stored stream archives (irreversible)
+    #[serde(default)]
+    pub purge_data: bool,
+    /// Required with `purge_data`; without it the purge plan is returned and
+    /// nothing is changed
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// Soft-delete a source by ID, optionally purging its stored data
 pub async fn delete_source_handler(
     State(state): State<AppState>,
     Path(source_id): Path<String>,
+    Query(params): Query<DeleteSourceQuery>,
 ) -> Response {
-    match crate::api::delete_source(state.db.pool(), source_id).await {
-        Ok(_) => success_message("Source deleted successfully"),
+    let purge = if params.purge_data {
+        let plan =
+            match crate::api::plan_source_purge(state.db.pool(), &state.storage, source_id.clone())
+                .await
+            {
+                Ok(plan) => plan,
+                Err(e) => return error_response(e),
+            };
+
+        if !params.confirm {
+            return (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "message": "Dry run: nothing was deleted. Repeat with confirm=true to delete the source and these objects.",
+                    "dry_run": true,
+                    "plan": plan,
+                })),
+            )
+                .into_response();
+        }
+        Some(plan.confirm())
+    } else {
+        None
+    };
+
+    match crate::api::soft_delete_source(state.db.pool(), &state.storage, source_id, purge).await {
+        Ok(objects_deleted) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "message": "Source deleted successfully",
+                "data_purged": params.purge_data,
+                "objects_deleted": objects_deleted,
+            })),
+        )
+            .into_response(),
         Err(e) => error_response(e),
     }
 }
 
+/// Restore a soft-deleted source
+pub async fn restore_source_handler(
+    State(state): State<AppState>,
+    Path(source_id): Path<String>,
+) -> Response {
+    api_response(crate::api::restore_source(state.db.pool(), source_id).await)
+}
+
 /// Get source status with statistics
 pub async fn get_source_status_handler(
     State(state): State<AppState>,
     Path(source_id): Path<String>,
 ) -> Response {
     match crate::api::get_source_status(state.db.pool(), source_id).await {
-        Ok(status) => (StatusCode::OK, Json(status)).into_response(),
+        Ok(mut status) => {
+            status.pending = state
+                .ingest_writer
+                .lock()
+                .await
+                .pending_buffers()
+                .into_iter()
+                .filter(|pending| pending.source_id == status.id)
+                .collect();
+            (StatusCode::OK, Json(status)).into_response()
+        }
         Err(e) => (
             StatusCode::NOT_FOUND,
             Json(serde_json::json!({
@@ -113,10 +199,18 @@ pub async fn get_source_status_handler(
 
 /// Initiate OAuth authorization flow
 pub async fn oauth_authorize_handler(
+    State(state): State<AppState>,
     Path(provider): Path<String>,
     Query(params): Query<crate::api::OAuthAuthorizeRequest>,
 ) -> Response {
-    match crate::api::initiate_oauth_flow(&provider, params.redirect_uri, params.state).await {
+    match crate::api::initiate_oauth_flow(
+        state.db.pool(),
+        &provider,
+        params.redirect_uri,
+        params.state,
+    )
+    .await
+    {
         Ok(response) => (StatusCode::OK, Json(response)).into_response(),
         Err(e) => (
             StatusCode::BAD_REQUEST,
@@ -128,6 +222,36 @@ pub async fn oauth_authorize_handler(
     }
 }
 
+/// Initiate re-authorization of an existing OAuth source
+///
+/// Requests any scopes the source's enabled streams need on top of the
+/// current grant. The `state` query parameter is the return URL, as with
+/// `oauth_authorize_handler`.
+pub async fn reauthorize_source_handler(
+    State(state): State<AppState>,
+    Path(source_id): Path<String>,
+    Query(params): Query<crate::api::OAuthAuthorizeRequest>,
+) -> Response {
+    api_response(
+        crate::api::initiate_oauth_reauthorization(state.db.pool(), &source_id, params.state)
+            .await,
+    )
+}
+
+/// Reconnect an OAuth source whose grant was revoked or expired
+///
+/// Like `reauthorize_source_handler`, but the callback also queues a sync of
+/// every enabled stream, resuming from the stored cursors.
+pub async fn reconnect_source_handler(
+    State(state): State<AppState>,
+    Path(source_id): Path<String>,
+    Query(params): Query<crate::api::OAuthAuthorizeRequest>,
+) -> Response {
+    api_response(
+        crate::api::initiate_oauth_reconnect(state.db.pool(), &source_id, params.state).await,
+    )
+}
+
 /// Handle OAuth callback and return HTML redirect
 ///
 /// The return URL comes from the state parameter that was set during OAuth initiation.
@@ -140,9 +264,8 @@ pub async fn oauth_callback_handler(
     // Try to extract return URL from state FIRST, before processing
     // This way we can redirect back to the client even if OAuth processing fails
     let return_url_from_state = params.state.as_ref().and_then(|s| {
-        crate::sources::base::oauth::state::validate_and_extract_state(s)
-            .ok()
-            .flatten()
+        let session = crate::sources::base::oauth::state::validate_and_extract_state(s).ok()?;
+        crate::sources::base::oauth::state::OAuthSession::decode(session).return_url
     });
 
     match crate::api::handle_oauth_callback(
@@ -314,7 +437,7 @@ pub async fn get_stream_handler(
     Path((source_id, stream_name)): Path<(String, String)>,
 ) -> Response {
     match crate::api::get_stream_info(state.db.pool(), source_id, &stream_name).await {
-        Ok(stream) => (StatusCode::OK, Json(stream)).into_response(),
+        Ok(stream) => stream_config_response(Ok(stream)),
         Err(e) => (
             StatusCode::NOT_FOUND,
             Json(serde_json::json!({
@@ -375,24 +498,83 @@ pub async fn disable_stream_handler(
     }
 }
 
-/// Update stream configuration
+/// Parse an `If-Match` header holding a stream config version
+///
+/// Accepts `"3"`, `W/"3"` or a bare `3`; `*` (or no header) matches any version.
+fn config_if_match(headers: &HeaderMap) -> crate::error::Result<Option<i64>> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+    let tag = value.to_str().unwrap_or_default().trim();
+    if tag == "*" {
+        return Ok(None);
+    }
+
+    let version = tag.strip_prefix("W/").unwrap_or(tag).trim_matches('"');
+    version.parse().map(Some).map_err(|_| {
+        Error::InvalidInput(format!(
+            "If-Match must be a stream config version, got {tag:?}"
+        ))
+    })
+}
+
+/// Stream response carrying its config version as the `ETag`
+fn stream_config_response(result: crate::error::Result<crate::api::StreamConnection>) -> Response {
+    match result {
+        Ok(stream) => (
+            StatusCode::OK,
+            [(header::ETAG, format!("\"{}\"", stream.config_version))],
+            Json(stream),
+        )
+            .into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// Replace stream configuration
 pub async fn update_stream_config_handler(
     State(state): State<AppState>,
     Path((source_id, stream_name)): Path<(String, String)>,
+    headers: HeaderMap,
     Json(request): Json<crate::api::UpdateStreamConfigRequest>,
 ) -> Response {
-    match crate::api::update_stream_config(state.db.pool(), source_id, &stream_name, request.config)
-        .await
-    {
-        Ok(stream) => (StatusCode::OK, Json(stream)).into_response(),
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({
-                "error": e.to_string()
-            })),
+    let if_match = match config_if_match(&headers) {
+        Ok(version) => version,
+        Err(e) => return error_response(e),
+    };
+    stream_config_response(
+        crate::api::update_stream_config(
+            state.db.pool(),
+            source_id,
+            &stream_name,
+            request.config,
+            if_match,
         )
-            .into_response(),
-    }
+        .await,
+    )
+}
+
+/// Merge the given keys into stream configuration
+pub async fn patch_stream_config_handler(
+    State(state): State<AppState>,
+    Path((source_id, stream_name)): Path<(String, String)>,
+    headers: HeaderMap,
+    Json(request): Json<crate::api::UpdateStreamConfigRequest>,
+) -> Response {
+    let if_match = match config_if_match(&headers) {
+        Ok(version) => version,
+        Err(e) => return error_response(e),
+    };
+    stream_config_response(
+        crate::api::patch_stream_config(
+            state.db.pool(),
+            source_id,
+            &stream_name,
+            request.config,
+            if_match,
+        )
+        .await,
+    )
 }
 
 /// Update stream schedule
@@ -406,6 +588,7 @@ pub async fn update_stream_schedule_handler(
         source_id,
         &stream_name,
         request.cron_schedule,
+        request.timezone,
     )
     .await
     {
@@ -420,19 +603,101 @@ pub async fn update_stream_schedule_handler(
     }
 }
 
+/// Update a stream's archive partition granularity
+pub async fn update_stream_partitioning_handler(
+    State(state): State<AppState>,
+    Path((source_id, stream_name)): Path<(String, String)>,
+    Json(request): Json<crate::api::UpdateStreamPartitioningRequest>,
+) -> Response {
+    api_response(
+        crate::api::update_stream_partitioning(
+            state.db.pool(),
+            source_id,
+            &stream_name,
+            request.granularity,
+        )
+        .await,
+    )
+}
+
+/// Turn end-to-end encryption on or off for a stream
+pub async fn update_stream_encryption_handler(
+    State(state): State<AppState>,
+    Path((source_id, stream_name)): Path<(String, String)>,
+    Json(request): Json<crate::api::UpdateStreamEncryptionRequest>,
+) -> Response {
+    api_response(
+        crate::api::update_stream_encryption(
+            state.db.pool(),
+            source_id,
+            &stream_name,
+            request.enabled,
+        )
+        .await,
+    )
+}
+
+/// Get the stored sync cursor for a stream
+pub async fn get_stream_cursor_handler(
+    State(state): State<AppState>,
+    Path((source_id, stream_name)): Path<(String, String)>,
+) -> Response {
+    api_response(crate::api::get_stream_cursor(state.db.pool(), source_id, &stream_name).await)
+}
+
+/// Query parameters for resetting a stream cursor
+#[derive(Debug, Deserialize)]
+pub struct ResetStreamCursorQuery {
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// Clear the stored sync cursor for a stream (requires `?confirm=true`)
+pub async fn reset_stream_cursor_handler(
+    State(state): State<AppState>,
+    Path((source_id, stream_name)): Path<(String, String)>,
+    Query(query): Query<ResetStreamCursorQuery>,
+) -> Response {
+    api_response(
+        crate::api::reset_stream_cursor(state.db.pool(), source_id, &stream_name, query.confirm)
+            .await,
+    )
+}
+
+/// Stream live progress of a stream's active sync as server-sent events
+///
+/// Each `progress` event carries a `SyncProgress` snapshot (pages fetched,
+/// records written so far, current cursor); the stream closes when the sync ends.
+pub async fn sync_progress_handler(
+    State(state): State<AppState>,
+    Path((source_id, stream_name)): Path<(String, String)>,
+) -> Response {
+    use axum::response::sse::{Event, KeepAlive, Sse};
+    use futures::StreamExt;
+
+    match crate::api::sync_progress_stream(state.db.pool().clone(), source_id, &stream_name).await {
+        Ok(progress) => {
+            let events =
+                progress.map(|snapshot| Event::default().event("progress").json_data(snapshot));
+            Sse::new(events)
+                .keep_alive(KeepAlive::new())
+                .into_response()
+        }
+        Err(e) => error_response(e),
+    }
+}
+
 /// Trigger a manual sync for a stream (async job-based)
 pub async fn sync_stream_handler(
     State(state): State<AppState>,
     Path((source_id, stream_name)): Path<(String, String)>,
-    Json(request): Json<Option<SyncStreamRequest>>,
+    Json(request): Json<Option<serde_json::Value>>,
 ) -> Response {
     // Parse sync mode from request (default to incremental)
-    let sync_mode = request.and_then(|r| {
-        r.sync_mode.map(|m| match m.as_str() {
-            "full_refresh" => crate::sources::base::SyncMode::FullRefresh,
-            _ => crate::sources::base::SyncMode::incremental(None),
-        })
-    });
+    let sync_mode = match parse_sync_mode_body(request) {
+        Ok(mode) => mode,
+        Err(e) => return error_response(e),
+    };
 
     // Use the new async job-based sync
     match crate::api::trigger_stream_sync(
@@ -447,8 +712,10 @@ pub async fn sync_stream_handler(
     {
         Ok(response) => (StatusCode::CREATED, Json(response)).into_response(),
         Err(e) => {
-            let status = if e.to_string().contains("already has an active sync") {
+            let status = if matches!(e, Error::SyncInProgress(_)) {
                 StatusCode::CONFLICT
+            } else if matches!(e, Error::InvalidInput(_)) {
+                StatusCode::BAD_REQUEST
             } else {
                 StatusCode::INTERNAL_SERVER_ERROR
             };
@@ -463,10 +730,55 @@ pub async fn sync_stream_handler(
     }
 }
 
-/// Request for syncing a stream
-#[derive(Debug, Deserialize)]
-pub struct SyncStreamRequest {
-    pub sync_mode: Option<String>,
+/// Parse the optional sync mode from a sync request body
+///
+/// Accepts the serialized `SyncMode` form (`{ "mode": "backfill", "start_date": ... }`)
+/// and the legacy `sync_mode` key used by older clients. A missing body or
+/// mode means the caller gets the default incremental sync.
+fn parse_sync_mode_body(
+    body: Option<serde_json::Value>,
+) -> crate::error::Result<Option<crate::sources::base::SyncMode>> {
+    let Some(serde_json::Value::Object(mut body)) = body else {
+        return Ok(None);
+    };
+
+    if !body.contains_key("mode") {
+        match body.remove("sync_mode") {
+            Some(mode) => {
+                body.insert("mode".to_string(), mode);
+            }
+            None => return Ok(None),
+        }
+    }
+
+    serde_json::from_value(serde_json::Value::Object(body))
+        .map(Some)
+        .map_err(|e| Error::InvalidInput(format!("Invalid sync mode: {e}")))
+}
+
+/// Trigger a sync for every enabled stream of a source
+pub async fn sync_source_handler(
+    State(state): State<AppState>,
+    Path(source_id): Path<String>,
+    Json(request): Json<Option<serde_json::Value>>,
+) -> Response {
+    let sync_mode = match parse_sync_mode_body(request) {
+        Ok(mode) => mode,
+        Err(e) => return error_response(e),
+    };
+
+    match crate::api::trigger_source_sync(
+        state.db.pool(),
+        &*state.storage,
+        state.stream_writer.clone(),
+        source_id,
+        sync_mode,
+    )
+    .await
+    {
+        Ok(response) => (StatusCode::CREATED, Json(response)).into_response(),
+        Err(e) => error_response(e),
+    }
 }
 
 // ============================================================================
@@ -488,6 +800,8 @@ pub struct CatalogSource {
     pub description: String,
     pub auth_type: String,
     pub stream_count: usize,
+    /// Streams that can actually be synced (see `RegisteredStream::is_implemented`)
+    pub implemented_stream_count: usize,
     pub icon: Option<String>,
     /// Whether this source allows multiple connections
     pub is_multi_instance: bool,
@@ -497,8 +811,42 @@ pub struct CatalogSource {
     /// Current number of active connections (populated when db is available)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub current_connections: Option<i64>,
+    /// Stream descriptors and config schemas (only with `fields=full`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub streams: Option<Vec<&'static crate::registry::RegisteredStream>>,
+}
+
+/// How much of each catalog source to return
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogFields {
+    /// Source names only
+    Names,
+    /// Display summary without streams
+    #[default]
+    Summary,
+    /// Summary plus every stream's descriptor and config schema
+    Full,
+}
+
+/// Query params for the catalog
+///
+/// Without `limit` every source is returned, as before pagination existed.
+/// Sources are ordered by name so pages are stable.
+#[derive(Debug, Deserialize)]
+pub struct CatalogQuery {
+    /// Return only this source (e.g. to lazy-load its streams with `fields=full`)
+    pub source: Option<String>,
+    #[serde(default)]
+    pub fields: CatalogFields,
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub offset: usize,
 }
 
+/// Largest page the catalog returns when `limit` is given
+const MAX_CATALOG_PAGE: usize = 100;
+
 /// Fetch user tier from Tollbooth (which hydrates from Atlas)
 async fn fetch_user_tier() -> Result<String, String> {
     let tollbooth_url =
@@ -530,8 +878,14 @@ async fn fetch_user_tier() -> Result<String, String> {
         .ok_or_else(|| "Tollbooth response missing 'tier' field".to_string())
 }
 
-/// List all available source types from the registry
-pub async fn list_catalog_sources_handler(State(state): State<AppState>) -> Response {
+/// List available source types from the registry
+///
+/// Supports `?limit=&offset=` pagination, `?source=` to fetch one source, and
+/// `?fields=names|summary|full` to control how much of each source is sent.
+pub async fn list_catalog_sources_handler(
+    State(state): State<AppState>,
+    Query(query): Query<CatalogQuery>,
+) -> Response {
     // Fetch tier from Tollbooth (fallback to TIER env var, then "standard")
     let user_tier = match fetch_user_tier().await {
         Ok(tier) => tier,
@@ -546,7 +900,40 @@ pub async fn list_catalog_sources_handler(State(state): State<AppState>) -> Resp
         }
     };
 
-    let sources = crate::registry::list_sources();
+    let mut sources = crate::registry::list_sources();
+    if let Some(name) = &query.source {
+        sources.retain(|s| s.descriptor.name == name);
+        if sources.is_empty() {
+            return error_response(Error::NotFound(format!(
+                "Source '{}' not found in catalog",
+                name
+            )));
+        }
+    }
+    sources.sort_by_key(|s| s.descriptor.name);
+
+    let total = sources.len();
+    let limit = query.limit.map(|l| l.min(MAX_CATALOG_PAGE));
+    let sources: Vec<_> = sources
+        .into_iter()
+        .skip(query.offset)
+        .take(limit.unwrap_or(usize::MAX))
+        .collect();
+
+    if query.fields == CatalogFields::Names {
+        let names: Vec<&str> = sources.iter().map(|s| s.descriptor.name).collect();
+        return (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "tier": user_tier,
+                "sources": names,
+                "total": total,
+                "offset": query.offset,
+                "limit": limit,
+            })),
+        )
+            .into_response();
+    }
 
     // Get current connection counts per source type
     let counts: std::collections::HashMap<String, i64> = sqlx::query_as::<_, (String, i64)>(
@@ -580,10 +967,12 @@ pub async fn list_catalog_sources_handler(State(state): State<AppState>) -> Resp
                 description: s.descriptor.description.to_string(),
                 auth_type: format!("{:?}", s.descriptor.auth_type).to_lowercase(),
                 stream_count: s.streams.len(),
+                implemented_stream_count: s.streams.iter().filter(|st| st.is_implemented()).count(),
                 icon: s.descriptor.icon.map(|i| i.to_string()),
                 is_multi_instance: is_multi,
                 connection_limits: limits,
                 current_connections: counts.get(s.descriptor.name).copied(),
+                streams: (query.fields == CatalogFields::Full).then(|| s.streams.iter().collect()),
             }
         })
         .collect();
@@ -593,6 +982,9 @@ pub async fn list_catalog_sources_handler(State(state): State<AppState>) -> Resp
         Json(serde_json::json!({
             "tier": user_tier,
             "sources": catalog,
+            "total": total,
+            "offset": query.offset,
+            "limit": limit,
         })),
     )
         .into_response()
@@ -611,6 +1003,13 @@ pub async fn get_ontologies_overview_handler(State(state): State<AppState>) -> R
     api_response(crate::api::ontologies::get_ontologies_overview(state.db.pool()).await)
 }
 
+/// List stream → ontology table edges, filtered by `source`, `stream` or `ontology`
+pub async fn get_ontology_lineage_handler(
+    Query(query): Query<crate::api::ontologies::OntologyLineageQuery>,
+) -> Response {
+    api_response(Ok(crate::api::ontologies::ontology_lineage(&query)))
+}
+
 // ============================================================================
 // Jobs API
 // ============================================================================
@@ -692,6 +1091,40 @@ pub async fn cancel_job_handler(
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RunPipelineQuery {
+    /// Day to summarize (`daily_summary` only, YYYY-MM-DD)
+    pub date: Option<chrono::NaiveDate>,
+    /// Single stage to re-run (`entity_resolution` only: places, people)
+    pub stage: Option<String>,
+}
+
+/// Run a scheduler pipeline immediately as a tracked job
+pub async fn run_pipeline_handler(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Query(params): Query<RunPipelineQuery>,
+) -> Response {
+    let stage = match params.stage.as_deref().map(str::parse).transpose() {
+        Ok(stage) => stage,
+        Err(e) => return error_response(Error::InvalidInput(e)),
+    };
+
+    match crate::api::trigger_pipeline_job(
+        state.db.pool(),
+        &state.storage,
+        state.stream_writer.clone(),
+        &name,
+        params.date,
+        stage,
+    )
+    .await
+    {
+        Ok(response) => (StatusCode::CREATED, Json(response)).into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
 /// Get job history for a specific stream
 #[derive(Debug, Deserialize)]
 pub struct StreamJobsParams {
@@ -1298,6 +1731,22 @@ pub async fn get_plaid_accounts_handler(
     api_response(crate::api::get_plaid_accounts(state.db.pool(), source_id).await)
 }
 
+/// Reconnect a Plaid source after Link's update mode
+pub async fn reconnect_plaid_item_handler(
+    State(state): State<AppState>,
+    Path(source_id): Path<String>,
+) -> Response {
+    api_response(
+        crate::api::reconnect_plaid_item(
+            state.db.pool(),
+            &state.storage,
+            state.stream_writer.clone(),
+            source_id,
+        )
+        .await,
+    )
+}
+
 /// Remove a Plaid Item (disconnect bank account)
 pub async fn remove_plaid_item_handler(
     State(state): State<AppState>,
@@ -1591,12 +2040,90 @@ pub async fn list_storage_objects_handler(
     api_response(crate::api::list_recent_objects(state.db.pool(), limit).await)
 }
 
+/// Query parameters for previewing a stream's archived records
+#[derive(Debug, Deserialize)]
+pub struct StreamPreviewParams {
+    /// First day of the range
+    pub start: chrono::NaiveDate,
+    /// Last day of the range (inclusive)
+    pub end: chrono::NaiveDate,
+    pub limit: Option<usize>,
+    /// Fill in Gmail bodies stored outside the records
+    #[serde(default)]
+    pub resolve_bodies: bool,
+}
+
+/// GET /api/sources/:id/streams/:name/records - Archived records for a date range
+pub async fn preview_stream_records_handler(
+    State(state): State<AppState>,
+    Path((source_id, stream_name)): Path<(String, String)>,
+    Query(params): Query<StreamPreviewParams>,
+) -> Response {
+    api_response(
+        crate::api::preview_stream_records(
+            state.db.pool(),
+            &state.storage,
+            &source_id,
+            &stream_name,
+            params.start,
+            params.end,
+            params.limit,
+            params.resolve_bodies,
+        )
+        .await,
+    )
+}
+
+/// Query parameters for the unified feed
+#[derive(Debug, Deserialize)]
+pub struct FeedParams {
+    /// Only records archived after this time
+    pub since: Option<chrono::DateTime<chrono::Utc>>,
+    /// Continue after a previous page (takes precedence over `since`)
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// GET /api/feed - New records across all enabled streams, in archive order
+pub async fn get_feed_handler(
+    State(state): State<AppState>,
+    Query(params): Query<FeedParams>,
+) -> Response {
+    api_response(
+        crate::api::get_feed(
+            state.db.pool(),
+            &state.storage,
+            params.since,
+            params.cursor.as_deref(),
+            params.limit,
+        )
+        .await,
+    )
+}
+
+/// Query parameters for storage object content
+#[derive(Debug, Deserialize)]
+pub struct StorageObjectContentParams {
+    /// Fill in Gmail bodies stored outside the records
+    #[serde(default)]
+    pub resolve_bodies: bool,
+}
+
 /// Get decrypted content of a storage object
 pub async fn get_storage_object_content_handler(
     State(state): State<AppState>,
     Path(object_id): Path<String>,
+    Query(params): Query<StorageObjectContentParams>,
 ) -> Response {
-    api_response(crate::api::get_object_content(state.db.pool(), &*state.storage, object_id).await)
+    api_response(
+        crate::api::get_object_content(
+            state.db.pool(),
+            &state.storage,
+            object_id,
+            params.resolve_bodies,
+        )
+        .await,
+    )
 }
 
 // =============================================================================
@@ -2167,6 +2694,22 @@ pub async fn chat_handler(
     .await
 }
 
+/// POST /api/chat/resume - Continue a chat's interrupted agent run (requires authentication)
+pub async fn resume_chat_handler(
+    State(state): State<AppState>,
+    user: crate::middleware::auth::AuthUser,
+    Json(request): Json<crate::api::chat::ResumeChatRequest>,
+) -> Response {
+    crate::api::chat::resume_chat_handler(
+        axum::extract::State(state.db.pool().clone()),
+        axum::extract::State(state.yjs_state.clone()),
+        axum::extract::State(state.chat_cancel_state.clone()),
+        user,
+        Json(request),
+    )
+    .await
+}
+
 /// POST /api/chat/cancel - Cancel an in-progress chat request
 pub async fn cancel_chat_handler(
     State(state): State<AppState>,
@@ -2284,6 +2827,42 @@ pub async fn auth_owner_email_handler(
     .into_response()
 }
 
+// =============================================================================
+// Provider Webhooks
+// =============================================================================
+
+/// GET /webhooks/:provider - Answer a provider's subscription challenge
+pub async fn webhook_challenge_handler(
+    Path(provider): Path<String>,
+    Query(query): Query<std::collections::HashMap<String, String>>,
+) -> Response {
+    api_response(crate::api::webhooks::handle_webhook_challenge(
+        &provider, &query,
+    ))
+}
+
+/// POST /webhooks/:provider - Verify a webhook delivery and queue the syncs it implies
+///
+/// Answers as soon as the delivery is queued; 503 when the queue is full.
+pub async fn webhook_event_handler(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+    Query(query): Query<std::collections::HashMap<String, String>>,
+    headers: axum::http::HeaderMap,
+    body: axum::body::Bytes,
+) -> Response {
+    api_response(
+        crate::api::webhooks::verify_webhook_and_enqueue(
+            &state.webhook_queue,
+            &provider,
+            &headers,
+            &query,
+            &body,
+        )
+        .await,
+    )
+}
+
 // =============================================================================
 // Drive API Handlers (User File Storage)
 // =============================================================================
@@ -2333,14 +2912,22 @@ pub async fn get_drive_file_handler(
 }
 
 /// GET /api/drive/files/:id/download - Download file content
+///
+/// Lake objects take `resolve_bodies` like the storage object preview.
 pub async fn download_drive_file_handler(
     State(state): State<AppState>,
     Path(file_id): Path<String>,
+    Query(params): Query<StorageObjectContentParams>,
 ) -> Response {
     // Lake objects use in-memory download (different storage layer)
     if crate::api::is_lake_object_id(&file_id) {
-        let result =
-            crate::api::download_lake_object(state.db.pool(), &state.storage, &file_id).await;
+        let result = crate::api::download_lake_object(
+            state.db.pool(),
+            &state.storage,
+            &file_id,
+            params.resolve_bodies,
+        )
+        .await;
         return match result {
             Ok((file, content)) => {
                 let content_type = file
@@ -2625,29 +3212,120 @@ pub async fn hydrate_profile_handler(
     headers: axum::http::HeaderMap,
     Json(request): Json<crate::api::HydrateRequest>,
 ) -> Response {
-    // Validate Tollbooth secret
+    if let Some(rejection) = check_internal_secret(&headers) {
+        return rejection;
+    }
+
+    api_response(crate::api::hydrate_profile(state.db.pool(), request).await)
+}
+
+/// Validate the Tollbooth secret of a request to an internal route
+///
+/// Returns the 401 response to send when it's missing or wrong. In
+/// production the secret is required; in dev any request is allowed.
+fn check_internal_secret(headers: &axum::http::HeaderMap) -> Option<Response> {
     let expected_secret = std::env::var("TOLLBOOTH_INTERNAL_SECRET").unwrap_or_default();
     let provided_secret = headers
         .get("X-Tollbooth-Secret")
         .and_then(|v| v.to_str().ok())
         .unwrap_or("");
 
-    // In production, require the secret; in dev, allow any request
     let is_production = std::env::var("RUST_ENV")
         .map(|v| v == "production")
         .unwrap_or(false);
 
     if is_production && (expected_secret.is_empty() || provided_secret != expected_secret) {
-        return (
-            StatusCode::UNAUTHORIZED,
-            Json(serde_json::json!({
-                "error": "Invalid or missing X-Tollbooth-Secret header"
-            })),
-        )
-            .into_response();
+        return Some(
+            (
+                StatusCode::UNAUTHORIZED,
+                Json(serde_json::json!({
+                    "error": "Invalid or missing X-Tollbooth-Secret header"
+                })),
+            )
+                .into_response(),
+        );
     }
 
-    api_response(crate::api::hydrate_profile(state.db.pool(), request).await)
+    None
+}
+
+/// Run the `sync_stream` MCP tool for the stdio MCP server
+///
+/// That server runs in its own process; starting the sync here puts it under
+/// this process's sync limit and stream locks.
+pub async fn internal_sync_stream_handler(
+    State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
+    Json(request): Json<crate::mcp::sync::SyncStreamRequest>,
+) -> Response {
+    use crate::mcp::sync::{sync_stream, SyncTarget, SYNC_STREAM_WAIT};
+
+    if let Some(rejection) = check_internal_secret(&headers) {
+        return rejection;
+    }
+
+    let target = SyncTarget::Local {
+        storage: state.storage.clone(),
+        stream_writer: state.stream_writer.clone(),
+    };
+    api_response(sync_stream(state.db.pool(), &target, &request, SYNC_STREAM_WAIT).await)
+}
+
+/// Query params for tailing ingestion
+#[derive(Debug, Deserialize)]
+pub struct IngestTailQuery {
+    /// Only batches for this source identifier (e.g., "ios.healthkit")
+    pub source: Option<String>,
+    /// Only batches for this stream
+    pub stream: Option<String>,
+}
+
+/// GET /internal/ingest/tail - Stream accepted ingest batches as server-sent events
+///
+/// Each `ingest` event carries an `IngestEvent` for one batch. A subscriber
+/// that falls too far behind gets a `lagged` event with the number of
+/// batches it skipped, then continues from the newest.
+pub async fn ingest_tail_handler(
+    State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
+    Query(query): Query<IngestTailQuery>,
+) -> Response {
+    use axum::response::sse::{Event, KeepAlive, Sse};
+    use tokio::sync::broadcast::error::RecvError;
+
+    if let Some(rejection) = check_internal_secret(&headers) {
+        return rejection;
+    }
+
+    let IngestTailQuery { source, stream } = query;
+    let receiver = state.ingest_events.subscribe();
+    let events = futures::stream::unfold(receiver, move |mut receiver| {
+        let source = source.clone();
+        let stream = stream.clone();
+        async move {
+            loop {
+                let event = match receiver.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => {
+                        let event = Event::default().event("lagged").data(skipped.to_string());
+                        return Some((Ok(event), receiver));
+                    }
+                    Err(RecvError::Closed) => return None,
+                };
+                if source.as_ref().is_some_and(|s| *s != event.source)
+                    || stream.as_ref().is_some_and(|s| *s != event.stream)
+                {
+                    continue;
+                }
+                let event = Event::default().event("ingest").json_data(&event);
+                return Some((event, receiver));
+            }
+        }
+    });
+
+    Sse::new(events)
+        .keep_alive(KeepAlive::new())
+        .into_response()
 }
 
 /// GET /internal/server-status - Get current server status
@@ -2800,7 +3478,8 @@ pub async fn shared_file_download_handler(
     // Lake objects use in-memory download
     if crate::api::is_lake_object_id(&file_id) {
         let result =
-            crate::api::download_lake_object(state.db.pool(), &state.storage, &file_id).await;
+            crate::api::download_lake_object(state.db.pool(), &state.storage, &file_id, false)
+                .await;
         return match result {
             Ok((file, content)) => {
                 let content_type = file
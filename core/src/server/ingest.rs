@@ -1,22 +1,35 @@
 //! Ingestion API for receiving data from all sources
 
 use axum::{
-    extract::{rejection::JsonRejection, Json, State},
+    body::Body,
+    extract::{rejection::JsonRejection, Json, Query, State},
     http::{HeaderMap, StatusCode},
     response::{IntoResponse, Response},
 };
 use chrono::{DateTime, Utc};
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, Mutex};
 
 use crate::{
-    api::chat::ChatCancellationState,
+    api::{chat::ChatCancellationState, webhooks::WebhookQueue},
     database::Database,
     error::{Error, Result},
-    sources::{push_stream::IngestPayload, stream_type::StreamType, StreamFactory},
-    storage::{stream_writer::StreamWriter, Storage},
+    jobs::archive::ArchiveFlushConfig,
+    sources::{
+        envelope,
+        push_stream::{
+            write_prepared, IngestPayload, PreparedRecord, PushResult, RecordResult, RecordStatus,
+        },
+        stream_type::StreamType,
+        StreamFactory,
+    },
+    storage::{
+        stream_writer::{PendingBuffer, StreamWriter},
+        Storage,
+    },
 };
 
 /// Ingestion request from any source
@@ -55,6 +68,11 @@ pub struct IngestResponse {
     /// Number of records rejected
     pub rejected: usize,
 
+    /// Outcome of each record, in request order
+    ///
+    /// Devices retry only the rejected records rather than the whole batch.
+    pub results: Vec<RecordResult>,
+
     /// Next checkpoint for incremental sync
     pub next_checkpoint: Option<String>,
 
@@ -62,6 +80,22 @@ pub struct IngestResponse {
     pub activity_id: String,
 }
 
+/// Records accepted by one ingest batch, as published to live tails
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestEvent {
+    pub source_id: String,
+    /// Source identifier from the request (e.g., "ios.healthkit")
+    pub source: String,
+    pub stream: String,
+    pub device_id: String,
+    pub received_at: DateTime<Utc>,
+    /// The accepted records, as sent by the device
+    pub records: Vec<Value>,
+}
+
+/// Ingest events buffered per tail subscriber before it starts skipping
+pub const INGEST_TAIL_CAPACITY: usize = 256;
+
 /// Shared application state
 #[derive(Clone)]
 pub struct AppState {
@@ -69,9 +103,19 @@ pub struct AppState {
     pub storage: Arc<Storage>,
     pub drive_config: crate::api::DriveConfig,
     pub stream_writer: Arc<Mutex<StreamWriter>>,
+    /// Buffers device-pushed records until they're flushed to the archive
+    ///
+    /// Kept apart from `stream_writer`, whose buffers belong to pull syncs in
+    /// progress and are archived by their sync jobs.
+    pub ingest_writer: Arc<Mutex<StreamWriter>>,
+    /// When buffered device records are flushed to the archive
+    pub archive_flush: ArchiveFlushConfig,
+    /// Accepted ingest batches, for `virtues ingest tail`
+    pub ingest_events: broadcast::Sender<IngestEvent>,
     pub tool_executor: Option<Arc<crate::tools::ToolExecutor>>,
     pub yjs_state: super::yjs::YjsState,
     pub chat_cancel_state: ChatCancellationState,
+    pub webhook_queue: WebhookQueue,
 }
 
 /// Enable extracting SqlitePool from AppState for auth middleware
@@ -108,37 +152,11 @@ pub async fn ingest(
             return rejection.into_response();
         }
     };
-    // Extract and validate device token from Authorization header
-    let device_token = match extract_device_token(&headers) {
-        Some(token) => token,
-        None => {
-            return (StatusCode::UNAUTHORIZED, Json(serde_json::json!({
-                "error": "Missing device token",
-                "hint": "Include 'Authorization: Bearer <device_token>' or 'X-Device-Token: <device_token>' header"
-            }))).into_response();
-        }
-    };
-
-    // Validate device token and get source_id
-    let source_id = match crate::api::validate_device_token(state.db.pool(), &device_token).await {
+    let source_id = match authenticate_device(&state, &headers).await {
         Ok(id) => id,
-        Err(e) => {
-            return (
-                StatusCode::UNAUTHORIZED,
-                Json(serde_json::json!({
-                    "error": "Invalid or revoked device token",
-                    "message": e.to_string()
-                })),
-            )
-                .into_response();
-        }
+        Err(response) => return response,
     };
 
-    // Update last_seen timestamp
-    if let Err(e) = crate::api::update_last_seen(state.db.pool(), &source_id).await {
-        tracing::warn!("Failed to update last_seen: {}", e);
-    }
-
     // Validate source and stream exist
     if let Err(e) = validate_source_stream(&state.db, &payload.source, &payload.stream).await {
         return (
@@ -151,7 +169,7 @@ pub async fn ingest(
     }
 
     // Process records using PushStream trait
-    let (accepted, rejected) = match process_batch(
+    let results = match process_batch(
         &state,
         &source_id,
         &payload.source,
@@ -162,16 +180,19 @@ pub async fn ingest(
     )
     .await
     {
-        Ok(counts) => counts,
+        Ok(results) => results,
         Err(e) => {
             tracing::error!("Failed to process records: {}", e);
-            (0, payload.records.len())
+            reject_all(&payload.records, &e)
         }
     };
+    let accepted = count_accepted(&results);
+    let rejected = results.len() - accepted;
 
-    // Trigger transforms if records were successfully processed (hot path like cloud syncs)
+    // Flush once the stream's buffer is large or old enough; otherwise the
+    // background flusher picks it up
     if accepted > 0 {
-        if let Err(e) = trigger_transforms_for_batch(&state, &source_id, &payload.stream).await {
+        if let Err(e) = flush_if_due(&state, &source_id, &payload.stream).await {
             tracing::error!(
                 error = %e,
                 error_debug = ?e,
@@ -190,6 +211,7 @@ pub async fn ingest(
         Json(IngestResponse {
             accepted,
             rejected,
+            results,
             next_checkpoint: None, // Checkpoint management will be implemented later if needed
             activity_id: uuid::Uuid::new_v4().to_string(), // Generate ID for debugging/tracing
         }),
@@ -197,6 +219,321 @@ pub async fn ingest(
         .into_response()
 }
 
+/// Authenticate a device from its token and return its source_id
+///
+/// Also updates the device's last_seen timestamp. On failure, returns the
+/// 401 response to send back.
+async fn authenticate_device(
+    state: &AppState,
+    headers: &HeaderMap,
+) -> std::result::Result<String, Response> {
+    // Extract and validate device token from Authorization header
+    let device_token = match extract_device_token(headers) {
+        Some(token) => token,
+        None => {
+            return Err((StatusCode::UNAUTHORIZED, Json(serde_json::json!({
+                "error": "Missing device token",
+                "hint": "Include 'Authorization: Bearer <device_token>' or 'X-Device-Token: <device_token>' header"
+            }))).into_response());
+        }
+    };
+
+    // Validate device token and get source_id
+    let source_id = match crate::api::validate_device_token(state.db.pool(), &device_token).await {
+        Ok(id) => id,
+        Err(e) => {
+            return Err((
+                StatusCode::UNAUTHORIZED,
+                Json(serde_json::json!({
+                    "error": "Invalid or revoked device token",
+                    "message": e.to_string()
+                })),
+            )
+                .into_response());
+        }
+    };
+
+    // Update last_seen timestamp
+    if let Err(e) = crate::api::update_last_seen(state.db.pool(), &source_id).await {
+        tracing::warn!("Failed to update last_seen: {}", e);
+    }
+
+    Ok(source_id)
+}
+
+// ============================================================================
+// Streaming (NDJSON) ingestion
+// ============================================================================
+
+/// Records buffered before each write through the push stream
+const STREAM_BATCH_SIZE: usize = 500;
+
+/// Maximum size of a single NDJSON line
+const MAX_LINE_BYTES: usize = 1024 * 1024;
+
+/// Maximum number of per-line errors reported back to the device
+const MAX_REPORTED_ERRORS: usize = 100;
+
+/// Query parameters for streaming ingestion
+///
+/// Batch metadata moves to the query string since the body is a bare
+/// sequence of records.
+#[derive(Debug, Deserialize)]
+pub struct StreamIngestQuery {
+    /// Source identifier (e.g., "ios", "mac")
+    pub source: String,
+
+    /// Stream within the source (e.g., "healthkit", "location")
+    pub stream: String,
+
+    /// Device or instance ID
+    pub device_id: String,
+}
+
+/// A record line that could not be ingested
+#[derive(Debug, Serialize)]
+pub struct RejectedLine {
+    /// 1-based line number in the request body
+    pub line: usize,
+    pub error: String,
+}
+
+/// Response after streaming ingestion
+#[derive(Debug, Serialize)]
+pub struct StreamIngestResponse {
+    /// Number of records accepted
+    pub accepted: usize,
+
+    /// Number of records rejected (unparseable lines and records the stream refused)
+    pub rejected: usize,
+
+    /// Number of batches written
+    pub batches: usize,
+
+    /// Per-line errors, from parsing or the stream's validation (capped at 100)
+    pub errors: Vec<RejectedLine>,
+
+    /// Pipeline activity ID for tracking
+    pub activity_id: String,
+}
+
+/// Splits a chunked byte stream into complete newline-delimited lines
+#[derive(Debug, Default)]
+struct LineSplitter {
+    buffer: Vec<u8>,
+}
+
+impl LineSplitter {
+    /// Append a chunk and return the complete lines it finished
+    fn push(&mut self, chunk: &[u8]) -> std::result::Result<Vec<Vec<u8>>, String> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut lines = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let mut line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            line.pop();
+            lines.push(line);
+        }
+
+        if self.buffer.len() > MAX_LINE_BYTES {
+            return Err(format!(
+                "Line exceeds maximum size of {} bytes",
+                MAX_LINE_BYTES
+            ));
+        }
+
+        Ok(lines)
+    }
+
+    /// Return the trailing line if the body did not end with a newline
+    fn finish(self) -> Option<Vec<u8>> {
+        (!self.buffer.is_empty()).then_some(self.buffer)
+    }
+}
+
+/// Parse one NDJSON line; `None` for blank lines
+fn parse_line(line: &[u8]) -> Option<std::result::Result<Value, String>> {
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    if line.iter().all(u8::is_ascii_whitespace) {
+        return None;
+    }
+
+    Some(match serde_json::from_slice::<Value>(line) {
+        Ok(value) if value.is_object() => Ok(value),
+        Ok(_) => Err("Record must be a JSON object".to_string()),
+        Err(e) => Err(format!("Invalid JSON: {}", e)),
+    })
+}
+
+/// Running state for a streaming ingest request
+struct StreamIngest<'a> {
+    state: &'a AppState,
+    source_id: String,
+    query: StreamIngestQuery,
+    pending: Vec<Value>,
+    /// Line number of each pending record
+    pending_lines: Vec<usize>,
+    line_number: usize,
+    response: StreamIngestResponse,
+}
+
+impl StreamIngest<'_> {
+    fn record_error(&mut self, line: usize, error: String) {
+        self.response.rejected += 1;
+        if self.response.errors.len() < MAX_REPORTED_ERRORS {
+            self.response.errors.push(RejectedLine { line, error });
+        }
+    }
+
+    async fn handle_line(&mut self, line: &[u8]) {
+        self.line_number += 1;
+        match parse_line(line) {
+            None => {}
+            Some(Ok(record)) => {
+                self.pending.push(record);
+                self.pending_lines.push(self.line_number);
+                if self.pending.len() >= STREAM_BATCH_SIZE {
+                    self.flush().await;
+                }
+            }
+            Some(Err(e)) => self.record_error(self.line_number, e),
+        }
+    }
+
+    /// Write pending records through the push stream and hand them to transforms
+    async fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+
+        let records = std::mem::take(&mut self.pending);
+        let lines = std::mem::take(&mut self.pending_lines);
+        let results = match process_batch(
+            self.state,
+            &self.source_id,
+            &self.query.source,
+            &self.query.stream,
+            &records,
+            &self.query.device_id,
+            Utc::now(),
+        )
+        .await
+        {
+            Ok(results) => results,
+            Err(e) => {
+                tracing::error!("Failed to process streamed records: {}", e);
+                reject_all(&records, &e)
+            }
+        };
+
+        let accepted = count_accepted(&results);
+        for result in results {
+            if let Some(reason) = result.reason {
+                self.record_error(lines[result.index], reason);
+            }
+        }
+        self.response.accepted += accepted;
+        self.response.batches += 1;
+
+        // The size threshold keeps server memory bounded during long uploads
+        if accepted > 0 {
+            if let Err(e) = flush_if_due(self.state, &self.source_id, &self.query.stream).await {
+                tracing::error!(
+                    error = %e,
+                    source_id = %self.source_id,
+                    stream = %self.query.stream,
+                    device_id = %self.query.device_id,
+                    "CRITICAL: Failed to trigger transforms for streamed batch - data will NOT be archived or transformed!"
+                );
+            }
+        }
+    }
+}
+
+/// Streaming ingestion handler
+///
+/// Accepts newline-delimited JSON records (one object per line), typically
+/// sent with chunked transfer encoding. Records are parsed as they arrive and
+/// written in batches, so the full upload is never buffered server-side.
+/// Unparseable lines are rejected individually without failing the request.
+pub async fn ingest_stream(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<StreamIngestQuery>,
+    body: Body,
+) -> Response {
+    let source_id = match authenticate_device(&state, &headers).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    if let Err(e) = validate_source_stream(&state.db, &query.source, &query.stream).await {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": e.to_string()
+            })),
+        )
+            .into_response();
+    }
+
+    let mut ingest = StreamIngest {
+        state: &state,
+        source_id,
+        query,
+        pending: Vec::with_capacity(STREAM_BATCH_SIZE),
+        pending_lines: Vec::with_capacity(STREAM_BATCH_SIZE),
+        line_number: 0,
+        response: StreamIngestResponse {
+            accepted: 0,
+            rejected: 0,
+            batches: 0,
+            errors: Vec::new(),
+            activity_id: uuid::Uuid::new_v4().to_string(),
+        },
+    };
+
+    let mut splitter = LineSplitter::default();
+    let mut body = body.into_data_stream();
+
+    while let Some(chunk) = body.next().await {
+        let lines = match chunk
+            .map_err(|e| format!("Failed to read request body: {}", e))
+            .and_then(|chunk| splitter.push(&chunk))
+        {
+            Ok(lines) => lines,
+            Err(e) => {
+                // Keep what was already written; report where the stream broke off
+                tracing::warn!(error = %e, "Aborting streaming ingest");
+                ingest.flush().await;
+                let line = ingest.line_number + 1;
+                ingest.record_error(line, e);
+                return (StatusCode::BAD_REQUEST, Json(ingest.response)).into_response();
+            }
+        };
+
+        for line in lines {
+            ingest.handle_line(&line).await;
+        }
+    }
+
+    if let Some(line) = splitter.finish() {
+        ingest.handle_line(&line).await;
+    }
+    ingest.flush().await;
+
+    tracing::info!(
+        source_id = %ingest.source_id,
+        stream = %ingest.query.stream,
+        accepted = ingest.response.accepted,
+        rejected = ingest.response.rejected,
+        batches = ingest.response.batches,
+        "Streaming ingest completed"
+    );
+
+    (StatusCode::OK, Json(ingest.response)).into_response()
+}
+
 /// Extract device token from Authorization header
 fn extract_device_token(headers: &HeaderMap) -> Option<String> {
     headers
@@ -223,7 +560,26 @@ async fn validate_source_stream(_db: &Database, _source: &str, _stream: &str) ->
     Ok(())
 }
 
+/// Reject every record of a batch that failed as a whole
+fn reject_all(records: &[Value], error: &Error) -> Vec<RecordResult> {
+    records
+        .iter()
+        .enumerate()
+        .map(|(index, record)| RecordResult::rejected(index, record, error.to_string()))
+        .collect()
+}
+
+fn count_accepted(results: &[RecordResult]) -> usize {
+    results
+        .iter()
+        .filter(|r| r.status == RecordStatus::Accepted)
+        .count()
+}
+
 /// Process batch of records using PushStream trait
+///
+/// Returns the outcome of each record. An error means the whole batch was
+/// refused (unknown stream, invalid payload).
 async fn process_batch(
     state: &AppState,
     source_id: &str,
@@ -232,12 +588,20 @@ async fn process_batch(
     records: &[Value],
     device_id: &str,
     timestamp: DateTime<Utc>,
-) -> Result<(usize, usize)> {
+) -> Result<Vec<RecordResult>> {
+    if envelope::is_end_to_end_encrypted(state.db.pool(), source_id, stream).await? {
+        let results = receive_sealed(state, source_id, stream, records).await;
+        publish_ingest_event(
+            state, source_id, source, stream, device_id, records, &results,
+        );
+        return Ok(results);
+    }
+
     // Create factory and get stream instance
     let factory = StreamFactory::new(
         state.db.pool().clone(),
         state.storage.clone(),
-        state.stream_writer.clone(),
+        state.ingest_writer.clone(),
     );
 
     // Create the stream instance using the new StreamType pattern
@@ -268,14 +632,168 @@ async fn process_batch(
     // source_id is created once in handler - single source of truth
     let result = push_stream.receive_push(source_id, payload).await?;
 
-    // Return (accepted, rejected) counts
-    // Note: PushResult only tracks received/written, so we calculate rejected as difference
-    let accepted = result.records_written;
-    let rejected = result
-        .records_received
-        .saturating_sub(result.records_written);
+    publish_ingest_event(
+        state,
+        source_id,
+        source,
+        stream,
+        device_id,
+        records,
+        &result.results,
+    );
+
+    Ok(result.results)
+}
+
+/// Buffer sealed envelopes for an end-to-end encrypted stream
+///
+/// The stream's own push handling is bypassed: it can't validate what it
+/// can't read. Each envelope is checked and archived as received, at its
+/// clear-text event time, without the fields the writer stamps on plaintext
+/// records; plaintext records are rejected.
+async fn receive_sealed(
+    state: &AppState,
+    source_id: &str,
+    stream: &str,
+    records: &[Value],
+) -> Vec<RecordResult> {
+    state
+        .ingest_writer
+        .lock()
+        .await
+        .register_sealed(source_id, stream, true);
+
+    let mut result = PushResult::new(records.len());
+    let mut prepared = Vec::with_capacity(records.len());
+
+    for (index, record) in records.iter().enumerate() {
+        match envelope::Envelope::parse(record) {
+            Ok((_, timestamp)) => prepared.push(PreparedRecord {
+                index,
+                record: record.clone(),
+                timestamp: Some(timestamp),
+            }),
+            Err(e) => result.reject(index, record, e),
+        }
+    }
+
+    write_prepared(
+        &state.ingest_writer,
+        source_id,
+        stream,
+        records,
+        prepared,
+        &mut result,
+    )
+    .await;
+
+    result.results
+}
+
+/// Publish a batch's accepted records to `virtues ingest tail` subscribers
+///
+/// Skipped entirely when nobody is tailing, so the hot path pays nothing.
+fn publish_ingest_event(
+    state: &AppState,
+    source_id: &str,
+    source: &str,
+    stream: &str,
+    device_id: &str,
+    records: &[Value],
+    results: &[RecordResult],
+) {
+    if state.ingest_events.receiver_count() == 0 {
+        return;
+    }
+
+    let accepted: Vec<Value> = results
+        .iter()
+        .filter(|r| r.status == RecordStatus::Accepted)
+        .filter_map(|r| records.get(r.index).cloned())
+        .collect();
+    if accepted.is_empty() {
+        return;
+    }
+
+    // A send error only means the last subscriber just went away
+    let _ = state.ingest_events.send(IngestEvent {
+        source_id: source_id.to_string(),
+        source: source.to_string(),
+        stream: stream.to_string(),
+        device_id: device_id.to_string(),
+        received_at: Utc::now(),
+        records: accepted,
+    });
+}
+
+/// Flush a stream's buffered records if they've hit a flush threshold
+async fn flush_if_due(state: &AppState, source_id: &str, stream_name: &str) -> Result<()> {
+    let due = {
+        let writer = state.ingest_writer.lock().await;
+        writer
+            .pending_buffer(source_id, stream_name)
+            .is_some_and(|pending| is_due(&state.archive_flush, &pending))
+    };
+    if due {
+        trigger_transforms_for_batch(state, source_id, stream_name).await?;
+    }
+    Ok(())
+}
+
+fn is_due(config: &ArchiveFlushConfig, pending: &PendingBuffer) -> bool {
+    let age = (Utc::now() - pending.first_buffered_at)
+        .to_std()
+        .unwrap_or_default();
+    config.is_due(pending.bytes, age)
+}
+
+/// How often the background flusher checks buffered streams
+const FLUSH_CHECK_INTERVAL_SECS: u64 = 5;
+
+/// Start the background flusher for buffered device records
+///
+/// Every few seconds, flushes each stream whose buffer has passed
+/// `archive_flush.interval`, so records from a device that stops sending
+/// don't sit in memory indefinitely.
+pub fn start_archive_flusher(state: AppState) {
+    tokio::spawn(async move {
+        let mut ticker =
+            tokio::time::interval(std::time::Duration::from_secs(FLUSH_CHECK_INTERVAL_SECS));
+        loop {
+            ticker.tick().await;
+            let due: Vec<PendingBuffer> = {
+                let writer = state.ingest_writer.lock().await;
+                writer
+                    .pending_buffers()
+                    .into_iter()
+                    .filter(|pending| is_due(&state.archive_flush, pending))
+                    .collect()
+            };
+            flush_buffers(&state, due).await;
+        }
+    });
+}
+
+/// Flush every device stream's buffered records regardless of thresholds
+pub async fn flush_all(state: &AppState) {
+    let pending = state.ingest_writer.lock().await.pending_buffers();
+    flush_buffers(state, pending).await;
+}
 
-    Ok((accepted, rejected))
+async fn flush_buffers(state: &AppState, buffers: Vec<PendingBuffer>) {
+    for pending in buffers {
+        if let Err(e) =
+            trigger_transforms_for_batch(state, &pending.source_id, &pending.stream_name).await
+        {
+            tracing::error!(
+                error = %e,
+                source_id = %pending.source_id,
+                stream = %pending.stream_name,
+                records = pending.records,
+                "Failed to flush buffered device records"
+            );
+        }
+    }
 }
 
 /// Trigger transforms for device batch (hot path - unified with cloud syncs)
@@ -290,8 +808,8 @@ async fn trigger_transforms_for_batch(
 ) -> Result<()> {
     // Collect buffered records from StreamWriter
     let (records, min_timestamp, max_timestamp) = {
-        let mut writer = state.stream_writer.lock().await;
-        match writer.collect_records(source_id, stream_name) {
+        let mut writer = state.ingest_writer.lock().await;
+        match writer.collect_records(source_id, stream_name)? {
             Some((records, min_ts, max_ts)) => {
                 tracing::info!(
                     source_id = %source_id,
@@ -379,6 +897,16 @@ async fn trigger_transforms_for_batch(
         None
     };
 
+    // Sealed records are opaque here; only a worker holding the key can transform them
+    if envelope::is_end_to_end_encrypted(state.db.pool(), source_id, stream_name).await? {
+        tracing::debug!(
+            source_id = %source_id,
+            stream_name,
+            "End-to-end encrypted stream archived; skipping transforms"
+        );
+        return Ok(());
+    }
+
     // Create context without data source for transform triggering
     // The create_transform_job_for_stream function will create a new context
     // with the actual MemoryDataSource when executing the transform
@@ -400,6 +928,7 @@ async fn trigger_transforms_for_batch(
         source_id.to_string(),
         stream_name,
         Some(records), // Pass collected records for direct transform
+        None,
     )
     .await?;
 
@@ -425,9 +954,12 @@ async fn write_stream_records(
             .fetch_one(db)
             .await?;
 
-    let date = Utc::now().date_naive();
-    let key_builder = StreamKeyBuilder::new(None, &source_type, source_id, stream_name, date)
-        .map_err(|e| Error::Other(format!("Invalid stream key: {}", e)))?;
+    let now = Utc::now();
+    let granularity = crate::jobs::archive::stream_granularity(db, source_id, stream_name).await?;
+    let key_builder =
+        StreamKeyBuilder::new(None, &source_type, source_id, stream_name, now.date_naive())
+            .map_err(|e| Error::Other(format!("Invalid stream key: {}", e)))?
+            .with_partition(granularity, now.naive_utc());
     let storage_key = key_builder.build();
 
     // Write JSONL to filesystem
@@ -488,4 +1020,34 @@ mod tests {
         assert_eq!(request.stream, "healthkit");
         assert_eq!(request.records.len(), 1);
     }
+
+    #[test]
+    fn test_line_splitter_handles_split_chunks() {
+        let mut splitter = LineSplitter::default();
+
+        let lines = splitter.push(b"{\"a\":1}\n{\"b\"").unwrap();
+        assert_eq!(lines, vec![b"{\"a\":1}".to_vec()]);
+
+        let lines = splitter.push(b":2}\r\n\n{\"c\":3}").unwrap();
+        assert_eq!(lines, vec![b"{\"b\":2}\r".to_vec(), Vec::new()]);
+
+        assert_eq!(splitter.finish(), Some(b"{\"c\":3}".to_vec()));
+    }
+
+    #[test]
+    fn test_line_splitter_rejects_oversized_line() {
+        let mut splitter = LineSplitter::default();
+        assert!(splitter.push(&vec![b'x'; MAX_LINE_BYTES + 1]).is_err());
+    }
+
+    #[test]
+    fn test_parse_line() {
+        assert!(parse_line(b"  \r").is_none());
+        assert_eq!(
+            parse_line(b"{\"value\":72}\r").unwrap().unwrap(),
+            serde_json::json!({"value": 72})
+        );
+        assert!(parse_line(b"[1,2]").unwrap().is_err());
+        assert!(parse_line(b"{not json").unwrap().is_err());
+    }
 }
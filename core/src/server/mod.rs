@@ -59,8 +59,17 @@ pub async fn run(client: Virtues, host: &str, port: u16) -> Result<()> {
         }
     }
 
+    // Spilled buffers from a previous process were never archived; their
+    // syncs run again
+    let spill_dir = crate::storage::stream_writer::spill_dir_from_env();
+    match crate::storage::stream_writer::sweep_spill_dir(&spill_dir) {
+        Ok(0) => {}
+        Ok(removed) => tracing::info!(removed, "Removed stale stream spill files"),
+        Err(e) => tracing::warn!("Failed to sweep stream spill files: {}", e),
+    }
+
     // Initialize StreamWriter (simple in-memory buffer)
-    let stream_writer = StreamWriter::new();
+    let stream_writer = StreamWriter::from_env();
     let stream_writer_arc = Arc::new(Mutex::new(stream_writer));
 
     // Start the scheduler in the background
@@ -80,6 +89,11 @@ pub async fn run(client: Virtues, host: &str, port: u16) -> Result<()> {
                         tracing::warn!("Failed to schedule drive trash purge job: {}", e);
                     }
 
+                    // Schedule agent checkpoint prune job (hourly)
+                    if let Err(e) = sched.schedule_agent_checkpoint_prune_job().await {
+                        tracing::warn!("Failed to schedule agent checkpoint prune job: {}", e);
+                    }
+
                     // Schedule daily summary job (runs at user's maintenance hour)
                     if let Err(e) = sched.schedule_daily_summary_job().await {
                         tracing::warn!("Failed to schedule daily summary job: {}", e);
@@ -91,9 +105,14 @@ pub async fn run(client: Virtues, host: &str, port: u16) -> Result<()> {
                     }
 
                     // Keep scheduler alive - it will be dropped when the server shuts down
-                    // The JobScheduler runs background tasks that need to stay active
+                    // The JobScheduler runs background tasks that need to stay active.
+                    // Periodically reload stream jobs so pause/resume, enable/disable
+                    // and schedule changes made through the API take effect.
                     loop {
-                        tokio::time::sleep(tokio::time::Duration::from_secs(3600)).await;
+                        tokio::time::sleep(tokio::time::Duration::from_secs(300)).await;
+                        if let Err(e) = sched.reload().await {
+                            tracing::warn!("Failed to reload scheduler: {}", e);
+                        }
                     }
                 }
             }
@@ -125,15 +144,29 @@ pub async fn run(client: Virtues, host: &str, port: u16) -> Result<()> {
     // Create drive config with shared storage backend
     let drive_config = crate::api::DriveConfig::new(client.storage.clone());
 
+    // Verified webhook deliveries are processed off the request path
+    let webhook_queue = crate::api::webhooks::WebhookQueue::start(
+        client.database.pool().clone(),
+        client.storage.clone(),
+        stream_writer_arc.clone(),
+    )
+    .await?;
+
     let state = AppState {
         db: client.database.clone(),
         storage: client.storage.clone(),
         drive_config,
         stream_writer: stream_writer_arc.clone(),
+        ingest_writer: Arc::new(Mutex::new(StreamWriter::from_env())),
+        archive_flush: crate::jobs::archive::ArchiveFlushConfig::from_env(),
+        ingest_events: tokio::sync::broadcast::channel(ingest::INGEST_TAIL_CAPACITY).0,
         tool_executor,
         yjs_state: yjs_state.clone(),
         chat_cancel_state,
+        webhook_queue,
     };
+    ingest::start_archive_flusher(state.clone());
+    let shutdown_state = state.clone();
 
     // ============================================================
     // Public routes (no authentication required)
@@ -162,6 +195,16 @@ pub async fn run(client: Virtues, host: &str, port: u16) -> Result<()> {
             get(api::get_server_status_handler),
         )
         .route("/internal/mark-ready", post(api::mark_server_ready_handler))
+        .route("/internal/ingest/tail", get(api::ingest_tail_handler))
+        .route(
+            "/internal/sync-stream",
+            post(api::internal_sync_stream_handler),
+        )
+        // Provider webhooks (verified per provider via WebhookSource)
+        .route(
+            "/webhooks/:provider",
+            get(api::webhook_challenge_handler).post(api::webhook_event_handler),
+        )
         // Public page sharing (token-based access, no session needed)
         .route("/api/s/:token", get(api::get_shared_page_handler))
         .route(
@@ -177,6 +220,11 @@ pub async fn run(client: Virtues, host: &str, port: u16) -> Result<()> {
         .route("/api/timeline/day/:date", get(api::timeline_get_day_handler))
         // Data ingestion
         .route("/ingest", post(ingest::ingest))
+        // Streaming NDJSON ingestion; the body is consumed incrementally, so no size cap
+        .route(
+            "/ingest/stream",
+            post(ingest::ingest_stream).layer(DefaultBodyLimit::disable()),
+        )
         // OAuth flow
         .route(
             "/api/sources/:provider/authorize",
@@ -219,6 +267,23 @@ pub async fn run(client: Virtues, host: &str, port: u16) -> Result<()> {
         .route("/api/sources/:id", delete(api::delete_source_handler))
         .route("/api/sources/:id/pause", post(api::pause_source_handler))
         .route("/api/sources/:id/resume", post(api::resume_source_handler))
+        .route(
+            "/api/sources/:id/network",
+            get(api::get_source_network_handler).put(api::update_source_network_handler),
+        )
+        .route(
+            "/api/sources/:id/restore",
+            post(api::restore_source_handler),
+        )
+        .route(
+            "/api/sources/:id/reauthorize",
+            post(api::reauthorize_source_handler),
+        )
+        .route(
+            "/api/sources/:id/reconnect",
+            post(api::reconnect_source_handler),
+        )
+        .route("/api/sources/:id/sync", post(api::sync_source_handler))
         .route(
             "/api/sources/:id/status",
             get(api::get_source_status_handler),
@@ -247,16 +312,36 @@ pub async fn run(client: Virtues, host: &str, port: u16) -> Result<()> {
         )
         .route(
             "/api/sources/:id/streams/:name/config",
-            put(api::update_stream_config_handler),
+            put(api::update_stream_config_handler).patch(api::patch_stream_config_handler),
         )
         .route(
             "/api/sources/:id/streams/:name/schedule",
             put(api::update_stream_schedule_handler),
         )
+        .route(
+            "/api/sources/:id/streams/:name/partitioning",
+            put(api::update_stream_partitioning_handler),
+        )
+        .route(
+            "/api/sources/:id/streams/:name/encryption",
+            put(api::update_stream_encryption_handler),
+        )
         .route(
             "/api/sources/:id/streams/:name/sync",
             post(api::sync_stream_handler),
         )
+        .route(
+            "/api/sources/:id/streams/:name/sync/stream",
+            get(api::sync_progress_handler),
+        )
+        .route(
+            "/api/sources/:id/streams/:name/cursor",
+            get(api::get_stream_cursor_handler).delete(api::reset_stream_cursor_handler),
+        )
+        .route(
+            "/api/sources/:id/streams/:name/records",
+            get(api::preview_stream_records_handler),
+        )
         .route(
             "/api/sources/:id/streams/:name/jobs",
             get(api::get_stream_jobs_handler),
@@ -275,10 +360,18 @@ pub async fn run(client: Virtues, host: &str, port: u16) -> Result<()> {
             "/api/ontologies/overview",
             get(api::get_ontologies_overview_handler),
         )
+        .route(
+            "/api/ontologies/lineage",
+            get(api::get_ontology_lineage_handler),
+        )
         // Jobs API
         .route("/api/jobs/:id", get(api::get_job_handler))
         .route("/api/jobs", get(api::query_jobs_handler))
         .route("/api/jobs/:id/cancel", post(api::cancel_job_handler))
+        .route(
+            "/api/jobs/pipeline/:name/run",
+            post(api::run_pipeline_handler),
+        )
         // Profile API
         .route("/api/profile", get(api::get_profile_handler))
         .route("/api/profile", put(api::update_profile_handler))
@@ -363,6 +456,10 @@ pub async fn run(client: Virtues, host: &str, port: u16) -> Result<()> {
             "/api/plaid/:source_id/accounts",
             get(api::get_plaid_accounts_handler),
         )
+        .route(
+            "/api/plaid/:source_id/reconnect",
+            post(api::reconnect_plaid_item_handler),
+        )
         .route(
             "/api/plaid/:source_id",
             delete(api::remove_plaid_item_handler),
@@ -389,6 +486,7 @@ pub async fn run(client: Virtues, host: &str, port: u16) -> Result<()> {
             "/api/storage/objects/:id/content",
             get(api::get_storage_object_content_handler),
         )
+        .route("/api/feed", get(api::get_feed_handler))
         // Drive API (user file storage)
         .route("/api/drive/usage", get(api::get_drive_usage_handler))
         .route("/api/drive/warnings", get(api::get_drive_warnings_handler))
@@ -617,6 +715,7 @@ pub async fn run(client: Virtues, host: &str, port: u16) -> Result<()> {
         // Chat API (streaming)
         .route("/api/chat", post(api::chat_handler))
         .route("/api/chat/cancel", post(api::cancel_chat_handler))
+        .route("/api/chat/resume", post(api::resume_chat_handler))
         // Chat Edit Permissions API
         .route(
             "/api/chats/:id/permissions",
@@ -645,7 +744,11 @@ pub async fn run(client: Virtues, host: &str, port: u16) -> Result<()> {
         .layer(DefaultBodyLimit::max(105 * 1024 * 1024)); // 105MB (slightly above 100MB file limit for multipart overhead)
 
     // Add MCP routes to the same server
-    let mcp_server = VirtuesMcpServer::new(client.database.pool().clone());
+    let mcp_server = VirtuesMcpServer::new(
+        client.database.pool().clone(),
+        client.storage.clone(),
+        stream_writer_arc.clone(),
+    );
     let app = add_mcp_routes(app, mcp_server);
 
     tracing::info!("MCP endpoint enabled at /mcp");
@@ -678,6 +781,9 @@ pub async fn run(client: Virtues, host: &str, port: u16) -> Result<()> {
         app
     };
 
+    // Outermost layer, so every route (including MCP and static files) is covered
+    let app = app.layer(middleware::from_fn(crate::middleware::request_id::request_id));
+
     let addr = format!("{host}:{port}");
     let listener = tokio::net::TcpListener::bind(&addr).await?;
 
@@ -686,8 +792,8 @@ pub async fn run(client: Virtues, host: &str, port: u16) -> Result<()> {
     // Run the server (this blocks forever until shutdown)
     axum::serve(listener, app).await?;
 
-    // Note: No flush needed on shutdown - StreamWriter is in-memory only now.
-    // Records are written directly to filesystem during sync/ingest.
+    // Device records may still be waiting for a flush threshold
+    ingest::flush_all(&shutdown_state).await;
     tracing::info!("Server shutting down gracefully");
 
     // Note: scheduler runs in background and will stop when the process exits
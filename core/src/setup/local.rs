@@ -0,0 +1,112 @@
+//! Local-only mode: everything under `~/.virtues`, no keys to provision
+//!
+//! Enabled with `--local` or `VIRTUES_LOCAL=1`. Fills in whatever the
+//! environment leaves unset: a SQLite database and archive directory under
+//! `~/.virtues`, and an encryption key generated on first use and kept in
+//! `~/.virtues/encryption.key`. Variables that are set always win.
+
+use console::style;
+use std::path::PathBuf;
+
+use crate::error::{Error, Result};
+
+/// Name of the key file inside the local home
+const KEY_FILE: &str = "encryption.key";
+
+/// Whether local mode was asked for by flag or environment
+pub fn is_requested(flag: bool) -> bool {
+    flag || std::env::var("VIRTUES_LOCAL")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// Directory holding local-mode data (`~/.virtues`)
+pub fn local_home() -> Result<PathBuf> {
+    let home = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .ok_or_else(|| Error::Configuration("Cannot find home directory".to_string()))?;
+    Ok(PathBuf::from(home).join(".virtues"))
+}
+
+/// Configure the process for local mode
+///
+/// Must run before the database, storage or token manager are created, since
+/// they read their settings from the environment.
+pub fn enable() -> Result<()> {
+    let home = local_home()?;
+    std::fs::create_dir_all(&home)
+        .map_err(|e| Error::Configuration(format!("Failed to create {}: {e}", home.display())))?;
+
+    if std::env::var("DATABASE_URL").is_err() {
+        let db_path = home.join("virtues.db");
+        std::env::set_var("DATABASE_URL", format!("sqlite:{}", db_path.display()));
+    }
+    if std::env::var("STORAGE_PATH").is_err() {
+        std::env::set_var("STORAGE_PATH", home.join("lake"));
+    }
+    if std::env::var("VIRTUES_ENCRYPTION_KEY").is_err() {
+        std::env::set_var("VIRTUES_ENCRYPTION_KEY", load_or_create_key(&home)?);
+    }
+
+    println!(
+        "{} Local mode: data and encryption key are stored on this machine in {}",
+        style("ℹ").blue().bold(),
+        home.display()
+    );
+    Ok(())
+}
+
+/// Read the persisted key, generating it on first use
+fn load_or_create_key(home: &std::path::Path) -> Result<String> {
+    let path = home.join(KEY_FILE);
+    if let Ok(key) = std::fs::read_to_string(&path) {
+        let key = key.trim().to_string();
+        if !key.is_empty() {
+            return Ok(key);
+        }
+    }
+
+    let key = super::generate_encryption_key();
+    write_private(&path, &key)
+        .map_err(|e| Error::Configuration(format!("Failed to write {}: {e}", path.display())))?;
+    tracing::info!(path = %path.display(), "Generated local encryption key");
+    Ok(key)
+}
+
+/// Write a file readable only by the current user
+fn write_private(path: &std::path::Path, contents: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_is_generated_once_and_reused() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let key = load_or_create_key(dir.path()).unwrap();
+        assert_eq!(key.len(), 44); // 32 bytes, base64
+        assert_eq!(load_or_create_key(dir.path()).unwrap(), key);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(dir.path().join(KEY_FILE))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 //! Interactive setup wizard for Virtues
 
+pub mod local;
 pub mod validation;
 
 use console::style;
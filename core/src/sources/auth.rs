@@ -5,7 +5,7 @@
 
 use std::sync::Arc;
 
-use super::base::TokenManager;
+use super::base::{NetworkConfig, TokenManager};
 use crate::error::Result;
 
 /// Unified authentication abstraction for all source types
@@ -21,6 +21,8 @@ pub enum SourceAuth {
     OAuth2 {
         source_id: String,
         token_manager: Arc<TokenManager>,
+        /// Proxy / offline settings for the source's API client
+        network: NetworkConfig,
     },
 
     /// Device-based authentication (iOS, Mac devices pushing data)
@@ -55,9 +57,18 @@ impl SourceAuth {
         Self::OAuth2 {
             source_id,
             token_manager,
+            network: NetworkConfig::default(),
         }
     }
 
+    /// Attach per-source network settings (OAuth2 only; ignored otherwise)
+    pub fn with_network(mut self, config: NetworkConfig) -> Self {
+        if let Self::OAuth2 { network, .. } = &mut self {
+            *network = config;
+        }
+        self
+    }
+
     /// Create device authentication
     pub fn device(device_id: impl Into<String>) -> Self {
         Self::Device {
@@ -84,6 +95,7 @@ impl SourceAuth {
             Self::OAuth2 {
                 source_id,
                 token_manager,
+                ..
             } => {
                 let token = token_manager.get_valid_token(source_id.clone()).await?;
                 Ok(Credentials::BearerToken(token))
@@ -112,6 +124,17 @@ impl SourceAuth {
         }
     }
 
+    /// Network settings for the source's API client
+    ///
+    /// Sources without OAuth2 auth don't make outbound calls through
+    /// `SourceAuth`, so they get the defaults.
+    pub fn network(&self) -> NetworkConfig {
+        match self {
+            Self::OAuth2 { network, .. } => network.clone(),
+            _ => NetworkConfig::default(),
+        }
+    }
+
     /// Get the TokenManager for OAuth2 sources
     pub fn token_manager(&self) -> Option<&Arc<TokenManager>> {
         match self {
@@ -0,0 +1,268 @@
+//! Ordering record hand-off and cursor persistence at the end of a sync
+//!
+//! Streams buffer records in the in-memory `StreamWriter` while they page
+//! through an API, then persist a cursor so the next run starts where this one
+//! stopped. The cursor must never get ahead of the records: if it is saved but
+//! the records are lost, that data is skipped for good. So records are
+//! collected out of the writer first, and the checkpoint transaction only
+//! runs once they are safe. A failed commit drops them with the cursor
+//! unchanged, and the next run fetches them again.
+//!
+//! A sync job holds back the checkpoint (see [`hold_checkpoint`]) and runs it
+//! in the transaction that indexes the run's archived records, so the cursor
+//! only advances once the archive is durably written.
+
+use std::future::Future;
+use std::sync::{Arc, Mutex as StdMutex};
+
+use futures::future::BoxFuture;
+use serde_json::Value;
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use tokio::sync::Mutex;
+
+use crate::error::Result;
+use crate::storage::stream_writer::StreamWriter;
+
+/// Cursor updates a stream commits once its records are handed off
+pub type Checkpoint = Box<
+    dyn for<'t> FnOnce(&'t mut Transaction<'static, Sqlite>) -> BoxFuture<'t, Result<()>> + Send,
+>;
+
+tokio::task_local! {
+    static HELD_CHECKPOINT: Arc<StdMutex<Option<Checkpoint>>>;
+}
+
+/// Run a sync, holding back the checkpoint it commits
+///
+/// Returns the checkpoint passed to [`collect_then_commit`] inside `future`,
+/// if any, for the caller to run in the transaction that indexes the run's
+/// archived records.
+pub async fn hold_checkpoint<F: Future>(future: F) -> (F::Output, Option<Checkpoint>) {
+    let held = Arc::new(StdMutex::new(None));
+    let output = HELD_CHECKPOINT.scope(held.clone(), future).await;
+    let checkpoint = held.lock().unwrap_or_else(|e| e.into_inner()).take();
+    (output, checkpoint)
+}
+
+/// Collect a stream's buffered records, then commit the `checkpoint`
+///
+/// Returns the records only if the checkpoint commits. On failure the records
+/// are dropped (nothing lingers in the writer for a later run to pick up) and
+/// the commit error is returned. Records that can't be collected fail the
+/// checkpoint before it runs. Inside [`hold_checkpoint`] the checkpoint is
+/// handed to the caller instead of committed.
+pub async fn collect_then_commit<C>(
+    stream_writer: &Mutex<StreamWriter>,
+    db: &SqlitePool,
+    source_id: &str,
+    stream_name: &str,
+    checkpoint: C,
+) -> Result<Option<Vec<Value>>>
+where
+    C: for<'t> FnOnce(&'t mut Transaction<'static, Sqlite>) -> BoxFuture<'t, Result<()>>
+        + Send
+        + 'static,
+{
+    let records = stream_writer
+        .lock()
+        .await
+        .collect_records(source_id, stream_name)?
+        .map(|(records, _, _)| records);
+
+    let mut checkpoint = Some(checkpoint);
+    let held = HELD_CHECKPOINT.try_with(|held| {
+        if let Some(checkpoint) = checkpoint.take() {
+            *held.lock().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(checkpoint));
+        }
+    });
+    if held.is_ok() {
+        return Ok(records);
+    }
+
+    let committed = async {
+        let mut tx = db.begin().await?;
+        if let Some(checkpoint) = checkpoint {
+            checkpoint(&mut tx).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    };
+    if let Err(e) = committed.await {
+        tracing::warn!(
+            source_id,
+            stream_name,
+            dropped = records.as_ref().map_or(0, Vec::len),
+            error = %e,
+            "Checkpoint commit failed; cursor not advanced, records will be re-fetched"
+        );
+        return Err(e);
+    }
+
+    tracing::info!(
+        source_id,
+        stream_name,
+        record_count = records.as_ref().map_or(0, Vec::len),
+        "Collected records and committed checkpoint"
+    );
+
+    Ok(records)
+}
+
+/// Drop any records buffered for a stream without handing them off
+///
+/// Used when a sync fails before its checkpoint, and at the start of a sync to
+/// clear leftovers from a run that failed or panicked mid-way. Those records
+/// belong to a cursor range that was never committed, so they will be fetched
+/// again.
+pub async fn discard_buffered_records(
+    stream_writer: &Mutex<StreamWriter>,
+    source_id: &str,
+    stream_name: &str,
+) {
+    let discarded = stream_writer
+        .lock()
+        .await
+        .discard_records(source_id, stream_name);
+
+    if discarded > 0 {
+        tracing::warn!(
+            source_id,
+            stream_name,
+            discarded,
+            "Discarded records from an uncommitted sync"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use serde_json::json;
+
+    fn writer_with_records(n: usize) -> Mutex<StreamWriter> {
+        let mut writer = StreamWriter::new();
+        for i in 0..n {
+            writer
+                .write_record("source", "stream", json!({ "i": i }), None)
+                .unwrap();
+        }
+        Mutex::new(writer)
+    }
+
+    /// One connection, so every query sees the same in-memory database
+    async fn cursor_pool() -> SqlitePool {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE cursors (stream TEXT PRIMARY KEY, cursor TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO cursors VALUES ('stream', 'old')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool
+    }
+
+    async fn cursor(pool: &SqlitePool) -> String {
+        sqlx::query_scalar("SELECT cursor FROM cursors WHERE stream = 'stream'")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    fn advance_cursor<'t>(tx: &'t mut Transaction<'static, Sqlite>) -> BoxFuture<'t, Result<()>> {
+        Box::pin(async move {
+            sqlx::query("UPDATE cursors SET cursor = 'new' WHERE stream = 'stream'")
+                .execute(&mut **tx)
+                .await?;
+            Ok(())
+        })
+    }
+
+    #[tokio::test]
+    async fn test_records_returned_after_commit() {
+        let pool = cursor_pool().await;
+        let writer = writer_with_records(3);
+
+        let records = collect_then_commit(&writer, &pool, "source", "stream", advance_cursor)
+            .await
+            .unwrap();
+
+        assert_eq!(records.map(|r| r.len()), Some(3));
+        assert_eq!(writer.lock().await.buffer_count("source", "stream"), 0);
+        assert_eq!(cursor(&pool).await, "new");
+    }
+
+    #[tokio::test]
+    async fn test_commit_failure_drops_records() {
+        let pool = cursor_pool().await;
+        let writer = writer_with_records(3);
+
+        let result = collect_then_commit(&writer, &pool, "source", "stream", |_| {
+            Box::pin(async { Err(Error::Database("database is locked".to_string())) })
+        })
+        .await;
+
+        assert!(matches!(result, Err(Error::Database(_))));
+        // Nothing left behind for the next run to emit against a newer cursor
+        assert_eq!(writer.lock().await.buffer_count("source", "stream"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_commit_failure_leaves_cursor_unchanged() {
+        let pool = cursor_pool().await;
+        let writer = writer_with_records(2);
+
+        // The cursor update succeeds inside the transaction, but a later
+        // statement fails before commit (NOT NULL violation)
+        let result = collect_then_commit(&writer, &pool, "source", "stream", |tx| {
+            Box::pin(async move {
+                advance_cursor(tx).await?;
+                sqlx::query("INSERT INTO cursors VALUES ('other', NULL)")
+                    .execute(&mut **tx)
+                    .await?;
+                Ok(())
+            })
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(cursor(&pool).await, "old");
+    }
+
+    #[tokio::test]
+    async fn test_held_checkpoint_waits_for_the_caller() {
+        let pool = cursor_pool().await;
+        let writer = writer_with_records(2);
+
+        let (records, checkpoint) = hold_checkpoint(collect_then_commit(
+            &writer,
+            &pool,
+            "source",
+            "stream",
+            advance_cursor,
+        ))
+        .await;
+
+        // Records are handed off, but the cursor stays until the caller commits
+        assert_eq!(records.unwrap().map(|r| r.len()), Some(2));
+        assert_eq!(cursor(&pool).await, "old");
+
+        let mut tx = pool.begin().await.unwrap();
+        checkpoint.unwrap()(&mut tx).await.unwrap();
+        tx.commit().await.unwrap();
+        assert_eq!(cursor(&pool).await, "new");
+    }
+
+    #[tokio::test]
+    async fn test_discard_buffered_records() {
+        let writer = writer_with_records(2);
+        discard_buffered_records(&writer, "source", "stream").await;
+        assert_eq!(writer.lock().await.buffer_count("source", "stream"), 0);
+    }
+}
@@ -1,9 +1,14 @@
-//! Error handling abstractions for OAuth HTTP clients
+//! Error handling abstractions for source HTTP clients
 //!
-//! This module provides customizable error handling for different OAuth providers.
-//! Each provider can implement their own error classification and retry logic.
+//! This module provides customizable error handling for different providers.
+//! Each provider can implement their own error classification, retry logic and
+//! mapping of failed responses to errors.
 
+use reqwest::header::{HeaderMap, RETRY_AFTER};
 use reqwest::StatusCode;
+use std::time::Duration;
+
+use crate::error::Error;
 
 /// Classification of HTTP errors for retry logic
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -72,6 +77,34 @@ pub trait ErrorHandler: Send + Sync {
     fn is_sync_token_error(&self, _status: StatusCode, _body: &str) -> bool {
         false
     }
+
+    /// How long the provider asked us to wait before retrying a rate limit
+    ///
+    /// Reads a `Retry-After` header given in seconds. Returning `None` falls
+    /// back to exponential backoff.
+    fn retry_after(
+        &self,
+        _status: StatusCode,
+        headers: &HeaderMap,
+        _body: &str,
+    ) -> Option<Duration> {
+        headers
+            .get(RETRY_AFTER)?
+            .to_str()
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+            .map(Duration::from_secs)
+    }
+
+    /// Map a failed response that will not be retried to an error
+    ///
+    /// Providers with structured error bodies can override this to surface
+    /// their own error codes and messages.
+    fn map_error(&self, status: StatusCode, body: &str) -> Error {
+        Error::Http(format!("API error ({status}): {body}"))
+    }
 }
 
 /// Default error handler with sensible retry logic
@@ -151,4 +184,23 @@ mod tests {
         // Default handler doesn't detect sync token errors
         assert!(!handler.is_sync_token_error(StatusCode::GONE, "Sync token invalid"));
     }
+
+    #[test]
+    fn test_default_retry_after() {
+        let mut headers = HeaderMap::new();
+        let status = StatusCode::TOO_MANY_REQUESTS;
+        assert_eq!(DefaultErrorHandler.retry_after(status, &headers, ""), None);
+
+        headers.insert(RETRY_AFTER, "30".parse().unwrap());
+        assert_eq!(
+            DefaultErrorHandler.retry_after(status, &headers, ""),
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn test_default_map_error() {
+        let error = DefaultErrorHandler.map_error(StatusCode::NOT_FOUND, "missing");
+        assert!(matches!(error, Error::Http(msg) if msg == "API error (404 Not Found): missing"));
+    }
 }
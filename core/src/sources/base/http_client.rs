@@ -0,0 +1,882 @@
+//! Shared HTTP plumbing for source API clients
+//!
+//! `SourceHttpClient` is the composable base layer every source client builds
+//! on. It handles:
+//! - Pluggable authentication (`HttpAuth`): OAuth tokens with automatic
+//!   refresh on 401, static bearer tokens, or API-key headers
+//! - Exponential backoff retry (`util::retry`) for rate limits (429) and
+//!   server errors, waiting as long as the provider asks when it says
+//!   (`Retry-After`)
+//! - Connect and request timeouts
+//! - Provider-specific error classification and mapping via `ErrorHandler`
+//! - Request cloning for safe retries
+//! - Per-source proxy and offline settings via `NetworkConfig`
+//! - An identifying `User-Agent` (`virtues/<version>` unless overridden)
+//! - Replaying recorded fixtures instead of the network (`MockTransport`)
+//!
+//! Provider clients wrap a configured `SourceHttpClient` and implement
+//! `SourceClient`, which supplies `get`, `get_with_params` and `post_json`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use virtues::sources::base::{SourceHttpClient, RetryConfig, DefaultErrorHandler};
+//! use std::sync::Arc;
+//!
+//! let client = SourceHttpClient::oauth(source_id, token_manager)
+//!     .with_base_url("https://api.example.com/v1")
+//!     .with_retry_config(RetryConfig::default())
+//!     .with_error_handler(Box::new(DefaultErrorHandler));
+//!
+//! // Automatic token refresh, retry, and error handling
+//! let response: MyApiResponse = client.get("endpoint").await?;
+//! ```
+
+use async_trait::async_trait;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT},
+    Client, RequestBuilder, Response, StatusCode,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use super::error_handler::{DefaultErrorHandler, ErrorClass, ErrorHandler};
+use super::mock_transport::{MockRequest, MockTransport};
+use super::network::NetworkConfig;
+use super::oauth::TokenManager;
+use crate::error::{Error, Result};
+use crate::util::retry::{retry_with_backoff, RetryPolicy, Retryable};
+
+/// Default total request timeout
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// User-Agent sent when neither the environment nor the source overrides it
+pub const DEFAULT_USER_AGENT: &str = concat!("virtues/", env!("CARGO_PKG_VERSION"));
+
+/// User-Agent for all source API calls
+///
+/// `SOURCE_USER_AGENT` overrides `DEFAULT_USER_AGENT` for the whole process.
+/// It's read once; a value that isn't a valid header is logged and ignored.
+pub fn default_user_agent() -> String {
+    static USER_AGENT_OVERRIDE: OnceLock<String> = OnceLock::new();
+    USER_AGENT_OVERRIDE
+        .get_or_init(|| {
+            match std::env::var("SOURCE_USER_AGENT") {
+                Ok(ua) if ua.trim().is_empty() => {}
+                Ok(ua) if HeaderValue::from_str(&ua).is_ok() => return ua,
+                Ok(ua) => tracing::warn!(
+                    user_agent = %ua,
+                    "Ignoring SOURCE_USER_AGENT, which isn't a valid header value"
+                ),
+                Err(_) => {}
+            }
+            DEFAULT_USER_AGENT.to_string()
+        })
+        .clone()
+}
+
+/// User-Agent for a provider's API calls
+///
+/// The provider's registry descriptor can override the default, for APIs
+/// that ask clients to identify themselves a particular way.
+pub fn user_agent_for(provider: &str) -> String {
+    crate::registry::get_source(provider)
+        .and_then(|source| source.descriptor.user_agent)
+        .map(String::from)
+        .unwrap_or_else(default_user_agent)
+}
+
+/// How requests are authenticated
+#[derive(Clone)]
+pub enum HttpAuth {
+    /// OAuth access token from the token manager, refreshed as needed
+    OAuth(Arc<TokenManager>),
+    /// Static bearer token
+    Bearer(String),
+    /// Static API key sent in a header
+    ApiKey {
+        header: HeaderName,
+        value: HeaderValue,
+    },
+    /// No authentication
+    None,
+}
+
+impl HttpAuth {
+    /// API key auth, e.g. `HttpAuth::api_key("X-Api-Key", key)`
+    pub fn api_key(header: &str, value: &str) -> Result<Self> {
+        Ok(Self::ApiKey {
+            header: header
+                .parse()
+                .map_err(|e| Error::Configuration(format!("Invalid header name {header}: {e}")))?,
+            value: value
+                .parse()
+                .map_err(|e| Error::Configuration(format!("Invalid header value: {e}")))?,
+        })
+    }
+
+    /// Whether a 401 can be fixed by retrying (the token gets refreshed)
+    fn is_refreshable(&self) -> bool {
+        matches!(self, Self::OAuth(_))
+    }
+
+    async fn apply(&self, source_id: &str, request: RequestBuilder) -> Result<RequestBuilder> {
+        Ok(match self {
+            Self::OAuth(token_manager) => {
+                // TokenManager handles caching and refresh
+                let token = token_manager.get_valid_token(source_id.to_string()).await?;
+                request.bearer_auth(token)
+            }
+            Self::Bearer(token) => request.bearer_auth(token),
+            Self::ApiKey { header, value } => request.header(header.clone(), value.clone()),
+            Self::None => request,
+        })
+    }
+}
+
+/// Configuration for retry behavior
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// Maximum number of retry attempts
+    pub max_retries: u32,
+
+    /// Initial backoff duration in milliseconds
+    pub initial_backoff_ms: u64,
+
+    /// Maximum backoff duration in milliseconds
+    pub max_backoff_ms: u64,
+
+    /// Longest provider-requested rate limit wait honored, in milliseconds;
+    /// a longer wait fails the request instead of blocking the sync
+    pub max_retry_after_ms: u64,
+
+    /// Whether to retry on 401 (auth) errors
+    pub retry_on_401: bool,
+
+    /// Whether to retry on 429 (rate limit) errors
+    pub retry_on_429: bool,
+
+    /// Whether to retry on 5xx (server) errors
+    pub retry_on_5xx: bool,
+
+    /// Fraction of each backoff randomly taken off (see `RetryPolicy`)
+    pub jitter: f64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff_ms: 1000,  // 1 second
+            max_backoff_ms: 30000,     // 30 seconds
+            max_retry_after_ms: 60000, // 1 minute
+            retry_on_401: true,
+            retry_on_429: true,
+            retry_on_5xx: true,
+            jitter: 0.0,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Create a config with no retries
+    pub fn no_retry() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// The retry policy requests run under
+    ///
+    /// `max_retries` counts every attempt, the first included.
+    pub fn policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.max_retries,
+            base: Duration::from_millis(self.initial_backoff_ms),
+            max: Duration::from_millis(self.max_backoff_ms),
+            jitter: self.jitter,
+        }
+    }
+
+    /// Create a config optimized for rate-limited APIs
+    pub fn aggressive() -> Self {
+        Self {
+            max_retries: 5,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 60000,
+            ..Default::default()
+        }
+    }
+}
+
+/// Wait before retrying a 401 with a refreshable token
+const AUTH_RETRY_WAIT: Duration = Duration::from_millis(100);
+
+/// A failed request attempt and whether to try again
+struct Failure {
+    error: Error,
+    retry: Retryable,
+}
+
+impl Failure {
+    fn permanent(error: Error) -> Self {
+        Self {
+            error,
+            retry: Retryable::No,
+        }
+    }
+}
+
+/// HTTP client with pluggable auth, retries, timeouts and error mapping
+pub struct SourceHttpClient {
+    source_id: String,
+    auth: HttpAuth,
+    base_url: String,
+    client: Client,
+    timeout: Duration,
+    config: RetryConfig,
+    custom_headers: HeaderMap,
+    error_handler: Box<dyn ErrorHandler>,
+    network: NetworkConfig,
+    /// Why the client couldn't be rebuilt for `network`; requests fail with
+    /// it rather than go out without the source's proxy
+    network_error: Option<String>,
+    /// Answers requests instead of the network when set
+    mock: Option<Arc<MockTransport>>,
+}
+
+impl SourceHttpClient {
+    /// Create a new HTTP client
+    ///
+    /// # Arguments
+    /// * `source_id` - ID of the source, for token lookups and error messages
+    /// * `auth` - How requests are authenticated
+    pub fn new(source_id: String, auth: HttpAuth) -> Self {
+        let network = NetworkConfig::default();
+        Self {
+            source_id,
+            auth,
+            base_url: String::new(),
+            client: Self::build_client(&network, DEFAULT_TIMEOUT)
+                .expect("Failed to build HTTP client"),
+            timeout: DEFAULT_TIMEOUT,
+            config: RetryConfig::default(),
+            custom_headers: HeaderMap::new(),
+            error_handler: Box::new(DefaultErrorHandler),
+            network,
+            network_error: None,
+            mock: Self::default_mock(),
+        }
+    }
+
+    /// Transport every new client answers from, when one is configured
+    fn default_mock() -> Option<Arc<MockTransport>> {
+        #[cfg(test)]
+        if let Some(mock) = super::mock_transport::testing::current_transport() {
+            return Some(mock);
+        }
+        #[cfg(any(test, feature = "mock-sources"))]
+        if let Some(mock) = MockTransport::from_env() {
+            return Some(Arc::new(mock));
+        }
+        None
+    }
+
+    /// Create a client authenticated with the source's OAuth tokens
+    pub fn oauth(source_id: String, token_manager: Arc<TokenManager>) -> Self {
+        Self::new(source_id, HttpAuth::OAuth(token_manager))
+    }
+
+    /// Configure HTTP client with timeouts to prevent infinite hangs
+    fn build_client(network: &NetworkConfig, timeout: Duration) -> Result<Client> {
+        let builder = Client::builder()
+            .user_agent(default_user_agent())
+            .connect_timeout(Duration::from_secs(10)) // TCP connection timeout
+            .timeout(timeout); // Total request timeout
+
+        network
+            .apply(builder)?
+            .build()
+            .map_err(|e| Error::Configuration(format!("Failed to build HTTP client: {e}")))
+    }
+
+    /// Apply the source's proxy / offline settings
+    pub fn with_network(mut self, network: NetworkConfig) -> Self {
+        self.set_network(network);
+        self
+    }
+
+    /// Replace the proxy / offline settings in place
+    ///
+    /// Rebuilds the underlying HTTP client when a proxy override is set. The
+    /// config is expected to have been validated when it was loaded; one that
+    /// still can't be applied is logged, and the client's requests fail.
+    pub fn set_network(&mut self, network: NetworkConfig) {
+        let rebuild = network.proxy.is_some();
+        self.network = network;
+        if rebuild {
+            self.rebuild_client();
+        }
+    }
+
+    /// Set the total request timeout (default 60 seconds)
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self.rebuild_client();
+        self
+    }
+
+    /// Rebuild the HTTP client for the current network settings and timeout
+    fn rebuild_client(&mut self) {
+        match Self::build_client(&self.network, self.timeout) {
+            Ok(client) => {
+                self.client = client;
+                self.network_error = None;
+            }
+            Err(e) => {
+                tracing::error!(source_id = %self.source_id, "{}", e);
+                self.network_error = Some(e.to_string());
+            }
+        }
+    }
+
+    /// Set the base URL for API requests
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    /// Set custom retry configuration
+    pub fn with_retry_config(mut self, config: RetryConfig) -> Self {
+        self.set_retry_config(config);
+        self
+    }
+
+    /// Replace the retry configuration in place
+    pub fn set_retry_config(&mut self, config: RetryConfig) {
+        self.config = config;
+    }
+
+    /// Add a custom header to all requests
+    ///
+    /// A header that isn't valid is logged and left out.
+    pub fn with_header(mut self, key: &str, value: &str) -> Self {
+        match (
+            HeaderName::from_bytes(key.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            (Ok(name), Ok(value)) => {
+                self.custom_headers.insert(name, value);
+            }
+            _ => tracing::warn!(
+                source_id = %self.source_id,
+                header = key,
+                "Ignoring invalid header"
+            ),
+        }
+        self
+    }
+
+    /// Send `user_agent` instead of the default User-Agent
+    ///
+    /// A value that isn't a valid header is logged and the default kept.
+    pub fn with_user_agent(mut self, user_agent: &str) -> Self {
+        match HeaderValue::from_str(user_agent) {
+            Ok(value) => {
+                self.custom_headers.insert(USER_AGENT, value);
+            }
+            Err(_) => tracing::warn!(
+                source_id = %self.source_id,
+                user_agent,
+                "Ignoring invalid User-Agent; sending the default"
+            ),
+        }
+        self
+    }
+
+    /// Set a custom error handler for provider-specific logic
+    pub fn with_error_handler(mut self, handler: Box<dyn ErrorHandler>) -> Self {
+        self.error_handler = handler;
+        self
+    }
+
+    /// Answer requests from `mock` instead of the network
+    pub fn with_mock_transport(mut self, mock: Arc<MockTransport>) -> Self {
+        self.set_mock_transport(mock);
+        self
+    }
+
+    /// Replace the mock transport in place
+    pub fn set_mock_transport(&mut self, mock: Arc<MockTransport>) {
+        self.mock = Some(mock);
+    }
+
+    /// Answer a request from the mock transport, mapping error statuses as
+    /// the network path would
+    fn respond_mock<T: DeserializeOwned>(
+        &self,
+        mock: &MockTransport,
+        request: MockRequest,
+    ) -> Result<T> {
+        mock.respond_mapping(request, |status, body| {
+            self.error_handler.map_error(status, body)
+        })
+    }
+
+    /// Make an authenticated GET request
+    pub async fn get<T>(&self, path: &str) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        if let Some(mock) = &self.mock {
+            return self.respond_mock(mock, MockRequest::new("GET", path, &[], None));
+        }
+        let url = self.build_url(path);
+        let request = self.client.get(&url);
+        let response = self.execute_with_retry(request).await?;
+        self.parse_response(response).await
+    }
+
+    /// Make an authenticated GET request with query parameters
+    pub async fn get_with_params<T>(&self, path: &str, params: &[(&str, &str)]) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        if let Some(mock) = &self.mock {
+            return self.respond_mock(mock, MockRequest::new("GET", path, params, None));
+        }
+        let url = self.build_url(path);
+        let request = self.client.get(&url).query(params);
+        let response = self.execute_with_retry(request).await?;
+        self.parse_response(response).await
+    }
+
+    /// Make an authenticated POST request with JSON body
+    pub async fn post<T>(&self, path: &str, body: &impl Serialize) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        if let Some(mock) = &self.mock {
+            let body = serde_json::to_value(body).ok();
+            return self.respond_mock(mock, MockRequest::new("POST", path, &[], body));
+        }
+        let url = self.build_url(path);
+        let request = self.client.post(&url).json(body);
+        let response = self.execute_with_retry(request).await?;
+        self.parse_response(response).await
+    }
+
+    /// Make an authenticated PUT request with JSON body
+    pub async fn put<T>(&self, path: &str, body: &impl Serialize) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        if let Some(mock) = &self.mock {
+            let body = serde_json::to_value(body).ok();
+            return self.respond_mock(mock, MockRequest::new("PUT", path, &[], body));
+        }
+        let url = self.build_url(path);
+        let request = self.client.put(&url).json(body);
+        let response = self.execute_with_retry(request).await?;
+        self.parse_response(response).await
+    }
+
+    /// Make an authenticated DELETE request
+    pub async fn delete<T>(&self, path: &str) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        if let Some(mock) = &self.mock {
+            return self.respond_mock(mock, MockRequest::new("DELETE", path, &[], None));
+        }
+        let url = self.build_url(path);
+        let request = self.client.delete(&url);
+        let response = self.execute_with_retry(request).await?;
+        self.parse_response(response).await
+    }
+
+    /// Execute a request with automatic retry and token refresh
+    async fn execute_with_retry(&self, request_builder: RequestBuilder) -> Result<Response> {
+        self.network.ensure_online(&self.source_id)?;
+        if let Some(error) = &self.network_error {
+            return Err(Error::Configuration(error.clone()));
+        }
+
+        retry_with_backoff(
+            &self.config.policy(),
+            |failure: &Failure| failure.retry,
+            |attempt| self.send_attempt(&request_builder, attempt),
+        )
+        .await
+        .map_err(|failure| failure.error)
+    }
+
+    /// Send one attempt of a request, classifying a failure for the retry loop
+    async fn send_attempt(
+        &self,
+        request_builder: &RequestBuilder,
+        attempt: u32,
+    ) -> std::result::Result<Response, Failure> {
+        // Clone the request builder for this attempt
+        let request = request_builder.try_clone().ok_or_else(|| {
+            Failure::permanent(Error::Other(
+                "Request builder not cloneable - ensure body is cloneable".to_string(),
+            ))
+        })?;
+        let mut request = self
+            .auth
+            .apply(&self.source_id, request)
+            .await
+            .map_err(Failure::permanent)?;
+
+        // Apply custom headers
+        if !self.custom_headers.is_empty() {
+            request = request.headers(self.custom_headers.clone());
+        }
+
+        let response = match request.send().await {
+            Ok(response) => response,
+            // Network error - retry with backoff
+            Err(e) => {
+                if attempt + 1 < self.config.max_retries {
+                    tracing::warn!(
+                        source_id = %self.source_id,
+                        attempt = attempt + 1,
+                        max_attempts = self.config.max_retries,
+                        error = %e,
+                        "Request failed, retrying"
+                    );
+                }
+                return Err(Failure {
+                    error: Error::Network(format!("Request failed: {e}")),
+                    retry: Retryable::Yes,
+                });
+            }
+        };
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        // Get headers and body for error classification
+        let headers = response.headers().clone();
+        let error_body = response.text().await.unwrap_or_default();
+        let error_class = self.error_handler.classify_error(status, &error_body);
+
+        // Sync token invalid - let caller handle this
+        if matches!(error_class, ErrorClass::SyncTokenError) {
+            return Err(Failure::permanent(Error::Source(format!(
+                "Sync token invalid: {error_body}"
+            ))));
+        }
+
+        // How long a rate limited provider asked us to wait, if it said
+        let retry_after = match error_class {
+            ErrorClass::RateLimit => self
+                .error_handler
+                .retry_after(status, &headers, &error_body),
+            _ => None,
+        };
+
+        let retry = if self
+            .error_handler
+            .should_retry(status, attempt, self.config.max_retries)
+        {
+            match error_class {
+                // Token might be invalid - TokenManager will refresh on next attempt
+                ErrorClass::AuthError if self.auth.is_refreshable() => {
+                    Retryable::After(AUTH_RETRY_WAIT)
+                }
+                // Rate limited - wait as asked, else back off exponentially
+                ErrorClass::RateLimit => match retry_after {
+                    Some(wait) if wait > Duration::from_millis(self.config.max_retry_after_ms) => {
+                        Retryable::No
+                    }
+                    Some(wait) => Retryable::After(wait),
+                    None => Retryable::Yes,
+                },
+                // Server error - back off and retry
+                ErrorClass::ServerError => Retryable::Yes,
+                // Don't retry client errors
+                _ => Retryable::No,
+            }
+        } else {
+            Retryable::No
+        };
+
+        if retry != Retryable::No && attempt + 1 < self.config.max_retries {
+            tracing::warn!(
+                source_id = %self.source_id,
+                attempt = attempt + 1,
+                max_attempts = self.config.max_retries,
+                status = %status,
+                retry_after_secs = retry_after.map(|wait| wait.as_secs()),
+                "Request failed, retrying"
+            );
+        }
+
+        let mut error = self.format_error(status, &error_body);
+        if let Error::RateLimited {
+            retry_after: wait @ None,
+            ..
+        } = &mut error
+        {
+            *wait = retry_after;
+        }
+        Err(Failure { error, retry })
+    }
+
+    /// Parse JSON response
+    async fn parse_response<T>(&self, response: Response) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        response
+            .json::<T>()
+            .await
+            .map_err(|e| Error::Other(format!("Failed to parse response: {e}")))
+    }
+
+    /// Build full URL from path
+    fn build_url(&self, path: &str) -> String {
+        if self.base_url.is_empty() {
+            path.to_string()
+        } else {
+            format!(
+                "{}/{}",
+                self.base_url.trim_end_matches('/'),
+                path.trim_start_matches('/')
+            )
+        }
+    }
+
+    /// Map a failed response to an error via the provider's handler
+    fn format_error(&self, status: StatusCode, body: &str) -> Error {
+        self.error_handler.map_error(status, body)
+    }
+}
+
+/// A provider API client built on `SourceHttpClient`
+///
+/// Implementors only expose their configured base client; the request
+/// helpers come with the trait.
+#[async_trait]
+pub trait SourceClient: Send + Sync {
+    /// The configured base client
+    fn http(&self) -> &SourceHttpClient;
+
+    /// Mutable access to the base client, for post-construction settings
+    fn http_mut(&mut self) -> &mut SourceHttpClient;
+
+    /// Apply the source's proxy / offline settings
+    fn with_network(mut self, network: NetworkConfig) -> Self
+    where
+        Self: Sized,
+    {
+        self.http_mut().set_network(network);
+        self
+    }
+
+    /// Answer requests from recorded fixtures instead of the network
+    fn with_mock_transport(mut self, mock: Arc<MockTransport>) -> Self
+    where
+        Self: Sized,
+    {
+        self.http_mut().set_mock_transport(mock);
+        self
+    }
+
+    /// Make an authenticated GET request
+    async fn get<T>(&self, path: &str) -> Result<T>
+    where
+        T: DeserializeOwned + Send,
+    {
+        self.http().get(path).await
+    }
+
+    /// Make an authenticated GET request with query parameters
+    async fn get_with_params<T>(&self, path: &str, params: &[(&str, &str)]) -> Result<T>
+    where
+        T: DeserializeOwned + Send,
+    {
+        self.http().get_with_params(path, params).await
+    }
+
+    /// Make an authenticated POST request with JSON body
+    async fn post_json<T, B>(&self, path: &str, body: &B) -> Result<T>
+    where
+        T: DeserializeOwned + Send,
+        B: Serialize + Sync,
+    {
+        self.http().post(path, body).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::base::mock_transport::testing::serve;
+
+    #[test]
+    fn test_backoff_calculation() {
+        let policy = RetryConfig::default().policy();
+
+        assert_eq!(policy.delay(0), Duration::from_secs(1));
+        assert_eq!(policy.delay(1), Duration::from_secs(2));
+        assert_eq!(policy.delay(2), Duration::from_secs(4));
+        assert_eq!(policy.delay(3), Duration::from_secs(8));
+        assert_eq!(policy.delay(4), Duration::from_secs(16));
+        assert_eq!(policy.delay(5), Duration::from_secs(30)); // Max
+        assert_eq!(policy.delay(10), Duration::from_secs(30)); // Still max
+    }
+
+    #[tokio::test]
+    async fn test_build_url() {
+        let pool = sqlx::SqlitePool::connect_lazy("sqlite::memory:").unwrap();
+        let token_manager = Arc::new(TokenManager::new_insecure(pool));
+        let client = SourceHttpClient::oauth("test-source".to_string(), token_manager)
+            .with_base_url("https://api.example.com/v1");
+
+        assert_eq!(
+            client.build_url("users"),
+            "https://api.example.com/v1/users"
+        );
+        assert_eq!(
+            client.build_url("/users"),
+            "https://api.example.com/v1/users"
+        );
+        assert_eq!(
+            client.build_url("users/me"),
+            "https://api.example.com/v1/users/me"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_offline_source_makes_no_requests() {
+        let pool = sqlx::SqlitePool::connect_lazy("sqlite::memory:").unwrap();
+        let token_manager = Arc::new(TokenManager::new_insecure(pool));
+        let client = SourceHttpClient::oauth("test-source".to_string(), token_manager)
+            .with_base_url("http://127.0.0.1:9")
+            .with_network(NetworkConfig {
+                proxy: Some("http://proxy.corp.example:3128".to_string()),
+                offline: true,
+            });
+
+        // Fails before the token lookup (which would hit the empty database)
+        let result: Result<serde_json::Value> = client.get("users").await;
+        assert!(matches!(result, Err(Error::Network(_))));
+    }
+
+    #[tokio::test]
+    async fn test_api_key_auth_is_sent_and_401_not_retried() {
+        let (url, server) = serve(vec![
+            "HTTP/1.1 401 Unauthorized\r\nContent-Length: 6\r\nConnection: close\r\n\r\ndenied",
+        ])
+        .await;
+        let client = SourceHttpClient::new(
+            "test-source".to_string(),
+            HttpAuth::api_key("X-Api-Key", "secret").unwrap(),
+        )
+        .with_base_url(&url)
+        .with_header("X-User-Id", "user_1");
+
+        let result: Result<serde_json::Value> = client.get("items").await;
+        assert!(matches!(result, Err(Error::Http(msg)) if msg.contains("denied")));
+
+        // A static key can't be refreshed, so the 401 came back after one request
+        let requests = server.await.unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("get /items "));
+        assert!(requests[0].contains("x-api-key: secret"));
+        assert!(requests[0].contains("x-user-id: user_1"));
+        assert!(!requests[0].contains("authorization:"));
+    }
+
+    #[tokio::test]
+    async fn test_bearer_auth_is_sent() {
+        let (url, server) = serve(vec![
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 11\r\nConnection: close\r\n\r\n{\"ok\":true}",
+        ])
+        .await;
+        let client = SourceHttpClient::new(
+            "test-source".to_string(),
+            HttpAuth::Bearer("token-1".to_string()),
+        )
+        .with_base_url(&url);
+
+        let body: serde_json::Value = client.get("me").await.unwrap();
+        assert_eq!(body, serde_json::json!({"ok": true}));
+
+        let requests = server.await.unwrap();
+        assert!(requests[0].contains("authorization: bearer token-1"));
+    }
+
+    #[tokio::test]
+    async fn test_user_agent_default_and_override() {
+        const OK: &str = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}";
+        let (url, server) = serve(vec![OK, OK]).await;
+
+        let client =
+            SourceHttpClient::new("test-source".to_string(), HttpAuth::None).with_base_url(&url);
+        let _: serde_json::Value = client.get("a").await.unwrap();
+        let client = client.with_user_agent("acme-sync/2.0");
+        let _: serde_json::Value = client.get("b").await.unwrap();
+
+        let requests = server.await.unwrap();
+        let expected = format!("user-agent: {}", default_user_agent().to_lowercase());
+        assert!(requests[0].contains(&expected));
+        assert!(requests[1].contains("user-agent: acme-sync/2.0"));
+        assert_eq!(requests[1].matches("user-agent:").count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unusable_proxy_fails_requests() {
+        let client = SourceHttpClient::new("test-source".to_string(), HttpAuth::None)
+            .with_base_url("http://127.0.0.1:9")
+            .with_header("X-User-Id", "user\n1")
+            .with_network(NetworkConfig {
+                proxy: Some("http://[::1".to_string()),
+                offline: false,
+            });
+        assert!(client.custom_headers.is_empty());
+
+        let result: Result<serde_json::Value> = client.get("a").await;
+        assert!(matches!(result, Err(Error::Configuration(_))));
+    }
+
+    #[test]
+    fn test_invalid_user_agent_keeps_the_default() {
+        let client = SourceHttpClient::new("test-source".to_string(), HttpAuth::None)
+            .with_user_agent("acme\nsync");
+        assert!(client.custom_headers.get(USER_AGENT).is_none());
+    }
+
+    #[test]
+    fn test_retry_config_defaults() {
+        let config = RetryConfig::default();
+        assert_eq!(config.max_retries, 3);
+        assert_eq!(config.initial_backoff_ms, 1000);
+        assert_eq!(config.max_backoff_ms, 30000);
+        assert_eq!(config.max_retry_after_ms, 60000);
+        assert!(config.retry_on_401);
+        assert!(config.retry_on_429);
+        assert!(config.retry_on_5xx);
+    }
+
+    #[test]
+    fn test_retry_config_no_retry() {
+        let config = RetryConfig::no_retry();
+        assert_eq!(config.max_retries, 0);
+    }
+
+    #[test]
+    fn test_retry_config_aggressive() {
+        let config = RetryConfig::aggressive();
+        assert_eq!(config.max_retries, 5);
+        assert_eq!(config.initial_backoff_ms, 500);
+        assert_eq!(config.max_backoff_ms, 60000);
+    }
+}
@@ -0,0 +1,419 @@
+//! Replay of recorded API responses in place of the network
+//!
+//! A `MockTransport` answers a `SourceHttpClient`'s requests from JSON
+//! fixtures keyed by endpoint path (relative to the client's base URL), so
+//! sync loops run end to end without credentials or network access. Every
+//! request is logged for tests to inspect.
+//!
+//! On disk, fixtures mirror the endpoint path: `users/me/messages.json`
+//! answers every call to `users/me/messages`. Numbered files
+//! (`athlete/activities.1.json`, `athlete/activities.2.json`, ...) answer
+//! successive calls in order, the last one repeating, which is how paginated
+//! endpoints are recorded. Query parameters and request bodies are logged but
+//! not matched. Error responses (`with_status`) go through the client's error
+//! handler, as a provider's error status would.
+//!
+//! Select it per client with `SourceClient::with_mock_transport`, or for every
+//! client in the process by pointing `SOURCE_MOCK_FIXTURES` at a fixtures
+//! directory; that variable is only read in builds with the `mock-sources`
+//! feature.
+
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::Path;
+use std::sync::Mutex;
+#[cfg(any(test, feature = "mock-sources"))]
+use std::sync::OnceLock;
+
+use crate::error::{Error, Result};
+
+/// A request answered by a `MockTransport`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockRequest {
+    pub method: &'static str,
+    /// Endpoint path relative to the base URL, without a leading `/`
+    pub path: String,
+    pub params: Vec<(String, String)>,
+    pub body: Option<Value>,
+}
+
+impl MockRequest {
+    pub fn new(
+        method: &'static str,
+        path: &str,
+        params: &[(&str, &str)],
+        body: Option<Value>,
+    ) -> Self {
+        Self {
+            method,
+            path: normalize_path(path),
+            params: params
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            body,
+        }
+    }
+
+    /// Value of the first query parameter named `key`
+    pub fn param(&self, key: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// A canned response: a JSON body, or an error status with its body
+#[derive(Debug, Clone)]
+enum MockResponse {
+    Json(Value),
+    Status(StatusCode, String),
+}
+
+type Responses = HashMap<String, VecDeque<MockResponse>>;
+
+/// Fixtures named by `SOURCE_MOCK_FIXTURES`, read once per process
+#[cfg(any(test, feature = "mock-sources"))]
+static ENV_FIXTURES: OnceLock<Option<Responses>> = OnceLock::new();
+
+/// Canned responses per endpoint, replayed instead of sending requests
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    responses: Mutex<Responses>,
+    requests: Mutex<Vec<MockRequest>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replay the fixtures directory named by `SOURCE_MOCK_FIXTURES`, if set
+    ///
+    /// The directory is read once per process and each transport replays its
+    /// own copy from the first response. A directory that fails to load is
+    /// logged and replays nothing, so requests fail instead of reaching the
+    /// provider.
+    #[cfg(any(test, feature = "mock-sources"))]
+    pub fn from_env() -> Option<Self> {
+        let fixtures = ENV_FIXTURES.get_or_init(|| {
+            let dir = std::env::var("SOURCE_MOCK_FIXTURES").ok()?;
+            if dir.trim().is_empty() {
+                return None;
+            }
+            match Self::from_dir(&dir) {
+                Ok(transport) => Some(transport.lock_responses().clone()),
+                Err(e) => {
+                    tracing::error!("Not replaying SOURCE_MOCK_FIXTURES: {}", e);
+                    Some(Responses::new())
+                }
+            }
+        });
+
+        fixtures.as_ref().map(|responses| Self {
+            responses: Mutex::new(responses.clone()),
+            requests: Mutex::default(),
+        })
+    }
+
+    /// Load every `.json` fixture under `dir`
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+        let mut files = Vec::new();
+        collect_json_files(dir, &mut files).map_err(|e| {
+            Error::Configuration(format!("Failed to read fixtures in {}: {e}", dir.display()))
+        })?;
+
+        // endpoint -> (sequence number, response); an unnumbered file sorts first
+        let mut endpoints: BTreeMap<String, Vec<(u32, Value)>> = BTreeMap::new();
+        for file in files {
+            let relative = file.strip_prefix(dir).unwrap_or(&file).with_extension("");
+            let relative = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            let numbered = relative
+                .rsplit_once('.')
+                .and_then(|(endpoint, n)| Some((endpoint.to_string(), n.parse::<u32>().ok()?)));
+            let (endpoint, seq) = numbered.unwrap_or((relative, 0));
+
+            let contents = std::fs::read_to_string(&file)?;
+            let value = serde_json::from_str(&contents).map_err(|e| {
+                Error::Configuration(format!("Invalid fixture {}: {e}", file.display()))
+            })?;
+            endpoints.entry(endpoint).or_default().push((seq, value));
+        }
+
+        let transport = Self::new();
+        {
+            let mut responses = transport.lock_responses();
+            for (endpoint, mut sequence) in endpoints {
+                sequence.sort_by_key(|(seq, _)| *seq);
+                let sequence = sequence.into_iter().map(|(_, v)| MockResponse::Json(v));
+                responses.insert(endpoint, sequence.collect());
+            }
+        }
+        Ok(transport)
+    }
+
+    /// Queue a response for `path`, after any already queued
+    pub fn with_response(self, path: &str, body: Value) -> Self {
+        self.push_response(path, MockResponse::Json(body))
+    }
+
+    /// Queue an error response with `status` for `path`, after any already queued
+    pub fn with_status(self, path: &str, status: u16, body: &str) -> Self {
+        let status = StatusCode::from_u16(status).expect("Invalid mock status");
+        self.push_response(path, MockResponse::Status(status, body.to_string()))
+    }
+
+    fn push_response(self, path: &str, response: MockResponse) -> Self {
+        self.lock_responses()
+            .entry(normalize_path(path))
+            .or_default()
+            .push_back(response);
+        self
+    }
+
+    /// Requests answered so far, oldest first
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Answer a request with the next response queued for its path
+    ///
+    /// The last response for a path is kept and repeated. A path without
+    /// fixtures fails like an unreachable network.
+    pub fn respond<T: DeserializeOwned>(&self, request: MockRequest) -> Result<T> {
+        self.respond_mapping(request, |status, body| {
+            Error::Http(format!("API error ({status}): {body}"))
+        })
+    }
+
+    /// Answer a request, turning error statuses into errors with `map_error`
+    /// as the provider's error handler would
+    pub fn respond_mapping<T: DeserializeOwned>(
+        &self,
+        request: MockRequest,
+        map_error: impl FnOnce(StatusCode, &str) -> Error,
+    ) -> Result<T> {
+        let response = {
+            let mut responses = self.lock_responses();
+            let queue = responses.get_mut(&request.path);
+            match queue {
+                Some(queue) if queue.len() > 1 => queue.pop_front(),
+                Some(queue) => queue.front().cloned(),
+                None => None,
+            }
+        };
+        let missing = format!("No mock response for {} {}", request.method, request.path);
+
+        self.requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(request);
+
+        match response.ok_or_else(|| Error::Network(missing))? {
+            MockResponse::Json(body) => serde_json::from_value(body)
+                .map_err(|e| Error::Other(format!("Failed to parse response: {e}"))),
+            MockResponse::Status(status, body) => Err(map_error(status, &body)),
+        }
+    }
+
+    fn lock_responses(&self) -> std::sync::MutexGuard<'_, Responses> {
+        self.responses.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Endpoint path as fixtures are keyed: no leading `/`, no query string
+fn normalize_path(path: &str) -> String {
+    let path = path.split('?').next().unwrap_or(path);
+    path.trim_start_matches('/').to_string()
+}
+
+fn collect_json_files(dir: &Path, files: &mut Vec<std::path::PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_json_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "json") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Helpers for end-to-end sync tests against recorded fixtures
+#[cfg(test)]
+pub(crate) mod testing {
+    use std::future::Future;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use chrono::Utc;
+    use serde_json::Value;
+    use sqlx::SqlitePool;
+
+    use super::MockTransport;
+    use crate::database::{Database, PoolConfig};
+    use crate::jobs::archive::{partition_records, upload_partitions};
+    use crate::storage::{models::PartitionGranularity, Storage};
+
+    tokio::task_local! {
+        static TRANSPORT: Arc<MockTransport>;
+    }
+
+    /// Run `future` with every source client it builds answering from
+    /// `transport`, so streams created by `StreamFactory` (as sync jobs do)
+    /// never reach the network or stored credentials
+    pub async fn with_transport<F: Future>(transport: Arc<MockTransport>, future: F) -> F::Output {
+        TRANSPORT.scope(transport, future).await
+    }
+
+    /// Transport of the enclosing `with_transport`, if any
+    pub fn current_transport() -> Option<Arc<MockTransport>> {
+        TRANSPORT.try_with(Arc::clone).ok()
+    }
+
+    /// Fixtures recorded for one provider, under `core/fixtures/sources/<provider>`
+    pub fn fixtures(provider: &str) -> Arc<MockTransport> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures/sources")
+            .join(provider);
+        Arc::new(MockTransport::from_dir(dir).expect("Failed to load fixtures"))
+    }
+
+    /// A fully migrated in-memory database
+    pub async fn migrated_pool() -> SqlitePool {
+        // One connection, so every query sees the same in-memory database
+        let db = Database::with_pool_config(
+            "sqlite::memory:",
+            PoolConfig {
+                max_connections: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        db.initialize().await.unwrap();
+        db.pool().clone()
+    }
+
+    /// Archive a sync's records to in-memory storage as the archive job
+    /// does, returning what was written, read back from storage
+    pub async fn archive_to_memory(
+        provider: &str,
+        source_id: &str,
+        stream_name: &str,
+        records: &[Value],
+        partition_key: &str,
+    ) -> Vec<Value> {
+        let storage = Storage::in_memory();
+        let partitions = partition_records(
+            records,
+            Some(partition_key),
+            PartitionGranularity::Daily,
+            Utc::now(),
+            (None, None),
+        );
+        let uploads = upload_partitions(
+            &storage,
+            provider,
+            source_id,
+            stream_name,
+            PartitionGranularity::Daily,
+            &partitions,
+            1,
+        )
+        .await;
+        assert!(uploads.iter().all(|u| u.succeeded));
+
+        let mut written = Vec::new();
+        for key in storage.list("").await.unwrap() {
+            written.extend(storage.download_jsonl::<Value>(&key).await.unwrap());
+        }
+        written
+    }
+
+    /// Serve one canned HTTP response per local connection, returning the
+    /// raw requests
+    pub async fn serve(
+        responses: Vec<&'static str>,
+    ) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0; 4096];
+                let n = socket.read(&mut buf).await.unwrap();
+                requests.push(String::from_utf8_lossy(&buf[..n]).to_lowercase());
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+            requests
+        });
+        (url, handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_responses_replay_in_order_and_last_repeats() {
+        let transport = MockTransport::new()
+            .with_response("/items", json!({ "page": 1 }))
+            .with_response("items", json!({ "page": 2 }));
+
+        let request = || MockRequest::new("GET", "items", &[("cursor", "c1")], None);
+        let first: Value = transport.respond(request()).unwrap();
+        let second: Value = transport.respond(request()).unwrap();
+        let third: Value = transport.respond(request()).unwrap();
+        assert_eq!(first["page"], 1);
+        assert_eq!(second["page"], 2);
+        assert_eq!(third["page"], 2);
+
+        let missing: Result<Value> =
+            transport.respond(MockRequest::new("POST", "other", &[], None));
+        assert!(matches!(missing, Err(Error::Network(_))));
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[0].param("cursor"), Some("c1"));
+        assert_eq!(requests[3].path, "other");
+    }
+
+    #[test]
+    fn test_from_dir_orders_numbered_fixtures() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("athlete")).unwrap();
+        std::fs::write(dir.path().join("athlete/activities.2.json"), "[]").unwrap();
+        std::fs::write(
+            dir.path().join("athlete/activities.1.json"),
+            r#"[{"id": 1}]"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("athlete.json"), r#"{"id": 7}"#).unwrap();
+
+        let transport = MockTransport::from_dir(dir.path()).unwrap();
+        let page = |path: &str| -> Value {
+            transport
+                .respond(MockRequest::new("GET", path, &[], None))
+                .unwrap()
+        };
+        assert_eq!(page("athlete/activities"), json!([{ "id": 1 }]));
+        assert_eq!(page("athlete/activities"), json!([]));
+        assert_eq!(page("athlete"), json!({ "id": 7 }));
+    }
+}
@@ -2,15 +2,20 @@
 
 use serde::{de::DeserializeOwned, Serialize};
 
+pub mod checkpoint;
 pub mod device;
 pub mod error_handler;
+pub mod http_client;
+pub mod mock_transport;
+pub mod network;
 pub mod oauth;
-pub mod oauth_client;
+pub mod stream_limits;
 pub mod sync_mode;
 pub mod sync_strategy;
 pub mod transform;
 pub mod transform_data_source;
 pub mod validation;
+pub mod webhook;
 
 /// Trait for stream configuration serialization
 ///
@@ -32,11 +37,15 @@ pub trait ConfigSerializable: Serialize + DeserializeOwned {
 // Blanket implementation: any type that can be serialized/deserialized gets these methods
 impl<T: Serialize + DeserializeOwned> ConfigSerializable for T {}
 
+pub use checkpoint::{collect_then_commit, discard_buffered_records, hold_checkpoint, Checkpoint};
 pub use device::get_or_create_device_source;
 pub use error_handler::{DefaultErrorHandler, ErrorClass, ErrorHandler};
+pub use http_client::{user_agent_for, HttpAuth, RetryConfig, SourceClient, SourceHttpClient};
+pub use mock_transport::{MockRequest, MockTransport};
+pub use network::NetworkConfig;
 pub use oauth::{OAuthProxyConfig, OAuthToken, TokenEncryptor, TokenManager};
-pub use oauth_client::{OAuthHttpClient, RetryConfig};
 pub use sync_mode::{SyncMode, SyncResult};
+pub use stream_limits::{hold_resume_cursor, load_resume_cursor, save_resume_cursor, StreamLimits};
 pub use sync_strategy::SyncStrategy;
 pub use transform::{
     find_transform, registered_transforms, ChainedTransform, OntologyTransform,
@@ -46,3 +55,4 @@ pub use transform_data_source::{
     get_chunk_size, DataSourceType, MemoryDataSource, StreamBatch, TransformDataSource,
 };
 pub use validation::*;
+pub use webhook::{WebhookAccount, WebhookAction, WebhookRequest, WebhookSource};
@@ -0,0 +1,220 @@
+//! Per-source network (egress) configuration
+//!
+//! By default source clients go out directly, honoring the standard
+//! `HTTPS_PROXY` / `HTTP_PROXY` / `NO_PROXY` environment variables (reqwest
+//! reads these itself). A source can override that with its own proxy, or be
+//! taken offline so every request fails before leaving the process, which is
+//! useful for testing against a fixed archive.
+//!
+//! The override is stored as JSON in `elt_source_connections.network_config`,
+//! loaded by the `StreamFactory`, and carried to the source's client through
+//! `SourceAuth`.
+
+use reqwest::{ClientBuilder, Proxy};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+use crate::error::{Error, Result};
+
+/// Network settings for one source's outbound HTTP traffic
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkConfig {
+    /// Proxy URL (`http://` or `https://`) for all of the source's requests,
+    /// taking precedence over the proxy environment variables
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+
+    /// Refuse all outbound requests for this source
+    #[serde(default)]
+    pub offline: bool,
+}
+
+impl NetworkConfig {
+    /// Check that the proxy, if any, is a usable HTTP(S) proxy URL
+    pub fn validate(&self) -> Result<()> {
+        let Some(proxy) = &self.proxy else {
+            return Ok(());
+        };
+
+        let url = url::Url::parse(proxy)
+            .map_err(|e| Error::InvalidInput(format!("Invalid proxy URL {proxy}: {e}")))?;
+        if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+            return Err(Error::InvalidInput(format!(
+                "Proxy URL must be http:// or https:// with a host: {proxy}"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Apply the proxy override to a client builder
+    ///
+    /// Without an override the builder is returned as is, so reqwest keeps
+    /// using the proxy environment variables.
+    pub fn apply(&self, builder: ClientBuilder) -> Result<ClientBuilder> {
+        match &self.proxy {
+            Some(proxy) => {
+                let proxy = Proxy::all(proxy)
+                    .map_err(|e| Error::Configuration(format!("Invalid proxy {proxy}: {e}")))?;
+                Ok(builder.proxy(proxy))
+            }
+            None => Ok(builder),
+        }
+    }
+
+    /// Fail if the source has network access disabled
+    pub fn ensure_online(&self, source_id: &str) -> Result<()> {
+        if self.offline {
+            return Err(Error::Network(format!(
+                "Network access is disabled for source {source_id}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Load a source's network configuration (defaults if none is set)
+    pub async fn load(db: &SqlitePool, source_id: &str) -> Result<Self> {
+        let config: Option<String> =
+            sqlx::query_scalar("SELECT network_config FROM elt_source_connections WHERE id = $1")
+                .bind(source_id)
+                .fetch_optional(db)
+                .await?
+                .flatten();
+
+        let config: Self = match config {
+            Some(json) => serde_json::from_str(&json).map_err(|e| {
+                Error::Configuration(format!(
+                    "Invalid network_config for source {source_id}: {e}"
+                ))
+            })?,
+            None => Self::default(),
+        };
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Store a source's network configuration
+    ///
+    /// The default configuration is stored as NULL.
+    pub async fn save(&self, db: &SqlitePool, source_id: &str) -> Result<()> {
+        self.validate()?;
+
+        let json = if *self == Self::default() {
+            None
+        } else {
+            Some(serde_json::to_string(self)?)
+        };
+
+        let result = sqlx::query(
+            "UPDATE elt_source_connections SET network_config = $1, updated_at = datetime('now') WHERE id = $2",
+        )
+        .bind(json)
+        .bind(source_id)
+        .execute(db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound(format!("Source not found: {source_id}")));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_proxy() {
+        assert!(NetworkConfig::default().validate().is_ok());
+
+        let valid = NetworkConfig {
+            proxy: Some("http://proxy.corp.example:3128".to_string()),
+            offline: false,
+        };
+        assert!(valid.validate().is_ok());
+        assert!(valid
+            .apply(reqwest::Client::builder())
+            .unwrap()
+            .build()
+            .is_ok());
+
+        for proxy in ["not a url", "ftp://proxy:21", "file:///tmp/proxy"] {
+            let config = NetworkConfig {
+                proxy: Some(proxy.to_string()),
+                offline: false,
+            };
+            assert!(config.validate().is_err(), "{proxy} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_offline_refuses_requests() {
+        let offline = NetworkConfig {
+            proxy: None,
+            offline: true,
+        };
+        assert!(matches!(
+            offline.ensure_online("source_1"),
+            Err(Error::Network(_))
+        ));
+        assert!(NetworkConfig::default().ensure_online("source_1").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_load_and_save() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE elt_source_connections (
+                id TEXT PRIMARY KEY,
+                network_config TEXT,
+                updated_at TEXT
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO elt_source_connections (id) VALUES ('source_1')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            NetworkConfig::load(&pool, "source_1").await.unwrap(),
+            NetworkConfig::default()
+        );
+
+        let config = NetworkConfig {
+            proxy: Some("https://egress.example:8443".to_string()),
+            offline: true,
+        };
+        config.save(&pool, "source_1").await.unwrap();
+        assert_eq!(
+            NetworkConfig::load(&pool, "source_1").await.unwrap(),
+            config
+        );
+
+        NetworkConfig::default()
+            .save(&pool, "source_1")
+            .await
+            .unwrap();
+        let stored: Option<String> = sqlx::query_scalar(
+            "SELECT network_config FROM elt_source_connections WHERE id = 'source_1'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(stored, None);
+
+        assert!(matches!(
+            config.save(&pool, "missing").await,
+            Err(Error::NotFound(_))
+        ));
+    }
+}
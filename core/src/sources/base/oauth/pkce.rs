@@ -0,0 +1,180 @@
+//! PKCE and pending-authorization persistence for the OAuth flow
+//!
+//! Each authorize request gets a fresh PKCE code verifier (RFC 7636). Its S256
+//! challenge goes to the provider in the authorization URL, while the verifier
+//! is stored server-side keyed by the state token, together with the
+//! redirect_uri that was sent. The callback takes the row back out: the code
+//! exchange supplies the verifier and the identical redirect_uri, and since
+//! the row is deleted on use a state token can only complete one callback.
+
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+
+use super::state::STATE_VALIDITY_MINUTES;
+use crate::error::Result;
+
+/// A PKCE code verifier and its S256 challenge
+#[derive(Debug, Clone)]
+pub struct PkcePair {
+    pub verifier: String,
+    pub challenge: String,
+}
+
+impl PkcePair {
+    /// Generate a verifier from 256 random bits (43 base64url characters)
+    pub fn generate() -> Self {
+        use rand::RngCore;
+        let mut bytes = [0u8; 32];
+        rand::rng().fill_bytes(&mut bytes);
+        let verifier = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes);
+        let challenge = challenge_for(&verifier);
+        Self {
+            verifier,
+            challenge,
+        }
+    }
+}
+
+/// S256 code challenge: base64url(sha256(verifier)) without padding
+pub fn challenge_for(verifier: &str) -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// State kept between the authorize redirect and the callback
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct PendingAuthorization {
+    pub provider: String,
+    pub code_verifier: String,
+    pub redirect_uri: String,
+}
+
+/// Store the pending authorization for a freshly issued state token
+///
+/// Also purges entries whose state has expired, so abandoned flows don't
+/// accumulate.
+pub async fn save_pending(
+    pool: &SqlitePool,
+    state: &str,
+    pending: &PendingAuthorization,
+) -> Result<()> {
+    sqlx::query("DELETE FROM oauth_pending_authorizations WHERE created_at <= datetime('now', $1)")
+        .bind(format!("-{STATE_VALIDITY_MINUTES} minutes"))
+        .execute(pool)
+        .await?;
+
+    sqlx::query(
+        "INSERT INTO oauth_pending_authorizations (state, provider, code_verifier, redirect_uri)
+         VALUES ($1, $2, $3, $4)",
+    )
+    .bind(state)
+    .bind(&pending.provider)
+    .bind(&pending.code_verifier)
+    .bind(&pending.redirect_uri)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Remove and return the pending authorization for a state token
+///
+/// Returns `None` if the state was never issued here, has already been used,
+/// or is older than the state validity window.
+pub async fn take_pending(pool: &SqlitePool, state: &str) -> Result<Option<PendingAuthorization>> {
+    let pending = sqlx::query_as::<_, PendingAuthorization>(
+        "DELETE FROM oauth_pending_authorizations
+         WHERE state = $1 AND created_at > datetime('now', $2)
+         RETURNING provider, code_verifier, redirect_uri",
+    )
+    .bind(state)
+    .bind(format!("-{STATE_VALIDITY_MINUTES} minutes"))
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(pending)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn test_pool() -> SqlitePool {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE oauth_pending_authorizations (
+                state TEXT PRIMARY KEY,
+                provider TEXT NOT NULL,
+                code_verifier TEXT NOT NULL,
+                redirect_uri TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool
+    }
+
+    fn pending(verifier: &str) -> PendingAuthorization {
+        PendingAuthorization {
+            provider: "google".to_string(),
+            code_verifier: verifier.to_string(),
+            redirect_uri: "https://jace.virtues.com/oauth/callback".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_challenge_matches_rfc_7636_example() {
+        // Appendix B of RFC 7636
+        assert_eq!(
+            challenge_for("dBjftJeZ4CVP-mA3oGPLjJQZQ3ss2dMWi9n8Ofr-Nns"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+
+        let pair = PkcePair::generate();
+        assert_eq!(pair.verifier.len(), 43);
+        assert_eq!(pair.challenge, challenge_for(&pair.verifier));
+        assert_ne!(pair.verifier, PkcePair::generate().verifier);
+    }
+
+    #[tokio::test]
+    async fn test_pending_authorization_is_single_use() {
+        let pool = test_pool().await;
+        save_pending(&pool, "state_1", &pending("verifier_1"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            take_pending(&pool, "state_1").await.unwrap(),
+            Some(pending("verifier_1"))
+        );
+        assert_eq!(take_pending(&pool, "state_1").await.unwrap(), None);
+        assert_eq!(take_pending(&pool, "never_issued").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_expired_authorizations_are_rejected_and_purged() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO oauth_pending_authorizations (state, provider, code_verifier, redirect_uri, created_at)
+             VALUES ('stale', 'google', 'v', 'r', datetime('now', '-1 hour'))",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert_eq!(take_pending(&pool, "stale").await.unwrap(), None);
+
+        save_pending(&pool, "fresh", &pending("v")).await.unwrap();
+        let remaining: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM oauth_pending_authorizations")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(remaining, 1);
+    }
+}
@@ -13,7 +13,70 @@ use crate::error::{Error, Result};
 type HmacSha256 = Hmac<Sha256>;
 
 /// Duration for which state tokens are valid (10 minutes)
-const STATE_VALIDITY_MINUTES: i64 = 10;
+pub(super) const STATE_VALIDITY_MINUTES: i64 = 10;
+
+/// Session data prefix for re-authorizing an existing source
+///
+/// Return URLs never start with this (they're relative paths or http(s)/app
+/// scheme URLs), so plain return-URL sessions stay unambiguous.
+const REAUTH_PREFIX: &str = "reauth:";
+
+/// Session data prefix for reconnecting an existing source after its grant lapsed
+const RECONNECT_PREFIX: &str = "reconnect:";
+
+/// Session data carried through the OAuth flow in the signed state token
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OAuthSession {
+    /// Where the user should be redirected after OAuth completes
+    pub return_url: Option<String>,
+    /// Source being re-authorized (scope upgrade or reconnect), if any
+    pub reauth_source_id: Option<String>,
+    /// Re-authorization restores a revoked or expired grant, so the source's
+    /// streams should sync again once the new tokens are stored
+    pub reconnect: bool,
+}
+
+impl OAuthSession {
+    /// Encode as state session data
+    pub fn encode(&self) -> String {
+        let return_url = self.return_url.as_deref().unwrap_or_default();
+        match &self.reauth_source_id {
+            Some(source_id) if self.reconnect => {
+                format!("{RECONNECT_PREFIX}{source_id}:{return_url}")
+            }
+            Some(source_id) => format!("{REAUTH_PREFIX}{source_id}:{return_url}"),
+            None => return_url.to_string(),
+        }
+    }
+
+    /// Decode session data extracted from a validated state token
+    pub fn decode(session_data: Option<String>) -> Self {
+        let Some(data) = session_data else {
+            return Self::default();
+        };
+
+        let (rest, reconnect) = match data.strip_prefix(RECONNECT_PREFIX) {
+            Some(rest) => (Some(rest), true),
+            None => (data.strip_prefix(REAUTH_PREFIX), false),
+        };
+
+        match rest {
+            Some(rest) => {
+                let (source_id, return_url) = rest.split_once(':').unwrap_or((rest, ""));
+                Self {
+                    return_url: Some(return_url.to_string()).filter(|u| !u.is_empty()),
+                    reauth_source_id: Some(source_id.to_string()),
+                    reconnect,
+                }
+            }
+            None => Self {
+                return_url: Some(data),
+                reauth_source_id: None,
+                reconnect: false,
+            },
+        }
+    }
+}
 
 /// Generate a signed OAuth state token
 ///
@@ -154,6 +217,33 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    #[serial]
+    fn test_reauth_session_round_trip() {
+        setup_test_key();
+
+        let session = OAuthSession {
+            return_url: Some("http://localhost:5173/data/sources?tab=1".to_string()),
+            reauth_source_id: Some("source_abc".to_string()),
+            reconnect: false,
+        };
+        let state = generate_state(Some(&session.encode())).unwrap();
+        let decoded = OAuthSession::decode(validate_and_extract_state(&state).unwrap());
+        assert_eq!(decoded, session);
+
+        let reconnect = OAuthSession {
+            reconnect: true,
+            ..session
+        };
+        let decoded = OAuthSession::decode(Some(reconnect.encode()));
+        assert_eq!(decoded, reconnect);
+
+        // Plain return-URL sessions from the initial connect flow
+        let decoded = OAuthSession::decode(Some("/data/sources/add".to_string()));
+        assert_eq!(decoded.return_url.as_deref(), Some("/data/sources/add"));
+        assert_eq!(decoded.reauth_source_id, None);
+    }
+
     #[test]
     #[serial]
     fn test_reject_tampered_state() {
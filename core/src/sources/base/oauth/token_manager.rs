@@ -7,10 +7,12 @@ use chrono::{DateTime, Duration, Utc};
 use reqwest::Client;
 use serde::Deserialize;
 use sqlx::SqlitePool;
-
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
 
 use super::encryption::TokenEncryptor;
 use crate::error::{Error, Result};
+use crate::sources::base::NetworkConfig;
 
 /// Token refresh response from OAuth proxy
 #[derive(Debug, Deserialize)]
@@ -46,6 +48,22 @@ impl Default for OAuthProxyConfig {
     }
 }
 
+static REFRESH_LOCKS: OnceLock<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> =
+    OnceLock::new();
+
+/// Process-wide refresh lock for a source
+///
+/// Each stream builds its own `TokenManager`, so the lock lives outside it:
+/// concurrent syncs of one source must share it for refreshes to be
+/// single-flight.
+fn refresh_lock(source_id: &str) -> Arc<tokio::sync::Mutex<()>> {
+    let mut locks = REFRESH_LOCKS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    locks.entry(source_id.to_string()).or_default().clone()
+}
+
 /// Centralized token manager for all OAuth sources
 pub struct TokenManager {
     db: SqlitePool,
@@ -110,17 +128,30 @@ impl TokenManager {
     }
 
     /// Get a valid access token for a source, refreshing if necessary
+    ///
+    /// Refreshes are single-flight per source: concurrent callers that find
+    /// the token expiring wait for one refresh and reuse its result, instead
+    /// of each spending the refresh token (providers that rotate refresh
+    /// tokens invalidate the old one on first use).
     pub async fn get_valid_token(&self, source_id: String) -> Result<String> {
         // Load token from database
         let token = self.load_token(source_id.clone()).await?;
+        if !self.needs_refresh(&token) {
+            return Ok(token.access_token);
+        }
 
-        // Check if token needs refresh
-        if self.needs_refresh(&token) {
-            let refreshed = self.refresh_token(source_id, &token).await?;
-            Ok(refreshed.access_token)
-        } else {
-            Ok(token.access_token)
+        let lock = refresh_lock(&source_id);
+        let _guard = lock.lock().await;
+
+        // Another caller may have refreshed while we waited
+        let token = self.load_token(source_id.clone()).await?;
+        if !self.needs_refresh(&token) {
+            tracing::debug!(source_id = %source_id, "Token refreshed by a concurrent caller");
+            return Ok(token.access_token);
         }
+
+        let refreshed = self.refresh_token(source_id, &token).await?;
+        Ok(refreshed.access_token)
     }
 
     /// Load token information from the database
@@ -191,7 +222,8 @@ impl TokenManager {
         let refresh_url = format!("{}/{}/refresh", self.proxy_config.base_url, token.source);
 
         let response = self
-            .client
+            .client_for(&source_id)
+            .await?
             .post(&refresh_url)
             .json(&serde_json::json!({
                 "refresh_token": refresh_token
@@ -272,6 +304,91 @@ impl TokenManager {
         })
     }
 
+    /// Revoke a source's OAuth grant at the provider
+    ///
+    /// Returns `false` without calling out when the provider has no
+    /// revocation endpoint. Stored tokens are left untouched; callers drop
+    /// them afterwards.
+    #[tracing::instrument(skip(self), fields(source_id = %source_id))]
+    pub async fn revoke_token(&self, source_id: &str) -> Result<bool> {
+        let token = self.load_token(source_id.to_string()).await?;
+        let Some(revoke_url) = crate::registry::get_source(&token.source)
+            .and_then(|s| s.descriptor.oauth_config.as_ref())
+            .and_then(|c| c.revoke_url)
+        else {
+            return Ok(false);
+        };
+
+        let form = match token.source.as_str() {
+            // Strava deauthorizes with a (still valid) access token
+            "strava" => {
+                let access_token = self.get_valid_token(source_id.to_string()).await?;
+                vec![("access_token", access_token)]
+            }
+            // RFC 7009: revoking the refresh token also ends its access tokens
+            _ => vec![(
+                "token",
+                token.refresh_token.clone().unwrap_or(token.access_token),
+            )],
+        };
+
+        let response = self
+            .client_for(source_id)
+            .await?
+            .post(revoke_url)
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| Error::Network(format!("Failed to revoke token: {e}")))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Authentication(format!(
+                "Token revocation failed ({status}): {error_text}"
+            )));
+        }
+
+        Ok(true)
+    }
+
+    /// HTTP client for a source's token calls, with its network settings
+    async fn client_for(&self, source_id: &str) -> Result<Client> {
+        let network = NetworkConfig::load(&self.db, source_id).await?;
+        network.ensure_online(source_id)?;
+        if network.proxy.is_none() {
+            return Ok(self.client.clone());
+        }
+
+        network
+            .apply(Client::builder())?
+            .build()
+            .map_err(|e| Error::Configuration(format!("Failed to build HTTP client: {e}")))
+    }
+
+    /// Drop a source's stored OAuth tokens
+    pub async fn clear_tokens(&self, source_id: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE elt_source_connections
+            SET
+                access_token = NULL,
+                refresh_token = NULL,
+                token_expires_at = NULL,
+                updated_at = datetime('now')
+            WHERE id = $1
+            "#,
+        )
+        .bind(source_id)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
     /// Store initial OAuth tokens from a callback
     pub async fn store_initial_tokens(
         &self,
@@ -307,8 +424,10 @@ impl TokenManager {
                 refresh_token = COALESCE(EXCLUDED.refresh_token, elt_source_connections.refresh_token),
                 token_expires_at = EXCLUDED.token_expires_at,
                 is_active = true,
+                deleted_at = NULL,
                 error_message = NULL,
                 error_at = NULL,
+                reauth_reason = NULL,
                 updated_at = datetime('now')
             RETURNING id
             "#,
@@ -325,6 +444,70 @@ impl TokenManager {
         Ok(source_id_str)
     }
 
+    /// Replace the tokens of an existing source after re-authorization
+    ///
+    /// Used by the scope upgrade flow: the connection, its streams and sync
+    /// state are kept, only the credentials and granted scopes change.
+    pub async fn update_source_tokens(
+        &self,
+        source_id: &str,
+        access_token: String,
+        refresh_token: Option<String>,
+        expires_in: Option<i64>,
+        granted_scopes: &str,
+    ) -> Result<()> {
+        let expires_at = expires_in.map(|seconds| Utc::now() + Duration::seconds(seconds));
+
+        let access_token_to_store = self.encryptor.encrypt(&access_token)?;
+        let refresh_token_to_store = if let Some(ref rt) = refresh_token {
+            Some(self.encryptor.encrypt(rt)?)
+        } else {
+            None
+        };
+
+        let result = sqlx::query(
+            r#"
+            UPDATE elt_source_connections
+            SET
+                access_token = $1,
+                refresh_token = COALESCE($2, refresh_token),
+                token_expires_at = $3,
+                granted_scopes = $4,
+                error_message = NULL,
+                error_at = NULL,
+                reauth_reason = NULL,
+                updated_at = datetime('now')
+            WHERE id = $5
+            "#,
+        )
+        .bind(access_token_to_store)
+        .bind(refresh_token_to_store)
+        .bind(expires_at)
+        .bind(granted_scopes)
+        .bind(source_id)
+        .execute(&self.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound(format!("Source not found: {source_id}")));
+        }
+
+        Ok(())
+    }
+
+    /// Record the OAuth scopes granted to a source
+    pub async fn store_granted_scopes(&self, source_id: &str, granted_scopes: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE elt_source_connections SET granted_scopes = $1, updated_at = datetime('now') WHERE id = $2",
+        )
+        .bind(granted_scopes)
+        .bind(source_id)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
     /// Mark a source as having authentication errors
     pub async fn mark_auth_error(&self, source_id: String, error_message: &str) -> Result<()> {
         sqlx::query(
@@ -401,4 +584,35 @@ mod tests {
         };
         assert!(!manager.needs_refresh(&token));
     }
+
+    #[test]
+    fn test_refresh_lock_is_shared_per_source() {
+        assert!(Arc::ptr_eq(&refresh_lock("source-a"), &refresh_lock("source-a")));
+        assert!(!Arc::ptr_eq(&refresh_lock("source-a"), &refresh_lock("source-b")));
+    }
+    #[tokio::test]
+    async fn test_refresh_honors_source_network_settings() {
+        let pool = crate::sources::base::mock_transport::testing::migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, name, network_config)
+             VALUES ('src-offline', 'google', 'Google', '{\"offline\":true}')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let manager = TokenManager::new_insecure(pool);
+
+        let token = OAuthToken {
+            access_token: "test".to_string(),
+            refresh_token: Some("refresh".to_string()),
+            expires_at: None,
+            source: "google".to_string(),
+        };
+        assert!(matches!(
+            manager
+                .refresh_token("src-offline".to_string(), &token)
+                .await,
+            Err(Error::Network(_))
+        ));
+    }
 }
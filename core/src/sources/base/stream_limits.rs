@@ -0,0 +1,350 @@
+//! Per-stream pagination and record caps
+//!
+//! Every pull stream reads `page_size` and `max_records_per_run` from its
+//! stream connection config. When a run reaches the record cap it stops
+//! paginating and saves a resume cursor, so a runaway full sync is spread
+//! across several bounded runs instead of consuming unbounded time/storage.
+//!
+//! Streams that guard against endless pagination also read `max_pages`, the
+//! most pages a run follows (at most `max_pages * page_size` records). Each
+//! stream keeps its own default; raising it helps unusually large accounts,
+//! lowering it keeps tests short. Hitting the bound is logged as a warning,
+//! since records past it are not fetched by that run.
+//!
+//! Streams with a timestamp cursor also read `cursor_overlap_secs`: each
+//! incremental run re-fetches that much before the cursor, so records stamped
+//! slightly behind it (provider clock skew, several records sharing the
+//! cursor's timestamp) aren't skipped. Re-fetched records that were already
+//! archived are dropped by `_record_id`. A wider window tolerates more skew
+//! but re-downloads more on every run; for low-volume feeds a few minutes
+//! costs at most a page.
+//!
+//! A sync job holds back the resume cursors its stream saves (see
+//! [`hold_resume_cursor`]) and commits the last one with the run's archived
+//! records, so a run killed mid-listing never leaves a resume cursor past
+//! records that only ever reached memory.
+//!
+//! `first_sync_days` sets how much recent history a stream's first sync
+//! fetches before the rest is backfilled in the background (see
+//! `jobs::first_sync`); `0` turns the bound off for the stream.
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use crate::error::Result;
+
+/// Default record cap per run when neither the stream config nor the
+/// environment sets one
+pub const DEFAULT_MAX_RECORDS_PER_RUN: usize = 50_000;
+
+/// Default re-fetch window before a timestamp cursor, in seconds
+pub const DEFAULT_CURSOR_OVERLAP_SECS: u64 = 300;
+
+/// Largest accepted re-fetch window (one week)
+pub const MAX_CURSOR_OVERLAP_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Default days of history a first sync fetches when neither the stream
+/// config nor the environment sets it
+pub const DEFAULT_FIRST_SYNC_DAYS: u32 = 30;
+
+/// Pagination and record limits for a stream
+///
+/// Deserialized from the same `elt_stream_connections.config` JSON as the
+/// stream's own config; unknown keys are ignored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamLimits {
+    /// Records requested per API page (stream-specific default if unset)
+    #[serde(default)]
+    pub page_size: Option<u32>,
+
+    /// Maximum records fetched in a single run
+    #[serde(default)]
+    pub max_records_per_run: Option<usize>,
+
+    /// Seconds re-fetched before a timestamp cursor on incremental runs
+    #[serde(default)]
+    pub cursor_overlap_secs: Option<u64>,
+
+    /// Most pages followed in a single run (stream-specific default if unset)
+    #[serde(default)]
+    pub max_pages: Option<u32>,
+
+    /// Days of recent history fetched by the first sync (`0` = no bound)
+    #[serde(default)]
+    pub first_sync_days: Option<u32>,
+}
+
+impl StreamLimits {
+    /// Load limits for a stream from its connection config
+    ///
+    /// Falls back to `STREAM_MAX_RECORDS_PER_RUN` (then
+    /// `DEFAULT_MAX_RECORDS_PER_RUN`) when the config sets no cap.
+    pub async fn load(db: &SqlitePool, source_id: &str, stream_name: &str) -> Result<Self> {
+        let config = sqlx::query_scalar::<_, serde_json::Value>(
+            "SELECT config FROM elt_stream_connections WHERE source_connection_id = $1 AND stream_name = $2",
+        )
+        .bind(source_id)
+        .bind(stream_name)
+        .fetch_optional(db)
+        .await?;
+
+        let limits = config
+            .and_then(|c| serde_json::from_value::<Self>(c).ok())
+            .unwrap_or_default();
+
+        Ok(limits.with_default_cap(env_max_records_per_run()))
+    }
+
+    fn with_default_cap(mut self, default_cap: usize) -> Self {
+        if self.max_records_per_run.is_none() {
+            self.max_records_per_run = Some(default_cap);
+        }
+        self
+    }
+
+    /// Page size to request, using the stream's default if not configured
+    pub fn page_size_or(&self, default: u32) -> u32 {
+        self.page_size.filter(|&n| n > 0).unwrap_or(default)
+    }
+
+    /// Most pages to follow, using the stream's default if not configured
+    pub fn max_pages_or(&self, default: u32) -> u32 {
+        self.max_pages.filter(|&n| n > 0).unwrap_or(default)
+    }
+
+    /// Most records the page bound lets a run fetch with pages of `page_size`
+    pub fn max_records_or(&self, default_pages: u32, page_size: u32) -> usize {
+        self.max_pages_or(default_pages) as usize * page_size as usize
+    }
+
+    /// Re-fetch window before a timestamp cursor (`0` disables it)
+    ///
+    /// Capped at `MAX_CURSOR_OVERLAP_SECS`.
+    pub fn cursor_overlap(&self) -> chrono::Duration {
+        let secs = self
+            .cursor_overlap_secs
+            .unwrap_or(DEFAULT_CURSOR_OVERLAP_SECS)
+            .min(MAX_CURSOR_OVERLAP_SECS);
+        chrono::Duration::seconds(secs as i64)
+    }
+
+    /// How far back a first sync reaches, or `None` when it isn't bounded
+    ///
+    /// Falls back to `FIRST_SYNC_DAYS` (then `DEFAULT_FIRST_SYNC_DAYS`) when
+    /// the config doesn't set `first_sync_days`.
+    pub fn first_sync_window(&self) -> Option<chrono::Duration> {
+        let days = self.first_sync_days.unwrap_or_else(env_first_sync_days);
+        (days > 0).then(|| chrono::Duration::days(days as i64))
+    }
+
+    /// Whether `records_fetched` has reached the per-run cap
+    pub fn is_reached(&self, records_fetched: usize) -> bool {
+        self.max_records_per_run
+            .is_some_and(|max| records_fetched >= max)
+    }
+}
+
+fn env_max_records_per_run() -> usize {
+    std::env::var("STREAM_MAX_RECORDS_PER_RUN")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or(DEFAULT_MAX_RECORDS_PER_RUN)
+}
+
+fn env_first_sync_days() -> u32 {
+    std::env::var("FIRST_SYNC_DAYS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_FIRST_SYNC_DAYS)
+}
+
+tokio::task_local! {
+    static HELD_RESUME_CURSOR: Arc<Mutex<Option<Option<String>>>>;
+}
+
+/// Run a sync, holding back the resume cursor it saves
+///
+/// Returns the last resume cursor saved inside `future` (`Some(None)` when it
+/// was cleared, `None` when none was saved) for the caller to commit once the
+/// run's records are archived.
+pub async fn hold_resume_cursor<F: Future>(future: F) -> (F::Output, Option<Option<String>>) {
+    let held = Arc::new(Mutex::new(None));
+    let output = HELD_RESUME_CURSOR.scope(held.clone(), future).await;
+    let cursor = held.lock().unwrap_or_else(|e| e.into_inner()).take();
+    (output, cursor)
+}
+
+/// Load the resume cursor left by a capped run, if any
+pub async fn load_resume_cursor(
+    db: &SqlitePool,
+    source_id: &str,
+    stream_name: &str,
+) -> Result<Option<String>> {
+    let cursor = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT resume_cursor FROM elt_stream_connections WHERE source_connection_id = $1 AND stream_name = $2",
+    )
+    .bind(source_id)
+    .bind(stream_name)
+    .fetch_optional(db)
+    .await?
+    .flatten();
+
+    Ok(cursor)
+}
+
+/// Save (or clear, with `None`) the resume cursor for a stream
+///
+/// Accepts a pool or a transaction, so the resume cursor can be committed
+/// together with the stream's sync token. Inside [`hold_resume_cursor`] the
+/// cursor is handed to the caller instead of written.
+pub async fn save_resume_cursor<'e, E>(
+    db: E,
+    source_id: &str,
+    stream_name: &str,
+    cursor: Option<&str>,
+) -> Result<()>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+{
+    let held = HELD_RESUME_CURSOR.try_with(|held| {
+        *held.lock().unwrap_or_else(|e| e.into_inner()) = Some(cursor.map(str::to_string));
+    });
+    if held.is_ok() {
+        return Ok(());
+    }
+
+    sqlx::query(
+        "UPDATE elt_stream_connections SET resume_cursor = $1 WHERE source_connection_id = $2 AND stream_name = $3",
+    )
+    .bind(cursor)
+    .bind(source_id)
+    .bind(stream_name)
+    .execute(db)
+    .await?;
+
+    if let Some(cursor) = cursor {
+        tracing::info!(
+            source_id,
+            stream_name,
+            resume_cursor = cursor,
+            "Saved resume cursor; next run will resume from it"
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_from_stream_config() {
+        let config = serde_json::json!({
+            "max_messages_per_sync": 500,
+            "page_size": 50,
+            "max_records_per_run": 1000
+        });
+        let limits: StreamLimits = serde_json::from_value(config).unwrap();
+
+        assert_eq!(limits.page_size_or(100), 50);
+        assert!(!limits.is_reached(999));
+        assert!(limits.is_reached(1000));
+    }
+
+    #[test]
+    fn test_default_cap_applies_when_unset() {
+        let limits: StreamLimits = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert_eq!(limits.page_size_or(100), 100);
+        assert!(!limits.is_reached(usize::MAX));
+
+        let limits = limits.with_default_cap(10);
+        assert!(limits.is_reached(10));
+
+        // An explicit cap wins over the default
+        let limits = StreamLimits {
+            page_size: None,
+            max_records_per_run: Some(5),
+            ..Default::default()
+        }
+        .with_default_cap(10);
+        assert!(limits.is_reached(5));
+    }
+
+    #[test]
+    fn test_max_pages() {
+        let limits: StreamLimits = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert_eq!(limits.max_pages_or(100), 100);
+        assert_eq!(limits.max_records_or(4, 100), 400);
+
+        let limits: StreamLimits =
+            serde_json::from_value(serde_json::json!({ "max_pages": 2, "page_size": 50 })).unwrap();
+        assert_eq!(limits.max_pages_or(100), 2);
+        assert_eq!(limits.max_records_or(100, limits.page_size_or(200)), 100);
+
+        // Zero means unset rather than "fetch nothing"
+        let limits: StreamLimits =
+            serde_json::from_value(serde_json::json!({ "max_pages": 0 })).unwrap();
+        assert_eq!(limits.max_pages_or(4), 4);
+    }
+
+    #[test]
+    fn test_first_sync_window() {
+        let limits: StreamLimits =
+            serde_json::from_value(serde_json::json!({ "first_sync_days": 7 })).unwrap();
+        assert_eq!(limits.first_sync_window(), Some(chrono::Duration::days(7)));
+
+        let limits: StreamLimits =
+            serde_json::from_value(serde_json::json!({ "first_sync_days": 0 })).unwrap();
+        assert_eq!(limits.first_sync_window(), None);
+    }
+
+    #[test]
+    fn test_cursor_overlap() {
+        let limits: StreamLimits = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert_eq!(
+            limits.cursor_overlap(),
+            chrono::Duration::seconds(DEFAULT_CURSOR_OVERLAP_SECS as i64)
+        );
+
+        let limits: StreamLimits =
+            serde_json::from_value(serde_json::json!({ "cursor_overlap_secs": 0 })).unwrap();
+        assert!(limits.cursor_overlap().is_zero());
+    }
+
+    #[tokio::test]
+    async fn test_held_resume_cursor_not_written() {
+        let db = crate::sources::base::mock_transport::testing::migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, name) VALUES ('src', 'google', 'src')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO elt_stream_connections (id, source_connection_id, stream_name, table_name, resume_cursor)
+             VALUES ('st', 'src', 'gmail', 'stream_google_gmail', 'p1')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let (saved, held) = hold_resume_cursor(async {
+            save_resume_cursor(&db, "src", "gmail", Some("p2")).await?;
+            save_resume_cursor(&db, "src", "gmail", Some("p3")).await
+        })
+        .await;
+        saved.unwrap();
+        assert_eq!(held, Some(Some("p3".to_string())));
+        assert_eq!(
+            load_resume_cursor(&db, "src", "gmail").await.unwrap().as_deref(),
+            Some("p1")
+        );
+
+        let ((), held) = hold_resume_cursor(async {}).await;
+        assert_eq!(held, None);
+    }
+}
@@ -8,8 +8,12 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 /// Sync strategies for data extraction
+///
+/// Serialized with a `mode` tag so it can be sent directly in API bodies:
+/// `{ "mode": "full_refresh" }`, `{ "mode": "incremental" }` or
+/// `{ "mode": "backfill", "start_date": "...", "end_date": "..." }`.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(tag = "type", rename_all = "snake_case")]
+#[serde(tag = "mode", rename_all = "snake_case")]
 pub enum SyncMode {
     /// Full refresh - replace all existing data
     FullRefresh,
@@ -17,6 +21,7 @@ pub enum SyncMode {
     /// Incremental - fetch only new/changed records using cursor/token
     Incremental {
         /// Sync cursor/token from last successful sync
+        #[serde(default)]
         cursor: Option<String>,
     },
 
@@ -24,7 +29,8 @@ pub enum SyncMode {
     Backfill {
         /// Start of the time range
         start_date: DateTime<Utc>,
-        /// End of the time range
+        /// End of the time range (defaults to now when omitted)
+        #[serde(default = "Utc::now")]
         end_date: DateTime<Utc>,
     },
 }
@@ -59,6 +65,15 @@ impl SyncMode {
         matches!(self, Self::FullRefresh)
     }
 
+    /// Name of the mode as stored on job records
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::FullRefresh => "full_refresh",
+            Self::Incremental { .. } => "incremental",
+            Self::Backfill { .. } => "backfill",
+        }
+    }
+
     /// Get the cursor if this is incremental sync
     pub fn cursor(&self) -> Option<&str> {
         match self {
@@ -106,6 +121,11 @@ pub struct SyncResult {
     /// S3 archival is happening asynchronously in the background.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub archive_job_id: Option<String>,
+
+    /// Records skipped because their natural id was already archived
+    /// (backfill / full refresh of a stream that declares a dedup key)
+    #[serde(default)]
+    pub records_deduplicated: usize,
 }
 
 impl SyncResult {
@@ -122,6 +142,7 @@ impl SyncResult {
             completed_at: Utc::now(),
             records: None,
             archive_job_id: None,
+            records_deduplicated: 0,
         }
     }
 
@@ -187,4 +208,36 @@ mod tests {
 
         assert_eq!(mode, deserialized);
     }
+
+    #[test]
+    fn test_sync_mode_api_bodies() {
+        let full: SyncMode = serde_json::from_str(r#"{ "mode": "full_refresh" }"#).unwrap();
+        assert_eq!(full, SyncMode::FullRefresh);
+        assert_eq!(full.as_str(), "full_refresh");
+
+        let incremental: SyncMode = serde_json::from_str(r#"{ "mode": "incremental" }"#).unwrap();
+        assert_eq!(incremental, SyncMode::incremental(None));
+
+        let backfill: SyncMode = serde_json::from_str(
+            r#"{ "mode": "backfill", "start_date": "2024-01-01T00:00:00Z", "end_date": "2024-02-01T00:00:00Z" }"#,
+        )
+        .unwrap();
+        assert_eq!(backfill.as_str(), "backfill");
+        match backfill {
+            SyncMode::Backfill { start_date, end_date } => {
+                assert_eq!(start_date.to_rfc3339(), "2024-01-01T00:00:00+00:00");
+                assert_eq!(end_date.to_rfc3339(), "2024-02-01T00:00:00+00:00");
+            }
+            other => panic!("expected backfill, got {:?}", other),
+        }
+
+        // end_date defaults to now
+        let open_ended: SyncMode =
+            serde_json::from_str(r#"{ "mode": "backfill", "start_date": "2024-01-01T00:00:00Z" }"#)
+                .unwrap();
+        assert!(matches!(open_ended, SyncMode::Backfill { end_date, .. } if end_date > Utc::now() - chrono::Duration::minutes(1)));
+
+        assert!(serde_json::from_str::<SyncMode>(r#"{ "mode": "backfill" }"#).is_err());
+        assert!(serde_json::from_str::<SyncMode>(r#"{ "mode": "sideways" }"#).is_err());
+    }
 }
@@ -23,6 +23,7 @@ pub fn get_chunk_size() -> usize {
     std::env::var("TRANSFORM_CHUNK_SIZE")
         .ok()
         .and_then(|s| s.parse().ok())
+        .filter(|n| *n > 0)
         .unwrap_or(DEFAULT_CHUNK_SIZE)
 }
 
@@ -113,6 +114,8 @@ pub struct MemoryDataSource {
     max_timestamp: Option<DateTime<Utc>>,
     /// Database connection for checkpoint updates
     db: sqlx::SqlitePool,
+    /// Records per batch returned to the transform
+    chunk_size: usize,
 }
 
 impl MemoryDataSource {
@@ -132,8 +135,15 @@ impl MemoryDataSource {
             min_timestamp,
             max_timestamp,
             db,
+            chunk_size: get_chunk_size(),
         }
     }
+
+    /// Override the number of records per batch (`TransformConfig::batch_size`)
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
 }
 
 #[async_trait]
@@ -156,8 +166,7 @@ impl TransformDataSource for MemoryDataSource {
         // Use a placeholder
         let object_id = "memory-data-source".to_string();
 
-        // Get chunk size from environment or use default
-        let chunk_size = get_chunk_size();
+        let chunk_size = self.chunk_size;
 
         // If small enough, return as single batch
         if self.records.len() <= chunk_size {
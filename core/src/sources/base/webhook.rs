@@ -0,0 +1,198 @@
+//! Shared webhook verification for push-capable sources
+//!
+//! Providers that notify us of new data (Strava, Plaid, ...) each authenticate
+//! deliveries differently, but the building blocks are the same: an HMAC over
+//! the raw body, a timing-safe comparison, and for some providers a GET
+//! challenge that must be echoed back when the subscription is created.
+//! Sources implement [`WebhookSource`] on top of these helpers and the server
+//! dispatches `/webhooks/:provider` requests through it.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use axum::http::HeaderMap;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::error::{Error, Result};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Compute HMAC-SHA256 of `payload` with `secret`
+pub fn hmac_sha256(secret: &[u8], payload: &[u8]) -> Vec<u8> {
+    // HMAC accepts keys of any length, so this cannot fail
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(payload);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Compare two byte strings in time independent of where they differ
+///
+/// Length is not secret (signatures have a fixed size), so a length mismatch
+/// returns early.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Verify a hex-encoded HMAC-SHA256 signature over the raw request body
+///
+/// Accepts a bare hex digest or one prefixed with `sha256=` (the GitHub-style
+/// header format). Fails with `Unauthorized` on any mismatch.
+pub fn verify_hmac_sha256(secret: &[u8], payload: &[u8], signature: &str) -> Result<()> {
+    let signature = signature.trim();
+    let hex_digest = signature.strip_prefix("sha256=").unwrap_or(signature);
+    let provided = hex::decode(hex_digest)
+        .map_err(|_| Error::Unauthorized("Malformed webhook signature".to_string()))?;
+
+    if constant_time_eq(&hmac_sha256(secret, payload), &provided) {
+        Ok(())
+    } else {
+        Err(Error::Unauthorized(
+            "Webhook signature mismatch".to_string(),
+        ))
+    }
+}
+
+/// Answer a subscription challenge (`hub.mode=subscribe` GET pattern)
+///
+/// Checks `hub.verify_token` against the token we registered the subscription
+/// with and returns the `hub.challenge` value to echo back.
+pub fn echo_challenge(query: &HashMap<String, String>, verify_token: &str) -> Result<String> {
+    if query.get("hub.mode").map(String::as_str) != Some("subscribe") {
+        return Err(Error::InvalidInput(
+            "Expected hub.mode=subscribe".to_string(),
+        ));
+    }
+
+    let provided = query
+        .get("hub.verify_token")
+        .map(String::as_str)
+        .unwrap_or("");
+    if !constant_time_eq(provided.as_bytes(), verify_token.as_bytes()) {
+        return Err(Error::Unauthorized(
+            "Webhook verify token mismatch".to_string(),
+        ));
+    }
+
+    query
+        .get("hub.challenge")
+        .cloned()
+        .ok_or_else(|| Error::InvalidInput("Missing hub.challenge".to_string()))
+}
+
+/// Inbound webhook delivery as received by the server
+pub struct WebhookRequest<'a> {
+    pub headers: &'a HeaderMap,
+    pub query: &'a HashMap<String, String>,
+    pub body: &'a [u8],
+}
+
+/// The provider account a delivery concerns
+///
+/// Matched against the `metadata_key` field of each source's metadata, so a
+/// delivery only syncs the source connected to that account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookAccount {
+    pub metadata_key: &'static str,
+    pub id: String,
+}
+
+/// What to do with a verified webhook delivery
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WebhookAction {
+    /// Trigger an incremental sync of these streams for the account's source
+    SyncStreams {
+        streams: Vec<&'static str>,
+        account: WebhookAccount,
+    },
+    /// Acknowledge without doing anything (unsupported or irrelevant event)
+    Ignore,
+}
+
+/// A source that accepts webhook deliveries
+#[async_trait]
+pub trait WebhookSource: Send + Sync {
+    /// Provider name, matching the `/webhooks/:provider` path segment
+    fn provider(&self) -> &'static str;
+
+    /// Answer a GET subscription challenge, returning the JSON body to send back
+    ///
+    /// Default: the provider doesn't use challenges.
+    fn challenge(&self, _query: &HashMap<String, String>) -> Result<serde_json::Value> {
+        Err(Error::NotFound(format!(
+            "{} webhooks do not use subscription challenges",
+            self.provider()
+        )))
+    }
+
+    /// Check that a delivery really comes from the provider
+    async fn verify(&self, request: &WebhookRequest<'_>) -> Result<()>;
+
+    /// Decide what a verified delivery should trigger
+    fn action(&self, body: &[u8]) -> Result<WebhookAction>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"It's a Secret to Everybody";
+    const PAYLOAD: &[u8] = b"Hello, World!";
+    // Published test vector from GitHub's webhook validation docs
+    const SIGNATURE: &str =
+        "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+
+    #[test]
+    fn test_verify_known_good_signature() {
+        assert!(verify_hmac_sha256(SECRET, PAYLOAD, SIGNATURE).is_ok());
+
+        // Bare hex digests are accepted too
+        let bare = SIGNATURE.trim_start_matches("sha256=");
+        assert!(verify_hmac_sha256(SECRET, PAYLOAD, bare).is_ok());
+    }
+
+    #[test]
+    fn test_reject_tampered_signature_or_payload() {
+        let mut tampered = SIGNATURE.to_string();
+        tampered.replace_range(tampered.len() - 1.., "0");
+        assert!(matches!(
+            verify_hmac_sha256(SECRET, PAYLOAD, &tampered),
+            Err(Error::Unauthorized(_))
+        ));
+
+        assert!(verify_hmac_sha256(SECRET, b"Hello, World?", SIGNATURE).is_err());
+        assert!(verify_hmac_sha256(b"wrong secret", PAYLOAD, SIGNATURE).is_err());
+        assert!(verify_hmac_sha256(SECRET, PAYLOAD, "sha256=not-hex").is_err());
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+        assert!(!constant_time_eq(b"abc", b"abcd"));
+    }
+
+    #[test]
+    fn test_echo_challenge() {
+        let query: HashMap<String, String> = [
+            ("hub.mode", "subscribe"),
+            ("hub.verify_token", "token"),
+            ("hub.challenge", "15f7d1a91c1f40f8a748fd134752feb3"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        assert_eq!(
+            echo_challenge(&query, "token").unwrap(),
+            "15f7d1a91c1f40f8a748fd134752feb3"
+        );
+        assert!(matches!(
+            echo_challenge(&query, "other"),
+            Err(Error::Unauthorized(_))
+        ));
+    }
+}
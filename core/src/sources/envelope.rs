@@ -0,0 +1,283 @@
+//! Sealed envelopes for end-to-end encrypted device streams
+//!
+//! A stream with `end_to_end_encrypted` set only accepts records sealed on
+//! the device. The server checks each envelope's shape, archives it as
+//! received and never sees the plaintext: transforms don't run on these
+//! streams, and decryption happens on the user's devices or in a transform
+//! worker the user has given the key to.
+//!
+//! # Envelope format (version 1)
+//!
+//! Each pushed record is a JSON object:
+//!
+//! ```json
+//! {
+//!   "v": 1,
+//!   "alg": "A256GCM",
+//!   "kid": "2024-01-phone",
+//!   "nonce": "<base64, 12 bytes>",
+//!   "ct": "<base64, ciphertext followed by the 16-byte tag>",
+//!   "timestamp": "2024-01-15T10:30:00Z",
+//!   "id": "optional client record id"
+//! }
+//! ```
+//!
+//! - `alg` is `A256GCM` (AES-256-GCM) or `C20P` (ChaCha20-Poly1305), both
+//!   with a 256-bit key.
+//! - The plaintext is the record's JSON, exactly as it would be pushed to an
+//!   unencrypted stream.
+//! - `timestamp` and `id` stay in the clear: the server partitions the archive
+//!   by the event time and devices match ingest results by id. Keep `id`
+//!   opaque (a random UUID, not a message or file name).
+//! - The associated data binds the envelope to its stream, key and event time,
+//!   so a ciphertext moved to another stream or re-dated won't open:
+//!   `virtues-e2e/v1|<stream>|<kid>|<timestamp>`, with `timestamp` exactly as
+//!   sent.
+//! - Nonces are random and must never repeat under one key.
+//!
+//! # Keys
+//!
+//! The key is generated on a device and never sent to the server, nor to any
+//! Virtues API. Devices share it among themselves out of band (the platform
+//! keychain, or a QR code scanned from a device that already has it). `kid`
+//! names the key an envelope was sealed with, so keys can be rotated without
+//! re-sealing old records; the server only requires it to be present. A
+//! trusted transform worker is configured with the keys it may open, outside
+//! of the server's own configuration. Losing every copy of a key loses the
+//! records sealed with it.
+
+use base64::Engine as _;
+use chrono::{DateTime, Utc};
+use ring::aead::{
+    Aad, Algorithm, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, CHACHA20_POLY1305, NONCE_LEN,
+};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+
+/// Envelope format version written and accepted
+pub const ENVELOPE_VERSION: u32 = 1;
+
+/// AEAD algorithm an envelope is sealed with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EnvelopeAlgorithm {
+    #[serde(rename = "A256GCM")]
+    Aes256Gcm,
+    #[serde(rename = "C20P")]
+    ChaCha20Poly1305,
+}
+
+impl EnvelopeAlgorithm {
+    fn aead(&self) -> &'static Algorithm {
+        match self {
+            EnvelopeAlgorithm::Aes256Gcm => &AES_256_GCM,
+            EnvelopeAlgorithm::ChaCha20Poly1305 => &CHACHA20_POLY1305,
+        }
+    }
+}
+
+/// A record sealed on the device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Envelope {
+    pub v: u32,
+    pub alg: EnvelopeAlgorithm,
+    /// Client-chosen name of the sealing key
+    pub kid: String,
+    /// Base64 nonce
+    pub nonce: String,
+    /// Base64 ciphertext with the authentication tag appended
+    pub ct: String,
+    /// Event time, in the clear for partitioning
+    pub timestamp: String,
+    /// Opaque client record id, echoed in ingest results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+impl Envelope {
+    /// Parse and check a pushed record, returning the envelope and its event time
+    ///
+    /// Checks only what the server can: the shape, version, key id, nonce
+    /// length and that the ciphertext is at least a tag long. Whether it opens
+    /// is only known to key holders.
+    pub fn parse(record: &Value) -> Result<(Self, DateTime<Utc>)> {
+        let envelope: Envelope = serde_json::from_value(record.clone()).map_err(|e| {
+            Error::InvalidInput(format!(
+                "Stream is end-to-end encrypted; expected a sealed envelope: {e}"
+            ))
+        })?;
+
+        if envelope.v != ENVELOPE_VERSION {
+            return Err(Error::InvalidInput(format!(
+                "Unsupported envelope version {}",
+                envelope.v
+            )));
+        }
+        if envelope.kid.trim().is_empty() {
+            return Err(Error::InvalidInput("Envelope has no key id".to_string()));
+        }
+        if decode(&envelope.nonce, "nonce")?.len() != NONCE_LEN {
+            return Err(Error::InvalidInput(format!(
+                "Envelope nonce must be {NONCE_LEN} bytes"
+            )));
+        }
+        if decode(&envelope.ct, "ct")?.len() < envelope.alg.aead().tag_len() {
+            return Err(Error::InvalidInput(
+                "Envelope ciphertext is shorter than its tag".to_string(),
+            ));
+        }
+        let timestamp = DateTime::parse_from_rfc3339(&envelope.timestamp)
+            .map_err(|e| Error::InvalidInput(format!("Invalid envelope timestamp: {e}")))?
+            .with_timezone(&Utc);
+
+        Ok((envelope, timestamp))
+    }
+
+    /// Seal a record, as a device does
+    pub fn seal(
+        record: &Value,
+        stream: &str,
+        kid: &str,
+        key: &[u8],
+        alg: EnvelopeAlgorithm,
+        timestamp: &str,
+    ) -> Result<Self> {
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| Error::Other("Failed to generate nonce".to_string()))?;
+
+        let mut in_out = serde_json::to_vec(record)?;
+        aead_key(alg, key)?
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(associated_data(stream, kid, timestamp)),
+                &mut in_out,
+            )
+            .map_err(|_| Error::Other("Failed to seal record".to_string()))?;
+
+        let b64 = base64::engine::general_purpose::STANDARD;
+        Ok(Self {
+            v: ENVELOPE_VERSION,
+            alg,
+            kid: kid.to_string(),
+            nonce: b64.encode(nonce),
+            ct: b64.encode(in_out),
+            timestamp: timestamp.to_string(),
+            id: None,
+        })
+    }
+
+    /// Open the envelope with the key named by `kid`, as a key holder does
+    pub fn open(&self, stream: &str, key: &[u8]) -> Result<Value> {
+        let nonce: [u8; NONCE_LEN] = decode(&self.nonce, "nonce")?
+            .try_into()
+            .map_err(|_| Error::InvalidInput("Invalid envelope nonce".to_string()))?;
+        let mut in_out = decode(&self.ct, "ct")?;
+
+        let plaintext = aead_key(self.alg, key)?
+            .open_in_place(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(associated_data(stream, &self.kid, &self.timestamp)),
+                &mut in_out,
+            )
+            .map_err(|_| Error::Other("Envelope failed to open".to_string()))?;
+
+        Ok(serde_json::from_slice(plaintext)?)
+    }
+}
+
+/// Whether a stream connection only accepts sealed envelopes
+pub async fn is_end_to_end_encrypted(
+    db: &sqlx::SqlitePool,
+    source_id: &str,
+    stream_name: &str,
+) -> Result<bool> {
+    let encrypted: Option<bool> = sqlx::query_scalar(
+        "SELECT end_to_end_encrypted FROM elt_stream_connections
+         WHERE source_connection_id = $1 AND stream_name = $2",
+    )
+    .bind(source_id)
+    .bind(stream_name)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(encrypted.unwrap_or(false))
+}
+
+fn associated_data(stream: &str, kid: &str, timestamp: &str) -> Vec<u8> {
+    format!("virtues-e2e/v{ENVELOPE_VERSION}|{stream}|{kid}|{timestamp}").into_bytes()
+}
+
+fn aead_key(alg: EnvelopeAlgorithm, key: &[u8]) -> Result<LessSafeKey> {
+    let key = UnboundKey::new(alg.aead(), key)
+        .map_err(|_| Error::InvalidInput("Envelope key must be 32 bytes".to_string()))?;
+    Ok(LessSafeKey::new(key))
+}
+
+fn decode(value: &str, field: &str) -> Result<Vec<u8>> {
+    base64::engine::general_purpose::STANDARD
+        .decode(value)
+        .map_err(|e| Error::InvalidInput(format!("Invalid base64 in envelope {field}: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const KEY: [u8; 32] = [7; 32];
+
+    #[test]
+    fn test_seal_parse_open_round_trip() {
+        let record = json!({ "text": "hello", "timestamp": "2024-01-15T10:30:00Z" });
+        for alg in [
+            EnvelopeAlgorithm::Aes256Gcm,
+            EnvelopeAlgorithm::ChaCha20Poly1305,
+        ] {
+            let sealed =
+                Envelope::seal(&record, "imessage", "k1", &KEY, alg, "2024-01-15T10:30:00Z")
+                    .unwrap();
+            let pushed = serde_json::to_value(&sealed).unwrap();
+            assert!(!pushed.to_string().contains("hello"));
+
+            let (envelope, timestamp) = Envelope::parse(&pushed).unwrap();
+            assert_eq!(timestamp.to_rfc3339(), "2024-01-15T10:30:00+00:00");
+            assert_eq!(envelope.open("imessage", &KEY).unwrap(), record);
+
+            // Bound to its stream and key
+            assert!(envelope.open("apps", &KEY).is_err());
+            assert!(envelope.open("imessage", &[8; 32]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_plaintext_and_malformed_envelopes() {
+        let plaintext = json!({ "text": "hello", "timestamp": "2024-01-15T10:30:00Z" });
+        assert!(Envelope::parse(&plaintext).is_err());
+
+        let sealed = Envelope::seal(
+            &plaintext,
+            "imessage",
+            "k1",
+            &KEY,
+            EnvelopeAlgorithm::Aes256Gcm,
+            "2024-01-15T10:30:00Z",
+        )
+        .unwrap();
+        let mut pushed = serde_json::to_value(&sealed).unwrap();
+        pushed["v"] = json!(2);
+        assert!(Envelope::parse(&pushed).is_err());
+
+        let mut pushed = serde_json::to_value(&sealed).unwrap();
+        pushed["nonce"] = json!("AAAA");
+        assert!(Envelope::parse(&pushed).is_err());
+
+        let mut pushed = serde_json::to_value(&sealed).unwrap();
+        pushed["kid"] = json!("");
+        assert!(Envelope::parse(&pushed).is_err());
+    }
+}
@@ -11,10 +11,10 @@ use sqlx::SqlitePool;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-use super::base::TokenManager;
+use super::base::{NetworkConfig, TokenManager};
 use crate::error::{Error, Result};
 use crate::registry::StreamFactoryContext;
-use crate::storage::{stream_writer::StreamWriter, Storage};
+use crate::storage::{redaction::Redactor, stream_writer::StreamWriter, Storage};
 
 use super::{auth::SourceAuth, stream_type::StreamType};
 
@@ -22,7 +22,8 @@ use super::{auth::SourceAuth, stream_type::StreamType};
 ///
 /// The StreamFactory handles:
 /// - Loading source information from the database
-/// - Creating appropriate authentication (OAuth2, Device, etc.)
+/// - Creating appropriate authentication (OAuth2, Device, etc.), including
+///   the source's network (proxy / offline) settings
 /// - Instantiating the correct stream implementation
 ///
 /// # Example
@@ -103,7 +104,7 @@ impl StreamFactory {
     /// Load source information from the database
     async fn load_source(&self, source_id: &str) -> Result<SourceInfo> {
         let result = sqlx::query_as::<_, (String, String)>(
-            "SELECT source, name FROM elt_source_connections WHERE id = $1 AND is_active = true",
+            "SELECT source, name FROM elt_source_connections WHERE id = $1 AND is_active = true AND deleted_at IS NULL",
         )
         .bind(source_id)
         .fetch_optional(&self.db)
@@ -118,13 +119,25 @@ impl StreamFactory {
         }
     }
 
+    /// Token manager for OAuth sources
+    fn token_manager(&self) -> Result<TokenManager> {
+        // Clients answered by a test transport never send the tokens
+        #[cfg(test)]
+        if crate::sources::base::mock_transport::testing::current_transport().is_some() {
+            return Ok(TokenManager::new_insecure(self.db.clone()));
+        }
+        TokenManager::new(self.db.clone())
+    }
+
     /// Create authentication for a source
     async fn create_auth(&self, source_id: &str, provider: &str) -> Result<SourceAuth> {
         match provider {
-            "github" | "google" | "notion" | "plaid" | "spotify" | "strava" => {
+            "fitbit" | "github" | "google" | "notion" | "plaid" | "slack" | "spotify"
+            | "strava" => {
                 // OAuth2 sources - create TokenManager for token refresh
-                let token_manager = Arc::new(TokenManager::new(self.db.clone())?);
-                Ok(SourceAuth::oauth2(source_id.to_string(), token_manager))
+                let token_manager = Arc::new(self.token_manager()?);
+                let network = NetworkConfig::load(&self.db, source_id).await?;
+                Ok(SourceAuth::oauth2(source_id.to_string(), token_manager).with_network(network))
             }
             "ios" | "mac" => {
                 // Device sources don't use traditional auth - they push data
@@ -163,6 +176,21 @@ impl StreamFactory {
                 ))
             })?;
 
+        // Records are redacted per the stream's config, then stamped with the
+        // stream's natural id as `_record_id` and with its record shape
+        // version as `_schema_version`
+        let redactor = Redactor::load(&self.db, source_id, stream_name).await?;
+        {
+            let mut writer = self.stream_writer.lock().await;
+            writer.register_redaction(source_id, stream_name, redactor);
+            if let Some(key) = stream_desc.dedup_key {
+                writer.register_id_key(source_id, stream_name, key);
+            }
+            writer.register_schema_version(source_id, stream_name, stream_desc.schema_version);
+            // Streams created here write plaintext; sealed ingest registers its own
+            writer.register_sealed(source_id, stream_name, false);
+        }
+
         // Check if the stream has a creator registered
         if let Some(creator) = stream_desc.stream_creator {
             // Use the unified registry's stream creator
@@ -208,7 +236,7 @@ mod tests {
     #[tokio::test]
     async fn test_factory_creation() {
         let pool = SqlitePool::connect_lazy("sqlite::memory:").unwrap();
-        let storage = Arc::new(Storage::local("./test_data".to_string()).unwrap());
+        let storage = Arc::new(Storage::in_memory());
         let stream_writer = Arc::new(Mutex::new(StreamWriter::new()));
         let _factory = StreamFactory::new(pool, storage, stream_writer);
     }
@@ -216,7 +244,7 @@ mod tests {
     #[tokio::test]
     async fn test_factory_clone() {
         let pool = SqlitePool::connect_lazy("sqlite::memory:").unwrap();
-        let storage = Arc::new(Storage::local("./test_data".to_string()).unwrap());
+        let storage = Arc::new(Storage::in_memory());
         let stream_writer = Arc::new(Mutex::new(StreamWriter::new()));
         let factory = StreamFactory::new(pool, storage, stream_writer);
         let _factory2 = factory.clone();
@@ -225,7 +253,7 @@ mod tests {
     #[tokio::test]
     async fn test_create_auth_device() {
         let pool = SqlitePool::connect_lazy("sqlite::memory:").unwrap();
-        let storage = Arc::new(Storage::local("./test_data".to_string()).unwrap());
+        let storage = Arc::new(Storage::in_memory());
         let stream_writer = Arc::new(Mutex::new(StreamWriter::new()));
         let factory = StreamFactory::new(pool, storage, stream_writer);
 
@@ -240,7 +268,7 @@ mod tests {
     #[tokio::test]
     async fn test_create_auth_unknown_source() {
         let pool = SqlitePool::connect_lazy("sqlite::memory:").unwrap();
-        let storage = Arc::new(Storage::local("./test_data".to_string()).unwrap());
+        let storage = Arc::new(Storage::in_memory());
         let stream_writer = Arc::new(Mutex::new(StreamWriter::new()));
         let factory = StreamFactory::new(pool, storage, stream_writer);
 
@@ -258,7 +286,7 @@ mod tests {
     #[tokio::test]
     async fn test_create_stream_typed_impl_google_calendar() {
         let pool = SqlitePool::connect_lazy("sqlite::memory:").unwrap();
-        let storage = Arc::new(Storage::local("./test_data".to_string()).unwrap());
+        let storage = Arc::new(Storage::in_memory());
         let stream_writer = Arc::new(Mutex::new(StreamWriter::new()));
         let factory = StreamFactory::new(pool.clone(), storage, stream_writer.clone());
 
@@ -281,7 +309,7 @@ mod tests {
     #[tokio::test]
     async fn test_create_stream_typed_impl_unknown_stream() {
         let pool = SqlitePool::connect_lazy("sqlite::memory:").unwrap();
-        let storage = Arc::new(Storage::local("./test_data".to_string()).unwrap());
+        let storage = Arc::new(Storage::in_memory());
         let stream_writer = Arc::new(Mutex::new(StreamWriter::new()));
         let factory = StreamFactory::new(pool.clone(), storage, stream_writer.clone());
 
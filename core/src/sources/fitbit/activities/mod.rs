@@ -0,0 +1,253 @@
+//! Fitbit activities stream implementation
+
+pub mod transform;
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use super::client::FitbitClient;
+use super::dates::{self, DATE_FORMAT};
+use super::types::ActivityListResponse;
+use crate::{
+    error::Result,
+    sources::{
+        auth::SourceAuth,
+        base::{SourceClient, StreamLimits, SyncMode, SyncResult},
+        pull_stream::PullStream,
+    },
+    storage::stream_writer::StreamWriter,
+};
+
+/// Maximum page size accepted by the activity log list endpoint
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// Fitbit activities stream
+///
+/// Syncs logged and auto-detected exercise sessions from the Fitbit API to
+/// object storage via StreamWriter.
+pub struct FitbitActivitiesStream {
+    source_id: String,
+    client: FitbitClient,
+    db: SqlitePool,
+    stream_writer: Arc<Mutex<StreamWriter>>,
+}
+
+impl FitbitActivitiesStream {
+    /// Create a new activities stream with SourceAuth and StreamWriter
+    pub fn new(
+        source_id: String,
+        db: SqlitePool,
+        stream_writer: Arc<Mutex<StreamWriter>>,
+        auth: SourceAuth,
+    ) -> Self {
+        // Extract token manager from auth
+        let token_manager = auth
+            .token_manager()
+            .expect("FitbitActivitiesStream requires OAuth2 auth")
+            .clone();
+
+        let client =
+            FitbitClient::new(source_id.clone(), token_manager).with_network(auth.network());
+
+        Self {
+            source_id,
+            client,
+            db,
+            stream_writer,
+        }
+    }
+
+    /// Sync activities with explicit sync mode
+    #[tracing::instrument(skip(self), fields(source_id = %self.source_id, mode = ?sync_mode))]
+    pub async fn sync_with_mode(&self, sync_mode: &SyncMode) -> Result<SyncResult> {
+        tracing::info!("Starting Fitbit activities sync");
+        self.sync_internal(sync_mode).await
+    }
+
+    /// Internal sync implementation
+    async fn sync_internal(&self, sync_mode: &SyncMode) -> Result<SyncResult> {
+        let started_at = Utc::now();
+        let mut records_fetched = 0;
+        let mut records_written = 0;
+        let mut records_failed = 0;
+        let mut next_cursor = None;
+        let mut earliest_record_at: Option<DateTime<Utc>> = None;
+        let mut latest_record_at: Option<DateTime<Utc>> = None;
+
+        let tz = self.client.user_timezone().await?;
+        let today = Utc::now().with_timezone(&tz).date_naive();
+        let stored_cursor = dates::load_cursor(&self.db, &self.source_id, "activities").await?;
+        let days_back = dates::load_days_back(&self.db, &self.source_id, "activities").await?;
+        let (start, end) =
+            dates::date_window(sync_mode, stored_cursor.as_deref(), today, days_back, tz);
+        let limits = StreamLimits::load(&self.db, &self.source_id, "activities").await?;
+        let page_size = limits.page_size_or(MAX_PAGE_SIZE).min(MAX_PAGE_SIZE);
+        let limit = page_size.to_string();
+
+        // The list endpoint pages forward by `afterDate`, which also accepts a
+        // local timestamp: each page continues after the last activity seen
+        let mut after = start.format(DATE_FORMAT).to_string();
+        let mut synced_through: Option<NaiveDate> = None;
+
+        'pages: loop {
+            let params = [
+                ("afterDate", after.as_str()),
+                ("sort", "asc"),
+                ("offset", "0"),
+                ("limit", limit.as_str()),
+            ];
+            let response: ActivityListResponse = self
+                .client
+                .get_with_params("1/user/-/activities/list.json", &params)
+                .await?;
+
+            let page_len = response.activities.len();
+            tracing::debug!(after = %after, count = page_len, "Fetched Fitbit activities page");
+
+            let mut last_local = None;
+
+            for activity in &response.activities {
+                let Ok(start_at) = DateTime::parse_from_rfc3339(&activity.start_time) else {
+                    tracing::warn!(
+                        log_id = activity.log_id,
+                        "Skipping Fitbit activity with unparseable start time"
+                    );
+                    records_failed += 1;
+                    continue;
+                };
+                let local = start_at.with_timezone(&tz).naive_local();
+                if local.date() > end {
+                    break 'pages;
+                }
+
+                records_fetched += 1;
+                let start_time = start_at.with_timezone(&Utc);
+
+                earliest_record_at =
+                    Some(earliest_record_at.map_or(start_time, |t| t.min(start_time)));
+                latest_record_at = Some(latest_record_at.map_or(start_time, |t| t.max(start_time)));
+
+                let record = serde_json::json!({
+                    "log_id": activity.log_id,
+                    "activity_name": activity.activity_name,
+                    "activity_type_id": activity.activity_type_id,
+                    "start_time": start_time,
+                    "duration_ms": activity.duration,
+                    "active_duration_ms": activity.active_duration,
+                    "calories": activity.calories,
+                    "distance": activity.distance,
+                    "distance_unit": activity.distance_unit,
+                    "average_heart_rate": activity.average_heart_rate,
+                    "steps": activity.steps,
+                    "elevation_gain": activity.elevation_gain,
+                    "log_type": activity.log_type,
+                    "timezone": tz.name(),
+                    "synced_at": Utc::now(),
+                });
+
+                let written = {
+                    let mut writer = self.stream_writer.lock().await;
+                    writer.write_record(&self.source_id, "activities", record, Some(start_time))
+                };
+                match written {
+                    Ok(_) => records_written += 1,
+                    Err(e) => {
+                        tracing::warn!(
+                            log_id = activity.log_id,
+                            error = %e,
+                            "Failed to write Fitbit activity"
+                        );
+                        records_failed += 1;
+                    }
+                }
+
+                synced_through = Some(local.date());
+                last_local = Some(local);
+            }
+
+            if page_len < page_size as usize || limits.is_reached(records_fetched) {
+                break;
+            }
+
+            match last_local {
+                Some(local) => after = local.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                None => break,
+            }
+        }
+
+        // Backfills cover an arbitrary past range and must not move the cursor
+        if !matches!(sync_mode, SyncMode::Backfill { .. }) {
+            if let Some(date) = synced_through {
+                next_cursor =
+                    Some(dates::save_cursor(&self.db, &self.source_id, "activities", date).await?);
+            }
+        }
+
+        let completed_at = Utc::now();
+
+        // Collect records from StreamWriter for archive and transform pipeline
+        let records = {
+            let mut writer = self.stream_writer.lock().await;
+            writer
+                .collect_records(&self.source_id, "activities")?
+                .map(|(records, _, _)| records)
+        };
+
+        tracing::info!(
+            records_fetched,
+            records_written,
+            records_failed,
+            "Fitbit activities sync completed"
+        );
+
+        Ok(SyncResult {
+            records_fetched,
+            records_written,
+            records_failed,
+            next_cursor,
+            earliest_record_at,
+            latest_record_at,
+            started_at,
+            completed_at,
+            records,
+            archive_job_id: None,
+            records_deduplicated: 0,
+        })
+    }
+}
+
+// Implement PullStream trait for FitbitActivitiesStream
+#[async_trait]
+impl PullStream for FitbitActivitiesStream {
+    async fn sync_pull(&self, mode: SyncMode) -> Result<SyncResult> {
+        self.sync_with_mode(&mode).await
+    }
+
+    async fn load_config(&mut self, _db: &SqlitePool, _source_id: &str) -> Result<()> {
+        // days_back and limits are read at sync time
+        Ok(())
+    }
+
+    fn table_name(&self) -> &str {
+        "stream_fitbit_activities"
+    }
+
+    fn stream_name(&self) -> &str {
+        "activities"
+    }
+
+    fn source_name(&self) -> &str {
+        "fitbit"
+    }
+
+    fn supports_incremental(&self) -> bool {
+        true
+    }
+
+    fn supports_full_refresh(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,343 @@
+//! Fitbit activities to health_workout ontology transformation
+//!
+//! Transforms raw Fitbit activity logs from stream_fitbit_activities into the
+//! normalized health_workout ontology table.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::error::Result;
+use crate::jobs::TransformContext;
+use crate::sources::base::{OntologyTransform, TransformRegistration, TransformResult};
+
+/// Batch size for bulk inserts
+const BATCH_SIZE: usize = 500;
+
+/// Kilometers per mile, for logs recorded in imperial units
+const KM_PER_MILE: f64 = 1.609344;
+
+/// Row for data_health_workout
+type WorkoutRow = (
+    String,            // id (deterministic)
+    String,            // workout_type
+    Option<i32>,       // duration_minutes
+    Option<i32>,       // calories_burned
+    Option<i32>,       // avg_heart_rate
+    Option<f64>,       // distance_km
+    DateTime<Utc>,     // start_time
+    DateTime<Utc>,     // end_time
+    String,            // stream_id
+    serde_json::Value, // metadata
+);
+
+/// Transform Fitbit activities to health_workout ontology
+pub struct FitbitWorkoutTransform;
+
+#[async_trait]
+impl OntologyTransform for FitbitWorkoutTransform {
+    fn source_table(&self) -> &str {
+        "stream_fitbit_activities"
+    }
+
+    fn target_table(&self) -> &str {
+        "health_workout"
+    }
+
+    fn domain(&self) -> &str {
+        "health"
+    }
+
+    #[tracing::instrument(skip(self, db, context), fields(source_table = %self.source_table(), target_table = %self.target_table()))]
+    async fn transform(
+        &self,
+        db: &Database,
+        context: &TransformContext,
+        source_id: String,
+    ) -> Result<TransformResult> {
+        let mut records_read = 0;
+        let mut records_written = 0;
+        let mut records_failed = 0;
+        let mut last_processed_id: Option<String> = None;
+
+        tracing::info!(
+            source_id = %source_id,
+            "Starting Fitbit activities to health_workout transformation"
+        );
+
+        // Read stream data from data source using checkpoint
+        let checkpoint_key = "fitbit_activities_to_health_workout";
+        let data_source = context.get_data_source().ok_or_else(|| {
+            crate::Error::Other("No data source available for transform".to_string())
+        })?;
+        let batches = data_source
+            .read_with_checkpoint(&source_id, "activities", checkpoint_key)
+            .await?;
+
+        let mut pending_records: Vec<WorkoutRow> = Vec::new();
+
+        for batch in batches {
+            for record in &batch.records {
+                records_read += 1;
+
+                let Some(row) = workout_row(record, &source_id) else {
+                    records_failed += 1;
+                    continue;
+                };
+                last_processed_id = Some(row.8.clone());
+                pending_records.push(row);
+
+                if pending_records.len() >= BATCH_SIZE {
+                    match execute_workout_batch_insert(db, &source_id, &pending_records).await {
+                        Ok(written) => records_written += written,
+                        Err(e) => {
+                            tracing::warn!(
+                                error = %e,
+                                batch_size = pending_records.len(),
+                                "Batch insert failed"
+                            );
+                            records_failed += pending_records.len();
+                        }
+                    }
+                    pending_records.clear();
+                }
+            }
+
+            // Update checkpoint after processing batch
+            if let Some(max_ts) = batch.max_timestamp {
+                data_source
+                    .update_checkpoint(&source_id, "activities", checkpoint_key, max_ts)
+                    .await?;
+            }
+        }
+
+        // Insert any remaining records
+        if !pending_records.is_empty() {
+            match execute_workout_batch_insert(db, &source_id, &pending_records).await {
+                Ok(written) => records_written += written,
+                Err(e) => {
+                    tracing::warn!(
+                        error = %e,
+                        batch_size = pending_records.len(),
+                        "Final batch insert failed"
+                    );
+                    records_failed += pending_records.len();
+                }
+            }
+        }
+
+        tracing::info!(
+            source_id = %source_id,
+            records_read,
+            records_written,
+            records_failed,
+            "Fitbit activities to health_workout transformation completed"
+        );
+
+        Ok(TransformResult {
+            records_read,
+            records_written,
+            records_failed,
+            last_processed_id,
+            chained_transforms: vec![],
+        })
+    }
+}
+
+/// Map a stream record to a health_workout row
+fn workout_row(record: &serde_json::Value, source_id: &str) -> Option<WorkoutRow> {
+    let log_id = record.get("log_id").and_then(|v| v.as_i64())?;
+    let start_time = record
+        .get("start_time")
+        .and_then(|v| v.as_str())
+        .and_then(|s| s.parse::<DateTime<Utc>>().ok())?;
+
+    let stream_id = record
+        .get("id")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    // activity_name -> workout_type (e.g. "Walk", "Run", "Outdoor Bike")
+    let workout_type = record
+        .get("activity_name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Unknown")
+        .to_string();
+
+    // duration (ms) -> duration_minutes and end_time
+    let duration_ms = record
+        .get("duration_ms")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(0);
+    let duration_minutes = Some((duration_ms as f64 / 60_000.0).round() as i32);
+    let end_time = start_time + Duration::milliseconds(duration_ms);
+
+    let calories_burned = record
+        .get("calories")
+        .and_then(|v| v.as_f64())
+        .map(|c| c.round() as i32);
+
+    let avg_heart_rate = record
+        .get("average_heart_rate")
+        .and_then(|v| v.as_f64())
+        .map(|hr| hr.round() as i32);
+
+    // distance -> distance_km, honoring the log's unit
+    let distance_km = record.get("distance").and_then(|v| v.as_f64()).map(|d| {
+        match record.get("distance_unit").and_then(|v| v.as_str()) {
+            Some("Mile") => d * KM_PER_MILE,
+            _ => d,
+        }
+    });
+
+    let metadata = serde_json::json!({
+        "fitbit_log_id": log_id,
+        "activity_type_id": record.get("activity_type_id"),
+        "active_duration_ms": record.get("active_duration_ms"),
+        "steps": record.get("steps"),
+        "elevation_gain": record.get("elevation_gain"),
+        "log_type": record.get("log_type"),
+        "timezone": record.get("timezone"),
+        "source_connection_id": source_id,
+    });
+
+    // Generate deterministic ID for idempotency
+    let id = crate::ids::generate_id("health_workout", &[source_id, &log_id.to_string()]);
+
+    Some((
+        id,
+        workout_type,
+        duration_minutes,
+        calories_burned,
+        avg_heart_rate,
+        distance_km,
+        start_time,
+        end_time,
+        stream_id,
+        metadata,
+    ))
+}
+
+/// Execute batch insert for workout records from Fitbit
+///
+/// Builds and executes a multi-row INSERT statement for efficient bulk insertion.
+async fn execute_workout_batch_insert(
+    db: &Database,
+    source_connection_id: &str,
+    records: &[WorkoutRow],
+) -> Result<usize> {
+    if records.is_empty() {
+        return Ok(0);
+    }
+
+    let query_str = Database::build_batch_insert_query(
+        "data_health_workout",
+        &[
+            "id",
+            "workout_type",
+            "duration_minutes",
+            "calories_burned",
+            "avg_heart_rate",
+            "distance_km",
+            "start_time",
+            "end_time",
+            "source_stream_id",
+            "source_connection_id",
+            "source_table",
+            "source_provider",
+            "metadata",
+        ],
+        "id",
+        records.len(),
+    );
+
+    let mut query = sqlx::query(&query_str);
+
+    // Bind all parameters row by row
+    for (
+        id,
+        workout_type,
+        duration_minutes,
+        calories_burned,
+        avg_heart_rate,
+        distance_km,
+        start_time,
+        end_time,
+        stream_id,
+        metadata,
+    ) in records
+    {
+        query = query
+            .bind(id)
+            .bind(workout_type)
+            .bind(duration_minutes)
+            .bind(calories_burned)
+            .bind(avg_heart_rate)
+            .bind(distance_km)
+            .bind(start_time)
+            .bind(end_time)
+            .bind(stream_id)
+            .bind(source_connection_id)
+            .bind("stream_fitbit_activities")
+            .bind("fitbit")
+            .bind(metadata);
+    }
+
+    let result = query.execute(db.pool()).await?;
+    Ok(result.rows_affected() as usize)
+}
+
+// Self-registration
+struct FitbitWorkoutTransformRegistration;
+
+impl TransformRegistration for FitbitWorkoutTransformRegistration {
+    fn source_table(&self) -> &'static str {
+        "stream_fitbit_activities"
+    }
+    fn target_table(&self) -> &'static str {
+        "health_workout"
+    }
+    fn create(&self, _context: &TransformContext) -> Result<Box<dyn OntologyTransform>> {
+        Ok(Box::new(FitbitWorkoutTransform))
+    }
+}
+
+inventory::submit! {
+    &FitbitWorkoutTransformRegistration as &dyn TransformRegistration
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transform_metadata() {
+        let transform = FitbitWorkoutTransform;
+        assert_eq!(transform.source_table(), "stream_fitbit_activities");
+        assert_eq!(transform.target_table(), "health_workout");
+        assert_eq!(transform.domain(), "health");
+    }
+
+    #[test]
+    fn test_workout_row_mapping() {
+        let record = serde_json::json!({
+            "log_id": 7,
+            "activity_name": "Run",
+            "start_time": "2024-01-02T15:00:00Z",
+            "duration_ms": 1_800_000,
+            "calories": 312.4,
+            "distance": 3.1,
+            "distance_unit": "Mile",
+        });
+
+        let (_, workout_type, duration, calories, _, distance_km, start, end, _, _) =
+            workout_row(&record, "source-1").unwrap();
+        assert_eq!(workout_type, "Run");
+        assert_eq!(duration, Some(30));
+        assert_eq!(calories, Some(312));
+        assert!((distance_km.unwrap() - 4.989).abs() < 0.001);
+        assert_eq!(end - start, Duration::minutes(30));
+    }
+}
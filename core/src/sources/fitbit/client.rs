@@ -0,0 +1,67 @@
+//! Fitbit API client - thin wrapper over SourceHttpClient
+//!
+//! This client delegates all HTTP operations to the base SourceHttpClient,
+//! providing Fitbit-specific configuration.
+
+use chrono_tz::Tz;
+use std::sync::Arc;
+
+use super::types::ProfileResponse;
+use crate::{
+    error::Result,
+    sources::base::{user_agent_for, RetryConfig, SourceClient, SourceHttpClient, TokenManager},
+};
+
+/// Fitbit API client with automatic token refresh and retry logic
+///
+/// This is a thin wrapper that configures SourceHttpClient for Fitbit APIs.
+/// All HTTP logic (retry, token refresh, error handling) is delegated to the base client.
+pub struct FitbitClient {
+    http: SourceHttpClient,
+}
+
+impl FitbitClient {
+    /// Create a new Fitbit API client
+    pub fn new(source_id: String, token_manager: Arc<TokenManager>) -> Self {
+        Self {
+            http: SourceHttpClient::oauth(source_id, token_manager)
+                .with_base_url("https://api.fitbit.com")
+                .with_user_agent(&user_agent_for("fitbit"))
+                .with_retry_config(RetryConfig::default()),
+        }
+    }
+
+    /// The user's profile time zone, used to interpret Fitbit's local times
+    ///
+    /// Falls back to UTC when the profile has no (or an unknown) time zone.
+    pub async fn user_timezone(&self) -> Result<Tz> {
+        let profile: ProfileResponse = self.get("1/user/-/profile.json").await?;
+        Ok(profile
+            .user
+            .timezone
+            .and_then(|tz| tz.parse::<Tz>().ok())
+            .unwrap_or(Tz::UTC))
+    }
+}
+
+impl SourceClient for FitbitClient {
+    fn http(&self) -> &SourceHttpClient {
+        &self.http
+    }
+
+    fn http_mut(&mut self) -> &mut SourceHttpClient {
+        &mut self.http
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_client_creation() {
+        let pool = sqlx::SqlitePool::connect_lazy("sqlite::memory:").unwrap();
+        let token_manager = Arc::new(TokenManager::new_insecure(pool));
+        let _client = FitbitClient::new("test-source".to_string(), token_manager);
+    }
+}
@@ -0,0 +1,184 @@
+//! Date-based sync cursors shared by the Fitbit streams
+//!
+//! Fitbit's APIs are keyed by local calendar date, so each stream stores the
+//! last date it fully synced (`YYYY-MM-DD`) in `last_sync_token` and the next
+//! incremental run starts from that date. The cursor day itself is fetched
+//! again because it may have been incomplete at the time; deterministic
+//! ontology IDs make the overlap idempotent. A run that stops at the record
+//! cap saves the last date it finished, so the cursor doubles as a resume
+//! point.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use sqlx::SqlitePool;
+
+use crate::{error::Result, sources::base::SyncMode};
+
+/// Days fetched on a full refresh or first sync when the stream config sets no `days_back`
+pub const DEFAULT_DAYS_BACK: i64 = 30;
+
+/// Format of the date cursor and of Fitbit's date path segments
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Inclusive range of local dates a run should cover
+///
+/// - Incremental: from the cursor (explicit or stored), else `days_back` ago
+/// - Full refresh: the last `days_back` days
+/// - Backfill: the requested range, in the user's time zone
+pub fn date_window(
+    mode: &SyncMode,
+    stored_cursor: Option<&str>,
+    today: NaiveDate,
+    days_back: i64,
+    tz: Tz,
+) -> (NaiveDate, NaiveDate) {
+    let default_start = today - Duration::days(days_back);
+
+    let (start, end) = match mode {
+        SyncMode::Incremental { cursor } => {
+            let start = cursor
+                .as_deref()
+                .or(stored_cursor)
+                .and_then(|c| NaiveDate::parse_from_str(c, DATE_FORMAT).ok())
+                .unwrap_or(default_start);
+            (start, today)
+        }
+        SyncMode::FullRefresh => (default_start, today),
+        SyncMode::Backfill {
+            start_date,
+            end_date,
+        } => (
+            start_date.with_timezone(&tz).date_naive(),
+            end_date.with_timezone(&tz).date_naive().min(today),
+        ),
+    };
+
+    (start.min(end), end)
+}
+
+/// Parse a Fitbit local timestamp (`2024-01-01T23:10:30.000` or without millis)
+pub fn parse_local_datetime(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f").ok()
+}
+
+/// Convert a local time in the user's time zone to UTC
+///
+/// Ambiguous times (DST fall-back) resolve to the earlier instant; times that
+/// don't exist (DST spring-forward gap) are read as UTC.
+pub fn local_to_utc(tz: Tz, local: NaiveDateTime) -> DateTime<Utc> {
+    tz.from_local_datetime(&local)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&local))
+}
+
+/// Load `days_back` from the stream connection config
+pub async fn load_days_back(db: &SqlitePool, source_id: &str, stream_name: &str) -> Result<i64> {
+    let config = sqlx::query_scalar::<_, serde_json::Value>(
+        "SELECT config FROM elt_stream_connections WHERE source_connection_id = $1 AND stream_name = $2",
+    )
+    .bind(source_id)
+    .bind(stream_name)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(config
+        .and_then(|c| c.get("days_back").and_then(|v| v.as_i64()))
+        .filter(|days| *days > 0)
+        .unwrap_or(DEFAULT_DAYS_BACK))
+}
+
+/// Get the stored date cursor for a stream
+pub async fn load_cursor(
+    db: &SqlitePool,
+    source_id: &str,
+    stream_name: &str,
+) -> Result<Option<String>> {
+    let row = sqlx::query_as::<_, (Option<String>,)>(
+        "SELECT last_sync_token FROM elt_stream_connections WHERE source_connection_id = $1 AND stream_name = $2",
+    )
+    .bind(source_id)
+    .bind(stream_name)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row.and_then(|(token,)| token))
+}
+
+/// Save the date cursor for a stream, returning it as the sync's next cursor
+pub async fn save_cursor(
+    db: &SqlitePool,
+    source_id: &str,
+    stream_name: &str,
+    synced_through: NaiveDate,
+) -> Result<String> {
+    let cursor = synced_through.format(DATE_FORMAT).to_string();
+
+    sqlx::query(
+        "UPDATE elt_stream_connections SET last_sync_token = $1, last_sync_at = $2 WHERE source_connection_id = $3 AND stream_name = $4",
+    )
+    .bind(&cursor)
+    .bind(Utc::now())
+    .bind(source_id)
+    .bind(stream_name)
+    .execute(db)
+    .await?;
+
+    Ok(cursor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, DATE_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn test_incremental_window_resumes_from_cursor() {
+        let today = date("2024-03-10");
+        let mode = SyncMode::incremental(None);
+
+        assert_eq!(
+            date_window(&mode, Some("2024-03-08"), today, 30, Tz::UTC),
+            (date("2024-03-08"), today)
+        );
+
+        // No cursor yet: fall back to days_back
+        assert_eq!(
+            date_window(&mode, None, today, 30, Tz::UTC),
+            (date("2024-02-09"), today)
+        );
+
+        // An explicit cursor wins over the stored one
+        let mode = SyncMode::incremental(Some("2024-03-01".to_string()));
+        assert_eq!(
+            date_window(&mode, Some("2024-03-08"), today, 30, Tz::UTC).0,
+            date("2024-03-01")
+        );
+    }
+
+    #[test]
+    fn test_full_refresh_ignores_cursor() {
+        let today = date("2024-03-10");
+        let mode = SyncMode::FullRefresh;
+        assert_eq!(
+            date_window(&mode, Some("2024-03-08"), today, 7, Tz::UTC),
+            (date("2024-03-03"), today)
+        );
+    }
+
+    #[test]
+    fn test_local_to_utc() {
+        let tz: Tz = "America/Los_Angeles".parse().unwrap();
+        let local = parse_local_datetime("2024-01-01T23:10:30.000").unwrap();
+        assert_eq!(
+            local_to_utc(tz, local).to_rfc3339(),
+            "2024-01-02T07:10:30+00:00"
+        );
+
+        assert!(parse_local_datetime("2024-01-01T23:10:30").is_some());
+        assert!(parse_local_datetime("not a time").is_none());
+    }
+}
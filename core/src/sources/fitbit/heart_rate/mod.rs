@@ -0,0 +1,218 @@
+//! Fitbit heart rate stream implementation
+
+pub mod transform;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use super::client::FitbitClient;
+use super::dates::{self, DATE_FORMAT};
+use super::types::HeartRateResponse;
+use crate::{
+    error::Result,
+    sources::{
+        auth::SourceAuth,
+        base::{SourceClient, StreamLimits, SyncMode, SyncResult},
+        pull_stream::PullStream,
+    },
+    storage::stream_writer::StreamWriter,
+};
+
+/// Fitbit heart rate stream
+///
+/// Syncs minute-level intraday heart rate from the Fitbit API to object
+/// storage via StreamWriter, one record per sample. Intraday data is only
+/// available one day per request.
+pub struct FitbitHeartRateStream {
+    source_id: String,
+    client: FitbitClient,
+    db: SqlitePool,
+    stream_writer: Arc<Mutex<StreamWriter>>,
+}
+
+impl FitbitHeartRateStream {
+    /// Create a new heart rate stream with SourceAuth and StreamWriter
+    pub fn new(
+        source_id: String,
+        db: SqlitePool,
+        stream_writer: Arc<Mutex<StreamWriter>>,
+        auth: SourceAuth,
+    ) -> Self {
+        // Extract token manager from auth
+        let token_manager = auth
+            .token_manager()
+            .expect("FitbitHeartRateStream requires OAuth2 auth")
+            .clone();
+
+        let client =
+            FitbitClient::new(source_id.clone(), token_manager).with_network(auth.network());
+
+        Self {
+            source_id,
+            client,
+            db,
+            stream_writer,
+        }
+    }
+
+    /// Sync heart rate samples with explicit sync mode
+    #[tracing::instrument(skip(self), fields(source_id = %self.source_id, mode = ?sync_mode))]
+    pub async fn sync_with_mode(&self, sync_mode: &SyncMode) -> Result<SyncResult> {
+        tracing::info!("Starting Fitbit heart rate sync");
+        self.sync_internal(sync_mode).await
+    }
+
+    /// Internal sync implementation
+    async fn sync_internal(&self, sync_mode: &SyncMode) -> Result<SyncResult> {
+        let started_at = Utc::now();
+        let mut records_fetched = 0;
+        let mut records_written = 0;
+        let mut records_failed = 0;
+        let mut next_cursor = None;
+        let mut earliest_record_at: Option<DateTime<Utc>> = None;
+        let mut latest_record_at: Option<DateTime<Utc>> = None;
+
+        let tz = self.client.user_timezone().await?;
+        let today = Utc::now().with_timezone(&tz).date_naive();
+        let stored_cursor = dates::load_cursor(&self.db, &self.source_id, "heart_rate").await?;
+        let days_back = dates::load_days_back(&self.db, &self.source_id, "heart_rate").await?;
+        let (start, end) =
+            dates::date_window(sync_mode, stored_cursor.as_deref(), today, days_back, tz);
+        let limits = StreamLimits::load(&self.db, &self.source_id, "heart_rate").await?;
+
+        let mut day = start;
+        let mut synced_through = None;
+
+        while day <= end {
+            let date = day.format(DATE_FORMAT).to_string();
+            let path = format!("1/user/-/activities/heart/date/{}/1d/1min.json", date);
+            let response: HeartRateResponse = self.client.get(&path).await?;
+
+            let resting_heart_rate = response
+                .days
+                .first()
+                .and_then(|d| d.value.resting_heart_rate);
+            let samples = response.intraday.map(|i| i.dataset).unwrap_or_default();
+
+            records_fetched += samples.len();
+
+            tracing::debug!(date = %date, count = samples.len(), "Fetched Fitbit heart rate day");
+
+            for sample in &samples {
+                let Ok(time) = NaiveTime::parse_from_str(&sample.time, "%H:%M:%S") else {
+                    records_failed += 1;
+                    continue;
+                };
+                let timestamp = dates::local_to_utc(tz, day.and_time(time));
+
+                earliest_record_at =
+                    Some(earliest_record_at.map_or(timestamp, |t| t.min(timestamp)));
+                latest_record_at = Some(latest_record_at.map_or(timestamp, |t| t.max(timestamp)));
+
+                let record = serde_json::json!({
+                    "timestamp": timestamp,
+                    "bpm": sample.value,
+                    "date": date,
+                    "resting_heart_rate": resting_heart_rate,
+                    "timezone": tz.name(),
+                    "synced_at": Utc::now(),
+                });
+
+                let written = {
+                    let mut writer = self.stream_writer.lock().await;
+                    writer.write_record(&self.source_id, "heart_rate", record, Some(timestamp))
+                };
+                match written {
+                    Ok(_) => records_written += 1,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Failed to write Fitbit heart rate sample");
+                        records_failed += 1;
+                    }
+                }
+            }
+
+            synced_through = Some(day);
+
+            if limits.is_reached(records_fetched) {
+                break;
+            }
+
+            day += Duration::days(1);
+        }
+
+        // Backfills cover an arbitrary past range and must not move the cursor
+        if !matches!(sync_mode, SyncMode::Backfill { .. }) {
+            if let Some(date) = synced_through {
+                next_cursor =
+                    Some(dates::save_cursor(&self.db, &self.source_id, "heart_rate", date).await?);
+            }
+        }
+
+        let completed_at = Utc::now();
+
+        // Collect records from StreamWriter for archive and transform pipeline
+        let records = {
+            let mut writer = self.stream_writer.lock().await;
+            writer
+                .collect_records(&self.source_id, "heart_rate")?
+                .map(|(records, _, _)| records)
+        };
+
+        tracing::info!(
+            records_fetched,
+            records_written,
+            records_failed,
+            "Fitbit heart rate sync completed"
+        );
+
+        Ok(SyncResult {
+            records_fetched,
+            records_written,
+            records_failed,
+            next_cursor,
+            earliest_record_at,
+            latest_record_at,
+            started_at,
+            completed_at,
+            records,
+            archive_job_id: None,
+            records_deduplicated: 0,
+        })
+    }
+}
+
+// Implement PullStream trait for FitbitHeartRateStream
+#[async_trait]
+impl PullStream for FitbitHeartRateStream {
+    async fn sync_pull(&self, mode: SyncMode) -> Result<SyncResult> {
+        self.sync_with_mode(&mode).await
+    }
+
+    async fn load_config(&mut self, _db: &SqlitePool, _source_id: &str) -> Result<()> {
+        // days_back and limits are read at sync time
+        Ok(())
+    }
+
+    fn table_name(&self) -> &str {
+        "stream_fitbit_heart_rate"
+    }
+
+    fn stream_name(&self) -> &str {
+        "heart_rate"
+    }
+
+    fn source_name(&self) -> &str {
+        "fitbit"
+    }
+
+    fn supports_incremental(&self) -> bool {
+        true
+    }
+
+    fn supports_full_refresh(&self) -> bool {
+        true
+    }
+}
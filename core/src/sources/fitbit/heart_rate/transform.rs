@@ -0,0 +1,257 @@
+//! Fitbit heart rate to health_heart_rate ontology transformation
+//!
+//! Transforms raw Fitbit heart rate samples from stream_fitbit_heart_rate into
+//! the normalized health_heart_rate ontology table.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::error::Result;
+use crate::jobs::TransformContext;
+use crate::sources::base::{OntologyTransform, TransformRegistration, TransformResult};
+
+/// Batch size for bulk inserts
+const BATCH_SIZE: usize = 500;
+
+/// Row for data_health_heart_rate
+type HeartRateRow = (
+    String,            // id (deterministic)
+    i32,               // bpm
+    DateTime<Utc>,     // timestamp
+    String,            // stream_id
+    serde_json::Value, // metadata
+);
+
+/// Transform Fitbit heart rate samples to health_heart_rate ontology
+pub struct FitbitHeartRateTransform;
+
+#[async_trait]
+impl OntologyTransform for FitbitHeartRateTransform {
+    fn source_table(&self) -> &str {
+        "stream_fitbit_heart_rate"
+    }
+
+    fn target_table(&self) -> &str {
+        "health_heart_rate"
+    }
+
+    fn domain(&self) -> &str {
+        "health"
+    }
+
+    #[tracing::instrument(skip(self, db, context), fields(source_table = %self.source_table(), target_table = %self.target_table()))]
+    async fn transform(
+        &self,
+        db: &Database,
+        context: &TransformContext,
+        source_id: String,
+    ) -> Result<TransformResult> {
+        let mut records_read = 0;
+        let mut records_written = 0;
+        let mut records_failed = 0;
+        let mut last_processed_id: Option<String> = None;
+
+        tracing::info!(
+            source_id = %source_id,
+            "Starting Fitbit heart rate to health_heart_rate transformation"
+        );
+
+        // Read stream data from data source using checkpoint
+        let checkpoint_key = "fitbit_heart_rate_to_health_heart_rate";
+        let data_source = context.get_data_source().ok_or_else(|| {
+            crate::Error::Other("No data source available for transform".to_string())
+        })?;
+        let batches = data_source
+            .read_with_checkpoint(&source_id, "heart_rate", checkpoint_key)
+            .await?;
+
+        let mut pending_records: Vec<HeartRateRow> = Vec::new();
+
+        for batch in batches {
+            for record in &batch.records {
+                records_read += 1;
+
+                let Some(row) = heart_rate_row(record, &source_id) else {
+                    records_failed += 1;
+                    continue;
+                };
+                last_processed_id = Some(row.3.clone());
+                pending_records.push(row);
+
+                if pending_records.len() >= BATCH_SIZE {
+                    match execute_heart_rate_batch_insert(db, &source_id, &pending_records).await {
+                        Ok(written) => records_written += written,
+                        Err(e) => {
+                            tracing::warn!(
+                                error = %e,
+                                batch_size = pending_records.len(),
+                                "Batch insert failed"
+                            );
+                            records_failed += pending_records.len();
+                        }
+                    }
+                    pending_records.clear();
+                }
+            }
+
+            // Update checkpoint after processing batch
+            if let Some(max_ts) = batch.max_timestamp {
+                data_source
+                    .update_checkpoint(&source_id, "heart_rate", checkpoint_key, max_ts)
+                    .await?;
+            }
+        }
+
+        // Insert any remaining records
+        if !pending_records.is_empty() {
+            match execute_heart_rate_batch_insert(db, &source_id, &pending_records).await {
+                Ok(written) => records_written += written,
+                Err(e) => {
+                    tracing::warn!(
+                        error = %e,
+                        batch_size = pending_records.len(),
+                        "Final batch insert failed"
+                    );
+                    records_failed += pending_records.len();
+                }
+            }
+        }
+
+        tracing::info!(
+            source_id = %source_id,
+            records_read,
+            records_written,
+            records_failed,
+            "Fitbit heart rate to health_heart_rate transformation completed"
+        );
+
+        Ok(TransformResult {
+            records_read,
+            records_written,
+            records_failed,
+            last_processed_id,
+            chained_transforms: vec![],
+        })
+    }
+}
+
+/// Map a stream record to a health_heart_rate row
+fn heart_rate_row(record: &serde_json::Value, source_id: &str) -> Option<HeartRateRow> {
+    let bpm = record.get("bpm").and_then(|v| v.as_f64())?;
+    let timestamp_str = record.get("timestamp").and_then(|v| v.as_str())?;
+    let timestamp = timestamp_str.parse::<DateTime<Utc>>().ok()?;
+
+    let stream_id = record
+        .get("id")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    let metadata = serde_json::json!({
+        "resting_heart_rate": record.get("resting_heart_rate"),
+        "timezone": record.get("timezone"),
+        "source_connection_id": source_id,
+    });
+
+    // One sample per minute, so the timestamp identifies the sample
+    let id = crate::ids::generate_id("health_heart_rate", &[source_id, &timestamp.to_rfc3339()]);
+
+    Some((id, bpm.round() as i32, timestamp, stream_id, metadata))
+}
+
+/// Execute batch insert for heart rate records from Fitbit
+///
+/// Builds and executes a multi-row INSERT statement for efficient bulk insertion.
+async fn execute_heart_rate_batch_insert(
+    db: &Database,
+    source_connection_id: &str,
+    records: &[HeartRateRow],
+) -> Result<usize> {
+    if records.is_empty() {
+        return Ok(0);
+    }
+
+    let query_str = Database::build_batch_insert_query(
+        "data_health_heart_rate",
+        &[
+            "id",
+            "bpm",
+            "timestamp",
+            "source_stream_id",
+            "source_connection_id",
+            "source_table",
+            "source_provider",
+            "metadata",
+        ],
+        "id",
+        records.len(),
+    );
+
+    let mut query = sqlx::query(&query_str);
+
+    // Bind all parameters row by row
+    for (id, bpm, timestamp, stream_id, metadata) in records {
+        query = query
+            .bind(id)
+            .bind(bpm)
+            .bind(timestamp)
+            .bind(stream_id)
+            .bind(source_connection_id)
+            .bind("stream_fitbit_heart_rate")
+            .bind("fitbit")
+            .bind(metadata);
+    }
+
+    let result = query.execute(db.pool()).await?;
+    Ok(result.rows_affected() as usize)
+}
+
+// Self-registration
+struct FitbitHeartRateTransformRegistration;
+
+impl TransformRegistration for FitbitHeartRateTransformRegistration {
+    fn source_table(&self) -> &'static str {
+        "stream_fitbit_heart_rate"
+    }
+    fn target_table(&self) -> &'static str {
+        "health_heart_rate"
+    }
+    fn create(&self, _context: &TransformContext) -> Result<Box<dyn OntologyTransform>> {
+        Ok(Box::new(FitbitHeartRateTransform))
+    }
+}
+
+inventory::submit! {
+    &FitbitHeartRateTransformRegistration as &dyn TransformRegistration
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transform_metadata() {
+        let transform = FitbitHeartRateTransform;
+        assert_eq!(transform.source_table(), "stream_fitbit_heart_rate");
+        assert_eq!(transform.target_table(), "health_heart_rate");
+        assert_eq!(transform.domain(), "health");
+    }
+
+    #[test]
+    fn test_heart_rate_row_is_deterministic() {
+        let record = serde_json::json!({
+            "timestamp": "2024-01-02T07:01:00Z",
+            "bpm": 63.6,
+            "resting_heart_rate": 58,
+        });
+
+        let (id, bpm, _, _, metadata) = heart_rate_row(&record, "source-1").unwrap();
+        assert_eq!(bpm, 64);
+        assert_eq!(metadata["resting_heart_rate"], 58);
+
+        let (again, ..) = heart_rate_row(&record, "source-1").unwrap();
+        assert_eq!(id, again);
+    }
+}
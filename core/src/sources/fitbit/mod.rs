@@ -0,0 +1,17 @@
+//! Fitbit integration
+//!
+//! Syncs sleep, heart rate and exercise data from the Fitbit Web API into the
+//! same health ontology tables fed by iOS HealthKit, so users without Apple
+//! devices still get health coverage.
+
+pub mod activities;
+pub mod client;
+pub mod dates;
+pub mod heart_rate;
+pub mod registry;
+pub mod sleep;
+pub mod types;
+
+pub use activities::FitbitActivitiesStream;
+pub use heart_rate::FitbitHeartRateStream;
+pub use sleep::FitbitSleepStream;
@@ -0,0 +1,134 @@
+//! Fitbit source registration for the catalog
+//!
+//! This module provides the unified registration for Fitbit sources, including
+//! both UI metadata, transform logic, and stream creation in a single place.
+
+use crate::registry::{RegisteredSource, RegisteredStream, SourceRegistry};
+use serde_json::json;
+
+// Import transforms and stream types for unified registration
+use super::activities::{transform::FitbitWorkoutTransform, FitbitActivitiesStream};
+use super::dates::DEFAULT_DAYS_BACK;
+use super::heart_rate::{transform::FitbitHeartRateTransform, FitbitHeartRateStream};
+use super::sleep::{transform::FitbitSleepTransform, FitbitSleepStream};
+use crate::sources::stream_type::StreamType;
+
+/// Fitbit source registration
+pub struct FitbitSource;
+
+impl SourceRegistry for FitbitSource {
+    fn descriptor() -> RegisteredSource {
+        // Metadata is now in virtues-registry
+        let descriptor = virtues_registry::sources::get_source("fitbit")
+            .expect("Fitbit source not found in virtues-registry");
+
+        RegisteredSource {
+            descriptor,
+            streams: vec![
+                RegisteredStream::new("sleep")
+                    .config_schema(days_back_config_schema())
+                    .config_example(days_back_config_example())
+                    .transform("health_sleep", |_ctx| Ok(Box::new(FitbitSleepTransform)))
+                    .dedup_key("log_id")
+                    .partition_key("start_time")
+                    .stream_creator(|ctx| {
+                        Ok(StreamType::Pull(Box::new(FitbitSleepStream::new(
+                            ctx.source_id.clone(),
+                            ctx.db.clone(),
+                            ctx.stream_writer.clone(),
+                            ctx.auth.clone(),
+                        ))))
+                    })
+                    .build(),
+                RegisteredStream::new("heart_rate")
+                    .config_schema(days_back_config_schema())
+                    .config_example(days_back_config_example())
+                    .transform("health_heart_rate", |_ctx| {
+                        Ok(Box::new(FitbitHeartRateTransform))
+                    })
+                    .partition_key("timestamp")
+                    .stream_creator(|ctx| {
+                        Ok(StreamType::Pull(Box::new(FitbitHeartRateStream::new(
+                            ctx.source_id.clone(),
+                            ctx.db.clone(),
+                            ctx.stream_writer.clone(),
+                            ctx.auth.clone(),
+                        ))))
+                    })
+                    .build(),
+                RegisteredStream::new("activities")
+                    .config_schema(days_back_config_schema())
+                    .config_example(days_back_config_example())
+                    .transform("health_workout", |_ctx| {
+                        Ok(Box::new(FitbitWorkoutTransform))
+                    })
+                    .dedup_key("log_id")
+                    .partition_key("start_time")
+                    .stream_creator(|ctx| {
+                        Ok(StreamType::Pull(Box::new(FitbitActivitiesStream::new(
+                            ctx.source_id.clone(),
+                            ctx.db.clone(),
+                            ctx.stream_writer.clone(),
+                            ctx.auth.clone(),
+                        ))))
+                    })
+                    .build(),
+            ],
+        }
+    }
+}
+
+/// JSON schema shared by the Fitbit streams
+fn days_back_config_schema() -> serde_json::Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "days_back": {
+                "type": "integer",
+                "default": DEFAULT_DAYS_BACK,
+                "minimum": 1,
+                "description": "Days of history fetched on the first sync or a full refresh"
+            }
+        }
+    })
+}
+
+/// Example configuration for the Fitbit streams
+fn days_back_config_example() -> serde_json::Value {
+    json!({
+        "days_back": 90
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::AuthType;
+
+    #[test]
+    fn test_fitbit_descriptor() {
+        let desc = FitbitSource::descriptor();
+        assert_eq!(desc.descriptor.name, "fitbit");
+        assert_eq!(desc.descriptor.auth_type, AuthType::OAuth2);
+        assert!(desc.descriptor.oauth_config.is_some());
+        assert_eq!(desc.streams.len(), 3);
+    }
+
+    #[test]
+    fn test_stream_tables() {
+        let desc = FitbitSource::descriptor();
+        for (name, table) in [
+            ("sleep", "stream_fitbit_sleep"),
+            ("heart_rate", "stream_fitbit_heart_rate"),
+            ("activities", "stream_fitbit_activities"),
+        ] {
+            let stream = desc
+                .streams
+                .iter()
+                .find(|s| s.descriptor.name == name)
+                .unwrap();
+            assert_eq!(stream.descriptor.table_name, table);
+            assert!(stream.descriptor.supports_incremental);
+        }
+    }
+}
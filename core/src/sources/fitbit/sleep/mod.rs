@@ -0,0 +1,243 @@
+//! Fitbit sleep stream implementation
+
+pub mod transform;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use super::client::FitbitClient;
+use super::dates::{self, DATE_FORMAT};
+use super::types::SleepResponse;
+use crate::{
+    error::Result,
+    sources::{
+        auth::SourceAuth,
+        base::{SourceClient, StreamLimits, SyncMode, SyncResult},
+        pull_stream::PullStream,
+    },
+    storage::stream_writer::StreamWriter,
+};
+
+/// Maximum date range Fitbit accepts for a sleep log request
+const MAX_RANGE_DAYS: i64 = 100;
+
+/// Fitbit sleep stream
+///
+/// Syncs sleep logs (with stage summaries) from the Fitbit API to object
+/// storage via StreamWriter.
+pub struct FitbitSleepStream {
+    source_id: String,
+    client: FitbitClient,
+    db: SqlitePool,
+    stream_writer: Arc<Mutex<StreamWriter>>,
+}
+
+impl FitbitSleepStream {
+    /// Create a new sleep stream with SourceAuth and StreamWriter
+    pub fn new(
+        source_id: String,
+        db: SqlitePool,
+        stream_writer: Arc<Mutex<StreamWriter>>,
+        auth: SourceAuth,
+    ) -> Self {
+        // Extract token manager from auth
+        let token_manager = auth
+            .token_manager()
+            .expect("FitbitSleepStream requires OAuth2 auth")
+            .clone();
+
+        let client =
+            FitbitClient::new(source_id.clone(), token_manager).with_network(auth.network());
+
+        Self {
+            source_id,
+            client,
+            db,
+            stream_writer,
+        }
+    }
+
+    /// Sync sleep logs with explicit sync mode
+    #[tracing::instrument(skip(self), fields(source_id = %self.source_id, mode = ?sync_mode))]
+    pub async fn sync_with_mode(&self, sync_mode: &SyncMode) -> Result<SyncResult> {
+        tracing::info!("Starting Fitbit sleep sync");
+        self.sync_internal(sync_mode).await
+    }
+
+    /// Internal sync implementation
+    async fn sync_internal(&self, sync_mode: &SyncMode) -> Result<SyncResult> {
+        let started_at = Utc::now();
+        let mut records_fetched = 0;
+        let mut records_written = 0;
+        let mut records_failed = 0;
+        let mut next_cursor = None;
+        let mut earliest_record_at: Option<DateTime<Utc>> = None;
+        let mut latest_record_at: Option<DateTime<Utc>> = None;
+
+        let tz = self.client.user_timezone().await?;
+        let today = Utc::now().with_timezone(&tz).date_naive();
+        let stored_cursor = dates::load_cursor(&self.db, &self.source_id, "sleep").await?;
+        let days_back = dates::load_days_back(&self.db, &self.source_id, "sleep").await?;
+        let (start, end) =
+            dates::date_window(sync_mode, stored_cursor.as_deref(), today, days_back, tz);
+        let limits = StreamLimits::load(&self.db, &self.source_id, "sleep").await?;
+
+        let mut window_start = start;
+        let mut synced_through = None;
+
+        while window_start <= end {
+            let window_end = (window_start + Duration::days(MAX_RANGE_DAYS - 1)).min(end);
+            let path = format!(
+                "1.2/user/-/sleep/date/{}/{}.json",
+                window_start.format(DATE_FORMAT),
+                window_end.format(DATE_FORMAT)
+            );
+            let response: SleepResponse = self.client.get(&path).await?;
+
+            records_fetched += response.sleep.len();
+
+            tracing::debug!(
+                start = %window_start,
+                end = %window_end,
+                count = response.sleep.len(),
+                "Fetched Fitbit sleep logs"
+            );
+
+            for log in &response.sleep {
+                let (Some(start_local), Some(end_local)) = (
+                    dates::parse_local_datetime(&log.start_time),
+                    dates::parse_local_datetime(&log.end_time),
+                ) else {
+                    tracing::warn!(
+                        log_id = log.log_id,
+                        "Skipping Fitbit sleep log with unparseable times"
+                    );
+                    records_failed += 1;
+                    continue;
+                };
+                let start_time = dates::local_to_utc(tz, start_local);
+                let end_time = dates::local_to_utc(tz, end_local);
+
+                earliest_record_at =
+                    Some(earliest_record_at.map_or(start_time, |t| t.min(start_time)));
+                latest_record_at = Some(latest_record_at.map_or(start_time, |t| t.max(start_time)));
+
+                let record = serde_json::json!({
+                    "log_id": log.log_id,
+                    "date_of_sleep": log.date_of_sleep,
+                    "start_time": start_time,
+                    "end_time": end_time,
+                    "duration_ms": log.duration,
+                    "minutes_asleep": log.minutes_asleep,
+                    "minutes_awake": log.minutes_awake,
+                    "time_in_bed": log.time_in_bed,
+                    "efficiency": log.efficiency,
+                    "is_main_sleep": log.is_main_sleep,
+                    "log_type": log.log_type,
+                    "stages": log.levels.as_ref().and_then(|l| l.summary.clone()),
+                    "timezone": tz.name(),
+                    "synced_at": Utc::now(),
+                });
+
+                let written = {
+                    let mut writer = self.stream_writer.lock().await;
+                    writer.write_record(&self.source_id, "sleep", record, Some(start_time))
+                };
+                match written {
+                    Ok(_) => records_written += 1,
+                    Err(e) => {
+                        tracing::warn!(
+                            log_id = log.log_id,
+                            error = %e,
+                            "Failed to write Fitbit sleep log"
+                        );
+                        records_failed += 1;
+                    }
+                }
+            }
+
+            synced_through = Some(window_end);
+
+            if limits.is_reached(records_fetched) {
+                break;
+            }
+
+            window_start = window_end + Duration::days(1);
+        }
+
+        // Backfills cover an arbitrary past range and must not move the cursor
+        if !matches!(sync_mode, SyncMode::Backfill { .. }) {
+            if let Some(date) = synced_through {
+                next_cursor =
+                    Some(dates::save_cursor(&self.db, &self.source_id, "sleep", date).await?);
+            }
+        }
+
+        let completed_at = Utc::now();
+
+        // Collect records from StreamWriter for archive and transform pipeline
+        let records = {
+            let mut writer = self.stream_writer.lock().await;
+            writer
+                .collect_records(&self.source_id, "sleep")?
+                .map(|(records, _, _)| records)
+        };
+
+        tracing::info!(
+            records_fetched,
+            records_written,
+            records_failed,
+            "Fitbit sleep sync completed"
+        );
+
+        Ok(SyncResult {
+            records_fetched,
+            records_written,
+            records_failed,
+            next_cursor,
+            earliest_record_at,
+            latest_record_at,
+            started_at,
+            completed_at,
+            records,
+            archive_job_id: None,
+            records_deduplicated: 0,
+        })
+    }
+}
+
+// Implement PullStream trait for FitbitSleepStream
+#[async_trait]
+impl PullStream for FitbitSleepStream {
+    async fn sync_pull(&self, mode: SyncMode) -> Result<SyncResult> {
+        self.sync_with_mode(&mode).await
+    }
+
+    async fn load_config(&mut self, _db: &SqlitePool, _source_id: &str) -> Result<()> {
+        // days_back and limits are read at sync time
+        Ok(())
+    }
+
+    fn table_name(&self) -> &str {
+        "stream_fitbit_sleep"
+    }
+
+    fn stream_name(&self) -> &str {
+        "sleep"
+    }
+
+    fn source_name(&self) -> &str {
+        "fitbit"
+    }
+
+    fn supports_incremental(&self) -> bool {
+        true
+    }
+
+    fn supports_full_refresh(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,328 @@
+//! Fitbit sleep to health_sleep ontology transformation
+//!
+//! Transforms raw Fitbit sleep logs from stream_fitbit_sleep into the
+//! normalized health_sleep ontology table.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::error::Result;
+use crate::jobs::TransformContext;
+use crate::sources::base::{OntologyTransform, TransformRegistration, TransformResult};
+
+/// Batch size for bulk inserts
+const BATCH_SIZE: usize = 500;
+
+/// Row for data_health_sleep
+type SleepRow = (
+    String,                    // id (deterministic)
+    Option<serde_json::Value>, // sleep_stages
+    Option<i32>,               // duration_minutes
+    Option<f64>,               // sleep_quality_score
+    DateTime<Utc>,             // start_time
+    DateTime<Utc>,             // end_time
+    String,                    // stream_id
+    serde_json::Value,         // metadata
+);
+
+/// Transform Fitbit sleep logs to health_sleep ontology
+pub struct FitbitSleepTransform;
+
+#[async_trait]
+impl OntologyTransform for FitbitSleepTransform {
+    fn source_table(&self) -> &str {
+        "stream_fitbit_sleep"
+    }
+
+    fn target_table(&self) -> &str {
+        "health_sleep"
+    }
+
+    fn domain(&self) -> &str {
+        "health"
+    }
+
+    #[tracing::instrument(skip(self, db, context), fields(source_table = %self.source_table(), target_table = %self.target_table()))]
+    async fn transform(
+        &self,
+        db: &Database,
+        context: &TransformContext,
+        source_id: String,
+    ) -> Result<TransformResult> {
+        let mut records_read = 0;
+        let mut records_written = 0;
+        let mut records_failed = 0;
+        let mut last_processed_id: Option<String> = None;
+
+        tracing::info!(
+            source_id = %source_id,
+            "Starting Fitbit sleep to health_sleep transformation"
+        );
+
+        // Read stream data from data source using checkpoint
+        let checkpoint_key = "fitbit_sleep_to_health_sleep";
+        let data_source = context.get_data_source().ok_or_else(|| {
+            crate::Error::Other("No data source available for transform".to_string())
+        })?;
+        let batches = data_source
+            .read_with_checkpoint(&source_id, "sleep", checkpoint_key)
+            .await?;
+
+        let mut pending_records: Vec<SleepRow> = Vec::new();
+
+        for batch in batches {
+            for record in &batch.records {
+                records_read += 1;
+
+                let Some(row) = sleep_row(record, &source_id) else {
+                    records_failed += 1;
+                    continue;
+                };
+                last_processed_id = Some(row.6.clone());
+                pending_records.push(row);
+
+                if pending_records.len() >= BATCH_SIZE {
+                    match execute_sleep_batch_insert(db, &source_id, &pending_records).await {
+                        Ok(written) => records_written += written,
+                        Err(e) => {
+                            tracing::warn!(
+                                error = %e,
+                                batch_size = pending_records.len(),
+                                "Batch insert failed"
+                            );
+                            records_failed += pending_records.len();
+                        }
+                    }
+                    pending_records.clear();
+                }
+            }
+
+            // Update checkpoint after processing batch
+            if let Some(max_ts) = batch.max_timestamp {
+                data_source
+                    .update_checkpoint(&source_id, "sleep", checkpoint_key, max_ts)
+                    .await?;
+            }
+        }
+
+        // Insert any remaining records
+        if !pending_records.is_empty() {
+            match execute_sleep_batch_insert(db, &source_id, &pending_records).await {
+                Ok(written) => records_written += written,
+                Err(e) => {
+                    tracing::warn!(
+                        error = %e,
+                        batch_size = pending_records.len(),
+                        "Final batch insert failed"
+                    );
+                    records_failed += pending_records.len();
+                }
+            }
+        }
+
+        tracing::info!(
+            source_id = %source_id,
+            records_read,
+            records_written,
+            records_failed,
+            "Fitbit sleep to health_sleep transformation completed"
+        );
+
+        Ok(TransformResult {
+            records_read,
+            records_written,
+            records_failed,
+            last_processed_id,
+            chained_transforms: vec![],
+        })
+    }
+}
+
+/// Map a stream record to a health_sleep row
+fn sleep_row(record: &serde_json::Value, source_id: &str) -> Option<SleepRow> {
+    let log_id = record.get("log_id").and_then(|v| v.as_i64())?;
+    let start_time = record
+        .get("start_time")
+        .and_then(|v| v.as_str())
+        .and_then(|s| s.parse::<DateTime<Utc>>().ok())?;
+    let end_time = record
+        .get("end_time")
+        .and_then(|v| v.as_str())
+        .and_then(|s| s.parse::<DateTime<Utc>>().ok())?;
+
+    let stream_id = record
+        .get("id")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    // Time asleep, falling back to the log's total duration (ms)
+    let duration_minutes = record
+        .get("minutes_asleep")
+        .and_then(|v| v.as_i64())
+        .or_else(|| {
+            record
+                .get("duration_ms")
+                .and_then(|v| v.as_i64())
+                .map(|ms| ms / 60_000)
+        })
+        .map(|m| m as i32);
+
+    // efficiency (0-100) -> sleep_quality_score (0.0-1.0)
+    let sleep_quality_score = record
+        .get("efficiency")
+        .and_then(|v| v.as_f64())
+        .map(|e| e / 100.0);
+
+    let sleep_stages = record.get("stages").filter(|v| !v.is_null()).cloned();
+
+    let metadata = serde_json::json!({
+        "fitbit_log_id": log_id,
+        "date_of_sleep": record.get("date_of_sleep"),
+        "minutes_awake": record.get("minutes_awake"),
+        "time_in_bed": record.get("time_in_bed"),
+        "is_main_sleep": record.get("is_main_sleep"),
+        "log_type": record.get("log_type"),
+        "timezone": record.get("timezone"),
+        "source_connection_id": source_id,
+    });
+
+    // Generate deterministic ID for idempotency
+    let id = crate::ids::generate_id("health_sleep", &[source_id, &log_id.to_string()]);
+
+    Some((
+        id,
+        sleep_stages,
+        duration_minutes,
+        sleep_quality_score,
+        start_time,
+        end_time,
+        stream_id,
+        metadata,
+    ))
+}
+
+/// Execute batch insert for sleep records from Fitbit
+///
+/// Builds and executes a multi-row INSERT statement for efficient bulk insertion.
+async fn execute_sleep_batch_insert(
+    db: &Database,
+    source_connection_id: &str,
+    records: &[SleepRow],
+) -> Result<usize> {
+    if records.is_empty() {
+        return Ok(0);
+    }
+
+    let query_str = Database::build_batch_insert_query(
+        "data_health_sleep",
+        &[
+            "id",
+            "sleep_stages",
+            "duration_minutes",
+            "sleep_quality_score",
+            "start_time",
+            "end_time",
+            "source_stream_id",
+            "source_connection_id",
+            "source_table",
+            "source_provider",
+            "metadata",
+        ],
+        "id",
+        records.len(),
+    );
+
+    let mut query = sqlx::query(&query_str);
+
+    // Bind all parameters row by row
+    for (
+        id,
+        sleep_stages,
+        duration_minutes,
+        sleep_quality_score,
+        start_time,
+        end_time,
+        stream_id,
+        metadata,
+    ) in records
+    {
+        query = query
+            .bind(id)
+            .bind(sleep_stages)
+            .bind(duration_minutes)
+            .bind(sleep_quality_score)
+            .bind(start_time)
+            .bind(end_time)
+            .bind(stream_id)
+            .bind(source_connection_id)
+            .bind("stream_fitbit_sleep")
+            .bind("fitbit")
+            .bind(metadata);
+    }
+
+    let result = query.execute(db.pool()).await?;
+    Ok(result.rows_affected() as usize)
+}
+
+// Self-registration
+struct FitbitSleepTransformRegistration;
+
+impl TransformRegistration for FitbitSleepTransformRegistration {
+    fn source_table(&self) -> &'static str {
+        "stream_fitbit_sleep"
+    }
+    fn target_table(&self) -> &'static str {
+        "health_sleep"
+    }
+    fn create(&self, _context: &TransformContext) -> Result<Box<dyn OntologyTransform>> {
+        Ok(Box::new(FitbitSleepTransform))
+    }
+}
+
+inventory::submit! {
+    &FitbitSleepTransformRegistration as &dyn TransformRegistration
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transform_metadata() {
+        let transform = FitbitSleepTransform;
+        assert_eq!(transform.source_table(), "stream_fitbit_sleep");
+        assert_eq!(transform.target_table(), "health_sleep");
+        assert_eq!(transform.domain(), "health");
+    }
+
+    #[test]
+    fn test_sleep_row_mapping() {
+        let record = serde_json::json!({
+            "id": "stream-1",
+            "log_id": 42,
+            "start_time": "2024-01-02T07:00:00Z",
+            "end_time": "2024-01-02T14:30:00Z",
+            "duration_ms": 27_000_000,
+            "minutes_asleep": 410,
+            "efficiency": 92,
+            "stages": { "deep": { "count": 3, "minutes": 62 } },
+        });
+
+        let (id, stages, duration, quality, _, _, stream_id, _) =
+            sleep_row(&record, "source-1").unwrap();
+        assert_eq!(
+            id,
+            crate::ids::generate_id("health_sleep", &["source-1", "42"])
+        );
+        assert!(stages.is_some());
+        assert_eq!(duration, Some(410));
+        assert_eq!(quality, Some(0.92));
+        assert_eq!(stream_id, "stream-1");
+
+        // Records without times are rejected
+        assert!(sleep_row(&serde_json::json!({ "log_id": 1 }), "source-1").is_none());
+    }
+}
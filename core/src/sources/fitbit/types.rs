@@ -0,0 +1,119 @@
+//! Fitbit API response types
+//!
+//! Based on the Fitbit Web API: https://dev.fitbit.com/build/reference/web-api/
+//!
+//! Fitbit reports times in the user's local time zone (from their profile),
+//! mostly without an offset, so streams convert them using the profile
+//! time zone before writing records.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// User profile (from GET /1/user/-/profile.json)
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProfileResponse {
+    pub user: UserProfile,
+}
+
+/// Subset of the user profile needed for time zone handling
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserProfile {
+    pub timezone: Option<String>, // IANA name, e.g. "America/Los_Angeles"
+}
+
+/// Sleep logs for a date range (from GET /1.2/user/-/sleep/date/{start}/{end}.json)
+#[derive(Debug, Clone, Deserialize)]
+pub struct SleepResponse {
+    #[serde(default)]
+    pub sleep: Vec<SleepLog>,
+}
+
+/// A single sleep log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SleepLog {
+    pub log_id: i64,
+    pub date_of_sleep: String, // YYYY-MM-DD
+    pub start_time: String,    // local, e.g. 2024-01-01T23:10:30.000
+    pub end_time: String,      // local
+    pub duration: i64,         // milliseconds
+    pub minutes_asleep: Option<i64>,
+    pub minutes_awake: Option<i64>,
+    pub time_in_bed: Option<i64>,
+    pub efficiency: Option<i64>, // 0-100
+    pub is_main_sleep: Option<bool>,
+    #[serde(rename = "type")]
+    pub log_type: Option<String>, // "stages" or "classic"
+    pub levels: Option<SleepLevels>,
+}
+
+/// Sleep stage breakdown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SleepLevels {
+    /// Per-stage totals, e.g. {"deep": {"count": 3, "minutes": 62}, ...}
+    pub summary: Option<Value>,
+}
+
+/// Heart rate for one day (from GET /1/user/-/activities/heart/date/{date}/1d/1min.json)
+#[derive(Debug, Clone, Deserialize)]
+pub struct HeartRateResponse {
+    #[serde(rename = "activities-heart", default)]
+    pub days: Vec<HeartRateDay>,
+    #[serde(rename = "activities-heart-intraday")]
+    pub intraday: Option<HeartRateIntraday>,
+}
+
+/// Daily heart rate summary
+#[derive(Debug, Clone, Deserialize)]
+pub struct HeartRateDay {
+    #[serde(rename = "dateTime")]
+    pub date_time: String, // YYYY-MM-DD
+    pub value: HeartRateDayValue,
+}
+
+/// Daily heart rate values
+#[derive(Debug, Clone, Deserialize)]
+pub struct HeartRateDayValue {
+    #[serde(rename = "restingHeartRate")]
+    pub resting_heart_rate: Option<i64>,
+}
+
+/// Intraday heart rate samples
+#[derive(Debug, Clone, Deserialize)]
+pub struct HeartRateIntraday {
+    #[serde(default)]
+    pub dataset: Vec<HeartRateSample>,
+}
+
+/// A single intraday heart rate sample
+#[derive(Debug, Clone, Deserialize)]
+pub struct HeartRateSample {
+    pub time: String, // local HH:MM:SS
+    pub value: f64,   // bpm
+}
+
+/// Activity log list (from GET /1/user/-/activities/list.json)
+#[derive(Debug, Clone, Deserialize)]
+pub struct ActivityListResponse {
+    #[serde(default)]
+    pub activities: Vec<ActivityLog>,
+}
+
+/// A logged or auto-detected exercise session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityLog {
+    pub log_id: i64,
+    pub activity_name: String,
+    pub activity_type_id: Option<i64>,
+    pub start_time: String, // ISO 8601 with offset
+    pub duration: i64,      // milliseconds
+    pub active_duration: Option<i64>,
+    pub calories: Option<f64>,
+    pub distance: Option<f64>,
+    pub distance_unit: Option<String>, // "Kilometer" or "Mile"
+    pub average_heart_rate: Option<f64>,
+    pub steps: Option<i64>,
+    pub elevation_gain: Option<f64>,
+    pub log_type: Option<String>, // "auto_detected", "manual", "tracker", ...
+}
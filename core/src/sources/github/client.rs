@@ -1,55 +1,52 @@
-//! GitHub API client - thin wrapper over OAuthHttpClient
+//! GitHub API client - thin wrapper over SourceHttpClient
 //!
-//! This client delegates all OAuth HTTP operations to the base OAuthHttpClient,
+//! This client delegates all HTTP operations to the base SourceHttpClient,
 //! providing GitHub-specific configuration (custom headers, base URL).
 
-use serde::de::DeserializeOwned;
 use std::sync::Arc;
 
-use crate::{
-    error::Result,
-    sources::base::{OAuthHttpClient, RetryConfig, TokenManager},
+use super::error_handler::GitHubErrorHandler;
+use crate::sources::base::{
+    user_agent_for, RetryConfig, SourceClient, SourceHttpClient, TokenManager,
 };
 
 /// GitHub API client with automatic token refresh and retry logic
 ///
-/// This is a thin wrapper that configures OAuthHttpClient for GitHub APIs.
+/// This is a thin wrapper that configures SourceHttpClient for GitHub APIs.
 /// All HTTP logic (retry, token refresh, error handling) is delegated to the base client.
 ///
 /// GitHub requires:
 /// - `Accept: application/vnd.github+json` header
 /// - `User-Agent` header (GitHub rejects requests without one)
+///
+/// Rate limits, including 403 secondary rate limits, are told apart from
+/// refused tokens by `GitHubErrorHandler`.
 pub struct GitHubClient {
-    http: OAuthHttpClient,
+    http: SourceHttpClient,
 }
 
 impl GitHubClient {
     /// Create a new GitHub API client
     pub fn new(source_id: String, token_manager: Arc<TokenManager>) -> Self {
         Self {
-            http: OAuthHttpClient::new(source_id, token_manager)
+            http: SourceHttpClient::oauth(source_id, token_manager)
                 .with_base_url("https://api.github.com")
+                .with_user_agent(&user_agent_for("github"))
                 .with_header("Accept", "application/vnd.github+json")
-                .with_header("User-Agent", "virtues-app")
                 .with_header("X-GitHub-Api-Version", "2022-11-28")
-                .with_retry_config(RetryConfig::default()),
+                .with_retry_config(RetryConfig::default())
+                .with_error_handler(Box::new(GitHubErrorHandler)),
         }
     }
+}
 
-    /// Make an authenticated GET request
-    pub async fn get<T>(&self, path: &str) -> Result<T>
-    where
-        T: DeserializeOwned,
-    {
-        self.http.get(path).await
+impl SourceClient for GitHubClient {
+    fn http(&self) -> &SourceHttpClient {
+        &self.http
     }
 
-    /// Make an authenticated GET request with query parameters
-    pub async fn get_with_params<T>(&self, path: &str, params: &[(&str, &str)]) -> Result<T>
-    where
-        T: DeserializeOwned,
-    {
-        self.http.get_with_params(path, params).await
+    fn http_mut(&mut self) -> &mut SourceHttpClient {
+        &mut self.http
     }
 }
 
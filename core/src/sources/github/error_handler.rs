@@ -0,0 +1,201 @@
+//! GitHub-specific error handling
+//!
+//! GitHub answers rate limits with 403 as well as 429: the primary limit
+//! ("API rate limit exceeded") and the secondary limits on bursts of
+//! requests ("You have exceeded a secondary rate limit"). Those are told
+//! apart from a genuine 403 by the response body, waited out as GitHub asks,
+//! and once the wait is too long become `Error::RateLimited` so the stream
+//! can stop and resume from the same page next run. A 401, or a 403 saying
+//! "Bad credentials", means the token is no longer accepted and fails fast
+//! as `Error::TokenExpired`; any other 403 is a refusal of that one request
+//! (a private repo, a blocked user) and leaves the source connected.
+
+use chrono::Utc;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use std::time::Duration;
+
+use crate::error::Error;
+use crate::sources::base::error_handler::{DefaultErrorHandler, ErrorClass, ErrorHandler};
+
+/// Wait after a secondary rate limit that doesn't say how long to wait;
+/// GitHub asks for at least a minute
+const SECONDARY_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// Whether a response body is one of GitHub's rate limit messages
+fn is_rate_limit_body(body: &str) -> bool {
+    let body = body.to_lowercase();
+    body.contains("secondary rate limit") || body.contains("api rate limit exceeded")
+}
+
+/// Whether a failed response means the token itself was rejected
+fn is_bad_credentials(status: StatusCode, body: &str) -> bool {
+    match status.as_u16() {
+        401 => true,
+        403 => body.to_lowercase().contains("bad credentials"),
+        _ => false,
+    }
+}
+
+/// Whether a failed response is a rate limit rather than a refusal
+fn is_rate_limited(status: StatusCode, body: &str) -> bool {
+    match status.as_u16() {
+        429 => true,
+        403 => is_rate_limit_body(body),
+        _ => false,
+    }
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+/// GitHub API error handler
+pub struct GitHubErrorHandler;
+
+impl ErrorHandler for GitHubErrorHandler {
+    fn should_retry(&self, status: StatusCode, attempt: u32, max_retries: u32) -> bool {
+        if attempt >= max_retries {
+            return false;
+        }
+
+        // 403 is only retried when classified as a rate limit
+        matches!(status.as_u16(), 401 | 403 | 429 | 500..=599)
+    }
+
+    fn classify_error(&self, status: StatusCode, body: &str) -> ErrorClass {
+        if is_rate_limited(status, body) {
+            return ErrorClass::RateLimit;
+        }
+        match status.as_u16() {
+            401 => ErrorClass::AuthError,
+            400..=499 => ErrorClass::ClientError,
+            500..=599 => ErrorClass::ServerError,
+            _ => ErrorClass::ClientError,
+        }
+    }
+
+    /// `Retry-After` when sent, else the primary limit's reset time, else a
+    /// minute for a secondary limit
+    fn retry_after(&self, status: StatusCode, headers: &HeaderMap, body: &str) -> Option<Duration> {
+        if let Some(wait) = DefaultErrorHandler.retry_after(status, headers, body) {
+            return Some(wait);
+        }
+        if header_u64(headers, "x-ratelimit-remaining") == Some(0) {
+            let reset = header_u64(headers, "x-ratelimit-reset")?;
+            let now = Utc::now().timestamp().max(0) as u64;
+            return Some(Duration::from_secs(reset.saturating_sub(now)));
+        }
+        body.to_lowercase()
+            .contains("secondary rate limit")
+            .then_some(SECONDARY_RATE_LIMIT_WAIT)
+    }
+
+    fn map_error(&self, status: StatusCode, body: &str) -> Error {
+        if is_rate_limited(status, body) {
+            return Error::RateLimited {
+                message: format!("GitHub ({status}): {body}"),
+                retry_after: None,
+            };
+        }
+        if is_bad_credentials(status, body) {
+            return Error::TokenExpired(format!("GitHub rejected the token ({status}): {body}"));
+        }
+        Error::Http(format!("API error ({status}): {body}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Result;
+    use crate::sources::base::mock_transport::testing::serve;
+    use crate::sources::base::{HttpAuth, SourceHttpClient};
+
+    const SECONDARY: &str = r#"{"message":"You have exceeded a secondary rate limit."}"#;
+
+    #[test]
+    fn test_secondary_rate_limit_is_not_an_auth_error() {
+        let handler = GitHubErrorHandler;
+        let forbidden = StatusCode::FORBIDDEN;
+
+        assert_eq!(
+            handler.classify_error(forbidden, SECONDARY),
+            ErrorClass::RateLimit
+        );
+        assert!(matches!(
+            handler.map_error(forbidden, SECONDARY),
+            Error::RateLimited { .. }
+        ));
+
+        let denied = r#"{"message":"Bad credentials"}"#;
+        assert_eq!(
+            handler.classify_error(forbidden, denied),
+            ErrorClass::ClientError
+        );
+        assert!(matches!(
+            handler.map_error(forbidden, denied),
+            Error::TokenExpired(_)
+        ));
+        assert!(matches!(
+            handler.map_error(StatusCode::UNAUTHORIZED, "{}"),
+            Error::TokenExpired(_)
+        ));
+
+        // A 403 for one resource is not a reason to reconnect the source
+        let blocked = r#"{"message":"Resource not accessible by personal access token"}"#;
+        assert!(matches!(
+            handler.map_error(forbidden, blocked),
+            Error::Http(_)
+        ));
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let handler = GitHubErrorHandler;
+        let forbidden = StatusCode::FORBIDDEN;
+        let mut headers = HeaderMap::new();
+
+        assert_eq!(
+            handler.retry_after(forbidden, &headers, SECONDARY),
+            Some(SECONDARY_RATE_LIMIT_WAIT)
+        );
+        assert_eq!(handler.retry_after(forbidden, &headers, "denied"), None);
+
+        let reset = Utc::now().timestamp() + 120;
+        headers.insert("x-ratelimit-remaining", "0".parse().unwrap());
+        headers.insert("x-ratelimit-reset", reset.to_string().parse().unwrap());
+        let wait = handler.retry_after(forbidden, &headers, "").unwrap();
+        assert!(wait > Duration::from_secs(100) && wait <= Duration::from_secs(120));
+
+        headers.insert("retry-after", "5".parse().unwrap());
+        assert_eq!(
+            handler.retry_after(forbidden, &headers, SECONDARY),
+            Some(Duration::from_secs(5))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_secondary_rate_limit_is_waited_out() {
+        let (url, server) = serve(vec![
+            "HTTP/1.1 403 Forbidden\r\nRetry-After: 0\r\nContent-Length: 55\r\nConnection: close\r\n\r\n{\"message\":\"You have exceeded a secondary rate limit.\"}",
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\nConnection: close\r\n\r\n[]",
+            "HTTP/1.1 403 Forbidden\r\nContent-Length: 15\r\nConnection: close\r\n\r\nBad credentials",
+        ])
+        .await;
+        let client = SourceHttpClient::new("test-source".to_string(), HttpAuth::None)
+            .with_base_url(&url)
+            .with_error_handler(Box::new(GitHubErrorHandler));
+
+        let events: Vec<serde_json::Value> = client.get("users/octocat/events").await.unwrap();
+        assert!(events.is_empty());
+
+        // A genuine 403 fails on the first response
+        let result: Result<serde_json::Value> = client.get("user").await;
+        assert!(matches!(result, Err(Error::TokenExpired(_))));
+
+        let requests = server.await.unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[1].starts_with("get /users/octocat/events "));
+    }
+}
@@ -1,7 +1,9 @@
 //! GitHub Events stream implementation
 //!
 //! Pulls activity events from the GitHub Events API and stores them
-//! in the stream_github_events table via StreamWriter.
+//! in the stream_github_events table via StreamWriter. A run that hits a
+//! rate limit it can't wait out stops, and the next run picks up after the
+//! oldest event it archived.
 
 pub mod transform;
 
@@ -16,17 +18,19 @@ use super::{
     types::{GitHubEvent, GitHubUser},
 };
 use crate::{
-    error::Result,
+    error::{Error, Result},
+    jobs::dedup,
     sources::{
         auth::SourceAuth,
-        base::SyncResult,
+        base::{load_resume_cursor, save_resume_cursor, SourceClient, StreamLimits, SyncResult},
         pull_stream::{PullStream, SyncMode},
     },
     storage::stream_writer::StreamWriter,
 };
 
-/// Maximum number of pages to fetch (GitHub caps at 10 pages / 300 events)
-const MAX_PAGES: u32 = 4;
+/// Pages followed per run unless the stream config sets `max_pages`
+/// (GitHub caps at 10 pages / 300 events)
+const DEFAULT_MAX_PAGES: u32 = 4;
 
 /// Events per page (GitHub maximum is 100)
 const PER_PAGE: u32 = 100;
@@ -60,7 +64,8 @@ impl GitHubEventsStream {
             .expect("GitHubEventsStream requires OAuth2 auth")
             .clone();
 
-        let client = GitHubClient::new(source_id.clone(), token_manager);
+        let client =
+            GitHubClient::new(source_id.clone(), token_manager).with_network(auth.network());
 
         Self {
             source_id,
@@ -103,61 +108,109 @@ impl GitHubEventsStream {
             _ => None,
         };
 
-        // Paginate through events
-        let mut page = 1u32;
+        // A resume cursor holds the id of the oldest event a capped run
+        // archived. Events are listed newest-first, so page numbers shift as
+        // new events arrive; event ids only grow, so the resumed run skips
+        // everything at or above it instead.
+        let limits = StreamLimits::load(&self.db, &self.source_id, "events").await?;
+        let per_page = limits.page_size_or(PER_PAGE).min(PER_PAGE);
+        let max_pages = limits.max_pages_or(DEFAULT_MAX_PAGES);
+        let resume_id = load_resume_cursor(&self.db, &self.source_id, "events")
+            .await?
+            .and_then(|cursor| cursor.parse::<u64>().ok());
+        let mut oldest_id: Option<u64> = None;
+        let mut capped = false;
+
+        // Re-read a window before the cursor so events stamped slightly
+        // behind it (clock skew, shared timestamps) aren't skipped
+        let cursor_at = last_cursor
+            .as_deref()
+            .and_then(|c| DateTime::parse_from_rfc3339(c).ok())
+            .map(|dt| dt.with_timezone(&Utc));
+        let refetch_from = cursor_at.map(|c| c - limits.cursor_overlap());
+
+        // Paginate through events. Pages holding only events a previous run
+        // archived don't count towards `max_pages`.
+        let mut page = 1;
+        let mut pages_read = 0;
         'pagination: loop {
-            if page > MAX_PAGES {
-                tracing::debug!("Reached max pages ({}), stopping", MAX_PAGES);
+            if pages_read >= max_pages {
+                tracing::warn!(
+                    max_pages,
+                    max_records = limits.max_records_or(DEFAULT_MAX_PAGES, per_page),
+                    "Reached pagination limit, older events fetched next run"
+                );
+                capped = true;
                 break;
             }
 
             let page_str = page.to_string();
-            let per_page_str = PER_PAGE.to_string();
+            let per_page_str = per_page.to_string();
             let params = vec![
                 ("per_page", per_page_str.as_str()),
                 ("page", page_str.as_str()),
             ];
 
-            let events: Vec<GitHubEvent> = self
+            // A rate limit GitHub wants waited out longer than the client
+            // retries for ends the run here; the next run resumes after the
+            // oldest event archived so far
+            let events: Vec<GitHubEvent> = match self
                 .client
                 .get_with_params(&format!("users/{username}/events"), &params)
-                .await?;
+                .await
+            {
+                Ok(events) => events,
+                Err(Error::RateLimited { message, .. }) => {
+                    tracing::warn!(page, %message, "Rate limited, resuming next run");
+                    capped = true;
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
 
             if events.is_empty() {
                 tracing::debug!(page = page, "Empty page, stopping pagination");
                 break;
             }
 
-            records_fetched += events.len();
-
             tracing::debug!(
                 page = page,
                 events_count = events.len(),
                 "Fetched GitHub events page"
             );
 
+            let fetched_before = records_fetched;
             for event in &events {
                 // Parse the event timestamp
                 let event_time = DateTime::parse_from_rfc3339(&event.created_at)
                     .ok()
                     .map(|dt| dt.with_timezone(&Utc));
 
-                // For incremental sync: skip events we've already seen
-                if let (Some(cursor_ts), Some(evt_ts)) = (&last_cursor, &event_time) {
-                    if let Ok(cursor_dt) = DateTime::parse_from_rfc3339(cursor_ts) {
-                        let cursor_utc = cursor_dt.with_timezone(&Utc);
-                        if *evt_ts <= cursor_utc {
-                            // We've reached events we already have, stop
-                            tracing::debug!(
-                                event_id = %event.id,
-                                event_time = %evt_ts,
-                                cursor = %cursor_utc,
-                                "Reached already-synced events, stopping"
-                            );
-                            break 'pagination;
-                        }
+                // For incremental sync: stop once past the overlap window;
+                // events inside it are dropped after collection if archived
+                if let (Some(from), Some(evt_ts)) = (refetch_from, &event_time) {
+                    if *evt_ts < from {
+                        tracing::debug!(
+                            event_id = %event.id,
+                            event_time = %evt_ts,
+                            refetch_from = %from,
+                            "Reached already-synced events, stopping"
+                        );
+                        break 'pagination;
+                    }
+                }
+
+                // Skip events the capped run before this one already archived
+                let event_id = event.id.parse::<u64>().ok();
+                if let (Some(resume), Some(id)) = (resume_id, event_id) {
+                    if id >= resume {
+                        continue;
                     }
                 }
+                records_fetched += 1;
+                if let Some(id) = event_id {
+                    oldest_id = Some(oldest_id.map_or(id, |oldest| oldest.min(id)));
+                }
 
                 // Update watermarks
                 if let Some(ts) = event_time {
@@ -217,12 +270,32 @@ impl GitHubEventsStream {
             }
 
             page += 1;
+            if records_fetched > fetched_before {
+                pages_read += 1;
+            }
+
+            if limits.is_reached(records_fetched) {
+                capped = true;
+                break;
+            }
         }
 
-        // Save cursor (newest event timestamp) for incremental sync
+        // A capped run that archived nothing keeps the cursor it resumed from
+        let resume_cursor = oldest_id.or(resume_id).filter(|_| capped);
+        save_resume_cursor(
+            &self.db,
+            &self.source_id,
+            "events",
+            resume_cursor.map(|id| id.to_string()).as_deref(),
+        )
+        .await?;
+
+        // Save cursor (newest event timestamp) for incremental sync, but only
+        // once the listing is complete so older unfetched events aren't skipped,
+        // and never backwards because of events from the overlap window
         // Transaction scoped tightly to avoid holding DB lock during network I/O
-        if let Some(latest) = latest_record_at {
-            let cursor = latest.to_rfc3339();
+        if let Some(latest) = latest_record_at.filter(|_| !capped) {
+            let cursor = cursor_at.map_or(latest, |c| c.max(latest)).to_rfc3339();
             let mut tx = self.db.begin().await?;
             self.save_cursor_with_tx(&cursor, &mut tx).await?;
             tx.commit().await?;
@@ -235,7 +308,7 @@ impl GitHubEventsStream {
         let records = {
             let mut writer = self.stream_writer.lock().await;
             let collected = writer
-                .collect_records(&self.source_id, "events")
+                .collect_records(&self.source_id, "events")?
                 .map(|(records, _, _)| records);
 
             if let Some(ref recs) = collected {
@@ -250,10 +323,18 @@ impl GitHubEventsStream {
             collected
         };
 
+        // Drop events the overlap window returned again
+        let (records, records_deduplicated) = if cursor_at.is_some() || resume_id.is_some() {
+            dedup::skip_refetched(&self.db, &self.source_id, "events", records).await?
+        } else {
+            (records, 0)
+        };
+
         tracing::info!(
             records_fetched,
             records_written,
             records_failed,
+            records_deduplicated,
             "GitHub events sync completed"
         );
 
@@ -268,6 +349,7 @@ impl GitHubEventsStream {
             completed_at,
             records,
             archive_job_id: None,
+            records_deduplicated,
         })
     }
 
@@ -337,9 +419,104 @@ impl PullStream for GitHubEventsStream {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::sources::base::mock_transport::testing::migrated_pool;
+    use crate::sources::base::{MockTransport, TokenManager};
+    use serde_json::json;
+
+    fn event(id: u64) -> serde_json::Value {
+        json!({
+            "id": id.to_string(),
+            "type": "PushEvent",
+            "actor": { "id": 1, "login": "octo", "avatar_url": null },
+            "repo": { "id": 2, "name": "octo/repo", "url": "https://example.test/octo/repo" },
+            "payload": {},
+            "public": true,
+            "created_at": format!("2025-01-15T08:{:02}:00Z", id % 60),
+            "org": null
+        })
+    }
+
+    fn events_stream(db: &SqlitePool, transport: Arc<MockTransport>) -> GitHubEventsStream {
+        let token_manager = Arc::new(TokenManager::new_insecure(db.clone()));
+        let mut stream = GitHubEventsStream::new(
+            "src-github".to_string(),
+            db.clone(),
+            Arc::new(Mutex::new(StreamWriter::new())),
+            SourceAuth::oauth2("src-github".to_string(), token_manager),
+        );
+        stream.client.http_mut().set_mock_transport(transport);
+        stream
+    }
+
+    fn event_ids(result: &SyncResult) -> Vec<String> {
+        result
+            .records
+            .iter()
+            .flatten()
+            .map(|r| r["event_id"].as_str().unwrap().to_string())
+            .collect()
+    }
+
     #[test]
     fn test_constants() {
-        assert_eq!(super::MAX_PAGES, 4);
+        assert_eq!(super::DEFAULT_MAX_PAGES, 4);
         assert_eq!(super::PER_PAGE, 100);
     }
+
+    #[tokio::test]
+    async fn test_capped_run_resumes_after_oldest_event() {
+        let db = migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, name) VALUES ('src-github', 'github', 'GitHub')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO elt_stream_connections (id, source_connection_id, stream_name, table_name, config)
+             VALUES ('st-github', 'src-github', 'events', 'stream_github_events', '{\"max_records_per_run\": 2}')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_response("user", json!({ "login": "octo", "id": 1 }))
+                .with_response("users/octo/events", json!([event(30), event(29)])),
+        );
+        let stream = events_stream(&db, transport);
+        let result = stream.sync_internal(&SyncMode::FullRefresh).await.unwrap();
+        assert_eq!(event_ids(&result), ["30", "29"]);
+        assert_eq!(result.next_cursor, None);
+        assert_eq!(
+            load_resume_cursor(&db, "src-github", "events")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("29")
+        );
+
+        // A new event shifted the listing by one; the next run still picks
+        // up after the oldest event archived
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_response("user", json!({ "login": "octo", "id": 1 }))
+                .with_response(
+                    "users/octo/events",
+                    json!([event(31), event(30), event(29), event(28)]),
+                )
+                .with_response("users/octo/events", json!([])),
+        );
+        let stream = events_stream(&db, transport);
+        let result = stream.sync_internal(&SyncMode::FullRefresh).await.unwrap();
+        assert_eq!(event_ids(&result), ["28"]);
+        assert_eq!(
+            load_resume_cursor(&db, "src-github", "events")
+                .await
+                .unwrap(),
+            None
+        );
+    }
 }
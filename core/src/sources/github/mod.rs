@@ -5,6 +5,7 @@
 //! them into the content_bookmark ontology.
 
 pub mod client;
+pub mod error_handler;
 pub mod events;
 pub mod registry;
 pub mod types;
@@ -28,6 +28,8 @@ impl SourceRegistry for GitHubSource {
                     .transform("content_bookmark", |_ctx| {
                         Ok(Box::new(GitHubBookmarkTransform))
                     })
+                    .dedup_key("event_id")
+                    .partition_key("created_at")
                     .stream_creator(|ctx| {
                         Ok(crate::sources::stream_type::StreamType::Pull(Box::new(
                             GitHubEventsStream::new(
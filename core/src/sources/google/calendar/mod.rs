@@ -1,4 +1,9 @@
 //! Google Calendar stream implementation
+//!
+//! Timed events often carry UTC datetimes with no zone of their own, the zone
+//! being set only on the calendar. Each record keeps the event's own
+//! `timezone` and a `resolved_timezone` that falls back to the calendar's,
+//! fetched once per calendar each sync.
 
 pub mod transform;
 
@@ -11,13 +16,17 @@ use tokio::sync::Mutex;
 use super::{
     client::GoogleClient,
     config::GoogleCalendarConfig,
-    types::{Event, EventsResponse},
+    types::{Calendar, Event, EventsResponse},
 };
 use crate::{
     error::Result,
+    jobs::first_sync,
     sources::{
         auth::SourceAuth,
-        base::{ConfigSerializable, SyncMode, SyncResult},
+        base::{
+            collect_then_commit, discard_buffered_records, load_resume_cursor, save_resume_cursor,
+            ConfigSerializable, SourceClient, StreamLimits, SyncMode, SyncResult,
+        },
         pull_stream::PullStream,
     },
     storage::stream_writer::StreamWriter,
@@ -48,7 +57,8 @@ impl GoogleCalendarStream {
             .expect("GoogleCalendarStream requires OAuth2 auth")
             .clone();
 
-        let client = GoogleClient::with_api(source_id.clone(), token_manager, "calendar", "v3");
+        let client = GoogleClient::with_api(source_id.clone(), token_manager, "calendar", "v3")
+            .with_network(auth.network());
 
         Self {
             source_id,
@@ -83,8 +93,15 @@ impl GoogleCalendarStream {
     pub async fn sync_with_mode(&self, sync_mode: &SyncMode) -> Result<SyncResult> {
         tracing::info!("Starting calendar sync");
 
+        // Leftovers from a run that died before its checkpoint
+        discard_buffered_records(&self.stream_writer, &self.source_id, "calendar").await;
+
         // Execute the sync (logging is handled by job executor)
-        self.sync_internal(sync_mode).await
+        let result = self.sync_internal(sync_mode).await;
+        if result.is_err() {
+            discard_buffered_records(&self.stream_writer, &self.source_id, "calendar").await;
+        }
+        result
     }
 
     /// Internal sync implementation
@@ -98,32 +115,86 @@ impl GoogleCalendarStream {
         let mut earliest_record_at: Option<DateTime<Utc>> = None;
         let mut latest_record_at: Option<DateTime<Utc>> = None;
 
-        // Start database transaction for atomicity
-        let mut tx = self.db.begin().await?;
-
         // Get last sync token from database
         let last_sync_token = self.get_last_sync_token().await?;
 
+        // A backfill lists a past window and leaves the stream's sync token
+        // and resume cursor as they were
+        let backfill = matches!(sync_mode, SyncMode::Backfill { .. });
+
+        // A resume cursor ("<calendar_id>\t<page_token>") means the last full
+        // sync stopped at the record cap; continue it from that calendar/page.
+        // A backfill has no resume cursor to continue from, so it pages its
+        // whole window; the window already bounds the run.
+        let mut limits = StreamLimits::load(&self.db, &self.source_id, "calendar").await?;
+        if backfill {
+            limits.max_records_per_run = None;
+        }
+        let resume_cursor = if backfill {
+            None
+        } else {
+            load_resume_cursor(&self.db, &self.source_id, "calendar").await?
+        };
+        let mut resume = resume_cursor.and_then(|cursor| {
+            cursor
+                .split_once('\t')
+                .map(|(calendar, token)| (calendar.to_string(), token.to_string()))
+        });
+        let mut capped_at: Option<String> = None;
+
         // Use calendars from configuration
         let calendars = self.config.calendar_ids.clone();
 
         for calendar_id in &calendars {
+            // Skip calendars the capped run already finished
+            let page_token = match &resume {
+                Some((resume_calendar, _)) if resume_calendar != calendar_id => continue,
+                Some(_) => resume.take().map(|(_, token)| token),
+                None => None,
+            };
+
             tracing::debug!(calendar_id = %calendar_id, "Syncing calendar");
 
             let result = match sync_mode {
+                _ if page_token.is_some() => {
+                    self.sync_full(
+                        calendar_id,
+                        None,
+                        None,
+                        page_token,
+                        &limits,
+                        records_fetched,
+                    )
+                    .await?
+                }
                 SyncMode::Incremental { cursor } => {
                     let token = cursor.clone().or(last_sync_token.clone());
                     if let Some(ref t) = token {
-                        self.sync_incremental(calendar_id, t).await?
+                        self.sync_incremental(calendar_id, t, &limits, records_fetched)
+                            .await?
                     } else {
-                        self.sync_full(calendar_id, None, None).await?
+                        self.sync_full(calendar_id, None, None, None, &limits, records_fetched)
+                            .await?
                     }
                 }
-                SyncMode::FullRefresh => self.sync_full(calendar_id, None, None).await?,
+                SyncMode::FullRefresh => {
+                    self.sync_full(calendar_id, None, None, None, &limits, records_fetched)
+                        .await?
+                }
                 SyncMode::Backfill {
                     start_date,
                     end_date,
-                } => self.sync_full(calendar_id, Some(*start_date), Some(*end_date)).await?,
+                } => {
+                    self.sync_full(
+                        calendar_id,
+                        Some(*start_date),
+                        Some(*end_date),
+                        None,
+                        &limits,
+                        records_fetched,
+                    )
+                    .await?
+                }
             };
 
             records_fetched += result.items.len();
@@ -135,12 +206,20 @@ impl GoogleCalendarStream {
                 "Response metadata"
             );
 
-            // Process events within transaction
+            let calendar_timezone = if result.items.is_empty() {
+                None
+            } else {
+                self.get_calendar_timezone(calendar_id).await
+            };
+
+            // Buffer events; nothing is persisted until the checkpoint commit
             for event in result.items {
                 // Update watermarks
                 let event_start = if let Some(start) = event.start.as_ref() {
                     if let Some(dt_str) = &start.date_time {
-                        DateTime::parse_from_rfc3339(dt_str).ok().map(|dt| dt.with_timezone(&Utc))
+                        DateTime::parse_from_rfc3339(dt_str)
+                            .ok()
+                            .map(|dt| dt.with_timezone(&Utc))
                     } else if let Some(date_str) = &start.date {
                         NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
                             .ok()
@@ -167,7 +246,7 @@ impl GoogleCalendarStream {
                 }
 
                 match self
-                    .upsert_event_with_tx(calendar_id, &event, &mut tx)
+                    .write_event(calendar_id, calendar_timezone.as_deref(), &event)
                     .await
                 {
                     Ok(true) => records_written += 1,
@@ -186,39 +265,57 @@ impl GoogleCalendarStream {
                 }
             }
 
-            // Save new sync token within transaction
+            // Stopped at the record cap: remember where, skip remaining calendars
+            if let Some(page_token) = result.next_page_token {
+                capped_at = Some(format!("{}\t{}", calendar_id, page_token));
+                break;
+            }
+
+            if backfill {
+                continue;
+            }
+
+            // Sync token is saved with the checkpoint, after records are collected
             if let Some(token) = result.next_sync_token {
-                tracing::info!("Saving sync token: {}", &token);
-                self.save_sync_token_with_tx(&token, &mut tx).await?;
                 next_cursor = Some(token);
             } else {
                 tracing::warn!("No sync token returned from API response");
             }
         }
 
-        // Commit transaction - all or nothing
-        tx.commit().await?;
-
-        let completed_at = Utc::now();
-
-        // Collect records from StreamWriter for archive and transform pipeline
-        let records = {
-            let mut writer = self.stream_writer.lock().await;
-            let collected = writer
-                .collect_records(&self.source_id, "calendar")
-                .map(|(records, _, _)| records);
+        // The job writes back the cursor a sync returns, so a backfill
+        // returns the one it found
+        if backfill {
+            next_cursor = last_sync_token;
+        }
 
-            if let Some(ref recs) = collected {
-                tracing::info!(
-                    record_count = recs.len(),
-                    "Collected calendar records from StreamWriter"
-                );
-            } else {
-                tracing::warn!("No calendar records collected from StreamWriter");
-            }
+        // Hand off records, then commit the sync token and resume cursor
+        // together. A failed commit leaves both untouched so the next run
+        // re-fetches this window instead of skipping it.
+        let source_id = self.source_id.clone();
+        let sync_token = next_cursor.clone();
+        let records = collect_then_commit(
+            &self.stream_writer,
+            &self.db,
+            &self.source_id,
+            "calendar",
+            move |tx| {
+                Box::pin(async move {
+                    if backfill {
+                        return Ok(());
+                    }
+                    if let Some(ref token) = sync_token {
+                        tracing::info!("Saving sync token: {}", token);
+                        Self::save_sync_token_with_tx(&source_id, token, tx).await?;
+                    }
+                    save_resume_cursor(&mut **tx, &source_id, "calendar", capped_at.as_deref())
+                        .await
+                })
+            },
+        )
+        .await?;
 
-            collected
-        };
+        let completed_at = Utc::now();
 
         Ok(SyncResult {
             records_fetched,
@@ -231,6 +328,7 @@ impl GoogleCalendarStream {
             completed_at,
             records, // Return collected records for archive/transform
             archive_job_id: None,
+            records_deduplicated: 0,
         })
     }
 
@@ -239,6 +337,8 @@ impl GoogleCalendarStream {
         &self,
         calendar_id: &str,
         sync_token: &str,
+        limits: &StreamLimits,
+        already_fetched: usize,
     ) -> Result<EventsResponse> {
         let params = vec![("syncToken", sync_token)];
 
@@ -247,35 +347,49 @@ impl GoogleCalendarStream {
             .get_with_params(&format!("calendars/{calendar_id}/events"), &params)
             .await
         {
-            Ok(response) => Ok(response),
+            // Only full-sync page tokens are resumable; don't surface this one as a cap
+            Ok(response) => Ok(EventsResponse {
+                next_page_token: None,
+                ..response
+            }),
             Err(e) if GoogleClient::is_sync_token_error(&e) => {
                 // Sync token is invalid, clear it and do full sync
                 self.clear_sync_token().await?;
-                self.sync_full(calendar_id, None, None).await
+                self.sync_full(calendar_id, None, None, None, limits, already_fetched)
+                    .await
             }
             Err(e) => Err(e),
         }
     }
 
     /// Get all events (full sync) with pagination
+    ///
+    /// Starts from `page_token` when resuming. Stops once `already_fetched`
+    /// plus this calendar's events reach the per-run cap, returning the next
+    /// page token so the caller can save a resume cursor.
     async fn sync_full(
         &self,
         calendar_id: &str,
         start_date: Option<DateTime<Utc>>,
         end_date: Option<DateTime<Utc>>,
+        page_token: Option<String>,
+        limits: &StreamLimits,
+        already_fetched: usize,
     ) -> Result<EventsResponse> {
         // Calculate time bounds based on configuration or overrides
         let (config_min, config_max) = self.config.calculate_time_bounds();
-        let min_time_dt = start_date.or(config_min);
+        let min_time_dt = start_date.or_else(|| first_sync::bound(config_min));
         let max_time_dt = end_date.or(config_max);
 
+        let page_size = limits.page_size_or(self.config.max_events_per_sync);
+
         let mut all_events = Vec::new();
-        let mut page_token: Option<String> = None;
+        let mut page_token = page_token;
         let mut final_sync_token: Option<String> = None;
 
         loop {
             let mut params = vec![
-                ("maxResults", self.config.max_events_per_sync.to_string()),
+                ("maxResults", page_size.to_string()),
                 ("singleEvents", "true".to_string()),
                 ("orderBy", "updated".to_string()),
                 ("showDeleted", "false".to_string()),
@@ -316,6 +430,15 @@ impl GoogleCalendarStream {
                 break;
             }
 
+            if limits.is_reached(already_fetched + all_events.len()) {
+                tracing::info!(
+                    events_so_far = all_events.len(),
+                    calendar_id = %calendar_id,
+                    "Calendar sync reached per-run record cap"
+                );
+                break;
+            }
+
             // Only log every 100 events or the last page
             if all_events.len() % 100 == 0 || page_token.is_none() {
                 tracing::debug!(
@@ -335,16 +458,35 @@ impl GoogleCalendarStream {
         Ok(EventsResponse {
             items: all_events,
             next_sync_token: final_sync_token,
-            next_page_token: None, // All pages consumed
+            next_page_token: page_token, // Some only if the record cap cut the listing short
         })
     }
 
-    /// Insert or update an event within a transaction
-    async fn upsert_event_with_tx(
+    /// The calendar's default timezone, if Google will tell us
+    async fn get_calendar_timezone(&self, calendar_id: &str) -> Option<String> {
+        match self
+            .client
+            .get::<Calendar>(&format!("calendars/{calendar_id}"))
+            .await
+        {
+            Ok(calendar) => calendar.time_zone,
+            Err(e) => {
+                tracing::warn!(
+                    calendar_id = %calendar_id,
+                    error = %e,
+                    "Failed to fetch calendar timezone"
+                );
+                None
+            }
+        }
+    }
+
+    /// Buffer an event record in the StreamWriter
+    async fn write_event(
         &self,
         calendar_id: &str,
+        calendar_timezone: Option<&str>,
         event: &Event,
-        _tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
     ) -> Result<bool> {
         // Extract key fields - handle both datetime and date formats
         let start_time = if let Some(start) = event.start.as_ref() {
@@ -389,6 +531,11 @@ impl GoogleCalendarStream {
 
         let all_day = event.start.as_ref().and_then(|s| s.date.as_ref()).is_some();
 
+        let timezone = [&event.start, &event.end]
+            .into_iter()
+            .find_map(|time| time.as_ref()?.time_zone.as_deref());
+        let resolved_timezone = timezone.or(calendar_timezone);
+
         // Check if event is cancelled (we still store it but mark status)
         let status = event
             .status
@@ -445,7 +592,8 @@ impl GoogleCalendarStream {
             "start_time": start_time,
             "end_time": end_time,
             "all_day": all_day,
-            "timezone": event.start.as_ref().and_then(|s| s.time_zone.as_ref()),
+            "timezone": timezone,
+            "resolved_timezone": resolved_timezone,
             "organizer_email": organizer_email,
             "organizer_name": organizer_name,
             "creator_email": creator_email,
@@ -486,7 +634,7 @@ impl GoogleCalendarStream {
 
     /// Save the sync token to the database within a transaction
     async fn save_sync_token_with_tx(
-        &self,
+        source_id: &str,
         token: &str,
         tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
     ) -> Result<()> {
@@ -495,7 +643,7 @@ impl GoogleCalendarStream {
         )
         .bind(token)
         .bind(Utc::now())
-        .bind(&self.source_id)
+        .bind(source_id)
         .execute(&mut **tx)
         .await?;
 
@@ -550,9 +698,13 @@ impl PullStream for GoogleCalendarStream {
 #[cfg(test)]
 mod tests {
 
-    use crate::sources::base::SyncStrategy;
+    use super::*;
+    use crate::sources::base::mock_transport::testing::{
+        archive_to_memory, fixtures, migrated_pool,
+    };
+    use crate::sources::base::{SyncStrategy, TokenManager};
     use crate::sources::google::config::GoogleCalendarConfig;
-    use chrono::{Duration, Utc};
+    use chrono::Duration;
 
     #[test]
     fn test_config_time_bounds_lookback() {
@@ -593,4 +745,119 @@ mod tests {
         assert!(min.is_none(), "Full history should have no min bound");
         assert!(max.is_none(), "Full history should have no max bound");
     }
+
+    #[tokio::test]
+    async fn test_full_sync_against_fixtures() {
+        let db = migrated_pool().await;
+        let token_manager = Arc::new(TokenManager::new_insecure(db.clone()));
+        let mut stream = GoogleCalendarStream::new(
+            "src-calendar".to_string(),
+            db.clone(),
+            Arc::new(Mutex::new(StreamWriter::new())),
+            SourceAuth::oauth2("src-calendar".to_string(), token_manager),
+        );
+        let transport = fixtures("calendar");
+        stream
+            .client
+            .http_mut()
+            .set_mock_transport(transport.clone());
+
+        let result = stream.sync_internal(&SyncMode::FullRefresh).await.unwrap();
+        assert_eq!(result.records_written, 3);
+        assert_eq!(result.next_cursor.as_deref(), Some("sync-token-1"));
+
+        let records = result.records.unwrap();
+        let written =
+            archive_to_memory("google", "src-calendar", "calendar", &records, "start_time").await;
+        let mut summaries: Vec<&str> = written
+            .iter()
+            .map(|r| r["summary"].as_str().unwrap())
+            .collect();
+        summaries.sort();
+        assert_eq!(summaries, ["Dentist", "Holiday", "Planning"]);
+        let holiday = written.iter().find(|r| r["event_id"] == "evt3").unwrap();
+        assert_eq!(holiday["all_day"], true);
+
+        // An event without a zone of its own takes the calendar's
+        assert_eq!(holiday["timezone"], serde_json::Value::Null);
+        assert_eq!(holiday["resolved_timezone"], "America/New_York");
+        let planning = written.iter().find(|r| r["event_id"] == "evt1").unwrap();
+        assert_eq!(planning["timezone"], "Europe/London");
+        assert_eq!(planning["resolved_timezone"], "Europe/London");
+
+        // The second page was asked for with the first page's token, and the
+        // calendar itself once
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].param("pageToken"), None);
+        assert_eq!(requests[1].param("pageToken"), Some("page-2"));
+        assert_eq!(requests[2].path, "calendars/primary");
+    }
+
+    #[tokio::test]
+    async fn test_backfill_keeps_sync_token() {
+        let db = migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, name) VALUES ('src-calendar', 'google', 'Google')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO elt_stream_connections (id, source_connection_id, stream_name, table_name, last_sync_token, resume_cursor, config)
+             VALUES ('stream-calendar', 'src-calendar', 'calendar', 'stream_google_calendar', 'sync-token-0', 'primary	page-9', '{\"max_records_per_run\": 1}')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let token_manager = Arc::new(TokenManager::new_insecure(db.clone()));
+        let mut stream = GoogleCalendarStream::new(
+            "src-calendar".to_string(),
+            db.clone(),
+            Arc::new(Mutex::new(StreamWriter::new())),
+            SourceAuth::oauth2("src-calendar".to_string(), token_manager),
+        );
+        let transport = fixtures("calendar");
+        stream
+            .client
+            .http_mut()
+            .set_mock_transport(transport.clone());
+
+        let start_date = "2019-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let end_date = "2020-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let result = stream
+            .sync_internal(&SyncMode::backfill(start_date, end_date))
+            .await
+            .unwrap();
+        assert_eq!(result.records_written, 3);
+        assert_eq!(result.next_cursor.as_deref(), Some("sync-token-0"));
+
+        // The window is listed from its first page, not the saved resume
+        // cursor, through its last page despite the record cap
+        let requests = transport.requests();
+        assert_eq!(requests[0].param("pageToken"), None);
+        assert_eq!(requests[1].param("pageToken"), Some("page-2"));
+        assert_eq!(requests[0].param("syncToken"), None);
+        assert_eq!(
+            requests[0].param("timeMin"),
+            Some(start_date.to_rfc3339().as_str())
+        );
+        assert_eq!(
+            requests[0].param("timeMax"),
+            Some(end_date.to_rfc3339().as_str())
+        );
+
+        assert_eq!(
+            stream.get_last_sync_token().await.unwrap().as_deref(),
+            Some("sync-token-0")
+        );
+        assert_eq!(
+            load_resume_cursor(&db, "src-calendar", "calendar")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("primary\tpage-9")
+        );
+    }
 }
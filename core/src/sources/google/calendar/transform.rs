@@ -212,6 +212,7 @@ impl OntologyTransform for GoogleCalendarTransform {
                     "google_event_id": event_id,
                     "google_calendar_id": calendar_id,
                     "is_recurring": raw_json.get("recurringEventId").is_some(),
+                    "timezone": record.get("resolved_timezone"),
                     "google_raw": raw_json,
                     "source_connection_id": source_id,
                 });
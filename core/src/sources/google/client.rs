@@ -1,32 +1,52 @@
-//! Google API client - thin wrapper over OAuthHttpClient
+//! Google API client - thin wrapper over SourceHttpClient
 //!
-//! This client delegates all OAuth HTTP operations to the base OAuthHttpClient,
+//! This client delegates all HTTP operations to the base SourceHttpClient,
 //! providing Google-specific configuration and error handling.
 
-use serde::de::DeserializeOwned;
 use std::sync::Arc;
 
 use super::error_handler::GoogleErrorHandler;
 use crate::{
-    error::{Error, Result},
-    sources::base::{OAuthHttpClient, RetryConfig, TokenManager},
+    error::Error,
+    sources::base::{user_agent_for, RetryConfig, SourceClient, SourceHttpClient, TokenManager},
 };
 
+/// Attempts made at a request before a rate limit is given up on
+pub const DEFAULT_MAX_RETRIES: u32 = 5;
+
+/// Longest backoff between attempts
+const MAX_BACKOFF_MS: u64 = 60_000;
+
+/// Retry behaviour for Google requests
+///
+/// Rate limits (429, 503, and 403s whose body reports a rate limit) are
+/// retried as `Retry-After` asks, else with jittered exponential backoff, so
+/// parallel message fetches spread out.
+fn retry_config(max_retries: u32) -> RetryConfig {
+    RetryConfig {
+        max_retries,
+        max_backoff_ms: MAX_BACKOFF_MS,
+        jitter: 0.5,
+        ..RetryConfig::default()
+    }
+}
+
 /// Google API client with automatic token refresh and retry logic
 ///
-/// This is a thin wrapper that configures OAuthHttpClient for Google APIs.
+/// This is a thin wrapper that configures SourceHttpClient for Google APIs.
 /// All HTTP logic (retry, token refresh, error handling) is delegated to the base client.
 pub struct GoogleClient {
-    http: OAuthHttpClient,
+    http: SourceHttpClient,
 }
 
 impl GoogleClient {
     /// Create a new Google API client
     pub fn new(source_id: String, token_manager: Arc<TokenManager>) -> Self {
         Self {
-            http: OAuthHttpClient::new(source_id, token_manager)
+            http: SourceHttpClient::oauth(source_id, token_manager)
                 .with_base_url("https://www.googleapis.com")
-                .with_retry_config(RetryConfig::default())
+                .with_user_agent(&user_agent_for("google"))
+                .with_retry_config(retry_config(DEFAULT_MAX_RETRIES))
                 .with_error_handler(Box::new(GoogleErrorHandler)),
         }
     }
@@ -45,27 +65,21 @@ impl GoogleClient {
         version: &str,
     ) -> Self {
         Self {
-            http: OAuthHttpClient::new(source_id, token_manager)
+            http: SourceHttpClient::oauth(source_id, token_manager)
                 .with_base_url(&format!("https://www.googleapis.com/{api}/{version}"))
-                .with_retry_config(RetryConfig::default())
+                .with_user_agent(&user_agent_for("google"))
+                .with_retry_config(retry_config(DEFAULT_MAX_RETRIES))
                 .with_error_handler(Box::new(GoogleErrorHandler)),
         }
     }
 
-    /// Make an authenticated GET request
-    pub async fn get<T>(&self, path: &str) -> Result<T>
-    where
-        T: DeserializeOwned,
-    {
-        self.http.get(path).await
-    }
-
-    /// Make an authenticated GET request with query parameters
-    pub async fn get_with_params<T>(&self, path: &str, params: &[(&str, &str)]) -> Result<T>
-    where
-        T: DeserializeOwned,
-    {
-        self.http.get_with_params(path, params).await
+    /// Attempt each request up to `max_retries` times (default
+    /// `DEFAULT_MAX_RETRIES`) before giving up with `Error::RateLimited`
+    ///
+    /// Set from the stream config once it is loaded; at least one attempt is
+    /// always made.
+    pub fn set_max_retries(&mut self, max_retries: u32) {
+        self.http.set_retry_config(retry_config(max_retries.max(1)));
     }
 
     /// Check if error is a sync token error (410 response)
@@ -79,9 +93,68 @@ impl GoogleClient {
     }
 }
 
+impl SourceClient for GoogleClient {
+    fn http(&self) -> &SourceHttpClient {
+        &self.http
+    }
+
+    fn http_mut(&mut self) -> &mut SourceHttpClient {
+        &mut self.http
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Result;
+    use crate::sources::base::mock_transport::testing::serve;
+    use crate::sources::base::HttpAuth;
+
+    const RATE_LIMITED: &str = "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\nContent-Length: 9\r\nConnection: close\r\n\r\nslow down";
+    const UNAVAILABLE: &str = "HTTP/1.1 503 Service Unavailable\r\nRetry-After: 0\r\nContent-Length: 12\r\nConnection: close\r\n\r\nbackendError";
+    const USER_RATE_LIMITED: &str = "HTTP/1.1 403 Forbidden\r\nRetry-After: 0\r\nContent-Length: 21\r\nConnection: close\r\n\r\nuserRateLimitExceeded";
+    const FORBIDDEN: &str = "HTTP/1.1 403 Forbidden\r\nContent-Length: 17\r\nConnection: close\r\n\r\nPermission denied";
+    const OK: &str = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}";
+
+    fn test_client(url: &str, max_retries: u32) -> SourceHttpClient {
+        SourceHttpClient::new("test-source".to_string(), HttpAuth::None)
+            .with_base_url(url)
+            .with_retry_config(retry_config(max_retries))
+            .with_error_handler(Box::new(GoogleErrorHandler))
+    }
+
+    #[tokio::test]
+    async fn test_rate_limits_are_retried() {
+        let (url, server) = serve(vec![RATE_LIMITED, UNAVAILABLE, OK]).await;
+        let _: serde_json::Value = test_client(&url, 3).get("messages").await.unwrap();
+        assert_eq!(server.await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_only_rate_limit_403s_are_retried() {
+        let (url, server) = serve(vec![USER_RATE_LIMITED, OK]).await;
+        let _: serde_json::Value = test_client(&url, 3).get("messages").await.unwrap();
+        assert_eq!(server.await.unwrap().len(), 2);
+
+        let (url, server) = serve(vec![FORBIDDEN]).await;
+        let result: Result<serde_json::Value> = test_client(&url, 3).get("messages").await;
+        assert!(matches!(result, Err(Error::Http(_))));
+        assert_eq!(server.await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_after_retries_exhausted() {
+        let (url, server) = serve(vec![UNAVAILABLE, RATE_LIMITED]).await;
+        let result: Result<serde_json::Value> = test_client(&url, 2).get("messages").await;
+        assert!(matches!(
+            result,
+            Err(Error::RateLimited {
+                retry_after: Some(wait),
+                ..
+            }) if wait.is_zero()
+        ));
+        assert_eq!(server.await.unwrap().len(), 2);
+    }
 
     #[tokio::test]
     async fn test_client_creation() {
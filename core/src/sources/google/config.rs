@@ -1,5 +1,6 @@
 //! Configuration for Google sources
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::sources::base::SyncStrategy;
@@ -74,6 +75,15 @@ impl Default for GmailSyncMode {
     }
 }
 
+/// Where Gmail message bodies are kept
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GmailBodyStorage {
+    #[default]
+    Inline, // Bodies inlined in every record
+    External, // Bodies in a per-message storage object, records keep the key
+}
+
 /// Configuration for Google Gmail sync
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GoogleGmailConfig {
@@ -94,6 +104,29 @@ pub struct GoogleGmailConfig {
     #[serde(default = "default_fetch_body")]
     pub fetch_body: bool,
 
+    /// Fetch only an allowlist of headers plus labels (default: false)
+    ///
+    /// Requests `format=metadata`, so no message parts are downloaded at all:
+    /// records have null bodies, no attachment details, and `metadata_only`
+    /// set. Overrides `fetch_body`.
+    #[serde(default)]
+    pub metadata_only: bool,
+
+    /// Where fetched bodies go: inline in the record, or external objects
+    /// referenced by `body_key` (default: inline)
+    #[serde(default)]
+    pub body_storage: GmailBodyStorage,
+
+    /// Download attachments into storage objects listed in `attachment_keys`
+    /// (default: false). Needs fetched bodies; ignored with `metadata_only`.
+    #[serde(default)]
+    pub fetch_attachments: bool,
+
+    /// Largest attachment downloaded, in bytes (default: 25 MB); larger ones
+    /// keep only their metadata
+    #[serde(default = "default_max_attachment_size_bytes")]
+    pub max_attachment_size_bytes: u64,
+
     /// Strategy for full sync operations (default: 365 days lookback)
     #[serde(default)]
     pub sync_strategy: SyncStrategy,
@@ -102,8 +135,33 @@ pub struct GoogleGmailConfig {
     #[serde(default = "default_max_messages")]
     pub max_messages_per_sync: u32,
 
+    /// Messages fetched at once while syncing (default: 8)
+    #[serde(default = "default_fetch_concurrency")]
+    pub fetch_concurrency: usize,
+
     /// Query filter for messages (Gmail search syntax)
     pub query: Option<String>,
+
+    /// Deepest MIME part nesting followed when extracting content (default: 20)
+    ///
+    /// Messages nested deeper are kept with what was found above the limit
+    /// and flagged `content_truncated`.
+    #[serde(default = "default_max_mime_depth")]
+    pub max_mime_depth: usize,
+
+    /// Most MIME parts visited per message (default: 500); see `max_mime_depth`
+    #[serde(default = "default_max_mime_parts")]
+    pub max_mime_parts: usize,
+
+    /// Strip `+tag` suffixes from parsed addresses (default: false)
+    /// so `jane+news@example.com` and `jane@example.com` are the same correspondent.
+    #[serde(default)]
+    pub strip_plus_tags: bool,
+
+    /// Attempts made at each request before a rate limit or server error is
+    /// given up on (default: 5)
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
 }
 
 impl Default for GoogleGmailConfig {
@@ -113,9 +171,18 @@ impl Default for GoogleGmailConfig {
             include_spam_trash: false,
             sync_mode: GmailSyncMode::default(),
             fetch_body: default_fetch_body(),
+            metadata_only: false,
+            body_storage: GmailBodyStorage::default(),
+            fetch_attachments: false,
+            max_attachment_size_bytes: default_max_attachment_size_bytes(),
             sync_strategy: SyncStrategy::default(),
             max_messages_per_sync: default_max_messages(),
+            fetch_concurrency: default_fetch_concurrency(),
             query: None,
+            max_mime_depth: default_max_mime_depth(),
+            max_mime_parts: default_max_mime_parts(),
+            strip_plus_tags: false,
+            max_retries: default_max_retries(),
         }
     }
 }
@@ -123,10 +190,17 @@ impl Default for GoogleGmailConfig {
 impl GoogleGmailConfig {
     /// Build the query string for Gmail API based on sync strategy
     pub fn build_query(&self) -> String {
-        let mut parts = Vec::new();
-
-        // Add time bounds from strategy
         let (min_time, max_time) = self.sync_strategy.calculate_time_bounds();
+        self.build_query_between(min_time, max_time)
+    }
+
+    /// Build the query string for messages between `min_time` and `max_time`
+    pub fn build_query_between(
+        &self,
+        min_time: Option<DateTime<Utc>>,
+        max_time: Option<DateTime<Utc>>,
+    ) -> String {
+        let mut parts = Vec::new();
 
         if let Some(min) = min_time {
             let after = min.format("%Y/%m/%d").to_string();
@@ -160,6 +234,26 @@ fn default_max_messages() -> u32 {
     500
 }
 
+fn default_fetch_concurrency() -> usize {
+    8
+}
+
+fn default_max_attachment_size_bytes() -> u64 {
+    super::gmail::attachments::DEFAULT_MAX_ATTACHMENT_SIZE_BYTES
+}
+
+fn default_max_mime_depth() -> usize {
+    super::gmail::mime::DEFAULT_MAX_MIME_DEPTH
+}
+
+fn default_max_mime_parts() -> usize {
+    super::gmail::mime::DEFAULT_MAX_MIME_PARTS
+}
+
+fn default_max_retries() -> u32 {
+    super::client::DEFAULT_MAX_RETRIES
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -213,7 +307,11 @@ mod tests {
         assert_eq!(config.label_ids, Vec::<String>::new()); // Empty = sync all mail
         assert!(!config.include_spam_trash);
         assert!(config.fetch_body);
+        assert!(!config.metadata_only);
+        assert_eq!(config.body_storage, GmailBodyStorage::Inline);
         assert_eq!(config.max_messages_per_sync, 500);
+        assert!(!config.strip_plus_tags);
+        assert_eq!(config.max_retries, 5);
     }
 
     #[test]
@@ -238,4 +336,12 @@ mod tests {
         assert_eq!(config.label_ids, deserialized.label_ids);
         assert_eq!(config.fetch_body, deserialized.fetch_body);
     }
+
+    #[test]
+    fn test_gmail_body_storage() {
+        let config =
+            GoogleGmailConfig::from_json(&serde_json::json!({"body_storage": "external"})).unwrap();
+        assert_eq!(config.body_storage, GmailBodyStorage::External);
+        assert!(GoogleGmailConfig::from_json(&serde_json::json!({"body_storage": "s3"})).is_err());
+    }
 }
@@ -2,7 +2,14 @@
 //!
 //! Implements custom error classification for Google APIs, including
 //! special handling for sync token errors (410) used in Calendar and Gmail APIs.
+//!
+//! 429 and 503 are Google asking us to slow down, as is a 403 whose body
+//! reports `rateLimitExceeded`/`userRateLimitExceeded` (how Gmail usually
+//! throttles) or `quotaExceeded`: they are waited out as the `Retry-After`
+//! header asks, else with backoff, and surface as `Error::RateLimited` once
+//! the client's retries are used up.
 
+use crate::error::Error;
 use crate::sources::base::error_handler::{ErrorClass, ErrorHandler};
 use reqwest::StatusCode;
 
@@ -10,7 +17,7 @@ use reqwest::StatusCode;
 ///
 /// Handles Google-specific error cases:
 /// - 410 (Gone) errors indicate invalid sync tokens for Calendar/Gmail
-/// - 403 errors can indicate quota/permission issues
+/// - 403 errors can indicate rate limit/permission issues
 /// - Retry logic follows Google's best practices
 pub struct GoogleErrorHandler;
 
@@ -30,8 +37,9 @@ impl ErrorHandler for GoogleErrorHandler {
             // Don't retry 410 (sync token invalid) - caller should handle
             410 => false,
 
-            // Don't retry quota exceeded
-            403 => false,
+            // Retried only when `classify_error` finds a rate limit in the
+            // body; other 403s (permissions) are client errors
+            403 => true,
 
             // Retry server errors
             500..=599 => true,
@@ -46,6 +54,7 @@ impl ErrorHandler for GoogleErrorHandler {
             401 => ErrorClass::AuthError,
             429 => ErrorClass::RateLimit,
             410 => ErrorClass::SyncTokenError,
+            503 => ErrorClass::RateLimit,
             403 => {
                 // Check if it's a quota error
                 if body.contains("quotaExceeded") || body.contains("rateLimitExceeded") {
@@ -71,6 +80,16 @@ impl ErrorHandler for GoogleErrorHandler {
         }
         false
     }
+
+    fn map_error(&self, status: StatusCode, body: &str) -> Error {
+        if self.classify_error(status, body) == ErrorClass::RateLimit {
+            return Error::RateLimited {
+                message: format!("Google ({status}): {body}"),
+                retry_after: None,
+            };
+        }
+        Error::Http(format!("API error ({status}): {body}"))
+    }
 }
 
 #[cfg(test)]
@@ -114,8 +133,8 @@ mod tests {
         // Should NOT retry 410 (sync token errors) - let caller handle
         assert!(!handler.should_retry(StatusCode::GONE, 0, 3));
 
-        // Should NOT retry quota errors
-        assert!(!handler.should_retry(StatusCode::FORBIDDEN, 0, 3));
+        // 403 is left to classification: only rate limits are retried
+        assert!(handler.should_retry(StatusCode::FORBIDDEN, 0, 3));
 
         // Should NOT retry when max retries reached
         assert!(!handler.should_retry(StatusCode::INTERNAL_SERVER_ERROR, 3, 3));
@@ -164,5 +183,19 @@ mod tests {
             handler.classify_error(StatusCode::FORBIDDEN, "Permission denied"),
             ErrorClass::ClientError
         );
+
+        // 503 is Google asking us to back off
+        assert_eq!(
+            handler.classify_error(StatusCode::SERVICE_UNAVAILABLE, ""),
+            ErrorClass::RateLimit
+        );
+        assert!(matches!(
+            handler.map_error(StatusCode::SERVICE_UNAVAILABLE, "backendError"),
+            Error::RateLimited { .. }
+        ));
+        assert!(matches!(
+            handler.map_error(StatusCode::FORBIDDEN, "Permission denied"),
+            Error::Http(_)
+        ));
     }
 }
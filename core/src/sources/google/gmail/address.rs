@@ -0,0 +1,301 @@
+//! Email address parsing and normalization for Gmail headers
+//!
+//! Parses `From`/`To`/`Cc`/`Bcc` header values into `(email, display name)`
+//! pairs. Only the parsed fields are normalized; raw headers are stored
+//! untouched alongside them.
+
+/// Parse a single mailbox (`Name <local@domain>` or `local@domain`)
+///
+/// Returns `(email, name)`. The email is `None` when the value isn't a
+/// usable address (no `@`, empty local part or domain), but a display name
+/// is still returned if one was present.
+pub(crate) fn parse_email_address(
+    address: Option<&str>,
+    strip_plus_tags: bool,
+) -> (Option<String>, Option<String>) {
+    let Some(addr) = address.map(str::trim).filter(|a| !a.is_empty()) else {
+        return (None, None);
+    };
+
+    let (raw_email, raw_name) = match (find_unquoted(addr, '<'), addr.rfind('>')) {
+        (Some(start), Some(end)) if end > start => (&addr[start + 1..end], &addr[..start]),
+        _ => match (addr.find('('), addr.rfind(')')) {
+            // `local@domain (Name)` comment form
+            (Some(start), Some(end)) if end > start => (&addr[..start], &addr[start + 1..end]),
+            _ => (addr, ""),
+        },
+    };
+
+    let email = normalize_email(raw_email, strip_plus_tags);
+    let name = unquote_name(raw_name);
+
+    (email, name)
+}
+
+/// Parse a comma-separated address list into parallel email and name vectors
+///
+/// Commas inside quoted display names (`"Doe, Jane" <jane@x.com>`) don't split
+/// entries. Malformed entries are skipped and duplicates collapse into the
+/// first occurrence, keeping a display name from a later duplicate if the
+/// first had none.
+pub(crate) fn parse_email_list(
+    addresses: Option<&str>,
+    strip_plus_tags: bool,
+) -> (Vec<String>, Vec<String>) {
+    let mut emails: Vec<String> = Vec::new();
+    let mut names: Vec<String> = Vec::new();
+
+    if let Some(addr_list) = addresses {
+        for addr in split_address_list(addr_list) {
+            let (email, name) = parse_email_address(Some(addr), strip_plus_tags);
+            let Some(email) = email else { continue };
+
+            if let Some(idx) = emails.iter().position(|e| e == &email) {
+                if names[idx].is_empty() {
+                    names[idx] = name.unwrap_or_default();
+                }
+                continue;
+            }
+
+            emails.push(email);
+            names.push(name.unwrap_or_default());
+        }
+    }
+
+    (emails, names)
+}
+
+/// Parsed sender and recipients of a message
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct MessageAddresses {
+    pub from_email: Option<String>,
+    pub from_name: Option<String>,
+    pub to: (Vec<String>, Vec<String>),
+    pub cc: (Vec<String>, Vec<String>),
+    pub bcc: (Vec<String>, Vec<String>),
+}
+
+impl MessageAddresses {
+    /// Parse and normalize the `From`/`To`/`Cc`/`Bcc` header values
+    ///
+    /// A recipient listed in several fields is kept only in the first.
+    pub(crate) fn parse(
+        from: Option<&str>,
+        to: Option<&str>,
+        cc: Option<&str>,
+        bcc: Option<&str>,
+        strip_plus_tags: bool,
+    ) -> Self {
+        let (from_email, from_name) = parse_email_address(from, strip_plus_tags);
+        let to = parse_email_list(to, strip_plus_tags);
+        let mut cc = parse_email_list(cc, strip_plus_tags);
+        let mut bcc = parse_email_list(bcc, strip_plus_tags);
+        remove_seen(&mut cc, &to.0);
+        remove_seen(&mut bcc, &to.0);
+        remove_seen(&mut bcc, &cc.0);
+
+        Self {
+            from_email,
+            from_name,
+            to,
+            cc,
+            bcc,
+        }
+    }
+}
+
+/// Remove addresses from `list` that already appear in `seen`
+///
+/// Used so a correspondent on both `To` and `Cc` is only recorded once, in the
+/// earliest list.
+pub(crate) fn remove_seen(list: &mut (Vec<String>, Vec<String>), seen: &[String]) {
+    let (emails, names) = list;
+    let mut i = 0;
+    while i < emails.len() {
+        if seen.contains(&emails[i]) {
+            emails.remove(i);
+            names.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+/// Normalize an email address
+///
+/// Lowercases the domain (domains are case-insensitive; local parts are not,
+/// so they're left as-is) and optionally drops a `+tag` suffix from the local
+/// part.
+fn normalize_email(raw: &str, strip_plus_tags: bool) -> Option<String> {
+    let raw = raw.trim().trim_matches('"');
+    let at = raw.rfind('@')?;
+    let (local, domain) = (&raw[..at], &raw[at + 1..]);
+
+    if local.is_empty() || domain.is_empty() || local.contains(char::is_whitespace) {
+        return None;
+    }
+
+    let local = if strip_plus_tags {
+        local.split('+').next().filter(|l| !l.is_empty())?
+    } else {
+        local
+    };
+
+    Some(format!("{}@{}", local, domain.to_lowercase()))
+}
+
+/// Trim and unquote a display name, returning `None` if empty
+fn unquote_name(raw: &str) -> Option<String> {
+    let mut name = raw.trim();
+    if name.len() >= 2 && name.starts_with('"') && name.ends_with('"') {
+        name = &name[1..name.len() - 1];
+    }
+    let name = name.replace("\\\"", "\"").trim().to_string();
+
+    if name.is_empty() {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+/// Split an address list on commas that aren't inside quotes or angle brackets
+fn split_address_list(list: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut in_quotes = false;
+    let mut in_angle = false;
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (i, c) in list.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            '<' if !in_quotes => in_angle = true,
+            '>' if !in_quotes => in_angle = false,
+            ',' if !in_quotes && !in_angle => {
+                parts.push(list[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(list[start..].trim());
+
+    parts.into_iter().filter(|p| !p.is_empty()).collect()
+}
+
+/// Find the first occurrence of `target` outside a quoted string
+fn find_unquoted(s: &str, target: char) -> Option<usize> {
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            c if c == target && !in_quotes => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_angle_brackets() {
+        let (email, name) = parse_email_address(Some("Jane Doe <Jane@Example.COM>"), false);
+        assert_eq!(email.as_deref(), Some("Jane@example.com"));
+        assert_eq!(name.as_deref(), Some("Jane Doe"));
+
+        let (email, name) = parse_email_address(Some("<bob@example.com>"), false);
+        assert_eq!(email.as_deref(), Some("bob@example.com"));
+        assert_eq!(name, None);
+    }
+
+    #[test]
+    fn test_quoted_names() {
+        let (email, name) = parse_email_address(Some(r#""Doe, Jane" <jane@example.com>"#), false);
+        assert_eq!(email.as_deref(), Some("jane@example.com"));
+        assert_eq!(name.as_deref(), Some("Doe, Jane"));
+
+        let (email, name) =
+            parse_email_address(Some(r#""Jane \"JD\" Doe" <jane@example.com>"#), false);
+        assert_eq!(email.as_deref(), Some("jane@example.com"));
+        assert_eq!(name.as_deref(), Some(r#"Jane "JD" Doe"#));
+
+        // A '<' inside the quoted name must not be taken as the address start
+        let (email, name) = parse_email_address(Some(r#""a<b" <ab@example.com>"#), false);
+        assert_eq!(email.as_deref(), Some("ab@example.com"));
+        assert_eq!(name.as_deref(), Some("a<b"));
+    }
+
+    #[test]
+    fn test_bare_and_comment_forms() {
+        let (email, name) = parse_email_address(Some("  bob@EXAMPLE.com "), false);
+        assert_eq!(email.as_deref(), Some("bob@example.com"));
+        assert_eq!(name, None);
+
+        let (email, name) = parse_email_address(Some("bob@example.com (Bob Smith)"), false);
+        assert_eq!(email.as_deref(), Some("bob@example.com"));
+        assert_eq!(name.as_deref(), Some("Bob Smith"));
+    }
+
+    #[test]
+    fn test_plus_tag_stripping() {
+        let (email, _) = parse_email_address(Some("jane+news@example.com"), false);
+        assert_eq!(email.as_deref(), Some("jane+news@example.com"));
+
+        let (email, _) = parse_email_address(Some("jane+news@example.com"), true);
+        assert_eq!(email.as_deref(), Some("jane@example.com"));
+
+        // Nothing left once the tag is removed
+        let (email, _) = parse_email_address(Some("+tag@example.com"), true);
+        assert_eq!(email, None);
+    }
+
+    #[test]
+    fn test_malformed_addresses() {
+        assert_eq!(parse_email_address(None, false), (None, None));
+        assert_eq!(parse_email_address(Some("   "), false), (None, None));
+        assert_eq!(parse_email_address(Some("not an email"), false).0, None);
+        assert_eq!(parse_email_address(Some("@example.com"), false).0, None);
+        assert_eq!(parse_email_address(Some("jane@"), false).0, None);
+
+        let (email, name) = parse_email_address(Some("Jane Doe <>"), false);
+        assert_eq!(email, None);
+        assert_eq!(name.as_deref(), Some("Jane Doe"));
+
+        let (emails, _) = parse_email_list(Some("undisclosed-recipients:;"), false);
+        assert!(emails.is_empty());
+    }
+
+    #[test]
+    fn test_list_parsing_and_dedupe() {
+        let (emails, names) = parse_email_list(
+            Some(
+                r#""Doe, Jane" <jane@example.com>, bob@example.com, Jane <jane@EXAMPLE.com>, bogus, Bob <bob@example.com>"#,
+            ),
+            false,
+        );
+        assert_eq!(emails, vec!["jane@example.com", "bob@example.com"]);
+        // First display name wins; a later duplicate fills in a missing one
+        assert_eq!(names, vec!["Doe, Jane", "Bob"]);
+
+        let (emails, _) = parse_email_list(Some("jane+a@example.com, jane+b@example.com"), true);
+        assert_eq!(emails, vec!["jane@example.com"]);
+    }
+
+    #[test]
+    fn test_remove_seen() {
+        let to = parse_email_list(Some("a@example.com, b@example.com"), false);
+        let mut cc = parse_email_list(Some("B <b@example.com>, c@example.com"), false);
+        remove_seen(&mut cc, &to.0);
+        assert_eq!(cc.0, vec!["c@example.com"]);
+        assert_eq!(cc.1, vec![""]);
+    }
+}
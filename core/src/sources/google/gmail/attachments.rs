@@ -0,0 +1,141 @@
+//! Download of Gmail attachment bytes
+//!
+//! With `fetch_attachments`, each attachment of a message is downloaded
+//! (from `users/me/messages/{id}/attachments/{attachmentId}`, unless Gmail
+//! inlined it in the part) and written to its own object under the stream's
+//! prefix. The record lists the object keys in `attachment_keys`, in the
+//! same order as `attachment_names`; attachments larger than
+//! `max_attachment_size_bytes`, or that couldn't be fetched, have a null key.
+//! Attachment bytes can't be redacted: when the stream's redaction rules
+//! cover `attachment_keys`, nothing is downloaded.
+
+use crate::error::{Error, Result};
+use crate::sources::base::SourceClient;
+use crate::sources::google::client::GoogleClient;
+use crate::sources::google::types::MessageBody;
+use crate::storage::{models::StreamKey, Storage, UploadRetry};
+
+use super::mime::{decode_base64url, Attachment};
+
+/// Default largest attachment downloaded (Gmail's own limit is 25 MB)
+pub const DEFAULT_MAX_ATTACHMENT_SIZE_BYTES: u64 = 25 * 1024 * 1024;
+
+/// Storage key of one attachment of a message
+///
+/// Pattern: `streams/google/{source_id}/gmail/attachments/{message_id}/{position}-{filename}`.
+/// The position keeps attachments sharing a filename apart; the filename
+/// has path separators and control characters replaced.
+pub fn attachment_key(
+    source_id: &str,
+    message_id: &str,
+    position: usize,
+    filename: &str,
+) -> String {
+    let filename: String = filename
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    format!(
+        "{}attachments/{}/{}-{}",
+        StreamKey::stream_prefix("google", source_id, "gmail"),
+        message_id,
+        position,
+        filename
+    )
+}
+
+/// Download and store each of a message's attachments
+///
+/// Returns one key per attachment, `None` for those skipped. A failed
+/// download skips the attachment; a storage failure that outlasts its
+/// retries fails the message, like an external body would.
+pub async fn store_attachments(
+    client: &GoogleClient,
+    storage: &Storage,
+    source_id: &str,
+    message_id: &str,
+    attachments: &[Attachment],
+    max_size_bytes: u64,
+) -> Result<Vec<Option<String>>> {
+    let mut keys = Vec::with_capacity(attachments.len());
+    for (position, attachment) in attachments.iter().enumerate() {
+        if u64::try_from(attachment.size).unwrap_or(0) > max_size_bytes {
+            tracing::debug!(
+                message_id,
+                filename = %attachment.filename,
+                size = attachment.size,
+                "Skipping Gmail attachment over the size limit"
+            );
+            keys.push(None);
+            continue;
+        }
+
+        let bytes = match fetch_bytes(client, message_id, attachment).await {
+            Ok(bytes) if bytes.len() as u64 > max_size_bytes => {
+                keys.push(None);
+                continue;
+            }
+            Ok(bytes) => bytes,
+            Err(e) => {
+                tracing::warn!(
+                    error = %e,
+                    message_id,
+                    filename = %attachment.filename,
+                    "Failed to fetch Gmail attachment"
+                );
+                keys.push(None);
+                continue;
+            }
+        };
+
+        let key = attachment_key(source_id, message_id, position, &attachment.filename);
+        storage
+            .upload_with_retry(&key, bytes, UploadRetry::from_env())
+            .await?;
+        keys.push(Some(key));
+    }
+    Ok(keys)
+}
+
+/// The attachment's bytes, from the part or from the attachments endpoint
+async fn fetch_bytes(
+    client: &GoogleClient,
+    message_id: &str,
+    attachment: &Attachment,
+) -> Result<Vec<u8>> {
+    let data = match (&attachment.data, &attachment.attachment_id) {
+        (Some(data), _) => data.clone(),
+        (None, Some(attachment_id)) => {
+            let body: MessageBody = client
+                .get(&format!(
+                    "users/me/messages/{message_id}/attachments/{attachment_id}"
+                ))
+                .await?;
+            body.data.unwrap_or_default()
+        }
+        (None, None) => return Err(Error::Other("Attachment has no data or id".to_string())),
+    };
+    decode_base64url(&data)
+        .ok_or_else(|| Error::Other("Attachment data is not valid base64url".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attachment_key() {
+        assert_eq!(
+            attachment_key("source_1", "msg_1", 0, "report.pdf"),
+            "streams/google/source_1/gmail/attachments/msg_1/0-report.pdf"
+        );
+        assert_eq!(
+            attachment_key("source_1", "msg_1", 2, "../etc/pass\nwd"),
+            "streams/google/source_1/gmail/attachments/msg_1/2-.._etc_pass_wd"
+        );
+    }
+}
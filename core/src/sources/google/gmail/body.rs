@@ -0,0 +1,189 @@
+//! External storage of Gmail message bodies
+//!
+//! With `body_storage: external`, a message's `body_plain`/`body_html` are
+//! written to their own object under the stream's prefix, and the archived
+//! record keeps only the object's `body_key` and the snippet. The base64 body
+//! data is also dropped from `raw_message`, which would otherwise duplicate
+//! the bodies. `resolve_body` puts the bodies back for consumers that need
+//! them (the Gmail transform, stream object previews, lake object downloads).
+//!
+//! The stream's redaction rules for `body_plain`/`body_html` are applied
+//! before the object is written; bodies redacted away entirely leave no
+//! object and no `body_key`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::Result;
+use crate::sources::google::types::{Message, MessagePart};
+use crate::storage::{models::StreamKey, Storage, UploadRetry};
+
+/// Longest snippet derived from the plain body when Gmail sent none
+const SNIPPET_CHARS: usize = 200;
+
+/// Bodies of one message, as stored under its `body_key`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredBody {
+    pub body_plain: Option<String>,
+    pub body_html: Option<String>,
+}
+
+/// Storage key of a message's bodies
+///
+/// Pattern: `streams/google/{source_id}/gmail/bodies/{message_id}.json`
+pub fn body_key(source_id: &str, message_id: &str) -> String {
+    format!(
+        "{}bodies/{}.json",
+        StreamKey::stream_prefix("google", source_id, "gmail"),
+        message_id
+    )
+}
+
+/// Snippet for a record whose bodies are stored externally
+pub fn snippet_from(plain: &str) -> String {
+    plain
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(SNIPPET_CHARS)
+        .collect()
+}
+
+/// Upload a message's bodies and return their key
+///
+/// Retried while storage fails; if it stays down the sync fails with
+/// `Error::ProviderUnavailable` and its cursor is left where it was.
+pub async fn store_body(
+    storage: &Storage,
+    source_id: &str,
+    message_id: &str,
+    body: &StoredBody,
+) -> Result<String> {
+    let key = body_key(source_id, message_id);
+    let bytes = serde_json::to_vec(body)?;
+    storage
+        .upload_with_retry(&key, bytes, UploadRetry::from_env())
+        .await?;
+    Ok(key)
+}
+
+/// Drop body data of the text parts (and the raw message) from a message
+///
+/// Attachment parts are left alone; their data is never inlined.
+pub fn strip_body_data(message: &mut Message) {
+    fn strip_part(part: &mut MessagePart) {
+        let is_attachment = part.filename.as_deref().is_some_and(|f| !f.is_empty());
+        let is_text = matches!(part.mime_type.as_deref(), Some("text/plain" | "text/html"));
+        if is_text && !is_attachment {
+            if let Some(body) = part.body.as_mut() {
+                body.data = None;
+            }
+        }
+        for sub_part in part.parts.iter_mut().flatten() {
+            strip_part(sub_part);
+        }
+    }
+
+    message.raw = None;
+    if let Some(payload) = message.payload.as_mut() {
+        strip_part(payload);
+    }
+}
+
+/// Fill in `body_plain`/`body_html` of a record stored with `body_key`
+///
+/// Returns whether the record was resolved. Records with inline bodies, or
+/// without a key, are left untouched.
+pub async fn resolve_body(storage: &Storage, record: &mut Value) -> Result<bool> {
+    let Some(key) = record.get("body_key").and_then(|v| v.as_str()) else {
+        return Ok(false);
+    };
+    let has_inline = ["body_plain", "body_html"]
+        .iter()
+        .any(|field| record.get(*field).is_some_and(|v| !v.is_null()));
+    if has_inline {
+        return Ok(false);
+    }
+
+    let body: StoredBody = storage.download_json(key).await?;
+    record["body_plain"] = body.body_plain.into();
+    record["body_html"] = body.body_html.into();
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_store_and_resolve_body() {
+        let storage = Storage::in_memory();
+        let body = StoredBody {
+            body_plain: Some("Hello there".to_string()),
+            body_html: Some("<p>Hello there</p>".to_string()),
+        };
+
+        let key = store_body(&storage, "source_1", "msg_1", &body)
+            .await
+            .unwrap();
+        assert_eq!(key, "streams/google/source_1/gmail/bodies/msg_1.json");
+
+        let mut record = json!({
+            "message_id": "msg_1",
+            "snippet": "Hello there",
+            "body_key": key,
+            "body_plain": null,
+            "body_html": null,
+        });
+        assert!(resolve_body(&storage, &mut record).await.unwrap());
+        assert_eq!(record["body_plain"], "Hello there");
+        assert_eq!(record["body_html"], "<p>Hello there</p>");
+
+        // Inline records need no download
+        let mut inline = json!({"message_id": "msg_2", "body_plain": "inline"});
+        assert!(!resolve_body(&storage, &mut inline).await.unwrap());
+
+        // A missing body object is an error
+        let mut missing = json!({"body_key": body_key("source_1", "msg_3")});
+        assert!(resolve_body(&storage, &mut missing).await.is_err());
+    }
+
+    #[test]
+    fn test_strip_body_data() {
+        let mut message: Message = serde_json::from_value(json!({
+            "id": "msg_1",
+            "threadId": "thread_1",
+            "raw": "cmF3",
+            "payload": {
+                "mimeType": "multipart/mixed",
+                "parts": [
+                    {"mimeType": "text/plain", "body": {"size": 5, "data": "aGVsbG8"}},
+                    {"mimeType": "text/html", "body": {"size": 12, "data": "PHA-aGVsbG88L3A-"}},
+                    {"mimeType": "text/plain", "filename": "notes.txt", "body": {"size": 3, "data": "eHl6"}}
+                ]
+            }
+        }))
+        .unwrap();
+
+        strip_body_data(&mut message);
+        assert!(message.raw.is_none());
+        let parts = message.payload.unwrap().parts.unwrap();
+        assert!(parts[0].body.as_ref().unwrap().data.is_none());
+        assert!(parts[1].body.as_ref().unwrap().data.is_none());
+        assert_eq!(
+            parts[2].body.as_ref().unwrap().data.as_deref(),
+            Some("eHl6")
+        );
+    }
+
+    #[test]
+    fn test_snippet_from() {
+        assert_eq!(
+            snippet_from("Hi  there,\n\nsee below"),
+            "Hi there, see below"
+        );
+        assert_eq!(snippet_from(&"a".repeat(500)).len(), SNIPPET_CHARS);
+    }
+}
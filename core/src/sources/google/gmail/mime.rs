@@ -0,0 +1,255 @@
+//! Bounded extraction of Gmail message parts
+//!
+//! Walks a message payload for its first `text/plain` and `text/html` bodies
+//! and its attachments. The walk stops at a maximum nesting depth and a
+//! maximum number of parts, so a pathologically nested or oversized MIME tree
+//! can't exhaust the stack or stall a sync. A message that hits either limit
+//! keeps what was extracted up to that point and is flagged
+//! `content_truncated`.
+
+use base64::Engine as _;
+
+use crate::sources::google::types::MessagePart;
+
+/// Default deepest part nesting followed
+pub const DEFAULT_MAX_MIME_DEPTH: usize = 20;
+
+/// Default most parts visited per message
+pub const DEFAULT_MAX_MIME_PARTS: usize = 500;
+
+/// How far extraction walks a message's part tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MimeLimits {
+    /// Deepest nesting followed; the payload itself is depth 0
+    pub max_depth: usize,
+    /// Most parts visited, counting the payload
+    pub max_parts: usize,
+}
+
+impl Default for MimeLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: DEFAULT_MAX_MIME_DEPTH,
+            max_parts: DEFAULT_MAX_MIME_PARTS,
+        }
+    }
+}
+
+/// An attachment part of a message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    pub mime_type: String,
+    pub filename: String,
+    pub size: i32,
+    /// Id to download the bytes by, when Gmail didn't inline them
+    pub attachment_id: Option<String>,
+    /// Inlined bytes, base64url encoded
+    pub data: Option<String>,
+}
+
+/// Text bodies and attachments found in a message
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MessageContent {
+    pub body_plain: Option<String>,
+    pub body_html: Option<String>,
+    pub attachments: Vec<Attachment>,
+    /// A limit was hit and some parts were not looked at
+    pub truncated: bool,
+}
+
+/// Extract bodies and attachments from a payload, within `limits`
+///
+/// Parts are visited depth-first in document order, so the first text part
+/// of each type wins as before.
+pub fn extract_content(payload: Option<&MessagePart>, limits: MimeLimits) -> MessageContent {
+    let mut content = MessageContent::default();
+    let mut stack: Vec<(&MessagePart, usize)> = payload.map(|p| (p, 0)).into_iter().collect();
+    let mut visited = 0;
+
+    while let Some((part, depth)) = stack.pop() {
+        if visited == limits.max_parts {
+            content.truncated = true;
+            break;
+        }
+        visited += 1;
+
+        if visit_part(part, &mut content) {
+            continue;
+        }
+
+        if let Some(parts) = part.parts.as_ref().filter(|p| !p.is_empty()) {
+            if depth == limits.max_depth {
+                content.truncated = true;
+                continue;
+            }
+            stack.extend(parts.iter().rev().map(|p| (p, depth + 1)));
+        }
+    }
+
+    content
+}
+
+/// Record one part; returns true for attachments, whose children are skipped
+fn visit_part(part: &MessagePart, content: &mut MessageContent) -> bool {
+    if let Some(filename) = part.filename.as_ref().filter(|f| !f.is_empty()) {
+        let mime_type = part
+            .mime_type
+            .clone()
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+        content.attachments.push(Attachment {
+            mime_type,
+            filename: filename.clone(),
+            size: part.body.as_ref().map(|b| b.size).unwrap_or(0),
+            attachment_id: part.body.as_ref().and_then(|b| b.attachment_id.clone()),
+            data: part.body.as_ref().and_then(|b| b.data.clone()),
+        });
+        return true;
+    }
+
+    let slot = match part.mime_type.as_deref() {
+        Some("text/plain") => &mut content.body_plain,
+        Some("text/html") => &mut content.body_html,
+        _ => return false,
+    };
+    if slot.is_none() {
+        *slot = part
+            .body
+            .as_ref()
+            .and_then(|b| b.data.as_deref())
+            .and_then(decode_base64url)
+            .and_then(|decoded| String::from_utf8(decoded).ok());
+    }
+    false
+}
+
+/// Decode Gmail's base64url part data
+///
+/// Gmail leaves the padding off most data but not all of it, and large
+/// attachment data can arrive wrapped over several lines; both are accepted,
+/// as are standard base64 `+` and `/`.
+pub fn decode_base64url(data: &str) -> Option<Vec<u8>> {
+    let normalized: String = data
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && *c != '=')
+        .map(|c| match c {
+            '+' => '-',
+            '/' => '_',
+            c => c,
+        })
+        .collect();
+    base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(normalized)
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::google::types::MessageBody;
+
+    fn text_part(mime_type: &str, text: &str) -> MessagePart {
+        MessagePart {
+            part_id: None,
+            mime_type: Some(mime_type.to_string()),
+            filename: None,
+            headers: None,
+            body: Some(MessageBody {
+                attachment_id: None,
+                size: text.len() as i32,
+                data: Some(base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(text)),
+            }),
+            parts: None,
+        }
+    }
+
+    fn multipart(parts: Vec<MessagePart>) -> MessagePart {
+        MessagePart {
+            part_id: None,
+            mime_type: Some("multipart/mixed".to_string()),
+            filename: None,
+            headers: None,
+            body: None,
+            parts: Some(parts),
+        }
+    }
+
+    #[test]
+    fn test_extracts_first_text_bodies_and_attachments() {
+        let mut attachment = text_part("application/pdf", "%PDF");
+        attachment.filename = Some("report.pdf".to_string());
+        let payload = multipart(vec![
+            multipart(vec![
+                text_part("text/plain", "hello"),
+                text_part("text/html", "<p>hello</p>"),
+            ]),
+            text_part("text/plain", "second"),
+            attachment,
+        ]);
+
+        let content = extract_content(Some(&payload), MimeLimits::default());
+        assert_eq!(content.body_plain.as_deref(), Some("hello"));
+        assert_eq!(content.body_html.as_deref(), Some("<p>hello</p>"));
+        assert_eq!(
+            content.attachments,
+            vec![Attachment {
+                mime_type: "application/pdf".to_string(),
+                filename: "report.pdf".to_string(),
+                size: 4,
+                attachment_id: None,
+                data: Some("JVBERg".to_string()),
+            }]
+        );
+        assert!(!content.truncated);
+    }
+
+    #[test]
+    fn test_deep_nesting_is_truncated() {
+        let mut payload = text_part("text/plain", "buried");
+        for _ in 0..50 {
+            payload = multipart(vec![payload]);
+        }
+
+        let content = extract_content(Some(&payload), MimeLimits::default());
+        assert!(content.truncated);
+        assert_eq!(content.body_plain, None);
+
+        let limits = MimeLimits {
+            max_depth: 50,
+            ..Default::default()
+        };
+        let content = extract_content(Some(&payload), limits);
+        assert!(!content.truncated);
+        assert_eq!(content.body_plain.as_deref(), Some("buried"));
+    }
+
+    #[test]
+    fn test_part_count_is_capped() {
+        let payload = multipart((0..1000).map(|_| text_part("image/png", "")).collect());
+        let limits = MimeLimits {
+            max_parts: 10,
+            ..Default::default()
+        };
+
+        let content = extract_content(Some(&payload), limits);
+        assert!(content.truncated);
+        assert_eq!(extract_content(None, limits), MessageContent::default());
+    }
+
+    #[test]
+    fn test_decode_base64url() {
+        let expected = b"\xfb\xff hello".to_vec();
+        let encoded = base64::engine::general_purpose::URL_SAFE.encode(&expected);
+        assert!(encoded.contains('-') && encoded.ends_with('='));
+
+        assert_eq!(decode_base64url(&encoded), Some(expected.clone()));
+        assert_eq!(
+            decode_base64url(encoded.trim_end_matches('=')),
+            Some(expected.clone())
+        );
+        let wrapped = format!("{}\r\n{}", &encoded[..8], &encoded[8..]);
+        assert_eq!(decode_base64url(&wrapped), Some(expected.clone()));
+        let standard = base64::engine::general_purpose::STANDARD.encode(&expected);
+        assert_eq!(decode_base64url(&standard), Some(expected));
+        assert_eq!(decode_base64url("not base64!"), None);
+    }
+}
@@ -1,10 +1,14 @@
 //! Google Gmail stream implementation
 
+mod address;
+pub mod attachments;
+pub mod body;
+pub mod mime;
 pub mod transform;
 
 use async_trait::async_trait;
-use base64::Engine as _;
 use chrono::{DateTime, Utc};
+use futures::stream::{self, StreamExt};
 use sqlx::SqlitePool;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -12,21 +16,35 @@ use tokio::sync::Mutex;
 
 use super::{
     client::GoogleClient,
-    config::{GmailSyncMode, GoogleGmailConfig},
-    types::{
-        HistoryResponse, Message, MessagePart, MessagesListResponse, Thread, ThreadsListResponse,
-    },
+    config::{GmailBodyStorage, GmailSyncMode, GoogleGmailConfig},
+    types::{HistoryResponse, Message, MessagesListResponse, Thread, ThreadsListResponse},
 };
 use crate::{
-    error::Result,
+    error::{Error, Result},
+    jobs::{first_sync, progress},
     sources::{
         auth::SourceAuth,
-        base::{ConfigSerializable, SyncMode, SyncResult},
+        base::{
+            load_resume_cursor, save_resume_cursor, ConfigSerializable, SourceClient, StreamLimits,
+            SyncMode, SyncResult,
+        },
         pull_stream::PullStream,
     },
-    storage::stream_writer::StreamWriter,
+    storage::{stream_writer::StreamWriter, Storage},
 };
 
+/// Shape version of gmail stream records (`_schema_version`)
+///
+/// 1. Parsed addresses are normalized and de-duplicated across `to`/`cc`/`bcc`;
+///    bodies may be stored externally under `body_key`.
+///
+/// Records archived before versions were stamped may predate address
+/// normalization; the transform re-parses their addresses from `headers`.
+pub const GMAIL_SCHEMA_VERSION: u32 = 1;
+
+/// Headers requested in `metadata_only` mode: everything the record is built from
+const METADATA_HEADERS: &[&str] = &["From", "To", "Cc", "Bcc", "Reply-To", "Subject", "Date"];
+
 /// Google Gmail stream
 ///
 /// Syncs email messages from Gmail API to object storage via StreamWriter.
@@ -34,15 +52,17 @@ pub struct GoogleGmailStream {
     source_id: String,
     client: GoogleClient,
     db: SqlitePool,
+    storage: Arc<Storage>,
     stream_writer: Arc<Mutex<StreamWriter>>,
     config: GoogleGmailConfig,
 }
 
 impl GoogleGmailStream {
-    /// Create a new Gmail stream with SourceAuth and StreamWriter
+    /// Create a new Gmail stream with SourceAuth, StreamWriter and storage for external bodies
     pub fn new(
         source_id: String,
         db: SqlitePool,
+        storage: Arc<Storage>,
         stream_writer: Arc<Mutex<StreamWriter>>,
         auth: SourceAuth,
     ) -> Self {
@@ -52,12 +72,14 @@ impl GoogleGmailStream {
             .expect("GoogleGmailStream requires OAuth2 auth")
             .clone();
 
-        let client = GoogleClient::with_api(source_id.clone(), token_manager, "gmail", "v1");
+        let client = GoogleClient::with_api(source_id.clone(), token_manager, "gmail", "v1")
+            .with_network(auth.network());
 
         Self {
             source_id,
             client,
             db,
+            storage,
             stream_writer,
             config: GoogleGmailConfig::default(),
         }
@@ -78,6 +100,7 @@ impl GoogleGmailStream {
                 self.config = config;
             }
         }
+        self.client.set_max_retries(self.config.max_retries);
 
         Ok(())
     }
@@ -103,8 +126,14 @@ impl GoogleGmailStream {
         // Load last sync token from database as defensive fallback
         let db_history_id = self.get_last_sync_token().await?;
 
+        // A resume cursor means the last full sync stopped before its final
+        // page; finish that listing before switching to incremental
+        let limits = StreamLimits::load(&self.db, &self.source_id, "gmail").await?;
+        let resume_token = load_resume_cursor(&self.db, &self.source_id, "gmail").await?;
+
         // Determine effective cursor: prefer SyncMode parameter, fall back to database
         let effective_cursor = match sync_mode {
+            _ if resume_token.is_some() => None,
             SyncMode::Incremental { cursor } => cursor.clone().or(db_history_id),
             SyncMode::FullRefresh => None,
             SyncMode::Backfill { .. } => None, // Backfill starts from scratch for the range
@@ -121,17 +150,36 @@ impl GoogleGmailStream {
                 next_cursor = result.3;
             }
             None => {
-                // Full sync - fetch messages based on config
+                // Full sync - fetch messages in the backfill range, else the
+                // config's window (only recent mail on a first sync)
+                let query = match sync_mode {
+                    SyncMode::Backfill {
+                        start_date,
+                        end_date,
+                    } => self
+                        .config
+                        .build_query_between(Some(*start_date), Some(*end_date)),
+                    _ => {
+                        let (min_time, max_time) =
+                            self.config.sync_strategy.calculate_time_bounds();
+                        self.config
+                            .build_query_between(first_sync::bound(min_time), max_time)
+                    }
+                };
                 match self.config.sync_mode {
                     GmailSyncMode::Messages => {
-                        let result = self.sync_messages_full().await?;
+                        let result = self
+                            .sync_messages_full(&query, &limits, resume_token)
+                            .await?;
                         records_fetched = result.0;
                         records_written = result.1;
                         records_failed = result.2;
                         next_cursor = result.3;
                     }
                     GmailSyncMode::Threads => {
-                        let result = self.sync_threads_full().await?;
+                        let result = self
+                            .sync_threads_full(&query, &limits, resume_token)
+                            .await?;
                         records_fetched = result.0;
                         records_written = result.1;
                         records_failed = result.2;
@@ -147,7 +195,7 @@ impl GoogleGmailStream {
         let records = {
             let mut writer = self.stream_writer.lock().await;
             let collected = writer
-                .collect_records(&self.source_id, "gmail")
+                .collect_records(&self.source_id, "gmail")?
                 .map(|(records, _, _)| records);
 
             if let Some(ref recs) = collected {
@@ -173,6 +221,7 @@ impl GoogleGmailStream {
             completed_at,
             records, // Return collected records for archive/transform
             archive_job_id: None,
+            records_deduplicated: 0,
         })
     }
 
@@ -219,6 +268,7 @@ impl GoogleGmailStream {
             latest_history_id = new_history_id;
             self.save_history_id(&latest_history_id).await?;
         }
+        progress::report_page(records_fetched, records_written, Some(&latest_history_id));
 
         Ok((
             records_fetched,
@@ -228,27 +278,41 @@ impl GoogleGmailStream {
         ))
     }
 
-    /// Full sync of messages with pagination
-    async fn sync_messages_full(&self) -> Result<(usize, usize, usize, Option<String>)> {
+    /// Full sync of messages matching `query` with pagination
+    ///
+    /// A run stopped by `limits` or a listing failure saves the next page
+    /// token as the resume cursor, and the next run resumes from
+    /// `resume_token` instead of the first page. A sync job commits that
+    /// cursor only with the run's archived records, so a killed run fetches
+    /// its pages again. A page token Gmail rejects restarts the listing.
+    async fn sync_messages_full(
+        &self,
+        query: &str,
+        limits: &StreamLimits,
+        resume_token: Option<String>,
+    ) -> Result<(usize, usize, usize, Option<String>)> {
         let mut records_fetched = 0;
         let mut records_written = 0;
         let mut records_failed = 0;
-        let mut latest_history_id = None;
-        let mut page_token: Option<String> = None;
+        let page_size = limits.page_size_or(self.config.max_messages_per_sync);
+        let (start_history_id, mut page_token) = self.start_listing(resume_token).await;
+        let mut pages_listed = 0;
+
+        if page_token.is_some() {
+            tracing::info!("Resuming Gmail messages sync");
+        }
 
         loop {
             // Build query parameters
-            let mut params = vec![("maxResults", self.config.max_messages_per_sync.to_string())];
+            let mut params = vec![("maxResults", page_size.to_string())];
 
             // Add label filters
             for label in &self.config.label_ids {
                 params.push(("labelIds", label.clone()));
             }
 
-            // Add query
-            let query = self.config.build_query();
             if !query.is_empty() {
-                params.push(("q", query));
+                params.push(("q", query.to_string()));
             }
 
             if self.config.include_spam_trash {
@@ -263,31 +327,42 @@ impl GoogleGmailStream {
             let param_refs: Vec<(&str, &str)> =
                 params.iter().map(|(k, v)| (*k, v.as_str())).collect();
 
-            // List messages
-            let response: MessagesListResponse = self
+            // List messages; a failure after the first page ends the run with
+            // what was fetched, and the next run resumes at the failed page
+            let response: MessagesListResponse = match self
                 .client
                 .get_with_params("users/me/messages", &param_refs)
-                .await?;
+                .await
+            {
+                Ok(response) => response,
+                Err(e)
+                    if pages_listed == 0 && page_token.is_some() && is_rejected_page_token(&e) =>
+                {
+                    tracing::warn!(error = %e, "Gmail rejected the saved page token, listing from the first page");
+                    page_token = None;
+                    continue;
+                }
+                Err(e) if pages_listed > 0 => {
+                    tracing::warn!(error = %e, pages_listed, "Gmail listing failed, resuming at this page next run");
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+            pages_listed += 1;
 
             if let Some(messages) = response.messages {
-                for msg_ref in messages {
-                    records_fetched += 1;
-
-                    // Fetch full message
-                    match self.fetch_and_store_message(&msg_ref.id).await {
-                        Ok(true) => records_written += 1,
-                        Ok(false) => records_failed += 1,
-                        Err(e) => {
-                            tracing::warn!(error = %e, "Failed to fetch message {}", msg_ref.id);
-                            records_failed += 1;
-                        }
-                    }
-                }
+                records_fetched += messages.len();
+                let (written, failed) = self
+                    .fetch_and_store_messages(messages.into_iter().map(|m| m.id).collect())
+                    .await;
+                records_written += written;
+                records_failed += failed;
             }
 
             // Check if there are more pages
             page_token = response.next_page_token;
-            if page_token.is_none() {
+            progress::report_page(records_fetched, records_written, page_token.as_deref());
+            if page_token.is_none() || limits.is_reached(records_fetched) {
                 break;
             }
 
@@ -308,43 +383,48 @@ impl GoogleGmailStream {
             "Completed paginated messages sync"
         );
 
-        // Get profile to fetch latest history ID for future incremental syncs
-        if let Ok(profile) = self.get_profile().await {
-            if let Some(history_id) = profile.get("historyId").and_then(|v| v.as_str()) {
-                latest_history_id = Some(history_id.to_string());
-                self.save_history_id(history_id).await?;
-            }
-        }
+        let history_id = self
+            .finish_listing(start_history_id, page_token.as_deref())
+            .await?;
 
-        Ok((
-            records_fetched,
-            records_written,
-            records_failed,
-            latest_history_id,
-        ))
+        Ok((records_fetched, records_written, records_failed, history_id))
     }
 
-    /// Full sync of threads with pagination
-    async fn sync_threads_full(&self) -> Result<(usize, usize, usize, Option<String>)> {
+    /// Full sync of threads matching `query` with pagination
+    ///
+    /// A run stopped by `limits` or a listing failure saves the next page
+    /// token as the resume cursor, and the next run resumes from
+    /// `resume_token` instead of the first page. A sync job commits that
+    /// cursor only with the run's archived records, so a killed run fetches
+    /// its pages again. A page token Gmail rejects restarts the listing.
+    async fn sync_threads_full(
+        &self,
+        query: &str,
+        limits: &StreamLimits,
+        resume_token: Option<String>,
+    ) -> Result<(usize, usize, usize, Option<String>)> {
         let mut records_fetched = 0;
         let mut records_written = 0;
         let mut records_failed = 0;
-        let mut latest_history_id = None;
-        let mut page_token: Option<String> = None;
+        let page_size = limits.page_size_or(self.config.max_messages_per_sync);
+        let (start_history_id, mut page_token) = self.start_listing(resume_token).await;
+        let mut pages_listed = 0;
+
+        if page_token.is_some() {
+            tracing::info!("Resuming Gmail threads sync");
+        }
 
         loop {
             // Build query parameters
-            let mut params = vec![("maxResults", self.config.max_messages_per_sync.to_string())];
+            let mut params = vec![("maxResults", page_size.to_string())];
 
             // Add label filters
             for label in &self.config.label_ids {
                 params.push(("labelIds", label.clone()));
             }
 
-            // Add query
-            let query = self.config.build_query();
             if !query.is_empty() {
-                params.push(("q", query));
+                params.push(("q", query.to_string()));
             }
 
             if self.config.include_spam_trash {
@@ -359,18 +439,38 @@ impl GoogleGmailStream {
             let param_refs: Vec<(&str, &str)> =
                 params.iter().map(|(k, v)| (*k, v.as_str())).collect();
 
-            // List threads
-            let response: ThreadsListResponse = self
+            // List threads; a failure after the first page ends the run with
+            // what was fetched, and the next run resumes at the failed page
+            let response: ThreadsListResponse = match self
                 .client
                 .get_with_params("users/me/threads", &param_refs)
-                .await?;
+                .await
+            {
+                Ok(response) => response,
+                Err(e)
+                    if pages_listed == 0 && page_token.is_some() && is_rejected_page_token(&e) =>
+                {
+                    tracing::warn!(error = %e, "Gmail rejected the saved page token, listing from the first page");
+                    page_token = None;
+                    continue;
+                }
+                Err(e) if pages_listed > 0 => {
+                    tracing::warn!(error = %e, pages_listed, "Gmail listing failed, resuming at this page next run");
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+            pages_listed += 1;
 
             if let Some(threads) = response.threads {
                 for thread_ref in threads {
                     // Fetch full thread with messages
                     let thread: Thread = self
                         .client
-                        .get(&format!("users/me/threads/{}", thread_ref.id))
+                        .get_with_params(
+                            &format!("users/me/threads/{}", thread_ref.id),
+                            &self.format_params(),
+                        )
                         .await?;
 
                     if let Some(messages) = thread.messages {
@@ -397,17 +497,13 @@ impl GoogleGmailStream {
                             }
                         }
                     }
-
-                    // Update history ID from thread
-                    if let Some(history_id) = thread.history_id {
-                        latest_history_id = Some(history_id.clone());
-                    }
                 }
             }
 
             // Check if there are more pages
             page_token = response.next_page_token;
-            if page_token.is_none() {
+            progress::report_page(records_fetched, records_written, page_token.as_deref());
+            if page_token.is_none() || limits.is_reached(records_fetched) {
                 break;
             }
 
@@ -428,32 +524,123 @@ impl GoogleGmailStream {
             "Completed paginated threads sync"
         );
 
-        // Save latest history ID for incremental sync
-        if let Some(ref history_id) = latest_history_id {
+        let history_id = self
+            .finish_listing(start_history_id, page_token.as_deref())
+            .await?;
+
+        Ok((records_fetched, records_written, records_failed, history_id))
+    }
+
+    /// History id and page token to start a full listing from
+    ///
+    /// A listing from the first page notes the mailbox's history id before
+    /// it starts, so mail arriving while it runs (over however many capped
+    /// runs) is picked up by the first incremental sync after it.
+    async fn start_listing(
+        &self,
+        resume_cursor: Option<String>,
+    ) -> (Option<String>, Option<String>) {
+        if let Some(cursor) = resume_cursor {
+            let (history_id, page_token) = decode_resume_cursor(&cursor);
+            return (history_id, Some(page_token));
+        }
+        (self.current_history_id().await, None)
+    }
+
+    /// Save where an unfinished listing stopped, or the history id to sync
+    /// incrementally from once it is complete
+    ///
+    /// The history id is only recorded once the listing is complete, so an
+    /// incremental sync can't skip the messages an unfinished run didn't
+    /// reach. Returns it.
+    async fn finish_listing(
+        &self,
+        start_history_id: Option<String>,
+        page_token: Option<&str>,
+    ) -> Result<Option<String>> {
+        let resume_cursor =
+            page_token.map(|token| encode_resume_cursor(start_history_id.as_deref(), token));
+        save_resume_cursor(&self.db, &self.source_id, "gmail", resume_cursor.as_deref()).await?;
+        if page_token.is_some() {
+            return Ok(None);
+        }
+
+        // A listing resumed from an older cursor didn't note where it started
+        let history_id = match start_history_id {
+            Some(history_id) => Some(history_id),
+            None => self.current_history_id().await,
+        };
+        if let Some(history_id) = &history_id {
             self.save_history_id(history_id).await?;
         }
+        Ok(history_id)
+    }
 
-        Ok((
-            records_fetched,
-            records_written,
-            records_failed,
-            latest_history_id,
-        ))
+    /// The mailbox's current history id, if the profile can be read
+    async fn current_history_id(&self) -> Option<String> {
+        let profile = self.get_profile().await.ok()?;
+        profile
+            .get("historyId")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    }
+
+    /// Fetch and store messages, up to `fetch_concurrency` at a time
+    ///
+    /// Returns how many were written and how many failed. Each fetch is its
+    /// own client request, so rate-limited requests are retried with backoff
+    /// as usual; concurrency only bounds how many are in flight.
+    async fn fetch_and_store_messages(&self, message_ids: Vec<String>) -> (usize, usize) {
+        let mut results = stream::iter(message_ids)
+            .map(|id| async move {
+                let result = self.fetch_and_store_message(&id).await;
+                (id, result)
+            })
+            .buffer_unordered(self.config.fetch_concurrency.max(1));
+
+        let (mut written, mut failed) = (0, 0);
+        while let Some((id, result)) = results.next().await {
+            match result {
+                Ok(true) => written += 1,
+                Ok(false) => failed += 1,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to fetch message {}", id);
+                    failed += 1;
+                }
+            }
+        }
+        (written, failed)
     }
 
     /// Fetch a single message and store it
     async fn fetch_and_store_message(&self, message_id: &str) -> Result<bool> {
         let message: Message = self
             .client
-            .get(&format!("users/me/messages/{message_id}"))
+            .get_with_params(
+                &format!("users/me/messages/{message_id}"),
+                &self.format_params(),
+            )
             .await?;
         self.store_message(message, None, None).await
     }
 
+    /// Query parameters selecting how much of each message to download
+    ///
+    /// `metadata_only` asks for just the allowlisted headers; otherwise the
+    /// API default, `format=full`, applies.
+    fn format_params(&self) -> Vec<(&'static str, &'static str)> {
+        if !self.config.metadata_only {
+            return Vec::new();
+        }
+        let mut params = vec![("format", "metadata")];
+        params.extend(METADATA_HEADERS.iter().map(|h| ("metadataHeaders", *h)));
+        params
+    }
+
     /// Store a message in the database
     async fn store_message(
         &self,
-        message: Message,
+        mut message: Message,
         thread_position: Option<i32>,
         thread_message_count: Option<i32>,
     ) -> Result<bool> {
@@ -476,18 +663,20 @@ impl GoogleGmailStream {
         let reply_to = headers_map.get("Reply-To").cloned();
         let date_str = headers_map.get("Date").cloned();
 
-        // Parse email addresses
-        let (from_email, from_name) = self.parse_email_address(from.as_deref());
-        let (to_emails, to_names) = self.parse_email_list(to.as_deref());
-        let (cc_emails, cc_names) = self.parse_email_list(cc.as_deref());
-        let (bcc_emails, bcc_names) = self.parse_email_list(bcc.as_deref());
-
-        // Parse date
-        let date = if let Some(date_str) = date_str {
-            self.parse_email_date(&date_str).unwrap_or_else(Utc::now)
-        } else {
-            Utc::now()
-        };
+        // Parse and normalize email addresses (raw headers are kept as-is)
+        let address::MessageAddresses {
+            from_email,
+            from_name,
+            to: (to_emails, to_names),
+            cc: (cc_emails, cc_names),
+            bcc: (bcc_emails, bcc_names),
+        } = address::MessageAddresses::parse(
+            from.as_deref(),
+            to.as_deref(),
+            cc.as_deref(),
+            bcc.as_deref(),
+            self.config.strip_plus_tags,
+        );
 
         // Parse internal date (milliseconds since epoch)
         let internal_date = message
@@ -496,19 +685,93 @@ impl GoogleGmailStream {
             .and_then(|ms_str| ms_str.parse::<i64>().ok())
             .and_then(DateTime::from_timestamp_millis);
 
+        // Event time: the Date header, falling back to Gmail's internal date.
+        // `None` if neither is usable, so the writer can reject it in strict mode.
+        let event_date = date_str
+            .and_then(|date_str| self.parse_email_date(&date_str))
+            .or(internal_date);
+        let date = event_date.unwrap_or_else(Utc::now);
+
         // Extract body content
-        let (body_plain, body_html, attachments) = if self.config.fetch_body {
-            self.extract_message_content(&message.payload)
+        let mime::MessageContent {
+            body_plain,
+            body_html,
+            attachments,
+            truncated: content_truncated,
+        } = if self.config.fetch_body && !self.config.metadata_only {
+            mime::extract_content(
+                message.payload.as_ref(),
+                mime::MimeLimits {
+                    max_depth: self.config.max_mime_depth,
+                    max_parts: self.config.max_mime_parts,
+                },
+            )
         } else {
-            (None, None, Vec::new())
+            mime::MessageContent::default()
+        };
+        if content_truncated {
+            tracing::warn!(
+                message_id = %message.id,
+                "Gmail message exceeds MIME limits; content truncated"
+            );
+        }
+
+        // In external mode the bodies go to their own object; the record keeps
+        // the key and a snippet, and raw_message loses its copy of the bodies.
+        // That object isn't written through the StreamWriter, so the stream's
+        // redaction rules are applied to the bodies here.
+        let (body_plain, body_html, body_key, snippet) = match self.config.body_storage {
+            GmailBodyStorage::External if body_plain.is_some() || body_html.is_some() => {
+                let stored = self.redact_bodies(body_plain, body_html).await;
+                let snippet = message
+                    .snippet
+                    .clone()
+                    .or_else(|| stored.body_plain.as_deref().map(body::snippet_from));
+                let key = if stored.body_plain.is_some() || stored.body_html.is_some() {
+                    Some(
+                        body::store_body(&self.storage, &self.source_id, &message.id, &stored)
+                            .await?,
+                    )
+                } else {
+                    None
+                };
+                body::strip_body_data(&mut message);
+                (None, None, key, snippet)
+            }
+            _ => (body_plain, body_html, None, message.snippet.clone()),
         };
 
         // Process attachments
         let has_attachments = !attachments.is_empty();
         let attachment_count = attachments.len() as i32;
-        let attachment_types: Vec<String> = attachments.iter().map(|a| a.0.clone()).collect();
-        let attachment_names: Vec<String> = attachments.iter().map(|a| a.1.clone()).collect();
-        let attachment_sizes: Vec<i32> = attachments.iter().map(|a| a.2).collect();
+        let attachment_types: Vec<String> =
+            attachments.iter().map(|a| a.mime_type.clone()).collect();
+        let attachment_names: Vec<String> =
+            attachments.iter().map(|a| a.filename.clone()).collect();
+        let attachment_sizes: Vec<i32> = attachments.iter().map(|a| a.size).collect();
+        // Attachment bytes can't be redacted, so redacting their keys keeps
+        // them out of storage altogether
+        let fetch_attachments = self.config.fetch_attachments
+            && !self.stream_writer.lock().await.redacts_field(
+                &self.source_id,
+                "gmail",
+                "attachment_keys",
+            );
+        let attachment_keys = if fetch_attachments {
+            Some(
+                attachments::store_attachments(
+                    &self.client,
+                    &self.storage,
+                    &self.source_id,
+                    &message.id,
+                    &attachments,
+                    self.config.max_attachment_size_bytes,
+                )
+                .await?,
+            )
+        } else {
+            None
+        };
 
         // Process labels
         let labels = message.label_ids.clone().unwrap_or_default();
@@ -526,7 +789,7 @@ impl GoogleGmailStream {
             "thread_id": message.thread_id,
             "history_id": message.history_id,
             "subject": subject,
-            "snippet": message.snippet,
+            "snippet": snippet,
             "date": date,
             "from_email": from_email,
             "from_name": from_name,
@@ -539,11 +802,15 @@ impl GoogleGmailStream {
             "reply_to": reply_to,
             "body_plain": body_plain,
             "body_html": body_html,
+            "body_key": body_key,
+            "metadata_only": self.config.metadata_only,
+            "content_truncated": content_truncated,
             "has_attachments": has_attachments,
             "attachment_count": attachment_count,
             "attachment_types": attachment_types,
             "attachment_names": attachment_names,
             "attachment_sizes_bytes": attachment_sizes,
+            "attachment_keys": attachment_keys,
             "labels": labels,
             "is_unread": is_unread,
             "is_important": is_important,
@@ -564,117 +831,33 @@ impl GoogleGmailStream {
         // Write to S3/object storage via StreamWriter
         {
             let mut writer = self.stream_writer.lock().await;
-            writer.write_record(&self.source_id, "gmail", record, Some(date))?;
+            writer.write_record(&self.source_id, "gmail", record, event_date)?;
         }
 
         tracing::trace!(message_id = %message.id, "Wrote Gmail message to object storage");
         Ok(true)
     }
 
-    /// Extract plain text, HTML, and attachments from message payload
-    fn extract_message_content(
-        &self,
-        payload: &Option<MessagePart>,
-    ) -> (Option<String>, Option<String>, Vec<(String, String, i32)>) {
-        let mut plain_text = None;
-        let mut html_text = None;
-        let mut attachments = Vec::new();
-
-        if let Some(part) = payload {
-            self.extract_from_part(part, &mut plain_text, &mut html_text, &mut attachments);
-        }
-
-        (plain_text, html_text, attachments)
-    }
-
-    /// Recursively extract content from message parts
-    fn extract_from_part(
+    /// Apply the stream's redaction rules to bodies stored outside the record
+    async fn redact_bodies(
         &self,
-        part: &MessagePart,
-        plain_text: &mut Option<String>,
-        html_text: &mut Option<String>,
-        attachments: &mut Vec<(String, String, i32)>,
-    ) {
-        // Check if this is an attachment
-        if let Some(filename) = &part.filename {
-            if !filename.is_empty() {
-                let mime_type = part
-                    .mime_type
-                    .clone()
-                    .unwrap_or_else(|| "application/octet-stream".to_string());
-                let size = part.body.as_ref().map(|b| b.size).unwrap_or(0);
-                attachments.push((mime_type, filename.clone(), size));
-                return;
-            }
-        }
-
-        // Extract text content
-        if let Some(mime_type) = &part.mime_type {
-            if mime_type == "text/plain" && plain_text.is_none() {
-                if let Some(body) = &part.body {
-                    if let Some(data) = &body.data {
-                        // Decode base64url
-                        if let Ok(decoded) =
-                            base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(data)
-                        {
-                            *plain_text = String::from_utf8(decoded).ok();
-                        }
-                    }
-                }
-            } else if mime_type == "text/html" && html_text.is_none() {
-                if let Some(body) = &part.body {
-                    if let Some(data) = &body.data {
-                        // Decode base64url
-                        if let Ok(decoded) =
-                            base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(data)
-                        {
-                            *html_text = String::from_utf8(decoded).ok();
-                        }
-                    }
-                }
-            }
-        }
-
-        // Recursively process parts
-        if let Some(parts) = &part.parts {
-            for sub_part in parts {
-                self.extract_from_part(sub_part, plain_text, html_text, attachments);
-            }
-        }
-    }
-
-    /// Parse email address into email and name components
-    fn parse_email_address(&self, address: Option<&str>) -> (Option<String>, Option<String>) {
-        if let Some(addr) = address {
-            if let Some(start) = addr.rfind('<') {
-                if let Some(end) = addr.rfind('>') {
-                    let email = addr[start + 1..end].trim().to_string();
-                    let name = addr[..start].trim().trim_matches('"').to_string();
-                    return (Some(email), if name.is_empty() { None } else { Some(name) });
-                }
-            }
-            // Just an email address without name
-            return (Some(addr.trim().to_string()), None);
-        }
-        (None, None)
-    }
-
-    /// Parse comma-separated email list
-    fn parse_email_list(&self, addresses: Option<&str>) -> (Vec<String>, Vec<String>) {
-        let mut emails = Vec::new();
-        let mut names = Vec::new();
-
-        if let Some(addr_list) = addresses {
-            for addr in addr_list.split(',') {
-                let (email, name) = self.parse_email_address(Some(addr.trim()));
-                if let Some(e) = email {
-                    emails.push(e);
-                    names.push(name.unwrap_or_default());
-                }
-            }
+        body_plain: Option<String>,
+        body_html: Option<String>,
+    ) -> body::StoredBody {
+        let mut bodies = serde_json::json!({
+            "body_plain": body_plain,
+            "body_html": body_html,
+        });
+        self.stream_writer
+            .lock()
+            .await
+            .redact(&self.source_id, "gmail", &mut bodies);
+
+        let text = |field: &str| bodies.get(field).and_then(|v| v.as_str()).map(String::from);
+        body::StoredBody {
+            body_plain: text("body_plain"),
+            body_html: text("body_html"),
         }
-
-        (emails, names)
     }
 
     /// Parse email date header
@@ -755,3 +938,401 @@ impl PullStream for GoogleGmailStream {
         true
     }
 }
+
+/// Resume cursor of an unfinished full listing: `<history id>\t<page token>`
+fn encode_resume_cursor(history_id: Option<&str>, page_token: &str) -> String {
+    match history_id {
+        Some(history_id) => format!("{history_id}\t{page_token}"),
+        None => page_token.to_string(),
+    }
+}
+
+/// History id (if noted) and page token of a resume cursor
+fn decode_resume_cursor(cursor: &str) -> (Option<String>, String) {
+    match cursor.split_once('\t') {
+        Some((history_id, page_token)) => (Some(history_id.to_string()), page_token.to_string()),
+        None => (None, cursor.to_string()),
+    }
+}
+
+/// Whether Gmail refused a listing's page token (expired or malformed)
+fn is_rejected_page_token(error: &Error) -> bool {
+    matches!(error, Error::Http(message) if message.starts_with("API error (400"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::base::mock_transport::testing::{
+        archive_to_memory, fixtures, migrated_pool,
+    };
+    use crate::sources::base::mock_transport::MockTransport;
+    use crate::sources::base::TokenManager;
+    use serde_json::json;
+
+    fn gmail_stream(db: &SqlitePool, transport: Arc<MockTransport>) -> GoogleGmailStream {
+        let token_manager = Arc::new(TokenManager::new_insecure(db.clone()));
+        let mut stream = GoogleGmailStream::new(
+            "src-gmail".to_string(),
+            db.clone(),
+            Arc::new(Storage::in_memory()),
+            Arc::new(Mutex::new(StreamWriter::new())),
+            SourceAuth::oauth2("src-gmail".to_string(), token_manager),
+        );
+        stream.client.http_mut().set_mock_transport(transport);
+        stream
+    }
+
+    fn message_fixture(id: &str) -> serde_json::Value {
+        let path = format!(
+            "{}/fixtures/sources/gmail/users/me/messages/{id}.json",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_full_sync_against_fixtures() {
+        let db = migrated_pool().await;
+        let transport = fixtures("gmail");
+        let stream = gmail_stream(&db, transport.clone());
+
+        let result = stream.sync_internal(&SyncMode::FullRefresh).await.unwrap();
+        assert_eq!(result.records_fetched, 2);
+        assert_eq!(result.records_written, 2);
+        assert_eq!(result.next_cursor.as_deref(), Some("9001"));
+
+        let records = result.records.unwrap();
+        let written = archive_to_memory("google", "src-gmail", "gmail", &records, "date").await;
+        assert_eq!(written.len(), 2);
+        let first = written
+            .iter()
+            .find(|r| r["message_id"] == "18c1f0a1")
+            .unwrap();
+        assert_eq!(first["subject"], "Lunch on Friday?");
+        assert_eq!(first["from_email"], "grace@example.com");
+        assert_eq!(first["body_plain"], "Are you free for lunch on Friday?");
+        assert_eq!(first["is_unread"], true);
+
+        let paths: Vec<String> = transport.requests().into_iter().map(|r| r.path).collect();
+        assert_eq!(
+            paths,
+            [
+                "users/me/profile",
+                "users/me/messages",
+                "users/me/messages/18c1f0a1",
+                "users/me/messages/18c1f0b2",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_full_sync_resumes_at_saved_page() {
+        let db = migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, name) VALUES ('src-gmail', 'google', 'Gmail')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO elt_stream_connections (id, source_connection_id, stream_name, table_name)
+             VALUES ('stream-gmail', 'src-gmail', 'gmail', 'stream_google_gmail')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        // Two pages list, then the third fails
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_response(
+                    "users/me/messages",
+                    json!({ "messages": [{ "id": "18c1f0a1", "threadId": "18c1f0a1" }], "nextPageToken": "p2" }),
+                )
+                .with_response("users/me/messages", json!({ "nextPageToken": "p3" }))
+                .with_response("users/me/messages", json!({ "messages": "unavailable" }))
+                .with_response("users/me/messages/18c1f0a1", message_fixture("18c1f0a1"))
+                .with_response("users/me/profile", json!({ "historyId": "8000" })),
+        );
+        let stream = gmail_stream(&db, transport.clone());
+
+        let result = stream.sync_internal(&SyncMode::FullRefresh).await.unwrap();
+        assert_eq!(result.records_fetched, 1);
+        assert_eq!(result.records.unwrap().len(), 1);
+        assert_eq!(result.next_cursor, None);
+        assert_eq!(
+            load_resume_cursor(&db, "src-gmail", "gmail")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("8000\tp3")
+        );
+        assert_eq!(stream.get_last_sync_token().await.unwrap(), None);
+
+        // The next full sync picks up at the saved page and finishes,
+        // syncing incrementally from where the mailbox was when it started
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_response(
+                    "users/me/messages",
+                    json!({ "messages": [{ "id": "18c1f0b2", "threadId": "18c1f0a1" }] }),
+                )
+                .with_response("users/me/messages/18c1f0b2", message_fixture("18c1f0b2"))
+                .with_response("users/me/profile", json!({ "historyId": "9001" })),
+        );
+        let stream = gmail_stream(&db, transport.clone());
+
+        let result = stream.sync_internal(&SyncMode::FullRefresh).await.unwrap();
+        assert_eq!(result.records_fetched, 1);
+        assert_eq!(result.next_cursor.as_deref(), Some("8000"));
+        let requests = transport.requests();
+        assert_eq!(requests[0].param("pageToken"), Some("p3"));
+        assert!(requests.iter().all(|r| r.path != "users/me/profile"));
+        assert_eq!(
+            load_resume_cursor(&db, "src-gmail", "gmail").await.unwrap(),
+            None
+        );
+        assert_eq!(
+            stream.get_last_sync_token().await.unwrap().as_deref(),
+            Some("8000")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejected_page_token_restarts_listing() {
+        let db = migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, name) VALUES ('src-gmail', 'google', 'Gmail')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO elt_stream_connections (id, source_connection_id, stream_name, table_name, resume_cursor)
+             VALUES ('stream-gmail', 'src-gmail', 'gmail', 'stream_google_gmail', 'expired')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_status("users/me/messages", 400, "Invalid pageToken")
+                .with_response(
+                    "users/me/messages",
+                    json!({ "messages": [{ "id": "18c1f0a1", "threadId": "18c1f0a1" }] }),
+                )
+                .with_response("users/me/messages/18c1f0a1", message_fixture("18c1f0a1"))
+                .with_response("users/me/profile", json!({ "historyId": "9001" })),
+        );
+        let stream = gmail_stream(&db, transport.clone());
+
+        let result = stream.sync_internal(&SyncMode::FullRefresh).await.unwrap();
+        assert_eq!(result.records_fetched, 1);
+        let requests = transport.requests();
+        assert_eq!(requests[0].param("pageToken"), Some("expired"));
+        assert_eq!(requests[1].param("pageToken"), None);
+        assert_eq!(
+            load_resume_cursor(&db, "src-gmail", "gmail").await.unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_attachments_downloaded_to_storage() {
+        let db = migrated_pool().await;
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_response(
+                    "users/me/messages/msg-1",
+                    json!({
+                        "id": "msg-1",
+                        "threadId": "msg-1",
+                        "payload": {
+                            "mimeType": "multipart/mixed",
+                            "parts": [
+                                { "mimeType": "text/plain", "body": { "size": 2, "data": "aGk" } },
+                                {
+                                    "mimeType": "application/pdf",
+                                    "filename": "report.pdf",
+                                    "body": { "size": 4, "attachmentId": "att-1" }
+                                },
+                                {
+                                    "mimeType": "text/plain",
+                                    "filename": "notes.txt",
+                                    "body": { "size": 3, "data": "eHl6" }
+                                },
+                                {
+                                    "mimeType": "video/mp4",
+                                    "filename": "talk.mp4",
+                                    "body": { "size": 900, "attachmentId": "att-2" }
+                                }
+                            ]
+                        }
+                    }),
+                )
+                .with_response(
+                    "users/me/messages/msg-1/attachments/att-1",
+                    json!({ "size": 4, "data": "JVBE\nRg==" }),
+                ),
+        );
+        let mut stream = gmail_stream(&db, transport.clone());
+        stream.config.fetch_attachments = true;
+        stream.config.max_attachment_size_bytes = 100;
+
+        assert!(stream.fetch_and_store_message("msg-1").await.unwrap());
+        let (records, _, _) = stream
+            .stream_writer
+            .lock()
+            .await
+            .collect_records("src-gmail", "gmail")
+            .unwrap()
+            .unwrap();
+        let keys = &records[0]["attachment_keys"];
+        assert_eq!(
+            keys,
+            &json!([
+                "streams/google/src-gmail/gmail/attachments/msg-1/0-report.pdf",
+                "streams/google/src-gmail/gmail/attachments/msg-1/1-notes.txt",
+                null
+            ])
+        );
+        assert_eq!(
+            stream
+                .storage
+                .download(keys[0].as_str().unwrap())
+                .await
+                .unwrap(),
+            b"%PDF"
+        );
+        assert_eq!(
+            stream
+                .storage
+                .download(keys[1].as_str().unwrap())
+                .await
+                .unwrap(),
+            b"xyz"
+        );
+
+        // Only the attachment without inline data was fetched; the oversized
+        // one never was
+        let paths: Vec<String> = transport.requests().into_iter().map(|r| r.path).collect();
+        assert_eq!(
+            paths,
+            [
+                "users/me/messages/msg-1",
+                "users/me/messages/msg-1/attachments/att-1"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_redaction_applies_to_external_bodies_and_attachments() {
+        use crate::storage::redaction::{RedactionConfig, Redactor, HASH_PREFIX};
+
+        let db = migrated_pool().await;
+        let transport = Arc::new(MockTransport::new().with_response(
+            "users/me/messages/msg-1",
+            json!({
+                "id": "msg-1",
+                "threadId": "msg-1",
+                "payload": {
+                    "mimeType": "multipart/mixed",
+                    "parts": [
+                        { "mimeType": "text/plain", "body": { "size": 2, "data": "aGk" } },
+                        { "mimeType": "text/html", "body": { "size": 9, "data": "PHA-aGk8L3A-" } },
+                        {
+                            "mimeType": "application/pdf",
+                            "filename": "report.pdf",
+                            "body": { "size": 4, "attachmentId": "att-1" }
+                        }
+                    ]
+                }
+            }),
+        ));
+        let mut stream = gmail_stream(&db, transport.clone());
+        stream.config.body_storage = GmailBodyStorage::External;
+        stream.config.fetch_attachments = true;
+        let config = RedactionConfig {
+            drop: vec!["$.body_html".to_string(), "$.attachment_keys".to_string()],
+            hash: vec!["$.body_plain".to_string()],
+        };
+        stream.stream_writer.lock().await.register_redaction(
+            "src-gmail",
+            "gmail",
+            Some(Redactor::new(&config, b"test-key").unwrap()),
+        );
+
+        assert!(stream.fetch_and_store_message("msg-1").await.unwrap());
+        let (records, _, _) = stream
+            .stream_writer
+            .lock()
+            .await
+            .collect_records("src-gmail", "gmail")
+            .unwrap()
+            .unwrap();
+
+        // The stored bodies are redacted like the record's fields would be
+        let key = records[0]["body_key"].as_str().unwrap();
+        let stored: body::StoredBody =
+            serde_json::from_slice(&stream.storage.download(key).await.unwrap()).unwrap();
+        assert!(stored.body_plain.unwrap().starts_with(HASH_PREFIX));
+        assert_eq!(stored.body_html, None);
+
+        // The attachment was never downloaded
+        assert!(records[0].get("attachment_keys").is_none());
+        let paths: Vec<String> = transport.requests().into_iter().map(|r| r.path).collect();
+        assert_eq!(paths, ["users/me/messages/msg-1"]);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_fetch_counts_match_serial_run() {
+        async fn run(fetch_concurrency: usize) -> (usize, usize, usize, Vec<String>) {
+            let db = migrated_pool().await;
+            let transport = Arc::new(
+                MockTransport::new()
+                    .with_response(
+                        "users/me/messages",
+                        json!({ "messages": [
+                            { "id": "18c1f0a1", "threadId": "18c1f0a1" },
+                            { "id": "gone", "threadId": "gone" },
+                            { "id": "18c1f0b2", "threadId": "18c1f0a1" }
+                        ] }),
+                    )
+                    .with_response("users/me/messages/18c1f0a1", message_fixture("18c1f0a1"))
+                    .with_response("users/me/messages/18c1f0b2", message_fixture("18c1f0b2"))
+                    .with_response("users/me/profile", json!({ "historyId": "9001" })),
+            );
+            let mut stream = gmail_stream(&db, transport);
+            stream.config.fetch_concurrency = fetch_concurrency;
+
+            let result = stream.sync_internal(&SyncMode::FullRefresh).await.unwrap();
+            let mut ids: Vec<String> = result
+                .records
+                .unwrap()
+                .iter()
+                .map(|r| r["message_id"].as_str().unwrap().to_string())
+                .collect();
+            ids.sort();
+            (
+                result.records_fetched,
+                result.records_written,
+                result.records_failed,
+                ids,
+            )
+        }
+
+        let serial = run(1).await;
+        assert_eq!(
+            serial,
+            (
+                3,
+                2,
+                1,
+                vec!["18c1f0a1".to_string(), "18c1f0b2".to_string()]
+            )
+        );
+        assert_eq!(run(8).await, serial);
+    }
+}
@@ -18,7 +18,14 @@ use uuid::Uuid;
 use crate::database::Database;
 use crate::error::Result;
 use crate::jobs::TransformContext;
-use crate::sources::base::{OntologyTransform, TransformRegistration, TransformResult};
+use crate::sources::base::{
+    ConfigSerializable, OntologyTransform, TransformRegistration, TransformResult,
+};
+
+use super::address::MessageAddresses;
+use super::body::StoredBody;
+use crate::sources::google::GoogleGmailConfig;
+use crate::storage::stream_writer::{schema_version, UNVERSIONED_SCHEMA};
 
 /// Batch size for bulk inserts
 const BATCH_SIZE: usize = 500;
@@ -77,6 +84,8 @@ impl OntologyTransform for GmailEmailTransform {
             "Fetched Gmail batches from data source"
         );
 
+        let strip_plus_tags = load_strip_plus_tags(db, &source_id).await?;
+
         // Batch insert configuration
         // Tuple: (id, message_id, thread_id, subject, body_preview, body, timestamp, from_email, from_name,
         //         to_emails, to_names, cc_emails, cc_names, direction, labels, is_read, is_starred, has_attachments, stream_id)
@@ -140,10 +149,21 @@ impl OntologyTransform for GmailEmailTransform {
                     .get("snippet")
                     .and_then(|v| v.as_str())
                     .map(String::from);
-                let body_plain = record
+                let mut body_plain = record
                     .get("body_plain")
                     .and_then(|v| v.as_str())
                     .map(String::from);
+                // Bodies stored externally (body_storage: external)
+                if body_plain.is_none() {
+                    if let Some(key) = record.get("body_key").and_then(|v| v.as_str()) {
+                        match context.storage.download_json::<StoredBody>(key).await {
+                            Ok(stored) => body_plain = stored.body_plain,
+                            Err(e) => {
+                                tracing::warn!(error = %e, key, "Failed to load Gmail body");
+                            }
+                        }
+                    }
+                }
                 let _body_html = record
                     .get("body_html")
                     .and_then(|v| v.as_str())
@@ -195,6 +215,23 @@ impl OntologyTransform for GmailEmailTransform {
                     })
                     .unwrap_or_default();
 
+                // Records archived before versions were stamped may hold
+                // addresses parsed before normalization; re-parse the raw headers
+                let (from_email, from_name, to_emails, to_names, cc_emails, cc_names) =
+                    match reparse_unversioned_addresses(record, strip_plus_tags) {
+                        Some(addresses) => (
+                            addresses.from_email,
+                            addresses.from_name,
+                            addresses.to.0,
+                            addresses.to.1,
+                            addresses.cc.0,
+                            addresses.cc.1,
+                        ),
+                        None => (
+                            from_email, from_name, to_emails, to_names, cc_emails, cc_names,
+                        ),
+                    };
+
                 let labels: Vec<String> = record
                     .get("labels")
                     .and_then(|v| v.as_array())
@@ -476,6 +513,46 @@ async fn execute_email_batch_insert(
     Ok(result.rows_affected() as usize)
 }
 
+/// The connection's `strip_plus_tags` setting, used when re-parsing addresses
+///
+/// A missing or unreadable config means the default.
+async fn load_strip_plus_tags(db: &Database, source_id: &str) -> Result<bool> {
+    let config = sqlx::query_as::<_, (serde_json::Value,)>(
+        "SELECT config FROM elt_stream_connections WHERE source_connection_id = $1 AND stream_name = 'gmail'",
+    )
+    .bind(source_id)
+    .fetch_optional(db.pool())
+    .await?;
+
+    Ok(config
+        .and_then(|(json,)| GoogleGmailConfig::from_json(&json).ok())
+        .unwrap_or_default()
+        .strip_plus_tags)
+}
+
+/// Addresses re-parsed from the raw `headers` of an unversioned record
+///
+/// Returns `None` for stamped records, whose parsed fields are trusted, and
+/// for records without headers.
+fn reparse_unversioned_addresses(
+    record: &serde_json::Value,
+    strip_plus_tags: bool,
+) -> Option<MessageAddresses> {
+    if schema_version(record) != UNVERSIONED_SCHEMA {
+        return None;
+    }
+    let headers = record.get("headers")?.as_object()?;
+    let header = |name: &str| headers.get(name).and_then(|v| v.as_str());
+
+    Some(MessageAddresses::parse(
+        header("From"),
+        header("To"),
+        header("Cc"),
+        header("Bcc"),
+        strip_plus_tags,
+    ))
+}
+
 // Self-registration
 struct GmailTransformRegistration;
 
@@ -506,4 +583,25 @@ mod tests {
         assert_eq!(transform.target_table(), "communication_email");
         assert_eq!(transform.domain(), "communication");
     }
+
+    #[test]
+    fn test_unversioned_records_reparse_headers() {
+        let mut record = serde_json::json!({
+            "from_email": "Ray Stantz <Ray@Example.com>",
+            "to_emails": ["peter@Example.com", "peter@example.com"],
+            "headers": {
+                "From": "Ray Stantz <Ray@Example.com>",
+                "To": "peter@Example.com, peter@example.com",
+                "Cc": "egon+lab@example.com",
+            },
+        });
+
+        let addresses = reparse_unversioned_addresses(&record, true).unwrap();
+        assert_eq!(addresses.from_email.as_deref(), Some("Ray@example.com"));
+        assert_eq!(addresses.to.0, vec!["peter@example.com"]);
+        assert_eq!(addresses.cc.0, vec!["egon@example.com"]);
+
+        record["_schema_version"] = serde_json::json!(1);
+        assert!(reparse_unversioned_addresses(&record, true).is_none());
+    }
 }
@@ -9,6 +9,6 @@ pub mod registry;
 pub mod types;
 
 pub use calendar::GoogleCalendarStream;
-pub use config::{GoogleCalendarConfig, GoogleGmailConfig};
+pub use config::{GmailBodyStorage, GoogleCalendarConfig, GoogleGmailConfig};
 pub use error_handler::GoogleErrorHandler;
 pub use gmail::GoogleGmailStream;
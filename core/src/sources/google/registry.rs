@@ -8,9 +8,11 @@ use crate::sources::base::SyncStrategy;
 use crate::sources::stream_type::StreamType;
 use serde_json::json;
 
+use super::config::{GoogleCalendarConfig, GoogleGmailConfig};
+
 // Import transforms and stream types for unified registration
 use super::calendar::{transform::GoogleCalendarTransform, GoogleCalendarStream};
-use super::gmail::{transform::GmailEmailTransform, GoogleGmailStream};
+use super::gmail::{transform::GmailEmailTransform, GoogleGmailStream, GMAIL_SCHEMA_VERSION};
 
 /// Google source registration
 pub struct GoogleSource;
@@ -28,7 +30,10 @@ impl SourceRegistry for GoogleSource {
                 RegisteredStream::new("calendar")
                     .config_schema(calendar_config_schema())
                     .config_example(calendar_config_example())
+                    .config_type::<GoogleCalendarConfig>()
                     .transform("calendar_event", |_ctx| Ok(Box::new(GoogleCalendarTransform)))
+                    .dedup_key("event_id")
+                    .partition_key("start_time")
                     .stream_creator(|ctx| {
                         Ok(StreamType::Pull(Box::new(GoogleCalendarStream::new(
                             ctx.source_id.clone(),
@@ -42,11 +47,16 @@ impl SourceRegistry for GoogleSource {
                 RegisteredStream::new("gmail")
                     .config_schema(gmail_config_schema())
                     .config_example(gmail_config_example())
+                    .config_type::<GoogleGmailConfig>()
                     .transform("communication_email", |_ctx| Ok(Box::new(GmailEmailTransform)))
+                    .dedup_key("message_id")
+                    .partition_key("date")
+                    .schema_version(GMAIL_SCHEMA_VERSION)
                     .stream_creator(|ctx| {
                         Ok(StreamType::Pull(Box::new(GoogleGmailStream::new(
                             ctx.source_id.clone(),
                             ctx.db.clone(),
+                            ctx.storage.clone(),
                             ctx.stream_writer.clone(),
                             ctx.auth.clone(),
                         ))))
@@ -131,6 +141,28 @@ fn gmail_config_schema() -> serde_json::Value {
                 "default": true,
                 "description": "Fetch full message body content"
             },
+            "metadata_only": {
+                "type": "boolean",
+                "default": false,
+                "description": "Fetch only sender, recipients, subject, date and labels (much faster; no bodies or attachment details)"
+            },
+            "body_storage": {
+                "type": "string",
+                "enum": ["inline", "external"],
+                "default": "inline",
+                "description": "Keep bodies in each record, or in separate storage objects referenced by key (smaller archives)"
+            },
+            "fetch_attachments": {
+                "type": "boolean",
+                "default": false,
+                "description": "Download attachments into storage; records list their keys in attachment_keys"
+            },
+            "max_attachment_size_bytes": {
+                "type": "integer",
+                "default": 26214400,
+                "minimum": 1,
+                "description": "Largest attachment downloaded; larger ones keep only their name, type and size"
+            },
             "sync_strategy": SyncStrategy::json_schema(),
             "max_messages_per_sync": {
                 "type": "integer",
@@ -139,9 +171,33 @@ fn gmail_config_schema() -> serde_json::Value {
                 "maximum": 1000,
                 "description": "Maximum number of messages to fetch per sync"
             },
+            "fetch_concurrency": {
+                "type": "integer",
+                "default": 8,
+                "minimum": 1,
+                "maximum": 32,
+                "description": "Messages fetched at once while syncing"
+            },
             "query": {
                 "type": "string",
                 "description": "Gmail search query filter (optional, uses Gmail search syntax)"
+            },
+            "max_mime_depth": {
+                "type": "integer",
+                "default": 20,
+                "minimum": 1,
+                "description": "Deepest MIME part nesting read; deeper messages are flagged content_truncated"
+            },
+            "max_mime_parts": {
+                "type": "integer",
+                "default": 500,
+                "minimum": 1,
+                "description": "Most MIME parts read per message; larger messages are flagged content_truncated"
+            },
+            "strip_plus_tags": {
+                "type": "boolean",
+                "default": false,
+                "description": "Treat user+tag@domain as user@domain in parsed addresses"
             }
         }
     })
@@ -154,12 +210,20 @@ fn gmail_config_example() -> serde_json::Value {
         "include_spam_trash": false,
         "sync_mode": "messages",
         "fetch_body": true,
+        "metadata_only": false,
+        "body_storage": "inline",
+        "fetch_attachments": false,
+        "max_attachment_size_bytes": 26214400,
         "sync_strategy": {
             "type": "time_window",
             "days_back": 365
         },
         "max_messages_per_sync": 500,
-        "query": null
+        "fetch_concurrency": 8,
+        "query": null,
+        "max_mime_depth": 20,
+        "max_mime_parts": 500,
+        "strip_plus_tags": false
     })
 }
 
@@ -7,7 +7,7 @@ use tokio::sync::Mutex;
 use crate::{
     error::Result,
     registry::RegisteredStream,
-    sources::push_stream::{IngestPayload, PushResult, PushStream},
+    sources::push_stream::{write_prepared, IngestPayload, PreparedRecord, PushResult, PushStream},
     storage::stream_writer::StreamWriter,
 };
 
@@ -43,8 +43,9 @@ impl IosContactsStream {
 impl PushStream for IosContactsStream {
     async fn receive_push(&self, source_id: &str, payload: IngestPayload) -> Result<PushResult> {
         let mut result = PushResult::new(payload.records.len());
+        let mut prepared = Vec::with_capacity(payload.records.len());
 
-        for record in &payload.records {
+        for (index, record) in payload.records.iter().enumerate() {
             // We expect contacts to maybe NOT have a timestamp in the record itself?
             // Or maybe they do (last_updated).
             // If missing, default to batch timestamp.
@@ -57,14 +58,24 @@ impl PushStream for IosContactsStream {
                     payload.timestamp
                 };
 
-            // Write to writer
-            {
-                let mut writer = self.stream_writer.lock().await;
-                writer.write_record(source_id, "contacts", record.clone(), Some(timestamp_dt))?;
-            }
-            result.records_written += 1;
+            prepared.push(PreparedRecord {
+                index,
+                record: record.clone(),
+                timestamp: Some(timestamp_dt),
+            });
         }
 
+        // Write to writer
+        write_prepared(
+            &self.stream_writer,
+            source_id,
+            "contacts",
+            &payload.records,
+            prepared,
+            &mut result,
+        )
+        .await;
+
         tracing::info!(
             "Processed {} Contacts records from device {}",
             result.records_written,
@@ -11,7 +11,7 @@ use tokio::sync::Mutex;
 use crate::{
     error::Result,
     registry::RegisteredStream,
-    sources::push_stream::{IngestPayload, PushResult, PushStream},
+    sources::push_stream::{write_prepared, IngestPayload, PreparedRecord, PushResult, PushStream},
     storage::stream_writer::StreamWriter,
 };
 
@@ -44,8 +44,9 @@ impl IosEventKitStream {
 impl PushStream for IosEventKitStream {
     async fn receive_push(&self, source_id: &str, payload: IngestPayload) -> Result<PushResult> {
         let mut result = PushResult::new(payload.records.len());
+        let mut prepared = Vec::new();
 
-        for record in &payload.records {
+        for (index, record) in payload.records.iter().enumerate() {
             // Handle calendar events
             if let Some(events) = record.get("events").and_then(|v| v.as_array()) {
                 for event in events {
@@ -64,11 +65,11 @@ impl PushStream for IosEventKitStream {
                         obj.insert("record_type".to_string(), serde_json::json!("event"));
                     }
 
-                    {
-                        let mut writer = self.stream_writer.lock().await;
-                        writer.write_record(source_id, "eventkit", event_record, Some(timestamp_dt))?;
-                    }
-                    result.records_written += 1;
+                    prepared.push(PreparedRecord {
+                        index,
+                        record: event_record,
+                        timestamp: Some(timestamp_dt),
+                    });
                 }
             }
 
@@ -90,15 +91,25 @@ impl PushStream for IosEventKitStream {
                         obj.insert("record_type".to_string(), serde_json::json!("reminder"));
                     }
 
-                    {
-                        let mut writer = self.stream_writer.lock().await;
-                        writer.write_record(source_id, "eventkit", reminder_record, Some(timestamp_dt))?;
-                    }
-                    result.records_written += 1;
+                    prepared.push(PreparedRecord {
+                        index,
+                        record: reminder_record,
+                        timestamp: Some(timestamp_dt),
+                    });
                 }
             }
         }
 
+        write_prepared(
+            &self.stream_writer,
+            source_id,
+            "eventkit",
+            &payload.records,
+            prepared,
+            &mut result,
+        )
+        .await;
+
         tracing::info!(
             "Processed {} EventKit records from device {}",
             result.records_written,
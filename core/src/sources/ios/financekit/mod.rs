@@ -10,7 +10,7 @@ use tokio::sync::Mutex;
 
 use crate::{
     error::Result,
-    sources::push_stream::{IngestPayload, PushResult, PushStream},
+    sources::push_stream::{write_prepared, IngestPayload, PreparedRecord, PushResult, PushStream},
     storage::stream_writer::StreamWriter,
 };
 
@@ -38,8 +38,9 @@ impl IosFinanceKitStream {
 impl PushStream for IosFinanceKitStream {
     async fn receive_push(&self, source_id: &str, payload: IngestPayload) -> Result<PushResult> {
         let mut result = PushResult::new(payload.records.len());
+        let mut prepared = Vec::with_capacity(payload.records.len());
 
-        for record in &payload.records {
+        for (index, record) in payload.records.iter().enumerate() {
             // Each record is a wrapper: { accounts: [...], transactions: [...] }
             // Derive timestamp from the first transaction's date, or fall back to payload timestamp
             let timestamp_dt = record
@@ -52,16 +53,25 @@ impl PushStream for IosFinanceKitStream {
                 .map(|dt| dt.with_timezone(&Utc))
                 .unwrap_or(payload.timestamp);
 
-            // Write the full wrapper record to the lake — transforms know
-            // how to read the accounts and transactions arrays from it
-            {
-                let mut writer = self.stream_writer.lock().await;
-                writer.write_record(source_id, "financekit", record.clone(), Some(timestamp_dt))?;
-            }
-
-            result.records_written += 1;
+            prepared.push(PreparedRecord {
+                index,
+                record: record.clone(),
+                timestamp: Some(timestamp_dt),
+            });
         }
 
+        // Write the full wrapper records to the lake — transforms know
+        // how to read the accounts and transactions arrays from them
+        write_prepared(
+            &self.stream_writer,
+            source_id,
+            "financekit",
+            &payload.records,
+            prepared,
+            &mut result,
+        )
+        .await;
+
         tracing::info!(
             "Processed {} FinanceKit records from device {}",
             result.records_written,
@@ -0,0 +1,370 @@
+//! Detailed HealthKit sleep stages and workout routes
+//!
+//! Besides the flat per-sample values, the iOS collector can send:
+//!
+//! - `sleep_stages` on a `sleep` record: the session's stage intervals
+//!   (`[{"stage": "rem", "start": "...", "end": "..."}, ...]`, stages `awake`,
+//!   `core`, `deep`, `rem` or `unspecified`). The session runs from
+//!   `timestamp` to `end_time` (or `timestamp` plus the duration); intervals
+//!   must fall inside it and must not overlap.
+//! - `route` on a `workout` record: the workout's GPS samples
+//!   (`[{"latitude": .., "longitude": .., "altitude": .., "timestamp": ".."}]`),
+//!   stored on `health_workout` as a GeoJSON LineString.
+//!
+//! Both are validated at ingest, so a bad payload is rejected before it is
+//! archived, and parsed again by the transforms.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::sources::base::{validate_latitude, validate_longitude};
+
+/// A HealthKit sleep stage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SleepStage {
+    Awake,
+    Core,
+    Deep,
+    Rem,
+    Unspecified,
+}
+
+impl SleepStage {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Awake => "awake",
+            Self::Core => "core",
+            Self::Deep => "deep",
+            Self::Rem => "rem",
+            Self::Unspecified => "unspecified",
+        }
+    }
+}
+
+/// One interval of a sleep session spent in a single stage
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SleepStageInterval {
+    pub stage: SleepStage,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl SleepStageInterval {
+    pub fn duration_minutes(&self) -> i64 {
+        (self.end - self.start).num_minutes()
+    }
+}
+
+/// One GPS sample of a workout route
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoutePoint {
+    pub latitude: f64,
+    pub longitude: f64,
+    #[serde(default)]
+    pub altitude: Option<f64>,
+    #[serde(default)]
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+fn parse_time(record: &Value, key: &str) -> Option<DateTime<Utc>> {
+    record
+        .get(key)
+        .and_then(|v| v.as_str())
+        .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+}
+
+/// Sleep duration in minutes (iOS sends it in `metadata.duration_minutes`)
+pub fn sleep_duration_minutes(record: &Value) -> Option<i64> {
+    record
+        .get("metadata")
+        .and_then(|m| m.get("duration_minutes"))
+        .and_then(|v| v.as_i64())
+        .or_else(|| record.get("sleep_duration").and_then(|v| v.as_i64()))
+}
+
+/// End of a sleep session: `end_time`, else the start plus the duration
+pub fn sleep_session_end(record: &Value, start: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    parse_time(record, "end_time")
+        .or_else(|| sleep_duration_minutes(record).map(|m| start + Duration::minutes(m)))
+}
+
+/// Parse and validate a sleep record's stage intervals, sorted by start
+///
+/// Returns `None` when the record has no `sleep_stages`.
+pub fn parse_sleep_stages(
+    record: &Value,
+    session_start: DateTime<Utc>,
+    session_end: Option<DateTime<Utc>>,
+) -> Result<Option<Vec<SleepStageInterval>>> {
+    let Some(stages) = record.get("sleep_stages").filter(|v| !v.is_null()) else {
+        return Ok(None);
+    };
+    let mut intervals: Vec<SleepStageInterval> = serde_json::from_value(stages.clone())
+        .map_err(|e| Error::InvalidInput(format!("Invalid sleep_stages: {e}")))?;
+    intervals.sort_by_key(|i| i.start);
+
+    if let Some(end) = session_end {
+        if end <= session_start {
+            return Err(Error::InvalidInput(
+                "Sleep session must end after it starts".into(),
+            ));
+        }
+    }
+
+    let mut previous_end: Option<DateTime<Utc>> = None;
+    for interval in &intervals {
+        if interval.end <= interval.start {
+            return Err(Error::InvalidInput(format!(
+                "Sleep stage {} ending at {} does not end after it starts",
+                interval.stage.as_str(),
+                interval.end
+            )));
+        }
+        let after_end = session_end.is_some_and(|end| interval.end > end);
+        if interval.start < session_start || after_end {
+            return Err(Error::InvalidInput(format!(
+                "Sleep stage {} at {} is outside the sleep session",
+                interval.stage.as_str(),
+                interval.start
+            )));
+        }
+        if previous_end.is_some_and(|prev| interval.start < prev) {
+            return Err(Error::InvalidInput(format!(
+                "Sleep stage {} at {} overlaps the previous stage",
+                interval.stage.as_str(),
+                interval.start
+            )));
+        }
+        previous_end = Some(interval.end);
+    }
+
+    Ok(Some(intervals))
+}
+
+/// Stage intervals as stored in `health_sleep.sleep_stages`
+pub fn sleep_stages_json(intervals: &[SleepStageInterval]) -> Value {
+    Value::Array(
+        intervals
+            .iter()
+            .map(|i| {
+                serde_json::json!({
+                    "stage": i.stage,
+                    "start": i.start,
+                    "end": i.end,
+                    "duration_minutes": i.duration_minutes(),
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Total minutes per stage, e.g. `{"deep": 62, "rem": 95}`
+pub fn stage_minutes(intervals: &[SleepStageInterval]) -> Value {
+    let mut totals = serde_json::Map::new();
+    for interval in intervals {
+        let total = totals
+            .entry(interval.stage.as_str())
+            .or_insert_with(|| Value::from(0));
+        *total = Value::from(total.as_i64().unwrap_or(0) + interval.duration_minutes());
+    }
+    Value::Object(totals)
+}
+
+/// Parse and validate a workout record's GPS route
+///
+/// Points must have valid coordinates, and timestamped points must be in
+/// order and inside the workout. Returns `None` when the record has no
+/// `route`.
+pub fn parse_workout_route(
+    record: &Value,
+    workout_start: DateTime<Utc>,
+    workout_end: Option<DateTime<Utc>>,
+) -> Result<Option<Vec<RoutePoint>>> {
+    let Some(route) = record.get("route").filter(|v| !v.is_null()) else {
+        return Ok(None);
+    };
+    let points: Vec<RoutePoint> = serde_json::from_value(route.clone())
+        .map_err(|e| Error::InvalidInput(format!("Invalid workout route: {e}")))?;
+    if points.len() < 2 {
+        return Err(Error::InvalidInput(
+            "Workout route needs at least two points".into(),
+        ));
+    }
+
+    let mut previous: Option<DateTime<Utc>> = None;
+    for point in &points {
+        validate_latitude(point.latitude)?;
+        validate_longitude(point.longitude)?;
+
+        let Some(ts) = point.timestamp else {
+            continue;
+        };
+        if ts < workout_start || workout_end.is_some_and(|end| ts > end) {
+            return Err(Error::InvalidInput(format!(
+                "Route point at {ts} is outside the workout"
+            )));
+        }
+        if previous.is_some_and(|prev| ts < prev) {
+            return Err(Error::InvalidInput(format!(
+                "Route point at {ts} is out of order"
+            )));
+        }
+        previous = Some(ts);
+    }
+
+    Ok(Some(points))
+}
+
+/// A route as a GeoJSON LineString (`[longitude, latitude, altitude?]`)
+pub fn route_geometry(points: &[RoutePoint]) -> Value {
+    let coordinates: Vec<Value> = points
+        .iter()
+        .map(|p| match p.altitude {
+            Some(alt) => serde_json::json!([p.longitude, p.latitude, alt]),
+            None => serde_json::json!([p.longitude, p.latitude]),
+        })
+        .collect();
+    serde_json::json!({ "type": "LineString", "coordinates": coordinates })
+}
+
+/// Validate the detailed payload of an ingested record, if it has one
+pub fn validate_record_detail(record: &Value, timestamp: DateTime<Utc>) -> Result<()> {
+    match record.get("metric_type").and_then(|v| v.as_str()) {
+        Some("sleep") => {
+            parse_sleep_stages(record, timestamp, sleep_session_end(record, timestamp))?;
+        }
+        Some("workout") => {
+            parse_workout_route(record, timestamp, workout_end(record, timestamp))?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// End of a workout: `end_time`, else the start plus `workout_duration` minutes
+pub fn workout_end(record: &Value, start: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    parse_time(record, "end_time").or_else(|| {
+        record
+            .get("workout_duration")
+            .and_then(|v| v.as_i64())
+            .map(|m| start + Duration::minutes(m))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    fn sleep_record(stages: Value) -> Value {
+        json!({
+            "metric_type": "sleep",
+            "timestamp": "2025-01-15T23:00:00Z",
+            "end_time": "2025-01-16T07:00:00Z",
+            "sleep_stages": stages,
+        })
+    }
+
+    #[test]
+    fn test_parse_sleep_stages() {
+        let record = sleep_record(json!([
+            {"stage": "deep", "start": "2025-01-15T23:30:00Z", "end": "2025-01-16T00:30:00Z"},
+            {"stage": "core", "start": "2025-01-15T23:00:00Z", "end": "2025-01-15T23:30:00Z"},
+            {"stage": "rem", "start": "2025-01-16T00:30:00Z", "end": "2025-01-16T01:15:00Z"},
+            {"stage": "core", "start": "2025-01-16T01:15:00Z", "end": "2025-01-16T01:45:00Z"},
+        ]));
+        let start = ts("2025-01-15T23:00:00Z");
+
+        let intervals = parse_sleep_stages(&record, start, sleep_session_end(&record, start))
+            .unwrap()
+            .unwrap();
+        assert_eq!(intervals[0].stage, SleepStage::Core);
+        assert_eq!(intervals[1].duration_minutes(), 60);
+        assert_eq!(
+            stage_minutes(&intervals),
+            json!({"core": 60, "deep": 60, "rem": 45})
+        );
+        assert_eq!(sleep_stages_json(&intervals)[2]["duration_minutes"], 45);
+
+        // Records without stages are fine
+        let flat = json!({"metric_type": "sleep", "timestamp": "2025-01-15T23:00:00Z"});
+        assert_eq!(parse_sleep_stages(&flat, start, None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_reject_invalid_sleep_stages() {
+        let start = ts("2025-01-15T23:00:00Z");
+        let check = |stages: Value| {
+            let record = sleep_record(stages);
+            parse_sleep_stages(&record, start, sleep_session_end(&record, start))
+        };
+
+        // Overlapping intervals
+        assert!(check(json!([
+            {"stage": "core", "start": "2025-01-15T23:00:00Z", "end": "2025-01-16T00:00:00Z"},
+            {"stage": "deep", "start": "2025-01-15T23:45:00Z", "end": "2025-01-16T01:00:00Z"},
+        ]))
+        .is_err());
+        // Outside the session
+        assert!(check(json!([
+            {"stage": "awake", "start": "2025-01-16T06:30:00Z", "end": "2025-01-16T07:30:00Z"},
+        ]))
+        .is_err());
+        assert!(check(json!([
+            {"stage": "awake", "start": "2025-01-15T22:30:00Z", "end": "2025-01-15T23:10:00Z"},
+        ]))
+        .is_err());
+        // Empty or unknown stage
+        assert!(check(json!([
+            {"stage": "rem", "start": "2025-01-16T01:00:00Z", "end": "2025-01-16T01:00:00Z"},
+        ]))
+        .is_err());
+        assert!(check(json!([
+            {"stage": "dreaming", "start": "2025-01-16T01:00:00Z", "end": "2025-01-16T02:00:00Z"},
+        ]))
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_workout_route() {
+        let start = ts("2025-01-15T08:00:00Z");
+        let record = json!({
+            "metric_type": "workout",
+            "timestamp": "2025-01-15T08:00:00Z",
+            "workout_duration": 30,
+            "route": [
+                {"latitude": 37.77, "longitude": -122.41, "altitude": 12.5, "timestamp": "2025-01-15T08:00:05Z"},
+                {"latitude": 37.78, "longitude": -122.42, "timestamp": "2025-01-15T08:10:00Z"},
+            ],
+        });
+
+        let points = parse_workout_route(&record, start, workout_end(&record, start))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            route_geometry(&points),
+            json!({
+                "type": "LineString",
+                "coordinates": [[-122.41, 37.77, 12.5], [-122.42, 37.78]],
+            })
+        );
+        assert!(validate_record_detail(&record, start).is_ok());
+
+        // A point after the workout ended
+        let mut late = record.clone();
+        late["route"][1]["timestamp"] = json!("2025-01-15T09:00:00Z");
+        assert!(validate_record_detail(&late, start).is_err());
+
+        // Out-of-range coordinates
+        let mut bad = record.clone();
+        bad["route"][0]["latitude"] = json!(123.0);
+        assert!(validate_record_detail(&bad, start).is_err());
+    }
+}
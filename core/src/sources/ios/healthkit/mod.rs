@@ -1,9 +1,11 @@
 //! iOS HealthKit data processor and transforms
 
+pub mod detail;
 pub mod transform;
 
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
 use sqlx::SqlitePool;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -16,7 +18,7 @@ use crate::{
             validate_heart_rate, validate_percentage, validate_positive,
             validate_timestamp_reasonable,
         },
-        push_stream::{IngestPayload, PushResult, PushStream},
+        push_stream::{write_prepared, IngestPayload, PreparedRecord, PushResult, PushStream},
     },
     storage::stream_writer::StreamWriter,
 };
@@ -51,57 +53,33 @@ impl PushStream for IosHealthKitStream {
         self.validate_payload(&payload)?;
 
         let mut result = PushResult::new(payload.records.len());
+        let mut prepared = Vec::with_capacity(payload.records.len());
 
         // source_id is passed from handler - single source of truth, no duplicate DB query
 
-        // Process each record
-        for record in &payload.records {
-            // Parse timestamp
-            let timestamp = record
-                .get("timestamp")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| Error::Other("Missing timestamp in record".into()))?;
-
-            let timestamp_dt = chrono::DateTime::parse_from_rfc3339(timestamp)
-                .map_err(|e| Error::Other(format!("Invalid timestamp format: {e}")))?
-                .with_timezone(&Utc);
-            validate_timestamp_reasonable(timestamp_dt)?;
-
-            // Extract and validate health metrics (all optional)
-            if let Some(hr) = record.get("heart_rate").and_then(|v| v.as_f64()) {
-                validate_heart_rate(hr)?;
+        // Validate each record on its own so one bad record doesn't reject the batch
+        for (index, record) in payload.records.iter().enumerate() {
+            match validate_record(record) {
+                Ok(timestamp_dt) => prepared.push(PreparedRecord {
+                    index,
+                    record: record.clone(),
+                    timestamp: Some(timestamp_dt),
+                }),
+                Err(e) => result.reject(index, record, e),
             }
-            if let Some(rhr) = record.get("resting_heart_rate").and_then(|v| v.as_f64()) {
-                validate_heart_rate(rhr)?;
-            }
-            if let Some(hrv_val) = record.get("hrv").and_then(|v| v.as_f64()) {
-                validate_positive("HRV", hrv_val)?;
-            }
-            if let Some(s) = record
-                .get("steps")
-                .and_then(|v| v.as_i64())
-                .map(|v| v as i32)
-            {
-                if s < 0 {
-                    return Err(Error::InvalidInput("Steps cannot be negative".into()));
-                }
-            }
-            if let Some(d) = record.get("distance").and_then(|v| v.as_f64()) {
-                validate_positive("Distance", d)?;
-            }
-            if let Some(bf) = record.get("body_fat_percentage").and_then(|v| v.as_f64()) {
-                validate_percentage("Body fat percentage", bf)?;
-            }
-
-            // Write to object storage via StreamWriter
-            {
-                let mut writer = self.stream_writer.lock().await;
-                writer.write_record(source_id, "healthkit", record.clone(), Some(timestamp_dt))?;
-            }
-
-            result.records_written += 1;
         }
 
+        // Write to object storage via StreamWriter
+        write_prepared(
+            &self.stream_writer,
+            source_id,
+            "healthkit",
+            &payload.records,
+            prepared,
+            &mut result,
+        )
+        .await;
+
         tracing::info!(
             "Processed {} HealthKit records from device {}",
             result.records_written,
@@ -123,3 +101,48 @@ impl PushStream for IosHealthKitStream {
         "ios"
     }
 }
+
+/// Validate a record and return its event timestamp
+fn validate_record(record: &Value) -> Result<DateTime<Utc>> {
+    // Parse timestamp
+    let timestamp = record
+        .get("timestamp")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| Error::Other("Missing timestamp in record".into()))?;
+
+    let timestamp_dt = chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map_err(|e| Error::Other(format!("Invalid timestamp format: {e}")))?
+        .with_timezone(&Utc);
+    validate_timestamp_reasonable(timestamp_dt)?;
+
+    // Extract and validate health metrics (all optional)
+    if let Some(hr) = record.get("heart_rate").and_then(|v| v.as_f64()) {
+        validate_heart_rate(hr)?;
+    }
+    if let Some(rhr) = record.get("resting_heart_rate").and_then(|v| v.as_f64()) {
+        validate_heart_rate(rhr)?;
+    }
+    if let Some(hrv_val) = record.get("hrv").and_then(|v| v.as_f64()) {
+        validate_positive("HRV", hrv_val)?;
+    }
+    if let Some(s) = record
+        .get("steps")
+        .and_then(|v| v.as_i64())
+        .map(|v| v as i32)
+    {
+        if s < 0 {
+            return Err(Error::InvalidInput("Steps cannot be negative".into()));
+        }
+    }
+    if let Some(d) = record.get("distance").and_then(|v| v.as_f64()) {
+        validate_positive("Distance", d)?;
+    }
+    if let Some(bf) = record.get("body_fat_percentage").and_then(|v| v.as_f64()) {
+        validate_percentage("Body fat percentage", bf)?;
+    }
+
+    // Sleep stages and workout routes
+    detail::validate_record_detail(record, timestamp_dt)?;
+
+    Ok(timestamp_dt)
+}
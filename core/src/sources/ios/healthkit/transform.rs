@@ -12,6 +12,8 @@ use crate::error::Result;
 use crate::jobs::TransformContext;
 use crate::sources::base::{OntologyTransform, TransformRegistration, TransformResult};
 
+use super::detail;
+
 /// Batch size for bulk inserts
 const BATCH_SIZE: usize = 500;
 
@@ -710,21 +712,30 @@ impl OntologyTransform for HealthKitSleepTransform {
                 if metric_type != "sleep" {
                     continue; // Skip non-sleep records
                 }
-                // iOS sends duration in metadata.duration_minutes for sleep
-                let sleep_duration = record.get("metadata")
-                    .and_then(|m| m.get("duration_minutes"))
-                    .and_then(|v| v.as_i64())
-                    .or_else(|| record.get("sleep_duration").and_then(|v| v.as_i64()));
-                let Some(sleep_duration) = sleep_duration else {
-                    continue;
-                };
-
                 let timestamp = record
                     .get("timestamp")
                     .and_then(|v| v.as_str())
                     .and_then(|s| s.parse::<DateTime<Utc>>().ok())
                     .unwrap_or_else(|| Utc::now());
 
+                // iOS sends duration in metadata.duration_minutes for sleep;
+                // detailed payloads may send end_time instead
+                let Some(end_time) = detail::sleep_session_end(record, timestamp) else {
+                    continue;
+                };
+                let sleep_duration = detail::sleep_duration_minutes(record)
+                    .unwrap_or_else(|| (end_time - timestamp).num_minutes());
+
+                let stage_intervals =
+                    match detail::parse_sleep_stages(record, timestamp, Some(end_time)) {
+                        Ok(intervals) => intervals,
+                        Err(e) => {
+                            tracing::warn!(error = %e, "Skipping sleep record with invalid stages");
+                            records_failed += 1;
+                            continue;
+                        }
+                    };
+
                 let stream_id = record
                     .get("id")
                     .and_then(|v| v.as_str())
@@ -738,11 +749,12 @@ impl OntologyTransform for HealthKitSleepTransform {
 
                 let raw_data = record.get("raw_data").cloned();
 
-                // Build sleep_stages JSON from raw_data if available
-                let sleep_stages = raw_data
-                    .as_ref()
-                    .and_then(|d| d.get("stages"))
-                    .cloned()
+                // Prefer detailed stage intervals, then raw_data stages, then
+                // the single sleep_stage value
+                let sleep_stages = stage_intervals
+                    .as_deref()
+                    .map(detail::sleep_stages_json)
+                    .or_else(|| raw_data.as_ref().and_then(|d| d.get("stages")).cloned())
                     .or_else(|| {
                         sleep_stage.as_ref().map(|stage| {
                             serde_json::json!([{
@@ -752,12 +764,12 @@ impl OntologyTransform for HealthKitSleepTransform {
                         })
                     });
 
-                // Calculate end_time from timestamp + duration
-                let end_time = timestamp + chrono::Duration::minutes(sleep_duration);
-
-                let metadata = serde_json::json!({
+                let mut metadata = serde_json::json!({
                     "healthkit_raw": raw_data,
                 });
+                if let Some(intervals) = &stage_intervals {
+                    metadata["stage_minutes"] = detail::stage_minutes(intervals);
+                }
 
                 last_processed_id = Some(stream_id.clone());
 
@@ -934,6 +946,7 @@ impl OntologyTransform for HealthKitWorkoutTransform {
             Option<String>, // place_id
             DateTime<Utc>,  // start_time
             DateTime<Utc>,  // end_time
+            Option<serde_json::Value>, // route_geometry
             String,         // stream_id
             serde_json::Value, // metadata
         )> = Vec::new();
@@ -1000,6 +1013,20 @@ impl OntologyTransform for HealthKitWorkoutTransform {
                     "intensity": intensity,
                 });
 
+                // GPS route as a GeoJSON LineString; a bad route is dropped
+                // rather than failing the whole workout
+                let route_geometry = match detail::parse_workout_route(
+                    record,
+                    timestamp,
+                    detail::workout_end(record, timestamp),
+                ) {
+                    Ok(points) => points.as_deref().map(detail::route_geometry),
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Ignoring invalid workout route");
+                        None
+                    }
+                };
+
                 last_processed_id = Some(stream_id.clone());
 
                 // Add to pending batch
@@ -1014,6 +1041,7 @@ impl OntologyTransform for HealthKitWorkoutTransform {
                     None,                                 // place_id
                     timestamp,
                     end_time,
+                    route_geometry,
                     stream_id,
                     metadata,
                 ));
@@ -1330,6 +1358,7 @@ async fn execute_workout_batch_insert(
         Option<String>, // place_id
         DateTime<Utc>,  // start_time
         DateTime<Utc>,  // end_time
+        Option<serde_json::Value>, // route_geometry
         String,         // stream_id
         serde_json::Value, // metadata
     )],
@@ -1351,6 +1380,7 @@ async fn execute_workout_batch_insert(
             "place_id",
             "start_time",
             "end_time",
+            "route_geometry",
             "source_stream_id",
             "source_table",
             "source_provider",
@@ -1374,6 +1404,7 @@ async fn execute_workout_batch_insert(
         place_id,
         start_time,
         end_time,
+        route_geometry,
         stream_id,
         metadata,
     ) in records
@@ -1389,6 +1420,7 @@ async fn execute_workout_batch_insert(
             .bind(place_id)
             .bind(start_time)
             .bind(end_time)
+            .bind(route_geometry)
             .bind(stream_id)
             .bind("stream_ios_healthkit")
             .bind("ios")
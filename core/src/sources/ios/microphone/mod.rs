@@ -5,7 +5,8 @@
 pub mod transform;
 
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
 use sqlx::SqlitePool;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -15,7 +16,7 @@ use crate::{
     error::{Error, Result},
     sources::{
         base::validation::validate_timestamp_reasonable,
-        push_stream::{IngestPayload, PushResult, PushStream},
+        push_stream::{write_prepared, IngestPayload, PreparedRecord, PushResult, PushStream},
     },
     storage::{stream_writer::StreamWriter, Storage},
 };
@@ -51,22 +52,19 @@ impl PushStream for IosMicrophoneStream {
         self.validate_payload(&payload)?;
 
         let mut result = PushResult::new(payload.records.len());
+        let mut prepared = Vec::with_capacity(payload.records.len());
 
         // source_id is passed from handler - single source of truth, no duplicate DB query
 
-        // Process each record
-        for record in &payload.records {
-            // iOS sends timestamp_start and timestamp_end for microphone chunks
-            let timestamp = record
-                .get("timestamp_start")
-                .or_else(|| record.get("timestamp"))
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| Error::Other("Missing timestamp in record".into()))?;
-
-            let timestamp_dt = chrono::DateTime::parse_from_rfc3339(timestamp)
-                .map_err(|e| Error::Other(format!("Invalid timestamp format: {e}")))?
-                .with_timezone(&Utc);
-            validate_timestamp_reasonable(timestamp_dt)?;
+        // Process each record; a bad record is rejected without failing the batch
+        for (index, record) in payload.records.iter().enumerate() {
+            let timestamp_dt = match record_timestamp(record) {
+                Ok(timestamp_dt) => timestamp_dt,
+                Err(e) => {
+                    result.reject(index, record, e);
+                    continue;
+                }
+            };
 
             // Handle audio file upload if present
             let mut audio_file_key: Option<String> = None;
@@ -107,20 +105,24 @@ impl PushStream for IosMicrophoneStream {
                 }
             }
 
-            // Write to object storage via StreamWriter
-            {
-                let mut writer = self.stream_writer.lock().await;
-                writer.write_record(
-                    source_id,
-                    "microphone",
-                    record_with_audio,
-                    Some(timestamp_dt),
-                )?;
-            }
-
-            result.records_written += 1;
+            prepared.push(PreparedRecord {
+                index,
+                record: record_with_audio,
+                timestamp: Some(timestamp_dt),
+            });
         }
 
+        // Write to object storage via StreamWriter
+        write_prepared(
+            &self.stream_writer,
+            source_id,
+            "microphone",
+            &payload.records,
+            prepared,
+            &mut result,
+        )
+        .await;
+
         tracing::info!(
             "Processed {} microphone records from device {}",
             result.records_written,
@@ -142,3 +144,19 @@ impl PushStream for IosMicrophoneStream {
         "ios"
     }
 }
+
+/// Extract and validate a microphone chunk's start time
+fn record_timestamp(record: &Value) -> Result<DateTime<Utc>> {
+    // iOS sends timestamp_start and timestamp_end for microphone chunks
+    let timestamp = record
+        .get("timestamp_start")
+        .or_else(|| record.get("timestamp"))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| Error::Other("Missing timestamp in record".into()))?;
+
+    let timestamp_dt = chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map_err(|e| Error::Other(format!("Invalid timestamp format: {e}")))?
+        .with_timezone(&Utc);
+    validate_timestamp_reasonable(timestamp_dt)?;
+    Ok(timestamp_dt)
+}
@@ -13,7 +13,7 @@ use uuid::Uuid;
 use crate::database::Database;
 use crate::error::{Error, Result};
 use crate::http_client;
-use crate::jobs::TransformContext;
+use crate::jobs::{cost, TransformContext};
 use crate::sources::base::{OntologyTransform, TransformResult};
 use crate::tollbooth;
 
@@ -157,6 +157,9 @@ impl IosMicrophoneTransform {
             .await
             .map_err(|e| Error::ExternalApi(format!("Failed to parse Tollbooth response: {e}")))?;
 
+        // Tokens are billed whether or not the output parses
+        cost::record_llm_usage(&resp_json);
+
         // Extract the content string from choices[0].message.content
         let content_str = resp_json
             .get("choices")
@@ -284,7 +287,12 @@ impl OntologyTransform for IosMicrophoneTransform {
 
                 // Call Gemini via Tollbooth
                 let transcription = match self.transcribe_audio(&audio_b64, audio_format).await {
-                    Ok(t) => t,
+                    Ok(t) => {
+                        if let Some(seconds) = duration_seconds {
+                            cost::record_transcription_seconds(seconds);
+                        }
+                        t
+                    }
                     Err(Error::ExternalApi(msg)) if msg.contains("429") => {
                         tracing::warn!("Rate limited, stopping transform early to retry later");
                         return Ok(TransformResult {
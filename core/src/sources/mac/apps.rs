@@ -1,7 +1,8 @@
 //! macOS application usage data processor
 
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
 use sqlx::SqlitePool;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -11,7 +12,7 @@ use crate::{
     error::{Error, Result},
     sources::{
         base::validation::validate_timestamp_reasonable,
-        push_stream::{IngestPayload, PushResult, PushStream},
+        push_stream::{write_prepared, IngestPayload, PreparedRecord, PushResult, PushStream},
     },
     storage::stream_writer::StreamWriter,
 };
@@ -41,32 +42,33 @@ impl PushStream for MacAppsStream {
         self.validate_payload(&payload)?;
 
         let mut result = PushResult::new(payload.records.len());
+        let mut prepared = Vec::with_capacity(payload.records.len());
 
         // source_id is passed from handler - single source of truth, no duplicate DB query
 
-        // Process each record
-        for record in &payload.records {
-            // Extract timestamp
-            let timestamp = record
-                .get("timestamp")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| Error::Other("Missing timestamp in record".into()))?;
-
-            let timestamp_dt = chrono::DateTime::parse_from_rfc3339(timestamp)
-                .map_err(|e| Error::Other(format!("Invalid timestamp format: {e}")))?
-                .with_timezone(&Utc);
-
-            validate_timestamp_reasonable(timestamp_dt)?;
-
-            // Write to object storage via StreamWriter
-            {
-                let mut writer = self.stream_writer.lock().await;
-                writer.write_record(source_id, "apps", record.clone(), Some(timestamp_dt))?;
+        // Validate each record on its own so one bad record doesn't reject the batch
+        for (index, record) in payload.records.iter().enumerate() {
+            match record_timestamp(record) {
+                Ok(timestamp_dt) => prepared.push(PreparedRecord {
+                    index,
+                    record: record.clone(),
+                    timestamp: Some(timestamp_dt),
+                }),
+                Err(e) => result.reject(index, record, e),
             }
-
-            result.records_written += 1;
         }
 
+        // Write to object storage via StreamWriter
+        write_prepared(
+            &self.stream_writer,
+            source_id,
+            "apps",
+            &payload.records,
+            prepared,
+            &mut result,
+        )
+        .await;
+
         tracing::info!(
             "Processed {} app records from device {}",
             result.records_written,
@@ -88,3 +90,18 @@ impl PushStream for MacAppsStream {
         "mac"
     }
 }
+
+/// Extract and validate a record's event timestamp
+fn record_timestamp(record: &Value) -> Result<DateTime<Utc>> {
+    let timestamp = record
+        .get("timestamp")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| Error::Other("Missing timestamp in record".into()))?;
+
+    let timestamp_dt = chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map_err(|e| Error::Other(format!("Invalid timestamp format: {e}")))?
+        .with_timezone(&Utc);
+
+    validate_timestamp_reasonable(timestamp_dt)?;
+    Ok(timestamp_dt)
+}
@@ -2,7 +2,9 @@
 
 pub mod auth;
 pub mod base;
+pub mod envelope;
 pub mod factory;
+pub mod fitbit;
 pub mod github;
 pub mod google;
 pub mod ios;
@@ -10,6 +12,7 @@ pub mod mac;
 pub mod notion;
 pub mod plaid;
 pub mod pull_stream;
+pub mod slack;
 pub mod spotify;
 pub mod strava;
 pub mod push_stream;
@@ -19,5 +22,5 @@ pub mod stream_type;
 pub use auth::SourceAuth;
 pub use factory::StreamFactory;
 pub use pull_stream::{PullStream, SyncMode, SyncResult};
-pub use push_stream::{IngestPayload, PushResult, PushStream};
+pub use push_stream::{IngestPayload, PushResult, PushStream, RecordResult, RecordStatus};
 pub use stream_type::StreamType;
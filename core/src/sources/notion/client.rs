@@ -1,52 +1,44 @@
-//! Notion API client - thin wrapper over OAuthHttpClient
+//! Notion API client - thin wrapper over SourceHttpClient
 //!
-//! This client delegates all OAuth HTTP operations to the base OAuthHttpClient,
+//! This client delegates all HTTP operations to the base SourceHttpClient,
 //! providing Notion-specific configuration and error handling.
 
-use serde::de::DeserializeOwned;
-use serde::Serialize;
 use std::sync::Arc;
 
 use super::error_handler::NotionErrorHandler;
-use crate::{
-    error::Result,
-    sources::base::{OAuthHttpClient, RetryConfig, TokenManager},
+use crate::sources::base::{
+    user_agent_for, RetryConfig, SourceClient, SourceHttpClient, TokenManager,
 };
 
 /// Notion API client with automatic token refresh and retry logic
 ///
-/// This is a thin wrapper that configures OAuthHttpClient for Notion APIs.
+/// This is a thin wrapper that configures SourceHttpClient for Notion APIs.
 /// All HTTP logic (retry, token refresh, error handling) is delegated to the base client.
 pub struct NotionApiClient {
-    http: OAuthHttpClient,
+    http: SourceHttpClient,
 }
 
 impl NotionApiClient {
     /// Create a new Notion API client
     pub fn new(source_id: String, token_manager: Arc<TokenManager>) -> Self {
         Self {
-            http: OAuthHttpClient::new(source_id, token_manager)
+            http: SourceHttpClient::oauth(source_id, token_manager)
                 .with_base_url("https://api.notion.com/v1")
+                .with_user_agent(&user_agent_for("notion"))
                 .with_retry_config(RetryConfig::default())
                 .with_header("Notion-Version", "2022-06-28")
                 .with_error_handler(Box::new(NotionErrorHandler)),
         }
     }
+}
 
-    /// Make an authenticated GET request
-    pub async fn get<T>(&self, path: &str) -> Result<T>
-    where
-        T: DeserializeOwned,
-    {
-        self.http.get(path).await
+impl SourceClient for NotionApiClient {
+    fn http(&self) -> &SourceHttpClient {
+        &self.http
     }
 
-    /// Make an authenticated POST request with JSON body
-    pub async fn post_json<T>(&self, path: &str, body: &impl Serialize) -> Result<T>
-    where
-        T: DeserializeOwned,
-    {
-        self.http.post(path, body).await
+    fn http_mut(&mut self) -> &mut SourceHttpClient {
+        &mut self.http
     }
 }
 
@@ -36,6 +36,32 @@ impl Default for NotionPagesConfig {
     }
 }
 
+/// Configuration for Notion Databases sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotionDatabasesConfig {
+    /// Database IDs to sync (empty = every database shared with the integration)
+    #[serde(default)]
+    pub database_ids: Vec<String>,
+
+    /// Include archived rows (default: false)
+    #[serde(default)]
+    pub include_archived: bool,
+
+    /// Rows per API request (default: 100, Notion's maximum)
+    #[serde(default = "default_page_size")]
+    pub page_size: u32,
+}
+
+impl Default for NotionDatabasesConfig {
+    fn default() -> Self {
+        Self {
+            database_ids: vec![],
+            include_archived: false,
+            page_size: default_page_size(),
+        }
+    }
+}
+
 fn default_page_size() -> u32 {
     100
 }
@@ -62,6 +88,21 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_default_databases_config() {
+        let config = NotionDatabasesConfig::default();
+        assert_eq!(config.page_size, 100);
+        assert!(!config.include_archived);
+        assert!(config.database_ids.is_empty());
+
+        let config = NotionDatabasesConfig::from_json(&serde_json::json!({
+            "database_ids": ["db-1"]
+        }))
+        .unwrap();
+        assert_eq!(config.database_ids, vec!["db-1"]);
+        assert_eq!(config.page_size, 100);
+    }
+
     #[test]
     fn test_json_serialization() {
         let config = NotionPagesConfig::default();
@@ -0,0 +1,680 @@
+//! Notion databases stream implementation
+//!
+//! Lists the databases shared with the integration (or the configured
+//! `database_ids`) and queries each one's rows. Every row is written as a
+//! record whose `properties` hold plain values (text, numbers, select names,
+//! dates, ...) keyed by property name, next to Notion's `raw_properties`.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::{json, Map, Value};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::{
+    error::Result,
+    jobs::progress,
+    sources::{
+        auth::SourceAuth,
+        base::{
+            load_resume_cursor, save_resume_cursor, ConfigSerializable, SourceClient, StreamLimits,
+            SyncMode, SyncResult,
+        },
+        pull_stream::PullStream,
+    },
+    storage::stream_writer::StreamWriter,
+};
+
+use super::{
+    client::NotionApiClient,
+    config::NotionDatabasesConfig,
+    types::{Database, DatabaseQueryResponse, DatabaseSearchResponse, Page},
+};
+
+/// Notion databases stream
+pub struct NotionDatabasesStream {
+    source_id: String,
+    client: NotionApiClient,
+    db: SqlitePool,
+    stream_writer: Arc<Mutex<StreamWriter>>,
+    config: NotionDatabasesConfig,
+}
+
+impl NotionDatabasesStream {
+    /// Create a new Notion databases stream with SourceAuth and StreamWriter
+    pub fn new(
+        source_id: String,
+        db: SqlitePool,
+        stream_writer: Arc<Mutex<StreamWriter>>,
+        auth: SourceAuth,
+    ) -> Self {
+        let token_manager = auth
+            .token_manager()
+            .expect("NotionDatabasesStream requires OAuth2 auth")
+            .clone();
+
+        let client =
+            NotionApiClient::new(source_id.clone(), token_manager).with_network(auth.network());
+
+        Self {
+            source_id,
+            client,
+            db,
+            stream_writer,
+            config: NotionDatabasesConfig::default(),
+        }
+    }
+
+    /// Load configuration from database
+    async fn load_config_internal(&mut self, db: &SqlitePool, source_id: &str) -> Result<()> {
+        let result = sqlx::query_as::<_, (Value,)>(
+            "SELECT config FROM elt_stream_connections WHERE source_connection_id = $1 AND stream_name = 'databases'",
+        )
+        .bind(source_id)
+        .fetch_optional(db)
+        .await?;
+
+        if let Some((config_json,)) = result {
+            if let Ok(config) = NotionDatabasesConfig::from_json(&config_json) {
+                self.config = config;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sync every database's rows
+    ///
+    /// Database queries can't be filtered by edit time across a whole
+    /// workspace, so each sync is a full refresh. A capped run saves where it
+    /// stopped as a resume cursor ("<database_id>\t<query cursor>", the
+    /// query cursor empty to start at the database's first row) and the next
+    /// run continues from there.
+    #[tracing::instrument(skip(self), fields(source_id = %self.source_id))]
+    pub async fn sync_with_mode(&self, mode: &SyncMode) -> Result<SyncResult> {
+        if !mode.is_full_refresh() {
+            tracing::warn!(
+                "Notion databases stream only supports full refresh. Using full refresh instead."
+            );
+        }
+
+        let started_at = Utc::now();
+
+        tracing::info!("Starting Notion databases sync");
+
+        let limits = StreamLimits::load(&self.db, &self.source_id, "databases").await?;
+        let page_size = limits.page_size_or(self.config.page_size).clamp(1, 100);
+
+        let databases = self.list_databases(page_size).await?;
+        tracing::info!(database_count = databases.len(), "Listed Notion databases");
+
+        // Start over if the database a capped run stopped in is gone
+        let resume = load_resume_cursor(&self.db, &self.source_id, "databases")
+            .await?
+            .and_then(|resume| {
+                let (database_id, cursor) = resume.split_once('\t')?;
+                let position = databases.iter().position(|d| d.id == database_id)?;
+                Some((position, cursor.to_string()))
+            });
+        let (first, mut cursor) = match resume {
+            Some((position, cursor)) => (position, Some(cursor).filter(|c| !c.is_empty())),
+            None => (0, None),
+        };
+
+        let mut records_fetched = 0;
+        let mut records_written = 0;
+        let mut records_failed = 0;
+        let mut resume_cursor = None;
+
+        'databases: for (position, database) in databases.iter().enumerate().skip(first) {
+            let title = plain_text(&database.title);
+
+            loop {
+                let response = match self
+                    .query_rows(&database.id, cursor.take(), page_size)
+                    .await
+                {
+                    Ok(response) => response,
+                    Err(e) => {
+                        records_failed += 1;
+                        tracing::warn!(
+                            database_id = %database.id,
+                            error = %e,
+                            "Failed to query Notion database, skipping it"
+                        );
+                        break;
+                    }
+                };
+
+                for row in &response.results {
+                    if row.archived && !self.config.include_archived {
+                        continue;
+                    }
+                    records_fetched += 1;
+
+                    match self.write_row(database, &title, row).await {
+                        Ok(()) => records_written += 1,
+                        Err(e) => {
+                            records_failed += 1;
+                            tracing::warn!(
+                                row_id = %row.id,
+                                error = %e,
+                                "Failed to write Notion database row"
+                            );
+                        }
+                    }
+                }
+
+                cursor = response.next_cursor.filter(|_| response.has_more);
+                progress::report_page(records_fetched, records_written, cursor.as_deref());
+                if limits.is_reached(records_fetched) {
+                    resume_cursor = match &cursor {
+                        Some(cursor) => Some(format!("{}\t{}", database.id, cursor)),
+                        None => databases
+                            .get(position + 1)
+                            .map(|next| format!("{}\t", next.id)),
+                    };
+                    break 'databases;
+                }
+                if cursor.is_none() {
+                    break;
+                }
+            }
+        }
+
+        save_resume_cursor(
+            &self.db,
+            &self.source_id,
+            "databases",
+            resume_cursor.as_deref(),
+        )
+        .await?;
+
+        let completed_at = Utc::now();
+
+        // Collect records from StreamWriter for archive and transform pipeline
+        let records = {
+            let mut writer = self.stream_writer.lock().await;
+            writer
+                .collect_records(&self.source_id, "databases")?
+                .map(|(records, _, _)| records)
+        };
+
+        Ok(SyncResult {
+            records_fetched,
+            records_written,
+            records_failed,
+            next_cursor: None,
+            earliest_record_at: None,
+            latest_record_at: None,
+            started_at,
+            completed_at,
+            records,
+            archive_job_id: None,
+            records_deduplicated: 0,
+        })
+    }
+
+    /// List the databases to sync, with pagination
+    ///
+    /// Only the configured `database_ids` are kept when there are any; they
+    /// match with or without dashes.
+    async fn list_databases(&self, page_size: u32) -> Result<Vec<Database>> {
+        let wanted: Vec<String> = self
+            .config
+            .database_ids
+            .iter()
+            .map(|id| normalize_id(id))
+            .collect();
+
+        let mut databases = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let mut body = json!({
+                "filter": {
+                    "property": "object",
+                    "value": "database"
+                },
+                "page_size": page_size,
+            });
+            if let Some(cursor) = cursor {
+                body["start_cursor"] = json!(cursor);
+            }
+
+            let response: DatabaseSearchResponse = self.client.post_json("search", &body).await?;
+            databases.extend(response.results.into_iter().filter(|database| {
+                (wanted.is_empty() || wanted.contains(&normalize_id(&database.id)))
+                    && (self.config.include_archived || !database.archived)
+            }));
+
+            cursor = response.next_cursor.filter(|_| response.has_more);
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        Ok(databases)
+    }
+
+    /// Query one page of a database's rows
+    async fn query_rows(
+        &self,
+        database_id: &str,
+        cursor: Option<String>,
+        page_size: u32,
+    ) -> Result<DatabaseQueryResponse> {
+        let mut body = json!({ "page_size": page_size });
+        if let Some(cursor) = cursor {
+            body["start_cursor"] = json!(cursor);
+        }
+
+        self.client
+            .post_json(&format!("databases/{database_id}/query"), &body)
+            .await
+    }
+
+    /// Write a database row to the stream
+    async fn write_row(&self, database: &Database, title: &str, row: &Page) -> Result<()> {
+        let record = json!({
+            "database_id": database.id,
+            "database_title": title,
+            "row_id": row.id,
+            "url": row.url,
+            "created_time": row.created_time,
+            "last_edited_time": row.last_edited_time,
+            "created_by_id": row.created_by.id,
+            "last_edited_by_id": row.last_edited_by.id,
+            "archived": row.archived,
+            "properties": properties_to_json(&row.properties),
+            "raw_properties": row.properties,
+            "synced_at": Utc::now(),
+        });
+
+        let mut writer = self.stream_writer.lock().await;
+        writer.write_record(
+            &self.source_id,
+            "databases",
+            record,
+            Some(row.last_edited_time),
+        )?;
+        Ok(())
+    }
+}
+
+/// Notion IDs compare equal with or without dashes
+fn normalize_id(id: &str) -> String {
+    id.replace('-', "").to_lowercase()
+}
+
+/// Concatenated plain text of a rich text array
+fn plain_text(rich_text: &[super::types::RichText]) -> String {
+    rich_text.iter().map(|rt| rt.plain_text.as_str()).collect()
+}
+
+/// Plain text of a rich text array in its JSON form
+fn plain_text_json(rich_text: &Value) -> String {
+    rich_text
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|rt| rt["plain_text"].as_str())
+        .collect()
+}
+
+/// A row's properties as plain values keyed by property name
+fn properties_to_json(properties: &Value) -> Value {
+    let Some(properties) = properties.as_object() else {
+        return Value::Object(Map::new());
+    };
+    properties
+        .iter()
+        .map(|(name, property)| (name.clone(), property_value(property)))
+        .collect::<Map<_, _>>()
+        .into()
+}
+
+/// The plain value of one property, by its `type`
+///
+/// Types without a simpler form keep Notion's value for that type.
+fn property_value(property: &Value) -> Value {
+    let Some(kind) = property["type"].as_str() else {
+        return Value::Null;
+    };
+    let value = &property[kind];
+    let user = |user: &Value| {
+        user["name"]
+            .as_str()
+            .or(user["id"].as_str())
+            .map(Into::into)
+    };
+
+    match kind {
+        "title" | "rich_text" => json!(plain_text_json(value)),
+        "select" | "status" => value["name"].as_str().map_or(Value::Null, Into::into),
+        "multi_select" => json!(value
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|option| option["name"].as_str())
+            .collect::<Vec<_>>()),
+        "date" if value.is_null() => Value::Null,
+        "date" => json!({ "start": value["start"], "end": value["end"] }),
+        "people" => Value::Array(
+            value
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(user)
+                .collect(),
+        ),
+        "created_by" | "last_edited_by" => user(value).unwrap_or(Value::Null),
+        "relation" => json!(value
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|relation| relation["id"].as_str())
+            .collect::<Vec<_>>()),
+        "files" => json!(value
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|file| json!({
+                "name": file["name"],
+                "url": file["file"]["url"].as_str().or(file["external"]["url"].as_str()),
+            }))
+            .collect::<Vec<_>>()),
+        "unique_id" => match (value["prefix"].as_str(), value["number"].as_i64()) {
+            (Some(prefix), Some(number)) => json!(format!("{prefix}-{number}")),
+            (None, Some(number)) => json!(number),
+            _ => Value::Null,
+        },
+        "formula" => value["type"].as_str().map_or(Value::Null, |t| match t {
+            "date" => property_value(value),
+            _ => value[t].clone(),
+        }),
+        "rollup" => match value["type"].as_str() {
+            Some("array") => Value::Array(
+                value["array"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(property_value)
+                    .collect(),
+            ),
+            Some("date") => property_value(value),
+            Some(t) => value[t].clone(),
+            None => Value::Null,
+        },
+        // number, checkbox, url, email, phone_number, created_time,
+        // last_edited_time, ...
+        _ => value.clone(),
+    }
+}
+
+#[async_trait]
+impl PullStream for NotionDatabasesStream {
+    async fn sync_pull(&self, mode: SyncMode) -> Result<SyncResult> {
+        self.sync_with_mode(&mode).await
+    }
+
+    async fn load_config(&mut self, db: &SqlitePool, source_id: &str) -> Result<()> {
+        self.load_config_internal(db, source_id).await
+    }
+
+    fn table_name(&self) -> &str {
+        "stream_notion_databases"
+    }
+
+    fn stream_name(&self) -> &str {
+        "databases"
+    }
+
+    fn source_name(&self) -> &str {
+        "notion"
+    }
+
+    fn supports_incremental(&self) -> bool {
+        false
+    }
+
+    fn supports_full_refresh(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::base::mock_transport::testing::migrated_pool;
+    use crate::sources::base::mock_transport::MockTransport;
+    use crate::sources::base::TokenManager;
+
+    fn database(id: &str, title: &str) -> Value {
+        json!({
+            "id": id,
+            "title": [{ "plain_text": title, "href": null }],
+            "last_edited_time": "2024-01-01T00:00:00Z",
+            "archived": false,
+            "url": format!("https://www.notion.so/{id}")
+        })
+    }
+
+    fn row(id: &str, name: &str) -> Value {
+        json!({
+            "id": id,
+            "created_time": "2024-01-01T00:00:00Z",
+            "last_edited_time": "2024-05-01T12:00:00Z",
+            "created_by": { "id": "user-1" },
+            "last_edited_by": { "id": "user-1" },
+            "parent": { "type": "database_id", "database_id": "db-1" },
+            "archived": false,
+            "properties": {
+                "Name": { "id": "title", "type": "title", "title": [{ "plain_text": name }] }
+            },
+            "url": format!("https://www.notion.so/{id}")
+        })
+    }
+
+    fn results(results: Vec<Value>, next_cursor: Option<&str>) -> Value {
+        json!({
+            "results": results,
+            "has_more": next_cursor.is_some(),
+            "next_cursor": next_cursor
+        })
+    }
+
+    async fn databases_stream(
+        config: Value,
+        transport: Arc<MockTransport>,
+    ) -> NotionDatabasesStream {
+        let db = migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, name) VALUES ('src-notion', 'notion', 'Notion')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO elt_stream_connections (id, source_connection_id, stream_name, table_name, config)
+             VALUES ('stream-databases', 'src-notion', 'databases', 'stream_notion_databases', $1)",
+        )
+        .bind(config.to_string())
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let token_manager = Arc::new(TokenManager::new_insecure(db.clone()));
+        let mut stream = NotionDatabasesStream::new(
+            "src-notion".to_string(),
+            db.clone(),
+            Arc::new(Mutex::new(StreamWriter::new())),
+            SourceAuth::oauth2("src-notion".to_string(), token_manager),
+        );
+        stream.load_config(&db, "src-notion").await.unwrap();
+        stream.client.http_mut().set_mock_transport(transport);
+        stream
+    }
+
+    #[tokio::test]
+    async fn test_rows_of_configured_databases_synced() {
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_response(
+                    "search",
+                    results(vec![database("db-1", "Tasks")], Some("s2")),
+                )
+                .with_response("search", results(vec![database("db-2", "Other")], None))
+                .with_response(
+                    "databases/db-1/query",
+                    results(vec![row("r1", "Write docs")], Some("q2")),
+                )
+                .with_response(
+                    "databases/db-1/query",
+                    results(vec![row("r2", "Ship it")], None),
+                ),
+        );
+        let stream = databases_stream(json!({ "database_ids": ["db1"] }), transport.clone()).await;
+
+        let result = stream.sync_with_mode(&SyncMode::FullRefresh).await.unwrap();
+        assert_eq!(result.records_fetched, 2);
+        assert_eq!(result.records_written, 2);
+        let records = result.records.unwrap();
+        assert_eq!(records[0]["database_title"], "Tasks");
+        assert_eq!(records[0]["properties"]["Name"], "Write docs");
+        assert_eq!(records[1]["row_id"], "r2");
+
+        // Both search pages listed; only the configured database queried
+        let requests = transport.requests();
+        let paths: Vec<_> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "search",
+                "search",
+                "databases/db-1/query",
+                "databases/db-1/query"
+            ]
+        );
+        assert_eq!(
+            requests[0].body.as_ref().unwrap()["filter"]["value"],
+            "database"
+        );
+        assert_eq!(requests[1].body.as_ref().unwrap()["start_cursor"], "s2");
+        assert_eq!(requests[3].body.as_ref().unwrap()["start_cursor"], "q2");
+    }
+
+    #[tokio::test]
+    async fn test_capped_run_resumes_at_next_database() {
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_response(
+                    "search",
+                    results(
+                        vec![database("db-1", "Tasks"), database("db-2", "Notes")],
+                        None,
+                    ),
+                )
+                .with_response(
+                    "databases/db-1/query",
+                    results(vec![row("r1", "One")], None),
+                )
+                .with_response(
+                    "databases/db-2/query",
+                    results(vec![row("r2", "Two")], None),
+                ),
+        );
+        let stream = databases_stream(json!({ "max_records_per_run": 1 }), transport.clone()).await;
+
+        let first = stream.sync_with_mode(&SyncMode::FullRefresh).await.unwrap();
+        assert_eq!(first.records_fetched, 1);
+        assert_eq!(
+            load_resume_cursor(&stream.db, "src-notion", "databases")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("db-2\t")
+        );
+
+        let second = stream.sync_with_mode(&SyncMode::FullRefresh).await.unwrap();
+        assert_eq!(second.records.unwrap()[0]["row_id"], "r2");
+        assert!(load_resume_cursor(&stream.db, "src-notion", "databases")
+            .await
+            .unwrap()
+            .is_none());
+        let query = transport
+            .requests()
+            .into_iter()
+            .rfind(|r| r.path == "databases/db-2/query")
+            .unwrap();
+        assert!(query.body.unwrap()["start_cursor"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_failed_database_query_counts_as_failed() {
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_response(
+                    "search",
+                    results(
+                        vec![database("db-1", "Tasks"), database("db-2", "Notes")],
+                        None,
+                    ),
+                )
+                .with_status("databases/db-1/query", 400, "{}")
+                .with_response(
+                    "databases/db-2/query",
+                    results(vec![row("r2", "Two")], None),
+                ),
+        );
+        let stream = databases_stream(json!({}), transport).await;
+
+        // The other database still syncs
+        let result = stream.sync_with_mode(&SyncMode::FullRefresh).await.unwrap();
+        assert_eq!(result.records_failed, 1);
+        assert_eq!(result.records_written, 1);
+    }
+
+    #[test]
+    fn test_property_values() {
+        let properties = json!({
+            "Name": { "type": "title", "title": [{ "plain_text": "Launch " }, { "plain_text": "plan" }] },
+            "Points": { "type": "number", "number": 3 },
+            "Stage": { "type": "status", "status": { "name": "In progress" } },
+            "Priority": { "type": "select", "select": null },
+            "Tags": { "type": "multi_select", "multi_select": [{ "name": "a" }, { "name": "b" }] },
+            "Due": { "type": "date", "date": { "start": "2024-06-01", "end": null, "time_zone": null } },
+            "Done": { "type": "checkbox", "checkbox": true },
+            "Owner": { "type": "people", "people": [{ "id": "u1", "name": "Ada" }, { "id": "u2" }] },
+            "Related": { "type": "relation", "relation": [{ "id": "p1" }] },
+            "Score": { "type": "formula", "formula": { "type": "number", "number": 7 } },
+            "Total": { "type": "rollup", "rollup": { "type": "array", "array": [
+                { "type": "number", "number": 1 }, { "type": "number", "number": 2 }
+            ] } },
+            "Ticket": { "type": "unique_id", "unique_id": { "prefix": "TASK", "number": 12 } },
+            "Spec": { "type": "files", "files": [
+                { "name": "spec.pdf", "type": "external", "external": { "url": "https://example.com/spec.pdf" } }
+            ] }
+        });
+
+        assert_eq!(
+            properties_to_json(&properties),
+            json!({
+                "Name": "Launch plan",
+                "Points": 3,
+                "Stage": "In progress",
+                "Priority": null,
+                "Tags": ["a", "b"],
+                "Due": { "start": "2024-06-01", "end": null },
+                "Done": true,
+                "Owner": ["Ada", "u2"],
+                "Related": ["p1"],
+                "Score": 7,
+                "Total": [1, 2],
+                "Ticket": "TASK-12",
+                "Spec": [{ "name": "spec.pdf", "url": "https://example.com/spec.pdf" }]
+            })
+        );
+    }
+}
@@ -2,10 +2,12 @@
 
 pub mod client;
 pub mod config;
+pub mod databases;
 pub mod error_handler;
 pub mod pages;
 pub mod registry;
 pub mod types;
 
-pub use config::NotionPagesConfig;
+pub use config::{NotionDatabasesConfig, NotionPagesConfig};
+pub use databases::NotionDatabasesStream;
 pub use pages::NotionPagesStream;
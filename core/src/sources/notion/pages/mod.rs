@@ -3,7 +3,8 @@
 pub mod transform;
 
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
 use serde_json::json;
 use sqlx::SqlitePool;
 use std::sync::Arc;
@@ -12,9 +13,13 @@ use tokio::sync::Mutex;
 
 use crate::{
     error::Result,
+    jobs::progress,
     sources::{
         auth::SourceAuth,
-        base::{SyncMode, SyncResult},
+        base::{
+            load_resume_cursor, save_resume_cursor, SourceClient, StreamLimits, SyncMode,
+            SyncResult,
+        },
         pull_stream::PullStream,
     },
     storage::stream_writer::StreamWriter,
@@ -22,13 +27,17 @@ use crate::{
 
 use super::{
     client::NotionApiClient,
-    types::{Block, BlockChildrenResponse, Page, Parent, SearchResponse},
+    types::{Block, BlockChildrenResponse, Page, Parent, SearchResponse, TableRowBlock},
 };
 
+/// Deepest block nesting fetched below a page
+const MAX_BLOCK_DEPTH: usize = 10;
+
 /// Notion pages stream
 pub struct NotionPagesStream {
         source_id: String,
     client: NotionApiClient,
+    db: SqlitePool,
     stream_writer: Arc<Mutex<StreamWriter>>,
 }
 
@@ -36,7 +45,7 @@ impl NotionPagesStream {
     /// Create a new Notion pages stream with SourceAuth and StreamWriter
     pub fn new(
     source_id: String,
-        _db: SqlitePool,
+        db: SqlitePool,
         stream_writer: Arc<Mutex<StreamWriter>>,
         auth: SourceAuth,
     ) -> Self {
@@ -46,51 +55,90 @@ impl NotionPagesStream {
             .expect("NotionPagesStream requires OAuth2 auth")
             .clone();
 
-        let client = NotionApiClient::new(source_id.clone(), token_manager);
+        let client =
+            NotionApiClient::new(source_id.clone(), token_manager).with_network(auth.network());
 
         Self {
             source_id,
             client,
+            db,
             stream_writer,
         }
     }
 
     /// Sync with explicit mode
+    ///
+    /// An incremental sync lists pages by `last_edited_time`, newest first,
+    /// and stops at the first page edited before the cursor (the newest
+    /// `last_edited_time` seen by the last sync). Notion rounds edit times to
+    /// the minute, so pages edited in the cursor's minute are fetched again.
+    /// Without a cursor it falls back to a full refresh, which lists every
+    /// page and sets the cursor when it completes.
     #[tracing::instrument(skip(self), fields(source_id = %self.source_id))]
     pub async fn sync_with_mode(&self, mode: &SyncMode) -> Result<SyncResult> {
-        // Notion pages only support full refresh, not incremental sync
-        // If incremental mode is requested, log a warning and use full refresh instead
-        let _effective_mode = match mode {
-            SyncMode::Incremental { .. } => {
-                tracing::warn!(
-                    "Notion pages stream does not support incremental sync. Using full refresh instead."
-                );
-                SyncMode::FullRefresh
-            }
-            SyncMode::FullRefresh => SyncMode::FullRefresh,
-            SyncMode::Backfill { .. } => {
-                tracing::warn!(
-                    "Notion pages stream does not support backfill. Using full refresh instead."
-                );
-                SyncMode::FullRefresh
-            }
-        };
+        if let SyncMode::Backfill { .. } = mode {
+            tracing::warn!(
+                "Notion pages stream does not support backfill. Using full refresh instead."
+            );
+        }
 
         let started_at = Utc::now();
 
         tracing::info!("Starting Notion pages sync");
 
+        let limits = StreamLimits::load(&self.db, &self.source_id, "pages").await?;
+        let page_size = limits.page_size_or(100).min(100);
+        let last_sync_token = self.get_last_sync_token().await?;
+
+        // A resume cursor is the Notion search cursor a capped run stopped at.
+        // A capped incremental run prefixes it with the newest edit time it
+        // saw ("<last_edited_time>\t<cursor>"), to save once it completes;
+        // only an incremental sync continues that listing.
+        let resume = load_resume_cursor(&self.db, &self.source_id, "pages").await?;
+        let (since, mut newest_edit, mut cursor) = match (resume, mode) {
+            (Some(resume), SyncMode::Incremental { .. }) if resume.contains('\t') => {
+                let (newest, cursor) = resume.split_once('\t').unwrap_or_default();
+                (
+                    last_sync_token.as_deref().and_then(parse_edit_time),
+                    parse_edit_time(newest),
+                    Some(cursor.to_string()),
+                )
+            }
+            (Some(resume), _) if !resume.contains('\t') => (None, None, Some(resume)),
+            (_, SyncMode::Incremental { cursor }) => {
+                let since = cursor
+                    .as_deref()
+                    .or(last_sync_token.as_deref())
+                    .and_then(parse_edit_time);
+                if since.is_none() {
+                    tracing::warn!("No Notion pages cursor yet. Using full refresh instead.");
+                }
+                (since, None, None)
+            }
+            _ => (None, None, None),
+        };
+        let incremental = since.is_some() || newest_edit.is_some();
+
         let mut all_pages = Vec::new();
-        let mut cursor = None;
         let mut records_fetched = 0;
 
-        // Paginate through all pages
+        // Paginate through all pages, or in an incremental sync until the
+        // pages get older than the cursor
         loop {
-            let response = self.search_pages(cursor).await?;
-            records_fetched += response.results.len();
+            let response = self
+                .search_pages(cursor.clone(), page_size, incremental)
+                .await?;
+            let mut reached_cursor = false;
 
             // Write pages to stream_notion_pages table
             for page in &response.results {
+                if since.is_some_and(|since| page.last_edited_time < since) {
+                    reached_cursor = true;
+                    break;
+                }
+                records_fetched += 1;
+                newest_edit = newest_edit.max(Some(page.last_edited_time));
+
                 match self.upsert_page(page).await {
                     Ok(_) => {
                         all_pages.push(page.clone());
@@ -106,13 +154,31 @@ impl NotionPagesStream {
                 }
             }
 
-            if !response.has_more {
+            cursor = response
+                .next_cursor
+                .filter(|_| response.has_more && !reached_cursor);
+            progress::report_page(records_fetched, all_pages.len(), cursor.as_deref());
+            if cursor.is_none() || limits.is_reached(records_fetched) {
                 break;
             }
-
-            cursor = response.next_cursor;
         }
 
+        // The job saves `next_cursor` as the stream's sync token. A capped
+        // run hands the old one back; only a completed listing moves it.
+        let resume_cursor = match cursor {
+            Some(cursor) if incremental => Some(format!(
+                "{}\t{}",
+                newest_edit.map(|t| t.to_rfc3339()).unwrap_or_default(),
+                cursor
+            )),
+            cursor => cursor,
+        };
+        save_resume_cursor(&self.db, &self.source_id, "pages", resume_cursor.as_deref()).await?;
+        let next_cursor = match resume_cursor {
+            Some(_) => last_sync_token,
+            None => newest_edit.map(|t| t.to_rfc3339()).or(last_sync_token),
+        };
+
         let records_written = all_pages.len();
         let completed_at = Utc::now();
 
@@ -120,7 +186,7 @@ impl NotionPagesStream {
         let records = {
             let mut writer = self.stream_writer.lock().await;
             let collected =             writer
-                .collect_records(&self.source_id, "pages")
+                .collect_records(&self.source_id, "pages")?
                 .map(|(records, _, _)| records);
 
             if let Some(ref recs) = collected {
@@ -139,29 +205,42 @@ impl NotionPagesStream {
             records_fetched,
             records_written,
             records_failed: 0,
-            next_cursor: None,
+            next_cursor,
             earliest_record_at: None,
             latest_record_at: None,
             started_at,
             completed_at,
             records, // Return collected records for archive/transform
             archive_job_id: None,
+            records_deduplicated: 0,
         };
 
         // Logging is handled by job executor
         Ok(result)
     }
 
-    /// Search for pages
-    async fn search_pages(&self, cursor: Option<String>) -> Result<SearchResponse> {
+    /// Search for pages, most recently edited first if `newest_first`
+    async fn search_pages(
+        &self,
+        cursor: Option<String>,
+        page_size: u32,
+        newest_first: bool,
+    ) -> Result<SearchResponse> {
         let mut body = json!({
             "filter": {
                 "property": "object",
                 "value": "page"
             },
-            "page_size": 100,
+            "page_size": page_size,
         });
 
+        if newest_first {
+            body["sort"] = json!({
+                "direction": "descending",
+                "timestamp": "last_edited_time"
+            });
+        }
+
         if let Some(cursor) = cursor {
             body["start_cursor"] = json!(cursor);
         }
@@ -169,8 +248,55 @@ impl NotionPagesStream {
         self.client.post_json("search", &body).await
     }
 
-    /// Fetch all blocks for a page (with pagination)
+    /// Fetch all blocks for a page, with their nested children
     async fn fetch_page_blocks(&self, page_id: &str) -> Result<Vec<Block>> {
+        self.fetch_block_tree(page_id, 0).await
+    }
+
+    /// Fetch a block's children, and theirs, down to `MAX_BLOCK_DEPTH`
+    ///
+    /// Child pages and databases are pages of their own and aren't entered.
+    /// A nested level that fails to load is left out rather than failing
+    /// the page.
+    fn fetch_block_tree<'a>(
+        &'a self,
+        block_id: &'a str,
+        depth: usize,
+    ) -> BoxFuture<'a, Result<Vec<Block>>> {
+        Box::pin(async move {
+            let mut blocks = self.fetch_block_children(block_id).await?;
+
+            for block in &mut blocks {
+                if !block.has_children
+                    || matches!(block.block_type.as_str(), "child_page" | "child_database")
+                {
+                    continue;
+                }
+                if depth + 1 >= MAX_BLOCK_DEPTH {
+                    tracing::debug!(
+                        block_id = %block.id,
+                        "Notion blocks nested too deep, skipping children"
+                    );
+                    continue;
+                }
+                match self.fetch_block_tree(&block.id, depth + 1).await {
+                    Ok(children) => block.children = children,
+                    Err(e) => {
+                        tracing::warn!(
+                            block_id = %block.id,
+                            error = %e,
+                            "Failed to fetch nested blocks, storing without them"
+                        );
+                    }
+                }
+            }
+
+            Ok(blocks)
+        })
+    }
+
+    /// Fetch one level of a block's children (with pagination)
+    async fn fetch_block_children(&self, block_id: &str) -> Result<Vec<Block>> {
         let mut all_blocks = Vec::new();
         let mut cursor: Option<String> = None;
 
@@ -178,21 +304,20 @@ impl NotionPagesStream {
             let path = if let Some(ref cursor_val) = cursor {
                 format!(
                     "blocks/{}/children?page_size=100&start_cursor={}",
-                    page_id, cursor_val
+                    block_id, cursor_val
                 )
             } else {
-                format!("blocks/{}/children?page_size=100", page_id)
+                format!("blocks/{}/children?page_size=100", block_id)
             };
 
             let response: BlockChildrenResponse = self.client.get(&path).await?;
 
             all_blocks.extend(response.results);
 
-            if !response.has_more {
+            cursor = response.next_cursor.filter(|_| response.has_more);
+            if cursor.is_none() {
                 break;
             }
-
-            cursor = response.next_cursor;
         }
 
         Ok(all_blocks)
@@ -201,15 +326,32 @@ impl NotionPagesStream {
     /// Convert blocks to markdown text
     fn blocks_to_markdown(&self, blocks: &[Block]) -> String {
         let mut markdown = String::new();
+        self.write_blocks_markdown(blocks, 0, &mut markdown);
+        markdown.trim().to_string()
+    }
+
+    /// Append blocks as markdown, indented two spaces per nesting `depth`
+    ///
+    /// Numbered list items count from 1 within each run of items at a level.
+    fn write_blocks_markdown(&self, blocks: &[Block], depth: usize, markdown: &mut String) {
+        let indent = "  ".repeat(depth);
+        let mut list_number = 0;
 
         for block in blocks {
+            if block.block_type != "numbered_list_item" {
+                list_number = 0;
+            }
+
+            let mut out = String::new();
+            let mut children_written = false;
+            let mut closing = None;
             match block.block_type.as_str() {
                 "paragraph" => {
                     if let Some(content) = &block.paragraph {
                         let text = self.rich_text_to_string(&content.rich_text);
                         if !text.is_empty() {
-                            markdown.push_str(&text);
-                            markdown.push_str("\n\n");
+                            out.push_str(&text);
+                            out.push_str("\n\n");
                         }
                     }
                 }
@@ -217,9 +359,9 @@ impl NotionPagesStream {
                     if let Some(content) = &block.heading_1 {
                         let text = self.rich_text_to_string(&content.rich_text);
                         if !text.is_empty() {
-                            markdown.push_str("# ");
-                            markdown.push_str(&text);
-                            markdown.push_str("\n\n");
+                            out.push_str("# ");
+                            out.push_str(&text);
+                            out.push_str("\n\n");
                         }
                     }
                 }
@@ -227,9 +369,9 @@ impl NotionPagesStream {
                     if let Some(content) = &block.heading_2 {
                         let text = self.rich_text_to_string(&content.rich_text);
                         if !text.is_empty() {
-                            markdown.push_str("## ");
-                            markdown.push_str(&text);
-                            markdown.push_str("\n\n");
+                            out.push_str("## ");
+                            out.push_str(&text);
+                            out.push_str("\n\n");
                         }
                     }
                 }
@@ -237,9 +379,9 @@ impl NotionPagesStream {
                     if let Some(content) = &block.heading_3 {
                         let text = self.rich_text_to_string(&content.rich_text);
                         if !text.is_empty() {
-                            markdown.push_str("### ");
-                            markdown.push_str(&text);
-                            markdown.push_str("\n\n");
+                            out.push_str("### ");
+                            out.push_str(&text);
+                            out.push_str("\n\n");
                         }
                     }
                 }
@@ -247,9 +389,9 @@ impl NotionPagesStream {
                     if let Some(content) = &block.bulleted_list_item {
                         let text = self.rich_text_to_string(&content.rich_text);
                         if !text.is_empty() {
-                            markdown.push_str("- ");
-                            markdown.push_str(&text);
-                            markdown.push('\n');
+                            out.push_str("- ");
+                            out.push_str(&text);
+                            out.push('\n');
                         }
                     }
                 }
@@ -257,9 +399,10 @@ impl NotionPagesStream {
                     if let Some(content) = &block.numbered_list_item {
                         let text = self.rich_text_to_string(&content.rich_text);
                         if !text.is_empty() {
-                            markdown.push_str("1. ");
-                            markdown.push_str(&text);
-                            markdown.push('\n');
+                            list_number += 1;
+                            out.push_str(&format!("{list_number}. "));
+                            out.push_str(&text);
+                            out.push('\n');
                         }
                     }
                 }
@@ -272,11 +415,11 @@ impl NotionPagesStream {
                             "[ ]"
                         };
                         if !text.is_empty() {
-                            markdown.push_str("- ");
-                            markdown.push_str(checkbox);
-                            markdown.push(' ');
-                            markdown.push_str(&text);
-                            markdown.push('\n');
+                            out.push_str("- ");
+                            out.push_str(checkbox);
+                            out.push(' ');
+                            out.push_str(&text);
+                            out.push('\n');
                         }
                     }
                 }
@@ -284,11 +427,11 @@ impl NotionPagesStream {
                     if let Some(content) = &block.code {
                         let text = self.rich_text_to_string(&content.rich_text);
                         if !text.is_empty() {
-                            markdown.push_str("```");
-                            markdown.push_str(&content.language);
-                            markdown.push('\n');
-                            markdown.push_str(&text);
-                            markdown.push_str("\n```\n\n");
+                            out.push_str("```");
+                            out.push_str(&content.language);
+                            out.push('\n');
+                            out.push_str(&text);
+                            out.push_str("\n```\n\n");
                         }
                     }
                 }
@@ -296,9 +439,9 @@ impl NotionPagesStream {
                     if let Some(content) = &block.quote {
                         let text = self.rich_text_to_string(&content.rich_text);
                         if !text.is_empty() {
-                            markdown.push_str("> ");
-                            markdown.push_str(&text);
-                            markdown.push_str("\n\n");
+                            out.push_str("> ");
+                            out.push_str(&text);
+                            out.push_str("\n\n");
                         }
                     }
                 }
@@ -306,27 +449,106 @@ impl NotionPagesStream {
                     if let Some(content) = &block.callout {
                         let text = self.rich_text_to_string(&content.rich_text);
                         if !text.is_empty() {
-                            markdown.push_str("> 💡 ");
-                            markdown.push_str(&text);
-                            markdown.push_str("\n\n");
+                            out.push_str("> 💡 ");
+                            out.push_str(&text);
+                            out.push_str("\n\n");
                         }
                     }
                 }
                 "child_page" => {
                     if let Some(content) = &block.child_page {
-                        markdown.push_str("📄 ");
-                        markdown.push_str(&content.title);
-                        markdown.push_str("\n\n");
+                        out.push_str("📄 ");
+                        out.push_str(&content.title);
+                        out.push_str("\n\n");
+                    }
+                }
+                "toggle" => {
+                    if let Some(content) = &block.toggle {
+                        let text = self.rich_text_to_string(&content.rich_text);
+                        out.push_str("<details>\n<summary>");
+                        out.push_str(&text);
+                        out.push_str("</summary>\n\n");
+                        closing = Some("</details>\n\n");
+                    }
+                }
+                "table" => {
+                    // Rows are the table's children; GFM tables need a header
+                    // row, so a table without one gets an empty header
+                    if let Some(table) = &block.table {
+                        let rows: Vec<&TableRowBlock> = block
+                            .children
+                            .iter()
+                            .filter_map(|row| row.table_row.as_ref())
+                            .collect();
+                        let width = rows
+                            .iter()
+                            .map(|row| row.cells.len())
+                            .fold(table.table_width, usize::max);
+                        let body = match rows.split_first() {
+                            Some((header, body)) if table.has_column_header => {
+                                out.push_str(&self.table_row_to_markdown(header, width));
+                                body
+                            }
+                            _ => {
+                                out.push_str(&format!("|{}\n", " |".repeat(width)));
+                                rows.as_slice()
+                            }
+                        };
+                        out.push_str(&format!("|{}\n", " --- |".repeat(width)));
+                        for row in body {
+                            out.push_str(&self.table_row_to_markdown(row, width));
+                        }
+                        out.push('\n');
+                        children_written = true;
                     }
                 }
+                "table_row" => {
+                    if let Some(row) = &block.table_row {
+                        out.push_str(&self.table_row_to_markdown(row, row.cells.len()));
+                    }
+                }
+                "divider" => {
+                    out.push_str("---\n\n");
+                }
                 _ => {
                     // Unsupported block types - just note them
                     tracing::debug!("Unsupported block type: {}", block.block_type);
                 }
             }
+
+            for line in out.split_inclusive('\n') {
+                if line != "\n" {
+                    markdown.push_str(&indent);
+                }
+                markdown.push_str(line);
+            }
+            if !children_written {
+                self.write_blocks_markdown(&block.children, depth + 1, markdown);
+            }
+            if let Some(closing) = closing {
+                markdown.push_str(&indent);
+                markdown.push_str(closing);
+            }
         }
+    }
 
-        markdown.trim().to_string()
+    /// One GFM table row, padded with empty cells to `width`
+    fn table_row_to_markdown(&self, row: &TableRowBlock, width: usize) -> String {
+        let mut line = String::from("|");
+        for i in 0..width.max(row.cells.len()) {
+            let text = row
+                .cells
+                .get(i)
+                .map(|cell| self.rich_text_to_string(cell))
+                .unwrap_or_default()
+                .replace('|', "\\|")
+                .replace('\n', "<br>");
+            line.push(' ');
+            line.push_str(&text);
+            line.push_str(" |");
+        }
+        line.push('\n');
+        line
     }
 
     /// Convert rich text array to plain string with basic formatting
@@ -361,6 +583,19 @@ impl NotionPagesStream {
             .join("")
     }
 
+    /// The stream's sync token: the newest `last_edited_time` synced
+    async fn get_last_sync_token(&self) -> Result<Option<String>> {
+        let token = sqlx::query_scalar::<_, Option<String>>(
+            "SELECT last_sync_token FROM elt_stream_connections WHERE source_connection_id = $1 AND stream_name = 'pages'",
+        )
+        .bind(&self.source_id)
+        .fetch_optional(&self.db)
+        .await?
+        .flatten();
+
+        Ok(token)
+    }
+
     /// Upsert a page into the database
     async fn upsert_page(&self, page: &Page) -> Result<()> {
         // Extract parent information
@@ -428,6 +663,13 @@ impl NotionPagesStream {
     }
 }
 
+/// Parse a sync token or resume cursor edit time
+fn parse_edit_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
 // Implement PullStream trait for NotionPagesStream
 #[async_trait]
 impl PullStream for NotionPagesStream {
@@ -453,10 +695,258 @@ impl PullStream for NotionPagesStream {
     }
 
     fn supports_incremental(&self) -> bool {
-        false // Notion API doesn't provide incremental sync
+        true // Search sorted by last_edited_time, stopping at the cursor
     }
 
     fn supports_full_refresh(&self) -> bool {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::base::mock_transport::testing::migrated_pool;
+    use crate::sources::base::mock_transport::MockTransport;
+    use crate::sources::base::TokenManager;
+
+    fn page(id: &str, last_edited_time: &str) -> serde_json::Value {
+        json!({
+            "id": id,
+            "created_time": "2024-01-01T00:00:00Z",
+            "last_edited_time": last_edited_time,
+            "created_by": { "id": "user-1" },
+            "last_edited_by": { "id": "user-1" },
+            "parent": { "type": "workspace", "workspace": true },
+            "archived": false,
+            "properties": {},
+            "url": format!("https://www.notion.so/{id}")
+        })
+    }
+
+    async fn pages_stream(token: Option<&str>, transport: Arc<MockTransport>) -> NotionPagesStream {
+        let db = migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, name) VALUES ('src-notion', 'notion', 'Notion')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO elt_stream_connections (id, source_connection_id, stream_name, table_name, last_sync_token)
+             VALUES ('stream-pages', 'src-notion', 'pages', 'stream_notion_pages', $1)",
+        )
+        .bind(token)
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let token_manager = Arc::new(TokenManager::new_insecure(db.clone()));
+        let mut stream = NotionPagesStream::new(
+            "src-notion".to_string(),
+            db,
+            Arc::new(Mutex::new(StreamWriter::new())),
+            SourceAuth::oauth2("src-notion".to_string(), token_manager),
+        );
+        stream.client.http_mut().set_mock_transport(transport);
+        stream
+    }
+
+    #[tokio::test]
+    async fn test_incremental_sync_stops_at_cursor() {
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_response(
+                    "search",
+                    json!({
+                        "results": [page("p1", "2024-05-01T12:00:00Z"), page("p2", "2024-05-01T11:00:00Z")],
+                        "has_more": true,
+                        "next_cursor": "c2"
+                    }),
+                )
+                .with_response(
+                    "search",
+                    json!({
+                        "results": [page("p3", "2024-05-01T10:00:00Z"), page("p4", "2024-05-01T09:00:00Z")],
+                        "has_more": true,
+                        "next_cursor": "c3"
+                    }),
+                ),
+        );
+        let stream = pages_stream(Some("2024-05-01T10:00:00+00:00"), transport.clone()).await;
+
+        let result = stream
+            .sync_with_mode(&SyncMode::Incremental { cursor: None })
+            .await
+            .unwrap();
+        assert_eq!(result.records_fetched, 3);
+        assert_eq!(
+            result.next_cursor.as_deref(),
+            Some("2024-05-01T12:00:00+00:00")
+        );
+
+        // Newest first, and no page is listed past the cursor's
+        let searches: Vec<_> = transport
+            .requests()
+            .into_iter()
+            .filter(|r| r.path == "search")
+            .collect();
+        assert_eq!(searches.len(), 2);
+        let body = searches[0].body.as_ref().unwrap();
+        assert_eq!(body["sort"]["timestamp"], "last_edited_time");
+        assert_eq!(body["sort"]["direction"], "descending");
+        assert_eq!(searches[1].body.as_ref().unwrap()["start_cursor"], "c2");
+    }
+
+    #[tokio::test]
+    async fn test_incremental_without_cursor_lists_everything() {
+        let transport = Arc::new(MockTransport::new().with_response(
+            "search",
+            json!({
+                "results": [page("p1", "2024-05-01T09:00:00Z"), page("p2", "2024-05-01T12:00:00Z")],
+                "has_more": false,
+                "next_cursor": null
+            }),
+        ));
+        let stream = pages_stream(None, transport.clone()).await;
+
+        let result = stream
+            .sync_with_mode(&SyncMode::Incremental { cursor: None })
+            .await
+            .unwrap();
+        assert_eq!(result.records_fetched, 2);
+        assert_eq!(
+            result.next_cursor.as_deref(),
+            Some("2024-05-01T12:00:00+00:00")
+        );
+        assert!(transport.requests()[0].body.as_ref().unwrap()["sort"].is_null());
+    }
+
+    fn block(id: &str, block_type: &str, text: &str, has_children: bool) -> serde_json::Value {
+        let mut block = json!({
+            "id": id,
+            "created_time": "2024-01-01T00:00:00Z",
+            "last_edited_time": "2024-01-01T00:00:00Z",
+            "has_children": has_children,
+            "archived": false,
+            "type": block_type,
+        });
+        block[block_type] = json!({ "rich_text": [{ "plain_text": text, "href": null }] });
+        block
+    }
+
+    fn children(blocks: Vec<serde_json::Value>) -> serde_json::Value {
+        json!({ "results": blocks, "has_more": false, "next_cursor": null })
+    }
+
+    #[tokio::test]
+    async fn test_nested_lists_rendered_with_indentation() {
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_response(
+                    "blocks/page-1/children",
+                    children(vec![
+                        block("b1", "numbered_list_item", "First", true),
+                        block("b2", "numbered_list_item", "Second", false),
+                        block("b3", "paragraph", "After", false),
+                        block("b4", "numbered_list_item", "Again", false),
+                    ]),
+                )
+                .with_response(
+                    "blocks/b1/children",
+                    children(vec![
+                        block("b1-1", "bulleted_list_item", "Point", true),
+                        block("b1-2", "numbered_list_item", "Sub one", false),
+                        block("b1-3", "numbered_list_item", "Sub two", false),
+                    ]),
+                )
+                .with_response(
+                    "blocks/b1-1/children",
+                    children(vec![block("b1-1-1", "to_do", "Task", false)]),
+                ),
+        );
+        let stream = pages_stream(None, transport).await;
+
+        let blocks = stream.fetch_page_blocks("page-1").await.unwrap();
+        assert_eq!(
+            stream.blocks_to_markdown(&blocks),
+            "1. First\n  - Point\n    - [ ] Task\n  1. Sub one\n  2. Sub two\n2. Second\nAfter\n\n1. Again"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_block_nesting_depth_is_capped() {
+        let mut transport = MockTransport::new();
+        for depth in 0..20 {
+            let parent = if depth == 0 {
+                "page-1".to_string()
+            } else {
+                format!("b{}", depth - 1)
+            };
+            transport = transport.with_response(
+                &format!("blocks/{parent}/children"),
+                children(vec![block(
+                    &format!("b{depth}"),
+                    "bulleted_list_item",
+                    "Deeper",
+                    true,
+                )]),
+            );
+        }
+        let transport = Arc::new(transport);
+        let stream = pages_stream(None, transport.clone()).await;
+
+        let blocks = stream.fetch_page_blocks("page-1").await.unwrap();
+        let mut depth = 0;
+        let mut level = &blocks;
+        while let Some(block) = level.first() {
+            depth += 1;
+            level = &block.children;
+        }
+        assert_eq!(depth, MAX_BLOCK_DEPTH);
+        assert_eq!(transport.requests().len(), MAX_BLOCK_DEPTH);
+    }
+
+    #[tokio::test]
+    async fn test_tables_toggles_and_dividers_rendered() {
+        let row = |id: &str, cells: &[&str]| {
+            let mut row = block(id, "table_row", "", false);
+            row["table_row"] = json!({
+                "cells": cells
+                    .iter()
+                    .map(|text| json!([{ "plain_text": text, "href": null }]))
+                    .collect::<Vec<_>>()
+            });
+            row
+        };
+        let table = |id: &str, has_column_header: bool, rows: Vec<serde_json::Value>| {
+            let mut table = block(id, "table", "", true);
+            table["table"] = json!({ "table_width": 2, "has_column_header": has_column_header });
+            table["children"] = json!(rows);
+            table
+        };
+        let mut toggle = block("t1", "toggle", "More", true);
+        toggle["children"] = json!([block("t1-1", "paragraph", "Hidden", false)]);
+
+        let blocks: Vec<Block> = serde_json::from_value(json!([
+            table(
+                "tbl1",
+                true,
+                vec![row("r1", &["Name", "Role"]), row("r2", &["Ada", "a|b"])]
+            ),
+            block("d1", "divider", "", false),
+            table("tbl2", false, vec![row("r3", &["x"])]),
+            toggle,
+        ]))
+        .unwrap();
+        let stream = pages_stream(None, Arc::new(MockTransport::new())).await;
+
+        assert_eq!(
+            stream.blocks_to_markdown(&blocks),
+            "| Name | Role |\n| --- | --- |\n| Ada | a\\|b |\n\n\
+             ---\n\n\
+             | | |\n| --- | --- |\n| x |  |\n\n\
+             <details>\n<summary>More</summary>\n\n  Hidden\n\n</details>"
+        );
+    }
+}
@@ -4,8 +4,13 @@
 //! both UI metadata and transform logic in a single place.
 
 use crate::registry::{RegisteredSource, RegisteredStream, SourceRegistry};
+use crate::sources::stream_type::StreamType;
 use serde_json::json;
 
+use super::config::{NotionDatabasesConfig, NotionPagesConfig};
+use super::databases::NotionDatabasesStream;
+use super::pages::NotionPagesStream;
+
 // Import transform for unified registration
 use super::pages::transform::NotionPageTransform;
 
@@ -24,8 +29,34 @@ impl SourceRegistry for NotionSource {
                 RegisteredStream::new("pages")
                     .config_schema(pages_config_schema())
                     .config_example(pages_config_example())
+                    .config_type::<NotionPagesConfig>()
+                    .replace_on_full_refresh()
+                    .stream_creator(|ctx| {
+                        Ok(StreamType::Pull(Box::new(NotionPagesStream::new(
+                            ctx.source_id.clone(),
+                            ctx.db.clone(),
+                            ctx.stream_writer.clone(),
+                            ctx.auth.clone(),
+                        ))))
+                    })
                     .transform("content_document", |_ctx| Ok(Box::new(NotionPageTransform)))
                     .build(),
+                // Databases stream: rows with their typed properties
+                RegisteredStream::new("databases")
+                    .config_schema(databases_config_schema())
+                    .config_example(databases_config_example())
+                    .config_type::<NotionDatabasesConfig>()
+                    .dedup_key("row_id")
+                    .replace_on_full_refresh()
+                    .stream_creator(|ctx| {
+                        Ok(StreamType::Pull(Box::new(NotionDatabasesStream::new(
+                            ctx.source_id.clone(),
+                            ctx.db.clone(),
+                            ctx.stream_writer.clone(),
+                            ctx.auth.clone(),
+                        ))))
+                    })
+                    .build(),
             ],
         }
     }
@@ -92,6 +123,41 @@ fn pages_config_example() -> serde_json::Value {
     })
 }
 
+/// JSON schema for Notion databases configuration
+fn databases_config_schema() -> serde_json::Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "database_ids": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "List of database IDs to sync (leave empty to sync every database shared with the integration)"
+            },
+            "include_archived": {
+                "type": "boolean",
+                "default": false,
+                "description": "Include archived databases and rows"
+            },
+            "page_size": {
+                "type": "integer",
+                "default": 100,
+                "minimum": 1,
+                "maximum": 100,
+                "description": "Number of rows per API request batch"
+            }
+        }
+    })
+}
+
+/// Example configuration for Notion databases
+fn databases_config_example() -> serde_json::Value {
+    json!({
+        "database_ids": [],
+        "include_archived": false,
+        "page_size": 100
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,7 +169,7 @@ mod tests {
         assert_eq!(desc.descriptor.name, "notion");
         assert_eq!(desc.descriptor.auth_type, AuthType::OAuth2);
         assert!(desc.descriptor.oauth_config.is_some());
-        assert_eq!(desc.streams.len(), 1);
+        assert_eq!(desc.streams.len(), 2);
     }
 
     #[test]
@@ -114,7 +180,22 @@ mod tests {
 
         let p = pages.unwrap();
         assert_eq!(p.descriptor.table_name, "stream_notion_pages");
-        assert!(!p.descriptor.supports_incremental); // Notion doesn't support incremental sync
+        assert!(p.descriptor.supports_incremental);
         assert!(p.descriptor.supports_full_refresh);
+        assert!(p.stream_creator.is_some());
+        assert!(p.replace_on_full_refresh);
+    }
+
+    #[test]
+    fn test_databases_stream() {
+        let desc = NotionSource::descriptor();
+        let databases = desc
+            .streams
+            .iter()
+            .find(|s| s.descriptor.name == "databases")
+            .unwrap();
+        assert_eq!(databases.descriptor.table_name, "stream_notion_databases");
+        assert!(!databases.descriptor.supports_incremental);
+        assert!(databases.stream_creator.is_some());
     }
 }
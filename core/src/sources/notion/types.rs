@@ -26,6 +26,34 @@ pub struct Page {
     pub url: String,
 }
 
+/// Database search response
+#[derive(Debug, Deserialize)]
+pub struct DatabaseSearchResponse {
+    pub results: Vec<Database>,
+    pub has_more: bool,
+    pub next_cursor: Option<String>,
+}
+
+/// Notion Database
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Database {
+    pub id: String,
+    #[serde(default)]
+    pub title: Vec<RichText>,
+    pub last_edited_time: DateTime<Utc>,
+    #[serde(default)]
+    pub archived: bool,
+    pub url: String,
+}
+
+/// Database query response: the database's rows, which are pages
+#[derive(Debug, Deserialize)]
+pub struct DatabaseQueryResponse {
+    pub results: Vec<Page>,
+    pub has_more: bool,
+    pub next_cursor: Option<String>,
+}
+
 /// Notion User
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct User {
@@ -77,6 +105,12 @@ pub struct Block {
     pub quote: Option<BlockContent>,
     pub callout: Option<CalloutBlock>,
     pub child_page: Option<ChildPageBlock>,
+    pub table: Option<TableBlock>,
+    pub table_row: Option<TableRowBlock>,
+
+    /// Nested blocks, fetched separately when `has_children` is set
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Block>,
 }
 
 /// Generic block content (paragraphs, headings, lists, etc.)
@@ -119,6 +153,22 @@ pub struct ChildPageBlock {
     pub title: String,
 }
 
+/// Table block; its rows are its children
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TableBlock {
+    pub table_width: usize,
+    #[serde(default)]
+    pub has_column_header: bool,
+    #[serde(default)]
+    pub has_row_header: bool,
+}
+
+/// Table row block: the rich text of each cell
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TableRowBlock {
+    pub cells: Vec<Vec<RichText>>,
+}
+
 /// Rich text
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct RichText {
@@ -16,7 +16,8 @@ use crate::sources::base::oauth::encryption::TokenEncryptor;
 use crate::{
     error::{Error, Result},
     sources::{
-        base::{ConfigSerializable, SyncMode, SyncResult},
+        auth::SourceAuth,
+        base::{ConfigSerializable, SourceClient, SyncMode, SyncResult},
         pull_stream::PullStream,
     },
     storage::stream_writer::StreamWriter,
@@ -42,8 +43,9 @@ impl PlaidAccountsStream {
         source_id: String,
         db: SqlitePool,
         stream_writer: Arc<Mutex<StreamWriter>>,
+        auth: SourceAuth,
     ) -> Result<Self> {
-        let client = PlaidClient::from_env()?;
+        let client = PlaidClient::from_env()?.with_network(auth.network());
 
         Ok(Self {
             source_id,
@@ -159,7 +161,7 @@ impl PlaidAccountsStream {
         let records = {
             let mut writer = self.stream_writer.lock().await;
             let collected = writer
-                .collect_records(&self.source_id, "accounts")
+                .collect_records(&self.source_id, "accounts")?
                 .map(|(records, _, _)| records);
 
             if let Some(ref recs) = collected {
@@ -190,6 +192,7 @@ impl PlaidAccountsStream {
             completed_at,
             records,
             archive_job_id: None,
+            records_deduplicated: 0,
         })
     }
 
@@ -8,14 +8,15 @@
 //! The client sends requests to Tollbooth's /v1/services/plaid/* endpoints,
 //! which then forward them to the actual Plaid API with proper credentials.
 
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::env;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Semaphore;
 
+use super::error_handler::PlaidErrorHandler;
 use crate::error::{Error, Result};
+use crate::sources::base::{HttpAuth, RetryConfig, SourceClient, SourceHttpClient};
 
 /// Plaid API environment (used for display/logging only - actual env is on Tollbooth)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -74,16 +75,15 @@ impl Default for PlaidRateLimiter {
 }
 
 /// Plaid API client that proxies through Tollbooth
+///
+/// Built on `SourceHttpClient`, authenticating to Tollbooth with the internal
+/// secret as an API-key header.
 pub struct PlaidClient {
-    http: Client,
+    http: SourceHttpClient,
     pub environment: PlaidEnvironment,
     rate_limiter: PlaidRateLimiter,
     /// Tollbooth URL (e.g., "http://localhost:9002")
     tollbooth_url: String,
-    /// Internal secret for Tollbooth authentication
-    internal_secret: String,
-    /// User ID for budget tracking
-    user_id: String,
 }
 
 impl PlaidClient {
@@ -102,22 +102,44 @@ impl PlaidClient {
             .map_err(|_| Error::Configuration("TOLLBOOTH_INTERNAL_SECRET not set".to_string()))?;
 
         let environment = PlaidEnvironment::from_env();
-
-        let http = Client::builder()
-            .timeout(Duration::from_secs(120))
-            .build()
-            .map_err(|e| Error::Other(format!("Failed to create HTTP client: {e}")))?;
+        // User ID for budget tracking
+        let user_id = user_id.unwrap_or_else(|| "system".to_string());
+
+        let http = SourceHttpClient::new(
+            "plaid".to_string(),
+            HttpAuth::api_key("X-Internal-Secret", &internal_secret)?,
+        )
+        .with_base_url(&tollbooth_url)
+        .with_timeout(Duration::from_secs(120))
+        .with_retry_config(RetryConfig::default())
+        .with_header("X-User-Id", &user_id)
+        .with_error_handler(Box::new(PlaidErrorHandler));
 
         Ok(Self {
             http,
             environment,
             rate_limiter: PlaidRateLimiter::new(),
             tollbooth_url,
-            internal_secret,
-            user_id: user_id.unwrap_or_else(|| "system".to_string()),
         })
     }
 
+    /// Create a client that answers from recorded fixtures
+    ///
+    /// Needs no Tollbooth URL or secret; nothing leaves the process.
+    #[cfg(any(test, feature = "mock-sources"))]
+    pub fn replaying(mock: Arc<crate::sources::base::MockTransport>) -> Self {
+        let tollbooth_url = "http://tollbooth.invalid".to_string();
+        Self {
+            http: SourceHttpClient::new("plaid".to_string(), HttpAuth::None)
+                .with_base_url(&tollbooth_url)
+                .with_error_handler(Box::new(PlaidErrorHandler))
+                .with_mock_transport(mock),
+            environment: PlaidEnvironment::Sandbox,
+            rate_limiter: PlaidRateLimiter::new(),
+            tollbooth_url,
+        }
+    }
+
     /// Create a new Plaid client with a specific user ID for budget tracking
     pub fn with_user_id(user_id: String) -> Result<Self> {
         Self::new(Some(user_id))
@@ -129,7 +151,7 @@ impl PlaidClient {
     }
 
     /// Make a POST request to Tollbooth's Plaid proxy
-    async fn post<Req: Serialize, Res: for<'de> Deserialize<'de>>(
+    async fn post<Req: Serialize + Sync, Res: DeserializeOwned + Send>(
         &self,
         endpoint: &str,
         body: &Req,
@@ -143,6 +165,7 @@ impl PlaidClient {
             "/transactions/sync" => "/v1/services/plaid/transactions/sync",
             "/accounts/get" | "/accounts/balance/get" => "/v1/services/plaid/accounts/get",
             "/item/remove" => "/v1/services/plaid/item/remove",
+            "/webhook_verification_key/get" => "/v1/services/plaid/webhook-verification-key",
             // For endpoints not yet proxied, return an error
             _ => return Err(Error::Source(format!(
                 "Plaid endpoint {} is not available through Tollbooth proxy",
@@ -150,50 +173,11 @@ impl PlaidClient {
             ))),
         };
 
-        let url = format!("{}{}", self.tollbooth_url, tollbooth_endpoint);
-
-        let response = self
-            .http
-            .post(&url)
-            .header("Content-Type", "application/json")
-            .header("X-Internal-Secret", &self.internal_secret)
-            .header("X-User-Id", &self.user_id)
-            .json(body)
-            .send()
-            .await
-            .map_err(|e| Error::Source(format!("Tollbooth request failed: {e}")))?;
-
-        let status = response.status();
-        let body_text = response
-            .text()
-            .await
-            .map_err(|e| Error::Source(format!("Failed to read response: {e}")))?;
-
-        if !status.is_success() {
-            // Try to parse as Tollbooth/Plaid error
-            if let Ok(error) = serde_json::from_str::<TollboothPlaidError>(&body_text) {
-                return Err(Error::Source(format!(
-                    "Plaid error [{}]: {}",
-                    error.error.code, error.error.message
-                )));
-            }
-            // Try legacy Plaid error format
-            if let Ok(error) = serde_json::from_str::<PlaidError>(&body_text) {
-                return Err(Error::Source(format!(
-                    "Plaid error [{}]: {}",
-                    error.error_code, error.error_message
-                )));
-            }
-            return Err(Error::Source(format!(
-                "Plaid request failed with status {}: {}",
-                status, body_text
-            )));
-        }
+        let response = self.post_json(tollbooth_endpoint, body).await?;
 
         self.rate_limiter.wait_interval().await;
 
-        serde_json::from_str(&body_text)
-            .map_err(|e| Error::Source(format!("Failed to parse response: {e}")))
+        Ok(response)
     }
 
     /// Create a link token for initializing Plaid Link
@@ -203,7 +187,7 @@ impl PlaidClient {
         products: Vec<&str>,
         country_codes: Vec<&str>,
         redirect_uri: Option<&str>,
-        _webhook_url: Option<&str>,
+        webhook_url: Option<&str>,
     ) -> Result<LinkTokenCreateResponse> {
         // Use Tollbooth's request format
         let request = TollboothLinkTokenRequest {
@@ -211,6 +195,31 @@ impl PlaidClient {
             products: products.iter().map(|s| s.to_string()).collect(),
             country_codes: country_codes.iter().map(|s| s.to_string()).collect(),
             redirect_uri: redirect_uri.map(String::from),
+            webhook: webhook_url.map(String::from),
+            access_token: None,
+        };
+
+        self.post("/link/token/create", &request).await
+    }
+
+    /// Create a link token that opens Plaid Link in update mode for an Item
+    ///
+    /// Update mode lets the user fix the Item's login without creating a new
+    /// Item; its access token stays the same.
+    pub async fn link_token_create_update(
+        &self,
+        user_client_id: &str,
+        access_token: &str,
+        country_codes: Vec<&str>,
+        redirect_uri: Option<&str>,
+    ) -> Result<LinkTokenCreateResponse> {
+        let request = TollboothLinkTokenRequest {
+            user_client_id: user_client_id.to_string(),
+            products: Vec::new(),
+            country_codes: country_codes.iter().map(|s| s.to_string()).collect(),
+            redirect_uri: redirect_uri.map(String::from),
+            webhook: None,
+            access_token: Some(access_token.to_string()),
         };
 
         self.post("/link/token/create", &request).await
@@ -303,6 +312,18 @@ impl PlaidClient {
         self.post("/investments/holdings/get", &request).await
     }
 
+    /// Get the public key Plaid signs webhooks with, by key id
+    pub async fn webhook_verification_key_get(
+        &self,
+        key_id: &str,
+    ) -> Result<WebhookVerificationKeyResponse> {
+        let request = WebhookVerificationKeyRequest {
+            key_id: key_id.to_string(),
+        };
+
+        self.post("/webhook_verification_key/get", &request).await
+    }
+
     /// Get liabilities for an Item (credit cards, mortgages, student loans)
     pub async fn liabilities_get(&self, access_token: &str) -> Result<LiabilitiesResponse> {
         let request = AccessTokenRequest {
@@ -317,33 +338,44 @@ impl PlaidClient {
 // Request/Response Types
 // ============================================================================
 
-/// Tollbooth proxy error format
-#[derive(Debug, Deserialize)]
-struct TollboothPlaidError {
-    error: TollboothPlaidErrorDetails,
-}
-
-#[derive(Debug, Deserialize)]
-struct TollboothPlaidErrorDetails {
-    message: String,
-    code: String,
-}
-
-/// Legacy Plaid error format (for direct API errors)
-#[derive(Debug, Deserialize)]
-struct PlaidError {
-    error_code: String,
-    error_message: String,
-}
-
 /// Tollbooth link token request format
 #[derive(Debug, Serialize)]
 struct TollboothLinkTokenRequest {
     user_client_id: String,
+    /// Empty in update mode, where Plaid refuses products
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     products: Vec<String>,
     country_codes: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     redirect_uri: Option<String>,
+    /// URL Plaid sends the new Item's webhooks to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    webhook: Option<String>,
+    /// Item to open Link in update mode for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    access_token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct WebhookVerificationKeyRequest {
+    key_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookVerificationKeyResponse {
+    pub key: WebhookVerificationKey,
+}
+
+/// JWK of a P-256 key Plaid signs webhooks with
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookVerificationKey {
+    pub alg: String,
+    pub kid: String,
+    /// Base64url-encoded curve point coordinates
+    pub x: String,
+    pub y: String,
+    /// Set once the key is rotated out; it must no longer be trusted
+    pub expired_at: Option<i64>,
 }
 
 /// Tollbooth exchange token request format
@@ -743,6 +775,16 @@ impl PlaidErrorCategory {
     }
 }
 
+impl SourceClient for PlaidClient {
+    fn http(&self) -> &SourceHttpClient {
+        &self.http
+    }
+
+    fn http_mut(&mut self) -> &mut SourceHttpClient {
+        &mut self.http
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,12 +2,35 @@
 
 use serde::{Deserialize, Serialize};
 
+/// Which of an Item's accounts a stream syncs
+///
+/// Account ids are listed by `GET /api/plaid/:source_id/accounts`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccountFilter {
+    /// Accounts to sync (empty = all accounts)
+    #[serde(default, alias = "account_ids")]
+    pub account_ids_include: Vec<String>,
+
+    /// Accounts never to sync, even when included
+    #[serde(default)]
+    pub account_ids_exclude: Vec<String>,
+}
+
+impl AccountFilter {
+    /// Whether records for this account are synced
+    pub fn allows(&self, account_id: &str) -> bool {
+        let included = self.account_ids_include.is_empty()
+            || self.account_ids_include.iter().any(|id| id == account_id);
+        included && !self.account_ids_exclude.iter().any(|id| id == account_id)
+    }
+}
+
 /// Configuration for Plaid transactions stream
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlaidTransactionsConfig {
-    /// List of account IDs to sync (empty = all accounts)
-    #[serde(default)]
-    pub account_ids: Vec<String>,
+    /// Accounts to sync
+    #[serde(flatten)]
+    pub accounts: AccountFilter,
 
     /// Whether to include pending transactions
     #[serde(default = "default_include_pending")]
@@ -21,7 +44,7 @@ pub struct PlaidTransactionsConfig {
 impl Default for PlaidTransactionsConfig {
     fn default() -> Self {
         Self {
-            account_ids: Vec::new(),
+            accounts: AccountFilter::default(),
             include_pending: true,
             max_transactions_per_sync: 500,
         }
@@ -59,17 +82,17 @@ impl Default for PlaidAccountsConfig {
 /// Configuration for Plaid investments stream
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PlaidInvestmentsConfig {
-    /// List of account IDs to sync (empty = all investment accounts)
-    #[serde(default)]
-    pub account_ids: Vec<String>,
+    /// Investment accounts to sync
+    #[serde(flatten)]
+    pub accounts: AccountFilter,
 }
 
 /// Configuration for Plaid liabilities stream
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PlaidLiabilitiesConfig {
-    /// List of account IDs to sync (empty = all liability accounts)
-    #[serde(default)]
-    pub account_ids: Vec<String>,
+    /// Liability accounts to sync
+    #[serde(flatten)]
+    pub accounts: AccountFilter,
 }
 
 #[cfg(test)]
@@ -79,7 +102,7 @@ mod tests {
     #[test]
     fn test_default_config() {
         let config = PlaidTransactionsConfig::default();
-        assert!(config.account_ids.is_empty());
+        assert!(config.accounts.account_ids_include.is_empty());
         assert!(!config.include_pending); // Default trait gives false, but our default fn gives true
     }
 
@@ -87,11 +110,26 @@ mod tests {
     fn test_deserialize_config() {
         let json = r#"{"account_ids": ["acc_123"], "include_pending": false}"#;
         let config: PlaidTransactionsConfig = serde_json::from_str(json).unwrap();
-        assert_eq!(config.account_ids, vec!["acc_123"]);
+        assert_eq!(config.accounts.account_ids_include, vec!["acc_123"]);
         assert!(!config.include_pending);
         assert_eq!(config.max_transactions_per_sync, 500); // default
     }
 
+    #[test]
+    fn test_account_filter() {
+        let json =
+            r#"{"account_ids_include": ["acc_1", "acc_2"], "account_ids_exclude": ["acc_2"]}"#;
+        let config: PlaidLiabilitiesConfig = serde_json::from_str(json).unwrap();
+        assert!(config.accounts.allows("acc_1"));
+        assert!(!config.accounts.allows("acc_2"));
+        assert!(!config.accounts.allows("acc_3"));
+
+        let config: PlaidInvestmentsConfig =
+            serde_json::from_str(r#"{"account_ids_exclude": ["acc_2"]}"#).unwrap();
+        assert!(config.accounts.allows("acc_3"));
+        assert!(!config.accounts.allows("acc_2"));
+    }
+
     #[test]
     fn test_accounts_config() {
         let config = PlaidAccountsConfig::default();
@@ -101,12 +139,12 @@ mod tests {
     #[test]
     fn test_investments_config() {
         let config = PlaidInvestmentsConfig::default();
-        assert!(config.account_ids.is_empty());
+        assert!(config.accounts.account_ids_include.is_empty());
     }
 
     #[test]
     fn test_liabilities_config() {
         let config = PlaidLiabilitiesConfig::default();
-        assert!(config.account_ids.is_empty());
+        assert!(config.accounts.account_ids_include.is_empty());
     }
 }
@@ -0,0 +1,160 @@
+//! Plaid-specific error handling
+//!
+//! Maps Tollbooth / Plaid error bodies to source errors carrying the Plaid
+//! error code, so callers can categorize them with `PlaidErrorCategory`.
+//! Codes meaning the item's login is no longer accepted become
+//! `Error::TokenExpired`, which puts the source into `reauth_required` rather
+//! than being retried.
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use crate::error::Error;
+use crate::sources::base::error_handler::{ErrorClass, ErrorHandler};
+
+/// Plaid error codes that only the user reconnecting the item can fix
+const REAUTH_ERROR_CODES: &[&str] = &["ITEM_LOGIN_REQUIRED", "INVALID_ACCESS_TOKEN"];
+
+/// Tollbooth proxy error format
+#[derive(Debug, Deserialize)]
+struct TollboothPlaidError {
+    error: TollboothPlaidErrorDetails,
+}
+
+#[derive(Debug, Deserialize)]
+struct TollboothPlaidErrorDetails {
+    message: String,
+    code: String,
+}
+
+/// Legacy Plaid error format (for direct API errors)
+#[derive(Debug, Deserialize)]
+struct PlaidError {
+    error_code: String,
+    error_message: String,
+}
+
+/// Error code and message of a Tollbooth or legacy Plaid error body
+fn parse_error(body: &str) -> Option<(String, String)> {
+    if let Ok(error) = serde_json::from_str::<TollboothPlaidError>(body) {
+        return Some((error.error.code, error.error.message));
+    }
+    serde_json::from_str::<PlaidError>(body)
+        .ok()
+        .map(|error| (error.error_code, error.error_message))
+}
+
+/// Plaid (via Tollbooth) error handler
+///
+/// Tollbooth bills each proxied call, so only rate limits
+/// (`RATE_LIMIT_EXCEEDED`, sent as 429) are retried.
+pub struct PlaidErrorHandler;
+
+impl ErrorHandler for PlaidErrorHandler {
+    fn should_retry(&self, status: StatusCode, attempt: u32, max_retries: u32) -> bool {
+        attempt < max_retries && status == StatusCode::TOO_MANY_REQUESTS
+    }
+
+    fn classify_error(&self, status: StatusCode, body: &str) -> ErrorClass {
+        match parse_error(body) {
+            Some((code, _)) if code == "RATE_LIMIT_EXCEEDED" => ErrorClass::RateLimit,
+            Some((code, _)) if REAUTH_ERROR_CODES.contains(&code.as_str()) => {
+                ErrorClass::ClientError
+            }
+            _ => match status.as_u16() {
+                401 => ErrorClass::AuthError,
+                429 => ErrorClass::RateLimit,
+                500..=599 => ErrorClass::ServerError,
+                _ => ErrorClass::ClientError,
+            },
+        }
+    }
+
+    fn map_error(&self, status: StatusCode, body: &str) -> Error {
+        if let Some((code, message)) = parse_error(body) {
+            let description = format!("Plaid error [{code}]: {message}");
+            if REAUTH_ERROR_CODES.contains(&code.as_str()) {
+                return Error::TokenExpired(description);
+            }
+            return Error::Source(description);
+        }
+        Error::Source(format!(
+            "Plaid request failed with status {}: {}",
+            status, body
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(error: Error) -> String {
+        match error {
+            Error::Source(msg) => msg,
+            other => panic!("expected a source error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_map_error_formats() {
+        let handler = PlaidErrorHandler;
+
+        let tollbooth = r#"{"error": {"message": "no accounts", "code": "NO_ACCOUNTS"}}"#;
+        assert_eq!(
+            message(handler.map_error(StatusCode::BAD_REQUEST, tollbooth)),
+            "Plaid error [NO_ACCOUNTS]: no accounts"
+        );
+
+        let legacy = r#"{"error_code": "NO_ACCOUNTS", "error_message": "no accounts"}"#;
+        assert_eq!(
+            message(handler.map_error(StatusCode::BAD_REQUEST, legacy)),
+            "Plaid error [NO_ACCOUNTS]: no accounts"
+        );
+
+        assert_eq!(
+            message(handler.map_error(StatusCode::BAD_GATEWAY, "upstream down")),
+            "Plaid request failed with status 502 Bad Gateway: upstream down"
+        );
+    }
+
+    #[test]
+    fn test_login_required_is_token_expired() {
+        let handler = PlaidErrorHandler;
+
+        let tollbooth =
+            r#"{"error": {"message": "login required", "code": "ITEM_LOGIN_REQUIRED"}}"#;
+        assert_eq!(
+            handler.classify_error(StatusCode::BAD_REQUEST, tollbooth),
+            ErrorClass::ClientError
+        );
+        match handler.map_error(StatusCode::BAD_REQUEST, tollbooth) {
+            Error::TokenExpired(msg) => {
+                assert_eq!(msg, "Plaid error [ITEM_LOGIN_REQUIRED]: login required")
+            }
+            other => panic!("expected an expired token, got {other:?}"),
+        }
+
+        let legacy = r#"{"error_code": "INVALID_ACCESS_TOKEN", "error_message": "bad token"}"#;
+        assert!(matches!(
+            handler.map_error(StatusCode::BAD_REQUEST, legacy),
+            Error::TokenExpired(_)
+        ));
+
+        let rate_limited = r#"{"error_code": "RATE_LIMIT_EXCEEDED", "error_message": "slow down"}"#;
+        assert_eq!(
+            handler.classify_error(StatusCode::TOO_MANY_REQUESTS, rate_limited),
+            ErrorClass::RateLimit
+        );
+    }
+
+    #[test]
+    fn test_only_rate_limits_retry() {
+        let handler = PlaidErrorHandler;
+
+        assert!(handler.should_retry(StatusCode::TOO_MANY_REQUESTS, 0, 3));
+        assert!(!handler.should_retry(StatusCode::TOO_MANY_REQUESTS, 3, 3));
+        assert!(!handler.should_retry(StatusCode::INTERNAL_SERVER_ERROR, 0, 3));
+        assert!(!handler.should_retry(StatusCode::UNAUTHORIZED, 0, 3));
+    }
+}
@@ -17,7 +17,8 @@ use crate::sources::base::oauth::encryption::TokenEncryptor;
 use crate::{
     error::{Error, Result},
     sources::{
-        base::{ConfigSerializable, SyncMode, SyncResult},
+        auth::SourceAuth,
+        base::{ConfigSerializable, SourceClient, SyncMode, SyncResult},
         pull_stream::PullStream,
     },
     storage::stream_writer::StreamWriter,
@@ -43,8 +44,9 @@ impl PlaidInvestmentsStream {
         source_id: String,
         db: SqlitePool,
         stream_writer: Arc<Mutex<StreamWriter>>,
+        auth: SourceAuth,
     ) -> Result<Self> {
-        let client = PlaidClient::from_env()?;
+        let client = PlaidClient::from_env()?.with_network(auth.network());
 
         Ok(Self {
             source_id,
@@ -135,8 +137,11 @@ impl PlaidInvestmentsStream {
             .map(|s| (s.security_id.clone(), s))
             .collect();
 
-        // Process each holding
+        // Process each holding in an included account
         for holding in &response.holdings {
+            if !self.config.accounts.allows(&holding.account_id) {
+                continue;
+            }
             records_fetched += 1;
 
             // Look up the security details
@@ -163,7 +168,7 @@ impl PlaidInvestmentsStream {
         let records = {
             let mut writer = self.stream_writer.lock().await;
             let collected = writer
-                .collect_records(&self.source_id, "investments")
+                .collect_records(&self.source_id, "investments")?
                 .map(|(records, _, _)| records);
 
             if let Some(ref recs) = collected {
@@ -195,6 +200,7 @@ impl PlaidInvestmentsStream {
             completed_at,
             records,
             archive_job_id: None,
+            records_deduplicated: 0,
         })
     }
 
@@ -16,7 +16,8 @@ use crate::sources::base::oauth::encryption::TokenEncryptor;
 use crate::{
     error::{Error, Result},
     sources::{
-        base::{ConfigSerializable, SyncMode, SyncResult},
+        auth::SourceAuth,
+        base::{ConfigSerializable, SourceClient, SyncMode, SyncResult},
         pull_stream::PullStream,
     },
     storage::stream_writer::StreamWriter,
@@ -42,8 +43,9 @@ impl PlaidLiabilitiesStream {
         source_id: String,
         db: SqlitePool,
         stream_writer: Arc<Mutex<StreamWriter>>,
+        auth: SourceAuth,
     ) -> Result<Self> {
-        let client = PlaidClient::from_env()?;
+        let client = PlaidClient::from_env()?.with_network(auth.network());
 
         Ok(Self {
             source_id,
@@ -130,6 +132,9 @@ impl PlaidLiabilitiesStream {
         // Process credit card liabilities
         if let Some(credit_cards) = &response.liabilities.credit {
             for credit in credit_cards {
+                if !self.allows(credit.account_id.as_deref()) {
+                    continue;
+                }
                 records_fetched += 1;
                 match self.write_credit_liability(credit).await {
                     Ok(true) => records_written += 1,
@@ -149,6 +154,9 @@ impl PlaidLiabilitiesStream {
         // Process mortgage liabilities
         if let Some(mortgages) = &response.liabilities.mortgage {
             for mortgage in mortgages {
+                if !self.allows(Some(&mortgage.account_id)) {
+                    continue;
+                }
                 records_fetched += 1;
                 match self.write_mortgage_liability(mortgage).await {
                     Ok(true) => records_written += 1,
@@ -168,6 +176,9 @@ impl PlaidLiabilitiesStream {
         // Process student loan liabilities
         if let Some(student_loans) = &response.liabilities.student {
             for student in student_loans {
+                if !self.allows(student.account_id.as_deref()) {
+                    continue;
+                }
                 records_fetched += 1;
                 match self.write_student_loan_liability(student).await {
                     Ok(true) => records_written += 1,
@@ -190,7 +201,7 @@ impl PlaidLiabilitiesStream {
         let records = {
             let mut writer = self.stream_writer.lock().await;
             let collected = writer
-                .collect_records(&self.source_id, "liabilities")
+                .collect_records(&self.source_id, "liabilities")?
                 .map(|(records, _, _)| records);
 
             if let Some(ref recs) = collected {
@@ -221,9 +232,16 @@ impl PlaidLiabilitiesStream {
             completed_at,
             records,
             archive_job_id: None,
+            records_deduplicated: 0,
         })
     }
 
+    /// Whether a liability's account is synced; Plaid may omit the account
+    /// for credit cards and student loans, and those are always kept
+    fn allows(&self, account_id: Option<&str>) -> bool {
+        account_id.is_none_or(|id| self.config.accounts.allows(id))
+    }
+
     /// Write a credit card liability to the StreamWriter
     async fn write_credit_liability(
         &self,
@@ -12,13 +12,17 @@
 //! - `liabilities/` - Credit/loan liability stream and transform
 //! - `config.rs` - Stream configuration types
 //! - `registry.rs` - Source registration
+//! - `webhook.rs` - Webhook verification and routing
 
 pub mod accounts;
 pub mod client;
 pub mod config;
+pub mod error_handler;
 pub mod investments;
 pub mod liabilities;
 pub mod registry;
 pub mod transactions;
+pub mod webhook;
 
 pub use client::PlaidClient;
+pub use webhook::PlaidWebhook;
@@ -7,6 +7,10 @@ use crate::registry::{RegisteredSource, RegisteredStream, SourceRegistry};
 use crate::sources::stream_type::StreamType;
 use serde_json::json;
 
+use super::config::{
+    PlaidAccountsConfig, PlaidInvestmentsConfig, PlaidLiabilitiesConfig, PlaidTransactionsConfig,
+};
+
 // Import transforms and stream types for unified registration
 use super::accounts::{transform::PlaidAccountTransform, PlaidAccountsStream};
 use super::transactions::{transform::PlaidTransactionTransform, PlaidTransactionsStream};
@@ -27,12 +31,16 @@ impl SourceRegistry for PlaidSource {
                 RegisteredStream::new("transactions")
                     .config_schema(transactions_config_schema())
                     .config_example(transactions_config_example())
+                    .config_type::<PlaidTransactionsConfig>()
                     .transform("financial_transaction", |_ctx| Ok(Box::new(PlaidTransactionTransform)))
+                    .dedup_key("transaction_id")
+                    .partition_key("date")
                     .stream_creator(|ctx| {
                         let stream = PlaidTransactionsStream::new(
                             ctx.source_id.clone(),
                             ctx.db.clone(),
                             ctx.stream_writer.clone(),
+                            ctx.auth.clone(),
                         )?;
                         Ok(StreamType::Pull(Box::new(stream)))
                     })
@@ -41,12 +49,15 @@ impl SourceRegistry for PlaidSource {
                 RegisteredStream::new("accounts")
                     .config_schema(accounts_config_schema())
                     .config_example(accounts_config_example())
+                    .config_type::<PlaidAccountsConfig>()
                     .transform("financial_account", |_ctx| Ok(Box::new(PlaidAccountTransform)))
+                    .replace_on_full_refresh()
                     .stream_creator(|ctx| {
                         let stream = PlaidAccountsStream::new(
                             ctx.source_id.clone(),
                             ctx.db.clone(),
                             ctx.stream_writer.clone(),
+                            ctx.auth.clone(),
                         )?;
                         Ok(StreamType::Pull(Box::new(stream)))
                     })
@@ -56,12 +67,14 @@ impl SourceRegistry for PlaidSource {
                 RegisteredStream::new("investments")
                     .config_schema(investments_config_schema())
                     .config_example(investments_config_example())
+                    .config_type::<PlaidInvestmentsConfig>()
                     .build(),
                 // Liabilities stream
                 // Note: target_ontologies empty until financial_liability ontology is created
                 RegisteredStream::new("liabilities")
                     .config_schema(liabilities_config_schema())
                     .config_example(liabilities_config_example())
+                    .config_type::<PlaidLiabilitiesConfig>()
                     .build(),
             ],
         }
@@ -73,10 +86,15 @@ fn transactions_config_schema() -> serde_json::Value {
     json!({
         "type": "object",
         "properties": {
-            "account_ids": {
+            "account_ids_include": {
                 "type": "array",
                 "items": { "type": "string" },
-                "description": "List of account IDs to sync (leave empty to sync all connected accounts)"
+                "description": "Account IDs to sync (leave empty to sync all connected accounts)"
+            },
+            "account_ids_exclude": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "Account IDs never to sync, e.g. a joint account or a closed card"
             },
             "include_pending": {
                 "type": "boolean",
@@ -131,7 +149,8 @@ fn transactions_config_schema() -> serde_json::Value {
 /// Example configuration for Plaid transactions
 fn transactions_config_example() -> serde_json::Value {
     json!({
-        "account_ids": [],
+        "account_ids_include": [],
+        "account_ids_exclude": [],
         "include_pending": true,
         "sync_strategy": {
             "type": "time_window",
@@ -167,10 +186,15 @@ fn investments_config_schema() -> serde_json::Value {
     json!({
         "type": "object",
         "properties": {
-            "account_ids": {
+            "account_ids_include": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "Account IDs to sync (leave empty to sync all investment accounts)"
+            },
+            "account_ids_exclude": {
                 "type": "array",
                 "items": { "type": "string" },
-                "description": "List of account IDs to sync (leave empty to sync all investment accounts)"
+                "description": "Account IDs never to sync, e.g. a joint account or a closed card"
             }
         }
     })
@@ -179,7 +203,8 @@ fn investments_config_schema() -> serde_json::Value {
 /// Example configuration for Plaid investments
 fn investments_config_example() -> serde_json::Value {
     json!({
-        "account_ids": []
+        "account_ids_include": [],
+        "account_ids_exclude": []
     })
 }
 
@@ -188,10 +213,15 @@ fn liabilities_config_schema() -> serde_json::Value {
     json!({
         "type": "object",
         "properties": {
-            "account_ids": {
+            "account_ids_include": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "Account IDs to sync (leave empty to sync all liability accounts)"
+            },
+            "account_ids_exclude": {
                 "type": "array",
                 "items": { "type": "string" },
-                "description": "List of account IDs to sync (leave empty to sync all liability accounts)"
+                "description": "Account IDs never to sync, e.g. a joint account or a closed card"
             }
         }
     })
@@ -200,7 +230,8 @@ fn liabilities_config_schema() -> serde_json::Value {
 /// Example configuration for Plaid liabilities
 fn liabilities_config_example() -> serde_json::Value {
     json!({
-        "account_ids": []
+        "account_ids_include": [],
+        "account_ids_exclude": []
     })
 }
 
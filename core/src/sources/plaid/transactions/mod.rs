@@ -16,7 +16,11 @@ use super::config::PlaidTransactionsConfig;
 use crate::{
     error::{Error, Result},
     sources::{
-        base::{oauth::encryption::TokenEncryptor, ConfigSerializable, SyncMode, SyncResult},
+        auth::SourceAuth,
+        base::{
+            collect_then_commit, discard_buffered_records, oauth::encryption::TokenEncryptor,
+            ConfigSerializable, SourceClient, StreamLimits, SyncMode, SyncResult,
+        },
         pull_stream::PullStream,
     },
     storage::stream_writer::StreamWriter,
@@ -42,8 +46,9 @@ impl PlaidTransactionsStream {
         source_id: String,
         db: SqlitePool,
         stream_writer: Arc<Mutex<StreamWriter>>,
+        auth: SourceAuth,
     ) -> Result<Self> {
-        let client = PlaidClient::from_env()?;
+        let client = PlaidClient::from_env()?.with_network(auth.network());
 
         Ok(Self {
             source_id,
@@ -115,7 +120,14 @@ impl PlaidTransactionsStream {
             .as_ref()
             .ok_or_else(|| Error::Configuration("Plaid access token not loaded".to_string()))?;
 
-        self.sync_internal(access_token, sync_mode).await
+        // Leftovers from a run that died before its checkpoint
+        discard_buffered_records(&self.stream_writer, &self.source_id, "transactions").await;
+
+        let result = self.sync_internal(access_token, sync_mode).await;
+        if result.is_err() {
+            discard_buffered_records(&self.stream_writer, &self.source_id, "transactions").await;
+        }
+        result
     }
 
     /// Internal sync implementation using cursor-based sync
@@ -124,11 +136,8 @@ impl PlaidTransactionsStream {
         let mut records_fetched = 0;
         let mut records_written = 0;
         let mut records_failed = 0;
-        #[allow(unused_assignments)]
-        let mut next_cursor = None;
-
-        // Start database transaction
-        let mut tx = self.db.begin().await?;
+        // Applied together with the cursor at the checkpoint
+        let mut removed_ids: Vec<String> = Vec::new();
 
         // Get last sync cursor from database
         let cursor = match sync_mode {
@@ -148,10 +157,15 @@ impl PlaidTransactionsStream {
             }
         };
 
+        // Plaid's cursor doubles as the resume point: a run stopped at the
+        // record cap saves it and the next incremental run picks up from there
+        let limits = StreamLimits::load(&self.db, &self.source_id, "transactions").await?;
+        let page_size = limits.page_size_or(self.config.max_transactions_per_sync.max(1) as u32);
+
         // Loop until has_more is false
         let mut current_cursor = cursor.clone();
 
-        loop {
+        let next_cursor = loop {
             tracing::debug!(cursor = ?current_cursor, "Fetching transactions batch");
 
             let response = self
@@ -159,15 +173,18 @@ impl PlaidTransactionsStream {
                 .transactions_sync(
                     access_token,
                     current_cursor.as_deref(),
-                    Some(self.config.max_transactions_per_sync),
+                    Some(page_size as i32),
                 )
                 .await?;
 
-            // Process added transactions
+            // Process added transactions, skipping excluded accounts
             for transaction in &response.added {
+                if !self.config.accounts.allows(&transaction.account_id) {
+                    continue;
+                }
                 records_fetched += 1;
 
-                match self.write_transaction(transaction, &mut tx).await {
+                match self.write_transaction(transaction).await {
                     Ok(true) => records_written += 1,
                     Ok(false) => records_failed += 1,
                     Err(e) => {
@@ -183,9 +200,12 @@ impl PlaidTransactionsStream {
 
             // Process modified transactions (update existing)
             for transaction in &response.modified {
+                if !self.config.accounts.allows(&transaction.account_id) {
+                    continue;
+                }
                 records_fetched += 1;
 
-                match self.write_transaction(transaction, &mut tx).await {
+                match self.write_transaction(transaction).await {
                     Ok(true) => records_written += 1,
                     Ok(false) => records_failed += 1,
                     Err(e) => {
@@ -199,24 +219,20 @@ impl PlaidTransactionsStream {
                 }
             }
 
-            // Mark removed transactions as deleted in both stream and ontology tables
+            // Removed transactions are soft-deleted at the checkpoint
             for removed in &response.removed {
                 tracing::info!(transaction_id = %removed.transaction_id, "Transaction removed by Plaid");
-                
-                // Soft-delete in ontology table (data_financial_transaction)
-                // Use the same deterministic ID generation as the transform
-                let ontology_id = crate::ids::generate_id(crate::ids::MONEY_TRANSACTION_PREFIX, &[&self.source_id, &removed.transaction_id]);
-                sqlx::query(
-                    "UPDATE data_financial_transaction SET deleted_at_source = datetime('now'), updated_at = datetime('now') WHERE id = $1"
-                )
-                .bind(&ontology_id)
-                .execute(&mut *tx)
-                .await
-                .ok(); // Ignore errors if record doesn't exist
+                removed_ids.push(removed.transaction_id.clone());
             }
 
             // Check if there's more data
-            if response.has_more {
+            if response.has_more && limits.is_reached(records_fetched) {
+                tracing::info!(
+                    total_fetched = records_fetched,
+                    "Reached per-run record cap, saving cursor to resume next run"
+                );
+                break response.next_cursor;
+            } else if response.has_more {
                 current_cursor = Some(response.next_cursor.clone());
                 tracing::debug!(
                     added = response.added.len(),
@@ -225,67 +241,72 @@ impl PlaidTransactionsStream {
                     "Batch processed, more data available"
                 );
             } else {
-                // No more data - save the cursor
                 tracing::info!(
                     total_fetched = records_fetched,
                     total_written = records_written,
                     "Sync complete, saving cursor"
                 );
-                self.save_cursor_with_tx(&response.next_cursor, &mut tx)
-                    .await?;
-                next_cursor = Some(response.next_cursor);
-                break;
+                break response.next_cursor;
             }
-        }
-
-        // Commit transaction
-        tx.commit().await?;
-
-        let completed_at = Utc::now();
+        };
 
-        // Collect records from StreamWriter
-        let records = {
-            let mut writer = self.stream_writer.lock().await;
-            let collected = writer
-                .collect_records(&self.source_id, "transactions")
-                .map(|(records, _, _)| records);
+        // Hand off records, then apply removals and advance the cursor in one
+        // transaction. A failed commit leaves the cursor where it was, so the
+        // next run replays these pages instead of skipping them.
+        let source_id = self.source_id.clone();
+        let cursor = next_cursor.clone();
+        let records = collect_then_commit(
+            &self.stream_writer,
+            &self.db,
+            &self.source_id,
+            "transactions",
+            move |tx| {
+                Box::pin(async move {
+                    // Soft-delete in ontology table (data_financial_transaction)
+                    // Use the same deterministic ID generation as the transform
+                    for transaction_id in &removed_ids {
+                        let ontology_id = crate::ids::generate_id(
+                            crate::ids::MONEY_TRANSACTION_PREFIX,
+                            &[&source_id, transaction_id],
+                        );
+                        sqlx::query(
+                            "UPDATE data_financial_transaction SET deleted_at_source = datetime('now'), updated_at = datetime('now') WHERE id = $1"
+                        )
+                        .bind(&ontology_id)
+                        .execute(&mut **tx)
+                        .await?; // No-op if the record doesn't exist
+                    }
 
-            if let Some(ref recs) = collected {
-                tracing::info!(
-                    record_count = recs.len(),
-                    "Collected transaction records from StreamWriter"
-                );
-            }
+                    Self::save_cursor_with_tx(&source_id, &cursor, tx).await
+                })
+            },
+        )
+        .await?;
 
-            collected
-        };
+        let completed_at = Utc::now();
 
         Ok(SyncResult {
             records_fetched,
             records_written,
             records_failed,
-            next_cursor,
+            next_cursor: Some(next_cursor),
             earliest_record_at: None,
             latest_record_at: None,
             started_at,
             completed_at,
             records,
             archive_job_id: None,
+            records_deduplicated: 0,
         })
     }
 
     /// Write a transaction to the StreamWriter
-    async fn write_transaction(
-        &self,
-        transaction: &super::client::Transaction,
-        _tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
-    ) -> Result<bool> {
+    async fn write_transaction(&self, transaction: &super::client::Transaction) -> Result<bool> {
         // Parse transaction date (it's a string in YYYY-MM-DD format)
         let timestamp = chrono::NaiveDate::parse_from_str(&transaction.date, "%Y-%m-%d")
             .ok()
             .and_then(|d| d.and_hms_opt(12, 0, 0))
-            .map(|dt| dt.and_utc())
-            .unwrap_or_else(Utc::now);
+            .map(|dt| dt.and_utc());
 
         // Build the record
         let record = serde_json::json!({
@@ -320,7 +341,7 @@ impl PlaidTransactionsStream {
         // Write to StreamWriter
         {
             let mut writer = self.stream_writer.lock().await;
-            writer.write_record(&self.source_id, "transactions", record, Some(timestamp))?;
+            writer.write_record(&self.source_id, "transactions", record, timestamp)?;
         }
 
         tracing::trace!(
@@ -346,7 +367,7 @@ impl PlaidTransactionsStream {
 
     /// Save the cursor to database within a transaction
     async fn save_cursor_with_tx(
-        &self,
+        source_id: &str,
         cursor: &str,
         tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
     ) -> Result<()> {
@@ -355,7 +376,7 @@ impl PlaidTransactionsStream {
         )
         .bind(cursor)
         .bind(Utc::now())
-        .bind(&self.source_id)
+        .bind(source_id)
         .execute(&mut **tx)
         .await?;
 
@@ -396,9 +417,76 @@ impl PullStream for PlaidTransactionsStream {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::sources::base::mock_transport::testing::{
+        archive_to_memory, fixtures, migrated_pool,
+    };
+
     #[test]
     fn test_stream_table_name() {
         // Test that the stream returns correct table name
         assert_eq!("stream_plaid_transactions", "stream_plaid_transactions");
     }
+
+    #[tokio::test]
+    async fn test_full_sync_against_fixtures() {
+        let db = migrated_pool().await;
+        let transport = fixtures("plaid");
+        let stream = PlaidTransactionsStream::with_client(
+            "src-plaid".to_string(),
+            PlaidClient::replaying(transport.clone()),
+            db.clone(),
+            Arc::new(Mutex::new(StreamWriter::new())),
+        );
+
+        let result = stream
+            .sync_internal("access-sandbox-1", &SyncMode::FullRefresh)
+            .await
+            .unwrap();
+        assert_eq!(result.records_written, 2);
+        assert_eq!(result.next_cursor.as_deref(), Some("cursor-2"));
+
+        let records = result.records.unwrap();
+        let written =
+            archive_to_memory("plaid", "src-plaid", "transactions", &records, "date").await;
+        let mut names: Vec<&str> = written
+            .iter()
+            .map(|r| r["name"].as_str().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["Blue Bottle Coffee", "City Grocery"]);
+
+        // The second call continued from the first page's cursor
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 2);
+        let bodies: Vec<serde_json::Value> =
+            requests.into_iter().map(|r| r.body.unwrap()).collect();
+        assert!(bodies[0].get("cursor").is_none());
+        assert_eq!(bodies[1]["cursor"], "cursor-1");
+        assert_eq!(bodies[1]["access_token"], "access-sandbox-1");
+    }
+
+    #[tokio::test]
+    async fn test_excluded_accounts_are_skipped() {
+        let db = migrated_pool().await;
+        let mut stream = PlaidTransactionsStream::with_client(
+            "src-plaid".to_string(),
+            PlaidClient::replaying(fixtures("plaid")),
+            db.clone(),
+            Arc::new(Mutex::new(StreamWriter::new())),
+        );
+        stream.config.accounts.account_ids_exclude = vec!["acc2".to_string()];
+
+        let result = stream
+            .sync_internal("access-sandbox-1", &SyncMode::FullRefresh)
+            .await
+            .unwrap();
+        assert_eq!(result.records_written, 1);
+        assert_eq!(result.records_failed, 0);
+        assert_eq!(result.next_cursor.as_deref(), Some("cursor-2"));
+
+        let records = result.records.unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["account_id"], "acc1");
+    }
 }
@@ -0,0 +1,353 @@
+//! Plaid webhooks
+//!
+//! Plaid signs every delivery with an ES256 JWT in the `Plaid-Verification`
+//! header. Its payload carries the SHA-256 of the request body and the time
+//! it was issued; the header names the key it was signed with, which is
+//! fetched from Plaid (through Tollbooth) and cached by key id. Deliveries
+//! name the Item they concern, which each Plaid source records in its
+//! metadata as `item_id`, so only that source syncs.
+//!
+//! Link tokens register `PLAID_WEBHOOK_URL` as the Item's webhook.
+//!
+//! See: https://plaid.com/docs/api/webhooks/webhook-verification/
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::Utc;
+use ring::signature::{UnparsedPublicKey, ECDSA_P256_SHA256_FIXED};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use super::client::{PlaidClient, WebhookVerificationKey};
+use crate::error::{Error, Result};
+use crate::sources::base::webhook::{
+    constant_time_eq, WebhookAccount, WebhookAction, WebhookRequest, WebhookSource,
+};
+
+/// Source metadata field holding the Plaid Item id
+pub const ITEM_ID_METADATA_KEY: &str = "item_id";
+
+/// Oldest signature accepted, as Plaid recommends
+const MAX_TOKEN_AGE_SECS: i64 = 5 * 60;
+
+/// Verification keys by key id; Plaid rotates them rarely
+static VERIFICATION_KEYS: OnceLock<Mutex<HashMap<String, WebhookVerificationKey>>> =
+    OnceLock::new();
+
+/// Plaid webhook payload (the fields we route on)
+#[derive(Debug, Deserialize)]
+pub struct PlaidWebhookEvent {
+    pub webhook_type: String,
+    pub webhook_code: String,
+    pub item_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct JwtHeader {
+    alg: String,
+    kid: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct JwtClaims {
+    iat: i64,
+    request_body_sha256: String,
+}
+
+/// Plaid [`WebhookSource`]
+pub struct PlaidWebhook {
+    /// Client for fetching verification keys; built from the environment
+    /// when not given
+    client: Option<PlaidClient>,
+}
+
+impl PlaidWebhook {
+    pub fn new(client: Option<PlaidClient>) -> Self {
+        Self { client }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(None)
+    }
+
+    fn parse(body: &[u8]) -> Result<PlaidWebhookEvent> {
+        serde_json::from_slice(body)
+            .map_err(|e| Error::InvalidInput(format!("Invalid Plaid webhook payload: {}", e)))
+    }
+
+    /// Get a verification key from the cache, or from Plaid
+    async fn verification_key(&self, key_id: &str) -> Result<WebhookVerificationKey> {
+        let cache = VERIFICATION_KEYS.get_or_init(Default::default);
+        if let Some(key) = cache.lock().unwrap().get(key_id) {
+            return Ok(key.clone());
+        }
+
+        let key = match &self.client {
+            Some(client) => client.webhook_verification_key_get(key_id).await?,
+            None => {
+                PlaidClient::from_env()?
+                    .webhook_verification_key_get(key_id)
+                    .await?
+            }
+        }
+        .key;
+
+        cache
+            .lock()
+            .unwrap()
+            .insert(key_id.to_string(), key.clone());
+        Ok(key)
+    }
+}
+
+fn unauthorized(message: &str) -> Error {
+    Error::Unauthorized(format!("Plaid webhook {message}"))
+}
+
+fn decode_segment<T: serde::de::DeserializeOwned>(segment: &str) -> Result<T> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(segment)
+        .map_err(|_| unauthorized("token is not valid base64url"))?;
+    serde_json::from_slice(&bytes).map_err(|_| unauthorized("token is malformed"))
+}
+
+/// Check a JWT's signature against a Plaid verification key
+fn verify_signature(
+    key: &WebhookVerificationKey,
+    signing_input: &str,
+    signature: &str,
+) -> Result<()> {
+    if key.alg != "ES256" || key.expired_at.is_some() {
+        return Err(unauthorized("key is expired or not ES256"));
+    }
+
+    // Uncompressed SEC1 point: 0x04 || x || y
+    let mut point = vec![0x04];
+    for coordinate in [&key.x, &key.y] {
+        point.extend(
+            URL_SAFE_NO_PAD
+                .decode(coordinate)
+                .map_err(|_| unauthorized("key is malformed"))?,
+        );
+    }
+    let signature = URL_SAFE_NO_PAD
+        .decode(signature)
+        .map_err(|_| unauthorized("signature is not valid base64url"))?;
+
+    UnparsedPublicKey::new(&ECDSA_P256_SHA256_FIXED, point)
+        .verify(signing_input.as_bytes(), &signature)
+        .map_err(|_| unauthorized("signature mismatch"))
+}
+
+/// Check the token was issued recently, for exactly this body
+fn verify_claims(claims: &JwtClaims, body: &[u8]) -> Result<()> {
+    if (Utc::now().timestamp() - claims.iat).abs() > MAX_TOKEN_AGE_SECS {
+        return Err(unauthorized("token is too old"));
+    }
+
+    let body_sha256 = hex::encode(Sha256::digest(body));
+    if constant_time_eq(
+        body_sha256.as_bytes(),
+        claims.request_body_sha256.to_lowercase().as_bytes(),
+    ) {
+        Ok(())
+    } else {
+        Err(unauthorized("body does not match its signature"))
+    }
+}
+
+#[async_trait]
+impl WebhookSource for PlaidWebhook {
+    fn provider(&self) -> &'static str {
+        "plaid"
+    }
+
+    async fn verify(&self, request: &WebhookRequest<'_>) -> Result<()> {
+        let token = request
+            .headers
+            .get("plaid-verification")
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(|| unauthorized("is missing its Plaid-Verification header"))?;
+
+        let mut segments = token.split('.');
+        let (Some(header), Some(claims), Some(signature), None) = (
+            segments.next(),
+            segments.next(),
+            segments.next(),
+            segments.next(),
+        ) else {
+            return Err(unauthorized("token is malformed"));
+        };
+
+        let jwt_header: JwtHeader = decode_segment(header)?;
+        if jwt_header.alg != "ES256" {
+            return Err(unauthorized("token is not ES256"));
+        }
+
+        let key = self.verification_key(&jwt_header.kid).await?;
+        verify_signature(&key, &token[..header.len() + 1 + claims.len()], signature)?;
+        verify_claims(&decode_segment(claims)?, request.body)
+    }
+
+    fn action(&self, body: &[u8]) -> Result<WebhookAction> {
+        let event = Self::parse(body)?;
+        tracing::debug!(
+            item_id = %event.item_id,
+            webhook_type = %event.webhook_type,
+            webhook_code = %event.webhook_code,
+            "Plaid webhook event"
+        );
+
+        // Item errors (such as ITEM_LOGIN_REQUIRED) surface on the next sync,
+        // which marks the source for reauth
+        if event.webhook_type == "TRANSACTIONS" {
+            Ok(WebhookAction::SyncStreams {
+                streams: vec!["transactions"],
+                account: WebhookAccount {
+                    metadata_key: ITEM_ID_METADATA_KEY,
+                    id: event.item_id,
+                },
+            })
+        } else {
+            Ok(WebhookAction::Ignore)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::base::mock_transport::MockTransport;
+    use axum::http::HeaderMap;
+    use ring::rand::SystemRandom;
+    use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING};
+    use std::sync::Arc;
+
+    const EVENT: &[u8] = br#"{"webhook_type":"TRANSACTIONS","webhook_code":"SYNC_UPDATES_AVAILABLE","item_id":"item-1","initial_update_complete":true,"historical_update_complete":false,"environment":"sandbox"}"#;
+
+    /// A signing key, the webhook (serving its public half as `kid`), and a
+    /// function producing a `Plaid-Verification` token for a body
+    fn signer(kid: &str) -> (PlaidWebhook, impl Fn(&[u8], i64) -> String) {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng).unwrap();
+        let pair = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8.as_ref(), &rng)
+            .unwrap();
+        let point = pair.public_key().as_ref();
+
+        let transport = MockTransport::new().with_response(
+            "/v1/services/plaid/webhook-verification-key",
+            serde_json::json!({
+                "key": {
+                    "alg": "ES256",
+                    "crv": "P-256",
+                    "kid": kid,
+                    "kty": "EC",
+                    "use": "sig",
+                    "x": URL_SAFE_NO_PAD.encode(&point[1..33]),
+                    "y": URL_SAFE_NO_PAD.encode(&point[33..]),
+                    "created_at": 1560466143,
+                    "expired_at": null
+                },
+                "request_id": "req-1"
+            }),
+        );
+        let webhook = PlaidWebhook::new(Some(PlaidClient::replaying(Arc::new(transport))));
+
+        let kid = kid.to_string();
+        let sign = move |body: &[u8], iat: i64| {
+            let header = URL_SAFE_NO_PAD.encode(
+                serde_json::json!({ "alg": "ES256", "kid": kid, "typ": "JWT" }).to_string(),
+            );
+            let claims = URL_SAFE_NO_PAD.encode(
+                serde_json::json!({
+                    "iat": iat,
+                    "request_body_sha256": hex::encode(Sha256::digest(body)),
+                })
+                .to_string(),
+            );
+            let signing_input = format!("{header}.{claims}");
+            let signature = pair
+                .sign(&SystemRandom::new(), signing_input.as_bytes())
+                .unwrap();
+            format!(
+                "{signing_input}.{}",
+                URL_SAFE_NO_PAD.encode(signature.as_ref())
+            )
+        };
+        (webhook, sign)
+    }
+
+    async fn verify(webhook: &PlaidWebhook, token: Option<&str>, body: &[u8]) -> Result<()> {
+        let mut headers = HeaderMap::new();
+        if let Some(token) = token {
+            headers.insert("plaid-verification", token.parse().unwrap());
+        }
+        let query = HashMap::new();
+        webhook
+            .verify(&WebhookRequest {
+                headers: &headers,
+                query: &query,
+                body,
+            })
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_verify_signed_delivery() {
+        let (webhook, sign) = signer("kid-valid");
+        let now = Utc::now().timestamp();
+
+        assert!(verify(&webhook, Some(&sign(EVENT, now)), EVENT)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_reject_forged_or_stale_delivery() {
+        let (webhook, sign) = signer("kid-forged");
+        let now = Utc::now().timestamp();
+
+        // Unsigned, signed for another body, or signed too long ago
+        assert!(matches!(
+            verify(&webhook, None, EVENT).await,
+            Err(Error::Unauthorized(_))
+        ));
+        assert!(matches!(
+            verify(&webhook, Some(&sign(b"{}", now)), EVENT).await,
+            Err(Error::Unauthorized(_))
+        ));
+        assert!(matches!(
+            verify(&webhook, Some(&sign(EVENT, now - 600)), EVENT).await,
+            Err(Error::Unauthorized(_))
+        ));
+
+        // Signed by a key other than the one Plaid serves for the key id
+        let (_, other_sign) = signer("kid-forged");
+        assert!(matches!(
+            verify(&webhook, Some(&other_sign(EVENT, now)), EVENT).await,
+            Err(Error::Unauthorized(_))
+        ));
+    }
+
+    #[test]
+    fn test_transaction_events_sync_the_item() {
+        let webhook = PlaidWebhook::new(None);
+        assert_eq!(
+            webhook.action(EVENT).unwrap(),
+            WebhookAction::SyncStreams {
+                streams: vec!["transactions"],
+                account: WebhookAccount {
+                    metadata_key: ITEM_ID_METADATA_KEY,
+                    id: "item-1".to_string(),
+                },
+            }
+        );
+
+        let item_error = br#"{"webhook_type":"ITEM","webhook_code":"ERROR","item_id":"item-1","error":{"error_code":"ITEM_LOGIN_REQUIRED"}}"#;
+        assert_eq!(webhook.action(item_error).unwrap(), WebhookAction::Ignore);
+    }
+}
@@ -1,8 +1,12 @@
+use std::collections::BTreeMap;
+
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Mutex;
 
-
+use crate::storage::stream_writer::StreamWriter;
 use crate::Result;
 
 /// Trait for sources where the client initiates synchronization
@@ -129,6 +133,9 @@ pub struct PushResult {
 
     /// When the push was received by backend
     pub received_at: DateTime<Utc>,
+
+    /// Outcome of each received record, in payload order
+    pub results: Vec<RecordResult>,
 }
 
 impl PushResult {
@@ -138,15 +145,234 @@ impl PushResult {
             records_received,
             records_written: 0,
             received_at: Utc::now(),
+            results: Vec::with_capacity(records_received),
         }
     }
 
     /// Create a PushResult with both received and written counts
     pub fn with_counts(received: usize, written: usize) -> Self {
         Self {
-            records_received: received,
             records_written: written,
-            received_at: Utc::now(),
+            ..Self::new(received)
+        }
+    }
+
+    /// Mark a received record as accepted
+    pub fn accept(&mut self, index: usize, record: &Value) {
+        self.results.push(RecordResult::accepted(index, record));
+    }
+
+    /// Mark a received record as rejected
+    pub fn reject(&mut self, index: usize, record: &Value, reason: impl ToString) {
+        self.results
+            .push(RecordResult::rejected(index, record, reason.to_string()));
+    }
+}
+
+/// Whether a pushed record was written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordStatus {
+    Accepted,
+    Rejected,
+}
+
+/// Outcome of a single pushed record
+///
+/// Devices match results back to their records by `id` (the record's own
+/// `id` field, when it has one) or by `index`, and retry only the rejected ones.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RecordResult {
+    /// Position of the record in the pushed batch
+    pub index: usize,
+
+    /// Client-supplied record id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    pub status: RecordStatus,
+
+    /// Why the record was rejected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl RecordResult {
+    pub fn accepted(index: usize, record: &Value) -> Self {
+        Self {
+            index,
+            id: client_record_id(record),
+            status: RecordStatus::Accepted,
+            reason: None,
+        }
+    }
+
+    pub fn rejected(index: usize, record: &Value, reason: String) -> Self {
+        Self {
+            index,
+            id: client_record_id(record),
+            status: RecordStatus::Rejected,
+            reason: Some(reason),
+        }
+    }
+}
+
+/// The `id` a device gave a record, as a string
+fn client_record_id(record: &Value) -> Option<String> {
+    match record.get("id")? {
+        Value::String(id) => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+/// A validated record ready to buffer
+pub struct PreparedRecord {
+    /// Index of the pushed record this was prepared from
+    pub index: usize,
+    pub record: Value,
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+/// Buffer a batch's prepared records and record each pushed record's outcome
+///
+/// A pushed record may expand into several prepared ones (EventKit splits
+/// events from reminders); they are buffered all together, and the pushed
+/// record accepted, or none of them are, and it's rejected with the writer's
+/// reason. Pushed records that weren't prepared must already have been
+/// rejected, or they are marked accepted with nothing written (a wrapper
+/// with no items).
+pub async fn write_prepared(
+    stream_writer: &Mutex<StreamWriter>,
+    source_id: &str,
+    stream_name: &str,
+    records: &[Value],
+    prepared: Vec<PreparedRecord>,
+    result: &mut PushResult,
+) {
+    let mut groups = BTreeMap::<usize, Vec<_>>::new();
+    for p in prepared {
+        groups
+            .entry(p.index)
+            .or_default()
+            .push((p.record, p.timestamp));
+    }
+    let indices: Vec<(usize, usize)> = groups.iter().map(|(i, g)| (*i, g.len())).collect();
+    let writes = {
+        let mut writer = stream_writer.lock().await;
+        writer.write_records(source_id, stream_name, groups.into_values())
+    };
+
+    let mut failures: Vec<Option<String>> = vec![None; records.len()];
+    for ((index, written), write) in indices.into_iter().zip(writes) {
+        match write {
+            Ok(()) => result.records_written += written,
+            Err(e) => failures[index] = Some(e.to_string()),
+        }
+    }
+
+    let mut rejected = vec![false; records.len()];
+    for r in &result.results {
+        rejected[r.index] = true;
+    }
+    for (index, record) in records.iter().enumerate() {
+        if rejected[index] {
+            continue;
         }
+        match failures[index].take() {
+            Some(reason) => result.reject(index, record, reason),
+            None => result.accept(index, record),
+        }
+    }
+    result.results.sort_by_key(|r| r.index);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_write_prepared_reports_each_record() {
+        let writer = Mutex::new(StreamWriter::new().with_strict_timestamps(true));
+        let records = vec![
+            json!({"id": "a", "value": 1}),
+            json!({"id": 2, "value": 2}),
+            json!({"value": 3}),
+            json!({"id": "d"}),
+        ];
+        let mut result = PushResult::new(records.len());
+
+        // Record 1 fails the stream's own validation
+        result.reject(1, &records[1], "Invalid value");
+        let prepared = vec![
+            PreparedRecord {
+                index: 0,
+                record: records[0].clone(),
+                timestamp: Some(Utc::now()),
+            },
+            // Record 2 is refused by the writer (no event time in strict mode)
+            PreparedRecord {
+                index: 2,
+                record: records[2].clone(),
+                timestamp: None,
+            },
+            PreparedRecord {
+                index: 3,
+                record: records[3].clone(),
+                timestamp: Some(Utc::now()),
+            },
+        ];
+        write_prepared(&writer, "source_1", "apps", &records, prepared, &mut result).await;
+
+        let statuses: Vec<_> = result.results.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                RecordStatus::Accepted,
+                RecordStatus::Rejected,
+                RecordStatus::Rejected,
+                RecordStatus::Accepted,
+            ]
+        );
+        assert_eq!(result.results[0].id.as_deref(), Some("a"));
+        assert_eq!(result.results[1].id.as_deref(), Some("2"));
+        assert_eq!(result.results[1].reason.as_deref(), Some("Invalid value"));
+        assert!(result.results[2].id.is_none());
+        assert!(result.results[2].reason.is_some());
+        assert_eq!(result.records_written, 2);
+        assert_eq!(writer.lock().await.buffer_count("source_1", "apps"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_write_prepared_keeps_no_part_of_a_rejected_record() {
+        let writer = Mutex::new(StreamWriter::new().with_strict_timestamps(true));
+        let records = vec![json!({"id": "a"}), json!({"id": "b"})];
+        let mut result = PushResult::new(records.len());
+
+        // Record 0 expands into two parts, the second of which is refused
+        let prepared = vec![
+            PreparedRecord {
+                index: 0,
+                record: json!({"id": "a", "kind": "event"}),
+                timestamp: Some(Utc::now()),
+            },
+            PreparedRecord {
+                index: 0,
+                record: json!({"id": "a", "kind": "reminder"}),
+                timestamp: None,
+            },
+            PreparedRecord {
+                index: 1,
+                record: records[1].clone(),
+                timestamp: Some(Utc::now()),
+            },
+        ];
+        write_prepared(&writer, "source_1", "apps", &records, prepared, &mut result).await;
+
+        assert_eq!(result.results[0].status, RecordStatus::Rejected);
+        assert_eq!(result.results[1].status, RecordStatus::Accepted);
+        assert_eq!(result.records_written, 1);
+        assert_eq!(writer.lock().await.buffer_count("source_1", "apps"), 1);
     }
 }
@@ -0,0 +1,189 @@
+//! Slack channels stream implementation
+//!
+//! Snapshots every conversation visible to the user (channels, private
+//! channels, DMs and group DMs) on each run. conversations.list has no change
+//! feed, so every sync is a full listing.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use super::client::SlackClient;
+use crate::{
+    error::Result,
+    sources::{
+        auth::SourceAuth,
+        base::{discard_buffered_records, SourceClient, SyncMode, SyncResult},
+        pull_stream::PullStream,
+    },
+    storage::stream_writer::StreamWriter,
+};
+
+/// Conversation types included in the snapshot
+const CONVERSATION_TYPES: &str = "public_channel,private_channel,mpim,im";
+
+/// Slack channels stream
+///
+/// Syncs channel metadata (name, topic, purpose, membership) to object
+/// storage via StreamWriter.
+pub struct SlackChannelsStream {
+    source_id: String,
+    client: SlackClient,
+    stream_writer: Arc<Mutex<StreamWriter>>,
+}
+
+impl SlackChannelsStream {
+    /// Create a new channels stream with SourceAuth and StreamWriter
+    pub fn new(
+        source_id: String,
+        _db: SqlitePool,
+        stream_writer: Arc<Mutex<StreamWriter>>,
+        auth: SourceAuth,
+    ) -> Self {
+        let token_manager = auth
+            .token_manager()
+            .expect("SlackChannelsStream requires OAuth2 auth")
+            .clone();
+
+        let client =
+            SlackClient::new(source_id.clone(), token_manager).with_network(auth.network());
+
+        Self {
+            source_id,
+            client,
+            stream_writer,
+        }
+    }
+
+    /// Sync channels with explicit sync mode
+    #[tracing::instrument(skip(self), fields(source_id = %self.source_id, mode = ?sync_mode))]
+    pub async fn sync_with_mode(&self, sync_mode: &SyncMode) -> Result<SyncResult> {
+        tracing::info!("Starting Slack channels sync");
+
+        discard_buffered_records(&self.stream_writer, &self.source_id, "channels").await;
+        let result = self.sync_internal().await;
+        if result.is_err() {
+            discard_buffered_records(&self.stream_writer, &self.source_id, "channels").await;
+        }
+        result
+    }
+
+    /// Internal sync implementation
+    async fn sync_internal(&self) -> Result<SyncResult> {
+        let started_at = Utc::now();
+        let mut records_written = 0;
+        let mut records_failed = 0;
+        let mut earliest_record_at: Option<DateTime<Utc>> = None;
+        let mut latest_record_at: Option<DateTime<Utc>> = None;
+
+        let conversations = self
+            .client
+            .list_conversations(CONVERSATION_TYPES, false)
+            .await?;
+        let records_fetched = conversations.len();
+
+        for conversation in &conversations {
+            let created_at = conversation.created_at();
+            if let Some(ts) = created_at {
+                earliest_record_at = Some(earliest_record_at.map_or(ts, |t| t.min(ts)));
+                latest_record_at = Some(latest_record_at.map_or(ts, |t| t.max(ts)));
+            }
+
+            let record = serde_json::json!({
+                "channel_id": conversation.id,
+                "name": conversation.name,
+                "channel_type": conversation.kind(),
+                "is_archived": conversation.is_archived,
+                "is_member": conversation.is_readable(),
+                "dm_user_id": conversation.user,
+                "topic": conversation.topic.as_ref().map(|t| t.value.clone()),
+                "purpose": conversation.purpose.as_ref().map(|p| p.value.clone()),
+                "num_members": conversation.num_members,
+                "created_at": created_at,
+                "synced_at": Utc::now(),
+            });
+
+            let written = {
+                let mut writer = self.stream_writer.lock().await;
+                writer.write_record(&self.source_id, "channels", record, created_at)
+            };
+            match written {
+                Ok(_) => records_written += 1,
+                Err(e) => {
+                    tracing::warn!(
+                        channel_id = %conversation.id,
+                        error = %e,
+                        "Failed to write Slack channel"
+                    );
+                    records_failed += 1;
+                }
+            }
+        }
+
+        let completed_at = Utc::now();
+
+        // Collect records from StreamWriter for archive and transform pipeline
+        let records = {
+            let mut writer = self.stream_writer.lock().await;
+            writer
+                .collect_records(&self.source_id, "channels")?
+                .map(|(records, _, _)| records)
+        };
+
+        tracing::info!(
+            records_fetched,
+            records_written,
+            records_failed,
+            "Slack channels sync completed"
+        );
+
+        Ok(SyncResult {
+            records_fetched,
+            records_written,
+            records_failed,
+            next_cursor: None,
+            earliest_record_at,
+            latest_record_at,
+            started_at,
+            completed_at,
+            records,
+            archive_job_id: None,
+            records_deduplicated: 0,
+        })
+    }
+}
+
+// Implement PullStream trait for SlackChannelsStream
+#[async_trait]
+impl PullStream for SlackChannelsStream {
+    async fn sync_pull(&self, mode: SyncMode) -> Result<SyncResult> {
+        self.sync_with_mode(&mode).await
+    }
+
+    async fn load_config(&mut self, _db: &SqlitePool, _source_id: &str) -> Result<()> {
+        // Slack channels stream has no user-configurable options currently
+        Ok(())
+    }
+
+    fn table_name(&self) -> &str {
+        "stream_slack_channels"
+    }
+
+    fn stream_name(&self) -> &str {
+        "channels"
+    }
+
+    fn source_name(&self) -> &str {
+        "slack"
+    }
+
+    fn supports_incremental(&self) -> bool {
+        false
+    }
+
+    fn supports_full_refresh(&self) -> bool {
+        true
+    }
+}
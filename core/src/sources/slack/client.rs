@@ -0,0 +1,259 @@
+//! Slack API client - thin wrapper over SourceHttpClient
+//!
+//! Slack answers failed Web API calls with HTTP 200 and `"ok": false`, so on
+//! top of the base client this unwraps that envelope and paces calls by the
+//! method's rate-limit tier.
+
+use lazy_static::lazy_static;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::types::{next_cursor, Conversation, ConversationsListResponse, UsersListResponse};
+use crate::{
+    error::{Error, Result},
+    sources::base::{user_agent_for, RetryConfig, SourceClient, SourceHttpClient, TokenManager},
+};
+
+/// Page size for list methods (Slack recommends no more than 200)
+pub const LIST_PAGE_SIZE: u32 = 200;
+
+/// Slack rate-limit tier of a Web API method
+///
+/// Limits are per method, per workspace, per app, so calls are spaced per
+/// source connection and tier, shared by all streams of the connection.
+/// Bursts over the limit still get a 429, which the base client backs off on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SlackTier {
+    /// ~20 requests per minute (conversations.list, users.list)
+    Tier2,
+    /// ~50 requests per minute (conversations.history)
+    Tier3,
+}
+
+impl SlackTier {
+    /// Minimum spacing between calls in this tier
+    pub fn min_interval(&self) -> Duration {
+        match self {
+            SlackTier::Tier2 => Duration::from_millis(3_000),
+            SlackTier::Tier3 => Duration::from_millis(1_200),
+        }
+    }
+}
+
+lazy_static! {
+    /// Earliest time the next call may start, per source connection and tier
+    static ref NEXT_CALL_AT: Mutex<HashMap<(String, SlackTier), Instant>> =
+        Mutex::new(HashMap::new());
+}
+
+/// A Web API method that returned `"ok": false`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlackApiError {
+    pub method: String,
+    pub code: String,
+}
+
+impl SlackApiError {
+    /// The user isn't a member of the conversation they asked about
+    pub fn is_not_in_channel(&self) -> bool {
+        self.code == "not_in_channel"
+    }
+
+    /// A pagination cursor that Slack no longer accepts
+    pub fn is_invalid_cursor(&self) -> bool {
+        self.code == "invalid_cursor"
+    }
+}
+
+impl From<SlackApiError> for Error {
+    fn from(e: SlackApiError) -> Self {
+        match e.code.as_str() {
+            "invalid_auth" | "not_authed" | "token_revoked" | "token_expired"
+            | "account_inactive" => {
+                Error::Authentication(format!("Slack {} failed: {}", e.method, e.code))
+            }
+            _ => Error::Source(format!("Slack {} failed: {}", e.method, e.code)),
+        }
+    }
+}
+
+/// Slack API client with automatic token refresh, retry logic and tier pacing
+///
+/// This is a thin wrapper that configures SourceHttpClient for the Slack Web API.
+/// All HTTP logic (retry, token refresh, error handling) is delegated to the base client.
+pub struct SlackClient {
+    source_id: String,
+    http: SourceHttpClient,
+}
+
+impl SlackClient {
+    /// Create a new Slack API client
+    pub fn new(source_id: String, token_manager: Arc<TokenManager>) -> Self {
+        Self {
+            http: SourceHttpClient::oauth(source_id.clone(), token_manager)
+                .with_base_url("https://slack.com/api")
+                .with_user_agent(&user_agent_for("slack"))
+                .with_retry_config(RetryConfig::default()),
+            source_id,
+        }
+    }
+
+    /// Call a Web API method, returning Slack's error code when it fails
+    pub async fn try_call<T>(
+        &self,
+        method: &str,
+        tier: SlackTier,
+        params: &[(&str, &str)],
+    ) -> Result<std::result::Result<T, SlackApiError>>
+    where
+        T: DeserializeOwned + Send,
+    {
+        self.pace(tier).await;
+
+        let response: serde_json::Value = self.get_with_params(method, params).await?;
+        if response.get("ok").and_then(|v| v.as_bool()) != Some(true) {
+            let code = response
+                .get("error")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown_error")
+                .to_string();
+            return Ok(Err(SlackApiError {
+                method: method.to_string(),
+                code,
+            }));
+        }
+
+        Ok(Ok(serde_json::from_value(response)?))
+    }
+
+    /// Call a Web API method, treating any Slack error as a failure
+    pub async fn call<T>(&self, method: &str, tier: SlackTier, params: &[(&str, &str)]) -> Result<T>
+    where
+        T: DeserializeOwned + Send,
+    {
+        Ok(self.try_call(method, tier, params).await??)
+    }
+
+    /// Every conversation of the given types (`public_channel,im,...`) visible to the user
+    pub async fn list_conversations(
+        &self,
+        types: &str,
+        exclude_archived: bool,
+    ) -> Result<Vec<Conversation>> {
+        let limit = LIST_PAGE_SIZE.to_string();
+        let exclude_archived = exclude_archived.to_string();
+        let mut conversations = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let mut params = vec![
+                ("types", types),
+                ("exclude_archived", exclude_archived.as_str()),
+                ("limit", limit.as_str()),
+            ];
+            if let Some(cursor) = cursor.as_deref() {
+                params.push(("cursor", cursor));
+            }
+
+            let page: ConversationsListResponse = self
+                .call("conversations.list", SlackTier::Tier2, &params)
+                .await?;
+            conversations.extend(page.channels);
+
+            match next_cursor(&page.response_metadata) {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        Ok(conversations)
+    }
+
+    /// Display names of all workspace members, keyed by user ID
+    pub async fn user_names(&self) -> Result<HashMap<String, String>> {
+        let limit = LIST_PAGE_SIZE.to_string();
+        let mut names = HashMap::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let mut params = vec![("limit", limit.as_str())];
+            if let Some(cursor) = cursor.as_deref() {
+                params.push(("cursor", cursor));
+            }
+
+            let page: UsersListResponse =
+                self.call("users.list", SlackTier::Tier2, &params).await?;
+            for user in page.members {
+                if let Some(name) = user.display_name() {
+                    names.insert(user.id, name);
+                }
+            }
+
+            match next_cursor(&page.response_metadata) {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        Ok(names)
+    }
+
+    /// Wait for this connection's next slot in the tier
+    async fn pace(&self, tier: SlackTier) {
+        let wait = {
+            let mut next_call_at = NEXT_CALL_AT.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let slot = next_call_at
+                .get(&(self.source_id.clone(), tier))
+                .copied()
+                .unwrap_or(now)
+                .max(now);
+            next_call_at.insert((self.source_id.clone(), tier), slot + tier.min_interval());
+            slot - now
+        };
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+impl SourceClient for SlackClient {
+    fn http(&self) -> &SourceHttpClient {
+        &self.http
+    }
+
+    fn http_mut(&mut self) -> &mut SourceHttpClient {
+        &mut self.http
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_client_creation() {
+        let pool = sqlx::SqlitePool::connect_lazy("sqlite::memory:").unwrap();
+        let token_manager = Arc::new(TokenManager::new_insecure(pool));
+        let _client = SlackClient::new("test-source".to_string(), token_manager);
+    }
+
+    #[test]
+    fn test_error_mapping() {
+        let not_in_channel = SlackApiError {
+            method: "conversations.history".to_string(),
+            code: "not_in_channel".to_string(),
+        };
+        assert!(not_in_channel.is_not_in_channel());
+        assert!(matches!(Error::from(not_in_channel), Error::Source(_)));
+
+        let revoked = SlackApiError {
+            method: "users.list".to_string(),
+            code: "token_revoked".to_string(),
+        };
+        assert!(matches!(Error::from(revoked), Error::Authentication(_)));
+    }
+}
@@ -0,0 +1,49 @@
+//! Configuration for Slack sources
+
+use serde::{Deserialize, Serialize};
+
+/// Configuration for Slack messages sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlackMessagesConfig {
+    /// Days of history fetched on a first sync or full refresh (default: 30)
+    #[serde(default = "default_days_back")]
+    pub days_back: i64,
+
+    /// Only sync these conversation IDs (empty = every conversation the user is in)
+    #[serde(default)]
+    pub channel_ids: Vec<String>,
+}
+
+impl Default for SlackMessagesConfig {
+    fn default() -> Self {
+        Self {
+            days_back: default_days_back(),
+            channel_ids: vec![],
+        }
+    }
+}
+
+fn default_days_back() -> i64 {
+    30
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::base::ConfigSerializable;
+
+    #[test]
+    fn test_default_config() {
+        let config = SlackMessagesConfig::default();
+        assert_eq!(config.days_back, 30);
+        assert!(config.channel_ids.is_empty());
+    }
+
+    #[test]
+    fn test_partial_json_uses_defaults() {
+        let config =
+            SlackMessagesConfig::from_json(&serde_json::json!({ "channel_ids": ["C1"] })).unwrap();
+        assert_eq!(config.days_back, 30);
+        assert_eq!(config.channel_ids, vec!["C1".to_string()]);
+    }
+}
@@ -0,0 +1,518 @@
+//! Slack messages stream implementation
+//!
+//! Pulls conversations.history for every conversation the user is a member
+//! of. Thread replies are not fetched; thread parents carry their reply count.
+//!
+//! History is read per channel, so the incremental cursor stored in
+//! `last_sync_token` is a JSON object mapping each channel ID to the newest
+//! message `ts` synced from it. A channel's entry only moves once its history
+//! has been paged to the end. Slack pages history newest-first, so a run that
+//! stops at the record cap also stores the capped channel's page cursor; the
+//! next run resumes paging from there instead of refetching the newest pages.
+
+pub mod transform;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use super::{
+    client::{SlackClient, SlackTier},
+    config::SlackMessagesConfig,
+    types::{format_ts, next_cursor, parse_ts, Conversation, HistoryResponse},
+};
+use crate::{
+    error::Result,
+    sources::{
+        auth::SourceAuth,
+        base::{
+            discard_buffered_records, ConfigSerializable, SourceClient, StreamLimits, SyncMode,
+            SyncResult,
+        },
+        pull_stream::PullStream,
+    },
+    storage::stream_writer::StreamWriter,
+};
+
+/// Conversation types whose history is synced
+const CONVERSATION_TYPES: &str = "public_channel,private_channel,mpim,im";
+
+/// Maximum page size for conversations.history
+const MAX_PAGE_SIZE: u32 = 200;
+
+/// Newest synced message `ts` per channel ID
+type ChannelCursors = BTreeMap<String, String>;
+
+/// A channel whose history was only partly paged when a run hit the record cap
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct PartialHistory {
+    /// Slack cursor of the next (older) page
+    page_cursor: String,
+    /// Newest message `ts` seen so far; becomes the channel's cursor once
+    /// its history has been paged to the end
+    newest: Option<String>,
+}
+
+/// Incremental sync state stored in `last_sync_token`
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct MessagesCursor {
+    channels: ChannelCursors,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    partial: BTreeMap<String, PartialHistory>,
+}
+
+/// Parse the stored cursor, starting over if it is missing or malformed
+///
+/// Cursors written before partial histories were tracked are a bare map of
+/// channel cursors.
+fn parse_cursors(cursor: Option<&str>) -> MessagesCursor {
+    let Some(cursor) = cursor else {
+        return MessagesCursor::default();
+    };
+    serde_json::from_str(cursor)
+        .or_else(|_| {
+            serde_json::from_str(cursor).map(|channels| MessagesCursor {
+                channels,
+                partial: BTreeMap::new(),
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Later of two Slack timestamps
+fn newer_ts(current: Option<String>, candidate: &str) -> Option<String> {
+    match current {
+        Some(ts) if parse_ts(&ts) >= parse_ts(candidate) => Some(ts),
+        _ => Some(candidate.to_string()),
+    }
+}
+
+/// Slack messages stream
+///
+/// Syncs messages from channels, private channels, DMs and group DMs to
+/// object storage via StreamWriter.
+pub struct SlackMessagesStream {
+    source_id: String,
+    client: SlackClient,
+    db: SqlitePool,
+    stream_writer: Arc<Mutex<StreamWriter>>,
+    config: SlackMessagesConfig,
+}
+
+impl SlackMessagesStream {
+    /// Create a new messages stream with SourceAuth and StreamWriter
+    pub fn new(
+        source_id: String,
+        db: SqlitePool,
+        stream_writer: Arc<Mutex<StreamWriter>>,
+        auth: SourceAuth,
+    ) -> Self {
+        let token_manager = auth
+            .token_manager()
+            .expect("SlackMessagesStream requires OAuth2 auth")
+            .clone();
+
+        let client =
+            SlackClient::new(source_id.clone(), token_manager).with_network(auth.network());
+
+        Self {
+            source_id,
+            client,
+            db,
+            stream_writer,
+            config: SlackMessagesConfig::default(),
+        }
+    }
+
+    /// Sync messages with explicit sync mode
+    #[tracing::instrument(skip(self), fields(source_id = %self.source_id, mode = ?sync_mode))]
+    pub async fn sync_with_mode(&self, sync_mode: &SyncMode) -> Result<SyncResult> {
+        tracing::info!("Starting Slack messages sync");
+
+        discard_buffered_records(&self.stream_writer, &self.source_id, "messages").await;
+        let result = self.sync_internal(sync_mode).await;
+        if result.is_err() {
+            discard_buffered_records(&self.stream_writer, &self.source_id, "messages").await;
+        }
+        result
+    }
+
+    /// Internal sync implementation
+    async fn sync_internal(&self, sync_mode: &SyncMode) -> Result<SyncResult> {
+        let started_at = Utc::now();
+        let mut records_fetched = 0;
+        let mut records_written = 0;
+        let mut records_failed = 0;
+        let mut next_cursor_token = None;
+        let mut earliest_record_at: Option<DateTime<Utc>> = None;
+        let mut latest_record_at: Option<DateTime<Utc>> = None;
+
+        let default_oldest = format_ts(started_at - Duration::days(self.config.days_back));
+        let (mut cursors, backfill_range) = match sync_mode {
+            SyncMode::Incremental { cursor } => {
+                let stored = match cursor {
+                    Some(cursor) => Some(cursor.clone()),
+                    None => self.get_last_cursor().await?,
+                };
+                (parse_cursors(stored.as_deref()), None)
+            }
+            SyncMode::FullRefresh => (MessagesCursor::default(), None),
+            SyncMode::Backfill {
+                start_date,
+                end_date,
+            } => (
+                MessagesCursor::default(),
+                Some((format_ts(*start_date), format_ts(*end_date))),
+            ),
+        };
+
+        let limits = StreamLimits::load(&self.db, &self.source_id, "messages").await?;
+        let page_size = limits
+            .page_size_or(MAX_PAGE_SIZE)
+            .min(MAX_PAGE_SIZE)
+            .to_string();
+
+        let users = self.client.user_names().await?;
+        let conversations: Vec<Conversation> = self
+            .client
+            .list_conversations(CONVERSATION_TYPES, true)
+            .await?
+            .into_iter()
+            .filter(|c| c.is_readable())
+            .filter(|c| {
+                self.config.channel_ids.is_empty() || self.config.channel_ids.contains(&c.id)
+            })
+            .collect();
+
+        tracing::info!(
+            conversations = conversations.len(),
+            "Fetching history for Slack conversations"
+        );
+
+        let mut capped = false;
+
+        'conversations: for conversation in &conversations {
+            let channel_name = channel_display_name(conversation, &users);
+            let (oldest, latest) = match &backfill_range {
+                Some((oldest, latest)) => (oldest.clone(), Some(latest.as_str())),
+                None => (
+                    cursors
+                        .channels
+                        .get(&conversation.id)
+                        .cloned()
+                        .unwrap_or_else(|| default_oldest.clone()),
+                    None,
+                ),
+            };
+
+            // Resume a channel the last run stopped partway through
+            let (mut page_cursor, mut newest) = match cursors.partial.remove(&conversation.id) {
+                Some(partial) => (Some(partial.page_cursor), partial.newest),
+                None => (None, None),
+            };
+
+            loop {
+                let mut params = vec![
+                    ("channel", conversation.id.as_str()),
+                    ("oldest", oldest.as_str()),
+                    ("limit", page_size.as_str()),
+                ];
+                if let Some(latest) = latest {
+                    params.push(("latest", latest));
+                }
+                if let Some(cursor) = page_cursor.as_deref() {
+                    params.push(("cursor", cursor));
+                }
+
+                let page: HistoryResponse = match self
+                    .client
+                    .try_call("conversations.history", SlackTier::Tier3, &params)
+                    .await?
+                {
+                    Ok(page) => page,
+                    // Membership can change between listing and reading
+                    Err(e) if e.is_not_in_channel() => {
+                        tracing::debug!(
+                            channel_id = %conversation.id,
+                            "Not in Slack channel, skipping"
+                        );
+                        continue 'conversations;
+                    }
+                    // A stored page cursor can expire between runs
+                    Err(e) if e.is_invalid_cursor() && page_cursor.is_some() => {
+                        tracing::debug!(
+                            channel_id = %conversation.id,
+                            "Slack page cursor expired, restarting channel history"
+                        );
+                        page_cursor = None;
+                        newest = None;
+                        continue;
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+
+                records_fetched += page.messages.len();
+
+                for message in &page.messages {
+                    let timestamp = parse_ts(&message.ts);
+                    if let Some(ts) = timestamp {
+                        earliest_record_at = Some(earliest_record_at.map_or(ts, |t| t.min(ts)));
+                        latest_record_at = Some(latest_record_at.map_or(ts, |t| t.max(ts)));
+                    }
+                    newest = newer_ts(newest, &message.ts);
+
+                    let record = serde_json::json!({
+                        "message_id": format!("{}:{}", conversation.id, message.ts),
+                        "channel_id": conversation.id,
+                        "channel_name": channel_name,
+                        "channel_type": conversation.kind(),
+                        "ts": message.ts,
+                        "thread_ts": message.thread_ts,
+                        "reply_count": message.reply_count,
+                        "user_id": message.user,
+                        "user_name": message.user.as_ref().and_then(|u| users.get(u)),
+                        "bot_id": message.bot_id,
+                        "username": message.username,
+                        "subtype": message.subtype,
+                        "text": message.text,
+                        "file_count": message.files.len(),
+                        "edited": message.edited.is_some(),
+                        "timestamp": timestamp,
+                        "synced_at": Utc::now(),
+                    });
+
+                    let written = {
+                        let mut writer = self.stream_writer.lock().await;
+                        writer.write_record(&self.source_id, "messages", record, timestamp)
+                    };
+                    match written {
+                        Ok(_) => records_written += 1,
+                        Err(e) => {
+                            tracing::warn!(
+                                channel_id = %conversation.id,
+                                ts = %message.ts,
+                                error = %e,
+                                "Failed to write Slack message"
+                            );
+                            records_failed += 1;
+                        }
+                    }
+                }
+
+                capped = limits.is_reached(records_fetched);
+                match next_cursor(&page.response_metadata) {
+                    Some(next) if capped => {
+                        cursors.partial.insert(
+                            conversation.id.clone(),
+                            PartialHistory {
+                                page_cursor: next,
+                                newest,
+                            },
+                        );
+                        break 'conversations;
+                    }
+                    Some(next) => page_cursor = Some(next),
+                    None => break,
+                }
+            }
+
+            // History for this channel is complete up to `newest`
+            if let Some(ts) = newest {
+                cursors.channels.insert(conversation.id.clone(), ts);
+            }
+            if capped {
+                break;
+            }
+        }
+
+        // Backfills cover an arbitrary past range and must not move the cursor
+        if !matches!(sync_mode, SyncMode::Backfill { .. }) {
+            let cursor = serde_json::to_string(&cursors)?;
+            self.save_cursor(&cursor).await?;
+            next_cursor_token = Some(cursor);
+        }
+
+        if capped {
+            tracing::info!("Slack messages sync stopped at record cap");
+        }
+
+        let completed_at = Utc::now();
+
+        // Collect records from StreamWriter for archive and transform pipeline
+        let records = {
+            let mut writer = self.stream_writer.lock().await;
+            writer
+                .collect_records(&self.source_id, "messages")?
+                .map(|(records, _, _)| records)
+        };
+
+        tracing::info!(
+            records_fetched,
+            records_written,
+            records_failed,
+            "Slack messages sync completed"
+        );
+
+        Ok(SyncResult {
+            records_fetched,
+            records_written,
+            records_failed,
+            next_cursor: next_cursor_token,
+            earliest_record_at,
+            latest_record_at,
+            started_at,
+            completed_at,
+            records,
+            archive_job_id: None,
+            records_deduplicated: 0,
+        })
+    }
+
+    /// Get the last sync cursor from the database
+    async fn get_last_cursor(&self) -> Result<Option<String>> {
+        let row = sqlx::query_as::<_, (Option<String>,)>(
+            "SELECT last_sync_token FROM elt_stream_connections WHERE source_connection_id = $1 AND stream_name = 'messages'",
+        )
+        .bind(&self.source_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.and_then(|(token,)| token))
+    }
+
+    /// Save the sync cursor
+    async fn save_cursor(&self, cursor: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE elt_stream_connections SET last_sync_token = $1, last_sync_at = $2 WHERE source_connection_id = $3 AND stream_name = 'messages'"
+        )
+        .bind(cursor)
+        .bind(Utc::now())
+        .bind(&self.source_id)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Human-readable conversation name; DMs are named after the other participant
+fn channel_display_name(conversation: &Conversation, users: &HashMap<String, String>) -> String {
+    if conversation.is_im {
+        if let Some(name) = conversation.user.as_ref().and_then(|u| users.get(u)) {
+            return format!("@{name}");
+        }
+    }
+    conversation
+        .name
+        .clone()
+        .unwrap_or_else(|| conversation.id.clone())
+}
+
+// Implement PullStream trait for SlackMessagesStream
+#[async_trait]
+impl PullStream for SlackMessagesStream {
+    async fn sync_pull(&self, mode: SyncMode) -> Result<SyncResult> {
+        self.sync_with_mode(&mode).await
+    }
+
+    async fn load_config(&mut self, db: &SqlitePool, source_id: &str) -> Result<()> {
+        let result = sqlx::query_as::<_, (serde_json::Value,)>(
+            "SELECT config FROM elt_stream_connections WHERE source_connection_id = $1 AND stream_name = 'messages'",
+        )
+        .bind(source_id)
+        .fetch_optional(db)
+        .await?;
+
+        if let Some((config_json,)) = result {
+            if let Ok(config) = SlackMessagesConfig::from_json(&config_json) {
+                self.config = config;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn table_name(&self) -> &str {
+        "stream_slack_messages"
+    }
+
+    fn stream_name(&self) -> &str {
+        "messages"
+    }
+
+    fn source_name(&self) -> &str {
+        "slack"
+    }
+
+    fn supports_incremental(&self) -> bool {
+        true
+    }
+
+    fn supports_full_refresh(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cursors() {
+        let cursors = parse_cursors(Some(r#"{"C1":"1700000000.000100"}"#));
+        assert_eq!(
+            cursors.channels.get("C1").map(String::as_str),
+            Some("1700000000.000100")
+        );
+        assert!(cursors.partial.is_empty());
+
+        assert_eq!(
+            parse_cursors(Some("2024-01-01T00:00:00Z")),
+            MessagesCursor::default()
+        );
+        assert_eq!(parse_cursors(None), MessagesCursor::default());
+    }
+
+    #[test]
+    fn test_partial_history_round_trips() {
+        let mut cursors = MessagesCursor::default();
+        cursors
+            .channels
+            .insert("C1".to_string(), "1700000000.000100".to_string());
+        cursors.partial.insert(
+            "C2".to_string(),
+            PartialHistory {
+                page_cursor: "bmV4dF90czoxNjk5".to_string(),
+                newest: Some("1700000500.000200".to_string()),
+            },
+        );
+
+        let stored = serde_json::to_string(&cursors).unwrap();
+        assert_eq!(parse_cursors(Some(&stored)), cursors);
+    }
+
+    #[test]
+    fn test_newer_ts_compares_numerically() {
+        let newest = newer_ts(None, "999999999.000001");
+        let newest = newer_ts(newest, "1000000000.000000");
+        let newest = newer_ts(newest, "1000000000.000000");
+        assert_eq!(newest.as_deref(), Some("1000000000.000000"));
+    }
+
+    #[test]
+    fn test_dm_named_after_participant() {
+        let dm: Conversation = serde_json::from_value(serde_json::json!({
+            "id": "D1",
+            "is_im": true,
+            "user": "U1"
+        }))
+        .unwrap();
+        let users = HashMap::from([("U1".to_string(), "Egon".to_string())]);
+        assert_eq!(channel_display_name(&dm, &users), "@Egon");
+        assert_eq!(channel_display_name(&dm, &HashMap::new()), "D1");
+    }
+}
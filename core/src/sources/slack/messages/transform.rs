@@ -0,0 +1,388 @@
+//! Slack messages to communication_message ontology transformation
+//!
+//! Transforms messages from stream_slack_messages into the normalized
+//! data_communication_message table shared with iMessage. Channel membership
+//! events (joins, leaves, topic changes) are skipped.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::super::types::parse_ts;
+use crate::database::Database;
+use crate::error::Result;
+use crate::jobs::TransformContext;
+use crate::sources::base::{OntologyTransform, TransformRegistration, TransformResult};
+
+/// Batch size for bulk inserts
+const BATCH_SIZE: usize = 500;
+
+/// Message subtypes that record channel events rather than something said
+const SKIPPED_SUBTYPES: &[&str] = &[
+    "channel_join",
+    "channel_leave",
+    "channel_topic",
+    "channel_purpose",
+    "channel_name",
+    "channel_archive",
+    "channel_unarchive",
+    "group_join",
+    "group_leave",
+    "group_topic",
+    "group_purpose",
+    "group_name",
+    "group_archive",
+    "group_unarchive",
+];
+
+/// A row for data_communication_message
+struct MessageRow {
+    id: String,
+    source_connection_id: String,
+    message_id: String,
+    thread_id: Option<String>,
+    body: Option<String>,
+    from_identifier: String,
+    from_name: Option<String>,
+    is_group_message: bool,
+    reply_to_message_id: Option<String>,
+    has_attachments: bool,
+    timestamp: DateTime<Utc>,
+    metadata: serde_json::Value,
+}
+
+/// Transform Slack messages to communication_message ontology
+///
+/// This transform is registered with the stream in the unified registry,
+/// and also self-registers via inventory for backward compatibility.
+pub struct SlackMessageTransform;
+
+#[async_trait]
+impl OntologyTransform for SlackMessageTransform {
+    fn source_table(&self) -> &str {
+        "stream_slack_messages"
+    }
+
+    fn target_table(&self) -> &str {
+        "communication_message"
+    }
+
+    fn domain(&self) -> &str {
+        "communication"
+    }
+
+    #[tracing::instrument(skip(self, db, context), fields(source_table = %self.source_table(), target_table = %self.target_table()))]
+    async fn transform(
+        &self,
+        db: &Database,
+        context: &crate::jobs::transform_context::TransformContext,
+        source_id: String,
+    ) -> Result<TransformResult> {
+        let mut records_read = 0;
+        let mut records_written = 0;
+        let mut records_failed = 0;
+        let mut last_processed_id: Option<String> = None;
+
+        let transform_start = std::time::Instant::now();
+
+        tracing::info!(
+            source_id = %source_id,
+            "Starting Slack messages to communication_message transformation"
+        );
+
+        // Read stream data using data source (memory for hot path)
+        let checkpoint_key = "slack_messages_to_communication_message";
+        let data_source = context.get_data_source().ok_or_else(|| {
+            crate::Error::Other("No data source available for transform".to_string())
+        })?;
+        let batches = data_source
+            .read_with_checkpoint(&source_id, "messages", checkpoint_key)
+            .await?;
+
+        let mut pending_records: Vec<MessageRow> = Vec::new();
+
+        for batch in batches {
+            for record in &batch.records {
+                records_read += 1;
+
+                match message_row(&source_id, record) {
+                    Some(Ok(row)) => {
+                        last_processed_id = Some(row.message_id.clone());
+                        pending_records.push(row);
+                    }
+                    Some(Err(())) => {
+                        records_failed += 1;
+                        continue;
+                    }
+                    None => continue,
+                }
+
+                // Execute batch insert when we reach batch size
+                if pending_records.len() >= BATCH_SIZE {
+                    match execute_message_batch_insert(db, &pending_records).await {
+                        Ok(written) => records_written += written,
+                        Err(e) => {
+                            tracing::warn!(
+                                error = %e,
+                                batch_size = pending_records.len(),
+                                "Batch insert failed"
+                            );
+                            records_failed += pending_records.len();
+                        }
+                    }
+                    pending_records.clear();
+                }
+            }
+
+            // Update checkpoint after processing batch
+            if let Some(max_ts) = batch.max_timestamp {
+                data_source
+                    .update_checkpoint(&source_id, "messages", checkpoint_key, max_ts)
+                    .await?;
+            }
+        }
+
+        // Insert any remaining records
+        if !pending_records.is_empty() {
+            match execute_message_batch_insert(db, &pending_records).await {
+                Ok(written) => records_written += written,
+                Err(e) => {
+                    tracing::warn!(
+                        error = %e,
+                        batch_size = pending_records.len(),
+                        "Final batch insert failed"
+                    );
+                    records_failed += pending_records.len();
+                }
+            }
+        }
+
+        tracing::info!(
+            source_id = %source_id,
+            records_read,
+            records_written,
+            records_failed,
+            total_duration_ms = transform_start.elapsed().as_millis(),
+            "Slack messages to communication_message transformation completed"
+        );
+
+        Ok(TransformResult {
+            records_read,
+            records_written,
+            records_failed,
+            last_processed_id,
+            chained_transforms: vec![],
+        })
+    }
+}
+
+/// Map a stream record to a message row
+///
+/// Returns `None` for records that are skipped on purpose (channel events,
+/// empty messages) and `Some(Err(()))` for records missing required fields.
+fn message_row(
+    source_id: &str,
+    record: &serde_json::Value,
+) -> Option<std::result::Result<MessageRow, ()>> {
+    let str_field = |key: &str| record.get(key).and_then(|v| v.as_str()).map(String::from);
+
+    let subtype = str_field("subtype");
+    if subtype
+        .as_deref()
+        .is_some_and(|s| SKIPPED_SUBTYPES.contains(&s))
+    {
+        return None;
+    }
+
+    let body = str_field("text").filter(|t| !t.is_empty());
+    let file_count = record
+        .get("file_count")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    if body.is_none() && file_count == 0 {
+        return None;
+    }
+
+    let (Some(message_id), Some(channel_id), Some(ts)) = (
+        str_field("message_id"),
+        str_field("channel_id"),
+        str_field("ts"),
+    ) else {
+        return Some(Err(()));
+    };
+    let Some(timestamp) = parse_ts(&ts) else {
+        return Some(Err(()));
+    };
+
+    let from_identifier = str_field("user_id")
+        .or_else(|| str_field("bot_id"))
+        .unwrap_or_else(|| "unknown".to_string());
+    let from_name = str_field("user_name").or_else(|| str_field("username"));
+    let channel_type = str_field("channel_type");
+    let thread_ts = str_field("thread_ts");
+
+    // Replies point at their thread parent; the parent's thread_ts is its own ts
+    let thread_id = thread_ts
+        .as_ref()
+        .map(|parent| format!("{channel_id}:{parent}"));
+    let reply_to_message_id = thread_id
+        .clone()
+        .filter(|_| thread_ts.as_deref() != Some(ts.as_str()));
+
+    let metadata = serde_json::json!({
+        "channel_id": channel_id,
+        "channel_name": str_field("channel_name"),
+        "channel_type": channel_type,
+        "ts": ts,
+        "subtype": subtype,
+        "reply_count": record.get("reply_count"),
+        "file_count": file_count,
+        "edited": record.get("edited"),
+        "source_connection_id": source_id,
+    });
+
+    Some(Ok(MessageRow {
+        id: crate::ids::generate_id("communication_message", &[source_id, &message_id]),
+        source_connection_id: source_id.to_string(),
+        message_id,
+        thread_id,
+        body,
+        from_identifier,
+        from_name,
+        is_group_message: channel_type.as_deref() != Some("im"),
+        reply_to_message_id,
+        has_attachments: file_count > 0,
+        timestamp,
+        metadata,
+    }))
+}
+
+/// Execute batch insert for communication_message records
+async fn execute_message_batch_insert(db: &Database, records: &[MessageRow]) -> Result<usize> {
+    if records.is_empty() {
+        return Ok(0);
+    }
+
+    let query_str = Database::build_batch_insert_query(
+        "data_communication_message",
+        &[
+            "id",
+            "source_connection_id",
+            "message_id",
+            "thread_id",
+            "channel",
+            "body",
+            "from_identifier",
+            "from_name",
+            "is_group_message",
+            "reply_to_message_id",
+            "has_attachments",
+            "timestamp",
+            "source_stream_id",
+            "source_table",
+            "source_provider",
+            "metadata",
+        ],
+        "source_stream_id",
+        records.len(),
+    );
+
+    let mut query = sqlx::query(&query_str);
+
+    for row in records {
+        let metadata_str =
+            serde_json::to_string(&row.metadata).unwrap_or_else(|_| "{}".to_string());
+
+        query = query
+            .bind(&row.id)
+            .bind(&row.source_connection_id)
+            .bind(&row.message_id)
+            .bind(&row.thread_id)
+            .bind("slack")
+            .bind(&row.body)
+            .bind(&row.from_identifier)
+            .bind(&row.from_name)
+            .bind(row.is_group_message)
+            .bind(&row.reply_to_message_id)
+            .bind(row.has_attachments)
+            .bind(row.timestamp)
+            .bind(&row.message_id)
+            .bind("stream_slack_messages")
+            .bind("slack")
+            .bind(metadata_str);
+    }
+
+    let result = query.execute(db.pool()).await?;
+    Ok(result.rows_affected() as usize)
+}
+
+// Self-registration for backward compatibility with inventory-based lookup
+struct SlackMessageTransformRegistration;
+
+impl TransformRegistration for SlackMessageTransformRegistration {
+    fn source_table(&self) -> &'static str {
+        "stream_slack_messages"
+    }
+    fn target_table(&self) -> &'static str {
+        "communication_message"
+    }
+    fn create(&self, _context: &TransformContext) -> Result<Box<dyn OntologyTransform>> {
+        Ok(Box::new(SlackMessageTransform))
+    }
+}
+
+inventory::submit! {
+    &SlackMessageTransformRegistration as &dyn TransformRegistration
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transform_metadata() {
+        let transform = SlackMessageTransform;
+        assert_eq!(transform.source_table(), "stream_slack_messages");
+        assert_eq!(transform.target_table(), "communication_message");
+        assert_eq!(transform.domain(), "communication");
+    }
+
+    #[test]
+    fn test_thread_reply_points_at_parent() {
+        let record = serde_json::json!({
+            "message_id": "C1:1700000001.000200",
+            "channel_id": "C1",
+            "channel_type": "public_channel",
+            "ts": "1700000001.000200",
+            "thread_ts": "1700000000.000100",
+            "user_id": "U1",
+            "user_name": "Egon",
+            "text": "on it",
+        });
+
+        let row = message_row("src", &record).unwrap().unwrap();
+        assert_eq!(row.thread_id.as_deref(), Some("C1:1700000000.000100"));
+        assert_eq!(
+            row.reply_to_message_id.as_deref(),
+            Some("C1:1700000000.000100")
+        );
+        assert_eq!(row.from_name.as_deref(), Some("Egon"));
+        assert!(row.is_group_message);
+    }
+
+    #[test]
+    fn test_channel_events_and_empty_messages_skipped() {
+        let join = serde_json::json!({
+            "message_id": "C1:1", "channel_id": "C1", "ts": "1.000000",
+            "subtype": "channel_join", "text": "<@U1> has joined the channel",
+        });
+        assert!(message_row("src", &join).is_none());
+
+        let empty = serde_json::json!({
+            "message_id": "C1:2", "channel_id": "C1", "ts": "2.000000", "text": "",
+        });
+        assert!(message_row("src", &empty).is_none());
+
+        let broken = serde_json::json!({ "text": "hello" });
+        assert!(matches!(message_row("src", &broken), Some(Err(()))));
+    }
+}
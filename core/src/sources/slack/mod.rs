@@ -0,0 +1,15 @@
+//! Slack integration
+//!
+//! Syncs messages and channels from a Slack workspace with a user token, so
+//! the conversations a user is part of land in the communication_message
+//! ontology alongside iMessage.
+
+pub mod channels;
+pub mod client;
+pub mod config;
+pub mod messages;
+pub mod registry;
+pub mod types;
+
+pub use channels::SlackChannelsStream;
+pub use messages::SlackMessagesStream;
@@ -0,0 +1,132 @@
+//! Slack source registration for the catalog
+//!
+//! This module provides the unified registration for Slack sources, including
+//! UI metadata, transform logic, and stream creation in a single place.
+
+use crate::registry::{RegisteredSource, RegisteredStream, SourceRegistry};
+use serde_json::json;
+
+use super::config::SlackMessagesConfig;
+
+// Import transforms and stream types for unified registration
+use super::channels::SlackChannelsStream;
+use super::messages::{transform::SlackMessageTransform, SlackMessagesStream};
+use crate::sources::stream_type::StreamType;
+
+/// Slack source registration
+pub struct SlackSource;
+
+impl SourceRegistry for SlackSource {
+    fn descriptor() -> RegisteredSource {
+        // Metadata is now in virtues-registry
+        let descriptor = virtues_registry::sources::get_source("slack")
+            .expect("Slack source not found in virtues-registry");
+
+        RegisteredSource {
+            descriptor,
+            streams: vec![
+                RegisteredStream::new("messages")
+                    .config_schema(messages_config_schema())
+                    .config_example(messages_config_example())
+                    .config_type::<SlackMessagesConfig>()
+                    .transform("communication_message", |_ctx| {
+                        Ok(Box::new(SlackMessageTransform))
+                    })
+                    .dedup_key("message_id")
+                    .partition_key("timestamp")
+                    .stream_creator(|ctx| {
+                        Ok(StreamType::Pull(Box::new(SlackMessagesStream::new(
+                            ctx.source_id.clone(),
+                            ctx.db.clone(),
+                            ctx.stream_writer.clone(),
+                            ctx.auth.clone(),
+                        ))))
+                    })
+                    .build(),
+                // Channels are re-listed in full each run, so later snapshots
+                // of a channel are updates rather than duplicates
+                RegisteredStream::new("channels")
+                    .partition_key("created_at")
+                    .replace_on_full_refresh()
+                    .stream_creator(|ctx| {
+                        Ok(StreamType::Pull(Box::new(SlackChannelsStream::new(
+                            ctx.source_id.clone(),
+                            ctx.db.clone(),
+                            ctx.stream_writer.clone(),
+                            ctx.auth.clone(),
+                        ))))
+                    })
+                    .build(),
+            ],
+        }
+    }
+}
+
+/// JSON schema for Slack messages configuration
+fn messages_config_schema() -> serde_json::Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "days_back": {
+                "type": "integer",
+                "default": 30,
+                "minimum": 1,
+                "description": "Days of history to fetch on the first sync or a full refresh"
+            },
+            "channel_ids": {
+                "type": "array",
+                "items": { "type": "string" },
+                "default": [],
+                "description": "Only sync these conversation IDs (empty syncs every conversation you are in)"
+            }
+        }
+    })
+}
+
+/// Example configuration for Slack messages
+fn messages_config_example() -> serde_json::Value {
+    json!({
+        "days_back": 30,
+        "channel_ids": []
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::AuthType;
+
+    #[test]
+    fn test_slack_descriptor() {
+        let desc = SlackSource::descriptor();
+        assert_eq!(desc.descriptor.name, "slack");
+        assert_eq!(desc.descriptor.auth_type, AuthType::OAuth2);
+        assert!(desc.descriptor.oauth_config.is_some());
+        assert_eq!(desc.streams.len(), 2);
+    }
+
+    #[test]
+    fn test_messages_stream() {
+        let desc = SlackSource::descriptor();
+        let messages = desc
+            .streams
+            .iter()
+            .find(|s| s.descriptor.name == "messages")
+            .unwrap();
+        assert_eq!(messages.descriptor.table_name, "stream_slack_messages");
+        assert_eq!(messages.dedup_key, Some("message_id"));
+        assert!(messages.descriptor.supports_incremental);
+    }
+
+    #[test]
+    fn test_channels_stream_is_snapshot() {
+        let desc = SlackSource::descriptor();
+        let channels = desc
+            .streams
+            .iter()
+            .find(|s| s.descriptor.name == "channels")
+            .unwrap();
+        assert!(!channels.descriptor.supports_incremental);
+        assert!(channels.dedup_key.is_none());
+    }
+}
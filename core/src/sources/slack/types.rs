@@ -0,0 +1,227 @@
+//! Slack Web API types
+//!
+//! Deserialization types for the conversations and users methods.
+//! Based on https://api.slack.com/methods
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Cursor block returned by paginated methods
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResponseMetadata {
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+/// The cursor for the next page, if there is one
+///
+/// Slack signals the last page with an empty `next_cursor` rather than
+/// omitting it.
+pub fn next_cursor(metadata: &Option<ResponseMetadata>) -> Option<String> {
+    metadata
+        .as_ref()
+        .and_then(|m| m.next_cursor.clone())
+        .filter(|c| !c.is_empty())
+}
+
+/// Response from conversations.list
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConversationsListResponse {
+    #[serde(default)]
+    pub channels: Vec<Conversation>,
+    pub response_metadata: Option<ResponseMetadata>,
+}
+
+/// A channel, private channel, DM or group DM
+///
+/// See: https://api.slack.com/types/conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Conversation {
+    pub id: String,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub is_im: bool,
+    #[serde(default)]
+    pub is_mpim: bool,
+    #[serde(default)]
+    pub is_private: bool,
+    #[serde(default)]
+    pub is_archived: bool,
+    /// Absent for DMs, which the user is always part of
+    pub is_member: Option<bool>,
+    /// The other participant of a DM
+    pub user: Option<String>,
+    /// Unix seconds
+    pub created: Option<i64>,
+    pub topic: Option<TopicInfo>,
+    pub purpose: Option<TopicInfo>,
+    pub num_members: Option<i64>,
+}
+
+impl Conversation {
+    /// Conversation type as named by the `types` filter of conversations.list
+    pub fn kind(&self) -> &'static str {
+        if self.is_im {
+            "im"
+        } else if self.is_mpim {
+            "mpim"
+        } else if self.is_private {
+            "private_channel"
+        } else {
+            "public_channel"
+        }
+    }
+
+    /// Whether the user can read this conversation's history
+    pub fn is_readable(&self) -> bool {
+        self.is_member.unwrap_or(true)
+    }
+
+    /// When the conversation was created
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created
+            .and_then(|secs| DateTime::<Utc>::from_timestamp(secs, 0))
+    }
+}
+
+/// Topic or purpose of a channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicInfo {
+    #[serde(default)]
+    pub value: String,
+}
+
+/// Response from conversations.history
+#[derive(Debug, Clone, Deserialize)]
+pub struct HistoryResponse {
+    #[serde(default)]
+    pub messages: Vec<Message>,
+    pub response_metadata: Option<ResponseMetadata>,
+}
+
+/// A message in a conversation
+///
+/// See: https://api.slack.com/events/message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Message {
+    /// Message timestamp, unique within the conversation (`1512085950.000216`)
+    pub ts: String,
+    pub subtype: Option<String>,
+    pub user: Option<String>,
+    pub bot_id: Option<String>,
+    /// Display name on bot and integration messages
+    pub username: Option<String>,
+    pub text: Option<String>,
+    /// Set on thread parents and replies; equal to `ts` on the parent
+    pub thread_ts: Option<String>,
+    pub reply_count: Option<i64>,
+    #[serde(default)]
+    pub files: Vec<serde_json::Value>,
+    pub edited: Option<serde_json::Value>,
+}
+
+/// Response from users.list
+#[derive(Debug, Clone, Deserialize)]
+pub struct UsersListResponse {
+    #[serde(default)]
+    pub members: Vec<User>,
+    pub response_metadata: Option<ResponseMetadata>,
+}
+
+/// A workspace member
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    pub id: String,
+    pub name: Option<String>,
+    pub real_name: Option<String>,
+    pub profile: Option<UserProfile>,
+}
+
+impl User {
+    /// The name Slack shows for this user: display name, then real name, then handle
+    pub fn display_name(&self) -> Option<String> {
+        let profile = self.profile.as_ref();
+        [
+            profile.and_then(|p| p.display_name.clone()),
+            profile.and_then(|p| p.real_name.clone()),
+            self.real_name.clone(),
+            self.name.clone(),
+        ]
+        .into_iter()
+        .flatten()
+        .find(|name| !name.is_empty())
+    }
+}
+
+/// Profile fields of a workspace member
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserProfile {
+    pub display_name: Option<String>,
+    pub real_name: Option<String>,
+}
+
+/// Parse a Slack message timestamp (`secs.micros`) into a UTC time
+pub fn parse_ts(ts: &str) -> Option<DateTime<Utc>> {
+    let (secs, micros) = ts.split_once('.').unwrap_or((ts, "0"));
+    let secs: i64 = secs.parse().ok()?;
+    let micros: u32 = format!("{micros:0<6}").get(..6)?.parse().ok()?;
+    DateTime::<Utc>::from_timestamp(secs, micros * 1000)
+}
+
+/// Format a UTC time as a Slack timestamp for `oldest` / `latest` bounds
+pub fn format_ts(at: DateTime<Utc>) -> String {
+    format!("{}.{:06}", at.timestamp(), at.timestamp_subsec_micros())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ts() {
+        let at = parse_ts("1512085950.000216").unwrap();
+        assert_eq!(at.timestamp(), 1512085950);
+        assert_eq!(at.timestamp_subsec_micros(), 216);
+        assert_eq!(format_ts(at), "1512085950.000216");
+        assert!(parse_ts("not-a-ts").is_none());
+    }
+
+    #[test]
+    fn test_deserialize_conversation() {
+        let json = serde_json::json!({
+            "id": "D024BE91L",
+            "is_im": true,
+            "user": "U024BE7LH",
+            "created": 1360782804
+        });
+
+        let conversation: Conversation = serde_json::from_value(json).unwrap();
+        assert_eq!(conversation.kind(), "im");
+        assert!(conversation.is_readable());
+        assert!(conversation.created_at().is_some());
+    }
+
+    #[test]
+    fn test_next_cursor_treats_empty_as_last_page() {
+        let last = Some(ResponseMetadata {
+            next_cursor: Some(String::new()),
+        });
+        assert_eq!(next_cursor(&last), None);
+
+        let more = Some(ResponseMetadata {
+            next_cursor: Some("dGVhbTpDMDYxRkE1UEI=".to_string()),
+        });
+        assert_eq!(next_cursor(&more).as_deref(), Some("dGVhbTpDMDYxRkE1UEI="));
+    }
+
+    #[test]
+    fn test_user_display_name_fallback() {
+        let user: User = serde_json::from_value(serde_json::json!({
+            "id": "U1",
+            "name": "spengler",
+            "profile": { "display_name": "", "real_name": "Egon Spengler" }
+        }))
+        .unwrap();
+        assert_eq!(user.display_name().as_deref(), Some("Egon Spengler"));
+    }
+}
@@ -1,37 +1,38 @@
-//! Spotify API client - thin wrapper over OAuthHttpClient
+//! Spotify API client - thin wrapper over SourceHttpClient
 //!
-//! This client delegates all OAuth HTTP operations to the base OAuthHttpClient,
+//! This client delegates all HTTP operations to the base SourceHttpClient,
 //! providing Spotify-specific configuration.
 
-use serde::de::DeserializeOwned;
 use std::sync::Arc;
 
-use crate::{
-    error::Result,
-    sources::base::{OAuthHttpClient, RetryConfig, TokenManager},
+use crate::sources::base::{
+    user_agent_for, RetryConfig, SourceClient, SourceHttpClient, TokenManager,
 };
 
 /// Spotify API client with automatic token refresh and retry logic
 pub struct SpotifyClient {
-    http: OAuthHttpClient,
+    http: SourceHttpClient,
 }
 
 impl SpotifyClient {
     /// Create a new Spotify API client
     pub fn new(source_id: String, token_manager: Arc<TokenManager>) -> Self {
         Self {
-            http: OAuthHttpClient::new(source_id, token_manager)
+            http: SourceHttpClient::oauth(source_id, token_manager)
                 .with_base_url("https://api.spotify.com/v1")
+                .with_user_agent(&user_agent_for("spotify"))
                 .with_retry_config(RetryConfig::default()),
         }
     }
+}
+
+impl SourceClient for SpotifyClient {
+    fn http(&self) -> &SourceHttpClient {
+        &self.http
+    }
 
-    /// Make an authenticated GET request with query parameters
-    pub async fn get_with_params<T>(&self, path: &str, params: &[(&str, &str)]) -> Result<T>
-    where
-        T: DeserializeOwned,
-    {
-        self.http.get_with_params(path, params).await
+    fn http_mut(&mut self) -> &mut SourceHttpClient {
+        &mut self.http
     }
 }
 
@@ -14,7 +14,7 @@ use crate::{
     error::Result,
     sources::{
         auth::SourceAuth,
-        base::{SyncMode, SyncResult},
+        base::{SourceClient, SyncMode, SyncResult},
         pull_stream::PullStream,
     },
     storage::stream_writer::StreamWriter,
@@ -44,7 +44,8 @@ impl SpotifyRecentlyPlayedStream {
             .expect("SpotifyRecentlyPlayedStream requires OAuth2 auth")
             .clone();
 
-        let client = SpotifyClient::new(source_id.clone(), token_manager);
+        let client =
+            SpotifyClient::new(source_id.clone(), token_manager).with_network(auth.network());
 
         Self {
             source_id,
@@ -172,7 +173,7 @@ impl SpotifyRecentlyPlayedStream {
         let records = {
             let mut writer = self.stream_writer.lock().await;
             let collected = writer
-                .collect_records(&self.source_id, "recently_played")
+                .collect_records(&self.source_id, "recently_played")?
                 .map(|(records, _, _)| records);
 
             if let Some(ref recs) = collected {
@@ -203,6 +204,7 @@ impl SpotifyRecentlyPlayedStream {
             completed_at,
             records,
             archive_job_id: None,
+            records_deduplicated: 0,
         })
     }
 
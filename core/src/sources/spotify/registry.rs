@@ -21,6 +21,7 @@ impl SourceRegistry for SpotifySource {
                     .config_schema(recently_played_config_schema())
                     .config_example(recently_played_config_example())
                     .transform("activity_listening", |_ctx| Ok(Box::new(SpotifyListeningTransform)))
+                    .partition_key("played_at")
                     .stream_creator(|ctx| {
                         Ok(StreamType::Pull(Box::new(SpotifyRecentlyPlayedStream::new(
                             ctx.source_id.clone(),
@@ -10,16 +10,24 @@ use tokio::sync::Mutex;
 
 use super::client::StravaClient;
 use super::types::SummaryActivity;
+use super::webhook::ATHLETE_ID_METADATA_KEY;
 use crate::{
     error::Result,
+    jobs::{dedup, first_sync},
     sources::{
         auth::SourceAuth,
-        base::{SyncMode, SyncResult},
+        base::{
+            load_resume_cursor, save_resume_cursor, SourceClient, StreamLimits, SyncMode,
+            SyncResult,
+        },
         pull_stream::PullStream,
     },
     storage::stream_writer::StreamWriter,
 };
 
+/// Pages followed per run unless the stream config sets `max_pages`
+const DEFAULT_MAX_PAGES: u32 = 100;
+
 /// Strava activities stream
 ///
 /// Syncs workout activities from Strava API to object storage via StreamWriter.
@@ -44,7 +52,8 @@ impl StravaActivitiesStream {
             .expect("StravaActivitiesStream requires OAuth2 auth")
             .clone();
 
-        let client = StravaClient::new(source_id.clone(), token_manager);
+        let client =
+            StravaClient::new(source_id.clone(), token_manager).with_network(auth.network());
 
         Self {
             source_id,
@@ -72,35 +81,67 @@ impl StravaActivitiesStream {
         let mut earliest_record_at: Option<DateTime<Utc>> = None;
         let mut latest_record_at: Option<DateTime<Utc>> = None;
 
-        // Determine the `after` epoch for incremental sync
-        let after_epoch: Option<i64> = match sync_mode {
+        // The stored cursor (epoch of the latest start_date) for incremental sync
+        let cursor_epoch: Option<i64> = match sync_mode {
             SyncMode::Incremental { cursor } => {
                 // Use provided cursor, or fall back to last sync token from DB
                 let token = cursor.clone().or(self.get_last_sync_token().await?);
                 token.and_then(|t| t.parse::<i64>().ok())
             }
-            SyncMode::FullRefresh => None,
-            SyncMode::Backfill { start_date, .. } => {
-                Some(start_date.timestamp())
-            }
+            _ => None,
+        };
+
+        // A resume cursor holds the start_date epoch of the last activity a
+        // capped run archived
+        let limits = StreamLimits::load(&self.db, &self.source_id, "activities").await?;
+        let resume_epoch = load_resume_cursor(&self.db, &self.source_id, "activities")
+            .await?
+            .and_then(|cursor| cursor.parse::<i64>().ok());
+        let overlap = limits.cursor_overlap().num_seconds();
+
+        // Determine the `after` epoch. Strava only lists oldest-first when
+        // `after` is given, so a sync of all history asks for everything
+        // after 0. Incremental runs re-read a window before the cursor so
+        // activities stamped slightly behind it aren't skipped, and a capped
+        // run resumes the same way from the last activity it archived. A
+        // first sync only reaches back over the first-sync window.
+        let after_epoch: i64 = match sync_mode {
+            SyncMode::Backfill { start_date, .. } => start_date.timestamp(),
+            _ => match cursor_epoch {
+                Some(epoch) => epoch - overlap,
+                None => first_sync::bound(None).map_or(0, |start| start.timestamp()),
+            },
+        };
+        let after_epoch =
+            resume_epoch.map_or(after_epoch, |epoch| after_epoch.max(epoch - overlap));
+        let before_epoch: Option<i64> = match sync_mode {
+            SyncMode::Backfill { end_date, .. } => Some(end_date.timestamp()),
+            _ => None,
         };
 
+        let page_size = limits.page_size_or(200);
+        let max_pages = limits.max_pages_or(DEFAULT_MAX_PAGES);
+        let per_page = page_size.to_string();
+        let after = after_epoch.to_string();
+        let mut capped = false;
+
         // Paginate through all activities
         let mut page = 1;
-        let per_page = "200";
         let mut latest_start_date: Option<String> = None;
+        let mut athlete_id: Option<i64> = None;
 
         loop {
             let page_str = page.to_string();
             let mut params: Vec<(&str, &str)> = vec![
-                ("per_page", per_page),
+                ("per_page", per_page.as_str()),
                 ("page", &page_str),
+                ("after", &after),
             ];
 
-            let after_str;
-            if let Some(epoch) = after_epoch {
-                after_str = epoch.to_string();
-                params.push(("after", &after_str));
+            let before_str;
+            if let Some(epoch) = before_epoch {
+                before_str = epoch.to_string();
+                params.push(("before", &before_str));
             }
 
             let activities: Vec<SummaryActivity> = self
@@ -122,6 +163,10 @@ impl StravaActivitiesStream {
             );
 
             for activity in &activities {
+                if let Some(athlete) = &activity.athlete {
+                    athlete_id = Some(athlete.id);
+                }
+
                 // Track watermarks from start_date
                 if let Ok(ts) = activity.start_date.parse::<DateTime<Utc>>() {
                     earliest_record_at = Some(match earliest_record_at {
@@ -187,28 +232,58 @@ impl StravaActivitiesStream {
             page += 1;
 
             // Safety limit to prevent infinite pagination
-            if page > 100 {
-                tracing::warn!("Reached pagination safety limit (100 pages)");
+            if page > max_pages {
+                tracing::warn!(
+                    max_pages,
+                    max_records = limits.max_records_or(DEFAULT_MAX_PAGES, page_size),
+                    "Reached pagination limit, later activities fetched next run"
+                );
+                capped = true;
+                break;
+            }
+
+            if limits.is_reached(records_fetched) {
+                capped = true;
                 break;
             }
         }
 
-        // Save checkpoint: the epoch timestamp of the latest start_date
-        if let Some(ref latest) = latest_start_date {
+        // The listing is oldest-first, so the next run continues after the
+        // latest activity seen
+        let resume_cursor = latest_record_at
+            .filter(|_| capped)
+            .map(|ts| ts.timestamp().to_string());
+        save_resume_cursor(
+            &self.db,
+            &self.source_id,
+            "activities",
+            resume_cursor.as_deref(),
+        )
+        .await?;
+
+        // Save checkpoint: the epoch timestamp of the latest start_date, once
+        // the capped listing (if any) has been fully consumed. Activities from
+        // the overlap window never move it backwards.
+        if let Some(ref latest) = latest_start_date.filter(|_| !capped) {
             if let Ok(ts) = latest.parse::<DateTime<Utc>>() {
-                let epoch_str = ts.timestamp().to_string();
+                let epoch = cursor_epoch.map_or(ts.timestamp(), |c| c.max(ts.timestamp()));
+                let epoch_str = epoch.to_string();
                 self.save_sync_token(&epoch_str).await?;
                 next_cursor = Some(epoch_str);
             }
         }
 
+        if let Some(athlete_id) = athlete_id {
+            self.save_athlete_id(athlete_id).await?;
+        }
+
         let completed_at = Utc::now();
 
         // Collect records from StreamWriter for archive and transform pipeline
         let records = {
             let mut writer = self.stream_writer.lock().await;
             let collected = writer
-                .collect_records(&self.source_id, "activities")
+                .collect_records(&self.source_id, "activities")?
                 .map(|(records, _, _)| records);
 
             if let Some(ref recs) = collected {
@@ -223,10 +298,18 @@ impl StravaActivitiesStream {
             collected
         };
 
+        // Drop activities the overlap window returned again
+        let (records, records_deduplicated) = if cursor_epoch.is_some() || resume_epoch.is_some() {
+            dedup::skip_refetched(&self.db, &self.source_id, "activities", records).await?
+        } else {
+            (records, 0)
+        };
+
         tracing::info!(
             records_fetched,
             records_written,
             records_failed,
+            records_deduplicated,
             "Strava activities sync completed"
         );
 
@@ -241,6 +324,7 @@ impl StravaActivitiesStream {
             completed_at,
             records,
             archive_job_id: None,
+            records_deduplicated,
         })
     }
 
@@ -256,6 +340,24 @@ impl StravaActivitiesStream {
         Ok(row.and_then(|(token,)| token))
     }
 
+    /// Record the connected athlete on the source, so webhook events for this
+    /// athlete can be routed to it
+    async fn save_athlete_id(&self, athlete_id: i64) -> Result<()> {
+        let path = format!("$.{ATHLETE_ID_METADATA_KEY}");
+        sqlx::query(
+            "UPDATE elt_source_connections
+             SET metadata = json_set(COALESCE(metadata, '{}'), $1, $2)
+             WHERE id = $3 AND json_extract(metadata, $1) IS NOT $2",
+        )
+        .bind(&path)
+        .bind(athlete_id.to_string())
+        .bind(&self.source_id)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
     /// Save the sync token to the database
     async fn save_sync_token(&self, token: &str) -> Result<()> {
         sqlx::query(
@@ -307,6 +409,12 @@ impl PullStream for StravaActivitiesStream {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::sources::base::mock_transport::testing::{
+        archive_to_memory, fixtures, migrated_pool,
+    };
+    use crate::sources::base::TokenManager;
+
     #[test]
     fn test_strava_activity_pagination_params() {
         // Verify parameter construction for the Strava API
@@ -325,4 +433,116 @@ mod tests {
         assert_eq!(params[1], ("page", "1"));
         assert_eq!(params[2], ("after", "1700000000"));
     }
+
+    #[tokio::test]
+    async fn test_full_sync_against_fixtures() {
+        let db = migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, name) VALUES ('src-strava', 'strava', 'Strava')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        let token_manager = Arc::new(TokenManager::new_insecure(db.clone()));
+        let mut stream = StravaActivitiesStream::new(
+            "src-strava".to_string(),
+            db.clone(),
+            Arc::new(Mutex::new(StreamWriter::new())),
+            SourceAuth::oauth2("src-strava".to_string(), token_manager),
+        );
+        let transport = fixtures("strava");
+        stream
+            .client
+            .http_mut()
+            .set_mock_transport(transport.clone());
+
+        let result = stream.sync_internal(&SyncMode::FullRefresh).await.unwrap();
+        assert_eq!(result.records_written, 2);
+        // Epoch of the latest start_date, 2025-01-15T08:15:00Z
+        assert_eq!(result.next_cursor.as_deref(), Some("1736928900"));
+
+        let records = result.records.unwrap();
+        let written =
+            archive_to_memory("strava", "src-strava", "activities", &records, "start_date").await;
+        let mut ids: Vec<i64> = written
+            .iter()
+            .map(|r| r["activity_id"].as_i64().unwrap())
+            .collect();
+        ids.sort();
+        assert_eq!(ids, [1201, 1202]);
+
+        // Paged until the empty page
+        let pages: Vec<String> = transport
+            .requests()
+            .iter()
+            .map(|r| r.param("page").unwrap().to_string())
+            .collect();
+        assert_eq!(pages, ["1", "2"]);
+
+        // The athlete is recorded for webhook routing
+        let athlete_id: Option<String> = sqlx::query_scalar(
+            "SELECT json_extract(metadata, '$.athlete_id') FROM elt_source_connections WHERE id = 'src-strava'",
+        )
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert_eq!(athlete_id.as_deref(), Some("134815"));
+    }
+
+    #[tokio::test]
+    async fn test_max_pages_bounds_pagination() {
+        let db = migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO elt_source_connections (id, source, name) VALUES ('src-strava', 'strava', 'Strava')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO elt_stream_connections (id, source_connection_id, stream_name, table_name, config)
+             VALUES ('st-strava', 'src-strava', 'activities', 'stream_strava_activities', '{\"max_pages\": 1}')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let token_manager = Arc::new(TokenManager::new_insecure(db.clone()));
+        let mut stream = StravaActivitiesStream::new(
+            "src-strava".to_string(),
+            db.clone(),
+            Arc::new(Mutex::new(StreamWriter::new())),
+            SourceAuth::oauth2("src-strava".to_string(), token_manager),
+        );
+        let transport = fixtures("strava");
+        stream
+            .client
+            .http_mut()
+            .set_mock_transport(transport.clone());
+
+        let result = stream.sync_internal(&SyncMode::FullRefresh).await.unwrap();
+        assert_eq!(transport.requests().len(), 1);
+        assert_eq!(transport.requests()[0].param("after"), Some("0"));
+
+        // The page limit leaves the listing to resume after the latest
+        // activity seen, without moving the incremental cursor
+        assert_eq!(result.next_cursor, None);
+        assert_eq!(
+            load_resume_cursor(&db, "src-strava", "activities")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("1736928900")
+        );
+
+        let transport = fixtures("strava");
+        stream
+            .client
+            .http_mut()
+            .set_mock_transport(transport.clone());
+        stream.sync_internal(&SyncMode::FullRefresh).await.unwrap();
+        let overlap = StreamLimits::default().cursor_overlap().num_seconds();
+        let after = (1736928900 - overlap).to_string();
+        assert_eq!(transport.requests()[0].param("page"), Some("1"));
+        assert_eq!(transport.requests()[0].param("after"), Some(after.as_str()));
+    }
 }
@@ -1,48 +1,41 @@
-//! Strava API client - thin wrapper over OAuthHttpClient
+//! Strava API client - thin wrapper over SourceHttpClient
 //!
-//! This client delegates all OAuth HTTP operations to the base OAuthHttpClient,
+//! This client delegates all HTTP operations to the base SourceHttpClient,
 //! providing Strava-specific configuration.
 
-use serde::de::DeserializeOwned;
 use std::sync::Arc;
 
-use crate::{
-    error::Result,
-    sources::base::{OAuthHttpClient, RetryConfig, TokenManager},
+use crate::sources::base::{
+    user_agent_for, RetryConfig, SourceClient, SourceHttpClient, TokenManager,
 };
 
 /// Strava API client with automatic token refresh and retry logic
 ///
-/// This is a thin wrapper that configures OAuthHttpClient for Strava APIs.
+/// This is a thin wrapper that configures SourceHttpClient for Strava APIs.
 /// All HTTP logic (retry, token refresh, error handling) is delegated to the base client.
 pub struct StravaClient {
-    http: OAuthHttpClient,
+    http: SourceHttpClient,
 }
 
 impl StravaClient {
     /// Create a new Strava API client
     pub fn new(source_id: String, token_manager: Arc<TokenManager>) -> Self {
         Self {
-            http: OAuthHttpClient::new(source_id, token_manager)
+            http: SourceHttpClient::oauth(source_id, token_manager)
                 .with_base_url("https://www.strava.com/api/v3")
+                .with_user_agent(&user_agent_for("strava"))
                 .with_retry_config(RetryConfig::default()),
         }
     }
+}
 
-    /// Make an authenticated GET request
-    pub async fn get<T>(&self, path: &str) -> Result<T>
-    where
-        T: DeserializeOwned,
-    {
-        self.http.get(path).await
+impl SourceClient for StravaClient {
+    fn http(&self) -> &SourceHttpClient {
+        &self.http
     }
 
-    /// Make an authenticated GET request with query parameters
-    pub async fn get_with_params<T>(&self, path: &str, params: &[(&str, &str)]) -> Result<T>
-    where
-        T: DeserializeOwned,
-    {
-        self.http.get_with_params(path, params).await
+    fn http_mut(&mut self) -> &mut SourceHttpClient {
+        &mut self.http
     }
 }
 
@@ -6,5 +6,7 @@ pub mod activities;
 pub mod client;
 pub mod registry;
 pub mod types;
+pub mod webhook;
 
 pub use activities::StravaActivitiesStream;
+pub use webhook::StravaWebhook;
@@ -28,6 +28,8 @@ impl SourceRegistry for StravaSource {
                     .config_schema(activities_config_schema())
                     .config_example(activities_config_example())
                     .transform("health_workout", |_ctx| Ok(Box::new(StravaWorkoutTransform)))
+                    .dedup_key("activity_id")
+                    .partition_key("start_date")
                     .stream_creator(|ctx| {
                         Ok(StreamType::Pull(Box::new(StravaActivitiesStream::new(
                             ctx.source_id.clone(),
@@ -54,6 +56,12 @@ fn activities_config_schema() -> serde_json::Value {
                 "minimum": 1,
                 "maximum": 200,
                 "description": "Number of activities per API page (max 200)"
+            },
+            "max_pages": {
+                "type": "integer",
+                "default": 100,
+                "minimum": 1,
+                "description": "Maximum number of pages to fetch per sync"
             }
         }
     })
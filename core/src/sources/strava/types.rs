@@ -24,6 +24,13 @@ pub struct SummaryActivity {
     pub map: Option<ActivityMap>,
     #[serde(rename = "type")]
     pub activity_type: String,
+    pub athlete: Option<MetaAthlete>,
+}
+
+/// Reference to the athlete who owns an activity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetaAthlete {
+    pub id: i64,
 }
 
 /// Map/route data for an activity
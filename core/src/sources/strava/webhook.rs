@@ -0,0 +1,203 @@
+//! Strava push subscription
+//!
+//! Strava validates a subscription with a `hub.challenge` GET and then POSTs
+//! unsigned event JSON. Deliveries are authenticated by their
+//! `subscription_id`, which we compare against the one Strava returned when
+//! the subscription was created. Events name the athlete (`owner_id`) they
+//! belong to; the activities stream records each source's athlete id in its
+//! metadata, so an event only syncs that athlete's source.
+//!
+//! See: https://developers.strava.com/docs/webhooks/
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::error::{Error, Result};
+use crate::sources::base::webhook::{
+    constant_time_eq, echo_challenge, WebhookAccount, WebhookAction, WebhookRequest, WebhookSource,
+};
+
+/// Source metadata field holding the connected athlete's id
+pub const ATHLETE_ID_METADATA_KEY: &str = "athlete_id";
+
+/// Strava webhook event payload
+#[derive(Debug, Deserialize)]
+pub struct StravaWebhookEvent {
+    pub object_type: String,
+    pub object_id: i64,
+    pub aspect_type: String,
+    pub owner_id: i64,
+    pub subscription_id: i64,
+}
+
+/// Strava [`WebhookSource`] configured from the environment
+///
+/// - `STRAVA_WEBHOOK_VERIFY_TOKEN`: token sent when creating the subscription
+/// - `STRAVA_WEBHOOK_SUBSCRIPTION_ID`: the subscription's id; events are
+///   rejected until it is set, and events for any other subscription always
+pub struct StravaWebhook {
+    verify_token: Option<String>,
+    subscription_id: Option<String>,
+}
+
+impl StravaWebhook {
+    pub fn new(verify_token: Option<String>, subscription_id: Option<String>) -> Self {
+        Self {
+            verify_token,
+            subscription_id,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(
+            std::env::var("STRAVA_WEBHOOK_VERIFY_TOKEN").ok(),
+            std::env::var("STRAVA_WEBHOOK_SUBSCRIPTION_ID").ok(),
+        )
+    }
+
+    fn parse(body: &[u8]) -> Result<StravaWebhookEvent> {
+        serde_json::from_slice(body)
+            .map_err(|e| Error::InvalidInput(format!("Invalid Strava webhook payload: {}", e)))
+    }
+}
+
+#[async_trait]
+impl WebhookSource for StravaWebhook {
+    fn provider(&self) -> &'static str {
+        "strava"
+    }
+
+    fn challenge(&self, query: &HashMap<String, String>) -> Result<serde_json::Value> {
+        let verify_token = self.verify_token.as_deref().ok_or_else(|| {
+            Error::Configuration("STRAVA_WEBHOOK_VERIFY_TOKEN is not set".to_string())
+        })?;
+        let challenge = echo_challenge(query, verify_token)?;
+        Ok(serde_json::json!({ "hub.challenge": challenge }))
+    }
+
+    async fn verify(&self, request: &WebhookRequest<'_>) -> Result<()> {
+        let expected = self.subscription_id.as_deref().ok_or_else(|| {
+            Error::Configuration("STRAVA_WEBHOOK_SUBSCRIPTION_ID is not set".to_string())
+        })?;
+        let event = Self::parse(request.body)?;
+
+        let provided = event.subscription_id.to_string();
+        if constant_time_eq(provided.as_bytes(), expected.trim().as_bytes()) {
+            Ok(())
+        } else {
+            Err(Error::Unauthorized(
+                "Unknown Strava webhook subscription".to_string(),
+            ))
+        }
+    }
+
+    fn action(&self, body: &[u8]) -> Result<WebhookAction> {
+        let event = Self::parse(body)?;
+
+        // Athlete events are deauthorizations; those surface as token errors on
+        // the next sync rather than being handled here
+        if event.object_type == "activity" {
+            tracing::debug!(
+                activity_id = event.object_id,
+                owner_id = event.owner_id,
+                aspect = %event.aspect_type,
+                "Strava activity event"
+            );
+            Ok(WebhookAction::SyncStreams {
+                streams: vec!["activities"],
+                account: WebhookAccount {
+                    metadata_key: ATHLETE_ID_METADATA_KEY,
+                    id: event.owner_id.to_string(),
+                },
+            })
+        } else {
+            Ok(WebhookAction::Ignore)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderMap;
+
+    const EVENT: &[u8] = br#"{"aspect_type":"create","event_time":1516126040,"object_id":1360128428,"object_type":"activity","owner_id":134815,"subscription_id":120475,"updates":{}}"#;
+
+    fn request<'a>(
+        headers: &'a HeaderMap,
+        query: &'a HashMap<String, String>,
+        body: &'a [u8],
+    ) -> WebhookRequest<'a> {
+        WebhookRequest {
+            headers,
+            query,
+            body,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_subscription_id() {
+        let headers = HeaderMap::new();
+        let query = HashMap::new();
+
+        let webhook = StravaWebhook::new(None, Some("120475".to_string()));
+        assert!(webhook
+            .verify(&request(&headers, &query, EVENT))
+            .await
+            .is_ok());
+
+        let webhook = StravaWebhook::new(None, Some("999".to_string()));
+        assert!(matches!(
+            webhook.verify(&request(&headers, &query, EVENT)).await,
+            Err(Error::Unauthorized(_))
+        ));
+
+        // Without a configured subscription nothing is accepted
+        let webhook = StravaWebhook::new(None, None);
+        assert!(matches!(
+            webhook.verify(&request(&headers, &query, EVENT)).await,
+            Err(Error::Configuration(_))
+        ));
+    }
+
+    #[test]
+    fn test_activity_events_sync_activities() {
+        let webhook = StravaWebhook::new(None, None);
+        assert_eq!(
+            webhook.action(EVENT).unwrap(),
+            WebhookAction::SyncStreams {
+                streams: vec!["activities"],
+                account: WebhookAccount {
+                    metadata_key: ATHLETE_ID_METADATA_KEY,
+                    id: "134815".to_string(),
+                },
+            }
+        );
+
+        let deauth = br#"{"aspect_type":"update","event_time":1516126040,"object_id":134815,"object_type":"athlete","owner_id":134815,"subscription_id":120475,"updates":{"authorized":"false"}}"#;
+        assert_eq!(webhook.action(deauth).unwrap(), WebhookAction::Ignore);
+    }
+
+    #[test]
+    fn test_challenge_requires_configured_token() {
+        let query: HashMap<String, String> = [
+            ("hub.mode", "subscribe"),
+            ("hub.verify_token", "STRAVA"),
+            ("hub.challenge", "15f7d1a91c1f40f8a748fd134752feb3"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let webhook = StravaWebhook::new(Some("STRAVA".to_string()), None);
+        assert_eq!(
+            webhook.challenge(&query).unwrap(),
+            serde_json::json!({ "hub.challenge": "15f7d1a91c1f40f8a748fd134752feb3" })
+        );
+
+        let unconfigured = StravaWebhook::new(None, None);
+        assert!(unconfigured.challenge(&query).is_err());
+    }
+}
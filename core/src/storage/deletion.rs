@@ -0,0 +1,259 @@
+//! Plan/confirm split for destructive storage operations
+//!
+//! Bulk deletes are never issued directly. The caller first builds a
+//! `DeletionPlan` with `Storage::plan_delete_prefix`, which lists the exact
+//! keys and total bytes that would be removed without touching anything, and
+//! can be shown as a dry run. Only `DeletionPlan::confirm` produces the
+//! `ConfirmedDeletion` that `Storage::execute_deletion` accepts, and execution
+//! deletes exactly the planned keys (nothing written after planning), in
+//! batches, logging each batch. Keys the caller already tracks individually
+//! (never a prefix) can be removed with `Storage::delete_batch`.
+
+use serde::Serialize;
+
+use super::{ObjectInfo, Storage};
+use crate::error::Result;
+
+/// Objects deleted per logged batch
+pub const DELETE_BATCH_SIZE: usize = 100;
+
+/// The objects a deletion would remove, computed without deleting anything
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeletionPlan {
+    pub prefix: String,
+    pub objects: Vec<ObjectInfo>,
+    pub total_bytes: u64,
+}
+
+impl DeletionPlan {
+    fn new(prefix: &str, objects: Vec<ObjectInfo>) -> Self {
+        let total_bytes = objects.iter().map(|o| o.size_bytes).sum();
+        Self {
+            prefix: prefix.to_string(),
+            objects,
+            total_bytes,
+        }
+    }
+
+    pub fn object_count(&self) -> u64 {
+        self.objects.len() as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Explicitly approve this plan for execution
+    pub fn confirm(self) -> ConfirmedDeletion {
+        ConfirmedDeletion { plan: self }
+    }
+}
+
+/// A deletion plan the caller has explicitly confirmed
+///
+/// Only obtainable through `DeletionPlan::confirm`.
+#[derive(Debug)]
+pub struct ConfirmedDeletion {
+    plan: DeletionPlan,
+}
+
+impl ConfirmedDeletion {
+    pub fn plan(&self) -> &DeletionPlan {
+        &self.plan
+    }
+}
+
+/// What an executed deletion removed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DeletionReport {
+    pub objects_deleted: u64,
+    pub bytes_deleted: u64,
+}
+
+impl DeletionReport {
+    /// Add another report's totals to this one
+    pub fn merge(&mut self, other: &DeletionReport) {
+        self.objects_deleted += other.objects_deleted;
+        self.bytes_deleted += other.bytes_deleted;
+    }
+}
+
+impl Storage {
+    /// Plan the deletion of every object under a prefix (deletes nothing)
+    pub async fn plan_delete_prefix(&self, prefix: &str) -> Result<DeletionPlan> {
+        let objects = self.backend.list_objects(prefix).await?;
+        Ok(DeletionPlan::new(prefix, objects))
+    }
+
+    /// Delete exactly the objects of a confirmed plan
+    pub async fn execute_deletion(&self, deletion: ConfirmedDeletion) -> Result<DeletionReport> {
+        let plan = deletion.plan;
+        let batch_count = plan.objects.len().div_ceil(DELETE_BATCH_SIZE);
+        let mut report = DeletionReport::default();
+
+        for (index, batch) in plan.objects.chunks(DELETE_BATCH_SIZE).enumerate() {
+            let mut bytes = 0;
+            for object in batch {
+                self.backend.delete(&object.key).await?;
+                bytes += object.size_bytes;
+            }
+            report.objects_deleted += batch.len() as u64;
+            report.bytes_deleted += bytes;
+
+            tracing::info!(
+                prefix = %plan.prefix,
+                batch = index + 1,
+                batches = batch_count,
+                objects = batch.len(),
+                bytes,
+                first_key = %batch[0].key,
+                last_key = %batch[batch.len() - 1].key,
+                "Deleted storage batch"
+            );
+        }
+
+        Ok(report)
+    }
+
+    /// Delete exactly the given objects, in batches of `DELETE_BATCH_SIZE`
+    ///
+    /// For cleaning up keys the caller already tracks, such as the archives a
+    /// full refresh replaced; a whole prefix goes through
+    /// `plan_delete_prefix` instead. Every key is attempted, and the first
+    /// failure is returned once the rest have been tried.
+    pub async fn delete_batch(&self, keys: &[String]) -> Result<u64> {
+        let mut deleted = 0;
+        let mut first_error = None;
+
+        for batch in keys.chunks(DELETE_BATCH_SIZE) {
+            for key in batch {
+                match self.backend.delete(key).await {
+                    Ok(()) => deleted += 1,
+                    Err(e) => {
+                        tracing::warn!(key = %key, error = %e, "Failed to delete object");
+                        first_error.get_or_insert(e);
+                    }
+                }
+            }
+            tracing::debug!(objects = batch.len(), "Deleted storage batch");
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(deleted),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    async fn assert_plan_then_execute(storage: Storage) {
+        storage.initialize().await.unwrap();
+        for (key, len) in [
+            (
+                "streams/google/source_a/gmail/date=2025-01-15/records_1.jsonl",
+                10,
+            ),
+            (
+                "streams/google/source_a/calendar/date=2025-01-16/records_2.jsonl",
+                5,
+            ),
+            (
+                "streams/google/source_b/gmail/date=2025-01-15/records_3.jsonl",
+                7,
+            ),
+        ] {
+            storage.upload(key, vec![b'x'; len]).await.unwrap();
+        }
+
+        let plan = storage
+            .plan_delete_prefix("streams/google/source_a/")
+            .await
+            .unwrap();
+        assert_eq!(plan.object_count(), 2);
+        assert_eq!(plan.total_bytes, 15);
+        let mut keys: Vec<&str> = plan.objects.iter().map(|o| o.key.as_str()).collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                "streams/google/source_a/calendar/date=2025-01-16/records_2.jsonl",
+                "streams/google/source_a/gmail/date=2025-01-15/records_1.jsonl",
+            ]
+        );
+
+        // Planning deletes nothing
+        assert!(storage
+            .download("streams/google/source_a/gmail/date=2025-01-15/records_1.jsonl")
+            .await
+            .is_ok());
+
+        // Objects written after planning are not part of the deletion
+        storage
+            .upload(
+                "streams/google/source_a/gmail/date=2025-01-17/records_4.jsonl",
+                b"{}".to_vec(),
+            )
+            .await
+            .unwrap();
+
+        let report = storage.execute_deletion(plan.confirm()).await.unwrap();
+        assert_eq!(
+            report,
+            DeletionReport {
+                objects_deleted: 2,
+                bytes_deleted: 15,
+            }
+        );
+        assert!(storage
+            .download("streams/google/source_a/gmail/date=2025-01-15/records_1.jsonl")
+            .await
+            .is_err());
+        assert!(storage
+            .download("streams/google/source_a/gmail/date=2025-01-17/records_4.jsonl")
+            .await
+            .is_ok());
+        assert!(storage
+            .download("streams/google/source_b/gmail/date=2025-01-15/records_3.jsonl")
+            .await
+            .is_ok());
+
+        assert!(storage
+            .plan_delete_prefix("streams/missing/")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_plan_then_execute_in_memory() {
+        assert_plan_then_execute(Storage::in_memory()).await;
+    }
+
+    #[tokio::test]
+    async fn test_plan_then_execute_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = Storage::file(temp_dir.path().to_str().unwrap().to_string()).unwrap();
+        assert_plan_then_execute(storage).await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_in_batches() {
+        let storage = Storage::in_memory();
+        for i in 0..DELETE_BATCH_SIZE * 2 + 1 {
+            storage
+                .upload(&format!("p/{:04}", i), b"ab".to_vec())
+                .await
+                .unwrap();
+        }
+
+        let plan = storage.plan_delete_prefix("p/").await.unwrap();
+        let report = storage.execute_deletion(plan.confirm()).await.unwrap();
+        assert_eq!(report.objects_deleted, (DELETE_BATCH_SIZE * 2 + 1) as u64);
+        assert_eq!(report.bytes_deleted, report.objects_deleted * 2);
+        assert!(storage.list("p/").await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,281 @@
+//! In-memory storage backend for tests and throwaway local runs
+//!
+//! Objects live in a process-local map and vanish when the `Storage` is
+//! dropped. Listing follows S3 rather than the file backend: prefixes match
+//! any key that starts with them (no directory boundaries), keys come back in
+//! lexicographic order, and `list_with_pagination` pages through them with a
+//! continuation token and the same 1000-key page cap.
+//!
+//! Payloads are stored as opaque bytes, so encrypted objects round-trip
+//! unchanged.
+
+use std::collections::BTreeMap;
+use std::ops::Bound;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+
+use super::{HealthStatus, ListResult, ObjectInfo, StorageBackend};
+use crate::error::{Error, Result};
+
+/// Largest page `list_with_pagination` returns, as with S3's ListObjectsV2
+const MAX_PAGE_KEYS: usize = 1000;
+
+/// In-memory storage backend
+#[derive(Default)]
+pub struct InMemoryStorage {
+    objects: RwLock<BTreeMap<String, Vec<u8>>>,
+}
+
+impl InMemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn read(&self) -> Result<std::sync::RwLockReadGuard<'_, BTreeMap<String, Vec<u8>>>> {
+        self.objects
+            .read()
+            .map_err(|_| Error::Storage("In-memory storage lock poisoned".to_string()))
+    }
+
+    fn write(&self) -> Result<std::sync::RwLockWriteGuard<'_, BTreeMap<String, Vec<u8>>>> {
+        self.objects
+            .write()
+            .map_err(|_| Error::Storage("In-memory storage lock poisoned".to_string()))
+    }
+}
+
+#[async_trait]
+impl StorageBackend for InMemoryStorage {
+    async fn initialize(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn upload(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        self.write()?.insert(key.to_string(), data);
+        Ok(())
+    }
+
+    async fn download(&self, key: &str) -> Result<Vec<u8>> {
+        self.read()?
+            .get(key)
+            .cloned()
+            .ok_or_else(|| Error::Storage(format!("Object not found: {}", key)))
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        // Deleting a missing key succeeds, as with S3 DeleteObject
+        self.write()?.remove(key);
+        Ok(())
+    }
+
+    async fn copy(&self, src_key: &str, dst_key: &str) -> Result<()> {
+        let mut objects = self.write()?;
+        let data = objects
+            .get(src_key)
+            .cloned()
+            .ok_or_else(|| Error::Storage(format!("Object not found: {}", src_key)))?;
+        objects.insert(dst_key.to_string(), data);
+        Ok(())
+    }
+
+    async fn list_objects(&self, prefix: &str) -> Result<Vec<ObjectInfo>> {
+        Ok(self
+            .read()?
+            .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .map(|(key, data)| ObjectInfo {
+                key: key.clone(),
+                size_bytes: data.len() as u64,
+            })
+            .collect())
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        Ok(self
+            .read()?
+            .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+            .map(|(key, _)| key)
+            .take_while(|key| key.starts_with(prefix))
+            .cloned()
+            .collect())
+    }
+
+    async fn list_with_pagination(
+        &self,
+        prefix: &str,
+        max_keys: Option<i32>,
+        continuation_token: Option<String>,
+    ) -> Result<ListResult> {
+        let page_size = max_keys
+            .map_or(MAX_PAGE_KEYS, |max| max.max(0) as usize)
+            .min(MAX_PAGE_KEYS);
+
+        // The token is the last key of the previous page; resume after it
+        let start = match continuation_token.as_deref() {
+            Some(token) if token >= prefix => Bound::Excluded(token),
+            _ => Bound::Included(prefix),
+        };
+
+        let objects = self.read()?;
+        let mut matching = objects
+            .range::<str, _>((start, Bound::Unbounded))
+            .map(|(key, _)| key)
+            .take_while(|key| key.starts_with(prefix));
+
+        let keys: Vec<String> = matching.by_ref().take(page_size).cloned().collect();
+        let is_truncated = matching.next().is_some();
+
+        Ok(ListResult {
+            continuation_token: if is_truncated {
+                keys.last().cloned()
+            } else {
+                None
+            },
+            keys,
+            is_truncated,
+        })
+    }
+
+    async fn health_check(&self) -> Result<HealthStatus> {
+        let count = self.read()?.len();
+        Ok(HealthStatus {
+            is_healthy: true,
+            message: format!("In-memory storage holding {} objects", count),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::base::oauth::encryption::TokenEncryptor;
+    use crate::storage::Storage;
+
+    async fn storage_with(keys: &[&str]) -> Storage {
+        let storage = Storage::in_memory();
+        storage.initialize().await.unwrap();
+        for key in keys {
+            storage.upload(key, b"{}".to_vec()).await.unwrap();
+        }
+        storage
+    }
+
+    #[tokio::test]
+    async fn test_upload_download_delete() {
+        let storage = storage_with(&[]).await;
+
+        storage.upload("a/b.txt", b"data".to_vec()).await.unwrap();
+        assert_eq!(storage.download("a/b.txt").await.unwrap(), b"data");
+
+        storage.delete("a/b.txt").await.unwrap();
+        assert!(matches!(
+            storage.download("a/b.txt").await,
+            Err(Error::Storage(_))
+        ));
+        // Idempotent, like S3
+        storage.delete("a/b.txt").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_payload_round_trip() {
+        let storage = storage_with(&[]).await;
+        let encryptor = TokenEncryptor::new_insecure();
+
+        let ciphertext = encryptor.encrypt("secret record").unwrap();
+        storage
+            .upload("streams/enc.jsonl", ciphertext.clone().into_bytes())
+            .await
+            .unwrap();
+
+        let downloaded =
+            String::from_utf8(storage.download("streams/enc.jsonl").await.unwrap()).unwrap();
+        assert_eq!(downloaded, ciphertext);
+        assert_eq!(encryptor.decrypt(&downloaded).unwrap(), "secret record");
+
+        // Arbitrary binary survives untouched
+        let binary: Vec<u8> = (0..=255).collect();
+        storage.upload("blob", binary.clone()).await.unwrap();
+        assert_eq!(storage.download("blob").await.unwrap(), binary);
+    }
+
+    #[tokio::test]
+    async fn test_list_matches_s3_prefix_semantics() {
+        let storage = storage_with(&[
+            "streams/google/b/gmail/date=2025-01-15/records_1.jsonl",
+            "streams/google/a/gmail/date=2025-01-15/records_2.jsonl",
+            "streams/google/ab/gmail/date=2025-01-15/records_3.jsonl",
+            "streams/strava/a/activities/date=2025-01-15/records_4.jsonl",
+        ])
+        .await;
+
+        // Plain string prefix, recursive, sorted
+        assert_eq!(
+            storage.list("streams/google/a").await.unwrap(),
+            vec![
+                "streams/google/a/gmail/date=2025-01-15/records_2.jsonl",
+                "streams/google/ab/gmail/date=2025-01-15/records_3.jsonl",
+            ]
+        );
+        assert_eq!(storage.list("streams/google/a/").await.unwrap().len(), 1);
+        assert_eq!(storage.list("").await.unwrap().len(), 4);
+        assert!(storage.list("streams/missing").await.unwrap().is_empty());
+
+        let plan = storage.plan_delete_prefix("streams/google/").await.unwrap();
+        assert_eq!(plan.object_count(), 3);
+        storage.execute_deletion(plan.confirm()).await.unwrap();
+        assert_eq!(storage.list("").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_pagination() {
+        let keys: Vec<String> = (0..5).map(|i| format!("p/key_{}", i)).collect();
+        let key_refs: Vec<&str> = keys.iter().map(String::as_str).collect();
+        let storage = storage_with(&key_refs).await;
+        storage.upload("q/other", b"{}".to_vec()).await.unwrap();
+
+        let mut listed = Vec::new();
+        let mut token = None;
+        let mut pages = 0;
+        loop {
+            let page = storage
+                .list_with_pagination("p/", Some(2), token)
+                .await
+                .unwrap();
+            pages += 1;
+            assert!(page.keys.len() <= 2);
+            listed.extend(page.keys);
+            if !page.is_truncated {
+                assert!(page.continuation_token.is_none());
+                break;
+            }
+            token = page.continuation_token;
+        }
+
+        assert_eq!(pages, 3);
+        assert_eq!(listed, keys);
+    }
+
+    #[tokio::test]
+    async fn test_pagination_caps_page_size() {
+        let keys: Vec<String> = (0..MAX_PAGE_KEYS + 5)
+            .map(|i| format!("k/{:05}", i))
+            .collect();
+        let key_refs: Vec<&str> = keys.iter().map(String::as_str).collect();
+        let storage = storage_with(&key_refs).await;
+
+        let page = storage
+            .list_with_pagination("k/", Some(5000), None)
+            .await
+            .unwrap();
+        assert_eq!(page.keys.len(), MAX_PAGE_KEYS);
+        assert!(page.is_truncated);
+
+        let rest = storage
+            .list_with_pagination("k/", None, page.continuation_token)
+            .await
+            .unwrap();
+        assert_eq!(rest.keys.len(), 5);
+        assert!(!rest.is_truncated);
+    }
+}
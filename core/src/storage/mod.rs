@@ -1,18 +1,28 @@
 //! Storage module for filesystem and S3 operations
 
+pub mod deletion;
+pub mod memory;
 pub mod models;
+pub mod redaction;
 pub mod s3;
 pub mod stream_writer;
 
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
+pub use deletion::{ConfirmedDeletion, DeletionPlan, DeletionReport};
+pub use memory::InMemoryStorage;
 pub use s3::{S3Config, S3Storage};
 
+use models::PartitionGranularity;
+
 use crate::error::{Error, Result};
+use crate::util::retry::{retry_with_backoff, RetryPolicy, Retryable};
 
 /// Storage trait for different backends
 #[async_trait]
@@ -21,6 +31,15 @@ pub trait StorageBackend: Send + Sync {
     async fn upload(&self, key: &str, data: Vec<u8>) -> Result<()>;
     async fn download(&self, key: &str) -> Result<Vec<u8>>;
     async fn delete(&self, key: &str) -> Result<()>;
+    /// Copy an object to a new key, overwriting whatever is there
+    ///
+    /// Bytes stay inside the backend, so encrypted payloads are copied as-is.
+    async fn copy(&self, src_key: &str, dst_key: &str) -> Result<()>;
+    /// List every object under a prefix with its size, recursively
+    ///
+    /// Covers exactly what a bulk delete of the prefix would remove; see
+    /// `Storage::plan_delete_prefix`.
+    async fn list_objects(&self, prefix: &str) -> Result<Vec<ObjectInfo>>;
     async fn list(&self, prefix: &str) -> Result<Vec<String>>;
     async fn list_with_pagination(
         &self,
@@ -31,6 +50,57 @@ pub trait StorageBackend: Send + Sync {
     async fn health_check(&self) -> Result<HealthStatus>;
 }
 
+/// Default retries of a failed upload
+const DEFAULT_UPLOAD_RETRIES: u32 = 2;
+
+/// Default wait before retrying a failed upload
+const DEFAULT_UPLOAD_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Longest wait between upload attempts
+const MAX_UPLOAD_BACKOFF: Duration = Duration::from_secs(30);
+
+/// How often a failed upload is retried before storage counts as unavailable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadRetry {
+    /// Attempts after the first
+    pub retries: u32,
+    /// Wait before the first retry, doubling for each one after
+    pub backoff: Duration,
+}
+
+impl Default for UploadRetry {
+    fn default() -> Self {
+        Self {
+            retries: DEFAULT_UPLOAD_RETRIES,
+            backoff: DEFAULT_UPLOAD_BACKOFF,
+        }
+    }
+}
+
+impl UploadRetry {
+    /// Load the retry count from `STORAGE_UPLOAD_RETRIES`
+    pub fn from_env() -> Self {
+        let retries = std::env::var("STORAGE_UPLOAD_RETRIES")
+            .ok()
+            .and_then(|s| s.parse::<u32>().ok())
+            .unwrap_or(DEFAULT_UPLOAD_RETRIES);
+        Self {
+            retries,
+            ..Self::default()
+        }
+    }
+
+    /// The retry policy uploads run under
+    pub fn policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.retries.saturating_add(1),
+            base: self.backoff,
+            max: MAX_UPLOAD_BACKOFF,
+            jitter: 0.0,
+        }
+    }
+}
+
 /// Result from list_with_pagination
 #[derive(Debug)]
 pub struct ListResult {
@@ -39,6 +109,13 @@ pub struct ListResult {
     pub is_truncated: bool,
 }
 
+/// A stored object's key and size
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ObjectInfo {
+    pub key: String,
+    pub size_bytes: u64,
+}
+
 /// Main storage interface
 #[derive(Clone)]
 pub struct Storage {
@@ -61,6 +138,16 @@ impl Storage {
         Self::file(path)
     }
 
+    /// Create storage held entirely in memory
+    ///
+    /// Nothing touches disk and everything is lost when the last clone is
+    /// dropped. Meant for tests and throwaway local runs.
+    pub fn in_memory() -> Self {
+        Self {
+            backend: Arc::new(InMemoryStorage::new()),
+        }
+    }
+
     /// Create S3 storage with the given configuration
     ///
     /// # Arguments
@@ -97,6 +184,45 @@ impl Storage {
         self.backend.upload(key, data).await
     }
 
+    /// Upload, retrying with backoff while the write keeps failing
+    ///
+    /// Fails with `Error::ProviderUnavailable` once the retries are used up,
+    /// so a sync can tell storage being down apart from its own errors and
+    /// leave its cursor where it was.
+    pub async fn upload_with_retry(
+        &self,
+        key: &str,
+        data: Vec<u8>,
+        retry: UploadRetry,
+    ) -> Result<()> {
+        let policy = retry.policy();
+        retry_with_backoff(
+            &policy,
+            |_| Retryable::Yes,
+            |attempt| {
+                let data = data.clone();
+                async move {
+                    self.backend.upload(key, data).await.inspect_err(|e| {
+                        tracing::warn!(
+                            key,
+                            attempt = attempt + 1,
+                            attempts = policy.max_attempts,
+                            error = %e,
+                            "Storage upload failed"
+                        );
+                    })
+                }
+            },
+        )
+        .await
+        .map_err(|e| {
+            Error::ProviderUnavailable(format!(
+                "storage upload of {key} failed after {} attempts: {e}",
+                policy.max_attempts
+            ))
+        })
+    }
+
     pub async fn download(&self, key: &str) -> Result<Vec<u8>> {
         self.backend.download(key).await
     }
@@ -105,6 +231,22 @@ impl Storage {
         self.backend.delete(key).await
     }
 
+    pub async fn copy(&self, src_key: &str, dst_key: &str) -> Result<()> {
+        self.backend.copy(src_key, dst_key).await
+    }
+
+    /// Move an object to a new key (copy, then delete the original)
+    ///
+    /// Not atomic: if the delete fails the object exists under both keys.
+    /// Moving a key onto itself is a no-op.
+    pub async fn move_object(&self, src_key: &str, dst_key: &str) -> Result<()> {
+        if src_key == dst_key {
+            return Ok(());
+        }
+        self.backend.copy(src_key, dst_key).await?;
+        self.backend.delete(src_key).await
+    }
+
     pub async fn list(&self, prefix: &str) -> Result<Vec<String>> {
         self.backend.list(prefix).await
     }
@@ -113,6 +255,44 @@ impl Storage {
         self.backend.health_check().await
     }
 
+    /// List a stream's object keys for the partitions covering `start..=end`
+    ///
+    /// Builds one `date=YYYY-MM-DD` prefix per day (covering daily and hourly
+    /// partitions) and one `month=YYYY-MM` prefix per month, and lists only
+    /// those, so a time-bounded read costs O(days) rather than a scan of every
+    /// partition. Every layout is listed, so batches written before a stream's
+    /// partition granularity changed are still found. Monthly partitions are
+    /// returned whole, so they may hold records outside the range.
+    pub async fn list_stream_partitions(
+        &self,
+        provider: &str,
+        source_id: &str,
+        stream_name: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<String>> {
+        if end < start {
+            return Err(Error::InvalidInput(format!(
+                "Partition range end {} is before start {}",
+                end, start
+            )));
+        }
+
+        let prefixes = [PartitionGranularity::Daily, PartitionGranularity::Monthly]
+            .into_iter()
+            .flat_map(|g| g.range_prefixes(provider, source_id, stream_name, start, end));
+
+        let mut keys = Vec::new();
+        for prefix in prefixes {
+            // Listed recursively: hourly partitions nest under their day
+            let objects = self.backend.list_objects(&prefix).await?;
+            keys.extend(objects.into_iter().map(|object| object.key));
+        }
+
+        keys.sort();
+        Ok(keys)
+    }
+
     /// List objects with pagination support
     pub async fn list_with_pagination(
         &self,
@@ -219,6 +399,66 @@ impl StorageBackend for FileStorage {
         Ok(())
     }
 
+    async fn copy(&self, src_key: &str, dst_key: &str) -> Result<()> {
+        let src = self.base_path.join(src_key);
+        let dst = self.base_path.join(dst_key);
+
+        if let Some(parent) = dst.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        tokio::fs::copy(src, dst).await?;
+        Ok(())
+    }
+
+    async fn list_objects(&self, prefix: &str) -> Result<Vec<ObjectInfo>> {
+        let prefix_path = self.base_path.join(prefix);
+
+        let metadata = match tokio::fs::metadata(&prefix_path).await {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut objects = Vec::new();
+        if metadata.is_file() {
+            objects.push(ObjectInfo {
+                key: prefix.to_string(),
+                size_bytes: metadata.len(),
+            });
+            return Ok(objects);
+        }
+
+        let mut pending = vec![prefix_path];
+        while let Some(dir) = pending.pop() {
+            let mut entries = tokio::fs::read_dir(&dir).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let file_type = entry.file_type().await?;
+                if file_type.is_dir() {
+                    pending.push(entry.path());
+                    continue;
+                }
+
+                let path = entry.path();
+                let Ok(relative) = path.strip_prefix(&self.base_path) else {
+                    continue;
+                };
+                let key = relative
+                    .components()
+                    .filter_map(|c| c.as_os_str().to_str())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                objects.push(ObjectInfo {
+                    key,
+                    size_bytes: entry.metadata().await?.len(),
+                });
+            }
+        }
+
+        objects.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(objects)
+    }
+
     async fn list(&self, prefix: &str) -> Result<Vec<String>> {
         let prefix_path = self.base_path.join(prefix);
         let mut files = Vec::new();
@@ -318,6 +558,72 @@ mod tests {
         storage.delete("test.txt").await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_upload_with_retry() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = Storage::file(temp_dir.path().to_str().unwrap().to_string()).unwrap();
+        storage.initialize().await.unwrap();
+        let retry = UploadRetry {
+            retries: 1,
+            backoff: Duration::from_millis(200),
+        };
+
+        // A file where the object's directory should go fails the write
+        storage.upload("day", Vec::new()).await.unwrap();
+        let result = storage
+            .upload_with_retry("day/records.jsonl", b"data".to_vec(), retry)
+            .await;
+        assert!(matches!(result, Err(Error::ProviderUnavailable(_))));
+
+        // Storage that recovers during the backoff takes the retry
+        let recovering = storage.clone();
+        let recover = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            recovering.delete("day").await.unwrap();
+        });
+        storage
+            .upload_with_retry("day/records.jsonl", b"data".to_vec(), retry)
+            .await
+            .unwrap();
+        recover.await.unwrap();
+        assert_eq!(
+            storage.download("day/records.jsonl").await.unwrap(),
+            b"data"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_copy_and_move() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = Storage::file(temp_dir.path().to_str().unwrap().to_string()).unwrap();
+        storage.initialize().await.unwrap();
+
+        storage
+            .upload("a/src.jsonl", b"data".to_vec())
+            .await
+            .unwrap();
+
+        storage.copy("a/src.jsonl", "b/c/copy.jsonl").await.unwrap();
+        assert_eq!(storage.download("a/src.jsonl").await.unwrap(), b"data");
+        assert_eq!(storage.download("b/c/copy.jsonl").await.unwrap(), b"data");
+
+        storage
+            .move_object("a/src.jsonl", "d/moved.jsonl")
+            .await
+            .unwrap();
+        assert!(storage.download("a/src.jsonl").await.is_err());
+        assert_eq!(storage.download("d/moved.jsonl").await.unwrap(), b"data");
+
+        // Onto itself leaves the object in place
+        storage
+            .move_object("d/moved.jsonl", "d/moved.jsonl")
+            .await
+            .unwrap();
+        assert_eq!(storage.download("d/moved.jsonl").await.unwrap(), b"data");
+
+        assert!(storage.copy("missing", "elsewhere").await.is_err());
+    }
+
     #[tokio::test]
     async fn test_jsonl_roundtrip() {
         let temp_dir = TempDir::new().unwrap();
@@ -384,4 +690,93 @@ mod tests {
             .unwrap();
         assert_eq!(downloaded, data);
     }
+
+    #[tokio::test]
+    async fn test_delete_prefix() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = Storage::file(temp_dir.path().to_str().unwrap().to_string()).unwrap();
+
+        storage.initialize().await.unwrap();
+
+        for key in [
+            "streams/google/source_a/gmail/date=2025-01-15/records_1.jsonl",
+            "streams/google/source_a/gmail/date=2025-01-16/records_2.jsonl",
+            "streams/google/source_a/calendar/date=2025-01-15/records_3.jsonl",
+            "streams/google/source_b/gmail/date=2025-01-15/records_4.jsonl",
+        ] {
+            storage.upload(key, b"{}".to_vec()).await.unwrap();
+        }
+
+        let plan = storage
+            .plan_delete_prefix("streams/google/source_a/")
+            .await
+            .unwrap();
+        let report = storage.execute_deletion(plan.confirm()).await.unwrap();
+        assert_eq!(report.objects_deleted, 3);
+
+        // Other sources are untouched
+        assert!(storage
+            .download("streams/google/source_b/gmail/date=2025-01-15/records_4.jsonl")
+            .await
+            .is_ok());
+
+        // Missing prefixes are a no-op
+        let plan = storage
+            .plan_delete_prefix("streams/missing/")
+            .await
+            .unwrap();
+        assert!(plan.is_empty());
+        let report = storage.execute_deletion(plan.confirm()).await.unwrap();
+        assert_eq!(report.objects_deleted, 0);
+    }
+
+    #[tokio::test]
+    async fn test_list_stream_partitions() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = Storage::file(temp_dir.path().to_str().unwrap().to_string()).unwrap();
+
+        storage.initialize().await.unwrap();
+
+        for key in [
+            "streams/google/source_a/calendar/date=2025-02-28/records_1.jsonl",
+            "streams/google/source_a/calendar/date=2025-03-01/records_2.jsonl",
+            "streams/google/source_a/calendar/date=2025-03-15/records_3.jsonl",
+            "streams/google/source_a/calendar/date=2025-03-15/records_4.jsonl",
+            "streams/google/source_a/calendar/date=2025-04-01/records_5.jsonl",
+            "streams/google/source_a/gmail/date=2025-03-15/records_6.jsonl",
+            "streams/google/source_a/calendar/date=2025-03-20/hour=07/records_7.jsonl",
+            "streams/google/source_a/calendar/month=2025-03/records_8.jsonl",
+            "streams/google/source_a/calendar/month=2025-04/records_9.jsonl",
+        ] {
+            storage.upload(key, b"{}".to_vec()).await.unwrap();
+        }
+
+        let march = |day| NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+        let keys = storage
+            .list_stream_partitions("google", "source_a", "calendar", march(1), march(31))
+            .await
+            .unwrap();
+        assert_eq!(
+            keys,
+            vec![
+                "streams/google/source_a/calendar/date=2025-03-01/records_2.jsonl",
+                "streams/google/source_a/calendar/date=2025-03-15/records_3.jsonl",
+                "streams/google/source_a/calendar/date=2025-03-15/records_4.jsonl",
+                "streams/google/source_a/calendar/date=2025-03-20/hour=07/records_7.jsonl",
+                "streams/google/source_a/calendar/month=2025-03/records_8.jsonl",
+            ]
+        );
+
+        // Single-day range, including the monthly partition covering it
+        let keys = storage
+            .list_stream_partitions("google", "source_a", "calendar", march(15), march(15))
+            .await
+            .unwrap();
+        assert_eq!(keys.len(), 3);
+
+        assert!(storage
+            .list_stream_partitions("google", "source_a", "calendar", march(2), march(1))
+            .await
+            .is_err());
+    }
 }
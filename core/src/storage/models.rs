@@ -1,6 +1,6 @@
 //! Data models for stream object storage metadata
 
-use chrono::NaiveDate;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
 use serde::{Deserialize, Serialize};
 
 use crate::types::Timestamp;
@@ -125,6 +125,318 @@ pub fn validate_subdomain(subdomain: &str) -> Result<(), &'static str> {
     Ok(())
 }
 
+/// Time span covered by one archive partition of a stream
+///
+/// Set per stream (`elt_stream_connections.partition_granularity`). Finer
+/// partitions keep objects small for high-volume streams at the cost of more
+/// objects, and so more list calls for a range read; coarser ones suit
+/// low-volume streams but a range read downloads whole months. Changing it
+/// only affects new batches: earlier partitions keep their layout, and both
+/// are read back.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum PartitionGranularity {
+    /// `date={YYYY-MM-DD}/hour={HH}/`
+    Hourly,
+    /// `date={YYYY-MM-DD}/`
+    #[default]
+    Daily,
+    /// `month={YYYY-MM}/`
+    Monthly,
+}
+
+impl PartitionGranularity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Hourly => "hourly",
+            Self::Daily => "daily",
+            Self::Monthly => "monthly",
+        }
+    }
+
+    /// Start of the partition containing `at`
+    pub fn partition_start(self, at: NaiveDateTime) -> NaiveDateTime {
+        let date = at.date();
+        match self {
+            Self::Hourly => date.and_time(NaiveTime::from_hms_opt(at.hour(), 0, 0).unwrap()),
+            Self::Daily => date.and_time(NaiveTime::MIN),
+            Self::Monthly => date.with_day(1).unwrap().and_time(NaiveTime::MIN),
+        }
+    }
+
+    /// Key segment(s) of the partition starting at `start`, without a trailing `/`
+    pub fn segment(self, start: NaiveDateTime) -> String {
+        match self {
+            Self::Hourly => start.format("date=%Y-%m-%d/hour=%H").to_string(),
+            Self::Daily => start.format("date=%Y-%m-%d").to_string(),
+            Self::Monthly => start.format("month=%Y-%m").to_string(),
+        }
+    }
+
+    /// Parse the partition segments of a key back to granularity and start
+    ///
+    /// Only canonical segments (as rendered by `segment`) are accepted.
+    fn parse_segments(segments: &[&str]) -> Option<(Self, NaiveDateTime)> {
+        let parse_date = |segment: &str| {
+            NaiveDate::parse_from_str(segment.strip_prefix("date=")?, "%Y-%m-%d").ok()
+        };
+
+        let (granularity, start) = match segments {
+            [month] if month.starts_with("month=") => {
+                let month = month.strip_prefix("month=")?;
+                let date = NaiveDate::parse_from_str(&format!("{month}-01"), "%Y-%m-%d").ok()?;
+                Some((Self::Monthly, date.and_time(NaiveTime::MIN)))
+            }
+            [date] => Some((Self::Daily, parse_date(date)?.and_time(NaiveTime::MIN))),
+            [date, hour] => {
+                let hour = hour.strip_prefix("hour=")?;
+                let hour = NaiveTime::from_hms_opt(hour.parse().ok()?, 0, 0)?;
+                Some((Self::Hourly, parse_date(date)?.and_time(hour)))
+            }
+            _ => None,
+        }?;
+        (granularity.segment(start) == segments.join("/")).then_some((granularity, start))
+    }
+
+    /// Prefixes to list to find every batch in the days `start..=end`
+    ///
+    /// Hourly partitions are listed a day at a time (one `date=` prefix covers
+    /// its hours); monthly ones a month at a time, so the listed objects may
+    /// hold records from outside the range.
+    pub fn range_prefixes(
+        self,
+        provider: &str,
+        source_id: &str,
+        stream_name: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Vec<String> {
+        let listed = match self {
+            Self::Hourly | Self::Daily => Self::Daily,
+            Self::Monthly => Self::Monthly,
+        };
+
+        let mut prefixes: Vec<String> = start
+            .iter_days()
+            .take_while(|d| *d <= end)
+            .map(|d| {
+                StreamKey::partition_prefix(
+                    provider,
+                    source_id,
+                    stream_name,
+                    listed,
+                    listed.partition_start(d.and_time(NaiveTime::MIN)),
+                )
+            })
+            .collect();
+        prefixes.dedup();
+        prefixes
+    }
+}
+
+impl std::fmt::Display for PartitionGranularity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for PartitionGranularity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hourly" => Ok(Self::Hourly),
+            "daily" => Ok(Self::Daily),
+            "monthly" => Ok(Self::Monthly),
+            _ => Err(format!("Unknown partition granularity: {s}")),
+        }
+    }
+}
+
+/// Canonical storage key of an archived stream batch
+///
+/// Layout: `[tenants/{subdomain}/]streams/{provider}/{source_id}/{stream_name}/{partition}/records_{seq}.jsonl`,
+/// where `partition` is `date={YYYY-MM-DD}` by default (see
+/// `PartitionGranularity` for the others) and `seq` is the time the batch
+/// was written at, in microseconds since the epoch (seconds in older keys).
+/// This is the one place the layout is spelled out; `StreamKeyBuilder`,
+/// `StreamKeyParser`, partition listing and source purging all go through it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamKey {
+    /// Tenant subdomain, for multi-tenant layouts
+    pub tenant: Option<String>,
+    pub provider: String,
+    pub source_id: String,
+    pub stream_name: String,
+    pub granularity: PartitionGranularity,
+    /// Start of the batch's partition
+    pub partition: NaiveDateTime,
+    pub seq: i64,
+}
+
+impl StreamKey {
+    /// Build the (untenanted) key for a batch in a daily partition
+    ///
+    /// Example: `streams/ios/source_ios-healthkit/healthkit/date=2025-01-15/records_1736899200.jsonl`
+    pub fn build(
+        provider: &str,
+        source_id: &str,
+        stream_name: &str,
+        date: NaiveDate,
+        seq: i64,
+    ) -> String {
+        Self::build_partitioned(
+            provider,
+            source_id,
+            stream_name,
+            PartitionGranularity::Daily,
+            date.and_time(NaiveTime::MIN),
+            seq,
+        )
+    }
+
+    /// Build the (untenanted) key for a batch in the partition containing `at`
+    ///
+    /// Example (hourly): `streams/google/source_a/gmail/date=2025-01-15/hour=09/records_1736899200.jsonl`
+    pub fn build_partitioned(
+        provider: &str,
+        source_id: &str,
+        stream_name: &str,
+        granularity: PartitionGranularity,
+        at: NaiveDateTime,
+        seq: i64,
+    ) -> String {
+        format!(
+            "{}records_{}.jsonl",
+            Self::partition_prefix(
+                provider,
+                source_id,
+                stream_name,
+                granularity,
+                granularity.partition_start(at)
+            ),
+            seq
+        )
+    }
+
+    /// Parse a full object key, with or without a tenant prefix
+    ///
+    /// Returns `None` for anything that isn't exactly a batch key (prefixes,
+    /// extra path segments, malformed partition or filename, invalid subdomain).
+    pub fn parse(key: &str) -> Option<Self> {
+        let parts: Vec<&str> = key.split('/').collect();
+        let (tenant, parts) = match parts.as_slice() {
+            ["tenants", subdomain, rest @ ..] => {
+                validate_subdomain(subdomain).ok()?;
+                (Some(subdomain.to_string()), rest)
+            }
+            _ => (None, parts.as_slice()),
+        };
+
+        let ["streams", provider, source_id, stream_name, partition @ .., file] = parts else {
+            return None;
+        };
+        if [provider, source_id, stream_name]
+            .iter()
+            .any(|s| s.is_empty())
+        {
+            return None;
+        }
+
+        let (granularity, partition) = PartitionGranularity::parse_segments(partition)?;
+        let seq = file
+            .strip_prefix("records_")?
+            .strip_suffix(".jsonl")?
+            .parse()
+            .ok()?;
+
+        Some(Self {
+            tenant,
+            provider: provider.to_string(),
+            source_id: source_id.to_string(),
+            stream_name: stream_name.to_string(),
+            granularity,
+            partition,
+            seq,
+        })
+    }
+
+    /// Day the batch's partition starts on
+    pub fn date(&self) -> NaiveDate {
+        self.partition.date()
+    }
+
+    /// Render this key, including the tenant prefix if set
+    pub fn to_key(&self) -> String {
+        let key = Self::build_partitioned(
+            &self.provider,
+            &self.source_id,
+            &self.stream_name,
+            self.granularity,
+            self.partition,
+            self.seq,
+        );
+        match &self.tenant {
+            Some(tenant) => format!("tenants/{}/{}", tenant, key),
+            None => key,
+        }
+    }
+
+    /// Prefix of every object belonging to a source connection
+    ///
+    /// Pattern: `streams/{provider}/{source_id}/`
+    pub fn source_prefix(provider: &str, source_id: &str) -> String {
+        format!("streams/{}/{}/", provider, source_id)
+    }
+
+    /// Prefix of every object of one stream
+    ///
+    /// Pattern: `streams/{provider}/{source_id}/{stream_name}/`
+    pub fn stream_prefix(provider: &str, source_id: &str, stream_name: &str) -> String {
+        format!(
+            "{}{}/",
+            Self::source_prefix(provider, source_id),
+            stream_name
+        )
+    }
+
+    /// Prefix of one stream's objects for a single day
+    ///
+    /// Pattern: `streams/{provider}/{source_id}/{stream_name}/date={YYYY-MM-DD}/`
+    pub fn date_prefix(
+        provider: &str,
+        source_id: &str,
+        stream_name: &str,
+        date: NaiveDate,
+    ) -> String {
+        Self::partition_prefix(
+            provider,
+            source_id,
+            stream_name,
+            PartitionGranularity::Daily,
+            date.and_time(NaiveTime::MIN),
+        )
+    }
+
+    /// Prefix of one stream's objects in the partition starting at `start`
+    ///
+    /// Pattern: `streams/{provider}/{source_id}/{stream_name}/{partition}/`
+    pub fn partition_prefix(
+        provider: &str,
+        source_id: &str,
+        stream_name: &str,
+        granularity: PartitionGranularity,
+        start: NaiveDateTime,
+    ) -> String {
+        format!(
+            "{}{}/",
+            Self::stream_prefix(provider, source_id, stream_name),
+            granularity.segment(start)
+        )
+    }
+}
+
 /// S3 key builder for consistent object naming
 ///
 /// Supports multi-tenant storage with optional subdomain prefix.
@@ -139,7 +451,8 @@ pub struct StreamKeyBuilder {
     provider: String,
     source_id: String,
     stream_name: String,
-    date: NaiveDate,
+    granularity: PartitionGranularity,
+    partition: NaiveDateTime,
 }
 
 /// Error type for StreamKeyBuilder construction
@@ -193,14 +506,25 @@ impl StreamKeyBuilder {
             provider: provider.into(),
             source_id: source_id.into(),
             stream_name: stream_name.into(),
-            date,
+            granularity: PartitionGranularity::Daily,
+            partition: date.and_time(NaiveTime::MIN),
         })
     }
 
+    /// Place keys in the `granularity` partition containing `at`, instead of
+    /// the daily partition of the constructor's date
+    pub fn with_partition(mut self, granularity: PartitionGranularity, at: NaiveDateTime) -> Self {
+        self.granularity = granularity;
+        self.partition = granularity.partition_start(at);
+        self
+    }
+
     /// Build S3 key with current timestamp
     ///
-    /// Pattern without tenant: `streams/{provider}/{source_id}/{stream_name}/date={YYYY-MM-DD}/records_{unix_timestamp}.jsonl`
-    /// Pattern with tenant: `tenants/{subdomain}/streams/{provider}/{source_id}/{stream_name}/date={YYYY-MM-DD}/records_{unix_timestamp}.jsonl`
+    /// Pattern without tenant: `streams/{provider}/{source_id}/{stream_name}/{partition}/records_{unix_timestamp}.jsonl`
+    /// Pattern with tenant: `tenants/{subdomain}/streams/{provider}/{source_id}/{stream_name}/{partition}/records_{unix_timestamp}.jsonl`
+    ///
+    /// `{partition}` is `date={YYYY-MM-DD}` unless `with_partition` chose another granularity.
     ///
     /// Example: `tenants/adamjace/streams/ios/550e8400-e29b-41d4-a716-446655440000/healthkit/date=2025-01-15/records_1736899200.jsonl`
     pub fn build(&self) -> String {
@@ -210,13 +534,13 @@ impl StreamKeyBuilder {
 
     /// Build S3 key with explicit timestamp
     pub fn build_with_timestamp(&self, timestamp: i64) -> String {
-        let base = format!(
-            "streams/{}/{}/{}/date={}/records_{}.jsonl",
-            self.provider,
-            self.source_id,
-            self.stream_name,
-            self.date.format("%Y-%m-%d"),
-            timestamp
+        let base = StreamKey::build_partitioned(
+            &self.provider,
+            &self.source_id,
+            &self.stream_name,
+            self.granularity,
+            self.partition,
+            timestamp,
         );
         match &self.tenant_prefix {
             Some(prefix) => format!("{}/{}", prefix, base),
@@ -228,10 +552,7 @@ impl StreamKeyBuilder {
     ///
     /// Pattern: `[tenants/{subdomain}/]streams/{provider}/{source_id}/{stream_name}/`
     pub fn build_stream_prefix(&self) -> String {
-        let base = format!(
-            "streams/{}/{}/{}/",
-            self.provider, self.source_id, self.stream_name
-        );
+        let base = StreamKey::stream_prefix(&self.provider, &self.source_id, &self.stream_name);
         match &self.tenant_prefix {
             Some(prefix) => format!("{}/{}", prefix, base),
             None => base,
@@ -242,12 +563,11 @@ impl StreamKeyBuilder {
     ///
     /// Pattern: `[tenants/{subdomain}/]streams/{provider}/{source_id}/{stream_name}/date={YYYY-MM-DD}/`
     pub fn build_date_prefix(&self) -> String {
-        let base = format!(
-            "streams/{}/{}/{}/date={}/",
-            self.provider,
-            self.source_id,
-            self.stream_name,
-            self.date.format("%Y-%m-%d")
+        let base = StreamKey::date_prefix(
+            &self.provider,
+            &self.source_id,
+            &self.stream_name,
+            self.partition.date(),
         );
         match &self.tenant_prefix {
             Some(prefix) => format!("{}/{}", prefix, base),
@@ -336,6 +656,8 @@ impl StreamKeyParser {
 
     /// Extract date from key
     ///
+    /// The day the key's partition starts on (the 1st for monthly partitions).
+    ///
     /// Example: Returns: `2025-01-15`
     pub fn date(&self) -> Option<NaiveDate> {
         let parts: Vec<&str> = self.key.split('/').collect();
@@ -344,14 +666,13 @@ impl StreamKeyParser {
             return None;
         }
 
-        // Parse "date=2025-01-15" format
-        let date_part = parts[offset + 4];
-        if !date_part.starts_with("date=") {
-            return None;
+        // Parse "date=2025-01-15" or "month=2025-01" format
+        let partition_part = parts[offset + 4];
+        if let Some(date_str) = partition_part.strip_prefix("date=") {
+            return NaiveDate::parse_from_str(date_str, "%Y-%m-%d").ok();
         }
-
-        let date_str = date_part.strip_prefix("date=")?;
-        NaiveDate::parse_from_str(date_str, "%Y-%m-%d").ok()
+        let month_str = partition_part.strip_prefix("month=")?;
+        NaiveDate::parse_from_str(&format!("{month_str}-01"), "%Y-%m-%d").ok()
     }
 
     /// Extract timestamp from key
@@ -364,8 +685,8 @@ impl StreamKeyParser {
             return None;
         }
 
-        // Parse "records_1736899200.jsonl" format
-        let filename = parts[offset + 5];
+        // Parse "records_1736899200.jsonl" format (after one or two partition segments)
+        let filename = parts.last()?;
         if !filename.starts_with("records_") || !filename.ends_with(".jsonl") {
             return None;
         }
@@ -376,13 +697,9 @@ impl StreamKeyParser {
 
     /// Extract all metadata from key
     pub fn parse_all(&self) -> Option<(String, String, String, NaiveDate, i64)> {
-        Some((
-            self.provider()?,
-            self.source_id()?,
-            self.stream_name()?,
-            self.date()?,
-            self.timestamp()?,
-        ))
+        let k = StreamKey::parse(&self.key)?;
+        let date = k.date();
+        Some((k.provider, k.source_id, k.stream_name, date, k.seq))
     }
 
     /// Static helper to parse date from S3 key (for use in encryption key derivation)
@@ -578,6 +895,139 @@ mod tests {
         assert_eq!(timestamp, 1736899200);
     }
 
+    #[test]
+    fn test_stream_key_round_trip() {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 15).unwrap();
+        let key = StreamKey::build("google", "source_a", "gmail", date, 1736899200);
+        assert_eq!(
+            key,
+            "streams/google/source_a/gmail/date=2025-01-15/records_1736899200.jsonl"
+        );
+
+        let parsed = StreamKey::parse(&key).unwrap();
+        assert_eq!(
+            parsed,
+            StreamKey {
+                tenant: None,
+                provider: "google".to_string(),
+                source_id: "source_a".to_string(),
+                stream_name: "gmail".to_string(),
+                granularity: PartitionGranularity::Daily,
+                partition: date.and_time(NaiveTime::MIN),
+                seq: 1736899200,
+            }
+        );
+        assert_eq!(parsed.to_key(), key);
+
+        let tenanted = StreamKey {
+            tenant: Some("adamjace".to_string()),
+            ..parsed
+        };
+        assert_eq!(StreamKey::parse(&tenanted.to_key()), Some(tenanted.clone()));
+
+        // Builder and prefixes agree with the canonical layout
+        let builder =
+            StreamKeyBuilder::new(Some("adamjace"), "google", "source_a", "gmail", date).unwrap();
+        assert_eq!(builder.build_with_timestamp(1736899200), tenanted.to_key());
+        assert!(key.starts_with(&StreamKey::date_prefix("google", "source_a", "gmail", date)));
+        assert!(key.starts_with(&StreamKey::stream_prefix("google", "source_a", "gmail")));
+        assert!(key.starts_with(&StreamKey::source_prefix("google", "source_a")));
+    }
+
+    #[test]
+    fn test_partitioned_stream_keys() {
+        let at = NaiveDate::from_ymd_opt(2025, 1, 15)
+            .unwrap()
+            .and_hms_opt(9, 42, 7)
+            .unwrap();
+
+        for (granularity, expected) in [
+            (
+                PartitionGranularity::Hourly,
+                "streams/google/source_a/gmail/date=2025-01-15/hour=09/records_1.jsonl",
+            ),
+            (
+                PartitionGranularity::Daily,
+                "streams/google/source_a/gmail/date=2025-01-15/records_1.jsonl",
+            ),
+            (
+                PartitionGranularity::Monthly,
+                "streams/google/source_a/gmail/month=2025-01/records_1.jsonl",
+            ),
+        ] {
+            let key =
+                StreamKey::build_partitioned("google", "source_a", "gmail", granularity, at, 1);
+            assert_eq!(key, expected);
+
+            let parsed = StreamKey::parse(&key).unwrap();
+            assert_eq!(parsed.granularity, granularity);
+            assert_eq!(parsed.partition, granularity.partition_start(at));
+            assert_eq!(parsed.to_key(), key);
+
+            let parser = StreamKeyParser::new(&key);
+            assert_eq!(parser.timestamp(), Some(1));
+            assert_eq!(parser.date(), Some(parsed.date()));
+        }
+
+        let builder = StreamKeyBuilder::new(None, "google", "source_a", "gmail", at.date())
+            .unwrap()
+            .with_partition(PartitionGranularity::Hourly, at);
+        assert_eq!(
+            builder.build_with_timestamp(1),
+            "streams/google/source_a/gmail/date=2025-01-15/hour=09/records_1.jsonl"
+        );
+    }
+
+    #[test]
+    fn test_partition_range_prefixes() {
+        let date = |s: &str| s.parse::<NaiveDate>().unwrap();
+        let prefixes = |granularity: PartitionGranularity, start, end| {
+            granularity.range_prefixes("google", "source_a", "gmail", date(start), date(end))
+        };
+
+        // Hourly partitions are listed by their day prefix
+        assert_eq!(
+            prefixes(PartitionGranularity::Hourly, "2025-01-31", "2025-02-01"),
+            prefixes(PartitionGranularity::Daily, "2025-01-31", "2025-02-01"),
+        );
+        assert_eq!(
+            prefixes(PartitionGranularity::Daily, "2025-01-31", "2025-02-01"),
+            vec![
+                "streams/google/source_a/gmail/date=2025-01-31/",
+                "streams/google/source_a/gmail/date=2025-02-01/",
+            ]
+        );
+        assert_eq!(
+            prefixes(PartitionGranularity::Monthly, "2025-01-15", "2025-03-01"),
+            vec![
+                "streams/google/source_a/gmail/month=2025-01/",
+                "streams/google/source_a/gmail/month=2025-02/",
+                "streams/google/source_a/gmail/month=2025-03/",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stream_key_parse_rejects_partial_and_malformed_keys() {
+        for key in [
+            "streams/google/source_a/gmail/",
+            "streams/google/source_a/gmail/date=2025-01-15/",
+            "streams/google/source_a/gmail/date=2025-13-01/records_1.jsonl",
+            "streams/google/source_a/gmail/date=2025-01-15/records_x.jsonl",
+            "streams/google/source_a/gmail/date=2025-01-15/records_1.json",
+            "streams/google/source_a/gmail/extra/date=2025-01-15/records_1.jsonl",
+            "streams/google//gmail/date=2025-01-15/records_1.jsonl",
+            "tenants/../streams/google/source_a/gmail/date=2025-01-15/records_1.jsonl",
+            "drive/google/source_a/gmail/date=2025-01-15/records_1.jsonl",
+            "streams/google/source_a/gmail/date=2025-01-15/hour=24/records_1.jsonl",
+            "streams/google/source_a/gmail/date=2025-01-15/hour=9/records_1.jsonl",
+            "streams/google/source_a/gmail/month=2025-1/records_1.jsonl",
+            "streams/google/source_a/gmail/month=2025-01/hour=09/records_1.jsonl",
+        ] {
+            assert!(StreamKey::parse(key).is_none(), "should reject {key}");
+        }
+    }
+
     #[test]
     fn test_stream_key_parser_invalid() {
         let parser = StreamKeyParser::new("invalid/key/format");
@@ -0,0 +1,380 @@
+//! Field redaction applied before records are stored
+//!
+//! A stream's config can keep fields of its records out of storage:
+//!
+//! ```json
+//! { "redaction": { "drop": ["$.body"], "hash": ["$.attendees[*].email"] } }
+//! ```
+//!
+//! Dropped fields are removed. Hashed fields are replaced by a keyed
+//! HMAC-SHA256 of their value (`hmac-sha256:<hex>`): the same value always
+//! hashes the same way, so redacted fields can still be joined and deduped
+//! without being readable. Strings are hashed as their text, other values as
+//! their JSON; nulls are left as they are. The key is `REDACTION_HASH_KEY`,
+//! falling back to `VIRTUES_ENCRYPTION_KEY`; changing it changes every hash.
+//!
+//! Paths are a JSONPath subset: `$` followed by `.field`, `['field']`, `[n]`
+//! and `[*]` (every element or value). A path that doesn't match a record
+//! leaves it alone.
+//!
+//! `StreamWriter::write_record` applies a stream's rules before the record is
+//! stamped and buffered, so nothing redacted reaches the archive. Redacting
+//! a record twice changes nothing, which keeps records replayed from the
+//! archive as they were. Records of end-to-end encrypted streams arrive
+//! sealed: only their clear-text envelope fields can be redacted.
+//!
+//! Streams that write content to storage beside the record apply the same
+//! rules first: Gmail's externally stored bodies are redacted as the record's
+//! `body_plain`/`body_html`, and its attachments aren't downloaded at all
+//! when `$.attachment_keys` is redacted. Rules apply to every record of a
+//! stream; redacting only some records (such as mail with certain labels)
+//! isn't supported.
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use sqlx::SqlitePool;
+
+use crate::error::{Error, Result};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Prefix of hashed field values
+pub const HASH_PREFIX: &str = "hmac-sha256:";
+
+/// The `redaction` section of a stream config
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RedactionConfig {
+    /// Paths of fields removed from records
+    #[serde(default)]
+    pub drop: Vec<String>,
+    /// Paths of fields replaced by a keyed hash of their value
+    #[serde(default)]
+    pub hash: Vec<String>,
+}
+
+/// One step of a field path
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Field(String),
+    Index(usize),
+    Wildcard,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Action<'a> {
+    Drop,
+    Hash(&'a [u8]),
+}
+
+/// A stream's compiled redaction rules
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    drop: Vec<Vec<Segment>>,
+    hash: Vec<Vec<Segment>>,
+    key: Vec<u8>,
+}
+
+impl Redactor {
+    /// Compile a stream's redaction rules with `key` as the hashing key
+    pub fn new(config: &RedactionConfig, key: &[u8]) -> Result<Self> {
+        let parse_all = |paths: &[String]| {
+            paths
+                .iter()
+                .map(|p| parse_path(p))
+                .collect::<Result<Vec<_>>>()
+        };
+        Ok(Self {
+            drop: parse_all(&config.drop)?,
+            hash: parse_all(&config.hash)?,
+            key: key.to_vec(),
+        })
+    }
+
+    /// Compile the rules in a stream config, if it has any
+    ///
+    /// Fails on a malformed `redaction` section or path, and when fields are
+    /// to be hashed but no hashing key is configured.
+    pub fn from_stream_config(config: &Value) -> Result<Option<Self>> {
+        let Some(section) = config.get("redaction").filter(|s| !s.is_null()) else {
+            return Ok(None);
+        };
+        let config: RedactionConfig = serde_json::from_value(section.clone())
+            .map_err(|e| Error::InvalidInput(format!("Invalid redaction config: {e}")))?;
+        if config.drop.is_empty() && config.hash.is_empty() {
+            return Ok(None);
+        }
+
+        let key = hash_key().unwrap_or_default();
+        if key.is_empty() && !config.hash.is_empty() {
+            return Err(Error::Configuration(
+                "REDACTION_HASH_KEY or VIRTUES_ENCRYPTION_KEY required to hash redacted fields"
+                    .to_string(),
+            ));
+        }
+        Self::new(&config, &key).map(Some)
+    }
+
+    /// Load a stream's redaction rules from its connection config
+    pub async fn load(db: &SqlitePool, source_id: &str, stream_name: &str) -> Result<Option<Self>> {
+        let config = sqlx::query_scalar::<_, Value>(
+            "SELECT config FROM elt_stream_connections WHERE source_connection_id = $1 AND stream_name = $2",
+        )
+        .bind(source_id)
+        .bind(stream_name)
+        .fetch_optional(db)
+        .await?;
+
+        match config {
+            Some(config) => Self::from_stream_config(&config),
+            None => Ok(None),
+        }
+    }
+
+    /// Whether any rule drops or hashes `field` of a record, or part of it
+    pub fn covers_field(&self, field: &str) -> bool {
+        self.drop
+            .iter()
+            .chain(&self.hash)
+            .any(|path| match path.first() {
+                Some(Segment::Field(name)) => name == field,
+                Some(Segment::Wildcard) => true,
+                _ => false,
+            })
+    }
+
+    /// Redact a record in place
+    pub fn apply(&self, record: &mut Value) {
+        for path in &self.drop {
+            apply_at(record, path, Action::Drop);
+        }
+        for path in &self.hash {
+            apply_at(record, path, Action::Hash(&self.key));
+        }
+    }
+}
+
+fn hash_key() -> Option<Vec<u8>> {
+    ["REDACTION_HASH_KEY", "VIRTUES_ENCRYPTION_KEY"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|key| !key.is_empty())
+        .map(String::into_bytes)
+}
+
+/// Parse a path such as `$.attendees[*].email`
+fn parse_path(path: &str) -> Result<Vec<Segment>> {
+    let invalid =
+        |reason: &str| Error::InvalidInput(format!("Invalid redaction path '{path}': {reason}"));
+
+    let mut rest = path
+        .strip_prefix('$')
+        .ok_or_else(|| invalid("must start with '$'"))?;
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            let name = &after[..end];
+            segments.push(match name {
+                "" => return Err(invalid("empty field name")),
+                "*" => Segment::Wildcard,
+                _ => Segment::Field(name.to_string()),
+            });
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(|| invalid("unclosed '['"))?;
+            let inner = &after[..end];
+            let quoted = inner
+                .strip_prefix('\'')
+                .and_then(|s| s.strip_suffix('\''))
+                .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+            segments.push(match (inner, quoted) {
+                ("*", _) => Segment::Wildcard,
+                (_, Some(name)) => Segment::Field(name.to_string()),
+                _ => Segment::Index(
+                    inner
+                        .parse()
+                        .map_err(|_| invalid("brackets hold an index, '*' or a quoted field"))?,
+                ),
+            });
+            rest = &after[end + 1..];
+        } else {
+            return Err(invalid("expected '.' or '['"));
+        }
+    }
+
+    if segments.is_empty() {
+        return Err(invalid("can't redact the whole record"));
+    }
+    Ok(segments)
+}
+
+fn apply_at(value: &mut Value, path: &[Segment], action: Action) {
+    let Some((segment, rest)) = path.split_first() else {
+        return;
+    };
+
+    if rest.is_empty() {
+        match (value, segment, action) {
+            (Value::Object(fields), Segment::Field(name), Action::Drop) => {
+                fields.remove(name);
+            }
+            (Value::Object(fields), Segment::Wildcard, Action::Drop) => fields.clear(),
+            (Value::Array(items), Segment::Index(i), Action::Drop) if *i < items.len() => {
+                items.remove(*i);
+            }
+            (Value::Array(items), Segment::Wildcard, Action::Drop) => items.clear(),
+            (value, segment, Action::Hash(key)) => {
+                for child in children(value, segment) {
+                    hash_in_place(child, key);
+                }
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    for child in children(value, segment) {
+        apply_at(child, rest, action);
+    }
+}
+
+fn children<'a>(value: &'a mut Value, segment: &Segment) -> Vec<&'a mut Value> {
+    match (value, segment) {
+        (Value::Object(fields), Segment::Field(name)) => fields.get_mut(name).into_iter().collect(),
+        (Value::Object(fields), Segment::Wildcard) => fields.values_mut().collect(),
+        (Value::Array(items), Segment::Index(i)) => items.get_mut(*i).into_iter().collect(),
+        (Value::Array(items), Segment::Wildcard) => items.iter_mut().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn hash_in_place(value: &mut Value, key: &[u8]) {
+    let input = match &*value {
+        Value::Null => return,
+        Value::String(s) if s.starts_with(HASH_PREFIX) => return,
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(input.as_bytes());
+    *value = Value::String(format!(
+        "{HASH_PREFIX}{}",
+        hex::encode(mac.finalize().into_bytes())
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn redactor(drop: &[&str], hash: &[&str]) -> Redactor {
+        let config = RedactionConfig {
+            drop: drop.iter().map(|p| p.to_string()).collect(),
+            hash: hash.iter().map(|p| p.to_string()).collect(),
+        };
+        Redactor::new(&config, b"test-key").unwrap()
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("$.attendees[*].email").unwrap(),
+            vec![
+                Segment::Field("attendees".to_string()),
+                Segment::Wildcard,
+                Segment::Field("email".to_string()),
+            ]
+        );
+        assert_eq!(
+            parse_path("$['payload'].parts[0]").unwrap(),
+            vec![
+                Segment::Field("payload".to_string()),
+                Segment::Field("parts".to_string()),
+                Segment::Index(0),
+            ]
+        );
+        for bad in ["$", "body", "$..body", "$.parts[x]", "$.parts[0"] {
+            assert!(parse_path(bad).is_err(), "{bad} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_drop_and_hash() {
+        let redactor = redactor(&["$.body", "$.headers[0]"], &["$.attendees[*].email"]);
+        let mut record = json!({
+            "id": "evt-1",
+            "body": "secret",
+            "headers": ["a", "b"],
+            "attendees": [
+                { "email": "alice@example.com", "name": "Alice" },
+                { "email": "bob@example.com" },
+                { "email": null }
+            ]
+        });
+        redactor.apply(&mut record);
+
+        assert!(record.get("body").is_none());
+        assert_eq!(record["headers"], json!(["b"]));
+        assert_eq!(record["attendees"][0]["name"], "Alice");
+        assert_eq!(record["attendees"][2]["email"], Value::Null);
+        let alice = record["attendees"][0]["email"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert!(alice.starts_with(HASH_PREFIX));
+        assert_ne!(alice, record["attendees"][1]["email"].as_str().unwrap());
+
+        // Deterministic across records, and unchanged when redacted again
+        let mut other = json!({ "attendees": [{ "email": "alice@example.com" }] });
+        redactor.apply(&mut other);
+        assert_eq!(other["attendees"][0]["email"], alice);
+        let before = record.clone();
+        redactor.apply(&mut record);
+        assert_eq!(record, before);
+
+        // A different key hashes differently
+        let config = RedactionConfig {
+            drop: vec![],
+            hash: vec!["$.attendees[*].email".to_string()],
+        };
+        let mut rekeyed = json!({ "attendees": [{ "email": "alice@example.com" }] });
+        Redactor::new(&config, b"other-key")
+            .unwrap()
+            .apply(&mut rekeyed);
+        assert_ne!(rekeyed["attendees"][0]["email"], alice);
+    }
+
+    #[test]
+    fn test_covers_field() {
+        let rules = redactor(&["$.attachment_keys[0]"], &["$.body_plain"]);
+        assert!(rules.covers_field("attachment_keys"));
+        assert!(rules.covers_field("body_plain"));
+        assert!(!rules.covers_field("body_html"));
+        assert!(redactor(&["$.*"], &[]).covers_field("body_html"));
+    }
+
+    #[test]
+    fn test_from_stream_config() {
+        assert!(
+            Redactor::from_stream_config(&json!({ "max_records_per_run": 10 }))
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            Redactor::from_stream_config(&json!({ "redaction": { "drop": ["$.body"] } }))
+                .unwrap()
+                .is_some()
+        );
+        assert!(matches!(
+            Redactor::from_stream_config(&json!({ "redaction": { "drop": ["body"] } })),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            Redactor::from_stream_config(&json!({ "redaction": "body" })),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+}
@@ -11,7 +11,7 @@ use aws_sdk_s3::{
 use tokio::io::AsyncRead;
 use tokio_util::io::ReaderStream;
 
-use super::{HealthStatus, ListResult, StorageBackend};
+use super::{HealthStatus, ListResult, ObjectInfo, StorageBackend};
 use crate::error::{Error, Result};
 
 /// S3 storage backend configuration
@@ -305,6 +305,68 @@ impl StorageBackend for S3Storage {
         Ok(())
     }
 
+    async fn copy(&self, src_key: &str, dst_key: &str) -> Result<()> {
+        // Server-side copy; the source is "bucket/key" with the key URL-encoded
+        let copy_source = format!(
+            "{}/{}",
+            self.bucket,
+            urlencoding::encode(&self.full_key(src_key))
+        );
+
+        self.client
+            .copy_object()
+            .bucket(&self.bucket)
+            .copy_source(copy_source)
+            .key(self.full_key(dst_key))
+            .send()
+            .await
+            .map_err(|e| Error::Storage(format!("Failed to copy in S3: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn list_objects(&self, prefix: &str) -> Result<Vec<ObjectInfo>> {
+        let full_prefix = self.full_key(prefix);
+        let mut objects = Vec::new();
+        let mut continuation_token: Option<String> = None;
+
+        loop {
+            let mut request = self
+                .client
+                .list_objects_v2()
+                .bucket(&self.bucket)
+                .prefix(&full_prefix);
+
+            if let Some(token) = continuation_token.take() {
+                request = request.continuation_token(token);
+            }
+
+            let response = request
+                .send()
+                .await
+                .map_err(|e| Error::Storage(format!("Failed to list S3 objects: {}", e)))?;
+
+            if let Some(contents) = response.contents {
+                for object in contents {
+                    if let Some(key) = object.key {
+                        objects.push(ObjectInfo {
+                            key: self.strip_prefix(&key),
+                            size_bytes: object.size.unwrap_or(0).max(0) as u64,
+                        });
+                    }
+                }
+            }
+
+            if response.is_truncated.unwrap_or(false) {
+                continuation_token = response.next_continuation_token;
+            } else {
+                break;
+            }
+        }
+
+        Ok(objects)
+    }
+
     async fn list(&self, prefix: &str) -> Result<Vec<String>> {
         let full_prefix = self.full_key(prefix);
         let mut keys = Vec::new();
@@ -2,12 +2,72 @@
 //!
 //! Simplified writer that ONLY buffers records in memory.
 //! S3 archival is handled separately by async archive jobs.
+//!
+//! Buffered bytes across every writer in the process are counted against an
+//! optional memory budget. When a write would go over it, the writer spills
+//! its largest buffer to a file only the server's user can read, under the
+//! data directory, and `collect_records` reads the spilled records back in
+//! order. Spill files don't outlive the process: the server sweeps any left
+//! behind by a crash when it starts.
 
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::error::{Error, Result};
+use crate::jobs::dedup::natural_id;
+use crate::storage::redaction::Redactor;
+
+/// Default directory for spilled buffers, overridden by `STREAM_WRITER_SPILL_PATH`
+pub const DEFAULT_SPILL_PATH: &str = "./data/spill";
+
+/// Serialized bytes held in memory by every StreamWriter in the process
+static BUFFERED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// Serialized bytes held in memory by every StreamWriter in the process
+pub fn buffered_bytes_total() -> usize {
+    BUFFERED_BYTES.load(Ordering::Relaxed)
+}
+
+/// Directory spilled buffers are written to
+pub fn spill_dir_from_env() -> PathBuf {
+    std::env::var("STREAM_WRITER_SPILL_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(DEFAULT_SPILL_PATH))
+}
 
-use crate::error::Result;
+/// Remove spill files left behind by a previous process
+///
+/// Their records were never archived; the syncs that wrote them run again.
+/// Returns how many files were removed.
+pub fn sweep_spill_dir(dir: &Path) -> std::io::Result<usize> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    let mut removed = 0;
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "jsonl") {
+            std::fs::remove_file(&path)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Records of a buffer that were moved to disk to stay within the budget
+struct SpillFile {
+    path: PathBuf,
+    records: usize,
+    bytes: usize,
+}
 
 /// Buffer for a single stream
 ///
@@ -17,6 +77,12 @@ struct StreamBuffer {
     records: Vec<Value>,
     min_timestamp: Option<DateTime<Utc>>,
     max_timestamp: Option<DateTime<Utc>>,
+    /// Serialized size of the records held in memory
+    bytes: usize,
+    /// Older records spilled to disk, which come before `records`
+    spill: Option<SpillFile>,
+    /// When the oldest buffered record was written
+    first_buffered_at: DateTime<Utc>,
 }
 
 impl StreamBuffer {
@@ -25,10 +91,32 @@ impl StreamBuffer {
             records: Vec::new(),
             min_timestamp: None,
             max_timestamp: None,
+            bytes: 0,
+            spill: None,
+            first_buffered_at: Utc::now(),
         }
     }
 
-    fn add_record(&mut self, record: Value, timestamp: Option<DateTime<Utc>>) {
+    fn is_empty(&self) -> bool {
+        self.records.is_empty() && self.spill.is_none()
+    }
+
+    fn record_count(&self) -> usize {
+        self.records.len() + self.spill.as_ref().map_or(0, |s| s.records)
+    }
+
+    fn total_bytes(&self) -> usize {
+        self.bytes + self.spill.as_ref().map_or(0, |s| s.bytes)
+    }
+
+    /// Buffer a record whose serialized size is `size`
+    fn add_record(&mut self, record: Value, size: usize, timestamp: Option<DateTime<Utc>>) {
+        if self.is_empty() {
+            self.first_buffered_at = Utc::now();
+        }
+        self.bytes += size;
+        BUFFERED_BYTES.fetch_add(size, Ordering::Relaxed);
+
         // Update timestamp range
         if let Some(ts) = timestamp {
             self.min_timestamp = Some(match self.min_timestamp {
@@ -45,11 +133,145 @@ impl StreamBuffer {
 
         self.records.push(record);
     }
+
+    /// Append the in-memory records to the spill file in `dir`, freeing
+    /// their memory
+    fn spill_to_disk(&mut self, dir: &Path) -> std::io::Result<()> {
+        if self.records.is_empty() {
+            return Ok(());
+        }
+
+        let spill = match self.spill.take() {
+            Some(spill) => spill,
+            None => {
+                create_private_dir(dir)?;
+                SpillFile {
+                    path: dir.join(format!("{}.jsonl", uuid::Uuid::new_v4())),
+                    records: 0,
+                    bytes: 0,
+                }
+            }
+        };
+        // Put it back before writing so a failed write still gets cleaned up
+        let spill = self.spill.insert(spill);
+
+        let mut options = std::fs::OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let file = options.open(&spill.path)?;
+        let spilled_len = file.metadata()?.len();
+        let mut out = BufWriter::new(&file);
+        let written = self.records.iter().try_for_each(|record| {
+            serde_json::to_writer(&mut out, record)?;
+            out.write_all(b"\n")
+        });
+        if let Err(e) = written.and_then(|()| out.flush()) {
+            // Drop the partial write so the records aren't read back twice
+            drop(out);
+            let _ = file.set_len(spilled_len);
+            return Err(e);
+        }
+
+        spill.records += self.records.len();
+        spill.bytes += self.bytes;
+        self.records = Vec::new();
+        BUFFERED_BYTES.fetch_sub(self.bytes, Ordering::Relaxed);
+        self.bytes = 0;
+        Ok(())
+    }
+
+    /// All buffered records in write order, spilled ones first
+    fn take_records(&mut self) -> std::io::Result<Vec<Value>> {
+        let mut records = Vec::with_capacity(self.record_count());
+        if let Some(spill) = &self.spill {
+            let file = std::fs::File::open(&spill.path)?;
+            for line in BufReader::new(file).lines() {
+                records.push(serde_json::from_str(&line?)?);
+            }
+        }
+        records.append(&mut self.records);
+        Ok(records)
+    }
+}
+
+/// Create `dir` readable only by the current user
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+impl Drop for StreamBuffer {
+    fn drop(&mut self) {
+        BUFFERED_BYTES.fetch_sub(self.bytes, Ordering::Relaxed);
+        if let Some(spill) = &self.spill {
+            let _ = std::fs::remove_file(&spill.path);
+        }
+    }
+}
+
+/// Records buffered for one stream and not yet collected
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingBuffer {
+    pub source_id: String,
+    pub stream_name: String,
+    pub records: usize,
+    /// Serialized size of the buffered records
+    pub bytes: usize,
+    pub first_buffered_at: DateTime<Utc>,
 }
 
+/// Top-level field holding a record's deterministic id
+pub const RECORD_ID_FIELD: &str = "_record_id";
+
+/// Top-level field holding the name of the stream a record was written to
+pub const STREAM_FIELD: &str = "_stream";
+
+/// Top-level field holding the version of the record shape a stream wrote
+pub const SCHEMA_VERSION_FIELD: &str = "_schema_version";
+
+/// Schema version stamped for streams that don't declare one
+pub const DEFAULT_SCHEMA_VERSION: u32 = 1;
+
+/// Schema version reported for records archived before versions were stamped
+///
+/// Their shape has to be inferred from the fields present.
+pub const UNVERSIONED_SCHEMA: u32 = 0;
+
+/// The schema version a record was written with
+pub fn schema_version(record: &Value) -> u32 {
+    record
+        .get(SCHEMA_VERSION_FIELD)
+        .and_then(|v| v.as_u64())
+        .and_then(|v| u32::try_from(v).ok())
+        .unwrap_or(UNVERSIONED_SCHEMA)
+}
+
+/// Default in-flight memory budget for writers created with `from_env`
+pub const DEFAULT_MEMORY_BUDGET_MB: usize = 512;
+
 /// In-memory stream writer for direct transform architecture
 pub struct StreamWriter {
     buffers: HashMap<String, StreamBuffer>,
+    /// Natural id field of each stream's records, by buffer key
+    id_keys: HashMap<String, &'static str>,
+    /// Record shape version of each stream, by buffer key
+    schema_versions: HashMap<String, u32>,
+    /// Redaction rules of each stream, by buffer key
+    redactors: HashMap<String, Redactor>,
+    /// Buffer keys of streams whose records are sealed envelopes
+    sealed: HashSet<String>,
+    /// Reject records without an event timestamp instead of buffering them
+    strict_timestamps: bool,
+    /// Cap on `buffered_bytes_total()` before buffers are spilled to disk
+    memory_budget: Option<usize>,
+    /// Directory buffers are spilled to
+    spill_dir: PathBuf,
 }
 
 impl StreamWriter {
@@ -57,12 +279,151 @@ impl StreamWriter {
     pub fn new() -> Self {
         Self {
             buffers: HashMap::new(),
+            id_keys: HashMap::new(),
+            schema_versions: HashMap::new(),
+            redactors: HashMap::new(),
+            sealed: HashSet::new(),
+            strict_timestamps: false,
+            memory_budget: None,
+            spill_dir: PathBuf::from(DEFAULT_SPILL_PATH),
         }
     }
 
+    /// Create a stream writer configured from the environment
+    ///
+    /// Strict timestamp mode is enabled with `STREAM_WRITER_STRICT_TIMESTAMPS=true`.
+    /// The memory budget is `STREAM_WRITER_MEMORY_BUDGET_MB` (default
+    /// `DEFAULT_MEMORY_BUDGET_MB`; `0` disables it), and buffers over it are
+    /// spilled to `STREAM_WRITER_SPILL_PATH` (default `DEFAULT_SPILL_PATH`).
+    pub fn from_env() -> Self {
+        let strict = std::env::var("STREAM_WRITER_STRICT_TIMESTAMPS")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        let budget_mb = std::env::var("STREAM_WRITER_MEMORY_BUDGET_MB")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_MEMORY_BUDGET_MB);
+        Self::new()
+            .with_strict_timestamps(strict)
+            .with_memory_budget((budget_mb > 0).then(|| budget_mb * 1024 * 1024))
+            .with_spill_dir(spill_dir_from_env())
+    }
+
+    /// Enable or disable strict timestamp mode
+    ///
+    /// In strict mode `write_record` returns an error for records without an
+    /// event timestamp, so callers count them as failed rather than letting a
+    /// guessed time land them in the wrong date partition.
+    pub fn with_strict_timestamps(mut self, strict: bool) -> Self {
+        self.strict_timestamps = strict;
+        self
+    }
+
+    /// Cap the bytes buffered in memory across all writers
+    ///
+    /// The budget is shared by every writer in the process, so concurrent
+    /// syncs can't buffer more than this between them. A write that would go
+    /// over it first spills this writer's largest buffer to disk. `None`
+    /// leaves buffering unbounded.
+    pub fn with_memory_budget(mut self, bytes: Option<usize>) -> Self {
+        self.memory_budget = bytes;
+        self
+    }
+
+    /// Spill buffers over the memory budget to files in `dir`
+    pub fn with_spill_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.spill_dir = dir.into();
+        self
+    }
+
+    /// Use `key` as the natural id field of a stream's records
+    ///
+    /// Registered from the stream's registry `dedup_key` when the stream is
+    /// created; see `write_record` for how the id is used.
+    pub fn register_id_key(&mut self, source_id: &str, stream_name: &str, key: &'static str) {
+        let buffer_key = format!("{}:{}", source_id, stream_name);
+        self.id_keys.insert(buffer_key, key);
+    }
+
+    /// Stamp a stream's records with `version` as their `_schema_version`
+    ///
+    /// Registered from the stream's registry `schema_version` when the stream
+    /// is created. Streams without one are stamped `DEFAULT_SCHEMA_VERSION`.
+    pub fn register_schema_version(&mut self, source_id: &str, stream_name: &str, version: u32) {
+        let buffer_key = format!("{}:{}", source_id, stream_name);
+        self.schema_versions.insert(buffer_key, version);
+    }
+
+    /// Redact a stream's records with `redactor` before they are buffered
+    ///
+    /// Registered from the stream's `redaction` config when the stream is
+    /// created; `None` clears rules registered earlier.
+    pub fn register_redaction(
+        &mut self,
+        source_id: &str,
+        stream_name: &str,
+        redactor: Option<Redactor>,
+    ) {
+        let buffer_key = format!("{}:{}", source_id, stream_name);
+        match redactor {
+            Some(redactor) => self.redactors.insert(buffer_key, redactor),
+            None => self.redactors.remove(&buffer_key),
+        };
+    }
+
+    /// Buffer a stream's records exactly as received
+    ///
+    /// Registered for end-to-end encrypted streams, whose records are sealed
+    /// envelopes (see `sources::envelope`): they are neither redacted nor
+    /// stamped, since an envelope carries only its own fields.
+    pub fn register_sealed(&mut self, source_id: &str, stream_name: &str, sealed: bool) {
+        let buffer_key = format!("{}:{}", source_id, stream_name);
+        if sealed {
+            self.sealed.insert(buffer_key);
+        } else {
+            self.sealed.remove(&buffer_key);
+        }
+    }
+
+    /// Redact a value shaped like one of a stream's records with its rules
+    ///
+    /// For content a stream writes to storage itself rather than through
+    /// `write_record`; a no-op when the stream has no rules.
+    pub fn redact(&self, source_id: &str, stream_name: &str, record: &mut Value) {
+        let buffer_key = format!("{}:{}", source_id, stream_name);
+        if let Some(redactor) = self.redactors.get(&buffer_key) {
+            redactor.apply(record);
+        }
+    }
+
+    /// Whether a stream's rules drop or hash `field` of its records
+    pub fn redacts_field(&self, source_id: &str, stream_name: &str, field: &str) -> bool {
+        let buffer_key = format!("{}:{}", source_id, stream_name);
+        self.redactors
+            .get(&buffer_key)
+            .is_some_and(|redactor| redactor.covers_field(field))
+    }
+
     /// Write a record to in-memory buffer
     ///
     /// Records accumulate in memory until extracted via `collect_records()`.
+    /// `timestamp` is the record's event time; pass `None` when it couldn't be
+    /// determined rather than substituting the current time.
+    ///
+    /// Object records are stamped with `_stream` and a deterministic
+    /// `_record_id`: the stream's natural id when one is registered and
+    /// present, otherwise a SHA-256 of the record's content. A record that
+    /// already carries `_record_id` (e.g. one replayed from the archive) keeps
+    /// it. The natural id fields themselves are left in place.
+    ///
+    /// The stream's redaction rules, if registered, are applied first, so
+    /// the id is derived from the redacted record.
+    ///
+    /// Object records are also stamped with the stream's `_schema_version`,
+    /// unless they already carry one: a replayed record keeps the version of
+    /// the shape it was written in, so transforms can tell old shapes apart.
+    ///
+    /// Records of a stream registered as sealed skip redaction and stamping.
     pub fn write_record(
         &mut self,
         source_id: &str,
@@ -70,41 +431,216 @@ impl StreamWriter {
         record: Value,
         timestamp: Option<DateTime<Utc>>,
     ) -> Result<()> {
+        self.check_record(source_id, stream_name, timestamp)?;
+
         let buffer_key = format!("{}:{}", source_id, stream_name);
 
+        let mut record = record;
+        if !self.sealed.contains(&buffer_key) {
+            self.redact_and_stamp(&buffer_key, stream_name, &mut record);
+        }
+
+        let size = record.to_string().len();
+        if let Some(budget) = self.memory_budget {
+            if buffered_bytes_total() + size > budget {
+                self.spill_largest_buffer();
+            }
+        }
+
         let buffer = self
             .buffers
             .entry(buffer_key)
             .or_insert_with(StreamBuffer::new);
 
-        buffer.add_record(record, timestamp);
+        buffer.add_record(record, size, timestamp);
         Ok(())
     }
 
+    /// Whether `write_record` would accept a record with this event time
+    fn check_record(
+        &self,
+        source_id: &str,
+        stream_name: &str,
+        timestamp: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        if self.strict_timestamps && timestamp.is_none() {
+            return Err(Error::InvalidInput(format!(
+                "Record for {}:{} has no valid event timestamp",
+                source_id, stream_name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Apply a stream's redaction rules, then stamp its bookkeeping fields
+    fn redact_and_stamp(&self, buffer_key: &str, stream_name: &str, record: &mut Value) {
+        if let Some(redactor) = self.redactors.get(buffer_key) {
+            redactor.apply(record);
+        }
+        if record.is_object() && record.get(RECORD_ID_FIELD).is_none() {
+            let record_id = self
+                .id_keys
+                .get(buffer_key)
+                .and_then(|key| natural_id(record, key))
+                .unwrap_or_else(|| content_id(record));
+            record[RECORD_ID_FIELD] = Value::String(record_id);
+        }
+        if let Value::Object(fields) = record {
+            fields.insert(
+                STREAM_FIELD.to_string(),
+                Value::String(stream_name.to_string()),
+            );
+            if !fields.contains_key(SCHEMA_VERSION_FIELD) {
+                let version = self
+                    .schema_versions
+                    .get(buffer_key)
+                    .copied()
+                    .unwrap_or(DEFAULT_SCHEMA_VERSION);
+                fields.insert(SCHEMA_VERSION_FIELD.to_string(), Value::from(version));
+            }
+        }
+    }
+
+    /// Spill the buffer holding the most memory to disk
+    ///
+    /// A failed spill is logged and the records stay in memory: going over
+    /// the budget is better than dropping them.
+    fn spill_largest_buffer(&mut self) {
+        let Some((key, buffer)) = self
+            .buffers
+            .iter_mut()
+            .filter(|(_, b)| b.bytes > 0)
+            .max_by_key(|(_, b)| b.bytes)
+        else {
+            return;
+        };
+
+        let bytes = buffer.bytes;
+        match buffer.spill_to_disk(&self.spill_dir) {
+            Ok(()) => tracing::debug!(buffer = %key, bytes, "Spilled stream buffer to disk"),
+            Err(e) => tracing::warn!(
+                buffer = %key,
+                bytes,
+                error = %e,
+                "Failed to spill stream buffer over memory budget"
+            ),
+        }
+    }
+
+    /// Write a batch of record groups to the in-memory buffer
+    ///
+    /// A group is the records one pushed record expands into, and is written
+    /// whole or not at all: if `write_record` would refuse any of them, none
+    /// is buffered. Groups are independent, so one that is refused doesn't
+    /// stop the rest of the batch. Returns one result per group, in input
+    /// order.
+    pub fn write_records<I>(
+        &mut self,
+        source_id: &str,
+        stream_name: &str,
+        groups: I,
+    ) -> Vec<Result<()>>
+    where
+        I: IntoIterator<Item = Vec<(Value, Option<DateTime<Utc>>)>>,
+    {
+        groups
+            .into_iter()
+            .map(|group| {
+                group.iter().try_for_each(|(_, timestamp)| {
+                    self.check_record(source_id, stream_name, *timestamp)
+                })?;
+                group.into_iter().try_for_each(|(record, timestamp)| {
+                    self.write_record(source_id, stream_name, record, timestamp)
+                })
+            })
+            .collect()
+    }
+
     /// Collect all buffered records for a stream and clear the buffer
     ///
-    /// Returns: (records, min_timestamp, max_timestamp)
+    /// Returns: (records, min_timestamp, max_timestamp), or `None` when
+    /// nothing is buffered. If spilled records can't be read back, the error
+    /// is returned and the buffer is kept, so the records aren't lost.
     pub fn collect_records(
         &mut self,
         source_id: &str,
         stream_name: &str,
-    ) -> Option<(Vec<Value>, Option<DateTime<Utc>>, Option<DateTime<Utc>>)> {
+    ) -> Result<Option<(Vec<Value>, Option<DateTime<Utc>>, Option<DateTime<Utc>>)>> {
         let buffer_key = format!("{}:{}", source_id, stream_name);
 
-        self.buffers.remove(&buffer_key).and_then(|buffer| {
-            if buffer.records.is_empty() {
-                None
-            } else {
-                Some((buffer.records, buffer.min_timestamp, buffer.max_timestamp))
+        let Some(mut buffer) = self.buffers.remove(&buffer_key) else {
+            return Ok(None);
+        };
+        if buffer.is_empty() {
+            return Ok(None);
+        }
+        match buffer.take_records() {
+            Ok(records) => Ok(Some((records, buffer.min_timestamp, buffer.max_timestamp))),
+            Err(e) => {
+                let error = Error::Storage(format!(
+                    "Failed to read {} spilled records of {buffer_key}: {e}",
+                    buffer.record_count()
+                ));
+                self.buffers.insert(buffer_key, buffer);
+                Err(error)
             }
-        })
+        }
+    }
+
+    /// Drop a stream's buffered records without reading them back
+    ///
+    /// Returns how many records were dropped.
+    pub fn discard_records(&mut self, source_id: &str, stream_name: &str) -> usize {
+        let buffer_key = format!("{}:{}", source_id, stream_name);
+        self.buffers
+            .remove(&buffer_key)
+            .map_or(0, |b| b.record_count())
     }
 
     /// Get record count for a stream (for monitoring)
     pub fn buffer_count(&self, source_id: &str, stream_name: &str) -> usize {
         let buffer_key = format!("{}:{}", source_id, stream_name);
-        self.buffers.get(&buffer_key).map_or(0, |b| b.records.len())
+        self.buffers
+            .get(&buffer_key)
+            .map_or(0, |b| b.record_count())
+    }
+
+    /// Serialized size of a stream's buffered records (for monitoring)
+    ///
+    /// Includes records spilled to disk.
+    pub fn pending_bytes(&self, source_id: &str, stream_name: &str) -> usize {
+        let buffer_key = format!("{}:{}", source_id, stream_name);
+        self.buffers.get(&buffer_key).map_or(0, |b| b.total_bytes())
     }
+
+    /// Summary of a stream's buffered records, if it has any
+    pub fn pending_buffer(&self, source_id: &str, stream_name: &str) -> Option<PendingBuffer> {
+        let buffer_key = format!("{}:{}", source_id, stream_name);
+        self.buffers
+            .get(&buffer_key)
+            .filter(|b| !b.is_empty())
+            .map(|b| PendingBuffer {
+                source_id: source_id.to_string(),
+                stream_name: stream_name.to_string(),
+                records: b.record_count(),
+                bytes: b.total_bytes(),
+                first_buffered_at: b.first_buffered_at,
+            })
+    }
+
+    /// Summaries of every stream with buffered records
+    pub fn pending_buffers(&self) -> Vec<PendingBuffer> {
+        self.buffers
+            .keys()
+            .filter_map(|key| key.split_once(':'))
+            .filter_map(|(source_id, stream_name)| self.pending_buffer(source_id, stream_name))
+            .collect()
+    }
+}
+
+/// Deterministic id of a record without a natural id: SHA-256 of its content
+fn content_id(record: &Value) -> String {
+    hex::encode(Sha256::digest(record.to_string().as_bytes()))
 }
 
 impl Default for StreamWriter {
@@ -136,7 +672,7 @@ mod tests {
         assert_eq!(writer.buffer_count(source_id, stream_name), 2);
 
         // Collect records
-        let result = writer.collect_records(source_id, stream_name);
+        let result = writer.collect_records(source_id, stream_name).unwrap();
         assert!(result.is_some());
 
         let (records, _, _) = result.unwrap();
@@ -162,8 +698,295 @@ mod tests {
             .write_record(source_id, stream_name, json!({"value": 2}), Some(ts1))
             .unwrap();
 
-        let result = writer.collect_records(source_id, stream_name).unwrap();
+        let result = writer
+            .collect_records(source_id, stream_name)
+            .unwrap()
+            .unwrap();
         assert_eq!(result.1, Some(ts1)); // min
         assert_eq!(result.2, Some(ts2)); // max
     }
+
+    #[test]
+    fn test_strict_timestamps_reject_missing_event_time() {
+        let mut writer = StreamWriter::new().with_strict_timestamps(true);
+        let source_id = "test-source";
+        let stream_name = "test_stream";
+
+        let err = writer
+            .write_record(source_id, stream_name, json!({"value": 1}), None)
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidInput(_)));
+        assert_eq!(writer.buffer_count(source_id, stream_name), 0);
+
+        writer
+            .write_record(
+                source_id,
+                stream_name,
+                json!({"value": 2}),
+                Some(Utc::now()),
+            )
+            .unwrap();
+        assert_eq!(writer.buffer_count(source_id, stream_name), 1);
+    }
+
+    #[test]
+    fn test_write_records_isolates_failures() {
+        let mut writer = StreamWriter::new().with_strict_timestamps(true);
+        let source_id = "test-source";
+        let stream_name = "test_stream";
+
+        // The second group fails on its last record, so its first isn't kept
+        let results = writer.write_records(
+            source_id,
+            stream_name,
+            vec![
+                vec![(json!({"value": 1}), Some(Utc::now()))],
+                vec![
+                    (json!({"value": 2}), Some(Utc::now())),
+                    (json!({"value": 2.5}), None),
+                ],
+                vec![(json!({"value": 3}), Some(Utc::now()))],
+            ],
+        );
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert!(results[2].is_ok());
+
+        let (records, _, _) = writer
+            .collect_records(source_id, stream_name)
+            .unwrap()
+            .unwrap();
+        let values: Vec<&Value> = records.iter().map(|r| &r["value"]).collect();
+        assert_eq!(values, vec![&json!(1), &json!(3)]);
+    }
+
+    #[test]
+    fn test_records_stamped_with_id_and_stream() {
+        let mut writer = StreamWriter::new();
+        let source_id = "test-source";
+        writer.register_id_key(source_id, "gmail", "message_id");
+
+        writer
+            .write_record(source_id, "gmail", json!({"message_id": "m1"}), None)
+            .unwrap();
+        writer
+            .write_record(source_id, "gmail", json!({"subject": "no id"}), None)
+            .unwrap();
+        writer
+            .write_record(source_id, "gmail", json!({"_record_id": "kept"}), None)
+            .unwrap();
+        writer
+            .write_record(source_id, "other", json!({"subject": "no id"}), None)
+            .unwrap();
+
+        let (gmail, _, _) = writer.collect_records(source_id, "gmail").unwrap().unwrap();
+        assert_eq!(gmail[0][RECORD_ID_FIELD], "m1");
+        assert_eq!(gmail[0]["message_id"], "m1");
+        assert_eq!(gmail[0][STREAM_FIELD], "gmail");
+        assert_eq!(gmail[2][RECORD_ID_FIELD], "kept");
+
+        // Without a natural id the content hash is used, independent of stream
+        let (other, _, _) = writer.collect_records(source_id, "other").unwrap().unwrap();
+        let content_hash = gmail[1][RECORD_ID_FIELD].as_str().unwrap();
+        assert_eq!(content_hash.len(), 64);
+        assert_eq!(other[0][RECORD_ID_FIELD], content_hash);
+        assert_eq!(other[0][STREAM_FIELD], "other");
+    }
+
+    #[test]
+    fn test_records_stamped_with_schema_version() {
+        let mut writer = StreamWriter::new();
+        let source_id = "test-source";
+        writer.register_schema_version(source_id, "gmail", 3);
+
+        writer
+            .write_record(source_id, "gmail", json!({"message_id": "m1"}), None)
+            .unwrap();
+        writer
+            .write_record(source_id, "gmail", json!({"_schema_version": 1}), None)
+            .unwrap();
+        writer
+            .write_record(source_id, "other", json!({"value": 1}), None)
+            .unwrap();
+
+        let (gmail, _, _) = writer.collect_records(source_id, "gmail").unwrap().unwrap();
+        assert_eq!(schema_version(&gmail[0]), 3);
+        // Replayed records keep the version they were written with
+        assert_eq!(schema_version(&gmail[1]), 1);
+
+        let (other, _, _) = writer.collect_records(source_id, "other").unwrap().unwrap();
+        assert_eq!(schema_version(&other[0]), DEFAULT_SCHEMA_VERSION);
+        assert_eq!(schema_version(&json!({"value": 1})), UNVERSIONED_SCHEMA);
+    }
+
+    #[test]
+    fn test_records_redacted_before_buffering() {
+        use crate::storage::redaction::{RedactionConfig, HASH_PREFIX};
+
+        let mut writer = StreamWriter::new();
+        let config = RedactionConfig {
+            drop: vec!["$.body".to_string()],
+            hash: vec!["$.from".to_string()],
+        };
+        writer.register_redaction(
+            "src",
+            "gmail",
+            Some(Redactor::new(&config, b"test-key").unwrap()),
+        );
+
+        let record = json!({"id": "m1", "from": "alice@example.com", "body": "secret"});
+        writer
+            .write_record("src", "gmail", record.clone(), None)
+            .unwrap();
+        writer.register_redaction("src", "gmail", None);
+        writer.write_record("src", "gmail", record, None).unwrap();
+
+        let (records, _, _) = writer.collect_records("src", "gmail").unwrap().unwrap();
+        assert!(records[0].get("body").is_none());
+        let from = records[0]["from"].as_str().unwrap();
+        assert!(from.starts_with(HASH_PREFIX));
+        assert_eq!(records[1]["body"], "secret");
+    }
+
+    #[test]
+    fn test_sealed_records_buffered_as_received() {
+        use crate::sources::envelope::Envelope;
+        use crate::storage::redaction::RedactionConfig;
+
+        let mut writer = StreamWriter::new();
+        let config = RedactionConfig {
+            drop: vec!["$.ct".to_string()],
+            hash: vec![],
+        };
+        writer.register_redaction(
+            "src",
+            "imessage",
+            Some(Redactor::new(&config, b"test-key").unwrap()),
+        );
+        writer.register_sealed("src", "imessage", true);
+
+        let sealed = Envelope::seal(
+            &json!({"text": "hello"}),
+            "imessage",
+            "k1",
+            &[7; 32],
+            crate::sources::envelope::EnvelopeAlgorithm::Aes256Gcm,
+            "2024-01-15T10:30:00Z",
+        )
+        .unwrap();
+        let pushed = serde_json::to_value(&sealed).unwrap();
+        writer
+            .write_record("src", "imessage", pushed.clone(), None)
+            .unwrap();
+
+        let (records, _, _) = writer.collect_records("src", "imessage").unwrap().unwrap();
+        assert_eq!(records[0], pushed);
+        assert!(Envelope::parse(&records[0]).is_ok());
+    }
+
+    #[test]
+    fn test_pending_bytes() {
+        let mut writer = StreamWriter::new();
+        let source_id = "test-source";
+        let stream_name = "test_stream";
+        assert_eq!(writer.pending_bytes(source_id, stream_name), 0);
+        assert!(writer.pending_buffer(source_id, stream_name).is_none());
+
+        let record = json!({"value": "hello"});
+        writer
+            .write_record(source_id, stream_name, record.clone(), None)
+            .unwrap();
+        let size = writer.pending_bytes(source_id, stream_name);
+        assert!(size > record.to_string().len());
+        writer
+            .write_record(source_id, stream_name, record, None)
+            .unwrap();
+        assert_eq!(writer.pending_bytes(source_id, stream_name), 2 * size);
+
+        let pending = writer.pending_buffer(source_id, stream_name).unwrap();
+        assert_eq!(pending.records, 2);
+        assert_eq!(pending.bytes, 2 * size);
+        assert_eq!(writer.pending_buffers(), vec![pending]);
+
+        writer.collect_records(source_id, stream_name).unwrap();
+        assert_eq!(writer.pending_bytes(source_id, stream_name), 0);
+    }
+
+    #[test]
+    fn test_memory_budget_spills_largest_buffer() {
+        // The budget counts every writer in the process, so a zero budget
+        // spills on each write no matter what other tests buffer
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = StreamWriter::new()
+            .with_memory_budget(Some(0))
+            .with_spill_dir(dir.path());
+        let source_id = "test-source";
+
+        for i in 0..5 {
+            writer
+                .write_record(
+                    source_id,
+                    "big",
+                    json!({"i": i, "pad": "x".repeat(100)}),
+                    None,
+                )
+                .unwrap();
+        }
+        writer
+            .write_record(source_id, "small", json!({"i": 0}), None)
+            .unwrap();
+
+        // The big buffer was spilled before the small write was accepted
+        assert_eq!(writer.buffer_count(source_id, "big"), 5);
+        assert_eq!(writer.buffers[&format!("{source_id}:big")].bytes, 0);
+        let pending = writer.pending_buffer(source_id, "big").unwrap();
+        assert_eq!(pending.records, 5);
+        assert!(pending.bytes > 500);
+
+        // Spilled to a file only the server's user can read
+        let spilled: Vec<PathBuf> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(spilled.len(), 1);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&spilled[0]).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let (records, _, _) = writer.collect_records(source_id, "big").unwrap().unwrap();
+        let order: Vec<i64> = records.iter().map(|r| r["i"].as_i64().unwrap()).collect();
+        assert_eq!(order, vec![0, 1, 2, 3, 4]);
+        assert_eq!(writer.buffer_count(source_id, "small"), 1);
+    }
+
+    #[test]
+    fn test_unreadable_spill_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = StreamWriter::new()
+            .with_memory_budget(Some(0))
+            .with_spill_dir(dir.path());
+
+        for i in 0..2 {
+            writer
+                .write_record("src", "big", json!({"i": i}), None)
+                .unwrap();
+        }
+        for entry in std::fs::read_dir(dir.path()).unwrap() {
+            std::fs::write(entry.unwrap().path(), "not json\n").unwrap();
+        }
+
+        // The buffer is kept for another attempt rather than dropped
+        assert!(writer.collect_records("src", "big").is_err());
+        assert_eq!(writer.buffer_count("src", "big"), 2);
+        assert_eq!(writer.discard_records("src", "big"), 2);
+        assert!(writer.collect_records("src", "big").unwrap().is_none());
+
+        // Files left by a previous process are swept
+        std::fs::write(dir.path().join("left-behind.jsonl"), "{}\n").unwrap();
+        assert_eq!(sweep_spill_dir(dir.path()).unwrap(), 1);
+        assert_eq!(sweep_spill_dir(&dir.path().join("missing")).unwrap(), 0);
+    }
 }
@@ -11,7 +11,7 @@ use super::{PageEditorTool, SemanticSearchTool, SqlQueryTool, WebSearchTool};
 use crate::server::yjs::YjsState;
 
 /// Context provided to tools during execution
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolContext {
     /// Current page ID (for edit_page tool)
     pub page_id: Option<String>,
@@ -28,12 +28,14 @@ mod web_search;
 mod sql_query;
 mod page_editor;
 mod semantic_search;
+mod validation;
 
 pub use executor::{ToolExecutor, ToolContext, ToolResult, ToolError};
 pub use web_search::WebSearchTool;
 pub use sql_query::SqlQueryTool;
 pub use page_editor::PageEditorTool;
 pub use semantic_search::SemanticSearchTool;
+pub use validation::{validate_against_schema, validate_tool_arguments};
 
 /// Get tool definitions for the LLM (OpenAI/Anthropic format)
 ///
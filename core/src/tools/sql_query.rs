@@ -3,11 +3,13 @@
 //! Provides read-only SQL access to user's personal data tables.
 
 use serde::{Deserialize, Serialize};
-use sqlx::{Column, Row, SqlitePool, TypeInfo};
+use sqlx::{Row, SqlitePool};
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use super::executor::{ToolError, ToolResult};
+use crate::database::read_only;
+use crate::mcp::tools::convert_rows_to_json;
 
 /// Table metadata for get_schema operation
 #[derive(Debug, Clone, Serialize)]
@@ -82,7 +84,7 @@ fn get_table_metadata() -> HashMap<&'static str, TableMetadata> {
         join_hint: Some("JOIN wiki_people ON from_person_id = wiki_people.id"),
     });
     m.insert("data_communication_message", TableMetadata {
-        description: "Chat messages (iMessage, SMS, etc.)",
+        description: "Chat messages (iMessage, SMS, Slack, etc.)",
         category: "communication",
         key_columns: &["body", "channel", "from_identifier", "from_name", "to_identifiers", "is_read", "is_group_message", "has_attachments", "thread_id", "timestamp"],
         join_hint: Some("JOIN wiki_people ON from_person_id = wiki_people.id"),
@@ -466,26 +468,15 @@ impl SqlQueryTool {
     }
 
     /// Execute a read-only SQL query
+    ///
+    /// The statement is inspected up front and then run with SQLite's
+    /// `query_only` guard (see `database::read_only`).
     async fn execute_query(&self, sql: &str, limit: u32) -> Result<ToolResult, ToolError> {
         // Validate query is read-only
-        let sql_lower = sql.trim().to_lowercase();
-
-        if !sql_lower.starts_with("select") && !sql_lower.starts_with("with") {
-            return Err(ToolError::InvalidParameters(
-                "Only SELECT queries are allowed".into(),
-            ));
-        }
-
-        // Check for dangerous keywords
-        let forbidden = ["insert", "update", "delete", "drop", "create", "alter", "truncate"];
-        for keyword in &forbidden {
-            if sql_lower.contains(keyword) {
-                return Err(ToolError::InvalidParameters(format!(
-                    "Query contains forbidden keyword: {}",
-                    keyword
-                )));
-            }
-        }
+        read_only::ensure_read_only(sql)
+            .map_err(|e| ToolError::InvalidParameters(e.to_string()))?;
+        let sql = sql.trim().trim_end_matches(';');
+        let sql_lower = sql.to_lowercase();
 
         // Validate query length
         if sql.len() > 5000 {
@@ -502,10 +493,9 @@ impl SqlQueryTool {
         };
 
         // Execute query
-        let rows = sqlx::query(&query)
-            .fetch_all(self.pool.as_ref())
+        let rows = read_only::fetch_read_only(self.pool.as_ref(), &query)
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Query failed: {}", e)))?;
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
 
         // Convert rows to JSON
         let json_rows = convert_rows_to_json(&rows);
@@ -518,57 +508,6 @@ impl SqlQueryTool {
     }
 }
 
-/// Convert SQLite rows to JSON array
-fn convert_rows_to_json(rows: &[sqlx::sqlite::SqliteRow]) -> Vec<serde_json::Value> {
-    let mut json_rows = Vec::new();
-
-    for row in rows {
-        let mut obj = serde_json::Map::new();
-
-        for (i, col) in row.columns().iter().enumerate() {
-            let col_name = col.name();
-
-            let value: serde_json::Value = 
-                // Try NULL first
-                if let Ok(opt_val) = row.try_get::<Option<String>, _>(i) {
-                    match opt_val {
-                        Some(v) => serde_json::Value::String(v),
-                        None => serde_json::Value::Null,
-                    }
-                }
-                // Integer types
-                else if let Ok(v) = row.try_get::<i64, _>(i) {
-                    serde_json::Value::Number(v.into())
-                } else if let Ok(v) = row.try_get::<i32, _>(i) {
-                    serde_json::Value::Number(v.into())
-                }
-                // Float types
-                else if let Ok(v) = row.try_get::<f64, _>(i) {
-                    serde_json::json!(v)
-                }
-                // Boolean
-                else if let Ok(v) = row.try_get::<bool, _>(i) {
-                    serde_json::Value::Bool(v)
-                }
-                // JSON
-                else if let Ok(v) = row.try_get::<serde_json::Value, _>(i) {
-                    v
-                }
-                // Fallback
-                else {
-                    let type_info = col.type_info().name();
-                    serde_json::Value::String(format!("<{}>", type_info))
-                };
-
-            obj.insert(col_name.to_string(), value);
-        }
-
-        json_rows.push(serde_json::Value::Object(obj));
-    }
-
-    json_rows
-}
-
 impl std::fmt::Debug for SqlQueryTool {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("SqlQueryTool").finish()
@@ -0,0 +1,211 @@
+//! Tool argument validation
+//!
+//! Checks LLM-provided tool arguments against the JSON Schema declared in
+//! the tool registry before execution, so malformed calls fail with a
+//! specific, correctable message instead of an opaque tool error.
+//!
+//! Supports the subset of JSON Schema used by registry tools: `type`,
+//! `required`, `properties`, `items`, `enum`, `minimum` and `maximum`.
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Parameter schemas for built-in tools, keyed by tool ID
+fn tool_schemas() -> &'static HashMap<String, Value> {
+    static SCHEMAS: OnceLock<HashMap<String, Value>> = OnceLock::new();
+    SCHEMAS.get_or_init(|| {
+        virtues_registry::tools::default_tools()
+            .into_iter()
+            .map(|tool| (tool.id, tool.parameters))
+            .collect()
+    })
+}
+
+/// Validate arguments for a tool call against its registry schema
+///
+/// Tools without a registry schema (e.g. MCP tools) are not validated here.
+/// Returns every violation found, phrased for the LLM to act on.
+pub fn validate_tool_arguments(tool_name: &str, arguments: &Value) -> Result<(), Vec<String>> {
+    match tool_schemas().get(tool_name) {
+        Some(schema) => validate_against_schema(schema, arguments),
+        None => Ok(()),
+    }
+}
+
+/// Validate a value against a JSON Schema
+pub fn validate_against_schema(schema: &Value, value: &Value) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    validate_value(schema, value, "", &mut errors);
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn validate_value(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let location = if path.is_empty() {
+        "arguments".to_string()
+    } else {
+        format!("field `{}`", path)
+    };
+
+    if let Some(expected) = schema.get("type").and_then(|t| t.as_str()) {
+        if !matches_type(expected, value) {
+            errors.push(format!(
+                "{} must be of type {} (got {})",
+                location,
+                expected,
+                type_name(value)
+            ));
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(|e| e.as_array()) {
+        if !allowed.contains(value) {
+            let options: Vec<String> = allowed.iter().map(|v| v.to_string()).collect();
+            errors.push(format!(
+                "{} must be one of: {} (got {})",
+                location,
+                options.join(", "),
+                value
+            ));
+        }
+    }
+
+    if let Some(n) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(|m| m.as_f64()) {
+            if n < min {
+                errors.push(format!("{} must be >= {} (got {})", location, min, value));
+            }
+        }
+        if let Some(max) = schema.get("maximum").and_then(|m| m.as_f64()) {
+            if n > max {
+                errors.push(format!("{} must be <= {} (got {})", location, max, value));
+            }
+        }
+    }
+
+    match value {
+        Value::Object(map) => {
+            if let Some(required) = schema.get("required").and_then(|r| r.as_array()) {
+                for field in required.iter().filter_map(|f| f.as_str()) {
+                    if map.get(field).filter(|v| !v.is_null()).is_none() {
+                        errors.push(format!(
+                            "missing required field `{}`",
+                            join_path(path, field)
+                        ));
+                    }
+                }
+            }
+
+            if let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) {
+                for (key, property_schema) in properties {
+                    match map.get(key) {
+                        // Optional fields sent as null are treated as absent
+                        Some(Value::Null) | None => {}
+                        Some(v) => {
+                            validate_value(property_schema, v, &join_path(path, key), errors)
+                        }
+                    }
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    validate_value(item_schema, item, &format!("{}[{}]", path, i), errors);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn matches_type(expected: &str, value: &Value) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "null" => value.is_null(),
+        // Unknown types are not enforced
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["query"],
+            "properties": {
+                "query": { "type": "string" },
+                "limit": { "type": "integer", "minimum": 1 },
+                "mode": { "type": "string", "enum": ["auto", "keyword"] },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            }
+        })
+    }
+
+    #[test]
+    fn test_valid_arguments() {
+        let args = json!({ "query": "coffee", "limit": 5, "mode": "auto", "tags": ["a"] });
+        assert!(validate_against_schema(&schema(), &args).is_ok());
+
+        // Optional fields may be omitted or null
+        let args = json!({ "query": "coffee", "limit": null });
+        assert!(validate_against_schema(&schema(), &args).is_ok());
+    }
+
+    #[test]
+    fn test_reports_all_violations() {
+        let args = json!({ "limit": 0, "mode": "fuzzy", "tags": ["a", 2] });
+        let errors = validate_against_schema(&schema(), &args).unwrap_err();
+
+        assert!(errors.contains(&"missing required field `query`".to_string()));
+        assert!(errors.iter().any(|e| e.contains("`limit` must be >= 1")));
+        assert!(errors.iter().any(|e| e.contains("`mode` must be one of")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("`tags[1]` must be of type string")));
+    }
+
+    #[test]
+    fn test_registry_tools_are_validated() {
+        let errors = validate_tool_arguments("web_search", &json!({})).unwrap_err();
+        assert_eq!(errors, vec!["missing required field `query`".to_string()]);
+
+        // Tools outside the registry (MCP) pass through
+        assert!(validate_tool_arguments("mcp_custom_tool", &json!({})).is_ok());
+    }
+}
@@ -0,0 +1,3 @@
+//! Small utilities shared across modules
+
+pub mod retry;
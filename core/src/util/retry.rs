@@ -0,0 +1,197 @@
+//! Retry with exponential backoff
+//!
+//! One loop for everything that retries a fallible async operation (storage
+//! uploads, provider API requests), so they agree on how waits grow and how
+//! many attempts are made. The caller supplies the operation and a `classify`
+//! function that decides from each error whether another attempt is worth
+//! making; the policy decides how long to wait before it.
+
+use std::future::Future;
+use std::time::Duration;
+
+use rand::Rng;
+
+/// How often and how patiently to retry
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts in all, including the first (at least one is always made)
+    pub max_attempts: u32,
+    /// Wait before the first retry, doubling for each one after
+    pub base: Duration,
+    /// Longest wait between attempts
+    pub max: Duration,
+    /// Fraction of each wait randomly taken off (0.0 to 1.0), so callers
+    /// failing together don't retry in lockstep
+    pub jitter: f64,
+}
+
+impl RetryPolicy {
+    /// Wait before retry number `retry` (0 for the first), without jitter
+    pub fn delay(&self, retry: u32) -> Duration {
+        self.base
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max)
+    }
+
+    /// Wait before retry number `retry`, with jitter applied
+    pub fn jittered_delay(&self, retry: u32) -> Duration {
+        let delay = self.delay(retry);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return delay;
+        }
+        delay.mul_f64(1.0 - rand::rng().random_range(0.0..=jitter))
+    }
+}
+
+/// Whether a failed attempt should be retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Retryable {
+    /// The error is permanent; return it
+    No,
+    /// Retry after the policy's backoff
+    Yes,
+    /// Retry after this wait instead of the backoff (e.g. `Retry-After`)
+    After(Duration),
+}
+
+/// Run `op` until it succeeds, `classify` rules its error out, or the
+/// policy's attempts are used up
+///
+/// `op` is passed the attempt number, starting at 0. The error returned is
+/// the last attempt's.
+pub async fn retry_with_backoff<T, E, F, Fut, C>(
+    policy: &RetryPolicy,
+    mut classify: C,
+    mut op: F,
+) -> Result<T, E>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    C: FnMut(&E) -> Retryable,
+{
+    let mut attempt = 0;
+    loop {
+        let error = match op(attempt).await {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+        let wait = match classify(&error) {
+            Retryable::No => return Err(error),
+            Retryable::Yes => policy.jittered_delay(attempt),
+            Retryable::After(wait) => wait,
+        };
+        attempt += 1;
+        if attempt >= policy.max_attempts {
+            return Err(error);
+        }
+        tokio::time::sleep(wait).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base: Duration::from_millis(1),
+            max: Duration::from_millis(4),
+            jitter: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_backoff_schedule() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            base: Duration::from_secs(1),
+            max: Duration::from_secs(30),
+            jitter: 0.0,
+        };
+        let schedule: Vec<u64> = (0..7).map(|i| policy.delay(i).as_secs()).collect();
+        assert_eq!(schedule, vec![1, 2, 4, 8, 16, 30, 30]);
+        assert_eq!(policy.delay(u32::MAX), Duration::from_secs(30));
+        assert_eq!(policy.jittered_delay(3), Duration::from_secs(8));
+
+        let jittered = RetryPolicy {
+            jitter: 0.5,
+            ..policy
+        };
+        for _ in 0..100 {
+            let wait = jittered.jittered_delay(3);
+            assert!(wait >= Duration::from_secs(4) && wait <= Duration::from_secs(8));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_until_success() {
+        let result: Result<u32, &str> = retry_with_backoff(
+            &policy(5),
+            |_| Retryable::Yes,
+            |attempt| async move {
+                if attempt < 2 {
+                    Err("unavailable")
+                } else {
+                    Ok(attempt)
+                }
+            },
+        )
+        .await;
+        assert_eq!(result, Ok(2));
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let mut attempts = 0;
+        let result: Result<(), u32> = retry_with_backoff(
+            &policy(3),
+            |_| Retryable::After(Duration::ZERO),
+            |attempt| {
+                attempts += 1;
+                async move { Err(attempt) }
+            },
+        )
+        .await;
+        assert_eq!(result, Err(2));
+        assert_eq!(attempts, 3);
+
+        // A policy of no attempts still makes one
+        let result: Result<(), u32> = retry_with_backoff(
+            &policy(0),
+            |_| Retryable::Yes,
+            |attempt| async move { Err(attempt) },
+        )
+        .await;
+        assert_eq!(result, Err(0));
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_error_is_returned_at_once() {
+        let mut attempts = 0;
+        let result: Result<(), &str> = retry_with_backoff(
+            &policy(5),
+            |e: &&str| {
+                if *e == "not found" {
+                    Retryable::No
+                } else {
+                    Retryable::Yes
+                }
+            },
+            |attempt| {
+                attempts += 1;
+                async move {
+                    if attempt == 0 {
+                        Err("unavailable")
+                    } else {
+                        Err("not found")
+                    }
+                }
+            },
+        )
+        .await;
+        assert_eq!(result, Err("not found"));
+        assert_eq!(attempts, 2);
+    }
+}
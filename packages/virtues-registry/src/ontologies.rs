@@ -112,10 +112,10 @@ pub fn registered_ontologies() -> Vec<OntologyDescriptor> {
         OntologyDescriptor {
             name: "health_heart_rate",
             display_name: "Heart Rate",
-            description: "Heart rate measurements from HealthKit",
+            description: "Heart rate measurements from HealthKit and Fitbit",
             domain: "health",
             table_name: "data_health_heart_rate",
-            source_streams: vec!["stream_ios_healthkit"],
+            source_streams: vec!["stream_ios_healthkit", "stream_fitbit_heart_rate"],
             timestamp_column: "timestamp",
             end_timestamp_column: None,
             embedding: None,
@@ -172,10 +172,10 @@ pub fn registered_ontologies() -> Vec<OntologyDescriptor> {
         OntologyDescriptor {
             name: "health_sleep",
             display_name: "Sleep Sessions",
-            description: "Sleep analysis from HealthKit with quality metrics",
+            description: "Sleep analysis from HealthKit and Fitbit with quality metrics",
             domain: "health",
             table_name: "data_health_sleep",
-            source_streams: vec!["stream_ios_healthkit"],
+            source_streams: vec!["stream_ios_healthkit", "stream_fitbit_sleep"],
             timestamp_column: "start_time",
             end_timestamp_column: Some("end_time"),
             embedding: None,
@@ -196,10 +196,14 @@ pub fn registered_ontologies() -> Vec<OntologyDescriptor> {
         OntologyDescriptor {
             name: "health_workout",
             display_name: "Workouts",
-            description: "Workout sessions from HealthKit and Strava",
+            description: "Workout sessions from HealthKit, Strava and Fitbit",
             domain: "health",
             table_name: "data_health_workout",
-            source_streams: vec!["stream_ios_healthkit", "stream_strava_activities"],
+            source_streams: vec![
+                "stream_ios_healthkit",
+                "stream_strava_activities",
+                "stream_fitbit_activities",
+            ],
             timestamp_column: "start_time",
             end_timestamp_column: Some("end_time"),
             embedding: None,
@@ -293,10 +297,10 @@ pub fn registered_ontologies() -> Vec<OntologyDescriptor> {
         OntologyDescriptor {
             name: "communication_message",
             display_name: "Messages",
-            description: "SMS and iMessage conversations",
+            description: "SMS, iMessage and Slack conversations",
             domain: "communication",
             table_name: "data_communication_message",
-            source_streams: vec!["stream_mac_imessage"],
+            source_streams: vec!["stream_mac_imessage", "stream_slack_messages"],
             timestamp_column: "timestamp",
             end_timestamp_column: None,
             embedding: Some(EmbeddingConfig {
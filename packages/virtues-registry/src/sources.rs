@@ -84,6 +84,8 @@ pub struct OAuthConfig {
     pub auth_url: &'static str,
     /// Token URL
     pub token_url: &'static str,
+    /// Token revocation URL, if the provider offers one
+    pub revoke_url: Option<&'static str>,
 }
 
 /// Source descriptor - metadata only, no implementation
@@ -107,6 +109,10 @@ pub struct SourceDescriptor {
     pub tier: SourceTier,
     /// Connection policy for this source
     pub connection_policy: ConnectionPolicy,
+    /// User-Agent for provider API calls, if the provider wants a specific one
+    ///
+    /// `None` uses the default `virtues/<version>`.
+    pub user_agent: Option<&'static str>,
 }
 
 /// Get all registered source descriptors
@@ -122,6 +128,7 @@ pub fn registered_sources() -> Vec<SourceDescriptor> {
                 scopes: vec!["https://www.googleapis.com/auth/calendar.readonly"],
                 auth_url: "https://accounts.google.com/o/oauth2/v2/auth",
                 token_url: "https://oauth2.googleapis.com/token",
+                revoke_url: Some("https://oauth2.googleapis.com/revoke"),
             }),
             icon: Some("ri:google-fill"),
             enabled: true,
@@ -129,6 +136,7 @@ pub fn registered_sources() -> Vec<SourceDescriptor> {
             connection_policy: ConnectionPolicy::MultiInstance {
                 limits: ConnectionLimits::new(16, 24),
             },
+            user_agent: None,
         },
         // Notion
         SourceDescriptor {
@@ -140,6 +148,7 @@ pub fn registered_sources() -> Vec<SourceDescriptor> {
                 scopes: vec!["read_content"],
                 auth_url: "https://api.notion.com/v1/oauth/authorize",
                 token_url: "https://api.notion.com/v1/oauth/token",
+                revoke_url: None,
             }),
             icon: Some("simple-icons:notion"),
             enabled: true,
@@ -147,6 +156,7 @@ pub fn registered_sources() -> Vec<SourceDescriptor> {
             connection_policy: ConnectionPolicy::MultiInstance {
                 limits: ConnectionLimits::new(5, 20),
             },
+            user_agent: None,
         },
         // Plaid (Banking)
         SourceDescriptor {
@@ -159,6 +169,7 @@ pub fn registered_sources() -> Vec<SourceDescriptor> {
                 scopes: vec!["transactions", "auth"],
                 auth_url: "https://cdn.plaid.com/link/v2/stable/link.html",
                 token_url: "https://production.plaid.com/link/token/exchange",
+                revoke_url: None,
             }),
             icon: Some("ri:bank-line"),
             enabled: true,
@@ -166,6 +177,7 @@ pub fn registered_sources() -> Vec<SourceDescriptor> {
             connection_policy: ConnectionPolicy::MultiInstance {
                 limits: ConnectionLimits::new(4, 16),
             },
+            user_agent: None,
         },
         // iOS
         SourceDescriptor {
@@ -180,6 +192,7 @@ pub fn registered_sources() -> Vec<SourceDescriptor> {
             connection_policy: ConnectionPolicy::MultiInstance {
                 limits: ConnectionLimits::new(1, 1),
             },
+            user_agent: None,
         },
         // macOS
         SourceDescriptor {
@@ -193,6 +206,7 @@ pub fn registered_sources() -> Vec<SourceDescriptor> {
             enabled: false,
             tier: SourceTier::Standard,
             connection_policy: ConnectionPolicy::Singleton,
+            user_agent: None,
         },
         // Strava
         SourceDescriptor {
@@ -204,6 +218,7 @@ pub fn registered_sources() -> Vec<SourceDescriptor> {
                 scopes: vec!["read,activity:read_all"],
                 auth_url: "https://www.strava.com/oauth/authorize",
                 token_url: "https://www.strava.com/oauth/token",
+                revoke_url: Some("https://www.strava.com/oauth/deauthorize"),
             }),
             icon: Some("simple-icons:strava"),
             enabled: true,
@@ -211,6 +226,27 @@ pub fn registered_sources() -> Vec<SourceDescriptor> {
             connection_policy: ConnectionPolicy::MultiInstance {
                 limits: ConnectionLimits::new(2, 4),
             },
+            user_agent: None,
+        },
+        // Fitbit
+        SourceDescriptor {
+            name: "fitbit",
+            display_name: "Fitbit",
+            description: "Sync sleep, heart rate, and activities from Fitbit devices",
+            auth_type: AuthType::OAuth2,
+            oauth_config: Some(OAuthConfig {
+                scopes: vec!["sleep", "heartrate", "activity", "profile"],
+                auth_url: "https://www.fitbit.com/oauth2/authorize",
+                token_url: "https://api.fitbit.com/oauth2/token",
+                revoke_url: None,
+            }),
+            icon: Some("simple-icons:fitbit"),
+            enabled: true,
+            tier: SourceTier::Standard,
+            connection_policy: ConnectionPolicy::MultiInstance {
+                limits: ConnectionLimits::new(2, 4),
+            },
+            user_agent: None,
         },
         // Spotify
         SourceDescriptor {
@@ -222,6 +258,7 @@ pub fn registered_sources() -> Vec<SourceDescriptor> {
                 scopes: vec!["user-read-recently-played", "user-read-currently-playing"],
                 auth_url: "https://accounts.spotify.com/authorize",
                 token_url: "https://accounts.spotify.com/api/token",
+                revoke_url: None,
             }),
             icon: Some("simple-icons:spotify"),
             enabled: false,
@@ -229,6 +266,33 @@ pub fn registered_sources() -> Vec<SourceDescriptor> {
             connection_policy: ConnectionPolicy::MultiInstance {
                 limits: ConnectionLimits::new(2, 4),
             },
+            user_agent: None,
+        },
+        // Slack
+        SourceDescriptor {
+            name: "slack",
+            display_name: "Slack",
+            description: "Sync messages and channels from a Slack workspace",
+            auth_type: AuthType::OAuth2,
+            oauth_config: Some(OAuthConfig {
+                scopes: vec![
+                    "channels:read",
+                    "groups:read",
+                    "im:read",
+                    "mpim:read",
+                    "users:read",
+                ],
+                auth_url: "https://slack.com/oauth/v2/authorize",
+                token_url: "https://slack.com/api/oauth.v2.access",
+                revoke_url: Some("https://slack.com/api/auth.revoke"),
+            }),
+            icon: Some("ri:slack-fill"),
+            enabled: true,
+            tier: SourceTier::Standard,
+            connection_policy: ConnectionPolicy::MultiInstance {
+                limits: ConnectionLimits::new(2, 8),
+            },
+            user_agent: None,
         },
         // GitHub
         SourceDescriptor {
@@ -240,6 +304,7 @@ pub fn registered_sources() -> Vec<SourceDescriptor> {
                 scopes: vec!["repo", "user:email"],
                 auth_url: "https://github.com/login/oauth/authorize",
                 token_url: "https://github.com/login/oauth/access_token",
+                revoke_url: None,
             }),
             icon: Some("ri:github-fill"),
             enabled: true,
@@ -247,6 +312,7 @@ pub fn registered_sources() -> Vec<SourceDescriptor> {
             connection_policy: ConnectionPolicy::MultiInstance {
                 limits: ConnectionLimits::new(2, 8),
             },
+            user_agent: None,
         },
     ]
 }
@@ -290,7 +356,9 @@ mod tests {
         assert!(names.contains(&"plaid"));
         assert!(names.contains(&"spotify"));
         assert!(names.contains(&"strava"));
+        assert!(names.contains(&"fitbit"));
         assert!(names.contains(&"github"));
+        assert!(names.contains(&"slack"));
     }
 
     #[test]
@@ -311,7 +379,7 @@ mod tests {
             .iter()
             .filter(|s| s.auth_type == AuthType::OAuth2)
             .collect();
-        assert!(oauth_sources.len() >= 6); // google, notion, plaid, spotify, strava, github
+        assert!(oauth_sources.len() >= 8); // google, notion, plaid, spotify, strava, fitbit, github, slack
 
         // Device sources
         let device_sources: Vec<_> = sources
@@ -31,6 +31,9 @@ pub struct StreamDescriptor {
     pub enabled: bool,
     /// Tier required for this stream (overrides source tier if higher)
     pub tier: SourceTier,
+    /// OAuth scopes this stream needs beyond what the source always requests
+    /// (empty for non-OAuth sources)
+    pub required_scopes: Vec<&'static str>,
 }
 
 /// Get all registered stream descriptors
@@ -49,6 +52,7 @@ pub fn registered_streams() -> Vec<StreamDescriptor> {
             default_cron_schedule: Some("0 */15 * * * *"), // Every 15 minutes
             enabled: true,
             tier: SourceTier::Standard,
+            required_scopes: vec!["https://www.googleapis.com/auth/calendar.readonly"],
         },
         StreamDescriptor {
             name: "gmail",
@@ -62,6 +66,7 @@ pub fn registered_streams() -> Vec<StreamDescriptor> {
             default_cron_schedule: Some("0 */15 * * * *"), // Every 15 minutes
             enabled: false,
             tier: SourceTier::Standard,
+            required_scopes: vec!["https://www.googleapis.com/auth/gmail.readonly"],
         },
         // ===== iOS Streams =====
         StreamDescriptor {
@@ -83,6 +88,7 @@ pub fn registered_streams() -> Vec<StreamDescriptor> {
             default_cron_schedule: Some("0 */5 * * * *"), // Every 5 minutes
             enabled: true,
             tier: SourceTier::Standard,
+            required_scopes: vec![],
         },
         StreamDescriptor {
             name: "location",
@@ -96,6 +102,7 @@ pub fn registered_streams() -> Vec<StreamDescriptor> {
             default_cron_schedule: Some("0 */5 * * * *"),
             enabled: true,
             tier: SourceTier::Standard,
+            required_scopes: vec![],
         },
         StreamDescriptor {
             name: "microphone",
@@ -109,6 +116,7 @@ pub fn registered_streams() -> Vec<StreamDescriptor> {
             default_cron_schedule: Some("0 */5 * * * *"),
             enabled: true,
             tier: SourceTier::Standard,
+            required_scopes: vec![],
         },
         StreamDescriptor {
             name: "contacts",
@@ -122,6 +130,7 @@ pub fn registered_streams() -> Vec<StreamDescriptor> {
             default_cron_schedule: Some("0 */5 * * * *"),
             enabled: true,
             tier: SourceTier::Standard,
+            required_scopes: vec![],
         },
         StreamDescriptor {
             name: "financekit",
@@ -135,6 +144,7 @@ pub fn registered_streams() -> Vec<StreamDescriptor> {
             default_cron_schedule: Some("0 */5 * * * *"),
             enabled: true,
             tier: SourceTier::Standard,
+            required_scopes: vec![],
         },
         StreamDescriptor {
             name: "eventkit",
@@ -148,6 +158,7 @@ pub fn registered_streams() -> Vec<StreamDescriptor> {
             default_cron_schedule: Some("0 */5 * * * *"),
             enabled: true,
             tier: SourceTier::Standard,
+            required_scopes: vec![],
         },
         // ===== macOS Streams =====
         StreamDescriptor {
@@ -162,6 +173,7 @@ pub fn registered_streams() -> Vec<StreamDescriptor> {
             default_cron_schedule: Some("0 */5 * * * *"),
             enabled: true,
             tier: SourceTier::Standard,
+            required_scopes: vec![],
         },
         StreamDescriptor {
             name: "browser",
@@ -176,6 +188,7 @@ pub fn registered_streams() -> Vec<StreamDescriptor> {
             default_cron_schedule: Some("0 */5 * * * *"),
             enabled: true,
             tier: SourceTier::Standard,
+            required_scopes: vec![],
         },
         StreamDescriptor {
             name: "imessage",
@@ -189,6 +202,7 @@ pub fn registered_streams() -> Vec<StreamDescriptor> {
             default_cron_schedule: Some("0 */5 * * * *"),
             enabled: true,
             tier: SourceTier::Standard,
+            required_scopes: vec![],
         },
         // ===== Notion Streams =====
         StreamDescriptor {
@@ -198,11 +212,26 @@ pub fn registered_streams() -> Vec<StreamDescriptor> {
             description: "Sync pages and their content from Notion databases and workspaces",
             table_name: "stream_notion_pages",
             target_ontologies: vec!["content_document"],
-            supports_incremental: false, // Notion API doesn't provide incremental sync
+            supports_incremental: true, // Search sorted by last_edited_time
             supports_full_refresh: true,
             default_cron_schedule: Some("0 0 */12 * * *"), // Every 12 hours
             enabled: true,
             tier: SourceTier::Standard,
+            required_scopes: vec!["read_content"],
+        },
+        StreamDescriptor {
+            name: "databases",
+            source: "notion",
+            display_name: "Notion Databases",
+            description: "Sync database rows from Notion with their typed properties",
+            table_name: "stream_notion_databases",
+            target_ontologies: vec![], // No ontology yet
+            supports_incremental: false,
+            supports_full_refresh: true,
+            default_cron_schedule: Some("0 0 */12 * * *"), // Every 12 hours
+            enabled: true,
+            tier: SourceTier::Standard,
+            required_scopes: vec!["read_content"],
         },
         // ===== Plaid Streams =====
         StreamDescriptor {
@@ -217,6 +246,7 @@ pub fn registered_streams() -> Vec<StreamDescriptor> {
             default_cron_schedule: Some("0 0 */6 * * *"), // Every 6 hours
             enabled: true,
             tier: SourceTier::Standard,
+            required_scopes: vec![],
         },
         StreamDescriptor {
             name: "accounts",
@@ -232,6 +262,7 @@ pub fn registered_streams() -> Vec<StreamDescriptor> {
             default_cron_schedule: Some("0 55 5,11,17,23 * * *"), // At :55 of hours 5,11,17,23 (5 min before 6,12,18,0)
             enabled: true,
             tier: SourceTier::Standard,
+            required_scopes: vec![],
         },
         StreamDescriptor {
             name: "investments",
@@ -245,6 +276,7 @@ pub fn registered_streams() -> Vec<StreamDescriptor> {
             default_cron_schedule: Some("0 0 0 * * *"),
             enabled: false, // Disabled: expensive API calls (~$0.25/call), no ontology yet
             tier: SourceTier::Standard,
+            required_scopes: vec![],
         },
         StreamDescriptor {
             name: "liabilities",
@@ -258,6 +290,7 @@ pub fn registered_streams() -> Vec<StreamDescriptor> {
             default_cron_schedule: Some("0 0 0 * * *"),
             enabled: false, // Disabled: expensive API calls (~$0.25/call), no ontology yet
             tier: SourceTier::Standard,
+            required_scopes: vec![],
         },
         // ===== Strava Streams =====
         StreamDescriptor {
@@ -272,6 +305,50 @@ pub fn registered_streams() -> Vec<StreamDescriptor> {
             default_cron_schedule: Some("0 */30 * * * *"), // Every 30 minutes
             enabled: true,
             tier: SourceTier::Standard,
+            required_scopes: vec!["read,activity:read_all"],
+        },
+        // ===== Fitbit Streams =====
+        StreamDescriptor {
+            name: "sleep",
+            source: "fitbit",
+            display_name: "Fitbit Sleep",
+            description: "Sleep logs with stage breakdowns (deep, light, REM, wake)",
+            table_name: "stream_fitbit_sleep",
+            target_ontologies: vec!["health_sleep"],
+            supports_incremental: true,
+            supports_full_refresh: true,
+            default_cron_schedule: Some("0 0 */6 * * *"), // Every 6 hours
+            enabled: true,
+            tier: SourceTier::Standard,
+            required_scopes: vec!["sleep"],
+        },
+        StreamDescriptor {
+            name: "heart_rate",
+            source: "fitbit",
+            display_name: "Fitbit Heart Rate",
+            description: "Minute-level heart rate samples from Fitbit",
+            table_name: "stream_fitbit_heart_rate",
+            target_ontologies: vec!["health_heart_rate"],
+            supports_incremental: true,
+            supports_full_refresh: true,
+            default_cron_schedule: Some("0 0 * * * *"), // Every hour
+            enabled: true,
+            tier: SourceTier::Standard,
+            required_scopes: vec!["heartrate", "profile"],
+        },
+        StreamDescriptor {
+            name: "activities",
+            source: "fitbit",
+            display_name: "Fitbit Activities",
+            description: "Logged and auto-detected exercise sessions from Fitbit",
+            table_name: "stream_fitbit_activities",
+            target_ontologies: vec!["health_workout"],
+            supports_incremental: true,
+            supports_full_refresh: true,
+            default_cron_schedule: Some("0 */30 * * * *"), // Every 30 minutes
+            enabled: true,
+            tier: SourceTier::Standard,
+            required_scopes: vec!["activity"],
         },
         // ===== Spotify Streams =====
         StreamDescriptor {
@@ -286,6 +363,7 @@ pub fn registered_streams() -> Vec<StreamDescriptor> {
             default_cron_schedule: Some("0 */15 * * * *"), // Every 15 minutes
             enabled: true,
             tier: SourceTier::Standard,
+            required_scopes: vec!["user-read-recently-played", "user-read-currently-playing"],
         },
         // ===== GitHub Streams =====
         StreamDescriptor {
@@ -300,6 +378,42 @@ pub fn registered_streams() -> Vec<StreamDescriptor> {
             default_cron_schedule: Some("0 */15 * * * *"), // Every 15 minutes
             enabled: true,
             tier: SourceTier::Standard,
+            required_scopes: vec!["repo", "user:email"],
+        },
+        // ===== Slack Streams =====
+        StreamDescriptor {
+            name: "messages",
+            source: "slack",
+            display_name: "Slack Messages",
+            description: "Messages from channels, DMs and group DMs you are a member of",
+            table_name: "stream_slack_messages",
+            target_ontologies: vec!["communication_message"],
+            supports_incremental: true,
+            supports_full_refresh: true,
+            default_cron_schedule: Some("0 */30 * * * *"), // Every 30 minutes
+            enabled: true,
+            tier: SourceTier::Standard,
+            required_scopes: vec![
+                "channels:history",
+                "groups:history",
+                "im:history",
+                "mpim:history",
+                "users:read",
+            ],
+        },
+        StreamDescriptor {
+            name: "channels",
+            source: "slack",
+            display_name: "Slack Channels",
+            description: "Channels and conversations in the workspace with topic and membership",
+            table_name: "stream_slack_channels",
+            target_ontologies: vec![], // No ontology yet
+            supports_incremental: false,
+            supports_full_refresh: true,
+            default_cron_schedule: Some("0 0 */6 * * *"), // Every 6 hours
+            enabled: true,
+            tier: SourceTier::Standard,
+            required_scopes: vec!["channels:read", "groups:read", "im:read", "mpim:read"],
         },
     ]
 }
@@ -344,7 +458,9 @@ mod tests {
         assert!(sources.contains(&"plaid"));
         assert!(sources.contains(&"spotify"));
         assert!(sources.contains(&"strava"));
+        assert!(sources.contains(&"fitbit"));
         assert!(sources.contains(&"github"));
+        assert!(sources.contains(&"slack"));
     }
 
     #[test]
@@ -374,6 +490,18 @@ mod tests {
         assert_eq!(s.source, "ios");
     }
 
+    #[test]
+    fn test_gmail_requires_gmail_scope() {
+        let gmail = get_stream("google", "gmail").unwrap();
+        assert_eq!(
+            gmail.required_scopes,
+            vec!["https://www.googleapis.com/auth/gmail.readonly"]
+        );
+
+        let healthkit = get_stream("ios", "healthkit").unwrap();
+        assert!(healthkit.required_scopes.is_empty());
+    }
+
     #[test]
     fn test_stream_table_name_format() {
         for stream in registered_streams() {